@@ -1,3 +1,6 @@
+use phase_loading::ProfileKind;
+use std::{path::PathBuf, str::FromStr};
+
 use clap::{
     Args, Parser, Subcommand, ValueEnum,
     builder::{Styles, styling::AnsiColor},
@@ -10,16 +13,65 @@ pub struct Cli {
     #[arg(short, long = "verbose", action = clap::ArgAction::Count)]
     pub verbosity: u8,
 
+    /// Print nothing but errors and the data the command is asked for
+    #[arg(short, long, global = true, conflicts_with = "porcelain")]
+    pub quiet: bool,
+
+    /// Print a stable, versioned, tab separated line to stdout for every completed target,
+    /// e.g. `v1<TAB>exported<TAB>//icons:star<TAB>1230`, and nothing else but errors
+    #[arg(long, global = true)]
+    pub porcelain: bool,
+
+    /// Format of the logs, `json` writes one object per line to stderr and hides the
+    /// progress bar. Can also be set with the `FIGX_LOG_FORMAT` environment variable
+    #[arg(long, global = true, value_enum)]
+    pub log_format: Option<LogFormat>,
+
     /// Number of parallel jobs to run (0 means auto-detect)
     #[arg(short, action = clap::ArgAction::Set, default_value = "0")]
     pub jobs: usize,
 
+    /// Number of downloads in flight, overrides `[jobs] network` of the workspace
+    #[arg(long, global = true)]
+    pub network_jobs: Option<usize>,
+
+    /// Number of targets transformed in parallel, overrides `-j` and `[jobs] cpu`
+    /// of the workspace
+    #[arg(long, global = true)]
+    pub cpu_jobs: Option<usize>,
+
+    /// Seconds between progress summaries logged instead of the progress bar, e.g. on CI
+    /// (0 disables them)
+    #[arg(long, global = true, value_name = "SECONDS", default_value = "30")]
+    pub progress_interval: u64,
+
+    /// Redraws of the progress bar per second, from 1 to 60
+    #[arg(long, global = true, value_name = "FPS", default_value = "20")]
+    pub frame_rate: u64,
+
+    /// Read Figma files from a mirror archive created by `figx mirror` instead of network
+    #[arg(long, global = true)]
+    pub mirror: Option<PathBuf>,
+
     #[command(subcommand)]
     pub subcommand: CliSubcommand,
 }
 
+#[derive(ValueEnum, Debug, Clone, Copy)]
+#[clap(rename_all = "kebab_case")]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
 #[derive(Subcommand)]
 pub enum CliSubcommand {
+    /// Create a new workspace from a template
+    New(CommandNewArgs),
+
+    /// Convert the config of figma-export into a figx workspace
+    ImportConfig(CommandImportConfigArgs),
+
     /// Show brief info about entities of current workspace
     Info(CommandInfoArgs),
 
@@ -27,7 +79,11 @@ pub enum CliSubcommand {
     #[clap(visible_alias("q"))]
     Query(CommandQueryArgs),
 
+    /// Find the resource which writes the given output file
+    Rquery(CommandRqueryArgs),
+
     /// Explain how resources are transformed and imported into a project
+    #[clap(visible_alias("aquery"))]
     Explain(CommandExplainArgs),
 
     /// Download resources metadata from remote to cache
@@ -41,11 +97,32 @@ pub enum CliSubcommand {
     /// Clean up application cache
     Clean(CommandCleanArgs),
 
+    /// Delete imported files whose resources were removed or renamed
+    Prune(CommandPruneArgs),
+
     /// Add Figma personal token to secure storage
     Auth(CommandAuthArgs),
 
     /// Scan selected remotes and generate an output file with indexed remote metadata
     Scan(CommandScanArgs),
+
+    /// Download Figma files of selected remotes into an archive for offline use
+    Mirror(CommandMirrorArgs),
+
+    /// Render thumbnails of resources and write a static HTML gallery
+    Gallery(CommandGalleryArgs),
+
+    /// Find byte-identical or visually identical resources across the workspace
+    Dedupe(CommandDedupeArgs),
+
+    /// Check the workspace for likely mistakes which don't prevent the import
+    Lint(CommandLintArgs),
+
+    /// Listen for Figma webhooks and import the resources of updated remotes
+    Serve(CommandServeArgs),
+
+    /// Detect imported files edited by hand using their provenance records
+    VerifyProvenance(CommandVerifyProvenanceArgs),
 }
 
 #[derive(Args, Debug)]
@@ -53,11 +130,30 @@ pub struct CommandQueryArgs {
     /// A label pattern describing the resources affected by a command
     pub pattern: Vec<String>,
 
+    /// Only resources of profiles of this type, e.g. `compose` (can be repeated)
+    #[arg(long, value_name = "TYPE", value_parser = ProfileKind::from_str)]
+    pub profile_type: Vec<ProfileKind>,
+
+    /// Only resources imported from this remote (can be repeated)
+    #[arg(long, value_name = "REMOTE")]
+    pub remote: Vec<String>,
+
+    /// Only resources with this tag, or without it if prefixed with `-` (can be repeated)
+    #[arg(long, value_name = "TAG", allow_hyphen_values = true)]
+    pub tag: Vec<String>,
+
     /// Customize command's output type
     #[arg(short, long, value_enum, default_value = "label")]
     pub output: QueryOutput,
 }
 
+#[derive(Args, Debug)]
+pub struct CommandRqueryArgs {
+    /// Paths of imported files, e.g. `app/src/main/res/drawable-xhdpi/ic_star.webp`
+    #[arg(required = true)]
+    pub paths: Vec<PathBuf>,
+}
+
 #[derive(ValueEnum, Debug, Clone)]
 #[clap(rename_all = "kebab_case")]
 pub enum QueryOutput {
@@ -65,12 +161,43 @@ pub enum QueryOutput {
     Profile,
     Package,
     Tree,
+    /// Number of matched resources
+    Count,
+    /// Absolute paths of the files the matched resources are imported to
+    Files,
+    /// Content hash of every target, computed from its profile and the cached Figma node
+    Hash,
 }
 
 #[derive(Args, Debug)]
 pub struct CommandExplainArgs {
     /// A label pattern describing the resources affected by a command
     pub pattern: Vec<String>,
+
+    /// Customize command's output format
+    #[arg(short, long, alias = "output", value_enum, default_value = "tree")]
+    pub format: ExplainFormat,
+
+    /// Print per-package counts of targets, output files, actions and remotes
+    #[arg(long, conflicts_with = "format")]
+    pub summary: bool,
+
+    /// Show only this many levels of steps below every resource (0 shows resources only)
+    #[arg(long, conflicts_with = "summary")]
+    pub depth: Option<usize>,
+
+    /// Add the absolute paths of the written files and whether every step is likely cached
+    #[arg(long, conflicts_with = "summary")]
+    pub details: bool,
+}
+
+#[derive(ValueEnum, Debug, Clone)]
+#[clap(rename_all = "kebab_case")]
+pub enum ExplainFormat {
+    Tree,
+    Dot,
+    Mermaid,
+    Json,
 }
 
 #[derive(Args, Debug)]
@@ -90,6 +217,18 @@ pub enum InfoEntity {
 pub struct CommandFetchArgs {
     /// A label pattern describing the resources affected by a command
     pub pattern: Vec<String>,
+
+    /// Only resources of profiles of this type, e.g. `compose` (can be repeated)
+    #[arg(long, value_name = "TYPE", value_parser = ProfileKind::from_str)]
+    pub profile_type: Vec<ProfileKind>,
+
+    /// Only resources imported from this remote (can be repeated)
+    #[arg(long, value_name = "REMOTE")]
+    pub remote: Vec<String>,
+
+    /// Only resources with this tag, or without it if prefixed with `-` (can be repeated)
+    #[arg(long, value_name = "TAG", allow_hyphen_values = true)]
+    pub tag: Vec<String>,
 }
 
 #[derive(Args, Debug)]
@@ -97,9 +236,71 @@ pub struct CommandImportArgs {
     /// A label pattern describing the resources affected by a command
     pub pattern: Vec<String>,
 
+    /// Only resources of profiles of this type, e.g. `compose` (can be repeated)
+    #[arg(long, value_name = "TYPE", value_parser = ProfileKind::from_str)]
+    pub profile_type: Vec<ProfileKind>,
+
+    /// Only resources imported from this remote (can be repeated)
+    #[arg(long, value_name = "REMOTE")]
+    pub remote: Vec<String>,
+
+    /// Only resources with this tag, or without it if prefixed with `-` (can be repeated)
+    #[arg(long, value_name = "TAG", allow_hyphen_values = true)]
+    pub tag: Vec<String>,
+
     /// Run fetch even if already have cached remote metadata
     #[arg(long)]
     pub refetch: bool,
+
+    /// Continue importing other resources after a failure and report all errors at the end
+    #[arg(short, long)]
+    pub keep_going: bool,
+
+    /// Path to the JSON build report (default: `.figx-out/build-report.json`)
+    #[arg(long, value_name = "PATH")]
+    pub report: Option<PathBuf>,
+
+    /// Also write a JUnit XML report with a test case per target, for the test UI of CI systems
+    #[arg(long, value_name = "PATH")]
+    pub report_junit: Option<PathBuf>,
+
+    /// Measure wall time of every action and print the slowest ones after import
+    #[arg(long)]
+    pub profile: bool,
+
+    /// Write Chrome trace of all actions to `.figx-out/trace.json` (open it with Perfetto)
+    #[arg(long)]
+    pub trace: bool,
+
+    /// Write intermediate artifacts of every target (raw Figma exports, rendered PNGs,
+    /// generated code) to this directory for debugging
+    #[arg(long, value_name = "DIR")]
+    pub keep_intermediates: Option<PathBuf>,
+
+    /// Write files from a dedicated thread, grouped by directory, and sync each directory
+    /// once at the end. Reduces syscall overhead on network filesystems
+    #[arg(long)]
+    pub write_queue: bool,
+
+    /// Skip targets completed by the previous import if it crashed or was interrupted.
+    /// Their output files are verified, everything else is imported as usual
+    #[arg(long)]
+    pub resume_last: bool,
+
+    /// Don't touch the workspace, only verify that the imported files are up to date.
+    /// Fails with the list of out-of-date files otherwise
+    #[arg(long, conflicts_with = "resume_last")]
+    pub check: bool,
+
+    /// Decode every imported PNG and WEBP image and check that its dimensions match
+    /// the node size multiplied by the scale. Fails the target otherwise
+    #[arg(long)]
+    pub validate_images: bool,
+
+    /// Write the imported files under this directory instead of the packages, keeping
+    /// the structure of the packages. Overrides `[output] base` of the workspace
+    #[arg(long, value_name = "DIR")]
+    pub output_base: Option<PathBuf>,
 }
 
 #[derive(Args, Debug)]
@@ -109,6 +310,41 @@ pub struct CommandCleanArgs {
     pub all: bool,
 }
 
+#[derive(Args, Debug)]
+pub struct CommandNewArgs {
+    /// Directory of the new workspace, created if it doesn't exist
+    pub path: PathBuf,
+
+    /// Kind of the project the resources are imported to
+    #[arg(short, long, value_enum)]
+    pub template: NewTemplate,
+}
+
+#[derive(ValueEnum, Debug, Clone)]
+#[clap(rename_all = "kebab_case")]
+pub enum NewTemplate {
+    Android,
+    Ios,
+    Web,
+}
+
+#[derive(Args, Debug)]
+pub struct CommandImportConfigArgs {
+    /// Path to the config, e.g. `figma-export.yaml`
+    pub path: PathBuf,
+
+    /// Directory of the new workspace, the directory of the config by default
+    #[arg(short, long)]
+    pub output_dir: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct CommandPruneArgs {
+    /// Only list orphaned files without deleting them
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
 #[derive(Args, Debug)]
 pub struct CommandAuthArgs {
     /// Delete token from keychain
@@ -122,6 +358,78 @@ pub struct CommandScanArgs {
     pub remotes: Vec<String>,
 }
 
+#[derive(Args, Debug)]
+pub struct CommandMirrorArgs {
+    /// List of remotes to mirror (all remotes if empty)
+    pub remotes: Vec<String>,
+
+    /// Path to the output archive
+    #[arg(short, long, default_value = "mirror.tar.zst")]
+    pub output: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct CommandGalleryArgs {
+    /// A label pattern describing the resources affected by a command
+    pub pattern: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct CommandDedupeArgs {
+    /// A label pattern describing the resources affected by a command
+    pub pattern: Vec<String>,
+
+    /// Only report duplicates without changing anything (the only supported mode for now)
+    #[arg(long, required = true)]
+    pub report: bool,
+
+    /// Customize command's output format
+    #[arg(short, long, value_enum, default_value = "table")]
+    pub format: DedupeFormat,
+
+    /// Maximum number of different bits of perceptual hashes of similar images (0..=64)
+    #[arg(long, default_value = "4", value_parser = clap::value_parser!(u32).range(0..=64))]
+    pub threshold: u32,
+}
+
+#[derive(ValueEnum, Debug, Clone)]
+#[clap(rename_all = "kebab_case")]
+pub enum DedupeFormat {
+    Table,
+    Json,
+}
+
+#[derive(Args, Debug)]
+pub struct CommandLintArgs {
+    /// Customize command's output format
+    #[arg(short, long, value_enum, default_value = "text")]
+    pub format: LintFormat,
+}
+
+#[derive(ValueEnum, Debug, Clone)]
+#[clap(rename_all = "kebab_case")]
+pub enum LintFormat {
+    Text,
+    Json,
+}
+
+#[derive(Args, Debug)]
+pub struct CommandServeArgs {
+    /// Address to listen on, e.g. `0.0.0.0:8080` to accept webhooks from Figma
+    #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:8080")]
+    pub listen: String,
+}
+
+#[derive(Args, Debug)]
+pub struct CommandVerifyProvenanceArgs {
+    /// A label pattern describing the resources affected by a command
+    pub pattern: Vec<String>,
+
+    /// Fail on imported files without the provenance record as well
+    #[arg(long)]
+    pub strict: bool,
+}
+
 fn get_styles() -> Styles {
     Styles::styled()
         .header(AnsiColor::Green.on_default().bold())
@@ -1,6 +1,6 @@
 use codespan_reporting::{
     diagnostic::{Diagnostic, Label},
-    files::SimpleFile,
+    files::{SimpleFile, SimpleFiles},
     term::{
         self,
         termcolor::{ColorChoice, StandardStream},
@@ -34,11 +34,38 @@ pub enum Error {
     #[from]
     Clean(command_clean::Error),
 
+    #[from]
+    Prune(command_prune::Error),
+
+    #[from]
+    New(command_new::Error),
+
+    #[from]
+    ImportConfig(command_import_config::Error),
+
     #[from]
     Auth(command_auth::Error),
 
     #[from]
     Scan(command_scan::Error),
+
+    #[from]
+    Mirror(command_mirror::Error),
+
+    #[from]
+    Gallery(command_gallery::Error),
+
+    #[from]
+    Dedupe(command_dedupe::Error),
+
+    #[from]
+    Lint(command_lint::Error),
+
+    #[from]
+    Serve(command_serve::Error),
+
+    #[from]
+    VerifyProvenance(command_verify_provenance::Error),
 }
 
 pub fn handle_error(err: Error) {
@@ -50,8 +77,17 @@ pub fn handle_error(err: Error) {
         Fetch(err) => handle_cmd_fetch_error(err),
         Import(err) => handle_cmd_import_error(err),
         Clean(err) => handle_cmd_clean_error(err),
+        Prune(err) => handle_cmd_prune_error(err),
+        New(err) => handle_cmd_new_error(err),
+        ImportConfig(err) => handle_cmd_import_config_error(err),
         Auth(err) => handle_cmd_auth_error(err),
         Scan(err) => handle_cmd_scan_error(err),
+        Mirror(err) => handle_cmd_mirror_error(err),
+        Gallery(err) => handle_cmd_gallery_error(err),
+        Dedupe(err) => handle_cmd_dedupe_error(err),
+        Lint(err) => handle_cmd_lint_error(err),
+        Serve(err) => handle_cmd_serve_error(err),
+        VerifyProvenance(err) => handle_cmd_verify_provenance_error(err),
     }
 }
 
@@ -71,6 +107,23 @@ fn handle_cmd_query_error(err: command_query::Error) {
             message: &format!("unable to access config file: {err}"),
             labels: &[],
         }),
+        FileNotGenerated(path) => cli_input_error(CliInputDiagnostics {
+            message: &format!("no resource of the workspace writes `{}`", path.display()),
+            labels: &[CliInputLabel::Tip(
+                "resources of external workspaces and generated Dart classes are not looked up",
+            )],
+        }),
+        Evaluation(err) => handle_evaluation_error(err),
+        DigestUnknown(targets) => cli_input_error(CliInputDiagnostics {
+            message: &format!(
+                "hashes of {} targets are unknown, e.g. `{}`",
+                targets.len(),
+                targets[0],
+            ),
+            labels: &[CliInputLabel::Tip(
+                "hashes rely on the cached Figma files, run `figx fetch` with the same pattern first",
+            )],
+        }),
     }
 }
 
@@ -157,6 +210,150 @@ fn handle_cmd_scan_error(err: command_scan::Error) {
     }
 }
 
+fn handle_cmd_mirror_error(err: command_mirror::Error) {
+    use command_mirror::Error::*;
+    match err {
+        Pattern(err) => handle_pattern_error(err),
+        Workspace(err) => handle_phase_loading_error(err),
+        Evaluation(err) => handle_evaluation_error(err),
+        UserError(error) => cli_input_error(CliInputDiagnostics {
+            message: &format!("incorrect user input: {error}"),
+            labels: &[],
+        }),
+        FigmaError(error) => eprintln!(
+            "{err_label} figma error: {error}",
+            err_label = "error:".red().bold(),
+        ),
+    }
+}
+
+fn handle_cmd_gallery_error(err: command_gallery::Error) {
+    use command_gallery::Error::*;
+    match err {
+        Pattern(err) => handle_pattern_error(err),
+        Workspace(err) => handle_phase_loading_error(err),
+        Evaluation(err) => handle_evaluation_error(err),
+        IO(err) => eprintln!(
+            "{err_label} unable to write gallery: {err}",
+            err_label = "error:".red().bold(),
+        ),
+    }
+}
+
+fn handle_cmd_prune_error(err: command_prune::Error) {
+    use command_prune::Error::*;
+    match err {
+        Pattern(err) => handle_pattern_error(err),
+        Workspace(err) => handle_phase_loading_error(err),
+        Evaluation(err) => handle_evaluation_error(err),
+    }
+}
+
+fn handle_cmd_new_error(err: command_new::Error) {
+    use command_new::Error::*;
+    match err {
+        DirectoryNotEmpty(path) => cli_input_error(CliInputDiagnostics {
+            message: &format!("directory `{}` is not empty", path.display()),
+            labels: &[CliInputLabel::Tip(
+                "choose a new directory, figx never overwrites existing files",
+            )],
+        }),
+        IO(err) => eprintln!(
+            "{err_label} unable to create workspace: {err}",
+            err_label = "error:".red().bold(),
+        ),
+    }
+}
+
+fn handle_cmd_import_config_error(err: command_import_config::Error) {
+    use command_import_config::Error::*;
+    match err {
+        UnsupportedConfig(path) => cli_input_error(CliInputDiagnostics {
+            message: &format!("unsupported config `{}`", path.display()),
+            labels: &[CliInputLabel::Tip(
+                "only the YAML configs of figma-export with a `figma` section can be converted",
+            )],
+        }),
+        AlreadyExists(path) => cli_input_error(CliInputDiagnostics {
+            message: &format!("file `{}` already exists", path.display()),
+            labels: &[CliInputLabel::Tip(
+                "choose another directory with `--output-dir`, figx never overwrites existing files",
+            )],
+        }),
+        Yaml(err) => eprintln!(
+            "{err_label} unable to parse config: {err}",
+            err_label = "error:".red().bold(),
+        ),
+        IO(err) => eprintln!(
+            "{err_label} unable to convert config: {err}",
+            err_label = "error:".red().bold(),
+        ),
+    }
+}
+
+fn handle_cmd_dedupe_error(err: command_dedupe::Error) {
+    use command_dedupe::Error::*;
+    match err {
+        Pattern(err) => handle_pattern_error(err),
+        Workspace(err) => handle_phase_loading_error(err),
+        Evaluation(err) => handle_evaluation_error(err),
+        IO(err) => eprintln!(
+            "{err_label} unable to write dedupe report: {err}",
+            err_label = "error:".red().bold(),
+        ),
+    }
+}
+
+fn handle_cmd_lint_error(err: command_lint::Error) {
+    use command_lint::Error::*;
+    match err {
+        Workspace(err) => handle_phase_loading_error(err),
+        IO(err) => eprintln!(
+            "{err_label} unable to lint workspace: {err}",
+            err_label = "error:".red().bold(),
+        ),
+        IssuesFound(count) => eprintln!(
+            "{err_label} lint found {count} issues",
+            err_label = "error:".red().bold(),
+        ),
+    }
+}
+
+fn handle_cmd_serve_error(err: command_serve::Error) {
+    use command_serve::Error::*;
+    match err {
+        ServerCreation(s) => cli_input_error(CliInputDiagnostics {
+            message: &format!("unable to start server: {s}"),
+            labels: &[CliInputLabel::Tip(
+                "check that the address is correct and not used by another process",
+            )],
+        }),
+        Workspace(err) => handle_phase_loading_error(err),
+        Evaluation(err) => handle_evaluation_error(err),
+        IO(err) => eprintln!(
+            "{err_label} server io error: {err}",
+            err_label = "error:".red().bold(),
+        ),
+    }
+}
+
+fn handle_cmd_verify_provenance_error(err: command_verify_provenance::Error) {
+    use command_verify_provenance::Error::*;
+    match err {
+        Pattern(err) => handle_pattern_error(err),
+        Workspace(err) => handle_phase_loading_error(err),
+        IO(err) => eprintln!(
+            "{err_label} unable to read imported file: {err}",
+            err_label = "error:".red().bold(),
+        ),
+        FilesEdited(paths) => eprintln!(
+            "{err_label} {} imported files don't match their provenance",
+            paths.len(),
+            err_label = "error:".red().bold(),
+        ),
+    }
+}
+
 fn handle_pattern_error(err: lib_label::PatternError) {
     use lib_label::PatternError::*;
     match err {
@@ -169,6 +366,7 @@ fn handle_pattern_error(err: lib_label::PatternError) {
                         - `//foo/bar`
                         - `buz/...`
                         - `//...`
+                        - `@external//foo/...`
                         - or even empty
                     ",
                 )),
@@ -206,6 +404,30 @@ fn handle_pattern_error(err: lib_label::PatternError) {
                 ],
             })
         }
+        BadTag(pattern, tag) => {
+            let pos = pattern.find("tag:").map_or(0, |it| it + 4);
+            cli_input_error(CliInputDiagnostics {
+                message: &format!("entered pattern is incorrect: `{pattern}`"),
+                labels: &[
+                    CliInputLabel::Tip(&unindent::unindent(
+                        "
+                        valid tag patterns are:
+                        - tag:critical
+                        - -tag:marketing
+                    ",
+                    )),
+                    CliInputLabel::YellowHelp(
+                        &pattern,
+                        pos..pos + tag.len(),
+                        if tag.is_empty() {
+                            "^ tag mustn't be empty"
+                        } else {
+                            "tag contains invalid characters"
+                        },
+                    ),
+                ],
+            })
+        }
     }
 }
 
@@ -236,7 +458,14 @@ fn handle_phase_loading_error(err: phase_loading::Error) {
             labels: &[],
         }),
         WorkspaceParse(err, path) => {
-            handle_toml_parsing_error(err, &path, "failed to parse workspace file `.figtree.toml`")
+            let msg = match path.file_name() {
+                Some(name) => format!(
+                    "failed to parse workspace file `{}`",
+                    name.to_string_lossy()
+                ),
+                None => "failed to parse workspace file".to_string(),
+            };
+            handle_toml_parsing_error(err, &path, &msg)
         }
         WorkspaceRemoteNoAccessToken(id, path, span) => {
             let file = create_simple_file(&path);
@@ -269,6 +498,32 @@ fn handle_phase_loading_error(err: phase_loading::Error) {
             message: &format!("unable to get token from keychain: {err}"),
             labels: &[],
         }),
+        WorkspaceExternalUnknown(name) => cli_input_error(CliInputDiagnostics {
+            message: &format!("unknown external workspace `@{name}`"),
+            labels: &[CliInputLabel::Tip(&unindent::unindent(
+                "
+                    external workspaces should be declared in `.figtree.toml`:
+
+                    [externals]
+                    design_system = { path = \"../design-system\" }
+                ",
+            ))],
+        }),
+        WorkspaceRemoteUnknown(id) => cli_input_error(CliInputDiagnostics {
+            message: &format!("unknown remote `{id}`"),
+            labels: &[CliInputLabel::Tip(
+                "remotes are declared in the `[remotes]` table of `.figtree.toml`",
+            )],
+        }),
+        WorkspaceExternalNotFound(name, path) => cli_input_error(CliInputDiagnostics {
+            message: &format!(
+                "external workspace `@{name}` not found: {} does not exist",
+                path.display()
+            ),
+            labels: &[CliInputLabel::Tip(
+                "make sure `path` of the external points to a directory with `.figtree.toml`",
+            )],
+        }),
         FigTraversing(err) => cli_input_error(CliInputDiagnostics {
             message: &format!("[internal] fig-files traversing: {err}"),
             labels: &[CliInputLabel::Tip(
@@ -285,6 +540,39 @@ fn handle_phase_loading_error(err: phase_loading::Error) {
             handle_toml_parsing_error(err, &path, "failed to parse fig-file `.fig.toml`")
         }
         FigInvalidPackage(err) => handle_package_parsing_error(err),
+        FigDuplicateOutput(path, writers) => {
+            let [first, second] = *writers;
+            let mut files = SimpleFiles::new();
+            let mut labels = Vec::new();
+            for writer in [&first, &second] {
+                // the declaration is only shown if the file is still readable
+                let Ok(source) = std::fs::read_to_string(&writer.file) else {
+                    continue;
+                };
+                let file_id = files.add(writer.file.display().to_string(), source);
+                labels.push(
+                    Label::primary(file_id, writer.span.clone())
+                        .with_message(format!("`{}` is declared here", writer.label)),
+                );
+            }
+            let diagnostic = Diagnostic::error()
+                .with_message(format!(
+                    "resources `{}` and `{}` are written to the same file",
+                    first.label, second.label,
+                ))
+                .with_labels(labels)
+                .with_note(format!(
+                    "both write `{}`, rename one of them or change `output_dir` of its profile",
+                    path.display(),
+                ));
+            let writer = StandardStream::stderr(ColorChoice::Always);
+            let _ = term::emit(
+                &mut writer.lock(),
+                &term::Config::default(),
+                &files,
+                &diagnostic,
+            );
+        }
     }
 }
 
@@ -352,6 +640,12 @@ fn handle_evaluation_error(err: phase_evaluation::Error) {
                     "{err_label} too many requests Figma API: retry={retry_after_sec}s, tier={figma_plan_tier}, type={figma_limit_type}",
                     err_label = "error:".red().bold(),
                 ),
+                lib_figma_fluent::Error::Mirror(err) => eprintln!(
+                    "{err_label} while reading Figma mirror: {err}\n\n\
+                    {tip_label} re-create the mirror with `figx mirror` if the Figma files have changed\n",
+                    err_label = "error:".red().bold(),
+                    tip_label = "  tip:".green(),
+                ),
             }
         }
         ExportImage(err) => eprintln!(
@@ -379,6 +673,25 @@ fn handle_evaluation_error(err: phase_evaluation::Error) {
                 .with_label(Label::primary((), span));
             print_codespan_diag(diagnostic, &file);
         }
+        UnexpectedDimensions {
+            node_name,
+            mismatches,
+            file,
+            span,
+        } => {
+            let file = create_simple_file(&file);
+            let diagnostic = Diagnostic::error()
+                .with_message(format!("node `{node_name}` has unexpected dimensions"))
+                .with_notes(mismatches)
+                .with_note(unindent(
+                    "
+                        make sure the component was not resized in Figma by mistake,
+                        or update the `expect` attribute of the resource
+                    ",
+                ))
+                .with_label(Label::primary((), span));
+            print_codespan_diag(diagnostic, &file);
+        }
         SvgToCompose(err) => {
             eprintln!("{err_label} {err:?}", err_label = "error:".red().bold());
         }
@@ -388,6 +701,132 @@ fn handle_evaluation_error(err: phase_evaluation::Error) {
         ConversionError(err) => {
             eprintln!("{err_label} {err}", err_label = "error:".red().bold());
         }
+        VectorTooComplex(err) => eprintln!(
+            "{err_label} {err}\n\n\
+            {tip_label} simplify the node in Figma, raise the `limits` of the profile or set a raster `fallback` profile\n",
+            err_label = "error:".red().bold(),
+            tip_label = "  tip:".green(),
+        ),
+        RasterPdf(err) => eprintln!(
+            "{err_label} {err}\n\n\
+            {tip_label} replace raster fills with vectors in Figma or set `allow_raster = true` for the resource\n",
+            err_label = "error:".red().bold(),
+            tip_label = "  tip:".green(),
+        ),
+        InvalidImage(err) => eprintln!(
+            "{err_label} {err}\n\n\
+            {tip_label} re-run the import with `--refetch`, or set `legacy_loader = true` if the image is rendered locally\n",
+            err_label = "error:".red().bold(),
+            tip_label = "  tip:".green(),
+        ),
+        PostProcess(err) => eprintln!(
+            "{err_label} {err}\n\n\
+            {tip_label} make sure the `post_process` command of the profile works when run manually\n",
+            err_label = "error:".red().bold(),
+            tip_label = "  tip:".green(),
+        ),
+        OutOfDate(paths) => {
+            let current_dir = std::env::current_dir().unwrap_or_default();
+            let list = paths
+                .iter()
+                .map(|it| {
+                    format!(
+                        "  - {}",
+                        it.strip_prefix(&current_dir).unwrap_or(it).display()
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            eprintln!(
+                "{err_label} {n} imported file{s} out of date:\n{list}\n\n\
+                {tip_label} run `figx import` with the same pattern and commit the changes\n",
+                err_label = "error:".red().bold(),
+                tip_label = "  tip:".green(),
+                n = paths.len(),
+                s = if paths.len() == 1 { " is" } else { "s are" },
+            )
+        }
+        ResolveVariable(err) => eprintln!(
+            "{err_label} unable to resolve color variable: {err}\n\n\
+            {tip_label} make sure the variable exists in the Figma file and the access token has the `file_variables:read` scope\n",
+            err_label = "error:".red().bold(),
+            tip_label = "  tip:".green(),
+        ),
+        IncompleteBundle(err) => eprintln!(
+            "{err_label} {err}\n\n\
+            {tip_label} fix the errors of the failed variants, the bundle is written once all of them are imported\n",
+            err_label = "error:".red().bold(),
+            tip_label = "  tip:".green(),
+        ),
+        Cancelled => eprintln!(
+            "{err_label} evaluation was cancelled, partial results are kept",
+            err_label = "error:".red().bold(),
+        ),
+        TargetsFailed(failures) => {
+            let summary = failures
+                .iter()
+                .map(|it| {
+                    (
+                        it.target.clone(),
+                        evaluation_error_summary(&it.error),
+                        it.logs.clone(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            for failure in failures {
+                handle_evaluation_error(failure.error);
+            }
+            let width = summary.iter().map(|(t, _, _)| t.len()).max().unwrap_or(0);
+            eprintln!(
+                "\n{err_label} {n} target{s} failed:\n",
+                err_label = "error:".red().bold(),
+                n = summary.len(),
+                s = if summary.len() == 1 { "" } else { "s" },
+            );
+            for (target, reason, logs) in summary {
+                eprintln!("  {}  {reason}", format!("{target:<width$}").bold());
+                // only the most recent part of the action trail is relevant
+                for line in logs.iter().skip(logs.len().saturating_sub(MAX_TRAIL_LINES)) {
+                    eprintln!("      {}", line.as_str().dark_grey());
+                }
+            }
+            eprintln!();
+        }
+    }
+}
+
+/// Number of captured log lines shown under each failed target
+const MAX_TRAIL_LINES: usize = 10;
+
+/// One-line description of the evaluation error for the keep-going failure table
+fn evaluation_error_summary(err: &phase_evaluation::Error) -> String {
+    use phase_evaluation::Error::*;
+    match err {
+        IO(err) => format!("io error: {err}"),
+        Cache(err) => format!("cache error: {err}"),
+        WebpCreate => "while converting PNG to WEBP".to_string(),
+        ImageDecode(err) => format!("while decoding image from Figma: {err}"),
+        FigmaApiNetwork(err) => format!("while requesting Figma API: {err}"),
+        ExportImage(err) => format!("while exporting image: {err}"),
+        IndexingRemote(err) => format!("while indexing remote: {err}"),
+        FindNode { node_name, .. } => format!("cannot find node with name `{node_name}`"),
+        UnexpectedDimensions {
+            node_name,
+            mismatches,
+            ..
+        } => format!("node `{node_name}`: {}", mismatches.join(", ")),
+        SvgToCompose(err) => format!("{err:?}"),
+        RenderSvg(err) => err.to_owned(),
+        ConversionError(err) => err.to_owned(),
+        VectorTooComplex(err) => err.to_owned(),
+        RasterPdf(err) => err.to_owned(),
+        InvalidImage(err) => err.to_owned(),
+        PostProcess(err) => err.lines().next().unwrap_or_default().to_owned(),
+        OutOfDate(paths) => format!("{} files out of date", paths.len()),
+        ResolveVariable(err) => err.to_owned(),
+        IncompleteBundle(err) => err.to_owned(),
+        TargetsFailed(failures) => format!("{} targets failed", failures.len()),
+        Cancelled => "cancelled".to_string(),
     }
 }
 
@@ -1,24 +1,42 @@
-use std::process::ExitCode;
+use std::{process::ExitCode, time::Duration};
 
 use clap::Parser;
 use cli::{
     Cli, CliSubcommand, CommandCleanArgs, CommandExplainArgs, CommandFetchArgs, CommandImportArgs,
-    CommandInfoArgs, CommandQueryArgs,
+    CommandInfoArgs, CommandQueryArgs, CommandRqueryArgs,
 };
 use command_clean::FeatureCleanOptions;
 use command_explain::FeatureExplainOptions;
 use command_fetch::FeatureFetchOptions;
 use command_import::FeatureImportOptions;
 use command_info::FeatureInfoOptions;
-use command_query::FeatureQueryOptions;
+use command_query::{FeatureQueryOptions, FeatureRqueryOptions};
 
 mod cli;
 mod error;
+use command_dedupe::FeatureDedupeOptions;
+use command_gallery::FeatureGalleryOptions;
+use command_import_config::FeatureImportConfigOptions;
+use command_lint::FeatureLintOptions;
+use command_mirror::FeatureMirrorOptions;
+use command_new::FeatureNewOptions;
+use command_prune::FeaturePruneOptions;
 use command_scan::FeatureScanOptions;
+use command_serve::FeatureServeOptions;
+use command_verify_provenance::FeatureVerifyProvenanceOptions;
 use error::*;
-use lib_dashboard::init_log_impl;
+use lib_dashboard::{
+    LogFormat, OutputMode, init_log_impl, lifecycle, pause_dashboard, set_frame_rate,
+    set_summary_interval,
+};
+use phase_evaluation::CancellationToken;
+use phase_loading::ResourceFilter;
 
-use crate::cli::{CommandAuthArgs, CommandScanArgs};
+use crate::cli::{
+    CommandAuthArgs, CommandDedupeArgs, CommandGalleryArgs, CommandImportConfigArgs,
+    CommandLintArgs, CommandMirrorArgs, CommandNewArgs, CommandPruneArgs, CommandScanArgs,
+    CommandServeArgs, CommandVerifyProvenanceArgs,
+};
 
 pub fn main() -> ExitCode {
     let result = run_app();
@@ -33,9 +51,50 @@ pub fn main() -> ExitCode {
 
 fn run_app() -> Result<()> {
     let cli = Cli::parse();
-    init_log_impl(cli.verbosity);
+    let (log_format, log_format_error) = match cli.log_format {
+        Some(cli::LogFormat::Text) => (LogFormat::Text, None),
+        Some(cli::LogFormat::Json) => (LogFormat::Json, None),
+        None => match std::env::var("FIGX_LOG_FORMAT") {
+            Ok(format) => match format.parse() {
+                Ok(format) => (format, None),
+                Err(e) => (LogFormat::default(), Some(e)),
+            },
+            Err(_) => (LogFormat::default(), None),
+        },
+    };
+    let output_mode = match (cli.quiet, cli.porcelain) {
+        (_, true) => OutputMode::Porcelain,
+        (true, _) => OutputMode::Quiet,
+        _ => OutputMode::Normal,
+    };
+    init_log_impl(cli.verbosity, log_format, output_mode);
+    if let Some(e) = log_format_error {
+        log::warn!(target: "Logger", "ignoring FIGX_LOG_FORMAT: {e}");
+    }
+    set_summary_interval(match cli.progress_interval {
+        0 => None,
+        secs => Some(Duration::from_secs(secs)),
+    });
+    set_frame_rate(cli.frame_rate);
+    let cancellation = set_up_interrupt_handler();
+    let cpu_jobs = cli.cpu_jobs.unwrap_or(cli.jobs);
+    let network_jobs = cli.network_jobs.unwrap_or(0);
 
     match cli.subcommand {
+        CliSubcommand::New(CommandNewArgs { path, template }) => {
+            command_new::new(FeatureNewOptions {
+                path,
+                template: match template {
+                    cli::NewTemplate::Android => command_new::Template::Android,
+                    cli::NewTemplate::Ios => command_new::Template::Ios,
+                    cli::NewTemplate::Web => command_new::Template::Web,
+                },
+            })?
+        }
+        CliSubcommand::ImportConfig(CommandImportConfigArgs { path, output_dir }) => {
+            command_import_config::import_config(FeatureImportConfigOptions { path, output_dir })?
+        }
+
         CliSubcommand::Info(CommandInfoArgs { entity }) => {
             command_info::info(FeatureInfoOptions {
                 entity: match entity {
@@ -45,46 +104,221 @@ fn run_app() -> Result<()> {
             })?
         }
 
-        CliSubcommand::Query(CommandQueryArgs { pattern, output }) => {
-            command_query::query(FeatureQueryOptions {
-                pattern,
-                output: match output {
-                    cli::QueryOutput::Label => command_query::QueryOutputType::Label,
-                    cli::QueryOutput::Profile => command_query::QueryOutputType::Profile,
-                    cli::QueryOutput::Package => command_query::QueryOutputType::Package,
-                    cli::QueryOutput::Tree => command_query::QueryOutputType::Tree,
-                },
-            })?
+        CliSubcommand::Query(CommandQueryArgs {
+            pattern,
+            profile_type,
+            remote,
+            tag,
+            output,
+        }) => command_query::query(FeatureQueryOptions {
+            pattern: with_tag_patterns(pattern, tag),
+            filter: ResourceFilter {
+                profiles: profile_type,
+                remotes: remote,
+                // tags are entered along with the label patterns
+                ..Default::default()
+            },
+            output: match output {
+                cli::QueryOutput::Label => command_query::QueryOutputType::Label,
+                cli::QueryOutput::Profile => command_query::QueryOutputType::Profile,
+                cli::QueryOutput::Package => command_query::QueryOutputType::Package,
+                cli::QueryOutput::Tree => command_query::QueryOutputType::Tree,
+                cli::QueryOutput::Count => command_query::QueryOutputType::Count,
+                cli::QueryOutput::Files => command_query::QueryOutputType::Files,
+                cli::QueryOutput::Hash => command_query::QueryOutputType::Hash,
+            },
+        })?,
+
+        CliSubcommand::Rquery(CommandRqueryArgs { paths }) => {
+            command_query::rquery(FeatureRqueryOptions { paths })?
         }
 
-        CliSubcommand::Explain(CommandExplainArgs { pattern }) => {
-            command_explain::explain(FeatureExplainOptions { pattern })?
+        CliSubcommand::Explain(CommandExplainArgs {
+            pattern,
+            format,
+            summary,
+            depth,
+            details,
+        }) => command_explain::explain(FeatureExplainOptions {
+            pattern,
+            summary,
+            depth,
+            details,
+            format: match format {
+                cli::ExplainFormat::Tree => command_explain::ExplainOutputFormat::Tree,
+                cli::ExplainFormat::Dot => command_explain::ExplainOutputFormat::Dot,
+                cli::ExplainFormat::Mermaid => command_explain::ExplainOutputFormat::Mermaid,
+                cli::ExplainFormat::Json => command_explain::ExplainOutputFormat::Json,
+            },
+        })?,
+
+        CliSubcommand::Fetch(CommandFetchArgs {
+            pattern,
+            profile_type,
+            remote,
+            tag,
+        }) => command_fetch::fetch(FeatureFetchOptions {
+            pattern: with_tag_patterns(pattern, tag),
+            filter: ResourceFilter {
+                profiles: profile_type,
+                remotes: remote,
+                // tags are entered along with the label patterns
+                ..Default::default()
+            },
+            concurrency: cpu_jobs,
+            network_concurrency: network_jobs,
+            cancellation,
+            mirror: cli.mirror,
+        })?,
+
+        CliSubcommand::Import(CommandImportArgs {
+            pattern,
+            profile_type,
+            remote,
+            tag,
+            refetch,
+            keep_going,
+            report,
+            report_junit,
+            profile,
+            trace,
+            keep_intermediates,
+            write_queue,
+            resume_last,
+            check,
+            validate_images,
+            output_base,
+        }) => command_import::import(FeatureImportOptions {
+            pattern: with_tag_patterns(pattern, tag),
+            filter: ResourceFilter {
+                profiles: profile_type,
+                remotes: remote,
+                // tags are entered along with the label patterns
+                ..Default::default()
+            },
+            refetch,
+            keep_going,
+            concurrency: cpu_jobs,
+            network_concurrency: network_jobs,
+            cancellation,
+            report,
+            report_junit,
+            profile,
+            trace,
+            keep_intermediates,
+            write_queue,
+            resume_last,
+            check,
+            validate_images,
+            output_base,
+            mirror: cli.mirror,
+        })?,
+
+        CliSubcommand::Clean(CommandCleanArgs { all }) => {
+            command_clean::clean(FeatureCleanOptions { all })?
         }
 
-        CliSubcommand::Fetch(CommandFetchArgs { pattern }) => {
-            command_fetch::fetch(FeatureFetchOptions {
-                pattern,
-                concurrency: cli.jobs,
+        CliSubcommand::Prune(CommandPruneArgs { dry_run }) => {
+            command_prune::prune(FeaturePruneOptions { dry_run })?
+        }
+
+        CliSubcommand::Auth(CommandAuthArgs { delete }) => command_auth::auth(delete)?,
+
+        CliSubcommand::Scan(CommandScanArgs { remotes }) => {
+            command_scan::scan(FeatureScanOptions { remotes })?
+        }
+
+        CliSubcommand::Mirror(CommandMirrorArgs { remotes, output }) => {
+            command_mirror::mirror(FeatureMirrorOptions {
+                remotes,
+                output,
+                concurrency: cpu_jobs,
+                network_concurrency: network_jobs,
+                cancellation,
             })?
         }
 
-        CliSubcommand::Import(CommandImportArgs { pattern, refetch }) => {
-            command_import::import(FeatureImportOptions {
+        CliSubcommand::Gallery(CommandGalleryArgs { pattern }) => {
+            command_gallery::gallery(FeatureGalleryOptions {
                 pattern,
-                refetch,
-                concurrency: cli.jobs,
+                concurrency: cpu_jobs,
+                network_concurrency: network_jobs,
+                cancellation,
             })?
         }
 
-        CliSubcommand::Clean(CommandCleanArgs { all }) => {
-            command_clean::clean(FeatureCleanOptions { all })?
+        CliSubcommand::Dedupe(CommandDedupeArgs {
+            pattern,
+            report: _,
+            format,
+            threshold,
+        }) => command_dedupe::dedupe(FeatureDedupeOptions {
+            pattern,
+            format: match format {
+                cli::DedupeFormat::Table => command_dedupe::DedupeOutputFormat::Table,
+                cli::DedupeFormat::Json => command_dedupe::DedupeOutputFormat::Json,
+            },
+            threshold,
+            concurrency: cpu_jobs,
+            network_concurrency: network_jobs,
+            cancellation,
+        })?,
+
+        CliSubcommand::Lint(CommandLintArgs { format }) => {
+            command_lint::lint(FeatureLintOptions {
+                format: match format {
+                    cli::LintFormat::Text => command_lint::LintOutputFormat::Text,
+                    cli::LintFormat::Json => command_lint::LintOutputFormat::Json,
+                },
+            })?
         }
 
-        CliSubcommand::Auth(CommandAuthArgs { delete }) => command_auth::auth(delete)?,
+        CliSubcommand::Serve(CommandServeArgs { listen }) => {
+            command_serve::serve(FeatureServeOptions {
+                listen,
+                concurrency: cpu_jobs,
+                network_concurrency: network_jobs,
+                cancellation,
+            })?
+        }
 
-        CliSubcommand::Scan(CommandScanArgs { remotes }) => {
-            command_scan::scan(FeatureScanOptions { remotes })?
+        CliSubcommand::VerifyProvenance(CommandVerifyProvenanceArgs { pattern, strict }) => {
+            command_verify_provenance::verify_provenance(FeatureVerifyProvenanceOptions {
+                pattern,
+                strict,
+            })?
         }
     }
     Ok(())
 }
+
+/// `--tag critical` and `--tag -marketing` are the same as `tag:critical`
+/// and `-tag:marketing` patterns
+fn with_tag_patterns(mut pattern: Vec<String>, tags: Vec<String>) -> Vec<String> {
+    pattern.extend(tags.into_iter().map(|tag| match tag.strip_prefix('-') {
+        Some(tag) => format!("-tag:{tag}"),
+        None => format!("tag:{tag}"),
+    }));
+    pattern
+}
+
+/// The first Ctrl-C cooperatively cancels the evaluation: pending targets are skipped
+/// and already started file writes are allowed to finish, so the cache stays consistent.
+/// The second Ctrl-C, or the first one when nothing is evaluated, terminates the
+/// process immediately.
+fn set_up_interrupt_handler() -> CancellationToken {
+    let cancellation = CancellationToken::default();
+    let token = cancellation.clone();
+    let _ = ctrlc::set_handler(move || {
+        if token.is_cancelled() || !token.is_watched() {
+            std::process::exit(130);
+        }
+        token.cancel();
+        pause_dashboard();
+        lifecycle!(
+            target: "@Cancelling",
+            "waiting for in-flight actions to finish (press Ctrl-C again to force quit)"
+        );
+    });
+    cancellation
+}
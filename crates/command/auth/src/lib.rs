@@ -31,7 +31,9 @@ pub fn auth(delete: bool) -> Result<()> {
             _ => handle_unknown_res(request)?,
         };
     }
-    eprintln!("Token successfully saved!");
+    if !lib_dashboard::is_quiet() {
+        eprintln!("Token successfully saved!");
+    }
     Ok(())
 }
 
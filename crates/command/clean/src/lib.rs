@@ -1,6 +1,6 @@
 mod error;
 pub use error::*;
-use phase_evaluation::{figma::FigmaRepository, setup_cache};
+use phase_evaluation::{AuditAction, AuditLog, figma::FigmaRepository, setup_cache};
 use phase_loading::load_invocation_context;
 
 pub struct FeatureCleanOptions {
@@ -12,7 +12,11 @@ pub fn clean(opts: FeatureCleanOptions) -> Result<()> {
     let cache_dir = ctx.cache_dir;
     match opts {
         FeatureCleanOptions { all: true } => {
-            let _ = std::fs::remove_dir_all(cache_dir);
+            if std::fs::remove_dir_all(&cache_dir).is_ok() {
+                let audit =
+                    AuditLog::open(&ctx.out_dir.join(AuditLog::FILE_NAME), &ctx.workspace_dir);
+                audit.record(AuditAction::Delete, &cache_dir, "figx clean --all");
+            }
         }
         FeatureCleanOptions { all: false } => {
             let cache = setup_cache(&cache_dir)?;
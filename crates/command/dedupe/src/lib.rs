@@ -0,0 +1,593 @@
+use crossterm::style::Stylize;
+use image::{ImageFormat, imageops::FilterType};
+use lib_dashboard::is_quiet;
+use lib_label::LabelPattern;
+use lib_metrics::Metrics;
+use log::{info, warn};
+use phase_evaluation::{
+    CancellationToken, EvalArgs, actions::thumbnail_file_name, figma::indexing::RemoteIndex,
+    setup_cache, targets_from_resource,
+};
+use phase_loading::{RemoteSource, Workspace};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+mod error;
+pub use error::*;
+
+pub struct FeatureDedupeOptions {
+    pub pattern: Vec<String>,
+    pub format: DedupeOutputFormat,
+    /// Maximum Hamming distance between perceptual hashes of similar images
+    pub threshold: u32,
+    pub concurrency: usize,
+    pub network_concurrency: usize,
+    pub cancellation: CancellationToken,
+}
+
+pub enum DedupeOutputFormat {
+    Table,
+    Json,
+}
+
+/// Single target of the workspace with everything needed to compare it with others
+struct DedupeEntry {
+    label: String,
+    variant: Option<String>,
+    thumbnail: String,
+    /// Output file from the last build report, if the target was imported
+    output: Option<PathBuf>,
+    remote: Arc<RemoteSource>,
+    /// Name of the Figma node the target is exported from
+    figma_name: String,
+}
+
+#[derive(Serialize)]
+struct DedupeReportDto<'a> {
+    version: u32,
+    groups: &'a [DuplicateGroup],
+}
+
+#[derive(Serialize)]
+struct DuplicateGroup {
+    kind: DuplicateKind,
+    /// Maximum distance between perceptual hashes within the group
+    #[serde(skip_serializing_if = "Option::is_none")]
+    distance: Option<u32>,
+    targets: Vec<DuplicateTarget>,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+enum DuplicateKind {
+    /// Exported from the same Figma node, its hash covers the whole content of the node
+    SameNode,
+    /// Output files are byte-identical
+    Identical,
+    /// Rendered images look the same
+    Similar,
+}
+
+#[derive(Serialize, Clone, PartialEq, Eq, Debug)]
+struct DuplicateTarget {
+    label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    variant: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output: Option<PathBuf>,
+}
+
+// region: build report
+
+#[derive(Deserialize)]
+struct BuildReportDto {
+    targets: Vec<BuildReportTargetDto>,
+}
+
+#[derive(Deserialize)]
+struct BuildReportTargetDto {
+    label: String,
+    variant: Option<String>,
+    output: Option<BuildReportOutputDto>,
+}
+
+#[derive(Deserialize)]
+struct BuildReportOutputDto {
+    path: PathBuf,
+}
+
+// endregion: build report
+
+pub fn dedupe(opts: FeatureDedupeOptions) -> Result<()> {
+    let pattern = LabelPattern::try_from(opts.pattern)?;
+    let ws = phase_loading::load_workspace(pattern, false)?;
+    let thumbnails_dir = ws.context.out_dir.join("dedupe");
+    let outputs = read_build_report(&ws.context.out_dir.join("build-report.json"));
+    let entries = dedupe_entries(&ws, &outputs);
+    let cache_dir = ws.context.cache_dir.clone();
+
+    let result = phase_evaluation::evaluate(
+        ws,
+        EvalArgs {
+            // images which cannot be rendered are simply not compared
+            keep_going: true,
+            concurrency: opts.concurrency,
+            network_concurrency: opts.network_concurrency,
+            cancellation: opts.cancellation,
+            metrics: Metrics::default(),
+            gallery_dir: Some(thumbnails_dir.clone()),
+            ..Default::default()
+        },
+    );
+    match result {
+        Err(phase_evaluation::Error::Cancelled) => {
+            return Err(phase_evaluation::Error::Cancelled.into());
+        }
+        Err(e) => warn!(target: "Dedupe", "some images were not rendered and are skipped: {e}"),
+        Ok(_) => (),
+    }
+
+    // the evaluation has just indexed the remotes, so the node hashes are in the cache
+    let node_hashes = read_node_hashes(&cache_dir, &entries);
+    let mut groups = same_node_groups(&entries, &node_hashes);
+    let mut identical = identical_groups(&entries, &groups);
+    groups.append(&mut identical);
+    let mut similar = similar_groups(&entries, &thumbnails_dir, opts.threshold, &groups);
+    groups.append(&mut similar);
+    match opts.format {
+        DedupeOutputFormat::Table => print_table(&groups),
+        DedupeOutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&DedupeReportDto {
+                version: 1,
+                groups: &groups,
+            })
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+            println!("{json}");
+        }
+    }
+    Ok(())
+}
+
+/// Output files of the targets imported during the last `figx import`
+fn read_build_report(path: &Path) -> HashMap<(String, Option<String>), PathBuf> {
+    let Ok(json) = std::fs::read(path) else {
+        info!(target: "Dedupe", "no build report found, run `figx import` to compare output files too");
+        return HashMap::new();
+    };
+    match serde_json::from_slice::<BuildReportDto>(&json) {
+        Ok(report) => report
+            .targets
+            .into_iter()
+            .filter_map(|it| Some(((it.label, it.variant), it.output?.path)))
+            .collect(),
+        Err(e) => {
+            warn!(target: "Dedupe", "unable to read build report `{}`: {e}", path.display());
+            HashMap::new()
+        }
+    }
+}
+
+fn dedupe_entries(
+    ws: &Workspace,
+    outputs: &HashMap<(String, Option<String>), PathBuf>,
+) -> Vec<DedupeEntry> {
+    let mut entries = Vec::new();
+    for pkg in &ws.packages {
+        for res in &pkg.resources {
+            for target in targets_from_resource(res) {
+                let label = res.attrs.label.to_string();
+                let variant = target.id.clone();
+                let output = outputs.get(&(label.clone(), variant.clone())).cloned();
+                entries.push(DedupeEntry {
+                    label,
+                    variant: variant.filter(|it| !it.is_empty()),
+                    thumbnail: thumbnail_file_name(&target),
+                    output,
+                    remote: res.attrs.remote.clone(),
+                    figma_name: target.figma_name().to_owned(),
+                });
+            }
+        }
+    }
+    entries
+}
+
+/// Hashes of the nodes of the targets, in the order of the entries, from the cached
+/// indexes of the remotes. `None` if the remote is not indexed yet
+fn read_node_hashes(cache_dir: &Path, entries: &[DedupeEntry]) -> Vec<Option<u64>> {
+    let Ok(cache) = setup_cache(cache_dir) else {
+        return vec![None; entries.len()];
+    };
+    let mut indexes = HashMap::new();
+    entries
+        .iter()
+        .map(|entry| {
+            let index = indexes.entry(entry.remote.id.clone()).or_insert_with(|| {
+                RemoteIndex::get_cached(&cache, &entry.remote)
+                    .ok()
+                    .flatten()
+            });
+            let node = index.as_ref()?.name_to_node.get(&entry.figma_name)?;
+            Some(node.hash)
+        })
+        .collect()
+}
+
+/// Groups of targets exported from the same node, e.g. by several profiles
+fn same_node_groups(entries: &[DedupeEntry], node_hashes: &[Option<u64>]) -> Vec<DuplicateGroup> {
+    let keyed = entries
+        .iter()
+        .zip(node_hashes)
+        .filter_map(|(entry, hash)| Some(((*hash)?, entry)));
+    group_by_key(keyed, DuplicateKind::SameNode, &[])
+}
+
+/// Groups of targets whose output files have the same content. Groups which
+/// are already reported are skipped
+fn identical_groups(entries: &[DedupeEntry], reported: &[DuplicateGroup]) -> Vec<DuplicateGroup> {
+    let keyed = entries.iter().filter_map(|entry| {
+        let bytes = std::fs::read(entry.output.as_ref()?).ok()?;
+        Some((phase_evaluation::digest(&bytes), entry))
+    });
+    group_by_key(keyed, DuplicateKind::Identical, reported)
+}
+
+fn group_by_key<'a>(
+    keyed: impl Iterator<Item = (u64, &'a DedupeEntry)>,
+    kind: DuplicateKind,
+    reported: &[DuplicateGroup],
+) -> Vec<DuplicateGroup> {
+    let mut by_key = HashMap::<u64, Vec<&DedupeEntry>>::new();
+    for (key, entry) in keyed {
+        by_key.entry(key).or_default().push(entry);
+    }
+    let mut groups = by_key
+        .into_values()
+        .filter(|it| it.len() > 1)
+        .map(|it| DuplicateGroup {
+            kind,
+            distance: None,
+            targets: it.into_iter().map(to_duplicate_target).collect(),
+        })
+        .filter(|group| !is_reported(group, reported))
+        .collect::<Vec<_>>();
+    groups.sort_by(|a, b| a.targets[0].label.cmp(&b.targets[0].label));
+    groups
+}
+
+/// Whether all targets of the group are already in one of the reported groups
+fn is_reported(group: &DuplicateGroup, reported: &[DuplicateGroup]) -> bool {
+    reported
+        .iter()
+        .any(|it| group.targets.iter().all(|t| it.targets.contains(t)))
+}
+
+/// Groups of targets whose thumbnails are visually the same. Groups which
+/// are already reported are skipped.
+fn similar_groups(
+    entries: &[DedupeEntry],
+    thumbnails_dir: &Path,
+    threshold: u32,
+    reported: &[DuplicateGroup],
+) -> Vec<DuplicateGroup> {
+    let hashes = entries
+        .iter()
+        .filter_map(|entry| {
+            let png =
+                std::fs::read(thumbnails_dir.join(format!("{}.png", entry.thumbnail))).ok()?;
+            Some((entry, perceptual_hash(&png)?))
+        })
+        .collect::<Vec<_>>();
+    let perceptual_hashes = hashes.iter().map(|(_, hash)| *hash).collect::<Vec<_>>();
+
+    let mut groups = similar_clusters(&perceptual_hashes, threshold)
+        .into_iter()
+        .map(|(members, distance)| DuplicateGroup {
+            kind: DuplicateKind::Similar,
+            distance: Some(distance),
+            targets: members
+                .into_iter()
+                .map(|i| to_duplicate_target(hashes[i].0))
+                .collect(),
+        })
+        .filter(|group| !is_reported(group, reported))
+        .collect::<Vec<_>>();
+    groups.sort_by(|a, b| a.targets[0].label.cmp(&b.targets[0].label));
+    groups
+}
+
+/// Indexes of the hashes within the Hamming distance `threshold` of each other, directly
+/// or through other hashes, with the maximum distance within the cluster. Single hashes
+/// are left out
+fn similar_clusters(hashes: &[u64], threshold: u32) -> Vec<(Vec<usize>, u32)> {
+    // union-find over all pairs of close hashes
+    let mut parents = (0..hashes.len()).collect::<Vec<_>>();
+    fn root(parents: &mut [usize], mut i: usize) -> usize {
+        while parents[i] != i {
+            parents[i] = parents[parents[i]];
+            i = parents[i];
+        }
+        i
+    }
+    for i in 0..hashes.len() {
+        for j in (i + 1)..hashes.len() {
+            if (hashes[i] ^ hashes[j]).count_ones() <= threshold {
+                let (a, b) = (root(&mut parents, i), root(&mut parents, j));
+                parents[a] = b;
+            }
+        }
+    }
+    let mut clusters = HashMap::<usize, Vec<usize>>::new();
+    for i in 0..hashes.len() {
+        let root = root(&mut parents, i);
+        clusters.entry(root).or_default().push(i);
+    }
+
+    let mut clusters = clusters
+        .into_values()
+        .filter(|it| it.len() > 1)
+        .map(|members| {
+            let mut distance = 0;
+            for (n, i) in members.iter().enumerate() {
+                for j in &members[n + 1..] {
+                    distance = distance.max((hashes[*i] ^ hashes[*j]).count_ones());
+                }
+            }
+            (members, distance)
+        })
+        .collect::<Vec<_>>();
+    clusters.sort();
+    clusters
+}
+
+/// Difference hash of the image composited over white: 64 bits, one for every
+/// pair of horizontally adjacent pixels of the 9x8 grayscale thumbnail
+fn perceptual_hash(png: &[u8]) -> Option<u64> {
+    let image = image::load_from_memory_with_format(png, ImageFormat::Png).ok()?;
+    let small = image.resize_exact(9, 8, FilterType::Triangle).to_rgba8();
+    let luma = |x: u32, y: u32| {
+        let [r, g, b, a] = small.get_pixel(x, y).0;
+        let alpha = a as f32 / 255.0;
+        let gray = 0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32;
+        gray * alpha + 255.0 * (1.0 - alpha)
+    };
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash = (hash << 1) | (luma(x, y) > luma(x + 1, y)) as u64;
+        }
+    }
+    Some(hash)
+}
+
+fn to_duplicate_target(entry: &DedupeEntry) -> DuplicateTarget {
+    DuplicateTarget {
+        label: entry.label.clone(),
+        variant: entry.variant.clone(),
+        output: entry.output.clone(),
+    }
+}
+
+fn print_table(groups: &[DuplicateGroup]) {
+    if groups.is_empty() {
+        if !is_quiet() {
+            println!("No duplicates found");
+        }
+        return;
+    }
+    let width = groups
+        .iter()
+        .flat_map(|it| &it.targets)
+        .map(|it| target_name(it).chars().count())
+        .max()
+        .unwrap_or_default();
+    for (i, group) in groups.iter().enumerate() {
+        let kind = match (group.kind, group.distance) {
+            (DuplicateKind::SameNode, _) => "same node".to_string(),
+            (DuplicateKind::Identical, _) => "identical".to_string(),
+            (DuplicateKind::Similar, Some(distance)) => format!("similar (distance {distance})"),
+            (DuplicateKind::Similar, None) => "similar".to_string(),
+        };
+        println!("{} {}", format!("#{}", i + 1).bold(), kind.yellow());
+        for target in &group.targets {
+            let output = target
+                .output
+                .as_ref()
+                .map(|it| it.display().to_string())
+                .unwrap_or_default();
+            println!("  {:<width$}  {}", target_name(target), output.dark_grey());
+        }
+        println!();
+    }
+    if is_quiet() {
+        return;
+    }
+    let targets = groups.iter().map(|it| it.targets.len()).sum::<usize>();
+    println!(
+        "{} targets in {} groups may be consolidated into a single resource each",
+        targets,
+        groups.len()
+    );
+}
+
+fn target_name(target: &DuplicateTarget) -> String {
+    match &target.variant {
+        Some(variant) => format!("{} ({variant})", target.label),
+        None => target.label.clone(),
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use phase_loading::{NodeIdList, RetryPolicy};
+
+    fn entry(label: &str, output: Option<PathBuf>) -> DedupeEntry {
+        DedupeEntry {
+            label: label.to_owned(),
+            variant: None,
+            thumbnail: label.to_owned(),
+            output,
+            remote: Arc::new(RemoteSource {
+                id: "design".to_owned(),
+                file_key: "FILE".to_owned(),
+                branch: None,
+                container_node_ids: NodeIdList::Plain(vec!["0:1".to_owned()]),
+                access_token: String::new(),
+                shallow_depth: None,
+                export_batch_size: RemoteSource::DEFAULT_EXPORT_BATCH_SIZE,
+                retry: RetryPolicy::default(),
+            }),
+            figma_name: label.to_owned(),
+        }
+    }
+
+    fn labels(group: &DuplicateGroup) -> Vec<&str> {
+        group.targets.iter().map(|it| it.label.as_str()).collect()
+    }
+
+    fn encode_png(image: image::RgbaImage) -> Vec<u8> {
+        let mut png = std::io::Cursor::new(Vec::new());
+        image.write_to(&mut png, ImageFormat::Png).unwrap();
+        png.into_inner()
+    }
+
+    fn gradient(brightness: impl Fn(u32) -> u8) -> Vec<u8> {
+        encode_png(image::RgbaImage::from_fn(18, 16, |x, _| {
+            let it = brightness(x);
+            image::Rgba([it, it, it, 255])
+        }))
+    }
+
+    #[test]
+    fn perceptual_hash__gradients__EXPECT__bit_set_where_brightness_decreases() {
+        // When
+        let lighter = perceptual_hash(&gradient(|x| (x * 15) as u8));
+        let darker = perceptual_hash(&gradient(|x| 255 - (x * 15) as u8));
+
+        // Then
+        assert_eq!(Some(0), lighter);
+        assert_eq!(Some(u64::MAX), darker);
+    }
+
+    #[test]
+    fn perceptual_hash__transparent_image__EXPECT__same_as_white() {
+        // When
+        let transparent = perceptual_hash(&encode_png(image::RgbaImage::new(18, 16)));
+        let white = perceptual_hash(&gradient(|_| 255));
+
+        // Then
+        assert_eq!(white, transparent);
+        assert_eq!(None, perceptual_hash(b"not a png"));
+    }
+
+    #[test]
+    fn similar_clusters__chain_of_close_hashes__EXPECT__single_cluster_with_max_distance() {
+        // Given
+        let hashes = [0b0000, 0b0011, 0b1111, u64::MAX];
+
+        // When
+        let clusters = similar_clusters(&hashes, 2);
+
+        // Then
+        assert_eq!(vec![(vec![0, 1, 2], 4)], clusters);
+    }
+
+    #[test]
+    fn similar_clusters__distance_around_threshold__EXPECT__grouped_up_to_threshold() {
+        // Given
+        let hashes = [0b0000, 0b0111];
+
+        // Then
+        assert_eq!(vec![(vec![0, 1], 3)], similar_clusters(&hashes, 3));
+        assert!(similar_clusters(&hashes, 2).is_empty());
+        assert_eq!(vec![(vec![0, 1], 0)], similar_clusters(&[42, 42], 0));
+    }
+
+    #[test]
+    fn identical_groups__same_output_content__EXPECT__grouped_unless_reported() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let file = |name: &str, content: &str| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, content).unwrap();
+            Some(path)
+        };
+        let entries = vec![
+            entry("//a:star", file("a.svg", "<svg>star</svg>")),
+            entry("//b:star", file("b.svg", "<svg>star</svg>")),
+            entry("//c:moon", file("c.svg", "<svg>moon</svg>")),
+            entry("//d:sun", None),
+        ];
+
+        // When
+        let groups = identical_groups(&entries, &[]);
+        let already_reported = identical_groups(&entries, &groups);
+
+        // Then
+        assert_eq!(1, groups.len());
+        assert_eq!(DuplicateKind::Identical, groups[0].kind);
+        let mut grouped = labels(&groups[0]);
+        grouped.sort();
+        assert_eq!(vec!["//a:star", "//b:star"], grouped);
+        assert!(already_reported.is_empty());
+    }
+
+    #[test]
+    fn same_node_groups__same_node_hash__EXPECT__grouped() {
+        // Given
+        let entries = vec![
+            entry("//a:star", None),
+            entry("//b:star", None),
+            entry("//c:moon", None),
+            entry("//d:unknown", None),
+        ];
+        let node_hashes = [Some(1), Some(1), Some(2), None];
+
+        // When
+        let groups = same_node_groups(&entries, &node_hashes);
+
+        // Then
+        assert_eq!(1, groups.len());
+        assert_eq!(DuplicateKind::SameNode, groups[0].kind);
+        let mut grouped = labels(&groups[0]);
+        grouped.sort();
+        assert_eq!(vec!["//a:star", "//b:star"], grouped);
+    }
+
+    #[test]
+    fn read_build_report__valid_missing_and_malformed__EXPECT__outputs_of_valid_only() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let valid = dir.path().join("valid.json");
+        let malformed = dir.path().join("malformed.json");
+        std::fs::write(
+            &valid,
+            r#"{"version":1,"targets":[
+                {"label":"//a:star","variant":"small","status":"imported","output":{"path":"a/star_small.svg"}},
+                {"label":"//b:moon","variant":null,"status":"failed","output":null}
+            ]}"#,
+        )
+        .unwrap();
+        std::fs::write(&malformed, "{").unwrap();
+
+        // When
+        let outputs = read_build_report(&valid);
+
+        // Then
+        assert_eq!(
+            HashMap::from([(
+                ("//a:star".to_owned(), Some("small".to_owned())),
+                PathBuf::from("a/star_small.svg")
+            )]),
+            outputs
+        );
+        assert!(read_build_report(&dir.path().join("missing.json")).is_empty());
+        assert!(read_build_report(&malformed).is_empty());
+    }
+}
@@ -0,0 +1,285 @@
+use crate::{Node, WRITE_TO_FILE};
+use lib_cache::Cache;
+use phase_evaluation::{
+    actions::is_remote_image_cached,
+    figma::{RemoteMetadata, indexing::RemoteIndex},
+    setup_cache,
+};
+use phase_loading::{RemoteSource, Resource, Workspace};
+use std::{collections::BTreeMap, path::PathBuf};
+
+/// Predicts whether the steps of the resources will be served from the cache,
+/// consulting it with the same keys as the import, without touching the network
+pub(crate) struct CacheEstimator {
+    cache: Option<Cache>,
+    /// Remote id => the index of the remote cached for the loaded targets
+    indexes: BTreeMap<String, Option<RemoteMetadata>>,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum CacheEstimate {
+    Yes,
+    No,
+    Unknown,
+}
+
+impl CacheEstimator {
+    pub(crate) fn new(ws: &Workspace) -> Self {
+        let remotes = ws
+            .packages
+            .iter()
+            .flat_map(|pkg| &pkg.resources)
+            .map(|res| (res.attrs.remote.id.as_str(), &res.attrs.remote))
+            .collect::<BTreeMap<_, _>>();
+        let cache = setup_cache(&ws.context.cache_dir).ok();
+        let indexes = remotes
+            .into_iter()
+            .map(|(id, remote)| {
+                let index = cache
+                    .as_ref()
+                    .and_then(|cache| RemoteIndex::get_cached(cache, remote).ok())
+                    .flatten();
+                (id.to_owned(), index)
+            })
+            .collect();
+        Self { cache, indexes }
+    }
+
+    fn index(&self, remote: &RemoteSource) -> Option<&RemoteMetadata> {
+        self.indexes.get(&remote.id)?.as_ref()
+    }
+
+    /// `None` if the step does not export images, otherwise whether the exported image
+    /// is cached. Unknown without the cached index, as the cache key needs the node hash
+    fn export_estimate(
+        &self,
+        step: &Node,
+        remote: &RemoteSource,
+        use_absolute_bounds: bool,
+    ) -> Option<CacheEstimate> {
+        let export = step.export.as_ref()?;
+        let node = self
+            .index(remote)
+            .and_then(|index| index.name_to_node.get(&export.node));
+        let (Some(cache), Some(node)) = (&self.cache, node) else {
+            return Some(CacheEstimate::Unknown);
+        };
+        let cached = is_remote_image_cached(
+            cache,
+            remote,
+            node,
+            export.format,
+            export.scale,
+            use_absolute_bounds,
+        );
+        Some(match cached {
+            Ok(true) => CacheEstimate::Yes,
+            Ok(false) => CacheEstimate::No,
+            Err(_) => CacheEstimate::Unknown,
+        })
+    }
+}
+
+/// Adds whether the index of the remote is cached to the resource, the absolute path
+/// to every written file and the cache estimate to every step
+pub(crate) fn annotate(node: &mut Node, res: &Resource, estimator: &CacheEstimator) {
+    let index = match estimator.index(&res.attrs.remote) {
+        Some(_) => "cached",
+        None => "not cached",
+    };
+    node.params.push(("index", index.to_owned()));
+    let outputs = res.expected_outputs();
+    annotate_steps(node, res, &outputs, estimator, CacheEstimate::Unknown);
+}
+
+/// Steps of a pipeline follow its export, so they are cached as long as the export is
+fn annotate_steps(
+    node: &mut Node,
+    res: &Resource,
+    outputs: &[PathBuf],
+    estimator: &CacheEstimator,
+    inherited: CacheEstimate,
+) {
+    let mut estimate = inherited;
+    for child in &mut node.children {
+        if !child.children.is_empty() {
+            annotate_steps(child, res, outputs, estimator, estimate);
+            continue;
+        }
+        let remote = &res.attrs.remote;
+        if let Some(exported) =
+            estimator.export_estimate(child, remote, res.attrs.use_absolute_bounds)
+        {
+            estimate = exported;
+        }
+        annotate_step(child, outputs, estimate);
+    }
+}
+
+fn annotate_step(step: &mut Node, outputs: &[PathBuf], mut estimate: CacheEstimate) {
+    if step.name == WRITE_TO_FILE {
+        let output = step
+            .params
+            .iter()
+            .find(|(key, _)| *key == "output")
+            .map(|(_, value)| value.clone());
+        let path = output.and_then(|output| outputs.iter().find(|it| it.ends_with(&output)));
+        if let Some(path) = path {
+            step.params.push(("path", path.display().to_string()));
+            // a missing file is written again even if its content is cached
+            if !path.exists() {
+                estimate = CacheEstimate::No;
+            }
+        }
+    }
+    let estimate = match estimate {
+        CacheEstimate::Yes => "yes",
+        CacheEstimate::No => "no",
+        CacheEstimate::Unknown => "unknown",
+    };
+    step.params.push(("cached", estimate.to_owned()));
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use crate::{ExportedImage, export_step};
+    use phase_evaluation::figma::NodeMetadata;
+    use phase_loading::{NodeIdList, RetryPolicy};
+    use std::collections::HashMap;
+
+    fn remote() -> RemoteSource {
+        RemoteSource {
+            id: "design".to_owned(),
+            file_key: "FILE".to_owned(),
+            branch: None,
+            container_node_ids: NodeIdList::Plain(vec!["0:1".to_owned()]),
+            access_token: String::new(),
+            shallow_depth: None,
+            export_batch_size: RemoteSource::DEFAULT_EXPORT_BATCH_SIZE,
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    fn estimator(cache: Option<Cache>, nodes: &[&str]) -> CacheEstimator {
+        let name_to_node = nodes
+            .iter()
+            .map(|name| {
+                let node = NodeMetadata {
+                    id: "1:2".to_owned(),
+                    name: name.to_string(),
+                    hash: 0xABCD,
+                    uses_raster_paints: false,
+                    bounds: None,
+                    render_bounds: None,
+                };
+                (name.to_string(), node)
+            })
+            .collect::<HashMap<_, _>>();
+        CacheEstimator {
+            cache,
+            indexes: BTreeMap::from([("design".to_owned(), Some(RemoteMetadata { name_to_node }))]),
+        }
+    }
+
+    #[test]
+    fn export_estimate__other_steps__EXPECT__none() {
+        // Given
+        let estimator = estimator(None, &["Star"]);
+        let step = Node {
+            name: "📤 Export SVG from remote @design/FILE".to_owned(),
+            params: vec![("node", "Star".to_owned())],
+            ..Default::default()
+        };
+
+        // When
+        let estimate = estimator.export_estimate(&step, &remote(), false);
+
+        // Then
+        assert_eq!(None, estimate);
+    }
+
+    #[test]
+    fn export_estimate__no_cached_index__EXPECT__unknown() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let cache = setup_cache(dir.path()).unwrap();
+        let estimator = CacheEstimator {
+            cache: Some(cache),
+            indexes: BTreeMap::from([("design".to_owned(), None)]),
+        };
+        let step = export_step(&remote(), "svg", "Star", None);
+
+        // When
+        let estimate = estimator.export_estimate(&step, &remote(), false);
+
+        // Then
+        assert_eq!(Some(CacheEstimate::Unknown), estimate);
+    }
+
+    #[test]
+    fn export_estimate__node_missing_in_index__EXPECT__unknown() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let cache = setup_cache(dir.path()).unwrap();
+        let estimator = estimator(Some(cache), &["Star"]);
+        let step = export_step(&remote(), "svg", "Moon", None);
+
+        // When
+        let estimate = estimator.export_estimate(&step, &remote(), false);
+
+        // Then
+        assert_eq!(Some(CacheEstimate::Unknown), estimate);
+    }
+
+    #[test]
+    fn export_estimate__indexed_node_not_exported_yet__EXPECT__not_cached() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let cache = setup_cache(dir.path()).unwrap();
+        let estimator = estimator(Some(cache), &["Star"]);
+        let step = Node {
+            // the name is for humans only, the estimate relies on the exported image
+            name: "Export".to_owned(),
+            export: Some(ExportedImage {
+                format: "png",
+                node: "Star".to_owned(),
+                scale: 8.0,
+            }),
+            ..Default::default()
+        };
+
+        // When
+        let estimate = estimator.export_estimate(&step, &remote(), false);
+
+        // Then
+        assert_eq!(Some(CacheEstimate::No), estimate);
+    }
+
+    #[test]
+    fn annotate_step__written_file_is_missing__EXPECT__not_cached() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let outputs = vec![dir.path().join("img/star.svg")];
+        let mut step = Node {
+            name: WRITE_TO_FILE.to_owned(),
+            params: vec![("output", "star.svg".to_owned())],
+            ..Default::default()
+        };
+
+        // When
+        annotate_step(&mut step, &outputs, CacheEstimate::Yes);
+
+        // Then
+        let path = outputs[0].display().to_string();
+        assert_eq!(
+            vec![
+                ("output", "star.svg".to_owned()),
+                ("path", path),
+                ("cached", "no".to_owned()),
+            ],
+            step.params
+        );
+    }
+}
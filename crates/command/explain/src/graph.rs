@@ -0,0 +1,189 @@
+use crate::Node;
+use std::fmt::Write;
+
+/// Flat representation of the explained resource trees:
+/// every step is a vertex and every edge points in the direction of data flow.
+#[derive(Default)]
+pub(crate) struct Graph {
+    vertices: Vec<Vertex>,
+    edges: Vec<(usize, usize)>,
+}
+
+struct Vertex {
+    name: String,
+    params: Vec<(&'static str, String)>,
+}
+
+impl Graph {
+    /// Steps deeper than `depth` levels below the resources are left out
+    pub(crate) fn from_trees(nodes: &[Node], depth: Option<usize>) -> Self {
+        let mut graph = Graph::default();
+        for node in nodes {
+            graph.add_tree(node, depth);
+        }
+        graph
+    }
+
+    /// Adds node and its children, returns the id of the added node.
+    ///
+    /// Leaf children are the sequential steps of a pipeline, so they are chained one after
+    /// another and the last one flows into the parent. Non-leaf children (variants) are
+    /// independent inputs of the parent.
+    fn add_tree(&mut self, node: &Node, depth: Option<usize>) -> usize {
+        let id = self.add_vertex(node);
+        if depth == Some(0) && !node.children.is_empty() {
+            let hidden = format!("{} steps", node.count_steps(|_| true));
+            self.vertices[id].params.push(("hidden", hidden));
+            return id;
+        }
+        let depth = depth.map(|it| it.saturating_sub(1));
+        let mut previous_step = None;
+        for child in &node.children {
+            if child.children.is_empty() {
+                let step = self.add_vertex(child);
+                if let Some(previous_step) = previous_step {
+                    self.edges.push((previous_step, step));
+                }
+                previous_step = Some(step);
+            } else {
+                let child_id = self.add_tree(child, depth);
+                self.edges.push((child_id, id));
+            }
+        }
+        if let Some(last_step) = previous_step {
+            self.edges.push((last_step, id));
+        }
+        id
+    }
+
+    fn add_vertex(&mut self, node: &Node) -> usize {
+        self.vertices.push(Vertex {
+            name: node.name.clone(),
+            params: node.params.clone(),
+        });
+        self.vertices.len() - 1
+    }
+
+    pub(crate) fn to_dot(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "digraph figx {{");
+        let _ = writeln!(out, "  rankdir=LR;");
+        let _ = writeln!(out, "  node [shape=box, fontname=\"monospace\"];");
+        for (id, vertex) in self.vertices.iter().enumerate() {
+            let label = vertex.lines().map(escape_dot).collect::<Vec<_>>();
+            let _ = writeln!(out, "  n{id} [label=\"{}\"];", label.join("\\n"));
+        }
+        for (from, to) in &self.edges {
+            let _ = writeln!(out, "  n{from} -> n{to};");
+        }
+        let _ = writeln!(out, "}}");
+        out
+    }
+
+    pub(crate) fn to_mermaid(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "flowchart LR");
+        for (id, vertex) in self.vertices.iter().enumerate() {
+            let label = vertex.lines().map(escape_mermaid).collect::<Vec<_>>();
+            let _ = writeln!(out, "  n{id}[\"{}\"]", label.join("<br/>"));
+        }
+        for (from, to) in &self.edges {
+            let _ = writeln!(out, "  n{from} --> n{to}");
+        }
+        out
+    }
+}
+
+impl Vertex {
+    fn lines(&self) -> impl Iterator<Item = String> + '_ {
+        std::iter::once(self.name.clone()).chain(
+            self.params
+                .iter()
+                .map(|(key, value)| format!("{key}: {value}")),
+        )
+    }
+}
+
+fn escape_dot(line: String) -> String {
+    line.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_mermaid(line: String) -> String {
+    line.replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    fn step(name: &str) -> Node {
+        Node {
+            name: name.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    /// Resource with a variant of two steps followed by one more step
+    fn tree() -> Node {
+        Node {
+            name: "//icons:star".to_owned(),
+            children: vec![
+                Node {
+                    name: "Variant 'dark'".to_owned(),
+                    children: vec![step("Export"), step("Write")],
+                    ..Default::default()
+                },
+                step("Add"),
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn names(graph: &Graph) -> Vec<&str> {
+        graph.vertices.iter().map(|it| it.name.as_str()).collect()
+    }
+
+    #[test]
+    fn Graph__unlimited_depth__EXPECT__steps_chained_into_parents() {
+        // When
+        let graph = Graph::from_trees(&[tree()], None);
+
+        // Then
+        assert_eq!(
+            vec!["//icons:star", "Variant 'dark'", "Export", "Write", "Add"],
+            names(&graph)
+        );
+        assert_eq!(vec![(2, 3), (3, 1), (1, 0), (4, 0)], graph.edges);
+    }
+
+    #[test]
+    fn Graph__depth_0__EXPECT__only_resources_with_hidden_steps() {
+        // When
+        let graph = Graph::from_trees(&[tree(), tree()], Some(0));
+
+        // Then
+        assert_eq!(vec!["//icons:star", "//icons:star"], names(&graph));
+        assert_eq!(
+            vec![("hidden", "3 steps".to_owned())],
+            graph.vertices[0].params
+        );
+        assert!(graph.edges.is_empty());
+    }
+
+    #[test]
+    fn Graph__depth_1__EXPECT__steps_of_variants_hidden() {
+        // When
+        let graph = Graph::from_trees(&[tree()], Some(1));
+
+        // Then
+        assert_eq!(vec!["//icons:star", "Variant 'dark'", "Add"], names(&graph));
+        assert_eq!(
+            vec![("hidden", "2 steps".to_owned())],
+            graph.vertices[1].params
+        );
+        assert_eq!(vec![(1, 0), (2, 0)], graph.edges);
+    }
+}
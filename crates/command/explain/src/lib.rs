@@ -1,19 +1,41 @@
 use crossterm::style::Stylize;
 use lib_label::LabelPattern;
-use phase_evaluation::{
-    actions::{get_kotlin_package, get_output_dir_for_compose_profile},
-    targets_from_resource,
-};
+use phase_evaluation::{actions::get_kotlin_package, targets_from_resource};
 use phase_loading::{
-    AndroidDrawableProfile, AndroidWebpProfile, ComposeProfile, PdfProfile, PngProfile, Profile,
-    Resource, SvgProfile, WebpProfile,
+    AndroidDrawableProfile, AndroidWebpProfile, ComposeProfile, FaviconProfile, FlutterProfile,
+    PdfProfile, PngProfile, Profile, ReactProfile, RemoteSource, Resource, SvgProfile, WebpProfile,
 };
+use serde::{Serialize, ser::SerializeMap};
 
+mod details;
+use details::{CacheEstimator, annotate};
 mod error;
 pub use error::*;
+mod graph;
+use graph::Graph;
+mod summary;
+use summary::{PackageSummary, print_summary};
 
 pub struct FeatureExplainOptions {
     pub pattern: Vec<String>,
+    pub format: ExplainOutputFormat,
+    /// Print per-package statistics instead of the resource trees
+    pub summary: bool,
+    /// Levels of steps shown below every resource, all of them if `None`
+    pub depth: Option<usize>,
+    /// Add absolute paths of the written files and the cache estimate of every step
+    pub details: bool,
+}
+
+pub enum ExplainOutputFormat {
+    /// Human-readable tree
+    Tree,
+    /// Graphviz DOT
+    Dot,
+    /// Mermaid flowchart
+    Mermaid,
+    /// JSON array of the resource trees for tooling
+    Json,
 }
 
 #[derive(Default)]
@@ -21,6 +43,17 @@ struct Node {
     name: String,
     children: Vec<Node>,
     params: Vec<(&'static str, String)>,
+    /// Set on the steps which export images from the remote
+    export: Option<ExportedImage>,
+}
+
+/// Image requested from the remote by an export step, as the cache keys of the import need it
+struct ExportedImage {
+    /// Format of the export in Figma terms, e.g. `svg`
+    format: &'static str,
+    /// Name of the node in Figma
+    node: String,
+    scale: f32,
 }
 
 macro_rules! node {
@@ -28,7 +61,7 @@ macro_rules! node {
         Node { name: $name.to_string(), params: vec![ $( $par ),* ], ..Default::default() }
     };
     ($name:expr, [ $($par:expr),* ], $( $ch:expr ),+) => {
-        Node { name: $name.to_string(), params: vec![ $( $par ),* ], children: vec![ $( $ch ),+ ] }
+        Node { name: $name.to_string(), params: vec![ $( $par ),* ], children: vec![ $( $ch ),+ ], ..Default::default() }
     };
     ($name:expr, $( $ch:expr ),+) => {
         Node { name: $name.to_string(), children: vec![ $( $ch ),+ ], ..Default::default() }
@@ -39,35 +72,145 @@ pub fn explain(opts: FeatureExplainOptions) -> Result<()> {
     let pattern = LabelPattern::try_from(opts.pattern)?;
     let ws = phase_loading::load_workspace(pattern, true)?;
 
-    let mut nodes = Vec::with_capacity(1024);
-    for res in ws.packages.iter().flat_map(|pkg| &pkg.resources) {
-        let node = match res.profile.as_ref() {
-            Profile::Png(p) => png_resource_tree(res, p),
-            Profile::Svg(p) => svg_resource_tree(res, p),
-            Profile::Pdf(p) => pdf_resource_tree(res, p),
-            Profile::Webp(p) => webp_resource_tree(res, p),
-            Profile::Compose(p) => compose_resource_tree(res, p),
-            Profile::AndroidWebp(p) => android_webp_resource_tree(res, p),
-            Profile::AndroidDrawable(p) => android_drawable_resource_tree(res, p),
-        };
-        nodes.push(node);
+    if opts.summary {
+        let summaries = ws
+            .packages
+            .iter()
+            .map(|pkg| {
+                let mut summary = PackageSummary::new(pkg.label.to_string());
+                for res in &pkg.resources {
+                    summary.add_resource(
+                        &resource_tree(res),
+                        targets_from_resource(res).len(),
+                        &res.attrs.remote.id,
+                    );
+                }
+                summary
+            })
+            .collect::<Vec<_>>();
+        print_summary(&summaries);
+        return Ok(());
     }
 
-    for node in nodes {
-        println!("{node}");
+    let estimator = opts.details.then(|| CacheEstimator::new(&ws));
+    let nodes = ws
+        .packages
+        .iter()
+        .flat_map(|pkg| &pkg.resources)
+        .map(|res| {
+            let mut node = resource_tree(res);
+            if let Some(estimator) = &estimator {
+                annotate(&mut node, res, estimator);
+            }
+            node
+        })
+        .collect::<Vec<_>>();
+    let depth = opts.depth;
+    match opts.format {
+        ExplainOutputFormat::Tree => {
+            for node in &nodes {
+                println!("{}", LimitedNode { node, depth });
+            }
+        }
+        ExplainOutputFormat::Dot => print!("{}", Graph::from_trees(&nodes, depth).to_dot()),
+        ExplainOutputFormat::Mermaid => {
+            print!("{}", Graph::from_trees(&nodes, depth).to_mermaid())
+        }
+        ExplainOutputFormat::Json => {
+            let nodes = nodes
+                .iter()
+                .map(|node| LimitedNode { node, depth })
+                .collect::<Vec<_>>();
+            let json = serde_json::to_string_pretty(&nodes).expect("trees are always serializable");
+            println!("{json}");
+        }
     }
 
     Ok(())
 }
 
-impl std::fmt::Display for Node {
+fn resource_tree(res: &Resource) -> Node {
+    let node = match res.profile.as_ref() {
+        Profile::Png(p) => png_resource_tree(res, p),
+        Profile::Svg(p) => svg_resource_tree(res, p),
+        Profile::Pdf(p) => pdf_resource_tree(res, p),
+        Profile::Webp(p) => webp_resource_tree(res, p),
+        Profile::Compose(p) => compose_resource_tree(res, p),
+        Profile::AndroidWebp(p) => android_webp_resource_tree(res, p),
+        Profile::AndroidDrawable(p) => android_drawable_resource_tree(res, p),
+        Profile::Flutter(p) => flutter_resource_tree(res, p),
+        Profile::React(p) => react_resource_tree(res, p),
+        Profile::Favicon(p) => favicon_resource_tree(res, p),
+    };
+    match res.profile.post_process() {
+        Some(command) => with_post_process(node, &command.to_string()),
+        None => node,
+    }
+}
+
+/// Resource tree cut `depth` levels below the resource, the whole tree if `None`
+struct LimitedNode<'a> {
+    node: &'a Node,
+    depth: Option<usize>,
+}
+
+impl std::fmt::Display for LimitedNode<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.fmt_tree(f, "")
+        self.node.fmt_tree(f, "", self.depth)
+    }
+}
+
+impl Serialize for LimitedNode<'_> {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        struct Params<'a>(&'a [(&'static str, String)]);
+        impl Serialize for Params<'_> {
+            fn serialize<S: serde::Serializer>(
+                &self,
+                serializer: S,
+            ) -> std::result::Result<S::Ok, S::Error> {
+                // keeps the order of the params, unlike a map type
+                let mut map = serializer.serialize_map(Some(self.0.len()))?;
+                for (key, value) in self.0 {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+        }
+
+        let node = self.node;
+        let hidden = self.depth == Some(0) && !node.children.is_empty();
+        let children = match hidden {
+            true => Vec::new(),
+            false => node
+                .children
+                .iter()
+                .map(|child| LimitedNode {
+                    node: child,
+                    depth: self.depth.map(|it| it.saturating_sub(1)),
+                })
+                .collect(),
+        };
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("name", &node.name)?;
+        map.serialize_entry("params", &Params(&node.params))?;
+        map.serialize_entry("children", &children)?;
+        if hidden {
+            map.serialize_entry("hidden_steps", &node.count_steps(|_| true))?;
+        }
+        map.end()
     }
 }
 
 impl Node {
-    fn fmt_tree(&self, f: &mut std::fmt::Formatter<'_>, prefix: &str) -> std::fmt::Result {
+    fn fmt_tree(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        prefix: &str,
+        depth: Option<usize>,
+    ) -> std::fmt::Result {
         // Print current node
         writeln!(f, "{}", self.name.clone().bold())?;
         for (param_key, param_value) in &self.params {
@@ -80,6 +223,12 @@ impl Node {
                 param_value
             )?;
         }
+        if depth == Some(0) && !self.children.is_empty() {
+            let hidden = format!("… {} steps hidden", self.count_steps(|_| true));
+            writeln!(f, "{prefix}   {} {}", "┆".dark_grey(), hidden.dark_grey())?;
+            return Ok(());
+        }
+        let depth = depth.map(|it| it.saturating_sub(1));
 
         // Обрабатываем всех детей кроме последнего
         let middle_children = self.children.len().saturating_sub(1);
@@ -88,7 +237,7 @@ impl Node {
             write!(f, "{prefix}{corner} ", corner = "├──".dark_grey())?;
             // Префикс для детей текущего узла
             let new_prefix = format!("{prefix}{border}   ", border = "│".dark_grey());
-            child.fmt_tree(f, &new_prefix)?;
+            child.fmt_tree(f, &new_prefix, depth)?;
         }
 
         // Обрабатываем последнего ребенка (если есть)
@@ -97,13 +246,58 @@ impl Node {
             write!(f, "{prefix}{corner} ", corner = "╰──".dark_grey())?;
             // Префикс для детей последнего узла (пробелы вместо │)
             let new_prefix = format!("{prefix}    ");
-            last_child.fmt_tree(f, &new_prefix)?;
+            last_child.fmt_tree(f, &new_prefix, depth)?;
         }
 
         Ok(())
     }
 }
 
+/// Adds the post-processing step after the first file written by every pipeline,
+/// the following ones are auxiliary files like previews
+fn with_post_process(mut node: Node, command: &str) -> Node {
+    let mut children = Vec::with_capacity(node.children.len() + 1);
+    let mut processed = false;
+    for child in node.children {
+        let writes_output = child.name == WRITE_TO_FILE && child.children.is_empty();
+        children.push(with_post_process(child, command));
+        if writes_output && !processed {
+            children.push(node!("🛠️ Post-process", [("command", command.to_owned())]));
+            processed = true;
+        }
+    }
+    node.children = children;
+    node
+}
+
+const WRITE_TO_FILE: &str = "💾 Write to file";
+
+/// Step exporting the node from the remote, the scale is set only for raster exports
+fn export_step(
+    remote: &RemoteSource,
+    format: &'static str,
+    node: &str,
+    scale: Option<f32>,
+) -> Node {
+    let mut params = vec![("node", node.to_owned())];
+    if let Some(scale) = scale {
+        params.push(("scale", scale.to_string()));
+    }
+    Node {
+        name: format!(
+            "📤 Export {} from remote {remote}",
+            format.to_ascii_uppercase()
+        ),
+        params,
+        export: Some(ExportedImage {
+            format,
+            node: node.to_owned(),
+            scale: scale.unwrap_or(1.0),
+        }),
+        ..Default::default()
+    }
+}
+
 fn png_resource_tree(res: &Resource, p: &PngProfile) -> Node {
     let attrs = &res.attrs;
     let targets = targets_from_resource(res);
@@ -111,31 +305,33 @@ fn png_resource_tree(res: &Resource, p: &PngProfile) -> Node {
     let mut root_node = Node {
         name: attrs.label.to_string(),
         children: Vec::new(),
-        params: Vec::new(),
+        ..Default::default()
     };
     for t in targets {
         let mut child_nodes = Vec::with_capacity(4);
         let scale = t.scale.unwrap_or(*p.scale);
         if p.legacy_loader {
-            child_nodes.push(node!(
-                format!("📤 Export PNG from remote {}", attrs.remote),
-                [
-                    ("node", t.figma_name().to_string()),
-                    ("scale", scale.to_string())
-                ]
+            child_nodes.push(export_step(
+                &attrs.remote,
+                "png",
+                t.figma_name(),
+                Some(scale),
             ));
         } else {
-            child_nodes.push(node!(
-                format!("📤 Export SVG from remote {}", attrs.remote),
-                [("node", t.figma_name().to_string())]
-            ));
+            child_nodes.push(export_step(&attrs.remote, "svg", t.figma_name(), None));
             child_nodes.push(node!(
                 "🎨 Render PNG locally",
                 [("scale", scale.to_string())]
             ));
         }
+        if p.optimize {
+            child_nodes.push(node!(
+                "✨ Optimize PNG",
+                [("level", (*p.optimization_level).to_string())]
+            ));
+        }
         child_nodes.push(node!(
-            "💾 Write to file",
+            WRITE_TO_FILE,
             [("output", format!("{}.png", t.output_name()))]
         ));
 
@@ -143,7 +339,7 @@ fn png_resource_tree(res: &Resource, p: &PngProfile) -> Node {
             let variant_node = Node {
                 name: format!("Variant '{}'", variant_id),
                 children: child_nodes,
-                params: Vec::new(),
+                ..Default::default()
             };
             root_node.children.push(variant_node);
         } else {
@@ -160,16 +356,13 @@ fn svg_resource_tree(res: &Resource, _p: &SvgProfile) -> Node {
     let mut root_node = Node {
         name: attrs.label.to_string(),
         children: Vec::new(),
-        params: Vec::new(),
+        ..Default::default()
     };
     for t in targets {
         let mut child_nodes = vec![
+            export_step(&attrs.remote, "svg", t.figma_name(), None),
             node!(
-                format!("📤 Export SVG from remote {}", attrs.remote),
-                [("node", t.figma_name().to_string())]
-            ),
-            node!(
-                "💾 Write to file",
+                WRITE_TO_FILE,
                 [("output", format!("{}.svg", t.output_name()))]
             ),
         ];
@@ -178,7 +371,7 @@ fn svg_resource_tree(res: &Resource, _p: &SvgProfile) -> Node {
             let variant_node = Node {
                 name: format!("Variant '{}'", variant_id),
                 children: child_nodes,
-                params: Vec::new(),
+                ..Default::default()
             };
             root_node.children.push(variant_node);
         } else {
@@ -188,33 +381,107 @@ fn svg_resource_tree(res: &Resource, _p: &SvgProfile) -> Node {
     root_node
 }
 
-fn pdf_resource_tree(res: &Resource, _p: &PdfProfile) -> Node {
+fn pdf_resource_tree(res: &Resource, p: &PdfProfile) -> Node {
     let attrs = &res.attrs;
     let targets = targets_from_resource(res);
 
     let mut root_node = Node {
         name: attrs.label.to_string(),
         children: Vec::new(),
-        params: Vec::new(),
+        ..Default::default()
     };
 
     for t in targets {
+        let mut child_nodes = Vec::with_capacity(3);
+        if p.local_renderer {
+            child_nodes.push(export_step(&attrs.remote, "svg", t.figma_name(), None));
+            child_nodes.push(node!("✨ Convert SVG to PDF locally", []));
+        } else {
+            child_nodes.push(export_step(&attrs.remote, "pdf", t.figma_name(), None));
+        }
+        child_nodes.push(node!(
+            WRITE_TO_FILE,
+            [("output", format!("{}.pdf", t.output_name()))]
+        ));
+        for scale in &p.preview_scales {
+            // previews are rendered from the same SVG, or exported as PNG otherwise
+            if p.local_renderer {
+                child_nodes.push(node!(
+                    "🎨 Render PNG locally",
+                    [("scale", scale.to_string())]
+                ));
+            } else {
+                child_nodes.push(export_step(
+                    &attrs.remote,
+                    "png",
+                    t.figma_name(),
+                    Some(**scale),
+                ));
+            }
+            child_nodes.push(node!(
+                WRITE_TO_FILE,
+                [(
+                    "output",
+                    PdfProfile::preview_file_name(t.output_name(), **scale)
+                )]
+            ));
+        }
+
+        if let Some(variant_id) = t.id {
+            let variant_node = Node {
+                name: format!("Variant '{}'", variant_id),
+                children: child_nodes,
+                ..Default::default()
+            };
+            root_node.children.push(variant_node);
+        } else {
+            root_node.children.append(&mut child_nodes);
+        }
+    }
+    root_node
+}
+
+fn favicon_resource_tree(res: &Resource, p: &FaviconProfile) -> Node {
+    let attrs = &res.attrs;
+    let targets = targets_from_resource(res);
+
+    let mut root_node = Node {
+        name: attrs.label.to_string(),
+        children: Vec::new(),
+        ..Default::default()
+    };
+    for t in targets {
+        let name = t.output_name();
+        let sizes = p
+            .sizes
+            .iter()
+            .map(|it| it.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
         let mut child_nodes = vec![
-            node!(
-                format!("📤 Export PDF from remote {}", attrs.remote),
-                [("node", t.figma_name().to_string())]
-            ),
-            node!(
-                "💾 Write to file",
-                [("output", format!("{}.pdf", t.output_name()))]
-            ),
+            export_step(&attrs.remote, "svg", t.figma_name(), None),
+            node!("🎨 Render PNG locally", [("sizes", sizes)]),
+            node!("✨ Pack PNG into ICO", []),
+            node!(WRITE_TO_FILE, [("output", format!("{name}.ico"))]),
         ];
+        for size in p.sizes.iter().map(|it| **it) {
+            child_nodes.push(node!(
+                WRITE_TO_FILE,
+                [("output", format!("{name}-{size}x{size}.png"))]
+            ));
+        }
+        if p.webmanifest {
+            child_nodes.push(node!(
+                WRITE_TO_FILE,
+                [("output", format!("{name}.webmanifest"))]
+            ));
+        }
 
         if let Some(variant_id) = t.id {
             let variant_node = Node {
                 name: format!("Variant '{}'", variant_id),
                 children: child_nodes,
-                params: Vec::new(),
+                ..Default::default()
             };
             root_node.children.push(variant_node);
         } else {
@@ -231,24 +498,20 @@ fn webp_resource_tree(res: &Resource, p: &WebpProfile) -> Node {
     let mut root_node = Node {
         name: attrs.label.to_string(),
         children: Vec::new(),
-        params: Vec::new(),
+        ..Default::default()
     };
     for t in targets {
         let mut child_nodes = Vec::with_capacity(4);
         let scale = t.scale.unwrap_or(*p.scale);
         if p.legacy_loader {
-            child_nodes.push(node!(
-                format!("📤 Export PNG from remote {}", attrs.remote),
-                [
-                    ("node", t.figma_name().to_string()),
-                    ("scale", scale.to_string())
-                ]
+            child_nodes.push(export_step(
+                &attrs.remote,
+                "png",
+                t.figma_name(),
+                Some(scale),
             ));
         } else {
-            child_nodes.push(node!(
-                format!("📤 Export SVG from remote {}", attrs.remote),
-                [("node", t.figma_name().to_string())]
-            ));
+            child_nodes.push(export_step(&attrs.remote, "svg", t.figma_name(), None));
             child_nodes.push(node!(
                 "🎨 Render PNG locally",
                 [("scale", scale.to_string())]
@@ -259,7 +522,7 @@ fn webp_resource_tree(res: &Resource, p: &WebpProfile) -> Node {
             [("quality", p.quality.to_string())]
         ));
         child_nodes.push(node!(
-            "💾 Write to file",
+            WRITE_TO_FILE,
             [("output", format!("{}.webp", t.output_name()))]
         ));
 
@@ -267,7 +530,7 @@ fn webp_resource_tree(res: &Resource, p: &WebpProfile) -> Node {
             let variant_node = Node {
                 name: format!("Variant '{}'", variant_id),
                 children: child_nodes,
-                params: Vec::new(),
+                ..Default::default()
             };
             root_node.children.push(variant_node);
         } else {
@@ -281,7 +544,7 @@ fn compose_resource_tree(res: &Resource, p: &ComposeProfile) -> Node {
     let attrs = &res.attrs;
     let targets = targets_from_resource(res);
 
-    let output_dir = get_output_dir_for_compose_profile(p, &attrs.package_dir);
+    let output_dir = p.output_dir(&attrs.package_dir);
     let package = match &p.package {
         Some(pkg) if pkg.is_empty() => "Explicitly empty".to_owned(),
         Some(pkg) => pkg.to_owned(),
@@ -294,29 +557,30 @@ fn compose_resource_tree(res: &Resource, p: &ComposeProfile) -> Node {
     let mut root_node = Node {
         name: attrs.label.to_string(),
         children: Vec::new(),
-        params: Vec::new(),
+        ..Default::default()
     };
+    // combined variants are written into the file named after the resource
+    let combined_name = res.output_base_name();
     for t in targets {
+        let output_name = if p.combine_variants {
+            combined_name.as_str()
+        } else {
+            t.output_name()
+        };
         let mut child_nodes = vec![
-            node!(
-                format!("📤 Export SVG from remote {}", attrs.remote),
-                [("node", t.figma_name().to_string())]
-            ),
+            export_step(&attrs.remote, "svg", t.figma_name(), None),
             node!(
                 "✨ Transform SVG to Compose",
                 [("package", package.to_string())]
             ),
-            node!(
-                "💾 Write to file",
-                [("output", format!("{}.kt", t.output_name()))]
-            ),
+            node!(WRITE_TO_FILE, [("output", format!("{}.kt", output_name))]),
         ];
 
         if let Some(variant_id) = t.id {
             let variant_node = Node {
                 name: format!("Variant '{}'", variant_id),
                 children: child_nodes,
-                params: Vec::new(),
+                ..Default::default()
             };
             root_node.children.push(variant_node);
         } else {
@@ -330,7 +594,7 @@ fn android_webp_resource_tree(res: &Resource, p: &AndroidWebpProfile) -> Node {
     let attrs = &res.attrs;
     let targets = targets_from_resource(res);
 
-    let res_name = attrs.label.name.to_string();
+    let res_name = res.output_base_name();
     Node {
         name: attrs.label.to_string(),
         children: targets
@@ -340,18 +604,14 @@ fn android_webp_resource_tree(res: &Resource, p: &AndroidWebpProfile) -> Node {
                 let scale = target.scale.expect("always present");
                 let mut child_nodes = Vec::with_capacity(4);
                 if p.legacy_loader {
-                    child_nodes.push(node!(
-                        format!("📤 Export PNG from remote {}", attrs.remote),
-                        [
-                            ("node", target.figma_name().to_string()),
-                            ("scale", scale.to_string())
-                        ]
+                    child_nodes.push(export_step(
+                        &attrs.remote,
+                        "png",
+                        target.figma_name(),
+                        Some(scale),
                     ));
                 } else {
-                    child_nodes.push(node!(
-                        format!("📤 Export SVG from remote {}", attrs.remote),
-                        [("node", target.figma_name().to_string())]
-                    ));
+                    child_nodes.push(export_step(&attrs.remote, "svg", target.figma_name(), None));
                     child_nodes.push(node!(
                         "🎨 Render PNG locally",
                         [("scale", scale.to_string())]
@@ -362,13 +622,13 @@ fn android_webp_resource_tree(res: &Resource, p: &AndroidWebpProfile) -> Node {
                     [("quality", p.quality.to_string())]
                 ));
                 child_nodes.push(node!(
-                    "💾 Write to file",
+                    WRITE_TO_FILE,
                     [("output", format!("drawable-{variant_name}/{res_name}.webp"))]
                 ));
                 Node {
                     name: format!("Variant '{variant_name}'"),
                     children: child_nodes,
-                    params: Default::default(),
+                    ..Default::default()
                 }
             })
             .collect(),
@@ -376,11 +636,11 @@ fn android_webp_resource_tree(res: &Resource, p: &AndroidWebpProfile) -> Node {
     }
 }
 
-fn android_drawable_resource_tree(res: &Resource, _: &AndroidDrawableProfile) -> Node {
+fn android_drawable_resource_tree(res: &Resource, p: &AndroidDrawableProfile) -> Node {
     let attrs = &res.attrs;
     let targets = targets_from_resource(res);
 
-    let res_name = attrs.label.name.to_string();
+    let res_name = res.output_base_name();
     Node {
         name: attrs.label.to_string(),
         children: targets
@@ -394,15 +654,23 @@ fn android_drawable_resource_tree(res: &Resource, _: &AndroidDrawableProfile) ->
                 };
                 let mut child_nodes = Vec::with_capacity(4);
 
-                child_nodes.push(node!(
-                    format!("📤 Export SVG from remote {}", attrs.remote),
-                    [("node", target.figma_name().to_string())]
-                ));
+                child_nodes.push(export_step(&attrs.remote, "svg", target.figma_name(), None));
                 child_nodes.push(node!("✨ Transform SVG to Android Drawable", []));
                 child_nodes.push(node!(
-                    "💾 Write to file",
-                    [("output", format!("{drawable_dir_name}/{res_name}.webp"))]
+                    WRITE_TO_FILE,
+                    [("output", format!("{drawable_dir_name}/{res_name}.xml"))]
                 ));
+                // themed icons are tinted by the system, so the night variant needs no monochrome layer
+                if p.monochrome && variant_name.is_empty() {
+                    child_nodes.push(node!("✨ Transform SVG to monochrome Android Drawable", []));
+                    child_nodes.push(node!(
+                        WRITE_TO_FILE,
+                        [(
+                            "output",
+                            format!("{drawable_dir_name}/{res_name}_monochrome.xml")
+                        )]
+                    ));
+                }
 
                 let variant_name = if variant_name.is_empty() {
                     "light".to_string()
@@ -412,10 +680,431 @@ fn android_drawable_resource_tree(res: &Resource, _: &AndroidDrawableProfile) ->
                 Node {
                     name: format!("Variant '{variant_name}'"),
                     children: child_nodes,
-                    params: Default::default(),
+                    ..Default::default()
                 }
             })
             .collect(),
         ..Default::default()
     }
 }
+
+fn react_resource_tree(res: &Resource, p: &ReactProfile) -> Node {
+    let attrs = &res.attrs;
+    let targets = targets_from_resource(res);
+    let output_dir = p.output_dir.to_string_lossy().replace('\\', "/");
+
+    let mut root_node = Node {
+        name: attrs.label.to_string(),
+        children: Vec::new(),
+        ..Default::default()
+    };
+    for t in targets {
+        let component_name = ReactProfile::component_name(t.output_name());
+        let output = if output_dir.is_empty() {
+            format!("{component_name}.tsx")
+        } else {
+            format!("{output_dir}/{component_name}.tsx")
+        };
+        let mut child_nodes = vec![
+            export_step(&attrs.remote, "svg", t.figma_name(), None),
+            node!(
+                "✨ Transform SVG to React component",
+                [("current_color", p.current_color.to_string())]
+            ),
+            node!(WRITE_TO_FILE, [("output", output)]),
+        ];
+
+        if let Some(variant_id) = t.id {
+            let variant_node = Node {
+                name: format!("Variant '{}'", variant_id),
+                children: child_nodes,
+                ..Default::default()
+            };
+            root_node.children.push(variant_node);
+        } else {
+            root_node.children.append(&mut child_nodes);
+        }
+    }
+    root_node
+}
+
+fn flutter_resource_tree(res: &Resource, p: &FlutterProfile) -> Node {
+    let attrs = &res.attrs;
+    let res_name = res.output_base_name();
+    let assets_dir = p.assets_dir.to_string_lossy().replace('\\', "/");
+
+    let mut root_node = Node {
+        name: attrs.label.to_string(),
+        children: Vec::new(),
+        ..Default::default()
+    };
+    if !p.legacy_loader {
+        root_node
+            .children
+            .push(export_step(&attrs.remote, "svg", &attrs.node_name, None));
+    }
+    for scale in &p.scales {
+        let dir_name = FlutterProfile::density_dir_name(**scale);
+        let output = if **scale == 1.0 {
+            format!("{assets_dir}/{res_name}.png")
+        } else {
+            format!("{assets_dir}/{dir_name}/{res_name}.png")
+        };
+        let mut child_nodes = Vec::with_capacity(2);
+        if p.legacy_loader {
+            child_nodes.push(export_step(
+                &attrs.remote,
+                "png",
+                &attrs.node_name,
+                Some(**scale),
+            ));
+        } else {
+            child_nodes.push(node!(
+                "🎨 Render PNG locally",
+                [("scale", scale.to_string())]
+            ));
+        }
+        if p.optimize {
+            child_nodes.push(node!(
+                "✨ Optimize PNG",
+                [("level", (*p.optimization_level).to_string())]
+            ));
+        }
+        child_nodes.push(node!(WRITE_TO_FILE, [("output", output)]));
+        root_node.children.push(Node {
+            name: format!("Density '{dir_name}'"),
+            children: child_nodes,
+            ..Default::default()
+        });
+    }
+    if let Some(class_name) = &p.dart_class {
+        root_node.children.push(node!(
+            "📝 Add to Dart class",
+            [
+                ("class", class_name.to_string()),
+                ("output", p.dart_file.to_string_lossy().to_string())
+            ]
+        ));
+    }
+    root_node
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use phase_loading::{NodeIdList, RetryPolicy, Workspace};
+    use std::path::{Path, PathBuf};
+
+    static REMOTE: &str = "[remotes.design]\nfile_key = \"FILE\"\ncontainer_node_ids = [\"0:1\"]\n";
+
+    fn remote() -> RemoteSource {
+        RemoteSource {
+            id: "design".to_owned(),
+            file_key: "FILE".to_owned(),
+            branch: None,
+            container_node_ids: NodeIdList::Plain(vec!["0:1".to_owned()]),
+            access_token: String::new(),
+            shallow_depth: None,
+            export_batch_size: RemoteSource::DEFAULT_EXPORT_BATCH_SIZE,
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    #[test]
+    fn export_step__raster_and_vector_exports__EXPECT__exported_image_kept_beside_params() {
+        // When
+        let png = export_step(&remote(), "png", "Star", Some(2.0));
+        let svg = export_step(&remote(), "svg", "Star", None);
+
+        // Then
+        assert_eq!("📤 Export PNG from remote @design/FILE", png.name);
+        assert_eq!(
+            vec![("node", "Star".to_owned()), ("scale", "2".to_owned())],
+            png.params
+        );
+        let export = png.export.unwrap();
+        assert_eq!(
+            ("png", "Star", 2.0),
+            (export.format, export.node.as_str(), export.scale)
+        );
+
+        assert_eq!("📤 Export SVG from remote @design/FILE", svg.name);
+        assert_eq!(vec![("node", "Star".to_owned())], svg.params);
+        let export = svg.export.unwrap();
+        assert_eq!(
+            ("svg", "Star", 1.0),
+            (export.format, export.node.as_str(), export.scale)
+        );
+    }
+
+    /// Resource with a variant of two steps followed by one more step
+    fn tree() -> Node {
+        node!(
+            "//icons:star",
+            [("index", "cached".to_owned())],
+            node!(
+                "Variant 'dark'",
+                node!("📤 Export", [("node", "Star".to_owned())]),
+                node!(WRITE_TO_FILE, [("output", "star.svg".to_owned())])
+            ),
+            node!("📝 Add to Dart class", [])
+        )
+    }
+
+    fn to_json(node: &Node, depth: Option<usize>) -> serde_json::Value {
+        serde_json::to_value(LimitedNode { node, depth }).unwrap()
+    }
+
+    #[test]
+    fn LimitedNode__unlimited_depth__EXPECT__whole_tree_with_ordered_params() {
+        // Given
+        let node = node!(
+            "//icons:star",
+            [("node", "Star".to_owned()), ("index", "cached".to_owned())],
+            node!(WRITE_TO_FILE, [("output", "star.svg".to_owned())])
+        );
+
+        // When
+        let json = serde_json::to_string(&LimitedNode {
+            node: &node,
+            depth: None,
+        })
+        .unwrap();
+
+        // Then
+        assert_eq!(
+            r#"{"name":"//icons:star","params":{"node":"Star","index":"cached"},"children":[{"name":"💾 Write to file","params":{"output":"star.svg"},"children":[]}]}"#,
+            json
+        );
+    }
+
+    #[test]
+    fn LimitedNode__depth_0__EXPECT__resource_with_number_of_hidden_steps() {
+        // When
+        let json = to_json(&tree(), Some(0));
+
+        // Then
+        assert_eq!(
+            serde_json::json!({
+                "name": "//icons:star",
+                "params": { "index": "cached" },
+                "children": [],
+                "hidden_steps": 3,
+            }),
+            json
+        );
+    }
+
+    #[test]
+    fn LimitedNode__depth_1__EXPECT__steps_of_variants_hidden() {
+        // When
+        let json = to_json(&tree(), Some(1));
+
+        // Then
+        assert_eq!(
+            serde_json::json!({
+                "name": "//icons:star",
+                "params": { "index": "cached" },
+                "children": [
+                    {
+                        "name": "Variant 'dark'",
+                        "params": {},
+                        "children": [],
+                        "hidden_steps": 2,
+                    },
+                    {
+                        "name": "📝 Add to Dart class",
+                        "params": {},
+                        "children": [],
+                    },
+                ],
+            }),
+            json
+        );
+    }
+
+    #[test]
+    fn LimitedNode__depth_1__EXPECT__hidden_steps_in_printed_tree() {
+        // When
+        let full = LimitedNode {
+            node: &tree(),
+            depth: None,
+        }
+        .to_string();
+        let limited = LimitedNode {
+            node: &tree(),
+            depth: Some(1),
+        }
+        .to_string();
+
+        // Then
+        assert!(full.contains("star.svg"));
+        assert!(!full.contains("hidden"));
+        assert!(limited.contains("Variant 'dark'"));
+        assert!(limited.contains("… 2 steps hidden"));
+        assert!(!limited.contains("star.svg"));
+    }
+
+    fn write(path: PathBuf, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn load(dir: &Path) -> Workspace {
+        let pattern = LabelPattern::try_from(vec!["//...".to_string()]).unwrap();
+        phase_loading::load_workspace_in(dir, pattern, true).unwrap()
+    }
+
+    /// Outputs of the write steps of the tree, relative to the output directories
+    fn written_files(node: &Node) -> Vec<String> {
+        if node.children.is_empty() {
+            return match node.name == WRITE_TO_FILE {
+                true => node
+                    .params
+                    .iter()
+                    .filter(|(key, _)| *key == "output")
+                    .map(|(_, value)| value.to_owned())
+                    .collect(),
+                false => Vec::new(),
+            };
+        }
+        node.children.iter().flat_map(written_files).collect()
+    }
+
+    /// Checks that the tree shows exactly the files the import writes
+    fn assert_same_outputs(ws: &Workspace) {
+        for res in ws.packages.iter().flat_map(|pkg| &pkg.resources) {
+            let written = written_files(&resource_tree(res));
+            let expected = res.expected_outputs();
+            assert_eq!(
+                expected.len(),
+                written.len(),
+                "{}: {written:?}",
+                res.attrs.label
+            );
+            for (expected, written) in expected.iter().zip(&written) {
+                assert!(
+                    expected.ends_with(written),
+                    "{}: `{written}` is not `{}`",
+                    res.attrs.label,
+                    expected.display()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn resource_tree__naming_rules__EXPECT__files_named_as_imported() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let profiles = [
+            "[profiles.android-webp]\nnaming = { prefix = \"ic_\" }\n",
+            "[profiles.android-drawable]\nnaming = { prefix = \"ic_\" }\n",
+            "[profiles.flutter]\nnaming = { prefix = \"ic_\" }\n",
+        ];
+        write(
+            dir.path().join(".figtree.toml"),
+            &format!("{REMOTE}{}", profiles.concat()),
+        );
+        write(
+            dir.path().join("icons").join(".fig.toml"),
+            "[android-webp]\nstar = \"Star\"\n[android-drawable]\nmoon = \"Moon\"\n[flutter]\nsun = \"Sun\"\n",
+        );
+        let ws = load(dir.path());
+
+        // Then
+        assert_same_outputs(&ws);
+    }
+
+    #[test]
+    fn resource_tree__optimized_png__EXPECT__optimization_before_write() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let profiles = "[profiles.png]\noptimize = true\n";
+        write(
+            dir.path().join(".figtree.toml"),
+            &format!("{REMOTE}{profiles}"),
+        );
+        write(
+            dir.path().join("icons").join(".fig.toml"),
+            "[png]\nic_star = \"Star\"\n",
+        );
+        let ws = load(dir.path());
+        let res = &ws.packages[0].resources[0];
+
+        // When
+        let tree = resource_tree(res);
+
+        // Then
+        let steps = tree
+            .children
+            .iter()
+            .map(|it| it.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "📤 Export SVG from remote @design/FILE",
+                "🎨 Render PNG locally",
+                "✨ Optimize PNG",
+                WRITE_TO_FILE,
+            ],
+            steps
+        );
+        assert_eq!(vec![("level", "2".to_owned())], tree.children[2].params);
+    }
+
+    #[test]
+    fn resource_tree__pdf_with_previews__EXPECT__preview_written_after_document() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let profiles = "[profiles.pdf]\npreview_scales = [1.0, 2.0]\n";
+        write(
+            dir.path().join(".figtree.toml"),
+            &format!("{REMOTE}{profiles}"),
+        );
+        write(
+            dir.path().join("docs").join(".fig.toml"),
+            "[pdf]\nmanual = \"Manual\"\n",
+        );
+        let ws = load(dir.path());
+
+        // When
+        let written = written_files(&resource_tree(&ws.packages[0].resources[0]));
+
+        // Then
+        assert_eq!(vec!["manual.pdf", "manual.png", "manual@2x.png"], written);
+        assert_same_outputs(&ws);
+    }
+
+    #[test]
+    fn resource_tree__monochrome_drawable__EXPECT__monochrome_written_for_light_theme_only() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let profiles =
+            "[profiles.android-drawable]\nmonochrome = true\nnight = \"{base} / dark\"\n";
+        write(
+            dir.path().join(".figtree.toml"),
+            &format!("{REMOTE}{profiles}"),
+        );
+        write(
+            dir.path().join("icons").join(".fig.toml"),
+            "[android-drawable]\nic_star = \"Star\"\n",
+        );
+        let ws = load(dir.path());
+
+        // When
+        let written = written_files(&resource_tree(&ws.packages[0].resources[0]));
+
+        // Then
+        assert_eq!(
+            vec![
+                "drawable/ic_star.xml",
+                "drawable/ic_star_monochrome.xml",
+                "drawable-night/ic_star.xml",
+            ],
+            written
+        );
+        assert_same_outputs(&ws);
+    }
+}
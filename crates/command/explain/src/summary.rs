@@ -0,0 +1,98 @@
+use crate::{Node, WRITE_TO_FILE};
+use crossterm::style::Stylize;
+use std::collections::BTreeSet;
+
+/// Aggregated statistics of the explained resource trees of a single package
+#[derive(Default)]
+pub(crate) struct PackageSummary {
+    pub package: String,
+    pub targets: usize,
+    pub outputs: usize,
+    pub actions: usize,
+    pub remotes: BTreeSet<String>,
+}
+
+impl PackageSummary {
+    pub(crate) fn new(package: String) -> Self {
+        Self {
+            package,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn add_resource(&mut self, node: &Node, targets: usize, remote: &str) {
+        self.targets += targets;
+        self.outputs += node.count_steps(|step| step.name == WRITE_TO_FILE);
+        self.actions += node.count_steps(|_| true);
+        self.remotes.insert(remote.to_owned());
+    }
+
+    fn add_summary(&mut self, other: &PackageSummary) {
+        self.targets += other.targets;
+        self.outputs += other.outputs;
+        self.actions += other.actions;
+        self.remotes.extend(other.remotes.iter().cloned());
+    }
+}
+
+impl Node {
+    /// Counts pipeline steps (leaf nodes) matching the predicate
+    pub(crate) fn count_steps(&self, predicate: impl Fn(&Node) -> bool + Copy) -> usize {
+        if self.children.is_empty() {
+            return predicate(self) as usize;
+        }
+        self.children
+            .iter()
+            .map(|child| child.count_steps(predicate))
+            .sum()
+    }
+}
+
+pub(crate) fn print_summary(packages: &[PackageSummary]) {
+    let mut total = PackageSummary::new(format!("Total ({} packages)", packages.len()));
+    for pkg in packages {
+        total.add_summary(pkg);
+    }
+
+    let header = ["Package", "Targets", "Outputs", "Actions", "Remotes"];
+    let rows = packages
+        .iter()
+        .chain(std::iter::once(&total))
+        .map(|pkg| {
+            [
+                pkg.package.clone(),
+                pkg.targets.to_string(),
+                pkg.outputs.to_string(),
+                pkg.actions.to_string(),
+                pkg.remotes.iter().cloned().collect::<Vec<_>>().join(", "),
+            ]
+        })
+        .collect::<Vec<_>>();
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: &[String]| {
+        cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| match i {
+                // text columns are aligned to the left, numbers to the right
+                0 | 4 => format!("{cell:<width$}", width = widths[i]),
+                _ => format!("{cell:>width$}", width = widths[i]),
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_owned()
+    };
+    println!("{}", format_row(&header.map(str::to_owned)).bold());
+    let (total_row, package_rows) = rows.split_last().expect("total row is always present");
+    for row in package_rows {
+        println!("{}", format_row(row));
+    }
+    println!("{}", format_row(total_row).bold());
+}
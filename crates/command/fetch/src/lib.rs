@@ -1,13 +1,21 @@
-use lib_label::LabelPattern;
+use lib_figma_fluent::{FigmaApi, Mirror};
+use lib_label::{LabelPattern, split_tag_patterns};
+use phase_loading::ResourceFilter;
+use std::path::PathBuf;
 
 mod error;
 pub use error::*;
 use lib_metrics::Metrics;
-use phase_evaluation::EvalArgs;
+use phase_evaluation::{CancellationToken, EvalArgs};
 
 pub struct FeatureFetchOptions {
     pub pattern: Vec<String>,
+    pub filter: ResourceFilter,
     pub concurrency: usize,
+    pub network_concurrency: usize,
+    pub cancellation: CancellationToken,
+    /// Serve Figma API responses from the mirror archive instead of network
+    pub mirror: Option<PathBuf>,
 }
 
 pub fn fetch(opts: FeatureFetchOptions) -> Result<()> {
@@ -19,9 +27,22 @@ pub fn fetch(opts: FeatureFetchOptions) -> Result<()> {
     // endregion: metrics
 
     let loading_duration = loading_duration.record();
-    let pattern = LabelPattern::try_from(opts.pattern)?;
-    let ws = phase_loading::load_workspace(pattern, false)?;
+    let (pattern, tags) = split_tag_patterns(opts.pattern)?;
+    let pattern = LabelPattern::try_from(pattern)?;
+    let figma_api = match &opts.mirror {
+        Some(path) => {
+            FigmaApi::from_mirror(Mirror::load(path).map_err(phase_evaluation::Error::from)?)
+        }
+        None => FigmaApi::default(),
+    };
+    // access tokens are not needed when working offline
+    let mut ws = phase_loading::load_workspace(pattern, opts.mirror.is_some())?;
+    ws.retain_resources(&ResourceFilter {
+        tags,
+        ..opts.filter
+    })?;
     let cache_dir = ws.context.cache_dir.clone();
+    let otlp_url = lib_metrics::otlp_metrics_url(ws.metrics.otlp_endpoint.as_deref());
     drop(loading_duration);
     {
         phase_evaluation::evaluate(
@@ -29,7 +50,10 @@ pub fn fetch(opts: FeatureFetchOptions) -> Result<()> {
             EvalArgs {
                 fetch: true,
                 concurrency: opts.concurrency,
+                network_concurrency: opts.network_concurrency,
+                cancellation: opts.cancellation,
                 metrics: metrics.clone(),
+                figma_api,
                 ..Default::default()
             },
         )?;
@@ -40,5 +64,8 @@ pub fn fetch(opts: FeatureFetchOptions) -> Result<()> {
         Some(&[("command", "fetch")]),
         &cache_dir.join("metrics.prom"),
     );
+    if let Some(url) = otlp_url {
+        metrics.export_to_otlp(Some(&[("command", "fetch")]), &url);
+    }
     Ok(())
 }
@@ -0,0 +1,270 @@
+use lib_label::LabelPattern;
+use lib_metrics::Metrics;
+use log::{info, warn};
+use ordermap::OrderMap;
+use phase_evaluation::{
+    CancellationToken, EvalArgs, actions::thumbnail_file_name, targets_from_resource,
+};
+use phase_loading::Workspace;
+use std::{fmt::Write as _, path::Path};
+
+mod error;
+pub use error::*;
+
+pub struct FeatureGalleryOptions {
+    pub pattern: Vec<String>,
+    pub concurrency: usize,
+    pub network_concurrency: usize,
+    pub cancellation: CancellationToken,
+}
+
+/// Card of a single target on the gallery page
+struct GalleryEntry {
+    package: String,
+    profile: &'static str,
+    name: String,
+    variant: Option<String>,
+    node_name: String,
+    remote: String,
+    thumbnail: String,
+}
+
+pub fn gallery(opts: FeatureGalleryOptions) -> Result<()> {
+    let pattern = LabelPattern::try_from(opts.pattern)?;
+    let ws = phase_loading::load_workspace(pattern, false)?;
+    let gallery_dir = ws.context.out_dir.join("gallery");
+    let thumbnails_dir = gallery_dir.join("thumbnails");
+    let entries = gallery_entries(&ws);
+
+    let result = phase_evaluation::evaluate(
+        ws,
+        EvalArgs {
+            // missing thumbnails shouldn't prevent the gallery from being written
+            keep_going: true,
+            concurrency: opts.concurrency,
+            network_concurrency: opts.network_concurrency,
+            cancellation: opts.cancellation,
+            metrics: Metrics::default(),
+            gallery_dir: Some(thumbnails_dir.clone()),
+            ..Default::default()
+        },
+    );
+    if !matches!(result, Err(phase_evaluation::Error::Cancelled)) {
+        let index_file = gallery_dir.join("index.html");
+        std::fs::create_dir_all(&gallery_dir)?;
+        std::fs::write(&index_file, to_html_string(&entries, &thumbnails_dir))?;
+        info!(target: "Gallery", "{} targets written to `{}`", entries.len(), index_file.display());
+        if result.is_err() {
+            warn!(target: "Gallery", "some thumbnails are missing, see the errors below");
+        }
+    }
+    result?;
+    Ok(())
+}
+
+fn gallery_entries(ws: &Workspace) -> Vec<GalleryEntry> {
+    let mut entries = Vec::new();
+    for pkg in &ws.packages {
+        for res in &pkg.resources {
+            for target in targets_from_resource(res) {
+                entries.push(GalleryEntry {
+                    package: pkg.label.to_string(),
+                    profile: res.profile.kind().as_str(),
+                    name: res.attrs.label.name.to_string(),
+                    variant: target.id.clone().filter(|it| !it.is_empty()),
+                    node_name: target.figma_name().to_owned(),
+                    remote: res.attrs.remote.id.clone(),
+                    thumbnail: thumbnail_file_name(&target),
+                });
+            }
+        }
+    }
+    entries
+}
+
+fn to_html_string(entries: &[GalleryEntry], thumbnails_dir: &Path) -> String {
+    // package => profile => entries
+    let mut groups = OrderMap::<&str, OrderMap<&str, Vec<&GalleryEntry>>>::new();
+    for entry in entries {
+        groups
+            .entry(entry.package.as_str())
+            .or_default()
+            .entry(entry.profile)
+            .or_default()
+            .push(entry);
+    }
+
+    let mut buf = String::with_capacity(256 * entries.len() + 1024);
+    buf.push_str(HTML_HEADER);
+    let _ = writeln!(buf, "<p class=\"summary\">{} targets</p>", entries.len());
+    for (package, profiles) in groups {
+        let _ = writeln!(buf, "<section>\n<h2>{}</h2>", escape_html(package));
+        for (profile, entries) in profiles {
+            let _ = writeln!(buf, "<h3>{profile}</h3>\n<div class=\"grid\">");
+            for entry in entries {
+                let file_name = format!("{}.png", entry.thumbnail);
+                let preview = if thumbnails_dir.join(&file_name).is_file() {
+                    format!("<img src=\"thumbnails/{file_name}\" loading=\"lazy\">")
+                } else {
+                    "<div class=\"missing\">no preview</div>".to_string()
+                };
+                let variant = entry
+                    .variant
+                    .as_ref()
+                    .map(|it| format!(" <span class=\"variant\">{}</span>", escape_html(it)))
+                    .unwrap_or_default();
+                let _ = writeln!(
+                    buf,
+                    "<figure title=\"{node} ({remote})\">{preview}<figcaption>{name}{variant}</figcaption></figure>",
+                    node = escape_html(&entry.node_name),
+                    remote = escape_html(&entry.remote),
+                    name = escape_html(&entry.name),
+                );
+            }
+            buf.push_str("</div>\n");
+        }
+        buf.push_str("</section>\n");
+    }
+    buf.push_str("</body>\n</html>\n");
+    buf
+}
+
+fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+const HTML_HEADER: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>FigX Gallery</title>
+<style>
+body { font-family: sans-serif; margin: 24px; color: #222; }
+h2 { font-family: monospace; border-bottom: 1px solid #ddd; padding-bottom: 4px; }
+h3 { color: #888; font-weight: normal; }
+.grid { display: flex; flex-wrap: wrap; gap: 12px; }
+figure { margin: 0; width: 128px; text-align: center; }
+figure img, .missing { width: 96px; height: 96px; object-fit: contain; padding: 8px; border-radius: 8px;
+  background: repeating-conic-gradient(#eee 0% 25%, #fff 0% 50%) 50% / 16px 16px; }
+.missing { display: inline-flex; align-items: center; justify-content: center; color: #aaa; font-size: 12px; }
+figcaption { font-size: 12px; word-break: break-all; }
+.variant, .summary { color: #888; }
+</style>
+</head>
+<body>
+<h1>FigX Gallery</h1>
+"#;
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    fn entry(package: &str, profile: &'static str, name: &str) -> GalleryEntry {
+        GalleryEntry {
+            package: package.to_string(),
+            profile,
+            name: name.to_string(),
+            variant: None,
+            node_name: name.to_string(),
+            remote: "design".to_string(),
+            thumbnail: name.to_string(),
+        }
+    }
+
+    fn position(html: &str, pattern: &str) -> usize {
+        html.find(pattern)
+            .unwrap_or_else(|| panic!("`{pattern}` is not in the page"))
+    }
+
+    #[test]
+    fn to_html_string__several_packages_and_profiles__EXPECT__grouped_in_first_seen_order() {
+        // Given
+        let entries = [
+            entry("//icons", "png", "ic_star"),
+            entry("//images", "svg", "img_hero"),
+            entry("//icons", "svg", "ic_moon"),
+            entry("//icons", "png", "ic_sun"),
+        ];
+
+        // When
+        let html = to_html_string(&entries, Path::new("/nonexistent"));
+
+        // Then
+        assert!(html.contains("<p class=\"summary\">4 targets</p>"));
+        assert_eq!(1, html.matches("<h2>//icons</h2>").count());
+        assert_eq!(1, html.matches("<h2>//images</h2>").count());
+        assert_eq!(3, html.matches("<h3>").count());
+        let order = [
+            "<h2>//icons</h2>",
+            "<h3>png</h3>",
+            ">ic_star<",
+            ">ic_sun<",
+            "<h3>svg</h3>",
+            ">ic_moon<",
+            "<h2>//images</h2>",
+            ">img_hero<",
+        ]
+        .map(|it| position(&html, it));
+        assert!(order.is_sorted(), "unexpected order of the page: {html}");
+    }
+
+    #[test]
+    fn to_html_string__special_characters__EXPECT__escaped() {
+        // Given
+        let entries = [GalleryEntry {
+            variant: Some("<dark>".to_string()),
+            node_name: "Icon \"A\" & 'B'".to_string(),
+            remote: "<remote>".to_string(),
+            ..entry("//icons", "png", "ic_<b>")
+        }];
+
+        // When
+        let html = to_html_string(&entries, Path::new("/nonexistent"));
+
+        // Then
+        assert!(
+            html.contains(
+                "<figure title=\"Icon &quot;A&quot; &amp; &#39;B&#39; (&lt;remote&gt;)\">"
+            )
+        );
+        assert!(html.contains(
+            "<figcaption>ic_&lt;b&gt; <span class=\"variant\">&lt;dark&gt;</span></figcaption>"
+        ));
+        assert!(!html.contains("<b>"));
+    }
+
+    #[test]
+    fn to_html_string__existing_and_missing_thumbnails__EXPECT__image_or_placeholder() {
+        // Given
+        let thumbnails_dir = tempfile::tempdir().unwrap();
+        std::fs::write(thumbnails_dir.path().join("ic_star.png"), b"png").unwrap();
+        let entries = [
+            entry("//icons", "png", "ic_star"),
+            entry("//icons", "png", "ic_moon"),
+        ];
+
+        // When
+        let html = to_html_string(&entries, thumbnails_dir.path());
+
+        // Then
+        assert!(html.contains("<img src=\"thumbnails/ic_star.png\" loading=\"lazy\">"));
+        assert!(!html.contains("thumbnails/ic_moon.png"));
+        assert_eq!(
+            1,
+            html.matches("<div class=\"missing\">no preview</div>")
+                .count()
+        );
+    }
+}
@@ -1,39 +1,126 @@
-use lib_label::LabelPattern;
+use lib_figma_fluent::{FigmaApi, Mirror};
+use lib_label::{LabelPattern, split_tag_patterns};
+use phase_loading::ResourceFilter;
+use std::path::{Path, PathBuf};
 
 mod error;
 pub use error::*;
 use lib_metrics::Metrics;
-use phase_evaluation::EvalArgs;
+use phase_evaluation::{AuditLog, CancellationToken, EvalArgs, ImportManifest, ProfileReport};
 
 pub struct FeatureImportOptions {
     pub pattern: Vec<String>,
+    pub filter: ResourceFilter,
     pub refetch: bool,
+    pub keep_going: bool,
     pub concurrency: usize,
+    /// Number of downloads in flight, `0` means the workspace or default one
+    pub network_concurrency: usize,
+    pub cancellation: CancellationToken,
+    /// Serve Figma API responses from the mirror archive instead of network
+    pub mirror: Option<PathBuf>,
+    pub report: Option<PathBuf>,
+    pub report_junit: Option<PathBuf>,
+    pub profile: bool,
+    pub trace: bool,
+    pub keep_intermediates: Option<PathBuf>,
+    pub write_queue: bool,
+    pub resume_last: bool,
+    /// Verify the workspace files instead of writing them
+    pub check: bool,
+    /// Decode imported raster images and check their dimensions
+    pub validate_images: bool,
+    /// Write the outputs under this directory instead of the packages,
+    /// relative to the current dir, overrides `[output] base` of the workspace
+    pub output_base: Option<PathBuf>,
 }
 
+/// Number of rows in the table of the slowest actions
+const PROFILE_TOP_N: usize = 10;
+
 pub fn import(opts: FeatureImportOptions) -> Result<()> {
+    let working_dir = std::env::current_dir()
+        .map_err(|_| phase_loading::Error::InitInaccessibleCurrentWorkDir)?;
+    import_in(&working_dir, FigmaApi::default(), opts)
+}
+
+/// Same as [`import`], but as if figx was invoked in `working_dir`.
+/// `figma_api` is used unless the mirror is requested.
+pub fn import_in(
+    working_dir: &Path,
+    figma_api: FigmaApi,
+    opts: FeatureImportOptions,
+) -> Result<()> {
     // region: metrics
     let metrics = Metrics::default();
     let full_duration = metrics.duration("figx_full_duration");
     let loading_duration = metrics.duration("figx_loading_duration");
     let full_duration = full_duration.record();
+    if opts.profile || opts.trace {
+        metrics.spans().enable();
+    }
     // endregion: metrics
 
     let loading_duration = loading_duration.record();
-    let pattern = LabelPattern::try_from(opts.pattern)?;
-    let ws = phase_loading::load_workspace(pattern, false)?;
+    let (pattern, tags) = split_tag_patterns(opts.pattern)?;
+    let pattern = LabelPattern::try_from(pattern)?;
+    let figma_api = match &opts.mirror {
+        Some(path) => {
+            FigmaApi::from_mirror(Mirror::load(path).map_err(phase_evaluation::Error::from)?)
+        }
+        None => figma_api,
+    };
+    // access tokens are not needed when working offline
+    let mut ws = phase_loading::load_workspace_in(working_dir, pattern, opts.mirror.is_some())?;
+    ws.retain_resources(&ResourceFilter {
+        tags,
+        ..opts.filter
+    })?;
+    if let Some(base) = &opts.output_base {
+        let base = ws.context.current_dir.join(base);
+        ws.relocate_outputs(&base);
+    }
     let cache_dir = ws.context.cache_dir.clone();
+    let otlp_url = lib_metrics::otlp_metrics_url(ws.metrics.otlp_endpoint.as_deref());
+    let trace_path = ws.context.out_dir.join("trace.json");
+    let journal_path = ws.context.out_dir.join("import-journal.jsonl");
+    let manifest_path = ws.context.out_dir.join(ImportManifest::FILE_NAME);
+    let audit_path = ws.context.out_dir.join(AuditLog::FILE_NAME);
+    let report_path = opts
+        .report
+        .unwrap_or_else(|| ws.context.out_dir.join("build-report.json"));
     drop(loading_duration);
     {
-        phase_evaluation::evaluate(
+        let result = phase_evaluation::evaluate(
             ws,
             EvalArgs {
                 refetch: opts.refetch,
+                keep_going: opts.keep_going,
                 concurrency: opts.concurrency,
+                network_concurrency: opts.network_concurrency,
+                cancellation: opts.cancellation,
                 metrics: metrics.clone(),
+                figma_api,
+                report_path: Some(report_path),
+                junit_report_path: opts.report_junit,
+                intermediates_dir: opts.keep_intermediates,
+                write_queue: opts.write_queue,
+                journal_path: Some(journal_path),
+                resume: opts.resume_last,
+                manifest_path: Some(manifest_path),
+                check: opts.check,
+                audit_path: Some(audit_path),
+                validate_images: opts.validate_images,
                 ..Default::default()
             },
-        )?;
+        );
+        if opts.profile {
+            ProfileReport::from_spans(&metrics.spans().records(), PROFILE_TOP_N).print();
+        }
+        if opts.trace {
+            metrics.export_as_chrome_trace(&trace_path);
+        }
+        result?;
     }
 
     drop(full_duration);
@@ -41,5 +128,138 @@ pub fn import(opts: FeatureImportOptions) -> Result<()> {
         Some(&[("command", "import")]),
         &cache_dir.join("metrics.prom"),
     );
+    if let Some(url) = otlp_url {
+        metrics.export_to_otlp(Some(&[("command", "import")]), &url);
+    }
     Ok(())
 }
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use figma_fake_server::{FakeFigmaServer, FakeNode};
+
+    const STAR_SVG: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" width="24" height="24"/>"#;
+
+    fn star_server() -> FakeFigmaServer {
+        FakeFigmaServer::start().file(
+            "FILE",
+            [FakeNode::component("1:2", "Star").with_image("svg", STAR_SVG)],
+        )
+    }
+
+    fn star_workspace() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".figtree.toml"),
+            "[remotes.design]\nfile_key = \"FILE\"\ncontainer_node_ids = [\"0:1\"]\naccess_token = \"token\"\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join(".fig.toml"), "[svg]\nic_star = \"Star\"\n").unwrap();
+        dir
+    }
+
+    fn options(check: bool) -> FeatureImportOptions {
+        FeatureImportOptions {
+            pattern: vec!["//...".to_string()],
+            filter: Default::default(),
+            refetch: false,
+            keep_going: false,
+            concurrency: 2,
+            network_concurrency: 2,
+            cancellation: CancellationToken::default(),
+            mirror: None,
+            report: None,
+            report_junit: None,
+            profile: false,
+            trace: false,
+            keep_intermediates: None,
+            write_queue: false,
+            resume_last: false,
+            check,
+            validate_images: false,
+            output_base: None,
+        }
+    }
+
+    #[test]
+    fn import_in__workspace_with_svg_resource__EXPECT__file_and_report_written() {
+        // Given
+        let server = star_server();
+        let dir = star_workspace();
+
+        // When
+        let api = FigmaApi::default().with_base_url(server.url());
+        import_in(dir.path(), api, options(false)).unwrap();
+
+        // Then
+        let svg = std::fs::read_to_string(dir.path().join("ic_star.svg")).unwrap();
+        assert_eq!(STAR_SVG, svg);
+        assert!(
+            dir.path()
+                .join(".figx-out")
+                .join("build-report.json")
+                .exists()
+        );
+        assert!(
+            !dir.path()
+                .join(".figx-out")
+                .join("import-journal.jsonl")
+                .exists(),
+            "journal is removed after the successful import"
+        );
+    }
+
+    #[test]
+    fn import_in__check_mode__EXPECT__stale_files_reported_and_not_written() {
+        // Given
+        let server = star_server();
+        let dir = star_workspace();
+        let api = FigmaApi::default().with_base_url(server.url());
+
+        // When
+        let missing = import_in(dir.path(), api.clone(), options(true));
+        import_in(dir.path(), api.clone(), options(false)).unwrap();
+        let up_to_date = import_in(dir.path(), api.clone(), options(true));
+        std::fs::write(dir.path().join("ic_star.svg"), "<svg/>").unwrap();
+        let changed = import_in(dir.path(), api, options(true));
+
+        // Then
+        let output = dir.path().join("ic_star.svg");
+        assert!(matches!(
+            missing,
+            Err(Error::Evaluation(phase_evaluation::Error::OutOfDate(paths))) if paths.len() == 1 && paths[0].ends_with("ic_star.svg")
+        ));
+        assert!(up_to_date.is_ok());
+        assert!(matches!(
+            changed,
+            Err(Error::Evaluation(phase_evaluation::Error::OutOfDate(paths))) if paths.len() == 1 && paths[0].ends_with("ic_star.svg")
+        ));
+        assert_eq!("<svg/>", std::fs::read_to_string(&output).unwrap());
+    }
+
+    #[test]
+    fn import_in__output_base__EXPECT__file_written_under_base_only() {
+        // Given
+        let server = star_server();
+        let dir = star_workspace();
+        let api = FigmaApi::default().with_base_url(server.url());
+
+        // When
+        import_in(
+            dir.path(),
+            api,
+            FeatureImportOptions {
+                output_base: Some(PathBuf::from("build/generated/figx")),
+                ..options(false)
+            },
+        )
+        .unwrap();
+
+        // Then
+        let base = dir.path().join("build").join("generated").join("figx");
+        assert!(base.join("ic_star.svg").is_file());
+        assert!(!dir.path().join("ic_star.svg").exists());
+    }
+}
@@ -0,0 +1,43 @@
+use std::{
+    fmt::{Debug, Display},
+    path::PathBuf,
+};
+
+pub type Result<T> = ::std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    IO(std::io::Error),
+    Yaml(serde_yaml::Error),
+    /// The config is not of any supported tool
+    UnsupportedConfig(PathBuf),
+    /// Generated files are never written over the existing ones
+    AlreadyExists(PathBuf),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&self, f)
+    }
+}
+impl std::error::Error for Error {
+    fn cause(&self) -> Option<&dyn std::error::Error> {
+        match self {
+            Self::IO(err) => Some(err),
+            Self::Yaml(err) => Some(err),
+            Self::UnsupportedConfig(_) | Self::AlreadyExists(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Self::IO(value)
+    }
+}
+
+impl From<serde_yaml::Error> for Error {
+    fn from(value: serde_yaml::Error) -> Self {
+        Self::Yaml(value)
+    }
+}
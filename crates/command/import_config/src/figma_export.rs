@@ -0,0 +1,752 @@
+//! Configs of [figma-export](https://github.com/RedMadRobot/figma-export).
+//!
+//! figma-export imports every component of a frame, while figx imports the resources
+//! listed in `.fig.toml` only, so the profiles and remotes are converted and
+//! the list of resources is left to the user.
+
+use crate::{ConvertedConfig, UnmappedOption};
+use serde::Deserialize;
+use serde_yaml::Value;
+use std::{collections::BTreeMap, fmt::Write as _};
+
+/// Options which have no typed representation below
+type Other = BTreeMap<String, Value>;
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct FigmaExportConfig {
+    pub figma: Option<FigmaSection>,
+    common: CommonSection,
+    android: Option<AndroidSection>,
+    ios: Option<IosSection>,
+    #[serde(flatten)]
+    other: Other,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct FigmaSection {
+    light_file_id: Option<String>,
+    dark_file_id: Option<String>,
+    #[serde(flatten)]
+    other: Other,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct CommonSection {
+    icons: CommonAssets,
+    images: CommonAssets,
+    #[serde(flatten)]
+    other: Other,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct CommonAssets {
+    figma_frame_name: Option<String>,
+    dark_mode_suffix: Option<String>,
+    #[serde(flatten)]
+    other: Other,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct AndroidSection {
+    main_res: Option<String>,
+    main_src: Option<String>,
+    icons: Option<AndroidIcons>,
+    images: Option<AndroidImages>,
+    #[serde(flatten)]
+    other: Other,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct AndroidIcons {
+    output: Option<String>,
+    compose_package_name: Option<String>,
+    #[serde(flatten)]
+    other: Other,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct AndroidImages {
+    output: Option<String>,
+    format: Option<String>,
+    scales: Option<Vec<f32>>,
+    webp_options: Option<WebpOptions>,
+    #[serde(flatten)]
+    other: Other,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct WebpOptions {
+    encoding: Option<String>,
+    quality: Option<u8>,
+    #[serde(flatten)]
+    other: Other,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct IosSection {
+    xcassets_path: Option<String>,
+    icons: Option<IosIcons>,
+    images: Option<IosImages>,
+    #[serde(flatten)]
+    other: Other,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct IosIcons {
+    format: Option<String>,
+    assets_folder: Option<String>,
+    #[serde(flatten)]
+    other: Other,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct IosImages {
+    assets_folder: Option<String>,
+    scales: Option<Vec<f32>>,
+    #[serde(flatten)]
+    other: Other,
+}
+
+#[derive(Default)]
+struct Converter {
+    remotes: String,
+    profiles: String,
+    /// Profile names, they are the sections of `.fig.toml`
+    profile_names: Vec<&'static str>,
+    uses_icons_remote: bool,
+    uses_images_remote: bool,
+    unmapped: Vec<UnmappedOption>,
+}
+
+impl Converter {
+    fn unmapped(&mut self, option: impl Into<String>, reason: impl Into<String>) {
+        self.unmapped.push(UnmappedOption {
+            option: option.into(),
+            reason: reason.into(),
+        });
+    }
+
+    fn unmapped_other(&mut self, prefix: &str, other: &Other) {
+        for key in other.keys() {
+            let option = match prefix {
+                "" => key.to_owned(),
+                _ => format!("{prefix}.{key}"),
+            };
+            self.unmapped(option, reason(key));
+        }
+    }
+
+    fn profile(&mut self, name: &'static str, extends: &str, remote: &str) {
+        self.profile_names.push(name);
+        let _ = writeln!(self.profiles, "\n[profiles.{name}]");
+        let _ = writeln!(self.profiles, "extends = {}", quoted(extends));
+        let _ = writeln!(self.profiles, "remote = {}", quoted(remote));
+        match remote {
+            "icons" => self.uses_icons_remote = true,
+            _ => self.uses_images_remote = true,
+        }
+    }
+
+    fn option(&mut self, key: &str, value: impl std::fmt::Display) {
+        let _ = writeln!(self.profiles, "{key} = {value}");
+    }
+}
+
+impl FigmaExportConfig {
+    pub fn convert(self) -> ConvertedConfig {
+        let mut c = Converter::default();
+        let figma = self.figma.unwrap_or_default();
+        if figma.dark_file_id.is_some() {
+            c.unmapped(
+                "figma.darkFileId",
+                "figx takes dark variants from the same file, see the `night` option of Android profiles",
+            );
+        }
+        c.unmapped_other("figma", &figma.other);
+        c.unmapped_other("common", &self.common.other);
+        c.unmapped_other("common.icons", &self.common.icons.other);
+        c.unmapped_other("common.images", &self.common.images.other);
+        c.unmapped_other("", &self.other);
+
+        if let Some(android) = self.android {
+            convert_android(&mut c, android, &self.common);
+        }
+        if let Some(ios) = self.ios {
+            convert_ios(&mut c, ios, &self.common);
+        }
+
+        let file_key = figma
+            .light_file_id
+            .unwrap_or_else(|| "<FIGMA_FILE_KEY>".to_owned());
+        let mut default = true;
+        for (remote, used, assets) in [
+            ("icons", c.uses_icons_remote, &self.common.icons),
+            ("images", c.uses_images_remote, &self.common.images),
+        ] {
+            if !used {
+                continue;
+            }
+            let frame_name = assets.figma_frame_name.as_deref().unwrap_or(match remote {
+                "icons" => "Icons",
+                _ => "Illustrations",
+            });
+            let _ = writeln!(c.remotes, "\n[remotes.{remote}]");
+            let _ = writeln!(c.remotes, "file_key = {}", quoted(&file_key));
+            let _ = writeln!(
+                c.remotes,
+                "# node id of the {} frame, copy it from the link to the frame in Figma",
+                quoted(frame_name),
+            );
+            let _ = writeln!(c.remotes, "container_node_ids = [\"<NODE_ID>\"]");
+            let _ = writeln!(
+                c.remotes,
+                "access_token = {{ env = \"FIGMA_PERSONAL_TOKEN\" }}"
+            );
+            if default {
+                let _ = writeln!(c.remotes, "default = true");
+                default = false;
+            }
+        }
+
+        let mut figtree =
+            String::from("# Converted from the figma-export config by `figx import-config`\n");
+        figtree.push_str(&c.remotes);
+        figtree.push_str(&c.profiles);
+
+        let mut fig = String::from(
+            "# figma-export imported every component of a frame, figx imports the resources\n\
+             # listed here only: resource name = \"node name in Figma\"\n",
+        );
+        for name in &c.profile_names {
+            let _ = writeln!(fig, "\n[{name}]");
+        }
+        ConvertedConfig {
+            figtree,
+            fig,
+            unmapped: c.unmapped,
+        }
+    }
+}
+
+fn convert_android(c: &mut Converter, android: AndroidSection, common: &CommonSection) {
+    let main_res = android.main_res.as_deref().unwrap_or("src/main/res");
+    if let Some(icons) = android.icons {
+        match (&icons.compose_package_name, &android.main_src) {
+            (Some(package), Some(main_src)) => {
+                c.profile("android-icons", "compose", "icons");
+                c.option("src_dir", quoted(main_src));
+                c.option("package", quoted(package));
+                if icons.output.is_some() {
+                    c.unmapped(
+                        "android.icons.output",
+                        "Compose icons are written to `src_dir`",
+                    );
+                }
+                if common.icons.dark_mode_suffix.is_some() {
+                    c.unmapped(
+                        "common.icons.darkModeSuffix",
+                        "Compose icons have no dark variants, use `color_mappings` to theme them",
+                    );
+                }
+            }
+            (package, _) => {
+                if package.is_some() {
+                    c.unmapped(
+                        "android.icons.composePackageName",
+                        "`android.mainSrc` is required to write Compose icons",
+                    );
+                }
+                c.profile("android-icons", "android-drawable", "icons");
+                c.option("android_res_dir", quoted(&join(main_res, &icons.output)));
+                if let Some(suffix) = &common.icons.dark_mode_suffix {
+                    c.option("night", quoted(&format!("{{base}}{suffix}")));
+                }
+            }
+        }
+        c.unmapped_other("android.icons", &icons.other);
+    }
+    if let Some(images) = android.images {
+        match images.format.as_deref() {
+            Some("svg") => c.profile("android-images", "android-drawable", "images"),
+            format => {
+                if let Some(format) = format.filter(|it| *it != "webp") {
+                    c.unmapped(
+                        "android.images.format",
+                        format!("figx writes WEBP drawables instead of {format}"),
+                    );
+                }
+                c.profile("android-images", "android-webp", "images");
+                let scales = images
+                    .scales
+                    .unwrap_or_else(|| vec![1.0, 1.5, 2.0, 3.0, 4.0]);
+                let mut densities = Vec::new();
+                for scale in scales {
+                    match density(scale) {
+                        Some(density) => densities.push(quoted(density)),
+                        None => c.unmapped(
+                            "android.images.scales",
+                            format!("there is no Android density with scale {scale}"),
+                        ),
+                    }
+                }
+                c.option("densities", format!("[{}]", densities.join(", ")));
+                if let Some(webp) = images.webp_options {
+                    if webp.encoding.as_deref() == Some("lossless") {
+                        c.option("lossless", true);
+                    }
+                    if let Some(quality) = webp.quality {
+                        c.option("quality", quality);
+                    }
+                    c.unmapped_other("android.images.webpOptions", &webp.other);
+                }
+            }
+        }
+        c.option("android_res_dir", quoted(&join(main_res, &images.output)));
+        if let Some(suffix) = &common.images.dark_mode_suffix {
+            c.option("night", quoted(&format!("{{base}}{suffix}")));
+        }
+        c.unmapped_other("android.images", &images.other);
+    }
+    c.unmapped_other("android", &android.other);
+}
+
+fn convert_ios(c: &mut Converter, ios: IosSection, common: &CommonSection) {
+    let xcassets = ios.xcassets_path.as_deref().unwrap_or(".");
+    if ios.xcassets_path.is_some() {
+        c.unmapped(
+            "ios.xcassetsPath",
+            "figx writes plain files, move them into `.imageset` folders of the asset catalog",
+        );
+    }
+    if let Some(icons) = ios.icons {
+        let extends = match icons.format.as_deref() {
+            Some("svg") => "svg",
+            _ => "pdf",
+        };
+        c.profile("ios-icons", extends, "icons");
+        let folder = icons.assets_folder.or_else(|| Some("Icons".to_owned()));
+        c.option("output_dir", quoted(&join(xcassets, &folder)));
+        if common.icons.dark_mode_suffix.is_some() {
+            c.unmapped(
+                "common.icons.darkModeSuffix",
+                "iOS profiles have no dark variants, import them as separate resources",
+            );
+        }
+        c.unmapped_other("ios.icons", &icons.other);
+    }
+    if let Some(images) = ios.images {
+        c.profile("ios-images", "png", "images");
+        let folder = images.assets_folder.or_else(|| Some("Images".to_owned()));
+        c.option("output_dir", quoted(&join(xcassets, &folder)));
+        let scales = images.scales.unwrap_or_else(|| vec![1.0, 2.0, 3.0]);
+        let variants = scales
+            .iter()
+            .map(|scale| quoted(&variant_id(*scale)))
+            .collect::<Vec<_>>();
+        c.option("variants.use", format!("[{}]", variants.join(", ")));
+        for scale in scales {
+            let output_name = match scale {
+                1.0 => "{base}".to_owned(),
+                _ => format!("{{base}}@{scale}x"),
+            };
+            c.option(
+                &format!("variants.{}", variant_id(scale)),
+                format!(
+                    "{{ output_name = {}, figma_name = \"{{base}}\", scale = {scale:?} }}",
+                    quoted(&output_name),
+                ),
+            );
+        }
+        if common.images.dark_mode_suffix.is_some() {
+            c.unmapped(
+                "common.images.darkModeSuffix",
+                "iOS profiles have no dark variants, import them as separate resources",
+            );
+        }
+        c.unmapped_other("ios.images", &images.other);
+    }
+    c.unmapped_other("ios", &ios.other);
+}
+
+fn reason(key: &str) -> String {
+    match key {
+        "colors" | "typography" => "figx imports images only",
+        "nameValidateRegexp" | "nameReplaceRegexp" | "nameStyle" => {
+            "resources are named explicitly in `.fig.toml`"
+        }
+        "timeout" => "figx retries failed requests, see the `retry` option of remotes",
+        "xcodeprojPath" | "target" => "figx doesn't modify Xcode projects",
+        _ => "no equivalent in figx",
+    }
+    .to_owned()
+}
+
+fn density(scale: f32) -> Option<&'static str> {
+    match scale {
+        0.75 => Some("ldpi"),
+        1.0 => Some("mdpi"),
+        1.5 => Some("hdpi"),
+        2.0 => Some("xhdpi"),
+        3.0 => Some("xxhdpi"),
+        4.0 => Some("xxxhdpi"),
+        _ => None,
+    }
+}
+
+/// Name of the variant of PNG profile, e.g. `x1_5` for `@1.5x` images
+fn variant_id(scale: f32) -> String {
+    format!("x{}", scale.to_string().replace('.', "_"))
+}
+
+/// Joins the directory of figma-export with the optional subdirectory
+fn join(dir: &str, subdir: &Option<String>) -> String {
+    match subdir.as_deref().map(|it| it.trim_start_matches("./")) {
+        Some(subdir) if !subdir.is_empty() => format!("{}/{subdir}", dir.trim_end_matches('/')),
+        _ => dir.to_owned(),
+    }
+}
+
+fn quoted(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn FigmaExportConfig__android_config__EXPECT__profiles_and_unmapped_options() {
+        // Given
+        let yaml = r#"
+figma:
+  lightFileId: shPilWnVdJfo10YF12345
+  timeout: 30
+common:
+  icons:
+    figmaFrameName: Icons
+    nameValidateRegexp: '^(ic)_(\d\d)_([a-z0-9_]+)$'
+  images:
+    figmaFrameName: Illustrations
+    darkModeSuffix: '_dark'
+android:
+  mainRes: "./main/res"
+  resourcePackage: "com.example"
+  icons:
+    output: "figma-export-icons"
+  images:
+    scales: [1.5, 2, 3]
+    output: "figma-export-images"
+    format: webp
+    webpOptions:
+      encoding: lossy
+      quality: 90
+"#;
+
+        // When
+        let config: FigmaExportConfig = serde_yaml::from_str(yaml).unwrap();
+        let converted = config.convert();
+
+        // Then
+        let figtree = &converted.figtree;
+        assert!(figtree.contains("[remotes.icons]\nfile_key = \"shPilWnVdJfo10YF12345\""));
+        assert!(figtree.contains("# node id of the \"Illustrations\" frame"));
+        assert!(figtree.contains(
+            "[profiles.android-icons]\nextends = \"android-drawable\"\nremote = \"icons\"\nandroid_res_dir = \"./main/res/figma-export-icons\""
+        ));
+        assert!(figtree.contains("densities = [\"hdpi\", \"xhdpi\", \"xxhdpi\"]"));
+        assert!(figtree.contains("quality = 90"));
+        assert!(figtree.contains("night = \"{base}_dark\""));
+        assert!(
+            converted
+                .fig
+                .contains("[android-icons]\n\n[android-images]")
+        );
+        let unmapped = converted
+            .unmapped
+            .iter()
+            .map(|it| it.option.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "figma.timeout",
+                "common.icons.nameValidateRegexp",
+                "android.resourcePackage",
+            ],
+            unmapped,
+        );
+    }
+
+    fn convert(yaml: &str) -> ConvertedConfig {
+        let config: FigmaExportConfig = serde_yaml::from_str(yaml).unwrap();
+        config.convert()
+    }
+
+    fn unmapped(converted: &ConvertedConfig) -> Vec<(&str, &str)> {
+        converted
+            .unmapped
+            .iter()
+            .map(|it| (it.option.as_str(), it.reason.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn FigmaExportConfig__android_compose_icons__EXPECT__compose_profile() {
+        // Given
+        let yaml = r#"
+figma:
+  lightFileId: FILE
+common:
+  icons:
+    darkModeSuffix: '_dark'
+android:
+  mainSrc: "./src/main/kotlin"
+  icons:
+    output: "icons"
+    composePackageName: "com.example.icons"
+"#;
+
+        // When
+        let converted = convert(yaml);
+
+        // Then
+        assert!(converted.figtree.contains(
+            "[profiles.android-icons]\nextends = \"compose\"\nremote = \"icons\"\nsrc_dir = \"./src/main/kotlin\"\npackage = \"com.example.icons\"\n"
+        ));
+        assert!(converted.figtree.contains("[remotes.icons]"));
+        assert!(!converted.figtree.contains("[remotes.images]"));
+        assert_eq!(
+            vec![
+                (
+                    "android.icons.output",
+                    "Compose icons are written to `src_dir`"
+                ),
+                (
+                    "common.icons.darkModeSuffix",
+                    "Compose icons have no dark variants, use `color_mappings` to theme them",
+                ),
+            ],
+            unmapped(&converted),
+        );
+    }
+
+    #[test]
+    fn FigmaExportConfig__android_svg_images__EXPECT__drawable_profile() {
+        // Given
+        let yaml = r#"
+figma:
+  lightFileId: FILE
+common:
+  images:
+    darkModeSuffix: '_night'
+android:
+  images:
+    format: svg
+"#;
+
+        // When
+        let converted = convert(yaml);
+
+        // Then
+        assert!(converted.figtree.contains(
+            "[profiles.android-images]\nextends = \"android-drawable\"\nremote = \"images\"\nandroid_res_dir = \"src/main/res\"\nnight = \"{base}_night\"\n"
+        ));
+        assert!(converted.figtree.contains("[remotes.images]"));
+        assert!(converted.figtree.contains("default = true"));
+        assert!(unmapped(&converted).is_empty());
+    }
+
+    #[test]
+    fn FigmaExportConfig__android_png_images__EXPECT__webp_profile_and_unmapped_format_and_scale() {
+        // Given
+        let yaml = r#"
+figma:
+  lightFileId: FILE
+android:
+  images:
+    format: png
+    scales: [0.5, 1]
+    webpOptions:
+      encoding: lossless
+"#;
+
+        // When
+        let converted = convert(yaml);
+
+        // Then
+        assert!(converted.figtree.contains(
+            "[profiles.android-images]\nextends = \"android-webp\"\nremote = \"images\"\ndensities = [\"mdpi\"]\nlossless = true\n"
+        ));
+        assert_eq!(
+            vec![
+                (
+                    "android.images.format",
+                    "figx writes WEBP drawables instead of png",
+                ),
+                (
+                    "android.images.scales",
+                    "there is no Android density with scale 0.5",
+                ),
+            ],
+            unmapped(&converted),
+        );
+    }
+
+    #[test]
+    fn FigmaExportConfig__compose_package_without_main_src__EXPECT__drawable_profile() {
+        // Given
+        let yaml = r#"
+figma:
+  lightFileId: FILE
+android:
+  icons:
+    composePackageName: "com.example.icons"
+"#;
+
+        // When
+        let converted = convert(yaml);
+
+        // Then
+        assert!(converted.figtree.contains(
+            "[profiles.android-icons]\nextends = \"android-drawable\"\nremote = \"icons\"\nandroid_res_dir = \"src/main/res\"\n"
+        ));
+        assert_eq!(
+            vec![(
+                "android.icons.composePackageName",
+                "`android.mainSrc` is required to write Compose icons",
+            )],
+            unmapped(&converted),
+        );
+    }
+
+    #[test]
+    fn FigmaExportConfig__ios_config__EXPECT__svg_and_png_profiles() {
+        // Given
+        let yaml = r#"
+figma:
+  lightFileId: FILE
+ios:
+  xcodeprojPath: "Example.xcodeproj"
+  xcassetsPath: "./Resources/Assets.xcassets"
+  icons:
+    format: svg
+    assetsFolder: "Glyphs"
+  images:
+    scales: [1, 2]
+"#;
+
+        // When
+        let converted = convert(yaml);
+
+        // Then
+        let figtree = &converted.figtree;
+        assert!(figtree.contains(
+            "[profiles.ios-icons]\nextends = \"svg\"\nremote = \"icons\"\noutput_dir = \"./Resources/Assets.xcassets/Glyphs\"\n"
+        ));
+        assert!(figtree.contains(
+            "[profiles.ios-images]\nextends = \"png\"\nremote = \"images\"\noutput_dir = \"./Resources/Assets.xcassets/Images\"\n"
+        ));
+        assert!(figtree.contains("variants.use = [\"x1\", \"x2\"]"));
+        assert!(figtree.contains(
+            "variants.x1 = { output_name = \"{base}\", figma_name = \"{base}\", scale = 1.0 }"
+        ));
+        assert!(figtree.contains(
+            "variants.x2 = { output_name = \"{base}@2x\", figma_name = \"{base}\", scale = 2.0 }"
+        ));
+        assert!(converted.fig.contains("[ios-icons]\n\n[ios-images]"));
+        assert_eq!(
+            vec![
+                (
+                    "ios.xcassetsPath",
+                    "figx writes plain files, move them into `.imageset` folders of the asset catalog",
+                ),
+                ("ios.xcodeprojPath", "figx doesn't modify Xcode projects"),
+            ],
+            unmapped(&converted),
+        );
+    }
+
+    #[test]
+    fn FigmaExportConfig__ios_icons_without_format__EXPECT__pdf_profile() {
+        // Given
+        let yaml = r#"
+figma:
+  lightFileId: FILE
+ios:
+  icons: {}
+"#;
+
+        // When
+        let converted = convert(yaml);
+
+        // Then
+        assert!(converted.figtree.contains(
+            "[profiles.ios-icons]\nextends = \"pdf\"\nremote = \"icons\"\noutput_dir = \"./Icons\"\n"
+        ));
+        assert!(unmapped(&converted).is_empty());
+    }
+
+    #[test]
+    fn FigmaExportConfig__unsupported_options__EXPECT__reported_with_reasons() {
+        // Given
+        let yaml = r#"
+figma:
+  lightFileId: FILE
+  darkFileId: DARK_FILE
+common:
+  colors:
+    useColorAssets: true
+android:
+  mainRes: "./res"
+  typography: {}
+"#;
+
+        // When
+        let converted = convert(yaml);
+
+        // Then
+        assert_eq!(
+            vec![
+                (
+                    "figma.darkFileId",
+                    "figx takes dark variants from the same file, see the `night` option of Android profiles",
+                ),
+                ("common.colors", "figx imports images only"),
+                ("android.typography", "figx imports images only"),
+            ],
+            unmapped(&converted),
+        );
+        // nothing to import, so there are no remotes and profiles
+        assert!(!converted.figtree.contains("[remotes."));
+        assert!(!converted.figtree.contains("[profiles."));
+    }
+
+    #[test]
+    fn FigmaExportConfig__invalid_option_type__EXPECT__error() {
+        // Given
+        let yaml = r#"
+figma:
+  lightFileId: FILE
+android:
+  images:
+    scales: "all"
+"#;
+
+        // When
+        let result = serde_yaml::from_str::<FigmaExportConfig>(yaml);
+
+        // Then
+        assert!(result.is_err());
+    }
+}
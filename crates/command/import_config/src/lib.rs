@@ -0,0 +1,158 @@
+mod error;
+mod figma_export;
+use crossterm::style::Stylize;
+pub use error::*;
+use figma_export::FigmaExportConfig;
+use lib_dashboard::is_quiet;
+use std::path::{Path, PathBuf};
+
+pub struct FeatureImportConfigOptions {
+    /// Config of another tool, e.g. `figma-export.yaml`
+    pub path: PathBuf,
+    /// Where to write the workspace, the directory of the config by default
+    pub output_dir: Option<PathBuf>,
+}
+
+/// figx workspace converted from the config of another tool
+pub(crate) struct ConvertedConfig {
+    /// Content of `.figtree.toml`
+    pub figtree: String,
+    /// Content of the `.fig.toml` next to it
+    pub fig: String,
+    pub unmapped: Vec<UnmappedOption>,
+}
+
+/// Option of the converted config which has no equivalent in figx
+pub(crate) struct UnmappedOption {
+    /// Path to the option, e.g. `android.icons.nameStyle`
+    pub option: String,
+    pub reason: String,
+}
+
+pub fn import_config(opts: FeatureImportConfigOptions) -> Result<()> {
+    let content = std::fs::read_to_string(&opts.path)?;
+    let config: FigmaExportConfig = serde_yaml::from_str(&content)?;
+    // `figma.lightFileId` is the only required option of figma-export
+    if config.figma.is_none() {
+        return Err(Error::UnsupportedConfig(opts.path));
+    }
+    let converted = config.convert();
+
+    let dir = match opts.output_dir {
+        Some(dir) => dir,
+        None => opts
+            .path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default(),
+    };
+    let figtree_path = dir.join(".figtree.toml");
+    let fig_path = dir.join(".fig.toml");
+    for path in [&figtree_path, &fig_path] {
+        if path.exists() {
+            return Err(Error::AlreadyExists(path.to_owned()));
+        }
+    }
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(&figtree_path, converted.figtree)?;
+    std::fs::write(&fig_path, converted.fig)?;
+    if is_quiet() {
+        return Ok(());
+    }
+    println!("Created {}", figtree_path.display());
+    println!("Created {}", fig_path.display());
+
+    if !converted.unmapped.is_empty() {
+        let n = converted.unmapped.len();
+        println!(
+            "\n{n} option{s} could not be converted:",
+            s = if n == 1 { "" } else { "s" },
+        );
+        for UnmappedOption { option, reason } in &converted.unmapped {
+            println!(
+                "  {} {}",
+                option.as_str().yellow(),
+                reason.as_str().dark_grey()
+            );
+        }
+    }
+    println!(
+        "\nFill in the node ids of the remotes in `{}` and list the resources in `{}`, then run `figx import //...`",
+        figtree_path.display(),
+        fig_path.display(),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    fn import(dir: &Path, yaml: &str) -> Result<()> {
+        let path = dir.join("figma-export.yaml");
+        std::fs::write(&path, yaml).unwrap();
+        import_config(FeatureImportConfigOptions {
+            path,
+            output_dir: None,
+        })
+    }
+
+    #[test]
+    fn import_config__figma_export_config__EXPECT__workspace_written() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+
+        // When
+        let result = import(dir.path(), "figma:\n  lightFileId: FILE\n");
+
+        // Then
+        assert!(result.is_ok());
+        assert!(dir.path().join(".figtree.toml").is_file());
+        assert!(dir.path().join(".fig.toml").is_file());
+    }
+
+    #[test]
+    fn import_config__invalid_yaml__EXPECT__yaml_error() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+
+        // When
+        let result = import(dir.path(), "figma: [");
+
+        // Then
+        assert!(matches!(result, Err(Error::Yaml(_))));
+        assert!(!dir.path().join(".figtree.toml").exists());
+    }
+
+    #[test]
+    fn import_config__config_without_figma_section__EXPECT__unsupported_config() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+
+        // When
+        let result = import(dir.path(), "name: some other tool\n");
+
+        // Then
+        assert!(matches!(result, Err(Error::UnsupportedConfig(_))));
+        assert!(!dir.path().join(".figtree.toml").exists());
+    }
+
+    #[test]
+    fn import_config__existing_workspace__EXPECT__not_overwritten() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(".fig.toml"), "[icons]\n").unwrap();
+
+        // When
+        let result = import(dir.path(), "figma:\n  lightFileId: FILE\n");
+
+        // Then
+        assert!(matches!(result, Err(Error::AlreadyExists(_))));
+        assert!(!dir.path().join(".figtree.toml").exists());
+        assert_eq!(
+            "[icons]\n",
+            std::fs::read_to_string(dir.path().join(".fig.toml")).unwrap()
+        );
+    }
+}
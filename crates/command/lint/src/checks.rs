@@ -0,0 +1,491 @@
+use crate::{LintCheck, LintIssue, Result};
+use log::{info, warn};
+use phase_evaluation::targets_from_resource;
+use phase_loading::{
+    ExportScale, LOCAL_WORKSPACE_FILE_NAME, NameCase, Profile, ProfileKind, Resource, Workspace,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    str::FromStr,
+};
+
+fn resources(ws: &Workspace) -> impl Iterator<Item = &Resource> {
+    ws.packages.iter().flat_map(|it| &it.resources)
+}
+
+fn resource_issue(res: &Resource, check: LintCheck, message: String) -> LintIssue {
+    LintIssue {
+        check,
+        message,
+        file: res.attrs.diag.file.as_ref().clone(),
+        label: Some(res.attrs.label.to_string()),
+        help: None,
+    }
+}
+
+/// Resources whose nodes are not among the components found by the last `figx scan`.
+/// Remotes which were never scanned are skipped.
+pub(crate) fn missing_nodes(ws: &Workspace) -> Vec<LintIssue> {
+    let scans_dir = ws.context.out_dir.join("scans");
+    let mut scans = HashMap::<&str, Option<HashSet<String>>>::new();
+    let mut issues = Vec::new();
+    for res in resources(ws) {
+        let remote_id = res.attrs.remote.id.as_str();
+        let scanned = scans.entry(remote_id).or_insert_with(|| {
+            let names = read_scanned_names(&scans_dir.join(format!("{remote_id}.toml")));
+            if names.is_none() {
+                info!(target: "Lint", "remote `{remote_id}` is not scanned, run `figx scan {remote_id}` to check its nodes");
+            }
+            names
+        });
+        let Some(scanned) = scanned else {
+            continue;
+        };
+        let mut missing = Vec::new();
+        for target in targets_from_resource(res) {
+            let name = target.figma_name().to_owned();
+            if !scanned.contains(&name) && !missing.contains(&name) {
+                missing.push(name);
+            }
+        }
+        for name in missing {
+            issues.push(LintIssue {
+                help: Some(format!(
+                    "fix the node name or run `figx scan {remote_id}` again if the node was added recently"
+                )),
+                ..resource_issue(
+                    res,
+                    LintCheck::MissingNode,
+                    format!("node `{name}` is not found among the components of remote `{remote_id}`"),
+                )
+            });
+        }
+    }
+    issues
+}
+
+/// Names of the nodes listed in the scan file, `None` if there is no readable scan
+fn read_scanned_names(path: &Path) -> Option<HashSet<String>> {
+    let string = std::fs::read_to_string(path).ok()?;
+    let value = match toml_span::parse(&string) {
+        Ok(value) => value,
+        Err(e) => {
+            warn!(target: "Lint", "unable to read scan `{}`: {e}", path.display());
+            return None;
+        }
+    };
+    let nodes = value
+        .as_table()?
+        .get("node")
+        .and_then(|it| it.as_array())
+        .map(|it| it.as_slice())
+        .unwrap_or_default();
+    let names = nodes
+        .iter()
+        .filter_map(|node| node.as_table()?.get("name")?.as_str())
+        .map(str::to_owned)
+        .collect();
+    Some(names)
+}
+
+/// Custom profiles of the workspace file which no resource is declared under.
+///
+/// Profiles extended by other profiles or used as a raster fallback are not reported,
+/// including the references from the included files and `.figtree.local.toml`.
+pub(crate) fn unused_profiles(ws: &Workspace) -> Result<Vec<LintIssue>> {
+    let ws_file = &ws.context.workspace_file;
+    let string = std::fs::read_to_string(ws_file)?;
+    // the workspace is already loaded, so the file is valid
+    let Ok(value) = toml_span::parse(&string) else {
+        return Ok(Vec::new());
+    };
+    let Some(profiles) = profiles_table(&value) else {
+        return Ok(Vec::new());
+    };
+
+    let mut referenced = HashSet::new();
+    collect_references(profiles, &mut referenced);
+    let mut other_files = value
+        .as_table()
+        .and_then(|it| it.get("include"))
+        .and_then(|it| it.as_array())
+        .map(|it| it.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|it| it.as_str())
+        .map(|it| ws.context.workspace_dir.join(it))
+        .collect::<Vec<_>>();
+    other_files.push(ws.context.workspace_dir.join(LOCAL_WORKSPACE_FILE_NAME));
+    for file in other_files {
+        let Ok(string) = std::fs::read_to_string(&file) else {
+            continue;
+        };
+        let Ok(value) = toml_span::parse(&string) else {
+            continue;
+        };
+        if let Some(profiles) = profiles_table(&value) {
+            collect_references(profiles, &mut referenced);
+        }
+    }
+    for res in resources(ws) {
+        referenced.insert(res.attrs.profile_id.clone());
+    }
+
+    let issues = profiles
+        .iter()
+        .map(|(key, _)| key.name.as_ref())
+        .filter(|id| ProfileKind::from_str(id).is_err() && !referenced.contains(*id))
+        .map(|id| LintIssue {
+            check: LintCheck::UnusedProfile,
+            message: format!("profile `{id}` is not used by any resource"),
+            file: ws_file.to_owned(),
+            label: None,
+            help: Some("remove the profile or declare resources under it".to_owned()),
+        })
+        .collect();
+    Ok(issues)
+}
+
+fn profiles_table<'v, 'de>(
+    value: &'v toml_span::Value<'de>,
+) -> Option<&'v toml_span::value::Table<'de>> {
+    value.as_table()?.get("profiles")?.as_table()
+}
+
+/// Ids of the profiles which the `profiles` extend or fall back to
+fn collect_references(profiles: &toml_span::value::Table<'_>, referenced: &mut HashSet<String>) {
+    for (_, profile) in profiles.iter() {
+        let Some(profile) = profile.as_table() else {
+            continue;
+        };
+        if let Some(parent) = profile.get("extends").and_then(|it| it.as_str()) {
+            referenced.insert(parent.to_owned());
+        }
+        let fallback = profile
+            .get("limits")
+            .and_then(|it| it.as_table())
+            .and_then(|it| it.get("fallback"))
+            .and_then(|it| it.as_str());
+        if let Some(fallback) = fallback {
+            referenced.insert(fallback.to_owned());
+        }
+    }
+}
+
+/// Remotes no resource is imported from
+pub(crate) fn unused_remotes(ws: &Workspace) -> Vec<LintIssue> {
+    let used = resources(ws)
+        .map(|it| it.attrs.remote.id.as_str())
+        .collect::<HashSet<_>>();
+    ws.remotes
+        .iter()
+        .filter(|it| !used.contains(it.id.as_str()))
+        .map(|it| LintIssue {
+            check: LintCheck::UnusedRemote,
+            message: format!("remote `{}` is not used by any resource", it.id),
+            file: ws.context.workspace_file.to_owned(),
+            label: None,
+            help: Some("remove the remote, so that `figx fetch` doesn't download it".to_owned()),
+        })
+        .collect()
+}
+
+/// Scales which are likely typos: raster exports beyond what Figma renders,
+/// and Flutter scales without the main 1x asset
+pub(crate) fn suspicious_scales(ws: &Workspace) -> Vec<LintIssue> {
+    let mut checked_profiles = HashSet::new();
+    let mut issues = Vec::new();
+    for res in resources(ws) {
+        if let Profile::Flutter(profile) = res.profile.as_ref() {
+            // the same profile is shared by all resources without overrides
+            if !checked_profiles.insert(std::sync::Arc::as_ptr(&res.profile)) {
+                continue;
+            }
+            if !profile.scales.iter().any(|it| **it == 1.0) {
+                issues.push(LintIssue {
+                    help: Some("Flutter resolves the scaled assets next to the 1x one".to_owned()),
+                    ..resource_issue(
+                        res,
+                        LintCheck::SuspiciousScale,
+                        format!(
+                            "scales of profile `{}` don't include 1.0",
+                            res.attrs.profile_id
+                        ),
+                    )
+                });
+            }
+            continue;
+        }
+        for target in targets_from_resource(res) {
+            let Some(scale) = res.effective_scale(target.id.as_deref()) else {
+                continue;
+            };
+            if scale > ExportScale::FIGMA_MAX {
+                issues.push(LintIssue {
+                    help: Some(format!(
+                        "scales above {} are rendered by figx instead of Figma, which is slower and may look different",
+                        ExportScale::FIGMA_MAX,
+                    )),
+                    ..resource_issue(
+                        res,
+                        LintCheck::SuspiciousScale,
+                        format!("`{}` is exported at scale {scale}", target.qualified_name()),
+                    )
+                });
+            }
+        }
+    }
+    issues
+}
+
+/// Output names of Flutter assets, which are expected to be in snake case.
+/// Names of Android resources are already validated by the loader
+pub(crate) fn naming_conventions(ws: &Workspace) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    for res in resources(ws) {
+        if res.profile.kind() != ProfileKind::Flutter {
+            continue;
+        }
+        // the case is already enforced by the profile
+        if res.profile.naming().case.is_some() {
+            continue;
+        }
+        let name = res.output_base_name();
+        let expected = NameCase::Snake.apply(&name);
+        if name != expected {
+            issues.push(LintIssue {
+                help: Some(format!(
+                    "rename it to `{expected}` or set `naming = {{ case = \"snake\" }}` in the profile"
+                )),
+                ..resource_issue(
+                    res,
+                    LintCheck::NamingConvention,
+                    format!("name `{name}` is not in snake case, as Flutter assets are expected to be"),
+                )
+            });
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use lib_label::LabelPattern;
+    use std::path::PathBuf;
+
+    static REMOTE: &str = "[remotes.design]\nfile_key = \"FILE\"\ncontainer_node_ids = [\"0:1\"]\n";
+
+    fn write(path: PathBuf, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn load(dir: &Path) -> Workspace {
+        let pattern = LabelPattern::try_from(vec!["//...".to_string()]).unwrap();
+        phase_loading::load_workspace_in(dir, pattern, true).unwrap()
+    }
+
+    fn messages(issues: Vec<LintIssue>) -> Vec<String> {
+        issues.into_iter().map(|it| it.message).collect()
+    }
+
+    #[test]
+    fn unused_profiles__profile_without_resources__EXPECT__reported() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let profiles =
+            "[profiles.icons]\nextends = \"svg\"\n[profiles.raster]\nextends = \"webp\"\n";
+        write(
+            dir.path().join(".figtree.toml"),
+            &format!("{REMOTE}{profiles}"),
+        );
+        write(
+            dir.path().join("icons").join(".fig.toml"),
+            "[icons]\nic_star = \"Star\"\n",
+        );
+        let ws = load(dir.path());
+
+        // When
+        let issues = unused_profiles(&ws).unwrap();
+
+        // Then
+        assert_eq!(
+            vec!["profile `raster` is not used by any resource".to_owned()],
+            messages(issues),
+        );
+    }
+
+    #[test]
+    fn unused_profiles__fallback_in_local_file__EXPECT__not_reported() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let profiles = "[profiles.raster]\nextends = \"webp\"\n";
+        write(
+            dir.path().join(".figtree.toml"),
+            &format!("{REMOTE}{profiles}"),
+        );
+        write(
+            dir.path().join(LOCAL_WORKSPACE_FILE_NAME),
+            "[profiles.compose]\nlimits = { fallback = \"raster\" }\n",
+        );
+        write(
+            dir.path().join("icons").join(".fig.toml"),
+            "[svg]\nic_star = \"Star\"\n",
+        );
+        let ws = load(dir.path());
+
+        // When
+        let issues = unused_profiles(&ws).unwrap();
+
+        // Then
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn unused_remotes__remote_without_resources__EXPECT__reported() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let unused = "[remotes.old]\nfile_key = \"OLD\"\ncontainer_node_ids = [\"0:1\"]\n";
+        write(
+            dir.path().join(".figtree.toml"),
+            &format!("{REMOTE}{unused}"),
+        );
+        write(
+            dir.path().join("icons").join(".fig.toml"),
+            "[svg]\nic_star = \"Star\"\n",
+        );
+        let ws = load(dir.path());
+
+        // When
+        let issues = unused_remotes(&ws);
+
+        // Then
+        assert_eq!(
+            vec!["remote `old` is not used by any resource".to_owned()],
+            messages(issues),
+        );
+    }
+
+    #[test]
+    fn missing_nodes__node_not_in_scan__EXPECT__reported() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path().join(".figtree.toml"), REMOTE);
+        write(
+            dir.path().join("icons").join(".fig.toml"),
+            "[svg]\nic_star = \"Star\"\nic_moon = \"Moon\"\n",
+        );
+        write(
+            dir.path()
+                .join(".figx-out")
+                .join("scans")
+                .join("design.toml"),
+            "[[node]]\nname = \"Star\"\n",
+        );
+        let ws = load(dir.path());
+
+        // When
+        let issues = missing_nodes(&ws);
+
+        // Then
+        assert_eq!(
+            vec!["node `Moon` is not found among the components of remote `design`".to_owned()],
+            messages(issues),
+        );
+    }
+
+    #[test]
+    fn missing_nodes__remote_not_scanned__EXPECT__nothing_reported() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path().join(".figtree.toml"), REMOTE);
+        write(
+            dir.path().join("icons").join(".fig.toml"),
+            "[svg]\nic_star = \"Star\"\n",
+        );
+        let ws = load(dir.path());
+
+        // When
+        let issues = missing_nodes(&ws);
+
+        // Then
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn suspicious_scales__scale_above_figma_max__EXPECT__reported() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let profiles = "[profiles.png]\nscale = 8.0\n";
+        write(
+            dir.path().join(".figtree.toml"),
+            &format!("{REMOTE}{profiles}"),
+        );
+        write(
+            dir.path().join("icons").join(".fig.toml"),
+            "[png]\nic_star = \"Star\"\n",
+        );
+        let ws = load(dir.path());
+
+        // When
+        let issues = suspicious_scales(&ws);
+
+        // Then
+        assert_eq!(
+            vec!["`//icons:ic_star` is exported at scale 8".to_owned()],
+            messages(issues),
+        );
+    }
+
+    #[test]
+    fn suspicious_scales__flutter_without_1x__EXPECT__reported_once() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let profiles = "[profiles.flutter]\nscales = [2.0, 3.0]\n";
+        write(
+            dir.path().join(".figtree.toml"),
+            &format!("{REMOTE}{profiles}"),
+        );
+        write(
+            dir.path().join("icons").join(".fig.toml"),
+            "[flutter]\nic_star = \"Star\"\nic_moon = \"Moon\"\n",
+        );
+        let ws = load(dir.path());
+
+        // When
+        let issues = suspicious_scales(&ws);
+
+        // Then
+        assert_eq!(
+            vec!["scales of profile `flutter` don't include 1.0".to_owned()],
+            messages(issues),
+        );
+    }
+
+    #[test]
+    fn naming_conventions__flutter_name_not_in_snake_case__EXPECT__reported() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path().join(".figtree.toml"), REMOTE);
+        write(
+            dir.path().join("icons").join(".fig.toml"),
+            "[flutter]\nIcStar = \"Star\"\nic_moon = \"Moon\"\n",
+        );
+        let ws = load(dir.path());
+
+        // When
+        let issues = naming_conventions(&ws);
+
+        // Then
+        assert_eq!(
+            vec![
+                "name `IcStar` is not in snake case, as Flutter assets are expected to be"
+                    .to_owned()
+            ],
+            messages(issues),
+        );
+    }
+}
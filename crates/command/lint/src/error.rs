@@ -0,0 +1,30 @@
+use std::fmt::{Debug, Display};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    Workspace(phase_loading::Error),
+    IO(std::io::Error),
+    /// Lint found issues, they are already printed
+    IssuesFound(usize),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&self, f)
+    }
+}
+impl std::error::Error for Error {}
+
+impl From<phase_loading::Error> for Error {
+    fn from(value: phase_loading::Error) -> Self {
+        Self::Workspace(value)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Self::IO(value)
+    }
+}
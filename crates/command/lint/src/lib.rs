@@ -0,0 +1,117 @@
+use crossterm::style::Stylize;
+use lib_dashboard::is_quiet;
+use lib_label::LabelPattern;
+use serde::Serialize;
+use std::{path::PathBuf, str::FromStr};
+
+mod checks;
+mod error;
+pub use error::*;
+
+pub struct FeatureLintOptions {
+    pub format: LintOutputFormat,
+}
+
+pub enum LintOutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Serialize)]
+struct LintReportDto<'a> {
+    version: u32,
+    issues: &'a [LintIssue],
+}
+
+/// Problem of the workspace which doesn't prevent the import, but is likely a mistake
+#[derive(Serialize)]
+struct LintIssue {
+    check: LintCheck,
+    message: String,
+    /// Config file the issue is found in
+    file: PathBuf,
+    /// Resource the issue is found in, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help: Option<String>,
+}
+
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+enum LintCheck {
+    MissingNode,
+    UnusedProfile,
+    UnusedRemote,
+    SuspiciousScale,
+    NamingConvention,
+}
+
+impl LintCheck {
+    fn as_str(&self) -> &'static str {
+        use LintCheck::*;
+        match self {
+            MissingNode => "missing-node",
+            UnusedProfile => "unused-profile",
+            UnusedRemote => "unused-remote",
+            SuspiciousScale => "suspicious-scale",
+            NamingConvention => "naming-convention",
+        }
+    }
+}
+
+pub fn lint(opts: FeatureLintOptions) -> Result<()> {
+    // unused profiles and remotes can only be found among all packages
+    let pattern = LabelPattern::from_str("//...").expect("always valid pattern");
+    let ws = phase_loading::load_workspace(pattern, true)?;
+
+    // resources writing the same file are already rejected by the loading
+    let mut issues = Vec::new();
+    issues.append(&mut checks::missing_nodes(&ws));
+    issues.append(&mut checks::unused_profiles(&ws)?);
+    issues.append(&mut checks::unused_remotes(&ws));
+    issues.append(&mut checks::suspicious_scales(&ws));
+    issues.append(&mut checks::naming_conventions(&ws));
+
+    match opts.format {
+        LintOutputFormat::Text => print_text(&issues),
+        LintOutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&LintReportDto {
+                version: 1,
+                issues: &issues,
+            })
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+            println!("{json}");
+        }
+    }
+    match issues.len() {
+        0 => Ok(()),
+        count => Err(Error::IssuesFound(count)),
+    }
+}
+
+fn print_text(issues: &[LintIssue]) {
+    if issues.is_empty() {
+        if !is_quiet() {
+            println!("No issues found");
+        }
+        return;
+    }
+    for issue in issues {
+        println!(
+            "{}{} {}",
+            "warning".yellow().bold(),
+            format!("[{}]:", issue.check.as_str()).bold(),
+            issue.message,
+        );
+        let location = match &issue.label {
+            Some(label) => format!("{} ({label})", issue.file.display()),
+            None => issue.file.display().to_string(),
+        };
+        println!("  {} {location}", "-->".dark_grey());
+        if let Some(help) = &issue.help {
+            println!("  {} {help}", "= help:".dark_grey());
+        }
+        println!();
+    }
+}
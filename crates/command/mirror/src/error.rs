@@ -0,0 +1,47 @@
+use std::fmt::Display;
+
+pub type Result<T> = ::std::result::Result<T, Error>;
+
+pub enum Error {
+    Pattern(lib_label::PatternError),
+    Workspace(phase_loading::Error),
+    Evaluation(phase_evaluation::Error),
+    UserError(String),
+    FigmaError(lib_figma_fluent::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pattern(err) => write!(f, "mirror error: {err}"),
+            Self::Workspace(err) => write!(f, "mirror error: {err}"),
+            Self::Evaluation(err) => write!(f, "mirror error: {err}"),
+            Self::UserError(err) => write!(f, "mirror error: {err}"),
+            Self::FigmaError(err) => write!(f, "mirror error: {err}"),
+        }
+    }
+}
+
+impl From<lib_label::PatternError> for Error {
+    fn from(value: lib_label::PatternError) -> Self {
+        Self::Pattern(value)
+    }
+}
+
+impl From<phase_loading::Error> for Error {
+    fn from(value: phase_loading::Error) -> Self {
+        Self::Workspace(value)
+    }
+}
+
+impl From<phase_evaluation::Error> for Error {
+    fn from(value: phase_evaluation::Error) -> Self {
+        Self::Evaluation(value)
+    }
+}
+
+impl From<lib_figma_fluent::Error> for Error {
+    fn from(value: lib_figma_fluent::Error) -> Self {
+        Self::FigmaError(value)
+    }
+}
@@ -0,0 +1,76 @@
+mod error;
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+pub use error::*;
+use lib_figma_fluent::{FigmaApi, Mirror};
+use lib_label::LabelPattern;
+use lib_metrics::Metrics;
+use log::info;
+use phase_evaluation::{CancellationToken, EvalArgs};
+
+pub struct FeatureMirrorOptions {
+    pub remotes: Vec<String>,
+    pub output: PathBuf,
+    pub concurrency: usize,
+    pub network_concurrency: usize,
+    pub cancellation: CancellationToken,
+}
+
+pub fn mirror(opts: FeatureMirrorOptions) -> Result<()> {
+    let pattern = LabelPattern::try_from(vec!["//...".to_string()])?;
+    let mut ws = phase_loading::load_workspace(pattern, false)?;
+
+    for name in &opts.remotes {
+        if !ws.remotes.iter().any(|it| &it.id == name) {
+            return Err(Error::UserError(format!(
+                "No remote with name '{name}' defined in workspace"
+            )));
+        }
+    }
+    // all remotes are mirrored if none are specified
+    if !opts.remotes.is_empty() {
+        for pkg in &mut ws.packages {
+            pkg.resources
+                .retain(|res| opts.remotes.contains(&res.attrs.remote.id));
+        }
+        ws.packages.retain(|pkg| !pkg.resources.is_empty());
+    }
+
+    // responses from the cache are not recorded, so use an empty one
+    let cache_dir = std::env::temp_dir().join(format!("figx-mirror-{}", std::process::id()));
+    ws.context.cache_dir = cache_dir.clone();
+
+    let recorded = Arc::new(Mutex::new(Mirror::default()));
+    let result = phase_evaluation::evaluate(
+        ws,
+        EvalArgs {
+            fetch: true,
+            refetch: true,
+            concurrency: opts.concurrency,
+            network_concurrency: opts.network_concurrency,
+            cancellation: opts.cancellation,
+            metrics: Metrics::default(),
+            figma_api: FigmaApi::recording(recorded.clone()),
+            ..Default::default()
+        },
+    );
+    let _ = std::fs::remove_dir_all(&cache_dir);
+    result?;
+
+    let mirror = recorded.lock().unwrap();
+    mirror.save(&opts.output)?;
+    let summary = mirror.summary();
+    info!(
+        target: "Mirror",
+        "saved {} documents, {} exports and {} resources ({} KiB) to `{}`",
+        summary.documents,
+        summary.exports,
+        summary.resources,
+        summary.bytes / 1024,
+        opts.output.display(),
+    );
+    Ok(())
+}
@@ -0,0 +1,157 @@
+mod error;
+pub use error::*;
+use lib_dashboard::is_quiet;
+use std::path::{Path, PathBuf};
+
+pub struct FeatureNewOptions {
+    /// Directory of the new workspace, created if it doesn't exist
+    pub path: PathBuf,
+    pub template: Template,
+}
+
+pub enum Template {
+    Android,
+    Ios,
+    Web,
+}
+
+/// File of the new workspace. Variables `{{name}}` and `{{version}}` in its content
+/// are replaced with the name of the workspace directory and the version of figx
+struct TemplateFile {
+    path: &'static str,
+    content: &'static str,
+}
+
+const COMMON_FILES: &[TemplateFile] = &[
+    TemplateFile {
+        path: ".gitignore",
+        content: include_str!("../res/gitignore"),
+    },
+    TemplateFile {
+        path: ".github/workflows/figx.yml",
+        content: include_str!("../res/figx.yml"),
+    },
+];
+
+impl Template {
+    fn files(&self) -> &'static [TemplateFile] {
+        use Template::*;
+        match self {
+            Android => &[
+                TemplateFile {
+                    path: ".figtree.toml",
+                    content: include_str!("../res/android/figtree.toml"),
+                },
+                TemplateFile {
+                    path: "app/.fig.toml",
+                    content: include_str!("../res/android/fig.toml"),
+                },
+            ],
+            Ios => &[
+                TemplateFile {
+                    path: ".figtree.toml",
+                    content: include_str!("../res/ios/figtree.toml"),
+                },
+                TemplateFile {
+                    path: "Resources/.fig.toml",
+                    content: include_str!("../res/ios/fig.toml"),
+                },
+            ],
+            Web => &[
+                TemplateFile {
+                    path: ".figtree.toml",
+                    content: include_str!("../res/web/figtree.toml"),
+                },
+                TemplateFile {
+                    path: "src/.fig.toml",
+                    content: include_str!("../res/web/fig.toml"),
+                },
+            ],
+        }
+    }
+}
+
+pub fn new(opts: FeatureNewOptions) -> Result<()> {
+    let dir = &opts.path;
+    // never overwrite somebody's files
+    if dir.read_dir().is_ok_and(|mut it| it.next().is_some()) {
+        return Err(Error::DirectoryNotEmpty(dir.to_owned()));
+    }
+    let name = workspace_name(dir)?;
+    for file in opts.template.files().iter().chain(COMMON_FILES) {
+        let path = dir.join(file.path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, render(file.content, &name))?;
+        if !is_quiet() {
+            println!("Created {}", path.display());
+        }
+    }
+    if is_quiet() {
+        return Ok(());
+    }
+    println!(
+        "\nReplace the file key and node ids of the remote in `{}`, then run:\n\n  cd {}\n  figx import //...",
+        dir.join(".figtree.toml").display(),
+        dir.display(),
+    );
+    Ok(())
+}
+
+fn workspace_name(dir: &Path) -> Result<String> {
+    let dir = std::path::absolute(dir)?;
+    Ok(dir
+        .file_name()
+        .map(|it| it.to_string_lossy().to_string())
+        .unwrap_or_else(|| "figx workspace".to_string()))
+}
+
+fn render(content: &str, name: &str) -> String {
+    content
+        .replace("{{name}}", name)
+        .replace("{{version}}", env!("CARGO_PKG_VERSION"))
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use lib_label::LabelPattern;
+    use std::str::FromStr;
+
+    #[test]
+    fn render__ci_snippet__EXPECT__only_template_variables_replaced() {
+        // Given
+        let content = include_str!("../res/figx.yml");
+
+        // When
+        let rendered = render(content, "my-assets");
+
+        // Then
+        assert!(rendered.contains("Imports the resources of my-assets"));
+        assert!(rendered.contains(&format!("figx:v{}", env!("CARGO_PKG_VERSION"))));
+        assert!(rendered.contains("${{ secrets.FIGMA_PERSONAL_TOKEN }}"));
+    }
+
+    #[test]
+    fn new__every_template__EXPECT__workspace_loaded() {
+        for template in [Template::Android, Template::Ios, Template::Web] {
+            // Given
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("assets");
+
+            // When
+            new(FeatureNewOptions {
+                path: path.clone(),
+                template,
+            })
+            .unwrap();
+            let pattern = LabelPattern::from_str("//...").unwrap();
+            let ws = phase_loading::load_workspace_in(&path, pattern, true).unwrap();
+
+            // Then
+            assert!(ws.packages.iter().any(|it| !it.resources.is_empty()));
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use crossterm::style::Stylize;
+use lib_dashboard::is_quiet;
+use lib_label::LabelPattern;
+use log::warn;
+use phase_evaluation::OrphanedFile;
+use phase_evaluation::{AuditAction, AuditLog, ImportManifest, OrphanReason};
+use phase_loading::Workspace;
+use std::collections::HashSet;
+
+mod error;
+pub use error::*;
+
+pub struct FeaturePruneOptions {
+    /// Only list orphaned files without deleting them
+    pub dry_run: bool,
+}
+
+pub fn prune(opts: FeaturePruneOptions) -> Result<()> {
+    let (ws, manifest) = load_all_packages(|pattern| phase_loading::load_workspace(pattern, true))?;
+    let (orphans, deleted): (Vec<_>, Vec<_>) = find_orphans(&ws, &manifest)
+        .into_iter()
+        .partition(|it| it.path.exists());
+    if !opts.dry_run {
+        // already deleted by hand
+        for orphan in &deleted {
+            manifest.forget(&orphan.path);
+        }
+        manifest.save()?;
+    }
+    if orphans.is_empty() {
+        if !is_quiet() {
+            println!("No orphaned files");
+        }
+        return Ok(());
+    }
+
+    let audit = match opts.dry_run {
+        true => AuditLog::default(),
+        false => AuditLog::open(
+            &ws.context.out_dir.join(AuditLog::FILE_NAME),
+            &ws.context.workspace_dir,
+        ),
+    };
+    let mut pruned = 0;
+    for orphan in &orphans {
+        let path = orphan
+            .path
+            .strip_prefix(&ws.context.workspace_dir)
+            .unwrap_or(&orphan.path);
+        let reason = match orphan.reason {
+            OrphanReason::LabelRemoved => "resource removed",
+            OrphanReason::NoLongerProduced => "not produced anymore",
+        };
+        println!(
+            "  {}  {} ({reason})",
+            path.display().to_string().bold(),
+            orphan.label.as_str().dark_grey(),
+        );
+        if opts.dry_run {
+            continue;
+        }
+        match std::fs::remove_file(&orphan.path) {
+            Ok(_) => {
+                audit.record(AuditAction::Delete, &orphan.path, &orphan.label);
+                manifest.forget(&orphan.path);
+                pruned += 1;
+            }
+            Err(e) => warn!(target: "Prune", "unable to delete `{}`: {e}", path.display()),
+        }
+    }
+
+    let n = orphans.len();
+    let s = if n == 1 { "" } else { "s" };
+    if opts.dry_run {
+        if !is_quiet() {
+            println!("\n{n} orphaned file{s}, run `figx prune` without `--dry-run` to delete them");
+        }
+    } else {
+        manifest.save()?;
+        if !is_quiet() {
+            println!("\nDeleted {pruned} of {n} orphaned file{s}");
+        }
+    }
+    Ok(())
+}
+
+/// Every label of the workspace is needed to tell which files are orphaned, including
+/// the labels of the external packages whose files are in the manifest
+fn load_all_packages(
+    load: impl Fn(LabelPattern) -> phase_loading::Result<Workspace>,
+) -> Result<(Workspace, ImportManifest)> {
+    let ws = load(LabelPattern::try_from(vec!["//...".to_string()])?)?;
+    let manifest = ImportManifest::open(
+        &ws.context.out_dir.join(ImportManifest::FILE_NAME),
+        &ws.context.workspace_dir,
+    );
+    // externals removed from the workspace cannot be loaded, so their files are orphaned
+    let externals = manifest
+        .externals()
+        .into_iter()
+        .filter(|it| ws.externals.contains(it))
+        .collect::<Vec<_>>();
+    if externals.is_empty() {
+        return Ok((ws, manifest));
+    }
+    let patterns = std::iter::once("//...".to_string())
+        .chain(externals.iter().map(|it| format!("@{it}//...")))
+        .collect::<Vec<_>>();
+    let ws = load(LabelPattern::try_from(patterns)?)?;
+    Ok((ws, manifest))
+}
+
+fn find_orphans(ws: &Workspace, manifest: &ImportManifest) -> Vec<OrphanedFile> {
+    let labels = ws
+        .packages
+        .iter()
+        .flat_map(|pkg| &pkg.resources)
+        .map(|res| res.attrs.label.to_string())
+        .collect::<HashSet<_>>();
+    manifest.orphans(&labels)
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use phase_evaluation::MaterializedFile;
+    use std::path::{Path, PathBuf};
+
+    fn write(path: PathBuf, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn file(path: PathBuf) -> MaterializedFile {
+        MaterializedFile {
+            path,
+            bytes: 0,
+            written: true,
+            extra_paths: Vec::new(),
+        }
+    }
+
+    /// Workspace with `//icons:ic_star` and the external `@ds//icons:ic_moon`
+    fn workspace_with_external(dir: &Path) {
+        let remote = "[remotes.design]\nfile_key = \"FILE\"\ncontainer_node_ids = [\"0:1\"]\n";
+        write(
+            dir.join("app").join(".figtree.toml"),
+            &format!("{remote}[externals]\nds = {{ path = \"../ds\" }}\n"),
+        );
+        write(
+            dir.join("app").join("icons").join(".fig.toml"),
+            "[svg]\nic_star = \"Star\"\n",
+        );
+        write(dir.join("ds").join(".figtree.toml"), remote);
+        write(
+            dir.join("ds").join("icons").join(".fig.toml"),
+            "[svg]\nic_moon = \"Moon\"\n",
+        );
+    }
+
+    #[test]
+    fn find_orphans__files_of_external_resources__EXPECT__only_removed_resources_orphaned() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        workspace_with_external(dir.path());
+        let ws_dir = dir.path().join("app");
+        let manifest_path = ws_dir.join(".figx-out").join(ImportManifest::FILE_NAME);
+        let manifest = ImportManifest::start(&manifest_path, &ws_dir);
+        let labels = ["//icons:ic_star", "@ds//icons:ic_moon", "//icons:ic_sun"];
+        manifest.record(labels[0], &file(ws_dir.join("icons/ic_star.svg")));
+        manifest.record(labels[1], &file(dir.path().join("ds/icons/ic_moon.svg")));
+        manifest.record(labels[2], &file(ws_dir.join("icons/ic_sun.svg")));
+        manifest.finish(labels).unwrap();
+
+        // When
+        let (ws, manifest) =
+            load_all_packages(|pattern| phase_loading::load_workspace_in(&ws_dir, pattern, true))
+                .unwrap();
+        let orphans = find_orphans(&ws, &manifest);
+
+        // Then
+        let orphans = orphans
+            .iter()
+            .map(|it| it.label.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["//icons:ic_sun"], orphans);
+    }
+}
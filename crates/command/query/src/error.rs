@@ -4,6 +4,11 @@ pub enum Error {
     PatternError(lib_label::PatternError),
     WorkspaceError(phase_loading::Error),
     IO(std::io::Error),
+    Evaluation(phase_evaluation::Error),
+    /// Figma nodes of the targets are not in the cached indexes of their remotes
+    DigestUnknown(Vec<String>),
+    /// No resource of the workspace writes the file
+    FileNotGenerated(std::path::PathBuf),
 }
 
 impl From<lib_label::PatternError> for Error {
@@ -18,6 +23,12 @@ impl From<phase_loading::Error> for Error {
     }
 }
 
+impl From<phase_evaluation::Error> for Error {
+    fn from(value: phase_evaluation::Error) -> Self {
+        Self::Evaluation(value)
+    }
+}
+
 impl From<std::io::Error> for Error {
     fn from(value: std::io::Error) -> Self {
         Self::IO(value)
@@ -1,12 +1,16 @@
 use crossterm::style::Stylize;
-use lib_label::LabelPattern;
-use phase_loading::{Profile, Workspace};
+use lib_label::{LabelPattern, split_tag_patterns};
+use phase_evaluation::TargetDigest;
+use phase_loading::{ResourceFilter, Workspace};
 
 mod error;
+mod rquery;
 pub use error::*;
+pub use rquery::*;
 
 pub struct FeatureQueryOptions {
     pub pattern: Vec<String>,
+    pub filter: ResourceFilter,
     pub output: QueryOutputType,
 }
 
@@ -15,17 +19,28 @@ pub enum QueryOutputType {
     Profile,
     Package,
     Tree,
+    Count,
+    Files,
+    Hash,
 }
 
 pub fn query(opts: FeatureQueryOptions) -> Result<()> {
-    let pattern = LabelPattern::try_from(opts.pattern)?;
-    let ws = phase_loading::load_workspace(pattern, true)?;
+    let (pattern, tags) = split_tag_patterns(opts.pattern)?;
+    let pattern = LabelPattern::try_from(pattern)?;
+    let mut ws = phase_loading::load_workspace(pattern, true)?;
+    ws.retain_resources(&ResourceFilter {
+        tags,
+        ..opts.filter
+    })?;
     use QueryOutputType::*;
     match &opts.output {
         Label => print_labels(ws)?,
         Profile => print_profiles(ws)?,
         Package => print_packages(ws)?,
         Tree => print_trees(ws)?,
+        Count => print_count(ws)?,
+        Files => print_files(ws)?,
+        Hash => print_hashes(ws)?,
     }
     Ok(())
 }
@@ -44,20 +59,48 @@ fn print_profiles(ws: Workspace) -> Result<()> {
         .flat_map(|it| &it.resources)
         .for_each(|res| {
             let label = &res.attrs.label;
-            let profile = match res.profile.as_ref() {
-                Profile::Png(_) => "png",
-                Profile::Svg(_) => "svg",
-                Profile::Pdf(_) => "pdf",
-                Profile::Webp(_) => "webp",
-                Profile::Compose(_) => "compose",
-                Profile::AndroidWebp(_) => "android-webp",
-                Profile::AndroidDrawable(_) => "android-drawable",
-            };
+            let profile = res.profile.kind().as_str();
             println!("{} {label}", profile.bold())
         });
     Ok(())
 }
 
+fn print_count(ws: Workspace) -> Result<()> {
+    let count = ws
+        .packages
+        .iter()
+        .map(|it| it.resources.len())
+        .sum::<usize>();
+    println!("{count}");
+    Ok(())
+}
+
+fn print_files(ws: Workspace) -> Result<()> {
+    for res in ws.packages.iter().flat_map(|it| &it.resources) {
+        for path in res.expected_outputs() {
+            println!("{}", std::path::absolute(&path)?.display());
+        }
+    }
+    Ok(())
+}
+
+fn print_hashes(ws: Workspace) -> Result<()> {
+    let digests = phase_evaluation::get_target_digests(&ws)?;
+    let unknown = digests
+        .iter()
+        .filter(|it| it.digest.is_none())
+        .map(|it| it.target.clone())
+        .collect::<Vec<_>>();
+    // a partial list would look like a complete one to the build systems relying on it
+    if !unknown.is_empty() {
+        return Err(Error::DigestUnknown(unknown));
+    }
+    for TargetDigest { target, digest } in digests {
+        println!("{:016x} {target}", digest.unwrap_or_default());
+    }
+    Ok(())
+}
+
 fn print_packages(ws: Workspace) -> Result<()> {
     for file in &ws.context.fig_files {
         println!("{}", file.package)
@@ -75,15 +118,7 @@ fn print_trees(ws: Workspace) -> Result<()> {
             } else {
                 "├── ".dark_grey()
             };
-            let profile = match res.profile.as_ref() {
-                Profile::Png(_) => "png",
-                Profile::Svg(_) => "svg",
-                Profile::Pdf(_) => "pdf",
-                Profile::Webp(_) => "webp",
-                Profile::Compose(_) => "compose",
-                Profile::AndroidWebp(_) => "android-webp",
-                Profile::AndroidDrawable(_) => "android-drawable",
-            };
+            let profile = res.profile.kind().as_str();
             println!("{tab}{} {}", profile.bold(), res.attrs.label.name);
         }
         println!()
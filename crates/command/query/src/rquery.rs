@@ -0,0 +1,168 @@
+use crate::{Error, Result};
+use crossterm::style::Stylize;
+use lib_label::LabelPattern;
+use phase_loading::{Resource, Workspace};
+use std::{
+    path::{Component, Path, PathBuf},
+    str::FromStr,
+};
+
+pub struct FeatureRqueryOptions {
+    /// Files of the workspace, relative to the current directory
+    pub paths: Vec<PathBuf>,
+}
+
+/// Finds the resources which write the given files. The files don't have to exist,
+/// the output paths are evaluated from the profiles of all resources in the workspace.
+pub fn rquery(opts: FeatureRqueryOptions) -> Result<()> {
+    let pattern = LabelPattern::from_str("//...")?;
+    let ws = phase_loading::load_workspace(pattern, true)?;
+    for (idx, res) in find_writers(&ws, &opts.paths)?.into_iter().enumerate() {
+        if idx > 0 {
+            println!();
+        }
+        print_resource(res);
+    }
+    Ok(())
+}
+
+/// Resources which write the given files, in the order of the files
+fn find_writers<'a>(ws: &'a Workspace, paths: &[PathBuf]) -> Result<Vec<&'a Resource>> {
+    let resources = ws
+        .packages
+        .iter()
+        .flat_map(|pkg| &pkg.resources)
+        .collect::<Vec<_>>();
+    let mut outputs = Vec::new();
+    for res in &resources {
+        for path in res.expected_outputs() {
+            outputs.push((normalize(&std::path::absolute(path)?), *res));
+        }
+    }
+
+    let mut writers = Vec::with_capacity(paths.len());
+    for path in paths {
+        let wanted = normalize(&std::path::absolute(path)?);
+        let Some((_, res)) = outputs.iter().find(|(output, _)| output == &wanted) else {
+            return Err(Error::FileNotGenerated(path.to_owned()));
+        };
+        writers.push(*res);
+    }
+    Ok(writers)
+}
+
+fn print_resource(res: &Resource) {
+    println!("{}", res.attrs.label.to_string().bold());
+    println!("  {} {}", "profile".dark_grey(), res.profile.kind());
+    println!("  {}  {}", "remote".dark_grey(), res.attrs.remote.id);
+    println!("  {}    {}", "node".dark_grey(), res.attrs.node_name);
+}
+
+/// Resolves `.` and `..` without touching the file system, the file may not exist yet
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    static REMOTE: &str = "[remotes.design]\nfile_key = \"FILE\"\ncontainer_node_ids = [\"0:1\"]\n";
+
+    fn write(path: PathBuf, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn load(dir: &Path) -> Workspace {
+        let pattern = LabelPattern::try_from(vec!["//...".to_string()]).unwrap();
+        phase_loading::load_workspace_in(dir, pattern, true).unwrap()
+    }
+
+    fn workspace(dir: &Path) -> Workspace {
+        write(dir.join(".figtree.toml"), REMOTE);
+        write(
+            dir.join("icons").join(".fig.toml"),
+            "[android-drawable]\nic_star = \"Star\"\n",
+        );
+        write(
+            dir.join("images").join(".fig.toml"),
+            "[android-webp]\nimg_hero = { name = \"Hero\", densities = [\"xhdpi\"], night = \"{base} / Dark\" }\n",
+        );
+        load(dir)
+    }
+
+    fn labels(writers: &[&Resource]) -> Vec<String> {
+        writers
+            .iter()
+            .map(|res| res.attrs.label.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn find_writers__main_output__EXPECT__resource() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let ws = workspace(dir.path());
+        let path = dir.path().join("icons/src/main/res/drawable/ic_star.xml");
+
+        // When
+        let writers = find_writers(&ws, &[path]).unwrap();
+
+        // Then
+        assert_eq!(vec!["//icons:ic_star"], labels(&writers));
+    }
+
+    #[test]
+    fn find_writers__night_density_output__EXPECT__resource() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let ws = workspace(dir.path());
+        let paths = [
+            dir.path()
+                .join("images/src/main/res/drawable-night-xhdpi/img_hero.webp"),
+            // paths are compared after `.` and `..` are resolved
+            dir.path()
+                .join("images/./src/../src/main/res/drawable-xhdpi/img_hero.webp"),
+        ];
+
+        // When
+        let writers = find_writers(&ws, &paths).unwrap();
+
+        // Then
+        assert_eq!(
+            vec!["//images:img_hero", "//images:img_hero"],
+            labels(&writers)
+        );
+    }
+
+    #[test]
+    fn find_writers__file_of_no_resource__EXPECT__error() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let ws = workspace(dir.path());
+        let paths = [
+            dir.path().join("icons/src/main/res/drawable/ic_star.xml"),
+            // no resource writes the density which is not listed in the profile
+            dir.path()
+                .join("images/src/main/res/drawable-mdpi/img_hero.webp"),
+        ];
+
+        // When
+        let result = find_writers(&ws, &paths);
+
+        // Then
+        assert!(matches!(result, Err(Error::FileNotGenerated(path)) if path == paths[1]));
+    }
+}
@@ -36,10 +36,25 @@ pub fn scan(opts: FeatureScanOptions) -> Result<()> {
         let mut writer = BufWriter::new(File::create(&output_file)?);
         writer.write(b"version = 1\n\n")?;
 
-        let api = FigmaApi::default();
+        let api = match &ws.network.ca_bundle {
+            Some(ca_bundle) => FigmaApi::default().with_ca_bundle(ca_bundle)?,
+            None => FigmaApi::default(),
+        };
+        let file_key = match &remote.branch {
+            Some(branch) => {
+                let branches = api.get_file_branches(&remote.access_token, &remote.file_key)?;
+                let Some(key) = branches.branch_key(branch) else {
+                    return Err(Error::IndexingRemote(format!(
+                        "branch `{branch}` is not found in the file of remote `{name}`"
+                    )));
+                };
+                key.to_owned()
+            }
+            None => remote.file_key.clone(),
+        };
         let response = api.get_file_nodes_scan(
             &remote.access_token,
-            &remote.file_key,
+            &file_key,
             GetFileNodesScanQueryParameters {
                 ids: Some(&remote.container_node_ids.to_string_id_list()),
                 ..Default::default()
@@ -0,0 +1,42 @@
+use std::fmt::{Debug, Display};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    ServerCreation(String),
+    Workspace(phase_loading::Error),
+    Evaluation(phase_evaluation::Error),
+    IO(std::io::Error),
+}
+
+impl Error {
+    pub fn server_creation(err: impl Display) -> Self {
+        Self::ServerCreation(err.to_string())
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&self, f)
+    }
+}
+impl std::error::Error for Error {}
+
+impl From<phase_loading::Error> for Error {
+    fn from(value: phase_loading::Error) -> Self {
+        Self::Workspace(value)
+    }
+}
+
+impl From<phase_evaluation::Error> for Error {
+    fn from(value: phase_evaluation::Error) -> Self {
+        Self::Evaluation(value)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Self::IO(value)
+    }
+}
@@ -0,0 +1,409 @@
+use crossbeam_channel::{Receiver, Sender, unbounded};
+use lib_dashboard::lifecycle;
+use lib_figma_fluent::FigmaApi;
+use lib_label::LabelPattern;
+use lib_metrics::Metrics;
+use log::{debug, error, warn};
+use phase_evaluation::{AuditLog, CancellationToken, EvalArgs, ImportManifest};
+use phase_loading::{RemoteId, RemoteSource, ResourceFilter};
+use serde::Deserialize;
+use std::{collections::HashMap, io::Read, str::FromStr, sync::Arc, time::Duration};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+mod error;
+pub use error::*;
+
+pub struct FeatureServeOptions {
+    /// Address of the HTTP endpoint, e.g. `0.0.0.0:8080`
+    pub listen: String,
+    pub concurrency: usize,
+    pub network_concurrency: usize,
+    pub cancellation: CancellationToken,
+}
+
+/// Passcode of the Figma webhook, requests with another passcode are rejected if it is set
+const PASSCODE_ENV: &str = "FIGX_WEBHOOK_PASSCODE";
+/// Header with the passcode for manual imports, webhooks send it in the payload
+const PASSCODE_HEADER: &str = "X-Figx-Passcode";
+/// How often the server checks whether it is interrupted
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// Webhook payloads are tiny, anything bigger is not from Figma
+const MAX_BODY_SIZE: u64 = 64 * 1024;
+
+/// Payload of the Figma webhooks, only the fields needed to find the remotes
+#[derive(Deserialize)]
+struct WebhookPayloadDto {
+    event_type: String,
+    #[serde(default)]
+    file_key: String,
+    #[serde(default)]
+    passcode: String,
+}
+
+struct ServeContext {
+    /// File key => ids of the remotes importing from this file
+    file_remotes: HashMap<String, Vec<RemoteId>>,
+    passcode: Option<String>,
+    imports: Sender<RemoteId>,
+    metrics: Metrics,
+}
+
+pub fn serve(opts: FeatureServeOptions) -> Result<()> {
+    // remotes are read once, restart the server after changing them
+    let mut ws = phase_loading::load_workspace(all_packages(), false)?;
+    // webhooks of a branch come with the key of the branch instead of the main file
+    let api = match &ws.network.ca_bundle {
+        Some(ca_bundle) => FigmaApi::default()
+            .with_ca_bundle(ca_bundle)
+            .map_err(phase_evaluation::Error::from)?,
+        None => FigmaApi::default(),
+    };
+    phase_evaluation::figma::resolve_branches(&mut ws, &api)?;
+    let file_remotes = file_remotes(&ws.remotes);
+    drop(ws);
+
+    let server = Server::http(&opts.listen).map_err(Error::server_creation)?;
+    let passcode = std::env::var(PASSCODE_ENV).ok().filter(|it| !it.is_empty());
+    if passcode.is_none() {
+        warn!(target: "Serve", "{PASSCODE_ENV} is not set, anyone who can reach the server is able to trigger imports");
+    }
+    let metrics = Metrics::default();
+    let (imports, pending) = unbounded();
+    let ctx = ServeContext {
+        file_remotes,
+        passcode,
+        imports,
+        metrics: metrics.clone(),
+    };
+    lifecycle!(target: "@Listening", "on http://{}", opts.listen);
+
+    std::thread::scope(|s| {
+        let worker = s.spawn(|| run_imports(pending, &opts, &metrics));
+        let result = accept_requests(&server, &ctx, &opts.cancellation);
+        // the worker finishes the pending imports once there are no more senders
+        drop(ctx);
+        let _ = worker.join();
+        result
+    })
+}
+
+fn all_packages() -> LabelPattern {
+    LabelPattern::from_str("//...").expect("always valid pattern")
+}
+
+/// File key => ids of the remotes importing from this file
+fn file_remotes(remotes: &[Arc<RemoteSource>]) -> HashMap<String, Vec<RemoteId>> {
+    let mut file_remotes = HashMap::<String, Vec<RemoteId>>::new();
+    for remote in remotes {
+        file_remotes
+            .entry(remote.file_key.clone())
+            .or_default()
+            .push(remote.id.clone());
+    }
+    file_remotes
+}
+
+fn accept_requests(
+    server: &Server,
+    ctx: &ServeContext,
+    cancellation: &CancellationToken,
+) -> Result<()> {
+    let _watch = cancellation.watch();
+    while !cancellation.is_cancelled() {
+        let Some(request) = server.recv_timeout(POLL_INTERVAL)? else {
+            continue;
+        };
+        ctx.metrics.counter("figx_serve_requests").increment();
+        // a client going away is not a reason to stop the server
+        if let Err(e) = handle_request(request, ctx) {
+            warn!(target: "Serve", "unable to respond: {e}");
+        }
+    }
+    Ok(())
+}
+
+fn handle_request(mut request: Request, ctx: &ServeContext) -> std::io::Result<()> {
+    let method = request.method().clone();
+    let url = request.url().to_owned();
+    debug!(target: "Serve", "{method} {url}");
+    match (&method, url.as_str()) {
+        (Method::Get, "/metrics") => {
+            let body = ctx.metrics.to_prometheus(Some(&[("command", "serve")]));
+            let content_type = Header::from_bytes(b"Content-Type", b"text/plain; version=0.0.4")
+                .expect("correct header");
+            request.respond(Response::from_string(body).with_header(content_type))
+        }
+        (Method::Post, "/webhook") => {
+            let mut body = String::new();
+            request
+                .as_reader()
+                .take(MAX_BODY_SIZE)
+                .read_to_string(&mut body)?;
+            let (status, message) = handle_webhook(&body, ctx);
+            request.respond(Response::from_string(message).with_status_code(status))
+        }
+        (Method::Post, "/import") => {
+            let (status, message) = handle_manual_import(&request, None, ctx);
+            request.respond(Response::from_string(message).with_status_code(status))
+        }
+        (Method::Post, path) => match path.strip_prefix("/import/") {
+            Some(remote_id) => {
+                let (status, message) = handle_manual_import(&request, Some(remote_id), ctx);
+                request.respond(Response::from_string(message).with_status_code(status))
+            }
+            None => request.respond(Response::empty(404)),
+        },
+        _ => request.respond(Response::empty(404)),
+    }
+}
+
+/// Figma expects `200` for every accepted event, other statuses make it retry the delivery
+fn handle_webhook(body: &str, ctx: &ServeContext) -> (u16, String) {
+    let payload = match serde_json::from_str::<WebhookPayloadDto>(body) {
+        Ok(payload) => payload,
+        Err(e) => return (400, format!("malformed webhook payload: {e}")),
+    };
+    if !ctx.passcode_matches(&payload.passcode) {
+        return (403, "wrong passcode".to_owned());
+    }
+    match payload.event_type.as_str() {
+        "PING" => (200, "pong".to_owned()),
+        "FILE_UPDATE" => match ctx.file_remotes.get(&payload.file_key) {
+            Some(remotes) => {
+                for remote in remotes {
+                    ctx.enqueue(remote);
+                }
+                (200, format!("importing {}", remotes.join(", ")))
+            }
+            None => (
+                200,
+                format!("no remotes import file `{}`", payload.file_key),
+            ),
+        },
+        event_type => (200, format!("event `{event_type}` is ignored")),
+    }
+}
+
+fn handle_manual_import(
+    request: &Request,
+    remote_id: Option<&str>,
+    ctx: &ServeContext,
+) -> (u16, String) {
+    let passcode = request
+        .headers()
+        .iter()
+        .find(|it| it.field.equiv(PASSCODE_HEADER))
+        .map(|it| it.value.as_str())
+        .unwrap_or_default();
+    if !ctx.passcode_matches(passcode) {
+        return (403, "wrong passcode".to_owned());
+    }
+    let mut remotes = ctx.file_remotes.values().flatten();
+    match remote_id {
+        Some(id) => match remotes.find(|it| *it == id) {
+            Some(remote) => {
+                ctx.enqueue(remote);
+                (202, format!("importing {remote}"))
+            }
+            None => (
+                404,
+                format!("remote `{id}` is not declared in the workspace"),
+            ),
+        },
+        None => {
+            remotes.for_each(|it| ctx.enqueue(it));
+            (202, "importing all remotes".to_owned())
+        }
+    }
+}
+
+impl ServeContext {
+    fn passcode_matches(&self, passcode: &str) -> bool {
+        self.passcode
+            .as_deref()
+            .is_none_or(|it| constant_time_eq(it.as_bytes(), passcode.as_bytes()))
+    }
+
+    fn enqueue(&self, remote: &RemoteId) {
+        // the worker lives as long as the server accepts requests
+        let _ = self.imports.send(remote.clone());
+    }
+}
+
+/// Takes the same time wherever the first difference is, so that the passcode
+/// cannot be guessed byte by byte from the response times
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Imports the requested remotes one at a time, in the order of the requests
+fn run_imports(pending: Receiver<RemoteId>, opts: &FeatureServeOptions, metrics: &Metrics) {
+    while let Ok(remote) = pending.recv() {
+        // designers save often, so updates of the same file come in bursts
+        let mut remotes = vec![remote];
+        for remote in pending.try_iter() {
+            if !remotes.contains(&remote) {
+                remotes.push(remote);
+            }
+        }
+        if opts.cancellation.is_cancelled() {
+            break;
+        }
+        let remotes_list = remotes.join(", ");
+        lifecycle!(target: "@Importing", "{remotes_list}");
+        match import_remotes(remotes, opts, metrics) {
+            Ok(()) => {
+                metrics.counter("figx_serve_imports").increment();
+                lifecycle!(target: "@Imported", "{remotes_list}");
+            }
+            Err(e) => {
+                metrics.counter("figx_serve_failed_imports").increment();
+                error!(target: "Serve", "import of {remotes_list} failed: {e}");
+            }
+        }
+    }
+}
+
+fn import_remotes(
+    remotes: Vec<RemoteId>,
+    opts: &FeatureServeOptions,
+    metrics: &Metrics,
+) -> Result<()> {
+    // fig-files are read again, so that new resources are imported without a restart
+    let mut ws = phase_loading::load_workspace(all_packages(), false)?;
+    ws.retain_resources(&ResourceFilter {
+        remotes,
+        ..Default::default()
+    })?;
+    let out_dir = ws.context.out_dir.clone();
+    phase_evaluation::evaluate(
+        ws,
+        EvalArgs {
+            // the file is updated, so its index is stale, but unchanged targets stay cached
+            refetch: true,
+            concurrency: opts.concurrency,
+            network_concurrency: opts.network_concurrency,
+            cancellation: opts.cancellation.clone(),
+            metrics: metrics.clone(),
+            report_path: Some(out_dir.join("build-report.json")),
+            manifest_path: Some(out_dir.join(ImportManifest::FILE_NAME)),
+            audit_path: Some(out_dir.join(AuditLog::FILE_NAME)),
+            ..Default::default()
+        },
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use phase_loading::{NodeIdList, RetryPolicy};
+
+    fn remote(id: &str, file_key: &str) -> Arc<RemoteSource> {
+        Arc::new(RemoteSource {
+            id: id.to_owned(),
+            file_key: file_key.to_owned(),
+            branch: None,
+            container_node_ids: NodeIdList::Plain(vec!["0:1".to_owned()]),
+            access_token: String::new(),
+            shallow_depth: None,
+            export_batch_size: RemoteSource::DEFAULT_EXPORT_BATCH_SIZE,
+            retry: RetryPolicy::default(),
+        })
+    }
+
+    fn context(passcode: Option<&str>) -> (ServeContext, Receiver<RemoteId>) {
+        let (imports, pending) = unbounded();
+        let ctx = ServeContext {
+            file_remotes: file_remotes(&[
+                remote("icons", "MAIN"),
+                remote("illustrations", "MAIN"),
+                // key of the branch, as resolved on start
+                remote("icons_redesign", "BRANCH"),
+            ]),
+            passcode: passcode.map(str::to_owned),
+            imports,
+            metrics: Metrics::default(),
+        };
+        (ctx, pending)
+    }
+
+    #[test]
+    fn handle_webhook__file_update__EXPECT__remotes_of_file_enqueued() {
+        // Given
+        let (ctx, pending) = context(Some("secret"));
+        let body = r#"{"event_type":"FILE_UPDATE","file_key":"MAIN","passcode":"secret"}"#;
+
+        // When
+        let (status, _) = handle_webhook(body, &ctx);
+
+        // Then
+        assert_eq!(200, status);
+        assert_eq!(
+            vec!["icons".to_owned(), "illustrations".to_owned()],
+            pending.try_iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn handle_webhook__update_of_branch__EXPECT__branch_remote_enqueued() {
+        // Given
+        let (ctx, pending) = context(None);
+        let body = r#"{"event_type":"FILE_UPDATE","file_key":"BRANCH"}"#;
+
+        // When
+        let (status, _) = handle_webhook(body, &ctx);
+
+        // Then
+        assert_eq!(200, status);
+        assert_eq!(
+            vec!["icons_redesign".to_owned()],
+            pending.try_iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn handle_webhook__wrong_passcode__EXPECT__rejected_without_import() {
+        // Given
+        let (ctx, pending) = context(Some("secret"));
+        let body = r#"{"event_type":"FILE_UPDATE","file_key":"MAIN","passcode":"secreT"}"#;
+
+        // When
+        let (status, _) = handle_webhook(body, &ctx);
+
+        // Then
+        assert_eq!(403, status);
+        assert_eq!(0, pending.try_iter().count());
+    }
+
+    #[test]
+    fn handle_webhook__other_events_and_files__EXPECT__acknowledged_without_import() {
+        // Given
+        let (ctx, pending) = context(None);
+
+        // When
+        let ping = handle_webhook(r#"{"event_type":"PING"}"#, &ctx);
+        let comment = handle_webhook(r#"{"event_type":"FILE_COMMENT","file_key":"MAIN"}"#, &ctx);
+        let unknown = handle_webhook(r#"{"event_type":"FILE_UPDATE","file_key":"OTHER"}"#, &ctx);
+        let malformed = handle_webhook("{", &ctx);
+
+        // Then
+        assert_eq!((200, "pong".to_owned()), ping);
+        assert_eq!(200, comment.0);
+        assert_eq!(200, unknown.0);
+        assert_eq!(400, malformed.0);
+        assert_eq!(0, pending.try_iter().count());
+    }
+
+    #[test]
+    fn constant_time_eq__various_inputs__EXPECT__equal_only_if_same_bytes() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret!"));
+        assert!(constant_time_eq(b"", b""));
+    }
+}
@@ -0,0 +1,176 @@
+use crossterm::style::Stylize;
+use lib_dashboard::is_quiet;
+use lib_label::LabelPattern;
+use phase_evaluation::{ProvenanceCheck, check_provenance, is_stampable};
+use phase_loading::Workspace;
+use std::path::Path;
+
+mod error;
+pub use error::*;
+
+pub struct FeatureVerifyProvenanceOptions {
+    /// Every resource of the workspace is verified if empty
+    pub pattern: Vec<String>,
+    /// Fail on files without the provenance record as well
+    pub strict: bool,
+}
+
+pub fn verify_provenance(opts: FeatureVerifyProvenanceOptions) -> Result<()> {
+    let pattern = match opts.pattern.is_empty() {
+        true => vec!["//...".to_string()],
+        false => opts.pattern,
+    };
+    let pattern = LabelPattern::try_from(pattern)?;
+    let ws = phase_loading::load_workspace(pattern, true)?;
+    verify_outputs(&ws, opts.strict)
+}
+
+/// Checks every file written by the loaded resources, auxiliary ones included
+fn verify_outputs(ws: &Workspace, strict: bool) -> Result<()> {
+    let files = ws
+        .packages
+        .iter()
+        .flat_map(|pkg| &pkg.resources)
+        .flat_map(|res| res.expected_outputs())
+        .filter(|path| path.exists() && is_stampable(&extension(path)));
+
+    let mut intact = 0;
+    let mut edited = Vec::new();
+    let mut unstamped = Vec::new();
+    for path in files {
+        let bytes = std::fs::read(&path)?;
+        let display_path = path
+            .strip_prefix(&ws.context.workspace_dir)
+            .unwrap_or(&path)
+            .display()
+            .to_string();
+        match check_provenance(&bytes, &extension(&path)) {
+            ProvenanceCheck::Intact(_) => intact += 1,
+            ProvenanceCheck::Edited(record) => {
+                println!(
+                    "  {}  {} {}",
+                    "edited".red().bold(),
+                    display_path.bold(),
+                    format!("(generated from {})", record.label).dark_grey(),
+                );
+                edited.push(path);
+            }
+            ProvenanceCheck::Unstamped => {
+                if strict {
+                    println!("  {}  {}", "unstamped".yellow().bold(), display_path.bold());
+                }
+                unstamped.push(path);
+            }
+        }
+    }
+
+    if !is_quiet() {
+        println!(
+            "{intact} intact, {} edited, {} without provenance",
+            edited.len(),
+            unstamped.len(),
+        );
+    }
+    if !unstamped.is_empty() && !strict && !is_quiet() {
+        println!(
+            "{}",
+            "enable `provenance = true` in the `[output]` of `.figtree.toml` and run the import to stamp them"
+                .dark_grey(),
+        );
+    }
+    if strict {
+        edited.extend(unstamped);
+    }
+    match edited.is_empty() {
+        true => Ok(()),
+        false => Err(Error::FilesEdited(edited)),
+    }
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .map(|it| it.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use std::path::PathBuf;
+
+    static REMOTE: &str = "[remotes.design]\nfile_key = \"FILE\"\ncontainer_node_ids = [\"0:1\"]\n";
+    static MONOCHROME_PROFILE: &str = "[profiles.android-drawable]\nmonochrome = true\n";
+
+    fn write(path: PathBuf, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn load(dir: &Path) -> Workspace {
+        let pattern = LabelPattern::try_from(vec!["//...".to_string()]).unwrap();
+        phase_loading::load_workspace_in(dir, pattern, true).unwrap()
+    }
+
+    /// Drawable whose provenance record doesn't match its content
+    fn edited_drawable() -> String {
+        let record = "figx-provenance: label=//icons:ic_star figx=0.0.0 digest=0000000000000000";
+        format!("<!-- {record} -->\n<vector/>\n")
+    }
+
+    #[test]
+    fn verify_outputs__edited_monochrome_drawable__EXPECT__error() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path().join(".figtree.toml"),
+            &format!("{REMOTE}{MONOCHROME_PROFILE}"),
+        );
+        write(
+            dir.path().join("icons").join(".fig.toml"),
+            "[android-drawable]\nic_star = \"Star\"\n",
+        );
+        let drawable_dir = dir.path().join("icons/src/main/res/drawable");
+        write(drawable_dir.join("ic_star.xml"), "<vector/>\n");
+        write(
+            drawable_dir.join("ic_star_monochrome.xml"),
+            &edited_drawable(),
+        );
+        let ws = load(dir.path());
+
+        // When
+        let result = verify_outputs(&ws, false);
+
+        // Then
+        let Err(Error::FilesEdited(files)) = result else {
+            panic!("edited file must be reported");
+        };
+        assert_eq!(1, files.len());
+        assert!(files[0].ends_with("drawable/ic_star_monochrome.xml"));
+    }
+
+    #[test]
+    fn verify_outputs__unstamped_file_with_strict__EXPECT__error() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path().join(".figtree.toml"), REMOTE);
+        write(
+            dir.path().join("icons").join(".fig.toml"),
+            "[svg]\nic_star = \"Star\"\n",
+        );
+        write(dir.path().join("icons/ic_star.svg"), "<svg/>\n");
+        let ws = load(dir.path());
+
+        // When
+        let lenient = verify_outputs(&ws, false);
+        let strict = verify_outputs(&ws, true);
+
+        // Then
+        assert!(lenient.is_ok());
+        let Err(Error::FilesEdited(files)) = strict else {
+            panic!("unstamped file must be reported in strict mode");
+        };
+        assert_eq!(1, files.len());
+        assert!(files[0].ends_with("icons/ic_star.svg"));
+    }
+}
@@ -0,0 +1,215 @@
+//! Library API of figx for build tools which embed the import instead of running the CLI,
+//! e.g. Gradle or Bazel plugins and other Rust binaries.
+//!
+//! ```no_run
+//! use figx_core::{ImportRequest, Workspace};
+//!
+//! let ws = Workspace::load("path/to/project", &["//icons/..."])?;
+//! let report = ws.import(ImportRequest::default())?;
+//! for target in report.failed() {
+//!     eprintln!("{}: {}", target.label, target.error.as_deref().unwrap_or_default());
+//! }
+//! # Ok::<(), figx_core::Error>(())
+//! ```
+
+use lib_label::{LabelPattern, split_tag_patterns};
+use phase_evaluation::{AuditLog, BuildReport, EvalArgs, ImportManifest};
+use phase_loading::ResourceFilter;
+use std::{path::Path, sync::Arc, time::Duration, time::Instant};
+
+mod error;
+pub use error::*;
+
+pub use lib_figma_fluent::FigmaApi;
+pub use phase_evaluation::{
+    CancellationToken, EvaluationMeta, ExecutionObserver, IndexingProgress, OutputReport,
+    TargetMeta, TargetReport, TargetStatus,
+};
+
+/// Workspace of figx with the resources to import, see [`Workspace::load`]
+pub struct Workspace {
+    inner: phase_loading::Workspace,
+}
+
+/// How to import the resources of the workspace, the defaults match `figx import`
+#[derive(Default)]
+pub struct ImportRequest {
+    /// Request the indexes of the remotes again instead of taking them from the cache
+    pub refetch: bool,
+    /// Import the rest of the targets after one of them fails
+    pub keep_going: bool,
+    /// Compare imported files with the workspace ones instead of writing them
+    pub check: bool,
+    /// Decode imported raster images and check their dimensions before writing them
+    pub validate_images: bool,
+    /// Number of targets transformed in parallel, `0` means the workspace or default one
+    pub concurrency: usize,
+    /// Number of downloads in flight, `0` means the workspace or default one
+    pub network_concurrency: usize,
+    pub cancellation: CancellationToken,
+    /// Gets notified about the progress, no progress is shown if not set
+    pub observer: Option<Arc<dyn ExecutionObserver>>,
+    /// Client of the Figma API, e.g. pointed at a mirror
+    pub figma_api: Option<FigmaApi>,
+}
+
+/// Outcome of the import of every requested target
+#[derive(Debug)]
+pub struct ImportReport {
+    pub targets: Vec<TargetReport>,
+    pub duration: Duration,
+}
+
+impl Workspace {
+    /// Loads the workspace `dir` belongs to, keeping only the resources which match
+    /// the `patterns`, e.g. `//icons/...` or `tag:critical`
+    pub fn load(dir: impl AsRef<Path>, patterns: &[&str]) -> Result<Self> {
+        let patterns = patterns.iter().map(|it| it.to_string()).collect();
+        let (patterns, tags) = split_tag_patterns(patterns)?;
+        let pattern = LabelPattern::try_from(patterns)?;
+        let mut inner = phase_loading::load_workspace_in(dir.as_ref(), pattern, false)?;
+        inner.retain_resources(&ResourceFilter {
+            tags,
+            ..Default::default()
+        })?;
+        Ok(Self { inner })
+    }
+
+    /// Labels of the loaded resources, e.g. `//icons:star`
+    pub fn labels(&self) -> Vec<String> {
+        self.inner
+            .packages
+            .iter()
+            .flat_map(|it| &it.resources)
+            .map(|it| it.attrs.label.to_string())
+            .collect()
+    }
+
+    /// Imports the loaded resources into the workspace.
+    ///
+    /// Failed targets are listed in the report, errors are returned only if the import
+    /// cannot go on, e.g. when a remote is unavailable.
+    pub fn import(self, request: ImportRequest) -> Result<ImportReport> {
+        let ws = self.inner;
+        let out_dir = ws.context.out_dir.clone();
+        let report = BuildReport::default();
+        let started = Instant::now();
+        let result = phase_evaluation::evaluate(
+            ws,
+            EvalArgs {
+                refetch: request.refetch,
+                keep_going: request.keep_going,
+                check: request.check,
+                validate_images: request.validate_images,
+                concurrency: request.concurrency,
+                network_concurrency: request.network_concurrency,
+                cancellation: request.cancellation,
+                figma_api: request.figma_api.unwrap_or_default(),
+                // progress is not shown unless the embedder asks for it
+                observer: Some(request.observer.unwrap_or_else(|| Arc::new(SilentObserver))),
+                build_report: Some(report.clone()),
+                report_path: Some(out_dir.join("build-report.json")),
+                manifest_path: Some(out_dir.join(ImportManifest::FILE_NAME)),
+                audit_path: Some(out_dir.join(AuditLog::FILE_NAME)),
+                ..Default::default()
+            },
+        );
+        match result {
+            Ok(()) | Err(phase_evaluation::Error::TargetsFailed(_)) => Ok(ImportReport {
+                targets: report.targets(),
+                duration: started.elapsed(),
+            }),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl ImportReport {
+    pub fn exported(&self) -> impl Iterator<Item = &TargetReport> {
+        self.with_status(TargetStatus::Exported)
+    }
+
+    pub fn cached(&self) -> impl Iterator<Item = &TargetReport> {
+        self.with_status(TargetStatus::Cached)
+    }
+
+    pub fn failed(&self) -> impl Iterator<Item = &TargetReport> {
+        self.with_status(TargetStatus::Failed)
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.failed().next().is_none()
+    }
+
+    fn with_status(&self, status: TargetStatus) -> impl Iterator<Item = &TargetReport> {
+        self.targets.iter().filter(move |it| it.status == status)
+    }
+}
+
+struct SilentObserver;
+
+impl ExecutionObserver for SilentObserver {}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use figma_fake_server::{FakeFigmaServer, FakeNode};
+
+    const STAR_SVG: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" width="24" height="24"/>"#;
+
+    fn star_workspace() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".figtree.toml"),
+            "[remotes.design]\nfile_key = \"FILE\"\ncontainer_node_ids = [\"0:1\"]\naccess_token = \"token\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(dir.path().join("icons")).unwrap();
+        std::fs::write(
+            dir.path().join("icons").join(".fig.toml"),
+            "[svg]\nic_star = \"Star\"\nic_moon = \"Moon\"\n",
+        )
+        .unwrap();
+        dir
+    }
+
+    #[test]
+    fn Workspace__load_with_pattern__EXPECT__matching_labels() {
+        // Given
+        let dir = star_workspace();
+
+        // When
+        let ws = Workspace::load(dir.path(), &["//icons:ic_star"]).unwrap();
+
+        // Then
+        assert_eq!(vec!["//icons:ic_star".to_string()], ws.labels());
+    }
+
+    #[test]
+    fn import__missing_node_with_keep_going__EXPECT__failure_in_report() {
+        // Given
+        let server = FakeFigmaServer::start().file(
+            "FILE",
+            [FakeNode::component("1:2", "Star").with_image("svg", STAR_SVG)],
+        );
+        let dir = star_workspace();
+        let ws = Workspace::load(dir.path(), &["//..."]).unwrap();
+
+        // When
+        let report = ws
+            .import(ImportRequest {
+                keep_going: true,
+                figma_api: Some(FigmaApi::default().with_base_url(server.url())),
+                ..Default::default()
+            })
+            .unwrap();
+
+        // Then
+        assert!(!report.is_success());
+        assert_eq!(1, report.exported().count());
+        let failed = report.failed().collect::<Vec<_>>();
+        assert_eq!("//icons:ic_moon", failed[0].label);
+        assert!(dir.path().join("icons").join("ic_star.svg").is_file());
+    }
+}
@@ -0,0 +1,296 @@
+//! Fake Figma REST API for integration tests.
+//!
+//! Serves canned documents and exported images over HTTP on a random local port,
+//! so the whole pipeline (load → fetch → export → transform → materialize) can be
+//! tested without network access. Point the client to [`FakeFigmaServer::url`] with
+//! `FigmaApi::with_base_url`.
+//!
+//! ```no_run
+//! # use figma_fake_server::{FakeFigmaServer, FakeNode};
+//! let server = FakeFigmaServer::start()
+//!     .file("FILE_KEY", [FakeNode::component("1:2", "Star").with_image("svg", "<svg/>")]);
+//! // ... run the import against `server.url()`
+//! assert_eq!(1, server.requests_to("/v1/images/FILE_KEY"));
+//! ```
+
+use serde_json::{Value, json};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    thread::JoinHandle,
+};
+use tiny_http::{Header, Request, Response, Server};
+
+pub struct FakeFigmaServer {
+    server: Arc<Server>,
+    url: String,
+    state: Arc<State>,
+    thread: Option<JoinHandle<()>>,
+}
+
+#[derive(Default)]
+struct State {
+    files: Mutex<HashMap<String, Vec<FakeNode>>>,
+    /// Path and query of every request received so far
+    requests: Mutex<Vec<String>>,
+}
+
+/// Node of the fake document with the images it is exported to
+#[derive(Clone)]
+pub struct FakeNode {
+    id: String,
+    name: String,
+    r#type: String,
+    raster_fills: bool,
+    /// Format => image
+    images: HashMap<String, Vec<u8>>,
+}
+
+impl FakeNode {
+    pub fn component(id: &str, name: &str) -> Self {
+        Self {
+            id: id.to_owned(),
+            name: name.to_owned(),
+            r#type: "COMPONENT".to_owned(),
+            raster_fills: false,
+            images: HashMap::new(),
+        }
+    }
+
+    /// Image returned when the node is exported to `format` (`svg`, `png`, `pdf`)
+    pub fn with_image(mut self, format: &str, bytes: impl Into<Vec<u8>>) -> Self {
+        self.images.insert(format.to_owned(), bytes.into());
+        self
+    }
+
+    /// Marks the node as one with embedded raster images
+    pub fn with_raster_fills(mut self) -> Self {
+        self.raster_fills = true;
+        self
+    }
+
+    fn to_json(&self) -> Value {
+        let fills = if self.raster_fills {
+            json!([{ "type": "IMAGE" }])
+        } else {
+            json!([{ "type": "SOLID" }])
+        };
+        json!({
+            "id": self.id,
+            "name": self.name,
+            "type": self.r#type,
+            "visible": true,
+            "fills": fills,
+        })
+    }
+}
+
+impl FakeFigmaServer {
+    /// Starts the server on a random local port, it is stopped when dropped
+    pub fn start() -> Self {
+        let server = Arc::new(Server::http("127.0.0.1:0").expect("free local port"));
+        let port = server.server_addr().to_ip().expect("tcp listener").port();
+        let url = format!("http://127.0.0.1:{port}");
+        let state = Arc::new(State::default());
+        let thread = {
+            let server = server.clone();
+            let state = state.clone();
+            let url = url.clone();
+            std::thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    handle(&state, &url, request);
+                }
+            })
+        };
+        Self {
+            server,
+            url,
+            state,
+            thread: Some(thread),
+        }
+    }
+
+    /// Adds the file with the nodes, all of them are children of every container node
+    pub fn file(self, file_key: &str, nodes: impl IntoIterator<Item = FakeNode>) -> Self {
+        self.state
+            .files
+            .lock()
+            .unwrap()
+            .insert(file_key.to_owned(), nodes.into_iter().collect());
+        self
+    }
+
+    /// Replaces the image of the node, e.g. to simulate a design change between runs
+    pub fn update_image(&self, file_key: &str, node_id: &str, format: &str, bytes: &[u8]) {
+        let mut files = self.state.files.lock().unwrap();
+        let node = files
+            .get_mut(file_key)
+            .and_then(|nodes| nodes.iter_mut().find(|it| it.id == node_id))
+            .expect("node exists");
+        node.images.insert(format.to_owned(), bytes.to_vec());
+    }
+
+    /// Base URL of the server, e.g. `http://127.0.0.1:34567`
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Path and query of every request received so far
+    pub fn requests(&self) -> Vec<String> {
+        self.state.requests.lock().unwrap().clone()
+    }
+
+    /// Number of requests received so far with the path starting with `prefix`
+    pub fn requests_to(&self, prefix: &str) -> usize {
+        self.requests()
+            .iter()
+            .filter(|it| it.starts_with(prefix))
+            .count()
+    }
+}
+
+impl Drop for FakeFigmaServer {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn handle(state: &State, base_url: &str, request: Request) {
+    let url = request.url().to_owned();
+    state.requests.lock().unwrap().push(url.clone());
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+    let query = parse_query(query);
+    let segments = path.trim_start_matches('/').split('/').collect::<Vec<_>>();
+    let files = state.files.lock().unwrap();
+    let response = match segments.as_slice() {
+        ["v1", "files", file_key, "nodes"] => match files.get(*file_key) {
+            Some(nodes) => json_response(file_nodes(nodes, query.get("ids"))),
+            None => not_found(),
+        },
+        ["v1", "files", file_key, "variables", "local"] if files.contains_key(*file_key) => {
+            json_response(json!({
+                "status": 200,
+                "error": false,
+                "meta": { "variables": {}, "variableCollections": {} },
+            }))
+        }
+        ["v1", "images", file_key] => match files.get(*file_key) {
+            Some(nodes) => json_response(images(base_url, file_key, nodes, &query)),
+            None => not_found(),
+        },
+        ["images", file_key, image] => {
+            let image = percent_decode(image);
+            let (node_id, format) = image.rsplit_once('.').unwrap_or((&image, ""));
+            files
+                .get(*file_key)
+                .and_then(|nodes| nodes.iter().find(|it| it.id == node_id))
+                .and_then(|node| node.images.get(format))
+                .map(|bytes| Response::from_data(bytes.clone()))
+                .unwrap_or_else(not_found)
+        }
+        _ => not_found(),
+    };
+    drop(files);
+    let _ = request.respond(response);
+}
+
+/// Response of `GET /v1/files/:key/nodes`, every requested container holds all the nodes
+fn file_nodes(nodes: &[FakeNode], ids: Option<&String>) -> Value {
+    let children = nodes.iter().map(FakeNode::to_json).collect::<Vec<_>>();
+    let containers = match ids {
+        Some(ids) => ids.split(',').map(str::to_owned).collect(),
+        None => vec!["0:1".to_owned()],
+    };
+    let nodes = containers
+        .into_iter()
+        .map(|id| {
+            let document = json!({
+                "id": id,
+                "name": "Page",
+                "type": "CANVAS",
+                "children": children,
+            });
+            (id, json!({ "document": document, "components": {} }))
+        })
+        .collect::<serde_json::Map<_, _>>();
+    json!({ "nodes": nodes })
+}
+
+/// Response of `GET /v1/images/:key`, only the nodes having the image of the format are listed
+fn images(
+    base_url: &str,
+    file_key: &str,
+    nodes: &[FakeNode],
+    query: &HashMap<String, String>,
+) -> Value {
+    let format = query.get("format").map(String::as_str).unwrap_or("png");
+    let ids = query.get("ids").map(String::as_str).unwrap_or_default();
+    let images = ids
+        .split(',')
+        .filter_map(|id| nodes.iter().find(|it| it.id == id))
+        .filter(|node| node.images.contains_key(format))
+        .map(|node| {
+            let url = format!("{base_url}/images/{file_key}/{}.{format}", node.id);
+            (node.id.clone(), Value::String(url))
+        })
+        .collect::<serde_json::Map<_, _>>();
+    json!({ "err": null, "images": images })
+}
+
+fn json_response(value: Value) -> Response<std::io::Cursor<Vec<u8>>> {
+    let header = Header::from_bytes(b"Content-Type", b"application/json").expect("valid header");
+    Response::from_data(serde_json::to_vec(&value).expect("valid json")).with_header(header)
+}
+
+fn not_found() -> Response<std::io::Cursor<Vec<u8>>> {
+    Response::from_data(Vec::new()).with_status_code(404)
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| (percent_decode(key), percent_decode(value)))
+        .collect()
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut output = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let decoded = match bytes[i] {
+            b'%' if i + 2 < bytes.len() => std::str::from_utf8(&bytes[i + 1..i + 3])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+            _ => None,
+        };
+        match decoded {
+            Some(byte) => {
+                output.push(byte);
+                i += 3;
+            }
+            None => {
+                output.push(if bytes[i] == b'+' { b' ' } else { bytes[i] });
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&output).into_owned()
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn percent_decode__encoded_node_ids__EXPECT__decoded() {
+        assert_eq!("1:2,1:3", percent_decode("1%3A2%2C1%3A3"));
+        assert_eq!("a b", percent_decode("a+b"));
+        assert_eq!("100%", percent_decode("100%"));
+    }
+}
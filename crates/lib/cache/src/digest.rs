@@ -0,0 +1,122 @@
+use std::hash::Hasher;
+use xxhash_rust::xxh64::Xxh64;
+
+/// Algorithm of all digests computed by figx: cache keys, file digests and node hashes.
+///
+/// The id of the algorithm is embedded into every [`CacheKey`](crate::CacheKey), so when
+/// [`DigestAlgorithm::CURRENT`] changes, entries written with the old algorithm become
+/// unreachable instead of colliding with the new ones, and are removed by `figx clean`.
+/// To switch the algorithm, add a new variant with a new id, never change an existing one.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DigestAlgorithm {
+    Xxh64,
+}
+
+impl DigestAlgorithm {
+    pub const CURRENT: Self = Self::Xxh64;
+
+    /// Stable id of the algorithm, ids of removed algorithms must not be reused
+    pub fn id(self) -> u8 {
+        match self {
+            Self::Xxh64 => 1,
+        }
+    }
+
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            1 => Some(Self::Xxh64),
+            _ => None,
+        }
+    }
+
+    pub fn hasher(self) -> Digester {
+        let state = match self {
+            Self::Xxh64 => State::Xxh64(Xxh64::new(0)),
+        };
+        Digester {
+            algorithm: self,
+            state,
+        }
+    }
+}
+
+/// Streaming digest of the [`DigestAlgorithm::CURRENT`] algorithm, use it instead of
+/// the hashers of particular algorithms
+#[derive(Clone)]
+pub struct Digester {
+    algorithm: DigestAlgorithm,
+    state: State,
+}
+
+#[derive(Clone)]
+enum State {
+    Xxh64(Xxh64),
+}
+
+impl Digester {
+    pub fn new() -> Self {
+        DigestAlgorithm::CURRENT.hasher()
+    }
+
+    pub fn algorithm(&self) -> DigestAlgorithm {
+        self.algorithm
+    }
+
+    /// 64-bit digest of the bytes written so far, wider digests are truncated
+    pub fn digest(&self) -> u64 {
+        match &self.state {
+            State::Xxh64(hasher) => hasher.digest(),
+        }
+    }
+}
+
+impl Default for Digester {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for Digester {
+    fn write(&mut self, bytes: &[u8]) {
+        match &mut self.state {
+            State::Xxh64(hasher) => hasher.write(bytes),
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.digest()
+    }
+}
+
+/// Digest of the bytes with the current algorithm
+pub fn digest(bytes: &[u8]) -> u64 {
+    let mut digester = Digester::new();
+    digester.write(bytes);
+    digester.digest()
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn DigestAlgorithm__id__EXPECT__roundtrip() {
+        let algorithm = DigestAlgorithm::CURRENT;
+        assert_eq!(Some(algorithm), DigestAlgorithm::from_id(algorithm.id()));
+        assert_eq!(None, DigestAlgorithm::from_id(0));
+    }
+
+    #[test]
+    fn Digester__streaming_and_one_shot__EXPECT__same_digest() {
+        // Given
+        let mut digester = Digester::new();
+
+        // When
+        digester.write(b"Hello ");
+        digester.write(b"world!");
+
+        // Then
+        assert_eq!(digest(b"Hello world!"), digester.digest());
+    }
+}
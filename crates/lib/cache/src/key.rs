@@ -2,7 +2,11 @@ use std::hash::Hasher;
 
 use bincode::{BorrowDecode, Decode, Encode};
 use bytes::Bytes;
-use xxhash_rust::xxh64::Xxh64;
+
+use crate::{DigestAlgorithm, Digester};
+
+/// Length of the key: tag, id of the digest algorithm and the digest itself
+pub(crate) const KEY_LEN: usize = 10;
 
 #[derive(Clone, Hash, Eq, PartialEq)]
 #[non_exhaustive]
@@ -44,7 +48,7 @@ impl<'de, Context> BorrowDecode<'de, Context> for CacheKey {
 impl CacheKey {
     pub fn builder() -> CacheKeyBuilder {
         CacheKeyBuilder {
-            hasher: Xxh64::new(42),
+            hasher: Digester::new(),
             tag: 0,
         }
     }
@@ -53,6 +57,19 @@ impl CacheKey {
         let buf: &[u8] = self.as_ref();
         *buf.first().expect("cache key has never empty buffer")
     }
+
+    /// Algorithm of the key digest, `None` for keys written by older versions
+    pub fn algorithm(&self) -> Option<DigestAlgorithm> {
+        algorithm_of(self.as_ref())
+    }
+}
+
+/// Algorithm of the raw key digest, `None` for keys written by older versions
+pub(crate) fn algorithm_of(key: &[u8]) -> Option<DigestAlgorithm> {
+    match key {
+        [_, id, ..] if key.len() == KEY_LEN => DigestAlgorithm::from_id(*id),
+        _ => None,
+    }
 }
 
 impl AsRef<[u8]> for CacheKey {
@@ -71,7 +88,7 @@ impl std::fmt::Debug for CacheKey {
 }
 
 pub struct CacheKeyBuilder {
-    hasher: xxhash_rust::xxh64::Xxh64,
+    hasher: Digester,
     tag: u8,
 }
 
@@ -115,9 +132,10 @@ impl CacheKeyBuilder {
     }
 
     pub fn build(self) -> CacheKey {
-        let mut buf = [0u8; 9];
+        let mut buf = [0u8; KEY_LEN];
         buf[0] = self.tag;
-        buf[1..].copy_from_slice(&self.hasher.digest().to_be_bytes());
+        buf[1] = self.hasher.algorithm().id();
+        buf[2..].copy_from_slice(&self.hasher.digest().to_be_bytes());
         CacheKey {
             hash: Bytes::from_owner(buf),
         }
@@ -127,7 +145,7 @@ impl CacheKeyBuilder {
 #[cfg(test)]
 #[allow(non_snake_case)]
 mod test {
-    use crate::CacheKey;
+    use crate::{CacheKey, DigestAlgorithm};
 
     #[test]
     fn same_operators_order__LEADS_TO__same_keys() {
@@ -173,6 +191,18 @@ mod test {
         assert_eq!(42, key.hash[0]);
     }
 
+    #[test]
+    fn algorithm_is_always_inside_key() {
+        let key = CacheKey::builder().set_tag(42).write_str("hello").build();
+        assert_eq!(Some(DigestAlgorithm::CURRENT), key.algorithm());
+    }
+
+    #[test]
+    fn unversioned_key__EXPECT__no_algorithm() {
+        let legacy = [42u8, 1, 2, 3, 4, 5, 6, 7, 8];
+        assert_eq!(None, super::algorithm_of(&legacy));
+    }
+
     #[test]
     fn test_ser_de() {
         let source_key = CacheKey::builder()
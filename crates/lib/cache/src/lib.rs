@@ -1,11 +1,13 @@
 use bincode::{Decode, Encode};
 use bytes::Bytes;
+pub use digest::*;
 pub use error::*;
 pub use key::*;
 use log::debug;
 use std::{path::Path, sync::Arc};
 use surrealkv::{IsolationLevel, Options, Store};
 
+mod digest;
 mod error;
 mod key;
 
@@ -138,15 +140,17 @@ impl Cache {
         }
     }
 
+    /// Keeps only the entries with the tags matching the `predicate`. Entries with
+    /// keys of another digest algorithm are always removed, they are unreachable.
     pub fn retain(&self, predicate: impl Fn(u8) -> bool) -> Result<()> {
         let txn = self.store.begin()?;
-        let start: &[u8] = &[0x00; 9];
-        let end: &[u8] = &[0xff; 9];
+        let start: &[u8] = &[0x00];
+        let end: &[u8] = &[0xff; key::KEY_LEN + 1];
         let mut keys_to_delete = Vec::new();
         txn.keys(start..end, None)
-            .filter(|arr| match arr.first() {
-                Some(tag) => !predicate(*tag),
-                None => false,
+            .filter(|arr| {
+                key::algorithm_of(arr) != Some(DigestAlgorithm::CURRENT)
+                    || arr.first().is_some_and(|tag| !predicate(*tag))
             })
             .for_each(|key| keys_to_delete.push(key));
         let mut txn = self.store.begin()?;
@@ -0,0 +1,139 @@
+use crate::INSTANCE;
+use log::{Level, LevelFilter, Record};
+use std::{cell::RefCell, collections::VecDeque, sync::Mutex};
+
+/// Maximum number of records kept for a single capture, the oldest ones are dropped
+const MAX_CAPTURED_RECORDS: usize = 50;
+
+thread_local! {
+    static CAPTURE: RefCell<Option<VecDeque<String>>> = const { RefCell::new(None) };
+}
+
+/// Number of captures running on all threads. DEBUG records are emitted only while
+/// it is not zero, otherwise the log level chosen by the user applies
+static ACTIVE_CAPTURES: Mutex<usize> = Mutex::new(0);
+
+/// Runs `f` and collects log records (up to DEBUG level) emitted on the current thread
+/// while it was running, regardless of the verbosity chosen by the user.
+///
+/// Used to attach the action trail of a single target to its failure.
+pub fn capture_logs<R>(f: impl FnOnce() -> R) -> (R, Vec<String>) {
+    let _guard = ActiveCapture::start();
+    let previous = CAPTURE.with_borrow_mut(|it| it.replace(VecDeque::new()));
+    let result = f();
+    let captured = CAPTURE.with_borrow_mut(|it| std::mem::replace(it, previous));
+    (result, captured.map(Vec::from).unwrap_or_default())
+}
+
+/// Raises the log level to DEBUG for the first running capture and restores
+/// the displayed one when the last capture is finished
+struct ActiveCapture;
+
+impl ActiveCapture {
+    fn start() -> Self {
+        let mut active = ACTIVE_CAPTURES.lock().unwrap_or_else(|e| e.into_inner());
+        *active += 1;
+        if let (1, Some(display_level)) = (*active, INSTANCE.display_level.get()) {
+            log::set_max_level((*display_level).max(LevelFilter::Debug));
+        }
+        Self
+    }
+}
+
+impl Drop for ActiveCapture {
+    fn drop(&mut self) {
+        let mut active = ACTIVE_CAPTURES.lock().unwrap_or_else(|e| e.into_inner());
+        *active -= 1;
+        if let (0, Some(display_level)) = (*active, INSTANCE.display_level.get()) {
+            log::set_max_level(*display_level);
+        }
+    }
+}
+
+pub(crate) fn capture_record(record: &Record) {
+    if record.level() > Level::Debug || record.target().starts_with('@') {
+        return;
+    }
+    CAPTURE.with_borrow_mut(|capture| {
+        if let Some(records) = capture {
+            if records.len() == MAX_CAPTURED_RECORDS {
+                records.pop_front();
+            }
+            records.push_back(format!(
+                "{level} [{target}] {args}",
+                level = record.level().as_str().to_lowercase(),
+                target = record.target(),
+                args = record.args(),
+            ));
+        }
+    });
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn capture_logs__records_inside_closure__EXPECT__captured_in_order() {
+        // When
+        let (result, logs) = capture_logs(|| {
+            capture_record(
+                &Record::builder()
+                    .level(Level::Debug)
+                    .target("Figma API")
+                    .args(format_args!("get_image called"))
+                    .build(),
+            );
+            capture_record(
+                &Record::builder()
+                    .level(Level::Trace)
+                    .target("Figma API")
+                    .args(format_args!("too verbose"))
+                    .build(),
+            );
+            42
+        });
+
+        // Then
+        assert_eq!(42, result);
+        assert_eq!(vec!["debug [Figma API] get_image called".to_string()], logs);
+    }
+
+    #[test]
+    fn capture_record__no_active_capture__EXPECT__nothing_captured() {
+        // Given
+        capture_record(
+            &Record::builder()
+                .level(Level::Error)
+                .target("Import")
+                .args(format_args!("lost"))
+                .build(),
+        );
+
+        // When
+        let (_, logs) = capture_logs(|| ());
+
+        // Then
+        assert!(logs.is_empty());
+    }
+
+    #[test]
+    fn capture_logs__displayed_level_warn__EXPECT__debug_emitted_only_while_capturing() {
+        // Given
+        let _ = INSTANCE.display_level.set(LevelFilter::Warn);
+        let display_level = *INSTANCE.display_level.get().unwrap();
+        log::set_max_level(display_level);
+
+        // When
+        let (level_inside, _) = capture_logs(log::max_level);
+
+        // Then
+        assert!(level_inside >= LevelFilter::Debug);
+        // other tests may be capturing concurrently
+        let active = ACTIVE_CAPTURES.lock().unwrap();
+        if *active == 0 {
+            assert_eq!(display_level, log::max_level());
+        }
+    }
+}
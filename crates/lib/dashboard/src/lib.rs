@@ -1,39 +1,62 @@
 use crossbeam_channel::{Receiver, Sender, unbounded};
-use crossterm::{
-    cursor::MoveToColumn,
-    queue,
-    style::{Print, Stylize},
-    terminal::{Clear, ClearType},
-};
+use crossterm::style::Stylize;
 use lib_rainbow_bar::{ProgressBar, ProgressBarOptions};
+use lib_terminal::{
+    clear_progress, draw_progress, terminal_height, terminal_width, truncate_to_width,
+};
 use slab::Slab;
 use std::{
-    collections::HashSet,
-    io::{IsTerminal, Write, stderr},
+    borrow::Cow,
+    collections::{BTreeMap, HashSet},
+    io::{IsTerminal, stderr},
     sync::{
         Arc, LazyLock, Mutex, OnceLock,
-        atomic::{AtomicBool, AtomicUsize, Ordering},
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
     },
     thread::{self},
-    time::Duration,
+    time::{Duration, Instant},
 };
-use terminal_size::Width;
 
+mod capture;
+pub use capture::*;
 mod logger;
 pub use logger::*;
+mod remotes;
+pub use remotes::*;
 
 static INSTANCE: LazyLock<Dashboard> = LazyLock::new(|| Dashboard::new());
 
+/// Default number of progress bar redraws per second
+const DEFAULT_FPS: u64 = 20;
+
+/// Default interval between progress summaries when there is no progress bar
+const DEFAULT_SUMMARY_INTERVAL: Duration = Duration::from_secs(30);
+
 pub struct Dashboard {
     start_trigger: Sender<()>,
-    is_interactive: bool,
+    /// stderr is a terminal which can show the progress bar
+    has_terminal: bool,
+    log_format: OnceLock<LogFormat>,
+    output_mode: OnceLock<OutputMode>,
     pb_enabled: Arc<AtomicBool>,
+    paused: Arc<AtomicBool>,
+    frame_interval_ms: Arc<AtomicU64>,
     max_targets: Arc<AtomicUsize>,
     current_targets: Arc<AtomicUsize>,
+    exported_targets: Arc<AtomicUsize>,
+    cached_targets: Arc<AtomicUsize>,
+    failed_targets: Arc<AtomicUsize>,
+    /// Bytes received from remotes, for the transfer rate next to the progress bar
+    downloaded_bytes: Arc<AtomicU64>,
+    /// Milliseconds between progress summaries without a terminal, `0` to disable them
+    summary_interval_ms: Arc<AtomicU64>,
     requested_remotes: Arc<AtomicUsize>,
     loaded_packages: Arc<AtomicUsize>,
     in_progress_targets: Arc<Mutex<Slab<String>>>,
+    /// Remote name => its row under the progress bar
+    remotes: Arc<Mutex<BTreeMap<String, RemoteRow>>>,
     process_name: OnceLock<String>,
+    display_level: OnceLock<log::LevelFilter>,
     progress_bar: Arc<Mutex<ProgressBar>>,
 }
 
@@ -43,14 +66,27 @@ impl Dashboard {
         thread::spawn(move || lifecycle_loop(start_receiver));
         Self {
             start_trigger,
-            is_interactive: stderr().is_terminal() && !is_ci::cached(),
+            has_terminal: stderr().is_terminal() && !is_ci::cached(),
+            log_format: OnceLock::new(),
+            output_mode: OnceLock::new(),
             pb_enabled: Default::default(),
+            paused: Default::default(),
+            frame_interval_ms: Arc::new(AtomicU64::new(1000 / DEFAULT_FPS)),
             max_targets: Default::default(),
             current_targets: Default::default(),
+            exported_targets: Default::default(),
+            cached_targets: Default::default(),
+            failed_targets: Default::default(),
+            downloaded_bytes: Default::default(),
+            summary_interval_ms: Arc::new(AtomicU64::new(
+                DEFAULT_SUMMARY_INTERVAL.as_millis() as u64
+            )),
             requested_remotes: Default::default(),
             loaded_packages: Default::default(),
             in_progress_targets: Default::default(),
+            remotes: Default::default(),
             process_name: OnceLock::new(),
+            display_level: OnceLock::new(),
             progress_bar: Arc::new(Mutex::new(ProgressBar::new(ProgressBarOptions {
                 bar_width: 40,
                 ..Default::default()
@@ -59,23 +95,99 @@ impl Dashboard {
     }
 }
 
+impl Dashboard {
+    fn log_format(&self) -> LogFormat {
+        self.log_format.get().copied().unwrap_or_default()
+    }
+
+    fn output_mode(&self) -> OutputMode {
+        self.output_mode.get().copied().unwrap_or_default()
+    }
+
+    /// Progress bar is drawn, otherwise the progress is logged
+    fn is_interactive(&self) -> bool {
+        shows_progress_bar(self.has_terminal, self.log_format(), self.output_mode())
+    }
+}
+
+/// The progress bar is drawn over plain text logs of a terminal only, it would
+/// break JSON lines and the output of `--quiet` and `--porcelain`
+fn shows_progress_bar(has_terminal: bool, log_format: LogFormat, output_mode: OutputMode) -> bool {
+    has_terminal && log_format == LogFormat::Text && output_mode == OutputMode::Normal
+}
+
+/// Only errors and the data the command is asked for should be printed,
+/// e.g. with `--quiet` or `--porcelain`
+pub fn is_quiet() -> bool {
+    INSTANCE.output_mode() != OutputMode::Normal
+}
+
 fn lifecycle_loop(start_receiver: Receiver<()>) {
     if let Err(_) = start_receiver.recv() {
         return;
     }
+    let mut last_summary = Instant::now();
     while let Err(_) = start_receiver.try_recv() {
-        INSTANCE.progress_bar.lock().unwrap().update_anim_state();
-        lifecycle!(target: "@", "");
-        thread::sleep(Duration::from_millis(50));
+        if !INSTANCE.is_interactive() {
+            let interval = INSTANCE.summary_interval_ms.load(Ordering::Relaxed);
+            if interval > 0 && last_summary.elapsed() >= Duration::from_millis(interval) {
+                log_progress_summary();
+                last_summary = Instant::now();
+            }
+        } else if !INSTANCE.paused.load(Ordering::Relaxed) {
+            INSTANCE.progress_bar.lock().unwrap().update_anim_state();
+            lifecycle!(target: "@", "");
+        }
+        let frame_interval = INSTANCE.frame_interval_ms.load(Ordering::Relaxed);
+        thread::sleep(Duration::from_millis(frame_interval));
     }
 }
 
-pub(crate) fn render_progress_bar(pb: &mut ProgressBar) -> std::io::Result<()> {
+/// Plain replacement of the progress bar for CI logs
+fn log_progress_summary() {
+    let summary = ProgressSummary {
+        current: INSTANCE.current_targets.load(Ordering::Relaxed),
+        max: INSTANCE.max_targets.load(Ordering::Relaxed),
+        exported: INSTANCE.exported_targets.load(Ordering::Relaxed),
+        cached: INSTANCE.cached_targets.load(Ordering::Relaxed),
+        failed: INSTANCE.failed_targets.load(Ordering::Relaxed),
+        in_progress: INSTANCE.in_progress_targets.lock().unwrap().len(),
+    };
+    log::info!(target: "Progress", "{}", summary.line());
+}
+
+struct ProgressSummary {
+    current: usize,
+    max: usize,
+    exported: usize,
+    cached: usize,
+    failed: usize,
+    in_progress: usize,
+}
+
+impl ProgressSummary {
+    /// E.g. `124/500 targets done: 120 exported, 4 cached, 0 failed, 8 in progress`
+    fn line(&self) -> String {
+        let Self {
+            current,
+            max,
+            exported,
+            cached,
+            failed,
+            in_progress,
+        } = self;
+        format!(
+            "{current}/{max} targets done: {exported} exported, {cached} cached, {failed} failed, {in_progress} in progress"
+        )
+    }
+}
+
+pub(crate) fn render_progress_bar(pb: &mut ProgressBar) {
     let pb_enabled = INSTANCE.pb_enabled.load(Ordering::Relaxed);
-    if !INSTANCE.is_interactive || !pb_enabled {
-        return Ok(());
+    let paused = INSTANCE.paused.load(Ordering::Relaxed);
+    if !INSTANCE.is_interactive() || !pb_enabled || paused {
+        return;
     }
-    let mut stderr = stderr().lock();
     let max = INSTANCE.max_targets.load(Ordering::Relaxed);
     let process_name = match INSTANCE.process_name.get() {
         Some(name) => name.to_owned(),
@@ -85,19 +197,24 @@ pub(crate) fn render_progress_bar(pb: &mut ProgressBar) -> std::io::Result<()> {
     // first line: progress bar
     pb.max = max;
     pb.current = INSTANCE.current_targets.load(Ordering::Relaxed);
-    queue!(
-        stderr,
-        Print(format!("{: >12} ", process_name).cyan().bold()),
-        Print(&pb),
-    )?;
-    let _ = stderr.flush()?;
+    pb.transferred_bytes = INSTANCE.downloaded_bytes.load(Ordering::Relaxed);
+    pb.sample_rates(Instant::now());
+    let progress_line = format!("{} {pb}", format!("{: >12}", process_name).cyan().bold());
+
+    // every remote gets its own line, so slow remotes are visible, while the whole
+    // progress area takes at most half of the terminal
+    let max_remote_lines = match terminal_height() {
+        Some(h) => (h / 2).saturating_sub(1),
+        None => usize::MAX,
+    };
+    let remote_lines = remote_lines(max_remote_lines);
 
     // second line
     let in_progress_line = {
         let slab = INSTANCE.in_progress_targets.lock().unwrap();
         if slab.is_empty() {
-            queue!(stderr, Clear(ClearType::UntilNewLine), MoveToColumn(0))?;
-            return Ok(());
+            draw_progress(std::iter::once(progress_line).chain(remote_lines).collect());
+            return;
         }
         let mut unique_items = HashSet::with_capacity(slab.len());
         slab.iter()
@@ -113,25 +230,38 @@ pub(crate) fn render_progress_bar(pb: &mut ProgressBar) -> std::io::Result<()> {
             .collect::<Vec<_>>()
             .join(", ")
     };
-    let max_length = if let Some((Width(w), _)) = terminal_size::terminal_size_of(&stderr) {
-        (w as usize).saturating_sub(15 + pb.len())
-    } else {
-        30
-    };
-    let in_progress_line = if in_progress_line.len() > max_length {
-        format!("{}...", &in_progress_line[..(max_length.saturating_sub(3))])
-    } else {
-        in_progress_line
+    // the width is queried every frame, so the line follows resizes of the terminal
+    let first_line = match fit_in_progress_line(&in_progress_line, terminal_width(), pb.len()) {
+        Some(names) => format!("{progress_line}: {names}"),
+        None => progress_line,
     };
+    draw_progress(std::iter::once(first_line).chain(remote_lines).collect());
+}
 
-    queue!(
-        stderr,
-        Print(": "),
-        Print(in_progress_line),
-        Clear(ClearType::UntilNewLine),
-        MoveToColumn(0),
-    )?;
-    Ok(())
+/// Width of the column with the process name, e.g. `   Importing`
+const LABEL_WIDTH: usize = 12;
+
+/// Names of the targets in progress are cut to this width if the terminal width is unknown
+const FALLBACK_NAMES_WIDTH: usize = 30;
+
+/// Cuts the names of the targets in progress so that the first line of the dashboard
+/// fits the terminal, `None` if there is no room for them at all
+fn fit_in_progress_line(
+    names: &str,
+    terminal_width: Option<usize>,
+    bar_width: usize,
+) -> Option<Cow<'_, str>> {
+    // the label and a space before the bar, `: ` after it and the last column kept free
+    let taken = LABEL_WIDTH + 1 + bar_width + 2 + 1;
+    let max_width = match terminal_width {
+        Some(w) => w.saturating_sub(taken),
+        None => FALLBACK_NAMES_WIDTH,
+    };
+    // less than a letter and the ellipsis is just noise
+    if max_width < 4 {
+        return None;
+    }
+    Some(truncate_to_width(names, max_width))
 }
 
 pub fn init_dashboard(params: InitDashboardParams) {
@@ -158,24 +288,139 @@ pub struct InitDashboardParams {
 
 pub fn shutdown_dashboard() {
     let _ = INSTANCE.start_trigger.send(());
+    // holding the lock guarantees that no frame is being drawn right now
+    let _pb = INSTANCE.progress_bar.lock().unwrap();
+    INSTANCE.pb_enabled.store(false, Ordering::SeqCst);
+    clear_progress();
 }
 
-pub fn track_progress(name: String) -> InProgressItem {
+/// Sets how many times per second the progress bar is redrawn (clamped to 1..=60)
+pub fn set_frame_rate(fps: u64) {
+    INSTANCE
+        .frame_interval_ms
+        .store(1000 / fps.clamp(1, 60), Ordering::Relaxed);
+}
+
+/// Sets how often the progress summary is logged when there is no progress bar,
+/// e.g. on CI. `None` disables the summaries, completed targets are logged anyway
+pub fn set_summary_interval(interval: Option<Duration>) {
+    let interval_ms = interval.map(|it| it.as_millis() as u64).unwrap_or_default();
+    INSTANCE
+        .summary_interval_ms
+        .store(interval_ms, Ordering::Relaxed);
+}
+
+/// Stops redrawing the progress bar and erases it, so that other output
+/// can be printed to the terminal without interleaving with it
+pub fn pause_dashboard() {
+    // frames are drawn under this lock and check the flag first, so a frame started
+    // before the pause is finished before the erase and no frame is drawn after it
+    let _pb = INSTANCE.progress_bar.lock().unwrap();
+    if INSTANCE.paused.swap(true, Ordering::SeqCst) || !INSTANCE.is_interactive() {
+        return;
+    }
+    clear_progress();
+}
+
+/// Continues redrawing the progress bar after [`pause_dashboard`]
+pub fn resume_dashboard() {
+    INSTANCE.paused.store(false, Ordering::SeqCst);
+}
+
+/// Shows the target on the dashboard until it is finished.
+///
+/// The short `name` is shown next to the progress bar, while the `label` is logged
+/// along with the outcome of the target when there is no progress bar.
+pub fn track_progress(name: String, label: String) -> InProgressItem {
+    if !INSTANCE.is_interactive() {
+        log::debug!(target: "Started", "{label}");
+    }
     InProgressItem {
         id: INSTANCE.in_progress_targets.lock().unwrap().insert(name),
+        label,
     }
 }
 
 pub struct InProgressItem {
     id: usize,
+    label: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TargetOutcome {
+    Exported,
+    Cached,
+    Failed,
+}
+
+/// Counts the bytes received from a remote, so that the transfer rate is shown
+pub fn record_download(bytes: usize) {
+    INSTANCE
+        .downloaded_bytes
+        .fetch_add(bytes as u64, Ordering::Relaxed);
 }
 
 impl InProgressItem {
-    pub fn mark_as_done(self) {
-        INSTANCE.current_targets.fetch_add(1, Ordering::SeqCst);
+    pub fn finish(self, outcome: TargetOutcome, elapsed: Duration) {
+        let counter = match outcome {
+            TargetOutcome::Exported => &INSTANCE.exported_targets,
+            TargetOutcome::Cached => &INSTANCE.cached_targets,
+            TargetOutcome::Failed => &INSTANCE.failed_targets,
+        };
+        counter.fetch_add(1, Ordering::SeqCst);
+        let current = INSTANCE.current_targets.fetch_add(1, Ordering::SeqCst) + 1;
+        if INSTANCE.output_mode() == OutputMode::Porcelain {
+            println!("{}", porcelain_line(&self.label, outcome, elapsed));
+        } else if !INSTANCE.is_interactive() {
+            // No progress bar without a terminal, so log the transition once instead
+            let max = INSTANCE.max_targets.load(Ordering::Relaxed);
+            log::info!(
+                target: "Done",
+                "{}",
+                completed_target_line(current, max, &self.label, outcome, elapsed),
+            );
+        }
     }
 }
 
+/// E.g. `[123/500] //icons:star: exported (cache miss, 1.2s)`
+fn completed_target_line(
+    current: usize,
+    max: usize,
+    label: &str,
+    outcome: TargetOutcome,
+    elapsed: Duration,
+) -> String {
+    let (status, details) = match outcome {
+        TargetOutcome::Exported => ("exported", "cache miss, "),
+        TargetOutcome::Cached => ("cached", "cache hit, "),
+        TargetOutcome::Failed => ("failed", ""),
+    };
+    format!(
+        "[{current}/{max}] {label}: {status} ({details}{:.1}s)",
+        elapsed.as_secs_f32(),
+    )
+}
+
+/// Version of the porcelain format, bumped on every incompatible change of its lines
+const PORCELAIN_VERSION: u32 = 1;
+
+/// Tab separated format version, outcome, target and its time in milliseconds,
+/// e.g. `v1\texported\t//icons:star\t1230`.
+///
+/// Fields are only appended within a version, so parsers should ignore the extra ones.
+pub fn porcelain_line(label: &str, outcome: TargetOutcome, elapsed: Duration) -> String {
+    let status = match outcome {
+        TargetOutcome::Exported => "exported",
+        TargetOutcome::Cached => "cached",
+        TargetOutcome::Failed => "failed",
+    };
+    format!(
+        "v{PORCELAIN_VERSION}\t{status}\t{label}\t{}",
+        elapsed.as_millis()
+    )
+}
+
 impl Drop for InProgressItem {
     fn drop(&mut self) {
         if let Ok(mut targets) = INSTANCE.in_progress_targets.lock() {
@@ -183,3 +428,158 @@ impl Drop for InProgressItem {
         }
     }
 }
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use lib_terminal::visible_width;
+
+    #[test]
+    fn completed_target_line__exported_target__EXPECT__outcome_and_time() {
+        // When
+        let line = completed_target_line(
+            123,
+            500,
+            "//icons:star",
+            TargetOutcome::Exported,
+            Duration::from_millis(1230),
+        );
+
+        // Then
+        assert_eq!("[123/500] //icons:star: exported (cache miss, 1.2s)", line);
+    }
+
+    #[test]
+    fn completed_target_line__failed_target__EXPECT__no_cache_details() {
+        // When
+        let line = completed_target_line(
+            1,
+            2,
+            "//icons:moon (night)",
+            TargetOutcome::Failed,
+            Duration::ZERO,
+        );
+
+        // Then
+        assert_eq!("[1/2] //icons:moon (night): failed (0.0s)", line);
+    }
+
+    #[test]
+    fn porcelain_line__cached_target_with_variant__EXPECT__tab_separated_fields() {
+        // When
+        let line = porcelain_line(
+            "//icons:moon (night)",
+            TargetOutcome::Cached,
+            Duration::from_millis(1230),
+        );
+
+        // Then
+        assert_eq!("v1\tcached\t//icons:moon (night)\t1230", line);
+    }
+
+    #[test]
+    fn shows_progress_bar__no_terminal_or_machine_output__EXPECT__progress_logged() {
+        // Then
+        assert!(shows_progress_bar(
+            true,
+            LogFormat::Text,
+            OutputMode::Normal
+        ));
+        assert!(!shows_progress_bar(
+            false,
+            LogFormat::Text,
+            OutputMode::Normal
+        ));
+        assert!(!shows_progress_bar(
+            true,
+            LogFormat::Json,
+            OutputMode::Normal
+        ));
+        assert!(!shows_progress_bar(
+            true,
+            LogFormat::Text,
+            OutputMode::Quiet
+        ));
+        assert!(!shows_progress_bar(
+            true,
+            LogFormat::Text,
+            OutputMode::Porcelain
+        ));
+    }
+
+    #[test]
+    fn ProgressSummary__line__EXPECT__counts_of_every_outcome() {
+        // Given
+        let summary = ProgressSummary {
+            current: 124,
+            max: 500,
+            exported: 120,
+            cached: 4,
+            failed: 0,
+            in_progress: 8,
+        };
+
+        // Then
+        assert_eq!(
+            "124/500 targets done: 120 exported, 4 cached, 0 failed, 8 in progress",
+            summary.line()
+        );
+    }
+
+    #[test]
+    fn fit_in_progress_line__wide_terminal__EXPECT__names_kept() {
+        // When
+        let names = fit_in_progress_line("ic_star, ic_moon", Some(120), 50);
+
+        // Then
+        assert_eq!(Some("ic_star, ic_moon".into()), names);
+    }
+
+    #[test]
+    fn fit_in_progress_line__narrow_terminal__EXPECT__line_fits_terminal() {
+        // Given
+        let bar_width = 50;
+
+        // When
+        let names = fit_in_progress_line("ic_star, ic_moon", Some(76), bar_width).unwrap();
+
+        // Then
+        assert_eq!("ic_star...", names);
+        assert_eq!(75, LABEL_WIDTH + 1 + bar_width + 2 + names.len());
+    }
+
+    #[test]
+    fn fit_in_progress_line__multibyte_names__EXPECT__no_panic_on_cut() {
+        // When
+        let names = fit_in_progress_line("иконка_звезды, ☆☆☆", Some(75), 50).unwrap();
+
+        // Then
+        assert_eq!("иконка...", names);
+    }
+
+    #[test]
+    fn fit_in_progress_line__wide_names__EXPECT__cut_by_columns() {
+        // When
+        let names = fit_in_progress_line("图标_星, 图标_月", Some(76), 50).unwrap();
+
+        // Then
+        assert_eq!("图标_星...", names);
+        assert!(visible_width(&names) <= 10);
+    }
+
+    #[test]
+    fn fit_in_progress_line__no_room__EXPECT__names_omitted() {
+        // Then
+        assert_eq!(None, fit_in_progress_line("ic_star", Some(60), 50));
+    }
+
+    #[test]
+    fn fit_in_progress_line__unknown_terminal_width__EXPECT__fallback_width() {
+        // When
+        let names = fit_in_progress_line(&"a".repeat(40), None, 50).unwrap();
+
+        // Then
+        assert_eq!(FALLBACK_NAMES_WIDTH, names.chars().count());
+    }
+}
@@ -1,44 +1,119 @@
-use crate::{Dashboard, INSTANCE, render_progress_bar};
-use crossterm::{
-    cursor::MoveToColumn,
-    queue,
-    style::{Print, Stylize},
-    terminal::{Clear, ClearType},
+use crate::{Dashboard, INSTANCE, capture::capture_record, render_progress_bar};
+use crossterm::style::Stylize;
+use lib_terminal::{write_log, write_raw_log};
+use log::{Level, LevelFilter, Log, Record, info, max_level, set_logger};
+use serde::Serialize;
+use std::{
+    cell::RefCell,
+    io::{Write, stderr},
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
 };
-use log::{Level, Log, Record, info, max_level, set_logger};
-use std::io::{Write, stderr};
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum LogFormat {
+    /// Colored lines for humans, along with the progress bar in a terminal
+    #[default]
+    Text,
+    /// One JSON object per line for log processors, no progress bar
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            other => Err(format!(
+                "unknown log format `{other}`, expected `text` or `json`"
+            )),
+        }
+    }
+}
+
+/// How much the CLI tells about its work
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum OutputMode {
+    /// Lifecycle lines and warnings, along with the progress bar in a terminal
+    #[default]
+    Normal,
+    /// Only errors
+    Quiet,
+    /// Only errors, while every completed target is printed to stdout in a stable format,
+    /// see [`porcelain_line`](crate::porcelain_line)
+    Porcelain,
+}
+
+thread_local! {
+    static CONTEXT: RefCell<Option<LogContext>> = const { RefCell::new(None) };
+}
+
+/// Resource the records of the current thread are emitted for
+#[derive(Clone)]
+struct LogContext {
+    label: String,
+    remote: String,
+}
+
+/// Runs `f`, attaching the `label` and the `remote` to the JSON records emitted
+/// on the current thread while it was running
+pub fn with_log_context<R>(label: &str, remote: &str, f: impl FnOnce() -> R) -> R {
+    let context = LogContext {
+        label: label.to_owned(),
+        remote: remote.to_owned(),
+    };
+    let previous = CONTEXT.with_borrow_mut(|it| it.replace(context));
+    let result = f();
+    CONTEXT.with_borrow_mut(|it| *it = previous);
+    result
+}
+
+#[derive(Serialize)]
+struct JsonRecord<'a> {
+    /// Unix time in milliseconds
+    timestamp: u128,
+    level: &'a str,
+    target: &'a str,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remote: Option<String>,
+}
 
 impl Log for Dashboard {
     fn enabled(&self, metadata: &log::Metadata) -> bool {
-        metadata.target().starts_with('@') || metadata.level() <= max_level()
+        let display_level = self.display_level.get().copied().unwrap_or_else(max_level);
+        (metadata.target().starts_with('@') && self.output_mode() == OutputMode::Normal)
+            || metadata.level() <= display_level
     }
 
     fn log(&self, record: &log::Record) {
-        if !self.enabled(record.metadata()) {
+        if should_skip(&record) {
             return;
         }
-        if should_skip(&record) {
+        capture_record(record);
+        if !self.enabled(record.metadata()) {
             return;
         }
 
-        let mut stderr = stderr().lock();
-        let _ = match record.target().as_ref() {
-            "@" => Ok(()),
-            target if target.starts_with("@") => {
-                queue!(
-                    stderr,
-                    MoveToColumn(0),
-                    Print(format!(
-                        "{} {}",
-                        format!("{: >12}", target.trim_start_matches("@"))
-                            .bold()
-                            .green(),
-                        record.args(),
-                    )),
-                    Clear(ClearType::UntilNewLine),
-                    Print('\n'),
-                )
+        if self.log_format() == LogFormat::Json {
+            if record.target() != "@" {
+                write_raw_log(&json_line(record));
             }
+            return;
+        }
+        match record.target().as_ref() {
+            "@" => (),
+            target if target.starts_with("@") => write_log(&format!(
+                "{} {}",
+                format!("{: >12}", target.trim_start_matches("@"))
+                    .bold()
+                    .green(),
+                record.args(),
+            )),
             target => {
                 use log::Level::*;
                 let label = match record.level() {
@@ -48,17 +123,10 @@ impl Log for Dashboard {
                     Error => "error:".bold().red(),
                     Info => "info:".bold().cyan(),
                 };
-                queue!(
-                    stderr,
-                    MoveToColumn(0),
-                    Print(format!("{label} [{target}] {}", record.args())),
-                    Clear(ClearType::UntilNewLine),
-                    Print('\n'),
-                )
+                write_log(&format!("{label} [{target}] {}", record.args()))
             }
         };
-        let _ = render_progress_bar(&mut INSTANCE.progress_bar.lock().unwrap());
-        let _ = stderr.flush();
+        render_progress_bar(&mut INSTANCE.progress_bar.lock().unwrap());
     }
 
     fn flush(&self) {
@@ -66,7 +134,9 @@ impl Log for Dashboard {
     }
 }
 
-pub fn init_log_impl(verbosity: u8) {
+pub fn init_log_impl(verbosity: u8, format: LogFormat, mode: OutputMode) {
+    let _ = INSTANCE.log_format.set(format);
+    let _ = INSTANCE.output_mode.set(mode);
     set_logger(&*INSTANCE).unwrap();
 
     // Устанавливаем уровень логгирования в зависимости от verbosity
@@ -75,15 +145,23 @@ pub fn init_log_impl(verbosity: u8) {
         .or(std::env::var("ACTIONS_RUNNER_DEBUG"))
         .or(std::env::var("ACTIONS_STEP_DEBUG"))
         .is_ok();
-    log::set_max_level(match (verbosity, running_on_ci, force_debug_logging) {
-        (_, _, true) => log::LevelFilter::Debug,
-        (0, true, _) | (1, true, _) => log::LevelFilter::Info,
-        (0, _, _) => log::LevelFilter::Warn,
-        (1, _, _) => log::LevelFilter::Info,
-        (2, _, _) => log::LevelFilter::Debug,
-        _ => log::LevelFilter::Trace,
-    });
+    let display_level = match (verbosity, running_on_ci, force_debug_logging) {
+        _ if mode != OutputMode::Normal => LevelFilter::Error,
+        (_, _, true) => LevelFilter::Debug,
+        (0, true, _) | (1, true, _) => LevelFilter::Info,
+        (0, _, _) => LevelFilter::Warn,
+        (1, _, _) => LevelFilter::Info,
+        (2, _, _) => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    };
+    let _ = INSTANCE.display_level.set(display_level);
+    // DEBUG records are emitted only while target logs are captured for failure
+    // reports, and even then only records up to `display_level` are printed
+    log::set_max_level(display_level);
 
+    if mode != OutputMode::Normal {
+        return;
+    }
     if running_on_ci && !force_debug_logging {
         info!(target: "Logger", "CI environment detected, set verbosity to INFO")
     }
@@ -92,6 +170,40 @@ pub fn init_log_impl(verbosity: u8) {
     }
 }
 
+fn json_line(record: &Record) -> String {
+    let (label, remote) = CONTEXT
+        .with_borrow(|it| it.clone())
+        .map(|it| (Some(it.label), Some(it.remote)))
+        .unwrap_or_default();
+    // lifecycle records are logged with the WARN level only to be always shown
+    let (level, target) = match record.target().strip_prefix('@') {
+        Some(target) => ("info", target),
+        None => (level_name(record.level()), record.target()),
+    };
+    let json = JsonRecord {
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis(),
+        level,
+        target,
+        message: record.args().to_string(),
+        label,
+        remote,
+    };
+    serde_json::to_string(&json).expect("log record is always serializable")
+}
+
+fn level_name(level: Level) -> &'static str {
+    match level {
+        Level::Error => "error",
+        Level::Warn => "warn",
+        Level::Info => "info",
+        Level::Debug => "debug",
+        Level::Trace => "trace",
+    }
+}
+
 fn should_skip(record: &Record) -> bool {
     match record.target() {
         t if t.starts_with("ureq") => match record.level() {
@@ -111,3 +223,54 @@ macro_rules! lifecycle {
         log::log!(target: $target, log::Level::Warn, $($arg)+)
     });
 }
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    fn record_json(target: &str, level: Level) -> serde_json::Value {
+        let line = json_line(
+            &Record::builder()
+                .level(level)
+                .target(target)
+                .args(format_args!("exported"))
+                .build(),
+        );
+        serde_json::from_str(&line).unwrap()
+    }
+
+    #[test]
+    fn json_line__inside_log_context__EXPECT__label_and_remote_fields() {
+        // When
+        let json = with_log_context("//icons:star", "design", || {
+            record_json("Import", Level::Debug)
+        });
+
+        // Then
+        assert_eq!("debug", json["level"]);
+        assert_eq!("Import", json["target"]);
+        assert_eq!("exported", json["message"]);
+        assert_eq!("//icons:star", json["label"]);
+        assert_eq!("design", json["remote"]);
+        assert!(json["timestamp"].is_u64());
+    }
+
+    #[test]
+    fn json_line__lifecycle_record_without_context__EXPECT__info_without_label() {
+        // When
+        let json = record_json("@Requested", Level::Warn);
+
+        // Then
+        assert_eq!("info", json["level"]);
+        assert_eq!("Requested", json["target"]);
+        assert!(json.get("label").is_none());
+    }
+
+    #[test]
+    fn LogFormat__parse_unknown__EXPECT__error() {
+        // Then
+        assert_eq!(Ok(LogFormat::Json), "json".parse::<LogFormat>());
+        assert!("yaml".parse::<LogFormat>().is_err());
+    }
+}
@@ -0,0 +1,206 @@
+use crate::INSTANCE;
+use crossterm::style::Stylize;
+use lib_terminal::visible_width;
+
+/// Progress of a single remote, shown on its own row under the progress bar
+#[derive(Default)]
+pub(crate) struct RemoteRow {
+    /// Status of the indexing of the remote, e.g. the number of indexed components
+    fetch_status: Option<String>,
+    fetched: bool,
+    requested_targets: usize,
+    done_targets: usize,
+    downloaded_bytes: u64,
+}
+
+/// Shows the remote on its own row of the dashboard until the item is dropped.
+///
+/// Rows are ordered by the remote name, so they don't jump around while being redrawn.
+pub fn track_remote(name: String, requested_targets: usize) -> RemoteProgressItem {
+    INSTANCE.remotes.lock().unwrap().insert(
+        name.clone(),
+        RemoteRow {
+            requested_targets,
+            ..Default::default()
+        },
+    );
+    RemoteProgressItem { name }
+}
+
+pub struct RemoteProgressItem {
+    name: String,
+}
+
+impl RemoteProgressItem {
+    /// Status of the indexing of the remote, shown until [`Self::finish_fetch`]
+    pub fn set_status(&self, status: String) {
+        self.update(|row| row.fetch_status = Some(status));
+    }
+
+    /// The index of the remote is received or taken from the cache
+    pub fn finish_fetch(&self) {
+        self.update(|row| row.fetched = true);
+    }
+
+    /// One of the targets of the remote is done, no matter how
+    pub fn finish_target(&self) {
+        self.update(|row| row.done_targets += 1);
+    }
+
+    pub fn add_downloaded_bytes(&self, bytes: usize) {
+        self.update(|row| row.downloaded_bytes += bytes as u64);
+    }
+
+    fn update(&self, f: impl FnOnce(&mut RemoteRow)) {
+        if let Some(row) = INSTANCE.remotes.lock().unwrap().get_mut(&self.name) {
+            f(row);
+        }
+    }
+}
+
+impl Drop for RemoteProgressItem {
+    fn drop(&mut self) {
+        if let Ok(mut remotes) = INSTANCE.remotes.lock() {
+            remotes.remove(&self.name);
+        }
+    }
+}
+
+/// Rows of the tracked remotes, at most `max_lines` of them
+pub(crate) fn remote_lines(max_lines: usize) -> Vec<String> {
+    let remotes = INSTANCE.remotes.lock().unwrap();
+    // names are padded by their width on the screen, `format!` would count chars
+    let name_width = remotes
+        .keys()
+        .map(|it| visible_width(it))
+        .max()
+        .unwrap_or(0);
+    let lines = remotes
+        .iter()
+        .map(|(name, row)| {
+            format!(
+                "{} {name}{: <padding$}  {}",
+                format!("{: >12}", "Remote").dark_grey(),
+                "",
+                row_status(row),
+                padding = name_width - visible_width(name),
+            )
+        })
+        .collect();
+    collapse(lines, max_lines)
+}
+
+/// E.g. `fetch: done · export: 12/40 · download: 3.1 MB`
+fn row_status(row: &RemoteRow) -> String {
+    let fetch = match (row.fetched, &row.fetch_status) {
+        (true, _) => "done",
+        (false, Some(status)) => status,
+        (false, None) => "waiting",
+    };
+    let mut status = format!(
+        "fetch: {fetch} · export: {}/{}",
+        row.done_targets, row.requested_targets,
+    );
+    if row.downloaded_bytes > 0 {
+        status.push_str(&format!(
+            " · download: {}",
+            format_size(row.downloaded_bytes)
+        ));
+    }
+    status
+}
+
+/// Too many rows would push the logs out of a short terminal, so the last visible row
+/// counts the hidden ones instead
+fn collapse(mut lines: Vec<String>, max_lines: usize) -> Vec<String> {
+    if lines.len() <= max_lines {
+        return lines;
+    }
+    if max_lines == 0 {
+        return Vec::new();
+    }
+    let hidden = lines.len() - (max_lines - 1);
+    lines.truncate(max_lines - 1);
+    lines.push(format!(
+        "{} {hidden} more remote{}",
+        format!("{: >12}", "Remote").dark_grey(),
+        if hidden == 1 { "" } else { "s" },
+    ));
+    lines
+}
+
+/// In decimal units, the same way the transfer rate is shown next to the progress bar
+fn format_size(bytes: u64) -> String {
+    let bytes = bytes as f64;
+    if bytes < 1_000.0 {
+        format!("{bytes} B")
+    } else if bytes < 1_000_000.0 {
+        format!("{:.1} kB", bytes / 1_000.0)
+    } else {
+        format!("{:.1} MB", bytes / 1_000_000.0)
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn row_status__remote_being_indexed__EXPECT__indexing_status() {
+        // Given
+        let row = RemoteRow {
+            fetch_status: Some("indexing, 120 components".to_owned()),
+            requested_targets: 40,
+            ..Default::default()
+        };
+
+        // Then
+        assert_eq!(
+            "fetch: indexing, 120 components · export: 0/40",
+            row_status(&row)
+        );
+    }
+
+    #[test]
+    fn row_status__remote_downloading__EXPECT__downloaded_size() {
+        // Given
+        let row = RemoteRow {
+            fetch_status: Some("indexing, 120 components".to_owned()),
+            fetched: true,
+            requested_targets: 40,
+            done_targets: 12,
+            downloaded_bytes: 3_100_000,
+        };
+
+        // Then
+        assert_eq!(
+            "fetch: done · export: 12/40 · download: 3.1 MB",
+            row_status(&row)
+        );
+    }
+
+    #[test]
+    fn collapse__rows_fit__EXPECT__unchanged() {
+        // Given
+        let lines = vec!["a".to_owned(), "b".to_owned()];
+
+        // Then
+        assert_eq!(lines.clone(), collapse(lines, 2));
+    }
+
+    #[test]
+    fn collapse__too_many_rows__EXPECT__hidden_rows_counted() {
+        // Given
+        let lines = vec!["a".to_owned(), "b".to_owned(), "c".to_owned()];
+
+        // When
+        let collapsed = collapse(lines, 2);
+
+        // Then
+        assert_eq!(2, collapsed.len());
+        assert_eq!("a", collapsed[0]);
+        assert!(collapsed[1].ends_with(" 2 more remotes"));
+        assert!(collapse(vec!["a".to_owned()], 0).is_empty());
+    }
+}
@@ -0,0 +1,143 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, RwLock},
+};
+
+/// Source of fresh access tokens for the tokens which expire, e.g. OAuth ones.
+///
+/// Personal access tokens never expire, so by default no refresher is set and
+/// rejected tokens are reported as errors.
+pub trait TokenRefresher: Send + Sync {
+    /// Returns a new token to use instead of the `configured` one, or `None`
+    /// if it cannot be refreshed.
+    fn refresh(&self, configured: &str) -> Option<String>;
+}
+
+/// The only place which knows what token to send with a request.
+///
+/// Tokens from the workspace config are keys here: after a refresh, all requests
+/// made with the configured token are sent with the refreshed one instead.
+#[derive(Default)]
+pub(crate) struct Credentials {
+    refresher: Option<Arc<dyn TokenRefresher>>,
+    refreshed: RwLock<HashMap<String, String>>,
+    /// Serializes refreshes, so concurrent requests rejected with the same token
+    /// cause only one refresh
+    refreshing: Mutex<()>,
+}
+
+impl Credentials {
+    pub fn with_refresher(refresher: Arc<dyn TokenRefresher>) -> Self {
+        Self {
+            refresher: Some(refresher),
+            ..Default::default()
+        }
+    }
+
+    /// Token to send instead of the `configured` one
+    pub fn token(&self, configured: &str) -> String {
+        self.refreshed
+            .read()
+            .unwrap()
+            .get(configured)
+            .cloned()
+            .unwrap_or_else(|| configured.to_owned())
+    }
+
+    /// Called when the server rejected the `rejected` token sent instead of the
+    /// `configured` one. Returns a token to retry the request with, if there is one.
+    pub fn refresh(&self, configured: &str, rejected: &str) -> Option<String> {
+        let refresher = self.refresher.as_ref()?;
+        let _guard = self.refreshing.lock().unwrap();
+        // another request could have already refreshed the token while we were waiting
+        let current = self.token(configured);
+        if current != rejected {
+            return Some(current);
+        }
+        let fresh = refresher.refresh(configured)?;
+        self.refreshed
+            .write()
+            .unwrap()
+            .insert(configured.to_owned(), fresh.clone());
+        Some(fresh)
+    }
+
+    /// Name and value of the header carrying the `token`
+    pub fn header(token: &str) -> (&'static str, String) {
+        if token.starts_with(Self::OAUTH_TOKEN_PREFIX) {
+            ("Authorization", format!("Bearer {token}"))
+        } else {
+            ("X-FIGMA-TOKEN", token.to_owned())
+        }
+    }
+
+    const OAUTH_TOKEN_PREFIX: &str = "figu_";
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingRefresher(AtomicUsize);
+
+    impl TokenRefresher for CountingRefresher {
+        fn refresh(&self, configured: &str) -> Option<String> {
+            let n = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+            Some(format!("{configured}-{n}"))
+        }
+    }
+
+    #[test]
+    fn Credentials__concurrent_rejections_of_same_token__EXPECT__single_refresh() {
+        // Given
+        let refresher = Arc::new(CountingRefresher(AtomicUsize::new(0)));
+        let credentials = Credentials::with_refresher(refresher.clone());
+
+        // When
+        let tokens = std::thread::scope(|s| {
+            let threads = (0..8)
+                .map(|_| s.spawn(|| credentials.refresh("figu_token", "figu_token")))
+                .collect::<Vec<_>>();
+            threads
+                .into_iter()
+                .map(|it| it.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        // Then
+        assert_eq!(1, refresher.0.load(Ordering::SeqCst));
+        assert!(
+            tokens
+                .iter()
+                .all(|it| it.as_deref() == Some("figu_token-1"))
+        );
+        assert_eq!("figu_token-1", credentials.token("figu_token"));
+    }
+
+    #[test]
+    fn Credentials__no_refresher__EXPECT__configured_token_and_no_retry() {
+        // Given
+        let credentials = Credentials::default();
+
+        // When
+        let retry_with = credentials.refresh("figd_token", "figd_token");
+
+        // Then
+        assert_eq!(None, retry_with);
+        assert_eq!("figd_token", credentials.token("figd_token"));
+    }
+
+    #[test]
+    fn Credentials__header__EXPECT__bearer_for_oauth_tokens_only() {
+        assert_eq!(
+            ("Authorization", "Bearer figu_123".to_string()),
+            Credentials::header("figu_123")
+        );
+        assert_eq!(
+            ("X-FIGMA-TOKEN", "figd_123".to_string()),
+            Credentials::header("figd_123")
+        );
+    }
+}
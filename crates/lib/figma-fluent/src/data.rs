@@ -1,38 +1,208 @@
 use crate::{
-    Node, Result,
+    ApiUsage, Error, FileUsage, Mirror, Node, Result, TokenRefresher,
+    credentials::Credentials,
+    http_cache::{HttpCache, PendingResponse, Validators},
+    network::{HttpClient, load_ca_bundle},
     node_stream::{NodeStream, NodeStreamError},
+    usage::CountingReader,
 };
 use bytes::Bytes;
 use log::debug;
 use serde::Deserialize;
 use std::{
     collections::{BTreeMap, HashMap},
-    sync::Arc,
-    time::Duration,
+    io::{Cursor, Read},
+    path::Path,
+    sync::{Arc, Mutex, OnceLock},
+};
+use ureq::{
+    Body, RequestBuilder,
+    http::{Response, StatusCode},
+    typestate::WithoutBody,
 };
-use ureq::http::StatusCode;
 
 #[derive(Clone)]
 pub struct FigmaApi {
-    client: Arc<ureq::Agent>,
+    client: Arc<HttpClient>,
+    credentials: Arc<Credentials>,
+    http_cache: Option<HttpCache>,
+    mirror: MirrorMode,
+    usage: Arc<ApiUsage>,
+    base_url: Arc<str>,
+}
+
+#[derive(Clone, Default)]
+enum MirrorMode {
+    #[default]
+    Disabled,
+    /// Successful responses are saved to the mirror
+    Record(Arc<Mutex<Mirror>>),
+    /// Responses are served from the mirror, network is never used
+    Replay(Arc<Mirror>),
+}
+
+/// Client shared by all apis of the process, so connections opened by one command
+/// are reused by another one instead of doing TLS handshakes again.
+///
+/// `ureq` speaks HTTP/1.1 only, so keeping enough idle connections per host is what
+/// makes hundreds of small export and download requests cheap.
+fn shared_client() -> Arc<HttpClient> {
+    static CLIENT: OnceLock<Arc<HttpClient>> = OnceLock::new();
+    CLIENT
+        .get_or_init(|| Arc::new(HttpClient::from_env(None)))
+        .clone()
 }
 
 impl Default for FigmaApi {
     fn default() -> Self {
         Self {
-            client: Arc::new(
-                ureq::Agent::config_builder()
-                    .timeout_connect(Some(Duration::from_secs(15)))
-                    .max_idle_connections(10)
-                    .max_idle_connections_per_host(3)
-                    .http_status_as_error(false) // handling manually
-                    .build()
-                    .into(),
-            ),
+            client: shared_client(),
+            credentials: Arc::new(Credentials::default()),
+            http_cache: None,
+            mirror: MirrorMode::Disabled,
+            usage: Arc::new(ApiUsage::default()),
+            base_url: Arc::from(Self::BASE_URL),
         }
     }
 }
 
+impl FigmaApi {
+    /// Api which saves all successful responses to the `mirror`
+    pub fn recording(mirror: Arc<Mutex<Mirror>>) -> Self {
+        Self {
+            mirror: MirrorMode::Record(mirror),
+            ..Default::default()
+        }
+    }
+
+    /// Api which serves responses from the `mirror` without network access
+    pub fn from_mirror(mirror: Mirror) -> Self {
+        Self {
+            mirror: MirrorMode::Replay(Arc::new(mirror)),
+            ..Default::default()
+        }
+    }
+
+    /// Api which sends requests to another server, e.g. to a fake one in tests
+    pub fn with_base_url(self, base_url: &str) -> Self {
+        Self {
+            base_url: Arc::from(base_url.trim_end_matches('/')),
+            ..self
+        }
+    }
+
+    /// Api which asks the `refresher` for a new token when the server rejects one
+    pub fn with_token_refresher(self, refresher: Arc<dyn TokenRefresher>) -> Self {
+        Self {
+            credentials: Arc::new(Credentials::with_refresher(refresher)),
+            ..self
+        }
+    }
+
+    /// Api which remembers document responses in the `cache` and revalidates them
+    /// with conditional requests, so unchanged documents are not downloaded again
+    pub fn with_http_cache(self, cache: lib_cache::Cache) -> Self {
+        Self {
+            http_cache: Some(HttpCache::new(cache)),
+            ..self
+        }
+    }
+
+    /// Api which trusts only the root certificates from the PEM bundle at `path`,
+    /// e.g. the ones of a corporate proxy inspecting the traffic
+    pub fn with_ca_bundle(self, path: &Path) -> Result<Self> {
+        let certs = load_ca_bundle(path).map_err(ureq::Error::Io)?;
+        Ok(Self {
+            client: Arc::new(HttpClient::from_env(Some(&certs))),
+            ..self
+        })
+    }
+
+    /// Requests sent by this api (and its clones) so far, per file key
+    pub fn usage(&self) -> &ApiUsage {
+        &self.usage
+    }
+
+    fn record_usage(&self, file_key: &str, usage: FileUsage) {
+        self.usage.record(file_key, usage);
+    }
+
+    /// Sends the request built by `request` with the auth header of `access_token`.
+    ///
+    /// If the token is rejected and can be refreshed, the request is built and sent
+    /// once again with the fresh token.
+    fn send(
+        &self,
+        access_token: &str,
+        request: impl Fn() -> RequestBuilder<WithoutBody>,
+    ) -> Result<Response<Body>> {
+        let token = self.credentials.token(access_token);
+        let (name, value) = Credentials::header(&token);
+        let response = request().header(name, value).call()?;
+        if !matches!(
+            response.status(),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+        ) {
+            return Ok(response);
+        }
+        match self.credentials.refresh(access_token, &token) {
+            Some(fresh) => {
+                debug!(target: "Figma API", "access token rejected, retrying with a refreshed one");
+                let (name, value) = Credentials::header(&fresh);
+                Ok(request().header(name, value).call()?)
+            }
+            None => Ok(response),
+        }
+    }
+
+    /// Same as [`Self::send`], but revalidates the response remembered for the request
+    /// identified by `request_key`, if any. A fresh successful response is remembered
+    /// once its body is read, if the server sent validators with it.
+    fn send_cached(
+        &self,
+        access_token: &str,
+        request_key: &str,
+        request: impl Fn() -> RequestBuilder<WithoutBody>,
+    ) -> Result<Fetched> {
+        let http_cache = match (&self.http_cache, &self.mirror) {
+            (Some(http_cache), MirrorMode::Disabled) => http_cache,
+            _ => return Ok(Fetched::Fresh(self.send(access_token, request)?, None)),
+        };
+        let cached = http_cache.get(request_key);
+        let response = self.send(access_token, || {
+            let mut request = request();
+            if let Some(cached) = &cached {
+                if let Some(etag) = &cached.validators.etag {
+                    request = request.header("If-None-Match", etag);
+                }
+                if let Some(last_modified) = &cached.validators.last_modified {
+                    request = request.header("If-Modified-Since", last_modified);
+                }
+            }
+            request
+        })?;
+        match (response.status(), cached) {
+            (StatusCode::NOT_MODIFIED, Some(cached)) => {
+                debug!(target: "Figma API", "not modified: {request_key}");
+                Ok(Fetched::NotModified(cached.body))
+            }
+            (status, _) if status.is_success() => {
+                let pending = Validators::from_response(&response)
+                    .map(|it| PendingResponse::new(http_cache.clone(), request_key, it));
+                Ok(Fetched::Fresh(response, pending))
+            }
+            _ => Ok(Fetched::Fresh(response, None)),
+        }
+    }
+}
+
+enum Fetched {
+    /// The body must be read from the response and passed to the pending response, if any
+    Fresh(Response<Body>, Option<PendingResponse>),
+    /// Body of the remembered response, which is still up to date
+    NotModified(Vec<u8>),
+}
+
 macro_rules! set_query_if_needed {
     (arr: $request:path, $q:literal => $x:expr) => {
         if let Some(arr) = $x {
@@ -61,7 +231,6 @@ const fn mb(size_in_mb: u64) -> u64 {
 }
 
 impl FigmaApi {
-    const X_FIGMA_TOKEN: &str = "X-FIGMA-TOKEN";
     const BASE_URL: &str = "https://api.figma.com";
 
     /// Streaming: Parses the Figma API response on-the-fly, emitting `Node`s to the
@@ -74,22 +243,54 @@ impl FigmaApi {
         query: GetFileNodesStreamQueryParameters,
     ) -> Result<impl Iterator<Item = std::result::Result<Node, NodeStreamError>>> {
         debug!(target: "Figma API", "get_file_nodes_stream called for: {file_key}");
-        let mut request = self
-            .client
-            .get(format!(
+        if let MirrorMode::Replay(mirror) = &self.mirror {
+            let key = Mirror::nodes_key(file_key, &query);
+            let Some(bytes) = mirror.nodes.get(&key) else {
+                return Err(Error::Mirror(format!("document `{key}` is not mirrored")));
+            };
+            let reader: Box<dyn Read + Send> = Box::new(Cursor::new(bytes.clone()));
+            return Ok(NodeStream::from(reader));
+        }
+        let request = || {
+            let mut request = self.client.get(format!(
                 "{base_url}/v1/files/{file_key}/nodes",
-                base_url = Self::BASE_URL,
-            ))
-            .header(Self::X_FIGMA_TOKEN, access_token);
-        // region: queries
-        set_query_if_needed!(arr: request, "ids" => &query.ids);
-        set_query_if_needed!(num: request, "depth" => &query.depth);
-        set_query_if_needed!(txt: request, "geometry" => &query.geometry);
-        set_query_if_needed!(txt: request, "version" => &query.version);
-        // endregion: queries
+                base_url = self.base_url,
+            ));
+            // region: queries
+            set_query_if_needed!(arr: request, "ids" => &query.ids);
+            set_query_if_needed!(num: request, "depth" => &query.depth);
+            set_query_if_needed!(txt: request, "geometry" => &query.geometry);
+            set_query_if_needed!(txt: request, "version" => &query.version);
+            // endregion: queries
+            request
+        };
+        let request_key = format!(
+            "nodes:{file_key}?ids={:?}&depth={:?}&geometry={:?}&version={:?}",
+            query.ids, query.depth, query.geometry, query.version,
+        );
 
         // region: handling rate limits
-        let response = request.call()?;
+        let (response, pending) = match self.send_cached(access_token, &request_key, request)? {
+            Fetched::Fresh(response, pending) => (response, pending),
+            Fetched::NotModified(bytes) => {
+                self.record_usage(
+                    file_key,
+                    FileUsage {
+                        document_requests: 1,
+                        ..Default::default()
+                    },
+                );
+                let reader: Box<dyn Read + Send> = Box::new(Cursor::new(bytes));
+                return Ok(NodeStream::from(reader));
+            }
+        };
+        self.record_usage(
+            file_key,
+            FileUsage {
+                document_requests: 1,
+                ..Default::default()
+            },
+        );
         if response.status() == StatusCode::TOO_MANY_REQUESTS {
             let retry_after_sec = response
                 .headers()
@@ -121,7 +322,26 @@ impl FigmaApi {
         }
         // endregion: handling rate limits
 
-        let reader = response.into_body().into_reader();
+        let reader = CountingReader::new(
+            response.into_body().into_reader(),
+            file_key,
+            self.usage.clone(),
+        );
+        let reader: Box<dyn Read + Send> = match (&self.mirror, pending) {
+            (MirrorMode::Record(mirror), _) => {
+                // the whole document is needed for the mirror, so streaming makes no sense
+                let mut bytes = Vec::new();
+                let mut reader = reader;
+                reader
+                    .read_to_end(&mut bytes)
+                    .map_err(|e| Error::Ureq(ureq::Error::Io(e)))?;
+                let key = Mirror::nodes_key(file_key, &query);
+                mirror.lock().unwrap().nodes.insert(key, bytes.clone());
+                Box::new(Cursor::new(bytes))
+            }
+            (_, Some(pending)) => Box::new(pending.reader(reader)),
+            _ => Box::new(reader),
+        };
         debug!(target: "Figma API", "get_file_nodes_stream done for: {file_key}");
         Ok(NodeStream::from(reader))
     }
@@ -137,57 +357,94 @@ impl FigmaApi {
         query: GetFileNodesScanQueryParameters,
     ) -> Result<GetFileNodesScanResponse> {
         debug!(target: "Figma API", "get_file_nodes_scan called for: {file_key}");
-        let mut request = self
-            .client
-            .get(format!(
+        let request = || {
+            let mut request = self.client.get(format!(
                 "{base_url}/v1/files/{file_key}/nodes",
-                base_url = Self::BASE_URL,
-            ))
-            .header(Self::X_FIGMA_TOKEN, access_token);
-        // region: queries
-        set_query_if_needed!(arr: request, "ids" => &query.ids);
-        set_query_if_needed!(num: request, "depth" => &query.depth);
-        set_query_if_needed!(txt: request, "version" => &query.version);
-        // endregion: queries
-
-        // region: handling rate limits
-        let mut response = request.call()?;
-        if response.status() == StatusCode::TOO_MANY_REQUESTS {
-            let retry_after_sec = response
-                .headers()
-                .get("Retry-After")
-                .and_then(|val| val.to_str().ok())
-                .and_then(|val| val.parse().ok())
-                .unwrap_or(5);
-            let figma_plan_tier = response
-                .headers()
-                .get("X-Figma-Plan-Tier")
-                .and_then(|val| val.to_str().ok())
-                .unwrap_or("")
-                .to_string();
-            let figma_limit_type = response
-                .headers()
-                .get("X-Figma-Rate-Limit-Type")
-                .and_then(|val| val.to_str().ok())
-                .unwrap_or("")
-                .to_string();
-
-            return Err(crate::Error::RateLimit {
-                retry_after_sec,
-                figma_plan_tier,
-                figma_limit_type,
-            });
-        }
-        if !response.status().is_success() {
-            return Err(ureq::Error::StatusCode(response.status().as_u16()).into());
-        }
-        // endregion: handling rate limits
-
-        let response = response
-            .body_mut()
-            .with_config()
-            .limit(mb(1024))
-            .read_json::<GetFileNodesScanResponse>()?;
+                base_url = self.base_url,
+            ));
+            // region: queries
+            set_query_if_needed!(arr: request, "ids" => &query.ids);
+            set_query_if_needed!(num: request, "depth" => &query.depth);
+            set_query_if_needed!(txt: request, "version" => &query.version);
+            // endregion: queries
+            request
+        };
+
+        let request_key = format!(
+            "scan:{file_key}?ids={:?}&depth={:?}&version={:?}",
+            query.ids, query.depth, query.version,
+        );
+        let bytes = match self.send_cached(access_token, &request_key, request)? {
+            Fetched::NotModified(bytes) => {
+                self.record_usage(
+                    file_key,
+                    FileUsage {
+                        document_requests: 1,
+                        ..Default::default()
+                    },
+                );
+                bytes
+            }
+            Fetched::Fresh(mut response, pending) => {
+                // region: handling rate limits
+                self.record_usage(
+                    file_key,
+                    FileUsage {
+                        document_requests: 1,
+                        ..Default::default()
+                    },
+                );
+                if response.status() == StatusCode::TOO_MANY_REQUESTS {
+                    let retry_after_sec = response
+                        .headers()
+                        .get("Retry-After")
+                        .and_then(|val| val.to_str().ok())
+                        .and_then(|val| val.parse().ok())
+                        .unwrap_or(5);
+                    let figma_plan_tier = response
+                        .headers()
+                        .get("X-Figma-Plan-Tier")
+                        .and_then(|val| val.to_str().ok())
+                        .unwrap_or("")
+                        .to_string();
+                    let figma_limit_type = response
+                        .headers()
+                        .get("X-Figma-Rate-Limit-Type")
+                        .and_then(|val| val.to_str().ok())
+                        .unwrap_or("")
+                        .to_string();
+
+                    return Err(crate::Error::RateLimit {
+                        retry_after_sec,
+                        figma_plan_tier,
+                        figma_limit_type,
+                    });
+                }
+                if !response.status().is_success() {
+                    return Err(ureq::Error::StatusCode(response.status().as_u16()).into());
+                }
+                // endregion: handling rate limits
+
+                let bytes = response
+                    .body_mut()
+                    .with_config()
+                    .limit(mb(1024))
+                    .read_to_vec()?;
+                self.record_usage(
+                    file_key,
+                    FileUsage {
+                        document_bytes: bytes.len() as u64,
+                        ..Default::default()
+                    },
+                );
+                if let Some(pending) = pending {
+                    pending.complete(&bytes);
+                }
+                bytes
+            }
+        };
+        let response = serde_json::from_slice::<GetFileNodesScanResponse>(&bytes)
+            .map_err(|e| Error::Ureq(ureq::Error::Io(e.into())))?;
         debug!(target: "Figma API", "get_file_nodes_scan done for: {file_key}");
         Ok(response)
     }
@@ -199,27 +456,46 @@ impl FigmaApi {
         query: GetImageQueryParameters,
     ) -> Result<GetImageResponse> {
         debug!(target: "Figma API", "get_image called for: {file_key}/{:?}", query.ids);
-        let mut request = self
-            .client
-            .get(format!(
+        if let MirrorMode::Replay(mirror) = &self.mirror {
+            let mut images = HashMap::new();
+            for id in query.ids.unwrap_or_default() {
+                let key = Mirror::image_key(file_key, id, &query);
+                let Some(url) = mirror.images.get(&key) else {
+                    return Err(Error::Mirror(format!("export `{key}` is not mirrored")));
+                };
+                images.insert(id.to_owned(), url.to_owned());
+            }
+            return Ok(GetImageResponse { images });
+        }
+        let request = || {
+            let mut request = self.client.get(format!(
                 "{base_url}/v1/images/{file_key}",
-                base_url = Self::BASE_URL,
-            ))
-            .header(Self::X_FIGMA_TOKEN, access_token);
-        // region: queries
-        set_query_if_needed!(arr: request, "ids" => &query.ids);
-        set_query_if_needed!(num: request, "scale" => &query.scale);
-        set_query_if_needed!(txt: request, "format" => &query.format);
-        set_query_if_needed!(bln: request, "svg_outline_text" => &query.svg_outline_text);
-        set_query_if_needed!(bln: request, "svg_include_id" => &query.svg_include_id);
-        set_query_if_needed!(bln: request, "svg_simplify_stroke" => &query.svg_simplify_stroke);
-        set_query_if_needed!(bln: request, "contents_only" => &query.contents_only);
-        set_query_if_needed!(bln: request, "use_absolute_bounds" => &query.use_absolute_bounds);
-        set_query_if_needed!(txt: request, "version" => &query.version);
-        // endregion: queries
+                base_url = self.base_url,
+            ));
+            // region: queries
+            set_query_if_needed!(arr: request, "ids" => &query.ids);
+            set_query_if_needed!(num: request, "scale" => &query.scale);
+            set_query_if_needed!(txt: request, "format" => &query.format);
+            set_query_if_needed!(bln: request, "svg_outline_text" => &query.svg_outline_text);
+            set_query_if_needed!(bln: request, "svg_include_id" => &query.svg_include_id);
+            set_query_if_needed!(bln: request, "svg_simplify_stroke" => &query.svg_simplify_stroke);
+            set_query_if_needed!(bln: request, "contents_only" => &query.contents_only);
+            set_query_if_needed!(bln: request, "use_absolute_bounds" => &query.use_absolute_bounds);
+            set_query_if_needed!(txt: request, "version" => &query.version);
+            // endregion: queries
+            request
+        };
 
         // region: handling rate limits
-        let mut response = request.call()?;
+        let mut response = self.send(access_token, request)?;
+        self.record_usage(
+            file_key,
+            FileUsage {
+                export_requests: 1,
+                exported_nodes: query.ids.map(|it| it.len()).unwrap_or_default(),
+                ..Default::default()
+            },
+        );
         if response.status() == StatusCode::TOO_MANY_REQUESTS {
             let retry_after_sec = response
                 .headers()
@@ -256,22 +532,221 @@ impl FigmaApi {
             .with_config()
             .limit(mb(50))
             .read_json::<GetImageResponse>()?;
+        if let MirrorMode::Record(mirror) = &self.mirror {
+            let mut mirror = mirror.lock().unwrap();
+            for (id, url) in &response.images {
+                let key = Mirror::image_key(file_key, id, &query);
+                mirror.images.insert(key, url.to_owned());
+            }
+        }
         debug!(target: "Figma API", "get_image done for: {file_key}/{:?}", query.ids);
         Ok(response)
     }
 
-    pub fn download_resource(&self, access_token: &str, url: &str) -> Result<Bytes> {
+    /// Gets local variables of the file and their collections.
+    ///
+    /// Available only for members of Enterprise orgs with `file_variables:read` scope.
+    pub fn get_local_variables(
+        &self,
+        access_token: &str,
+        file_key: &str,
+    ) -> Result<GetLocalVariablesResponse> {
+        debug!(target: "Figma API", "get_local_variables called for: {file_key}");
+        let key = Mirror::variables_key(file_key);
+        if let MirrorMode::Replay(mirror) = &self.mirror {
+            let Some(bytes) = mirror.nodes.get(&key) else {
+                return Err(Error::Mirror(format!("variables `{key}` are not mirrored")));
+            };
+            return serde_json::from_slice(bytes)
+                .map_err(|e| Error::Mirror(format!("variables `{key}` are corrupted: {e}")));
+        }
+        let request = || {
+            self.client.get(format!(
+                "{base_url}/v1/files/{file_key}/variables/local",
+                base_url = self.base_url,
+            ))
+        };
+
+        let request_key = format!("variables:{file_key}");
+        let bytes = match self.send_cached(access_token, &request_key, request)? {
+            Fetched::NotModified(bytes) => {
+                self.record_usage(
+                    file_key,
+                    FileUsage {
+                        variables_requests: 1,
+                        ..Default::default()
+                    },
+                );
+                bytes
+            }
+            Fetched::Fresh(mut response, pending) => {
+                // region: handling rate limits
+                self.record_usage(
+                    file_key,
+                    FileUsage {
+                        variables_requests: 1,
+                        ..Default::default()
+                    },
+                );
+                if response.status() == StatusCode::TOO_MANY_REQUESTS {
+                    let retry_after_sec = response
+                        .headers()
+                        .get("Retry-After")
+                        .and_then(|val| val.to_str().ok())
+                        .and_then(|val| val.parse().ok())
+                        .unwrap_or(5);
+                    let figma_plan_tier = response
+                        .headers()
+                        .get("X-Figma-Plan-Tier")
+                        .and_then(|val| val.to_str().ok())
+                        .unwrap_or("")
+                        .to_string();
+                    let figma_limit_type = response
+                        .headers()
+                        .get("X-Figma-Rate-Limit-Type")
+                        .and_then(|val| val.to_str().ok())
+                        .unwrap_or("")
+                        .to_string();
+
+                    return Err(crate::Error::RateLimit {
+                        retry_after_sec,
+                        figma_plan_tier,
+                        figma_limit_type,
+                    });
+                }
+                if !response.status().is_success() {
+                    return Err(ureq::Error::StatusCode(response.status().as_u16()).into());
+                }
+                // endregion: handling rate limits
+
+                let bytes = response
+                    .body_mut()
+                    .with_config()
+                    .limit(mb(50))
+                    .read_to_vec()?;
+                if let Some(pending) = pending {
+                    pending.complete(&bytes);
+                }
+                bytes
+            }
+        };
+        let response = serde_json::from_slice::<GetLocalVariablesResponse>(&bytes)
+            .map_err(|e| Error::Ureq(ureq::Error::Io(e.into())))?;
+        if let MirrorMode::Record(mirror) = &self.mirror {
+            mirror.lock().unwrap().nodes.insert(key, bytes);
+        }
+        debug!(target: "Figma API", "get_local_variables done for: {file_key}");
+        Ok(response)
+    }
+
+    /// Gets branches of the file, the metadata of the file itself is skipped
+    pub fn get_file_branches(
+        &self,
+        access_token: &str,
+        file_key: &str,
+    ) -> Result<GetFileBranchesResponse> {
+        debug!(target: "Figma API", "get_file_branches called for: {file_key}");
+        let key = Mirror::branches_key(file_key);
+        if let MirrorMode::Replay(mirror) = &self.mirror {
+            let Some(bytes) = mirror.nodes.get(&key) else {
+                return Err(Error::Mirror(format!("branches `{key}` are not mirrored")));
+            };
+            return serde_json::from_slice(bytes)
+                .map_err(|e| Error::Mirror(format!("branches `{key}` are corrupted: {e}")));
+        }
+        let request = || {
+            self.client
+                .get(format!(
+                    "{base_url}/v1/files/{file_key}",
+                    base_url = self.base_url,
+                ))
+                .query("branch_data", "true")
+                // only the document node, the tree of the file is not needed
+                .query("depth", "1")
+        };
+
+        let request_key = format!("branches:{file_key}");
+        let bytes = match self.send_cached(access_token, &request_key, request)? {
+            Fetched::NotModified(bytes) => bytes,
+            Fetched::Fresh(mut response, pending) => {
+                self.record_usage(
+                    file_key,
+                    FileUsage {
+                        document_requests: 1,
+                        ..Default::default()
+                    },
+                );
+                if response.status() == StatusCode::TOO_MANY_REQUESTS {
+                    let retry_after_sec = response
+                        .headers()
+                        .get("Retry-After")
+                        .and_then(|val| val.to_str().ok())
+                        .and_then(|val| val.parse().ok())
+                        .unwrap_or(5);
+                    let figma_plan_tier = response
+                        .headers()
+                        .get("X-Figma-Plan-Tier")
+                        .and_then(|val| val.to_str().ok())
+                        .unwrap_or("")
+                        .to_string();
+                    let figma_limit_type = response
+                        .headers()
+                        .get("X-Figma-Rate-Limit-Type")
+                        .and_then(|val| val.to_str().ok())
+                        .unwrap_or("")
+                        .to_string();
+
+                    return Err(crate::Error::RateLimit {
+                        retry_after_sec,
+                        figma_plan_tier,
+                        figma_limit_type,
+                    });
+                }
+                if !response.status().is_success() {
+                    return Err(ureq::Error::StatusCode(response.status().as_u16()).into());
+                }
+                let bytes = response
+                    .body_mut()
+                    .with_config()
+                    .limit(mb(50))
+                    .read_to_vec()?;
+                if let Some(pending) = pending {
+                    pending.complete(&bytes);
+                }
+                bytes
+            }
+        };
+        let response = serde_json::from_slice::<GetFileBranchesResponse>(&bytes)
+            .map_err(|e| Error::Ureq(ureq::Error::Io(e.into())))?;
+        if let MirrorMode::Record(mirror) = &self.mirror {
+            mirror.lock().unwrap().nodes.insert(key, bytes);
+        }
+        debug!(target: "Figma API", "get_file_branches done for: {file_key}");
+        Ok(response)
+    }
+
+    pub fn download_resource(&self, url: &str) -> Result<Bytes> {
         debug!(target: "Figma API", "download_resource called for: {url}");
-        let request = self
+        if let MirrorMode::Replay(mirror) = &self.mirror {
+            return match mirror.resources.get(url) {
+                Some(bytes) => Ok(Bytes::from(bytes.clone())),
+                None => Err(Error::Mirror(format!("resource `{url}` is not mirrored"))),
+            };
+        }
+        // rendered images are served by the storage from presigned urls,
+        // which reject any other authorization
+        let buf = self
             .client
             .get(url)
-            .header(Self::X_FIGMA_TOKEN, access_token);
-        let buf = request
             .call()?
             .body_mut()
             .with_config()
             .limit(mb(50))
             .read_to_vec()?;
+        if let MirrorMode::Record(mirror) = &self.mirror {
+            let mut mirror = mirror.lock().unwrap();
+            mirror.resources.insert(url.to_owned(), buf.clone());
+        }
         debug!(target: "Figma API", "download_resource done for: {url}");
         Ok(bytes::Bytes::from(buf))
     }
@@ -300,6 +775,9 @@ pub struct GetFileNodesScanQueryParameters<'a> {
 
 #[derive(Debug, Deserialize)]
 pub struct GetFileNodesScanResponse {
+    /// Version of the file the nodes belong to
+    #[serde(default)]
+    pub version: Option<String>,
     pub nodes: HashMap<String, RootNodeDto>,
 }
 
@@ -356,3 +834,101 @@ pub struct GetImageResponse {
 }
 
 // endregion: GET image
+
+// region: GET file branches
+
+#[derive(Debug, Deserialize)]
+pub struct GetFileBranchesResponse {
+    /// Empty for the files without branches and for the branches themselves
+    #[serde(default)]
+    pub branches: Vec<BranchDto>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BranchDto {
+    /// Key of the branch, used instead of the key of the main file
+    pub key: String,
+    pub name: String,
+}
+
+impl GetFileBranchesResponse {
+    /// Key of the branch with the given name, branch names are unique within the file
+    pub fn branch_key(&self, name: &str) -> Option<&str> {
+        self.branches
+            .iter()
+            .find(|it| it.name == name)
+            .map(|it| it.key.as_str())
+    }
+}
+
+// endregion: GET file branches
+
+// region: GET local variables
+
+#[derive(Debug, Deserialize)]
+pub struct GetLocalVariablesResponse {
+    pub meta: LocalVariablesMetaDto,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LocalVariablesMetaDto {
+    /// Variable ID => Variable
+    #[serde(default)]
+    pub variables: HashMap<String, VariableDto>,
+    /// Collection ID => Collection
+    #[serde(default, rename = "variableCollections")]
+    pub variable_collections: HashMap<String, VariableCollectionDto>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VariableDto {
+    pub id: String,
+    /// Full name of the variable including groups, e.g. `color/Primary`
+    pub name: String,
+    #[serde(rename = "variableCollectionId")]
+    pub variable_collection_id: String,
+    /// `BOOLEAN`, `FLOAT`, `STRING` or `COLOR`
+    #[serde(rename = "resolvedType")]
+    pub resolved_type: String,
+    /// Mode ID => Value
+    #[serde(default, rename = "valuesByMode")]
+    pub values_by_mode: HashMap<String, VariableValueDto>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VariableCollectionDto {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "defaultModeId")]
+    pub default_mode_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum VariableValueDto {
+    Alias(VariableAliasDto),
+    Color(RgbaDto),
+    Other(serde::de::IgnoredAny),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VariableAliasDto {
+    pub r#type: String,
+    pub id: String,
+}
+
+/// Color components in range `0.0..=1.0`
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct RgbaDto {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    #[serde(default = "opaque")]
+    pub a: f32,
+}
+
+fn opaque() -> f32 {
+    1.0
+}
+
+// endregion: GET local variables
@@ -8,6 +8,7 @@ pub enum Error {
         figma_plan_tier: String,
         figma_limit_type: String,
     },
+    Mirror(String),
 }
 
 impl std::error::Error for Error {}
@@ -23,6 +24,7 @@ impl std::fmt::Display for Error {
                 f,
                 "rate limit: retry after {retry_after_sec}s, (tier={figma_plan_tier}, type={figma_limit_type})"
             ),
+            Self::Mirror(e) => write!(f, "mirror: {e}"),
         }
     }
 }
@@ -0,0 +1,191 @@
+use bincode::{Decode, Encode};
+use lib_cache::{Cache, CacheKey};
+use log::debug;
+use std::io::Read;
+use ureq::{Body, http::Response};
+
+const HTTP_RESPONSE_TAG: u8 = 0x47;
+
+/// Larger bodies are not remembered, so that huge documents do not bloat the cache
+const MAX_CACHED_BODY: usize = 128 * 1024 * 1024;
+
+/// Responses of the document endpoints remembered together with their validators,
+/// so that they can be revalidated with conditional requests.
+#[derive(Clone)]
+pub(crate) struct HttpCache(Cache);
+
+#[derive(Encode, Decode)]
+pub(crate) struct CachedResponse {
+    pub validators: Validators,
+    pub body: Vec<u8>,
+}
+
+/// `ETag` and `Last-Modified` headers of the response
+#[derive(Clone, Encode, Decode)]
+pub(crate) struct Validators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl HttpCache {
+    pub fn new(cache: Cache) -> Self {
+        Self(cache)
+    }
+
+    /// Response remembered for the request identified by `request_key`
+    pub fn get(&self, request_key: &str) -> Option<CachedResponse> {
+        match self.0.get::<CachedResponse>(&Self::cache_key(request_key)) {
+            Ok(cached) => cached,
+            Err(e) => {
+                debug!(target: "Figma API", "cached response of {request_key} is unavailable: {e}");
+                None
+            }
+        }
+    }
+
+    fn put(&self, request_key: &str, validators: Validators, body: Vec<u8>) {
+        let cached = CachedResponse { validators, body };
+        if let Err(e) = self.0.put(&Self::cache_key(request_key), &cached) {
+            debug!(target: "Figma API", "unable to cache response of {request_key}: {e}");
+        }
+    }
+
+    fn cache_key(request_key: &str) -> CacheKey {
+        CacheKey::builder()
+            .set_tag(HTTP_RESPONSE_TAG)
+            .write_str(request_key)
+            .build()
+    }
+}
+
+impl Validators {
+    /// Validators of the `response`, if the server sent any
+    pub fn from_response(response: &Response<Body>) -> Option<Self> {
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|it| it.to_str().ok())
+                .map(str::to_owned)
+        };
+        match (header("ETag"), header("Last-Modified")) {
+            (None, None) => None,
+            (etag, last_modified) => Some(Self {
+                etag,
+                last_modified,
+            }),
+        }
+    }
+}
+
+/// Fresh response which is going to be remembered once its body is read
+pub(crate) struct PendingResponse {
+    cache: HttpCache,
+    request_key: String,
+    validators: Validators,
+}
+
+impl PendingResponse {
+    pub fn new(cache: HttpCache, request_key: &str, validators: Validators) -> Self {
+        Self {
+            cache,
+            request_key: request_key.to_owned(),
+            validators,
+        }
+    }
+
+    pub fn complete(self, body: &[u8]) {
+        if body.len() <= MAX_CACHED_BODY {
+            self.cache
+                .put(&self.request_key, self.validators, body.to_vec());
+        }
+    }
+
+    /// Wraps the streamed body, so that it is remembered when read to the end
+    pub fn reader<R: Read>(self, inner: R) -> CachingReader<R> {
+        CachingReader {
+            inner,
+            body: Some(Vec::new()),
+            pending: Some(self),
+        }
+    }
+}
+
+pub(crate) struct CachingReader<R: Read> {
+    inner: R,
+    /// `None` once the body turned out to be too large to remember
+    body: Option<Vec<u8>>,
+    pending: Option<PendingResponse>,
+}
+
+impl<R: Read> Read for CachingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        if n == 0 {
+            if let (Some(body), Some(pending)) = (self.body.take(), self.pending.take()) {
+                pending.complete(&body);
+            }
+            return Ok(0);
+        }
+        match &mut self.body {
+            Some(body) if body.len() + n <= MAX_CACHED_BODY => body.extend_from_slice(&buf[..n]),
+            _ => self.body = None,
+        }
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use lib_cache::CacheConfig;
+    use std::io::Cursor;
+
+    #[test]
+    fn CachingReader__read_to_end__EXPECT__body_remembered_with_validators() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let cache = HttpCache::new(Cache::new(dir.path(), CacheConfig::default()).unwrap());
+        let validators = Validators {
+            etag: Some("\"abc\"".to_owned()),
+            last_modified: None,
+        };
+        let pending = PendingResponse::new(cache.clone(), "nodes?ids=0:1", validators);
+
+        // When
+        let mut body = Vec::new();
+        pending
+            .reader(Cursor::new(b"{\"nodes\":{}}".to_vec()))
+            .read_to_end(&mut body)
+            .unwrap();
+
+        // Then
+        let cached = cache.get("nodes?ids=0:1").unwrap();
+        assert_eq!(body, cached.body);
+        assert_eq!(Some("\"abc\"".to_owned()), cached.validators.etag);
+        assert!(cache.get("nodes?ids=0:2").is_none());
+    }
+
+    #[test]
+    fn CachingReader__partially_read__EXPECT__nothing_remembered() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let cache = HttpCache::new(Cache::new(dir.path(), CacheConfig::default()).unwrap());
+        let validators = Validators {
+            etag: Some("\"abc\"".to_owned()),
+            last_modified: None,
+        };
+        let pending = PendingResponse::new(cache.clone(), "nodes?ids=0:1", validators);
+
+        // When
+        let mut buf = [0u8; 4];
+        pending
+            .reader(Cursor::new(b"{\"nodes\":{}}".to_vec()))
+            .read_exact(&mut buf)
+            .unwrap();
+
+        // Then
+        assert!(cache.get("nodes?ids=0:1").is_none());
+    }
+}
@@ -1,7 +1,16 @@
+mod credentials;
 mod data;
 mod error;
+mod http_cache;
+mod mirror;
+mod network;
 mod node_stream;
+mod usage;
+pub use credentials::TokenRefresher;
 pub use data::*;
 pub use error::*;
+pub use mirror::*;
 pub use node_stream::Node;
-pub use node_stream::NodeStreamError;
\ No newline at end of file
+pub use node_stream::NodeSize;
+pub use node_stream::NodeStreamError;
+pub use usage::{ApiUsage, FileUsage};
@@ -0,0 +1,312 @@
+use crate::{Error, GetFileNodesStreamQueryParameters, GetImageQueryParameters, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    io::{BufReader, BufWriter, Read},
+    path::Path,
+};
+
+/// Figma API responses recorded by `figx mirror`.
+///
+/// The mirror is saved as a `.tar.zst` archive and can replace the network
+/// on machines without access to Figma.
+#[derive(Default)]
+pub struct Mirror {
+    /// `file_key?query` => raw JSON of the file nodes response,
+    /// `file_key/variables` => raw JSON of the local variables response,
+    /// `file_key/branches` => raw JSON of the file branches response
+    pub(crate) nodes: HashMap<String, Vec<u8>>,
+    /// `file_key:node_id?query` => download url
+    pub(crate) images: HashMap<String, Option<String>>,
+    /// download url => downloaded bytes
+    pub(crate) resources: HashMap<String, Vec<u8>>,
+}
+
+pub struct MirrorSummary {
+    pub documents: usize,
+    pub exports: usize,
+    pub resources: usize,
+    pub bytes: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ManifestDto {
+    version: u32,
+    nodes: BTreeMap<String, String>,
+    images: BTreeMap<String, Option<String>>,
+    resources: BTreeMap<String, String>,
+}
+
+const MANIFEST_FILE_NAME: &str = "manifest.json";
+const MANIFEST_VERSION: u32 = 2;
+
+impl Mirror {
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::try_load(path).map_err(|e| Error::Mirror(format!("{}: {e}", path.display())))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        self.try_save(path)
+            .map_err(|e| Error::Mirror(format!("{}: {e}", path.display())))
+    }
+
+    pub fn summary(&self) -> MirrorSummary {
+        MirrorSummary {
+            documents: self.nodes.len(),
+            exports: self.images.len(),
+            resources: self.resources.len(),
+            bytes: self.nodes.values().map(Vec::len).sum::<usize>()
+                + self.resources.values().map(Vec::len).sum::<usize>(),
+        }
+    }
+
+    pub(crate) fn nodes_key(file_key: &str, query: &GetFileNodesStreamQueryParameters) -> String {
+        let query = query_string(&[
+            ("ids", query.ids.map(|ids| ids.join(","))),
+            ("depth", query.depth.map(|it| it.to_string())),
+            ("geometry", query.geometry.map(str::to_owned)),
+            ("version", query.version.map(str::to_owned)),
+        ]);
+        format!("{file_key}?{query}")
+    }
+
+    pub(crate) fn variables_key(file_key: &str) -> String {
+        format!("{file_key}/variables")
+    }
+
+    pub(crate) fn branches_key(file_key: &str) -> String {
+        format!("{file_key}/branches")
+    }
+
+    /// Key of the single image `id`, so the ids batched together do not matter
+    pub(crate) fn image_key(file_key: &str, id: &str, query: &GetImageQueryParameters) -> String {
+        let query = query_string(&[
+            ("format", query.format.map(str::to_owned)),
+            ("scale", query.scale.map(|it| it.to_string())),
+            (
+                "svg_outline_text",
+                query.svg_outline_text.map(|it| it.to_string()),
+            ),
+            (
+                "svg_include_id",
+                query.svg_include_id.map(|it| it.to_string()),
+            ),
+            (
+                "svg_simplify_stroke",
+                query.svg_simplify_stroke.map(|it| it.to_string()),
+            ),
+            (
+                "contents_only",
+                query.contents_only.map(|it| it.to_string()),
+            ),
+            (
+                "use_absolute_bounds",
+                query.use_absolute_bounds.map(|it| it.to_string()),
+            ),
+            ("version", query.version.map(str::to_owned)),
+        ]);
+        format!("{file_key}:{id}?{query}")
+    }
+
+    fn try_load(path: &Path) -> std::io::Result<Self> {
+        let decoder = zstd::Decoder::new(BufReader::new(File::open(path)?))?;
+        let mut entries = HashMap::new();
+        for entry in tar::Archive::new(decoder).entries()? {
+            let mut entry = entry?;
+            let name = entry.path()?.to_string_lossy().to_string();
+            let mut bytes = Vec::with_capacity(entry.size() as usize);
+            entry.read_to_end(&mut bytes)?;
+            entries.insert(name, bytes);
+        }
+
+        let manifest = entries
+            .get(MANIFEST_FILE_NAME)
+            .ok_or_else(|| invalid_data("manifest is missing"))?;
+        let manifest: ManifestDto = serde_json::from_slice(manifest).map_err(invalid_data)?;
+        if manifest.version != MANIFEST_VERSION {
+            return Err(invalid_data(format!(
+                "unsupported mirror version {}",
+                manifest.version
+            )));
+        }
+        let mut take = |name: &str| {
+            entries
+                .remove(name)
+                .ok_or_else(|| invalid_data(format!("entry `{name}` is missing")))
+        };
+        let mut mirror = Mirror::default();
+        for (key, name) in &manifest.nodes {
+            mirror.nodes.insert(key.to_owned(), take(name)?);
+        }
+        for (url, name) in &manifest.resources {
+            mirror.resources.insert(url.to_owned(), take(name)?);
+        }
+        mirror.images = manifest.images.into_iter().collect();
+        Ok(mirror)
+    }
+
+    fn try_save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let encoder = zstd::Encoder::new(BufWriter::new(File::create(path)?), 0)?;
+        let mut archive = tar::Builder::new(encoder);
+
+        // sort entries for reproducible archives
+        let mut manifest = ManifestDto {
+            version: MANIFEST_VERSION,
+            nodes: BTreeMap::new(),
+            images: self.images.clone().into_iter().collect(),
+            resources: BTreeMap::new(),
+        };
+        let nodes = self.nodes.iter().collect::<BTreeMap<_, _>>();
+        for (idx, (key, bytes)) in nodes.into_iter().enumerate() {
+            let name = format!("nodes/{idx}.json");
+            append(&mut archive, &name, bytes)?;
+            manifest.nodes.insert(key.to_owned(), name);
+        }
+        let resources = self.resources.iter().collect::<BTreeMap<_, _>>();
+        for (idx, (url, bytes)) in resources.into_iter().enumerate() {
+            let name = format!("resources/{idx}");
+            append(&mut archive, &name, bytes)?;
+            manifest.resources.insert(url.to_owned(), name);
+        }
+        let manifest = serde_json::to_vec_pretty(&manifest).map_err(invalid_data)?;
+        append(&mut archive, MANIFEST_FILE_NAME, &manifest)?;
+
+        archive.into_inner()?.finish()?;
+        Ok(())
+    }
+}
+
+/// Specified query parameters in the order of the request
+fn query_string(params: &[(&str, Option<String>)]) -> String {
+    params
+        .iter()
+        .filter_map(|(name, value)| Some(format!("{name}={}", value.as_ref()?)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+fn append<W: std::io::Write>(
+    archive: &mut tar::Builder<W>,
+    name: &str,
+    bytes: &[u8],
+) -> std::io::Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    archive.append_data(&mut header, name, bytes)
+}
+
+fn invalid_data<E>(error: E) -> std::io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    std::io::Error::new(std::io::ErrorKind::InvalidData, error)
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn save_and_load__EXPECT__same_responses() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mirror.tar.zst");
+        let ids = ["0:1".to_string()];
+        let mut mirror = Mirror::default();
+        mirror.nodes.insert(
+            Mirror::nodes_key(
+                "abc",
+                &GetFileNodesStreamQueryParameters {
+                    ids: Some(&ids),
+                    ..Default::default()
+                },
+            ),
+            br#"{"nodes":{}}"#.to_vec(),
+        );
+        mirror.images.insert(
+            Mirror::image_key(
+                "abc",
+                "1:2",
+                &GetImageQueryParameters {
+                    format: Some("svg"),
+                    scale: Some(1.0),
+                    ..Default::default()
+                },
+            ),
+            Some("https://example.com/1".to_string()),
+        );
+        mirror
+            .resources
+            .insert("https://example.com/1".to_string(), b"<svg/>".to_vec());
+
+        // When
+        mirror.save(&path).unwrap();
+        let loaded = Mirror::load(&path).unwrap();
+
+        // Then
+        assert_eq!(mirror.nodes, loaded.nodes);
+        assert_eq!(mirror.images, loaded.images);
+        assert_eq!(mirror.resources, loaded.resources);
+    }
+
+    #[test]
+    fn image_key__different_bounds__EXPECT__different_entries() {
+        // Given
+        let render_bounds = GetImageQueryParameters {
+            format: Some("svg"),
+            scale: Some(1.0),
+            ..Default::default()
+        };
+        let absolute_bounds = GetImageQueryParameters {
+            use_absolute_bounds: Some(true),
+            ..render_bounds
+        };
+        let mut mirror = Mirror::default();
+
+        // When
+        for (query, url) in [(&render_bounds, "render"), (&absolute_bounds, "absolute")] {
+            mirror.images.insert(
+                Mirror::image_key("abc", "1:2", query),
+                Some(url.to_string()),
+            );
+        }
+
+        // Then
+        assert_eq!(2, mirror.images.len());
+        assert_eq!(
+            Some(&Some("absolute".to_string())),
+            mirror
+                .images
+                .get(&Mirror::image_key("abc", "1:2", &absolute_bounds)),
+        );
+    }
+
+    #[test]
+    fn nodes_key__different_depth__EXPECT__different_entries() {
+        // Given
+        let ids = ["0:1".to_string()];
+        let full = GetFileNodesStreamQueryParameters {
+            ids: Some(&ids),
+            ..Default::default()
+        };
+        let shallow = GetFileNodesStreamQueryParameters {
+            ids: Some(&ids),
+            depth: Some(1),
+            ..Default::default()
+        };
+
+        // When
+        let full = Mirror::nodes_key("abc", &full);
+        let shallow = Mirror::nodes_key("abc", &shallow);
+
+        // Then
+        assert_ne!(full, shallow);
+    }
+}
@@ -0,0 +1,190 @@
+use log::{debug, warn};
+use std::{io, path::Path, time::Duration};
+use ureq::{
+    Proxy, RequestBuilder,
+    http::Uri,
+    tls::{Certificate, PemItem, RootCerts, TlsConfig},
+    typestate::WithoutBody,
+};
+
+/// Agents of the requests sent through the proxy from the environment and of the
+/// ones sent directly. The api and the storage of rendered images live on different
+/// hosts, so `NO_PROXY` is checked against the host of every request.
+pub(crate) struct HttpClient {
+    direct: ureq::Agent,
+    proxied: Option<ureq::Agent>,
+    no_proxy: Option<String>,
+}
+
+impl HttpClient {
+    /// Client which sends requests through the proxy from the environment and,
+    /// if `root_certs` are given, trusts only them instead of the bundled root certificates.
+    pub(crate) fn from_env(root_certs: Option<&[Certificate<'static>]>) -> Self {
+        Self::new(proxy_from_env(), env("NO_PROXY"), root_certs)
+    }
+
+    fn new(
+        proxy: Option<Proxy>,
+        no_proxy: Option<String>,
+        root_certs: Option<&[Certificate<'static>]>,
+    ) -> Self {
+        Self {
+            direct: build_agent(None, root_certs),
+            proxied: proxy.map(|proxy| build_agent(Some(proxy), root_certs)),
+            no_proxy,
+        }
+    }
+
+    pub(crate) fn get(&self, url: impl AsRef<str>) -> RequestBuilder<WithoutBody> {
+        let url = url.as_ref();
+        match &self.proxied {
+            Some(agent) if !self.is_excluded(url) => agent.get(url),
+            _ => self.direct.get(url),
+        }
+    }
+
+    /// Whether the host of the `url` is excluded from proxying by `NO_PROXY`
+    fn is_excluded(&self, url: &str) -> bool {
+        let Some(no_proxy) = &self.no_proxy else {
+            return false;
+        };
+        let uri = match url.parse::<Uri>() {
+            Ok(uri) => uri,
+            Err(_) => return false,
+        };
+        match uri.host() {
+            Some(host) => is_excluded(no_proxy, host),
+            None => false,
+        }
+    }
+}
+
+fn build_agent(proxy: Option<Proxy>, root_certs: Option<&[Certificate<'static>]>) -> ureq::Agent {
+    let mut config = ureq::Agent::config_builder()
+        .timeout_connect(Some(Duration::from_secs(15)))
+        .max_idle_connections(64)
+        .max_idle_connections_per_host(16)
+        .http_status_as_error(false) // handling manually
+        .proxy(proxy);
+    if let Some(certs) = root_certs {
+        config = config.tls_config(
+            TlsConfig::builder()
+                .root_certs(RootCerts::new_with_certs(certs))
+                .build(),
+        );
+    }
+    config.build().into()
+}
+
+/// Reads all certificates of the PEM bundle at `path`
+pub(crate) fn load_ca_bundle(path: &Path) -> io::Result<Vec<Certificate<'static>>> {
+    let invalid_bundle = |reason: String| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid CA bundle {}: {reason}", path.display()),
+        )
+    };
+    let pem = std::fs::read(path).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!("unable to read CA bundle {}: {e}", path.display()),
+        )
+    })?;
+    let mut certs = Vec::new();
+    for item in ureq::tls::parse_pem(&pem) {
+        match item.map_err(|e| invalid_bundle(e.to_string()))? {
+            PemItem::Certificate(cert) => certs.push(cert.to_owned()),
+            _ => continue, // private keys have nothing to do in a bundle of roots
+        }
+    }
+    if certs.is_empty() {
+        return Err(invalid_bundle("no certificates found".to_owned()));
+    }
+    debug!(target: "Figma API", "loaded {} certificates from {}", certs.len(), path.display());
+    Ok(certs)
+}
+
+/// Proxy from the `HTTPS_PROXY` (or `ALL_PROXY`) environment variable
+fn proxy_from_env() -> Option<Proxy> {
+    let url = env("HTTPS_PROXY").or_else(|| env("ALL_PROXY"))?;
+    match Proxy::new(&url) {
+        Ok(proxy) => {
+            debug!(target: "Figma API", "sending requests through proxy {url}");
+            Some(proxy)
+        }
+        Err(e) => {
+            warn!(target: "Figma API", "ignoring invalid proxy url `{url}`: {e}");
+            None
+        }
+    }
+}
+
+/// Proxy variables are spelled in both cases in the wild, the upper case wins
+fn env(name: &str) -> Option<String> {
+    std::env::var(name)
+        .or_else(|_| std::env::var(name.to_lowercase()))
+        .ok()
+        .filter(|it| !it.trim().is_empty())
+}
+
+/// Whether the `host` matches any entry of the comma separated `no_proxy` list.
+/// An entry matches the domain itself and all of its subdomains, `*` matches everything.
+fn is_excluded(no_proxy: &str, host: &str) -> bool {
+    no_proxy
+        .split(',')
+        .map(|it| it.trim().trim_start_matches("*.").trim_start_matches('.'))
+        .filter(|it| !it.is_empty())
+        .any(|entry| {
+            entry == "*"
+                || host == entry
+                || host
+                    .strip_suffix(entry)
+                    .is_some_and(|subdomain| subdomain.ends_with('.'))
+        })
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn is_excluded__various_no_proxy_lists__EXPECT__domains_and_subdomains_matched() {
+        assert!(is_excluded("*", "api.figma.com"));
+        assert!(is_excluded("localhost, figma.com", "api.figma.com"));
+        assert!(is_excluded(".figma.com", "api.figma.com"));
+        assert!(is_excluded("*.figma.com", "api.figma.com"));
+        assert!(is_excluded("api.figma.com", "api.figma.com"));
+        assert!(!is_excluded("notfigma.com", "api.figma.com"));
+        assert!(!is_excluded("localhost,,127.0.0.1", "api.figma.com"));
+        assert!(!is_excluded("", "api.figma.com"));
+    }
+
+    #[test]
+    fn HttpClient__no_proxy_of_storage_host__EXPECT__only_api_requests_proxied() {
+        // Given
+        let proxy = Proxy::new("http://proxy.corp:3128").unwrap();
+        let client = HttpClient::new(Some(proxy), Some("amazonaws.com".to_owned()), None);
+
+        // Then
+        assert!(!client.is_excluded("https://api.figma.com/v1/files/abc/nodes?ids=0:1"));
+        assert!(client.is_excluded(
+            "https://figma-alpha-api.s3.us-west-2.amazonaws.com/images/abc?X-Amz-Signature=1"
+        ));
+        assert!(!client.is_excluded("not a url"));
+    }
+
+    #[test]
+    fn load_ca_bundle__no_certificates__EXPECT__error() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bundle.pem");
+        std::fs::write(&path, "not a certificate").unwrap();
+
+        // When
+        let result = load_ca_bundle(&path);
+
+        // Then
+        assert_eq!(io::ErrorKind::InvalidData, result.unwrap_err().kind());
+    }
+}
@@ -1,7 +1,7 @@
 use json_event_parser::{JsonEvent, JsonParseError, ReaderJsonParser};
 use std::{collections::VecDeque, fmt::Display, hash::Hasher, io::Read};
 
-#[cfg_attr(test, derive(Debug, Eq, PartialEq, Hash))]
+#[cfg_attr(test, derive(Debug, PartialEq))]
 pub struct Node {
     pub id: String,
     pub name: String,
@@ -9,12 +9,30 @@ pub struct Node {
     pub r#type: String,
     pub has_raster_fills: bool,
     pub hash: u64,
+    /// Size of `absoluteBoundingBox`
+    pub bounds: Option<NodeSize>,
+    /// Size of `absoluteRenderBounds`, which includes effects and strokes
+    pub render_bounds: Option<NodeSize>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NodeSize {
+    pub width: f32,
+    pub height: f32,
 }
 
 pub struct NodeStream<R: Read> {
     reader: ReaderJsonParser<R>,
     stack: VecDeque<NodeDto>,
     state: NodeStreamState,
+    /// Set when the next object is the bounds of the current node
+    pending_bounds: Option<BoundsKind>,
+}
+
+#[derive(Clone, Copy)]
+pub enum BoundsKind {
+    Bounding,
+    Render,
 }
 
 enum NodeStreamState {
@@ -54,6 +72,7 @@ impl<R: Read> From<R> for NodeStream<R> {
             reader: ReaderJsonParser::new(value),
             stack: VecDeque::with_capacity(100),
             state: NodeStreamState::Default,
+            pending_bounds: None,
         }
     }
 }
@@ -65,7 +84,13 @@ pub struct NodeDto {
     pub visible: Option<bool>,
     pub r#type: Option<String>,
     pub has_raster_fills: bool,
-    pub hasher: xxhash_rust::xxh64::Xxh64,
+    pub hasher: lib_cache::Digester,
+    /// Which bounds of the parent node this object is, if it is not a node
+    pub bounds_of: Option<BoundsKind>,
+    pub width: Option<f32>,
+    pub height: Option<f32>,
+    pub bounds: Option<NodeSize>,
+    pub render_bounds: Option<NodeSize>,
 }
 
 macro_rules! parse_next {
@@ -98,6 +123,7 @@ impl<R: Read> Iterator for NodeStream<R> {
                 update_hash(dto, &event);
             }
 
+            let bounds_of = self.pending_bounds.take();
             use NodeStreamState::*;
             match self.state {
                 Default => match event {
@@ -109,10 +135,14 @@ impl<R: Read> Iterator for NodeStream<R> {
                         {
                             self.stack.push_back(NodeDto {
                                 visible: Some(false),
+                                bounds_of,
                                 ..NodeDto::default()
                             });
                         } else {
-                            self.stack.push_back(NodeDto::default())
+                            self.stack.push_back(NodeDto {
+                                bounds_of,
+                                ..NodeDto::default()
+                            })
                         }
                     }
                     JsonEvent::EndObject => {
@@ -122,6 +152,15 @@ impl<R: Read> Iterator for NodeStream<R> {
 
                         if let Some(parent) = self.stack.back_mut() {
                             parent.hasher.write_u64(dto.hasher.digest());
+                            let size = match (dto.width, dto.height) {
+                                (Some(width), Some(height)) => Some(NodeSize { width, height }),
+                                _ => None,
+                            };
+                            match dto.bounds_of {
+                                Some(BoundsKind::Bounding) => parent.bounds = size,
+                                Some(BoundsKind::Render) => parent.render_bounds = size,
+                                None => (),
+                            }
                         }
 
                         if let NodeDto {
@@ -131,6 +170,9 @@ impl<R: Read> Iterator for NodeStream<R> {
                             r#type: Some(r#type),
                             has_raster_fills,
                             hasher,
+                            bounds,
+                            render_bounds,
+                            ..
                         } = dto
                         {
                             return Some(Ok(Node {
@@ -140,6 +182,8 @@ impl<R: Read> Iterator for NodeStream<R> {
                                 r#type,
                                 has_raster_fills,
                                 hash: hasher.digest(),
+                                bounds,
+                                render_bounds,
                             }));
                         }
                     }
@@ -172,6 +216,22 @@ impl<R: Read> Iterator for NodeStream<R> {
                                 update_hash(dto, &JsonEvent::String(r#type));
                             }
                         }
+                        "width" => {
+                            let width = parse_next_value!(self.reader, JsonEvent::Number);
+                            if let (Some(dto), Some(width)) = (self.stack.back_mut(), width) {
+                                dto.width = width.parse().ok();
+                                update_hash(dto, &JsonEvent::Number(width));
+                            }
+                        }
+                        "height" => {
+                            let height = parse_next_value!(self.reader, JsonEvent::Number);
+                            if let (Some(dto), Some(height)) = (self.stack.back_mut(), height) {
+                                dto.height = height.parse().ok();
+                                update_hash(dto, &JsonEvent::Number(height));
+                            }
+                        }
+                        "absoluteBoundingBox" => self.pending_bounds = Some(BoundsKind::Bounding),
+                        "absoluteRenderBounds" => self.pending_bounds = Some(BoundsKind::Render),
                         "fills" => self.state = ExpectingFills,
                         _ => (), // just ignore
                     },
@@ -236,6 +296,8 @@ mod test {
             visible: true,
             r#type: "COMPONENT".to_string(),
             has_raster_fills: false,
+            bounds: None,
+            render_bounds: None,
             hash: 628479688892445678,
         }];
 
@@ -283,6 +345,8 @@ mod test {
                 r#type: "FRAME".to_string(),
                 has_raster_fills: false,
                 hash: 6074447386681386455,
+                bounds: None,
+                render_bounds: None,
             },
             Node {
                 id: "0-4".to_string(),
@@ -291,6 +355,8 @@ mod test {
                 r#type: "COMPONENT".to_string(),
                 has_raster_fills: false,
                 hash: 871105605844001166,
+                bounds: None,
+                render_bounds: None,
             },
         ];
 
@@ -319,6 +385,8 @@ mod test {
             r#type: "FRAME".to_string(),
             has_raster_fills: true,
             hash: 5252844981246604711,
+            bounds: None,
+            render_bounds: None,
         }];
 
         // When
@@ -366,6 +434,8 @@ mod test {
                 r#type: "FRAME".to_string(),
                 has_raster_fills: true,
                 hash: 14579911610367628434,
+                bounds: None,
+                render_bounds: None,
             },
             Node {
                 id: "0-4".to_string(),
@@ -374,6 +444,8 @@ mod test {
                 r#type: "COMPONENT".to_string(),
                 has_raster_fills: true,
                 hash: 3273161997491380655,
+                bounds: None,
+                render_bounds: None,
             },
         ];
 
@@ -385,6 +457,43 @@ mod test {
         assert_eq!(expected_nodes, actual_nodes);
     }
 
+    #[test]
+    fn parse_node_bounds_without_mixing_up_children_bounds() {
+        // Given
+        let json = r#"
+        {
+            "id":"0-1",
+            "name":"Icon / Coffee",
+            "type":"COMPONENT",
+            "absoluteBoundingBox": {"x":10,"y":20,"width":24,"height":24},
+            "absoluteRenderBounds": {"x":8,"y":18,"width":28.5,"height":28.5},
+            "children": [
+                {
+                    "id":"0-2",
+                    "name":"Cup",
+                    "type":"VECTOR",
+                    "absoluteBoundingBox": {"x":12,"y":22,"width":20,"height":20},
+                    "absoluteRenderBounds": null
+                }
+            ]
+        }
+        "#;
+
+        // When
+        let iter = NodeStream::from(BufReader::new(json.as_bytes()));
+        let actual_nodes = iter.collect::<std::result::Result<Vec<Node>, _>>().unwrap();
+
+        // Then
+        let [cup, coffee] = actual_nodes.as_slice() else {
+            panic!("expected two nodes");
+        };
+        let size = |width, height| Some(NodeSize { width, height });
+        assert_eq!(size(20.0, 20.0), cup.bounds);
+        assert_eq!(None, cup.render_bounds);
+        assert_eq!(size(24.0, 24.0), coffee.bounds);
+        assert_eq!(size(28.5, 28.5), coffee.render_bounds);
+    }
+
     #[test]
     fn similar_nodes_has_different_hash() {
         // Given
@@ -0,0 +1,136 @@
+use std::{
+    collections::BTreeMap,
+    io::Read,
+    sync::{Arc, Mutex},
+};
+
+/// Requests sent to the Figma REST API and bytes received, per file key.
+///
+/// Responses served from a mirror are not counted, they cost nothing.
+#[derive(Default)]
+pub struct ApiUsage(Mutex<BTreeMap<String, FileUsage>>);
+
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
+pub struct FileUsage {
+    /// Number of requested node documents
+    pub document_requests: usize,
+    /// Total size of the received node documents in bytes
+    pub document_bytes: u64,
+    /// Number of image export requests, one per batch of nodes
+    pub export_requests: usize,
+    /// Number of nodes requested for export
+    pub exported_nodes: usize,
+    /// Number of local variables requests
+    pub variables_requests: usize,
+}
+
+impl FileUsage {
+    /// Requests counted against the Tier 1 rate limit: file nodes and image exports
+    pub fn tier1_requests(&self) -> usize {
+        self.document_requests + self.export_requests
+    }
+
+    /// Requests counted against the Tier 2 rate limit: local variables
+    pub fn tier2_requests(&self) -> usize {
+        self.variables_requests
+    }
+
+    fn merge(&mut self, another: &FileUsage) {
+        self.document_requests += another.document_requests;
+        self.document_bytes += another.document_bytes;
+        self.export_requests += another.export_requests;
+        self.exported_nodes += another.exported_nodes;
+        self.variables_requests += another.variables_requests;
+    }
+}
+
+impl ApiUsage {
+    pub(crate) fn record(&self, file_key: &str, usage: FileUsage) {
+        let mut files = self.0.lock().unwrap();
+        match files.get_mut(file_key) {
+            Some(it) => it.merge(&usage),
+            None => {
+                files.insert(file_key.to_owned(), usage);
+            }
+        }
+    }
+
+    /// Usage of every file requested so far
+    pub fn snapshot(&self) -> BTreeMap<String, FileUsage> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+/// Counts bytes of the streamed document and records them when dropped,
+/// so partially read documents are counted too.
+pub(crate) struct CountingReader<R: Read> {
+    inner: R,
+    bytes: u64,
+    file_key: String,
+    usage: Arc<ApiUsage>,
+}
+
+impl<R: Read> CountingReader<R> {
+    pub(crate) fn new(inner: R, file_key: &str, usage: Arc<ApiUsage>) -> Self {
+        Self {
+            inner,
+            bytes: 0,
+            file_key: file_key.to_owned(),
+            usage,
+        }
+    }
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.bytes += n as u64;
+        Ok(n)
+    }
+}
+
+impl<R: Read> Drop for CountingReader<R> {
+    fn drop(&mut self) {
+        self.usage.record(
+            &self.file_key,
+            FileUsage {
+                document_bytes: self.bytes,
+                ..Default::default()
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn CountingReader__dropped_after_read__EXPECT__bytes_recorded() {
+        // Given
+        let usage = Arc::new(ApiUsage::default());
+        usage.record(
+            "key",
+            FileUsage {
+                document_requests: 1,
+                ..Default::default()
+            },
+        );
+
+        // When
+        let mut reader = CountingReader::new(Cursor::new(vec![0u8; 1000]), "key", usage.clone());
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf).unwrap();
+        drop(reader);
+
+        // Then
+        let expected = FileUsage {
+            document_requests: 1,
+            document_bytes: 1000,
+            ..Default::default()
+        };
+        assert_eq!(Some(&expected), usage.snapshot().get("key"));
+    }
+}
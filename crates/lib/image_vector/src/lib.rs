@@ -1,4 +1,6 @@
 mod model;
+mod monochrome;
+mod strokes;
 pub use model::*;
 
 #[cfg(feature = "usvg")]
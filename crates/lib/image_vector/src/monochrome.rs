@@ -0,0 +1,100 @@
+use crate::{Color, ImageVector, Node, PathNode};
+use colorsys::{ColorAlpha, Rgb};
+
+impl ImageVector {
+    /// Repaints every fill and stroke with the single `color`, keeping the shapes
+    /// and their opacity. Gradients are flattened to the solid color with the
+    /// opacity of their most opaque stop.
+    ///
+    /// Used for Android 13 themed icons, where only the alpha channel of the
+    /// monochrome layer matters and the color is chosen by the system.
+    pub fn into_monochrome(mut self, color: &Rgb) -> Self {
+        for node in self.nodes.iter_mut() {
+            flatten_node(node, color);
+        }
+        self
+    }
+}
+
+fn flatten_node(node: &mut Node, color: &Rgb) {
+    match node {
+        Node::Group(group) => {
+            for node in group.nodes.iter_mut() {
+                flatten_node(node, color);
+            }
+        }
+        Node::Path(path) => flatten_path(path, color),
+    }
+}
+
+fn flatten_path(path: &mut PathNode, color: &Rgb) {
+    if let Some(fill) = path.fill_color.take() {
+        path.alpha *= color_alpha(&fill);
+        path.fill_color = Some(Color::SolidColor(color.clone()));
+    }
+    if let Some(stroke) = path.stroke.color.take() {
+        path.stroke.alpha *= color_alpha(&stroke);
+        path.stroke.color = Some(Color::SolidColor(color.clone()));
+    }
+}
+
+fn color_alpha(color: &Color) -> f32 {
+    let max_alpha = |alphas: &mut dyn Iterator<Item = f64>| alphas.fold(0.0f64, f64::max) as f32;
+    match color {
+        Color::SolidColor(rgb) => rgb.alpha() as f32,
+        Color::LinearGradient(g) => max_alpha(&mut g.stops.iter().map(|it| it.color.alpha())),
+        Color::RadialGradient(g) => max_alpha(&mut g.stops.iter().map(|it| it.color.alpha())),
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use crate::{LinearGradient, LinearGradientStop, Stroke};
+
+    #[test]
+    fn into_monochrome__gradient_fill__EXPECT__solid_color_with_max_stop_alpha() {
+        // Given
+        let iv = ImageVector {
+            name: String::new(),
+            width: 24.0,
+            height: 24.0,
+            viewport_width: 24.0,
+            viewport_height: 24.0,
+            nodes: vec![Node::Path(PathNode {
+                fill_type: Default::default(),
+                fill_color: Some(Color::LinearGradient(LinearGradient {
+                    start_x: 0.0,
+                    start_y: 0.0,
+                    end_x: 24.0,
+                    end_y: 24.0,
+                    stops: vec![
+                        LinearGradientStop {
+                            offset: 0.0,
+                            color: Rgb::new(255.0, 0.0, 0.0, Some(0.25)),
+                        },
+                        LinearGradientStop {
+                            offset: 1.0,
+                            color: Rgb::new(0.0, 0.0, 255.0, Some(0.5)),
+                        },
+                    ],
+                })),
+                commands: Vec::new(),
+                alpha: 0.5,
+                stroke: Stroke::default(),
+            })],
+        };
+
+        // When
+        let iv = iv.into_monochrome(&Rgb::new(0.0, 0.0, 0.0, None));
+
+        // Then
+        let Node::Path(path) = &iv.nodes[0] else {
+            panic!("expected path node");
+        };
+        assert!(matches!(path.fill_color, Some(Color::SolidColor(_))));
+        assert_eq!(0.25, path.alpha);
+        assert!(path.stroke.color.is_none());
+    }
+}
@@ -0,0 +1,94 @@
+use crate::{ImageVector, Node};
+
+impl ImageVector {
+    /// Widths of the strokes which are thinner than one device pixel when the image
+    /// is drawn with `scale` pixels per point, e.g. `1.0` for Android `mdpi`.
+    ///
+    /// The widths are in device pixels, with the viewport and group scales applied.
+    /// Such strokes are antialiased into a blurry half-transparent line.
+    pub fn thin_strokes(&self, scale: f32) -> Vec<f32> {
+        let ratio = |size: f32, viewport: f32| match viewport > 0.0 {
+            true => size / viewport,
+            false => 1.0,
+        };
+        let px_per_unit = scale
+            * ratio(self.width, self.viewport_width).min(ratio(self.height, self.viewport_height));
+        let mut widths = Vec::new();
+        collect_thin_strokes(&self.nodes, px_per_unit, &mut widths);
+        widths
+    }
+}
+
+fn collect_thin_strokes(nodes: &[Node], px_per_unit: f32, widths: &mut Vec<f32>) {
+    for node in nodes {
+        match node {
+            Node::Group(group) => {
+                let scale = group.scale.x.abs().min(group.scale.y.abs());
+                collect_thin_strokes(&group.nodes, px_per_unit * scale, widths);
+            }
+            Node::Path(path) => {
+                if path.stroke.color.is_none() || path.stroke.width <= 0.0 {
+                    continue;
+                }
+                let width = path.stroke.width * px_per_unit;
+                if width < 1.0 {
+                    widths.push(width);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use crate::{Color, GroupNode, PathNode, Scale, Stroke, Translation};
+    use colorsys::Rgb;
+
+    fn stroked_path(width: f32) -> Node {
+        Node::Path(PathNode {
+            fill_type: Default::default(),
+            fill_color: None,
+            commands: Vec::new(),
+            alpha: 1.0,
+            stroke: Stroke {
+                color: Some(Color::SolidColor(Rgb::new(0.0, 0.0, 0.0, None))),
+                width,
+                ..Default::default()
+            },
+        })
+    }
+
+    #[test]
+    fn thin_strokes__scaled_group_and_viewport__EXPECT__widths_in_device_pixels() {
+        // Given
+        let iv = ImageVector {
+            name: String::new(),
+            width: 12.0,
+            height: 12.0,
+            viewport_width: 24.0,
+            viewport_height: 24.0,
+            nodes: vec![
+                stroked_path(2.0),
+                Node::Group(GroupNode {
+                    name: None,
+                    nodes: vec![stroked_path(2.0)],
+                    rotate: 0.0,
+                    pivot: Translation { x: 0.0, y: 0.0 },
+                    translation: Translation { x: 0.0, y: 0.0 },
+                    scale: Scale { x: 0.5, y: 0.5 },
+                    clip_path_data: None,
+                }),
+            ],
+        };
+
+        // When
+        let at_mdpi = iv.thin_strokes(1.0);
+        let at_xhdpi = iv.thin_strokes(2.0);
+
+        // Then
+        assert_eq!(vec![0.5], at_mdpi);
+        assert!(at_xhdpi.is_empty());
+    }
+}
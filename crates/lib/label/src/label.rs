@@ -90,14 +90,12 @@ impl From<PackageParsingError> for LabelError {
 
 #[cfg(test)]
 mod test {
-    use std::path::PathBuf;
-
     use super::*;
 
     #[test]
     fn test_create_from_package_and_name() {
         // Given
-        let package = Package(PathBuf::from("path/to/package"));
+        let package = Package::with_path("path/to/package").unwrap();
         let name = Name("res_name".to_string());
 
         // When
@@ -110,7 +108,7 @@ mod test {
     #[test]
     fn test_label_display() {
         // Given
-        let package = Package(PathBuf::from("path/to/package"));
+        let package = Package::with_path("path/to/package").unwrap();
         let name = Name("res_name".to_string());
 
         // When
@@ -123,7 +121,7 @@ mod test {
     #[test]
     fn test_label_debug() {
         // Given
-        let package = Package(PathBuf::from("path/to/package"));
+        let package = Package::with_path("path/to/package").unwrap();
         let name = Name("res_name".to_string());
 
         // When
@@ -133,6 +131,21 @@ mod test {
         assert_eq!("//path/to/package:res_name", format!("{label:?}"));
     }
 
+    #[test]
+    fn test_external_label_display() {
+        // Given
+        let package = Package::with_path("icons")
+            .unwrap()
+            .with_external("design_system");
+        let name = Name("ic_home".to_string());
+
+        // When
+        let label: Label = (package, name).into();
+
+        // Then
+        assert_eq!("@design_system//icons:ic_home", label.to_string());
+    }
+
     #[test]
     fn test_invalid_package() {
         // Given
@@ -158,4 +171,4 @@ mod test {
         // Then
         assert!(matches!(result, Err(LabelError::BadName(_))));
     }
-}
\ No newline at end of file
+}
@@ -3,8 +3,10 @@ mod matching;
 mod name;
 mod package;
 mod pathellipsis;
+mod tag;
 
 pub use label::*;
 pub use matching::*;
 pub use name::*;
 pub use package::*;
+pub use tag::*;
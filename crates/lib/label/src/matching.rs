@@ -12,6 +12,7 @@ use crate::{Label, Package};
 /// - `//foo/bar:baz`
 /// - `//foo/bar:*`
 /// - `//foo/...`
+/// - `@design_system//icons:*`
 ///
 /// Patterns can refer to:
 /// - specific targets,
@@ -29,6 +30,7 @@ use crate::{Label, Package};
 ///     target: TargetPattern::All,
 ///     absolute: true,
 ///     negative: false,
+///     external: None,
 /// };
 /// ```
 /// corresponds to the pattern `//foo/...:*`
@@ -44,6 +46,8 @@ pub struct LabelPatternImpl {
     pub target: TargetPattern,
     pub absolute: bool,
     pub negative: bool,
+    /// Name of the external workspace, e.g. `design_system` for `@design_system//...`
+    pub external: Option<String>,
 }
 
 /// A pattern used to match one or more packages.
@@ -52,7 +56,7 @@ pub struct LabelPatternImpl {
 /// e.g. `foo/bar` or `foo/...`.
 #[derive(Debug, PartialEq, Clone)]
 pub enum PackagePattern {
-    /// Matches all packages in the workspace (e.g. `//...`)
+    /// Matches all packages in the workspace (e.g. `//...` or `@design_system//...`)
     All,
 
     /// Matches exactly one package at the specified path (e.g. `//foo/bar`)
@@ -103,6 +107,7 @@ impl TryFrom<Vec<String>> for LabelPattern {
 pub enum PatternError {
     BadPackage(String, String),
     BadTarget(String, String),
+    BadTag(String, String),
 }
 
 impl std::error::Error for PatternError {}
@@ -120,17 +125,19 @@ fn parse_pattern(pattern: &str) -> Result<LabelPatternImpl, PatternError> {
     } else {
         (pattern, false)
     };
+    let (relevant_pattern, external) = split_external(pattern)?;
 
-    if pattern == "//..." {
+    if relevant_pattern == "//..." {
         return Ok(LabelPatternImpl {
             package: PackagePattern::All,
             target: TargetPattern::All,
             absolute: true,
             negative,
+            external,
         });
     }
 
-    if let Some((package, target)) = pattern.rsplit_once(':') {
+    if let Some((package, target)) = relevant_pattern.rsplit_once(':') {
         ensure_valid_package(package, pattern)?;
         ensure_valid_target(target, pattern)?;
         let is_absolute_path = package.starts_with("//");
@@ -149,9 +156,10 @@ fn parse_pattern(pattern: &str) -> Result<LabelPatternImpl, PatternError> {
             target: target_pattern,
             absolute: is_absolute_path,
             negative,
+            external,
         })
     } else {
-        let package = pattern;
+        let package = relevant_pattern;
         ensure_valid_package(package, pattern)?;
         let is_absolute_path = package.starts_with("//");
         let package_pattern = match package.trim_start_matches("//") {
@@ -164,10 +172,34 @@ fn parse_pattern(pattern: &str) -> Result<LabelPatternImpl, PatternError> {
             target: TargetPattern::All,
             absolute: is_absolute_path,
             negative,
+            external,
         })
     }
 }
 
+/// Splits `@name//foo:bar` into `//foo:bar` and `name`.
+/// External patterns are always absolute.
+fn split_external(pattern: &str) -> Result<(&str, Option<String>), PatternError> {
+    let Some(stripped) = pattern.strip_prefix('@') else {
+        return Ok((pattern, None));
+    };
+    let bad_package = || {
+        let package = pattern.rsplit_once(':').map_or(pattern, |(p, _)| p);
+        PatternError::BadPackage(pattern.to_string(), package.to_string())
+    };
+    let Some(pos) = stripped.find("//") else {
+        return Err(bad_package());
+    };
+    let (external, rest) = stripped.split_at(pos);
+    let only_allowed_chars = external
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if external.is_empty() || !only_allowed_chars {
+        return Err(bad_package());
+    }
+    Ok((rest, Some(external.to_string())))
+}
+
 fn ensure_valid_package(package: &str, pattern: &str) -> Result<(), PatternError> {
     let normalized_path = package.trim_start_matches("-").trim_start_matches("//");
     let full_path = PathBuf::from(normalized_path);
@@ -280,6 +312,9 @@ pub fn package_matches(pattern: &LabelPattern, package: &Package, current_dir: &
 }
 
 fn package_matches_impl(pattern: &LabelPatternImpl, package: &Package, current_dir: &Path) -> bool {
+    if pattern.external.as_deref() != package.external() {
+        return false;
+    }
     match (pattern.absolute, &pattern.package) {
         (true, PackagePattern::Exact(pattern)) => pattern == package.deref(),
         (false, PackagePattern::Exact(pattern)) => current_dir.join(pattern) == package.deref(),
@@ -322,6 +357,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: true,
                 negative: false,
+                external: None,
                 package: PackagePattern::Exact(PathBuf::from("foo/bar")),
                 target: TargetPattern::Exact("wiz".to_string()),
             }),
@@ -336,6 +372,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: true,
                 negative: true,
+                external: None,
                 package: PackagePattern::Exact(PathBuf::from("foo/bar")),
                 target: TargetPattern::Exact("wiz".to_string()),
             }),
@@ -350,6 +387,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: false,
                 negative: false,
+                external: None,
                 package: PackagePattern::Exact(PathBuf::from("foo/bar")),
                 target: TargetPattern::Exact("wiz".to_string()),
             }),
@@ -364,6 +402,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: false,
                 negative: true,
+                external: None,
                 package: PackagePattern::Exact(PathBuf::from("foo/bar")),
                 target: TargetPattern::Exact("wiz".to_string()),
             }),
@@ -378,6 +417,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: true,
                 negative: false,
+                external: None,
                 package: PackagePattern::Exact(PathBuf::from("foo/bar")),
                 target: TargetPattern::All,
             }),
@@ -392,6 +432,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: true,
                 negative: true,
+                external: None,
                 package: PackagePattern::Exact(PathBuf::from("foo/bar")),
                 target: TargetPattern::All,
             }),
@@ -406,6 +447,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: false,
                 negative: false,
+                external: None,
                 package: PackagePattern::Exact(PathBuf::from("foo/bar")),
                 target: TargetPattern::All,
             }),
@@ -420,6 +462,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: false,
                 negative: true,
+                external: None,
                 package: PackagePattern::Exact(PathBuf::from("foo/bar")),
                 target: TargetPattern::All,
             }),
@@ -434,6 +477,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: true,
                 negative: false,
+                external: None,
                 package: PackagePattern::Exact(PathBuf::from("foo/bar")),
                 target: TargetPattern::All,
             }),
@@ -448,6 +492,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: false,
                 negative: false,
+                external: None,
                 package: PackagePattern::Exact(PathBuf::from("foo/bar")),
                 target: TargetPattern::All,
             }),
@@ -462,6 +507,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: true,
                 negative: false,
+                external: None,
                 package: PackagePattern::Exact(PathBuf::from("foo/bar")),
                 target: TargetPattern::Wildcard("ic_*".to_string()),
             }),
@@ -476,6 +522,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: false,
                 negative: false,
+                external: None,
                 package: PackagePattern::Exact(PathBuf::from("foo/bar")),
                 target: TargetPattern::Wildcard("ic_*".to_string()),
             }),
@@ -490,6 +537,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: true,
                 negative: false,
+                external: None,
                 package: PackagePattern::Wildcard(PathBuf::from("foo/...")),
                 target: TargetPattern::Wildcard("ic_*".to_string()),
             }),
@@ -504,6 +552,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: false,
                 negative: false,
+                external: None,
                 package: PackagePattern::Wildcard(PathBuf::from("foo/...")),
                 target: TargetPattern::Wildcard("ic_*".to_string()),
             }),
@@ -518,6 +567,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: true,
                 negative: false,
+                external: None,
                 package: PackagePattern::Wildcard(PathBuf::from("foo/...")),
                 target: TargetPattern::All,
             }),
@@ -532,6 +582,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: false,
                 negative: false,
+                external: None,
                 package: PackagePattern::Wildcard(PathBuf::from("foo/...")),
                 target: TargetPattern::All,
             }),
@@ -546,6 +597,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: true,
                 negative: false,
+                external: None,
                 package: PackagePattern::All,
                 target: TargetPattern::All,
             }),
@@ -560,6 +612,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: false,
                 negative: false,
+                external: None,
                 package: PackagePattern::All,
                 target: TargetPattern::All,
             }),
@@ -574,6 +627,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: false,
                 negative: true,
+                external: None,
                 package: PackagePattern::All,
                 target: TargetPattern::All,
             }),
@@ -588,6 +642,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: false,
                 negative: false,
+                external: None,
                 package: PackagePattern::Exact(PathBuf::from("foo")),
                 target: TargetPattern::Exact("bar".to_string()),
             }),
@@ -602,6 +657,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: false,
                 negative: true,
+                external: None,
                 package: PackagePattern::Exact(PathBuf::from("foo")),
                 target: TargetPattern::Exact("bar".to_string()),
             }),
@@ -616,6 +672,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: false,
                 negative: false,
+                external: None,
                 package: PackagePattern::Exact(PathBuf::new()),
                 target: TargetPattern::Exact("bar".to_string()),
             }),
@@ -630,6 +687,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: false,
                 negative: true,
+                external: None,
                 package: PackagePattern::Exact(PathBuf::new()),
                 target: TargetPattern::Exact("bar".to_string()),
             }),
@@ -644,6 +702,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: false,
                 negative: false,
+                external: None,
                 package: PackagePattern::Exact(PathBuf::new()),
                 target: TargetPattern::All,
             }),
@@ -658,6 +717,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: false,
                 negative: true,
+                external: None,
                 package: PackagePattern::Exact(PathBuf::new()),
                 target: TargetPattern::All,
             }),
@@ -672,6 +732,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: false,
                 negative: false,
+                external: None,
                 package: PackagePattern::Exact(PathBuf::new()),
                 target: TargetPattern::All,
             }),
@@ -686,6 +747,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: false,
                 negative: false,
+                external: None,
                 package: PackagePattern::Exact(PathBuf::new()),
                 target: TargetPattern::Wildcard("ill_*".to_string()),
             }),
@@ -700,6 +762,7 @@ mod tests {
             LabelPattern::Single(LabelPatternImpl {
                 absolute: true,
                 negative: false,
+                external: None,
                 package: PackagePattern::Exact(PathBuf::new()),
                 target: TargetPattern::Wildcard("ill_*".to_string()),
             }),
@@ -942,6 +1005,78 @@ mod tests {
 
     // endregion: composed patterns
 
+    // region: external workspaces
+
+    #[test]
+    fn parse_external_exact_pattern__EXPECT__ok() {
+        assert_eq!(
+            LabelPattern::from_str("@design_system//icons:ic_home").unwrap(),
+            LabelPattern::Single(LabelPatternImpl {
+                absolute: true,
+                negative: false,
+                external: Some("design_system".to_string()),
+                package: PackagePattern::Exact(PathBuf::from("icons")),
+                target: TargetPattern::Exact("ic_home".to_string()),
+            }),
+        );
+    }
+
+    #[test]
+    fn parse_negative_external_recursive_pattern__EXPECT__ok() {
+        assert_eq!(
+            LabelPattern::from_str("-@design_system//...").unwrap(),
+            LabelPattern::Single(LabelPatternImpl {
+                absolute: true,
+                negative: true,
+                external: Some("design_system".to_string()),
+                package: PackagePattern::All,
+                target: TargetPattern::All,
+            }),
+        );
+    }
+
+    #[test]
+    fn parse_invalid_external_patterns__EXPECT__err() {
+        assert!(LabelPattern::from_str("@//icons:ic_home").is_err());
+        assert!(LabelPattern::from_str("@design_system:ic_home").is_err());
+        assert!(LabelPattern::from_str("@design.system//icons:ic_home").is_err());
+    }
+
+    #[test]
+    fn matches_external_pattern__EXPECT__only_external_labels() {
+        let p = LabelPattern::from_str("@design_system//icons/...").unwrap();
+        assert!(matches(
+            &p,
+            &target("@design_system//icons/16:ic_home"),
+            &path("")
+        ));
+        assert!(matches(
+            &p,
+            &target("@design_system//icons/24:ic_home"),
+            &path("")
+        ));
+        assert!(!matches(&p, &target("//icons:ic_home"), &path("")));
+        assert!(!matches(&p, &target("@brand//icons:ic_home"), &path("")));
+    }
+
+    #[test]
+    fn matches_local_pattern__EXPECT__no_external_labels() {
+        let p = LabelPattern::from_str("//...").unwrap();
+        assert!(matches(&p, &target("//icons:ic_home"), &path("")));
+        assert!(!matches(
+            &p,
+            &target("@design_system//icons:ic_home"),
+            &path("")
+        ));
+        assert!(!package_matches(
+            &p,
+            &package("@design_system//icons"),
+            &path("")
+        ));
+    }
+
+    // endregion: external workspaces
+
     #[test]
     fn matches_wildcard_targets__EXPECT__ok() {
         let p = LabelPattern::from_str("...:ic_*_24").unwrap();
@@ -974,13 +1109,19 @@ mod tests {
 
     // Util function
     fn target(s: &str) -> Label {
-        let (package, name) = s.rsplit_once(':').unwrap();
-        Label::from_package_and_name(package.trim_start_matches("//"), name).unwrap()
+        let (package_str, name) = s.rsplit_once(':').unwrap();
+        (package(package_str), crate::Name::from_str(name).unwrap()).into()
     }
 
     // Util function
     fn package(s: &str) -> Package {
-        Package(PathBuf::from(s.trim_start_matches("//")))
+        match s.strip_prefix('@') {
+            Some(s) => {
+                let (external, path) = s.split_once("//").unwrap();
+                Package::with_path(path).unwrap().with_external(external)
+            }
+            None => Package::with_path(s.trim_start_matches("//")).unwrap(),
+        }
     }
 
     fn path(s: &str) -> PathBuf {
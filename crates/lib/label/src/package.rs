@@ -4,7 +4,12 @@ use std::{
 };
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
-pub struct Package(pub(crate) PathBuf);
+pub struct Package {
+    pub(crate) path: PathBuf,
+    /// Name of the external workspace this package belongs to, e.g. `design_system`
+    /// for `@design_system//icons`. `None` for packages of the current workspace.
+    pub(crate) external: Option<String>,
+}
 
 impl Package {
     pub fn with_path<P>(path: P) -> Result<Self, PackageParsingError>
@@ -28,29 +33,50 @@ impl Package {
                 ));
             }
         }
-        Ok(Package(full_path))
+        Ok(Package {
+            path: full_path,
+            external: None,
+        })
     }
 
     pub fn empty() -> Self {
-        Self(PathBuf::new())
+        Self {
+            path: PathBuf::new(),
+            external: None,
+        }
+    }
+
+    /// Moves the package into the external workspace with the specified name
+    pub fn with_external<S: Into<String>>(self, external: S) -> Self {
+        Self {
+            external: Some(external.into()),
+            ..self
+        }
+    }
+
+    pub fn external(&self) -> Option<&str> {
+        self.external.as_deref()
     }
 }
 
 impl std::fmt::Display for Package {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "//{}", self.0.display())
+        if let Some(external) = &self.external {
+            write!(f, "@{external}")?;
+        }
+        write!(f, "//{}", self.path.display())
     }
 }
 
 impl AsRef<Path> for Package {
     fn as_ref(&self) -> &Path {
-        &self.0
+        &self.path
     }
 }
 
 impl AsRef<str> for Package {
     fn as_ref(&self) -> &str {
-        self.0
+        self.path
             .to_str()
             .expect("Always valid path inside Package value-objects")
     }
@@ -59,7 +85,7 @@ impl AsRef<str> for Package {
 impl Deref for Package {
     type Target = Path;
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.path
     }
 }
 
@@ -94,11 +120,17 @@ mod test {
     #[test]
     fn parse_valid_package__EXPECT__ok() {
         assert_eq!(
-            Package(PathBuf::from("foo/bar")),
+            Package {
+                path: PathBuf::from("foo/bar"),
+                external: None,
+            },
             Package::with_path("foo/bar").unwrap(),
         );
         assert_eq!(
-            Package(PathBuf::from("foo-123/bar_x")),
+            Package {
+                path: PathBuf::from("foo-123/bar_x"),
+                external: None,
+            },
             Package::with_path("foo-123/bar_x").unwrap(),
         );
     }
@@ -124,6 +156,17 @@ mod test {
         assert_eq!("//", Package::empty().to_string(),)
     }
 
+    #[test]
+    fn display_external_package__EXPECT__predictable_result() {
+        assert_eq!(
+            "@design_system//icons",
+            Package::with_path("icons")
+                .unwrap()
+                .with_external("design_system")
+                .to_string(),
+        )
+    }
+
     #[test]
     fn package_can_be_referenced_as_path() {
         let path = PathBuf::from("path/to/package");
@@ -6,24 +6,29 @@ impl Label {
         let name_len = self.name.as_ref().len();
         let pkg_path = self
             .package
-            .0
+            .path
             .to_str()
             .expect("always valid UTF-8")
             .to_string();
-        if name_len + pkg_path.len() + 3 <= n_chars {
-            return format!("//{pkg_path}:{}", self.name);
+        let root = match self.package.external() {
+            Some(external) => format!("@{external}//"),
+            None => "//".to_string(),
+        };
+        let overhead = root.len() + 1;
+        if name_len + pkg_path.len() + overhead <= n_chars {
+            return format!("{root}{pkg_path}:{}", self.name);
         }
 
         if self.package.as_os_str().is_empty() {
-            return format!("//:{}", self.name);
+            return format!("{root}:{}", self.name);
         } else {
             let mut path_len = pkg_path.len();
             let path = self
                 .package
-                .0
+                .path
                 .iter()
                 .skip_while(|p| {
-                    let skip = path_len + name_len + 3 + 4 > n_chars;
+                    let skip = path_len + name_len + overhead + 4 > n_chars;
                     path_len -= p.to_str().expect("always valid UTF-8").len();
                     if path_len > 0 {
                         path_len -= 1;
@@ -35,9 +40,9 @@ impl Label {
                 .join("/");
 
             if path.is_empty() {
-                return format!("//...:{}", self.name);
+                return format!("{root}...:{}", self.name);
             } else {
-                return format!("//.../{path}:{}", self.name);
+                return format!("{root}.../{path}:{}", self.name);
             }
         }
     }
@@ -51,7 +56,10 @@ mod test {
 
     fn label(path: &str, name: &str) -> Label {
         Label {
-            package: Package(PathBuf::from(path)),
+            package: Package {
+                path: PathBuf::from(path),
+                external: None,
+            },
             name: Name(name.to_owned()),
         }
     }
@@ -0,0 +1,151 @@
+use crate::PatternError;
+use std::str::FromStr;
+
+/// A user-supplied pattern used to match resources by their tags.
+///
+/// Tag patterns are entered along with the label patterns:
+/// - `tag:critical` matches resources tagged with `critical`
+/// - `-tag:marketing` excludes resources tagged with `marketing`
+#[derive(Debug, PartialEq, Clone)]
+pub struct TagPattern {
+    pub tag: String,
+    pub negative: bool,
+}
+
+impl FromStr for TagPattern {
+    type Err = PatternError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (pattern, negative) = match s.strip_prefix('-') {
+            Some(stripped) => (stripped, true),
+            None => (s, false),
+        };
+        let Some(tag) = pattern.strip_prefix("tag:") else {
+            return Err(PatternError::BadTag(s.to_string(), pattern.to_string()));
+        };
+        if !is_valid_tag(tag) {
+            return Err(PatternError::BadTag(s.to_string(), tag.to_string()));
+        }
+        Ok(Self {
+            tag: tag.to_string(),
+            negative,
+        })
+    }
+}
+
+/// Tags consist of the same characters as the names of packages
+pub fn is_valid_tag(tag: &str) -> bool {
+    !tag.is_empty()
+        && tag
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Separates tag patterns from the label patterns entered by user.
+///
+/// If only tag patterns are entered, they are applied to the whole workspace.
+pub fn split_tag_patterns(
+    patterns: Vec<String>,
+) -> Result<(Vec<String>, Vec<TagPattern>), PatternError> {
+    let (tag_patterns, mut label_patterns): (Vec<_>, Vec<_>) = patterns
+        .into_iter()
+        .partition(|it| it.trim_start_matches('-').starts_with("tag:"));
+    let tag_patterns = tag_patterns
+        .iter()
+        .map(|it| TagPattern::from_str(it))
+        .collect::<Result<Vec<_>, _>>()?;
+    if label_patterns.is_empty() && !tag_patterns.is_empty() {
+        label_patterns.push("//...".to_string());
+    }
+    Ok((label_patterns, tag_patterns))
+}
+
+/// Checks whether the `tags` of a resource match all of the tag `patterns`.
+///
+/// A resource must have at least one of the positive tags, if there are any,
+/// and none of the negative ones.
+pub fn tags_match(patterns: &[TagPattern], tags: &[String]) -> bool {
+    let mut has_positive = false;
+    let mut positive_match = false;
+    for pattern in patterns {
+        let matches = tags.contains(&pattern.tag);
+        if pattern.negative {
+            if matches {
+                return false;
+            }
+        } else {
+            has_positive = true;
+            positive_match |= matches;
+        }
+    }
+    !has_positive || positive_match
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_tag_patterns__mixed_patterns__EXPECT__tags_separated() {
+        // Given
+        let patterns = vec![
+            "//icons/...".to_string(),
+            "tag:critical".to_string(),
+            "-tag:marketing".to_string(),
+        ];
+
+        // When
+        let (labels, tags) = split_tag_patterns(patterns).unwrap();
+
+        // Then
+        assert_eq!(vec!["//icons/...".to_string()], labels);
+        assert_eq!(
+            vec![
+                TagPattern {
+                    tag: "critical".to_string(),
+                    negative: false,
+                },
+                TagPattern {
+                    tag: "marketing".to_string(),
+                    negative: true,
+                },
+            ],
+            tags,
+        );
+    }
+
+    #[test]
+    fn split_tag_patterns__only_tags__EXPECT__whole_workspace() {
+        // When
+        let (labels, _) = split_tag_patterns(vec!["tag:critical".to_string()]).unwrap();
+
+        // Then
+        assert_eq!(vec!["//...".to_string()], labels);
+    }
+
+    #[test]
+    fn split_tag_patterns__bad_tag__EXPECT__error() {
+        // When
+        let result = split_tag_patterns(vec!["tag:crit ical".to_string()]);
+
+        // Then
+        assert!(matches!(result, Err(PatternError::BadTag(_, tag)) if tag == "crit ical"));
+    }
+
+    #[test]
+    fn tags_match__positive_and_negative__EXPECT__negative_wins() {
+        // Given
+        let patterns = [
+            TagPattern::from_str("tag:critical").unwrap(),
+            TagPattern::from_str("-tag:marketing").unwrap(),
+        ];
+        let tags = |it: &[&str]| it.iter().map(|it| it.to_string()).collect::<Vec<_>>();
+
+        // Then
+        assert!(tags_match(&patterns, &tags(&["critical"])));
+        assert!(!tags_match(&patterns, &tags(&["critical", "marketing"])));
+        assert!(!tags_match(&patterns, &tags(&[])));
+        assert!(tags_match(&patterns[1..], &tags(&[])));
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{MetricsCollector, SpanRecord};
+use log::warn;
+use std::{collections::BTreeSet, fmt::Write as _, fs::File, io::Write, path::Path};
+
+/// Process id of all events, there is only one process
+const PID: u32 = 1;
+
+impl MetricsCollector {
+    /// Writes recorded spans in the Chrome Trace Event format,
+    /// which can be opened with Perfetto or `chrome://tracing`
+    pub fn export_as_chrome_trace(&self, path: &Path) {
+        if let Err(e) = self.try_export_as_chrome_trace(path) {
+            warn!("Unable to export trace: {e}")
+        }
+    }
+
+    pub fn try_export_as_chrome_trace(&self, path: &Path) -> std::io::Result<()> {
+        let buf = to_chrome_trace_string(&self.spans().records());
+        let mut file = File::create(path)?;
+        file.write_all(buf.as_bytes())
+    }
+}
+
+fn to_chrome_trace_string(records: &[SpanRecord]) -> String {
+    // (timestamp, is begin, depth order, record)
+    let mut events = Vec::with_capacity(records.len() * 2);
+    for record in records {
+        let begin = record.start.as_micros();
+        let end = (record.start + record.duration).as_micros();
+        // events with equal timestamps must stay properly nested:
+        // parents begin before children, children end before parents
+        events.push((begin, true, record.depth as isize, record));
+        events.push((end, false, -(record.depth as isize), record));
+    }
+    events.sort_by_key(|(ts, is_begin, order, _)| (*ts, *is_begin, *order));
+
+    let mut buf = String::with_capacity(128 * events.len() + 64);
+    buf.push_str("{\"traceEvents\":[");
+    let threads = records.iter().map(|it| it.thread).collect::<BTreeSet<_>>();
+    for (idx, thread) in threads.iter().enumerate() {
+        if idx > 0 {
+            buf.push(',');
+        }
+        let _ = write!(
+            buf,
+            r#"{{"name":"thread_name","ph":"M","pid":{PID},"tid":{thread},"args":{{"name":"worker-{thread}"}}}}"#,
+        );
+    }
+    for (idx, (ts, is_begin, _, record)) in events.iter().enumerate() {
+        if idx > 0 || !threads.is_empty() {
+            buf.push(',');
+        }
+        let phase = if *is_begin { "B" } else { "E" };
+        let _ = write!(
+            buf,
+            r#"{{"name":"{name}","cat":"action","ph":"{phase}","ts":{ts},"pid":{PID},"tid":{tid},"args":{{"target":"{target}"}}}}"#,
+            name = escape_json(record.name),
+            tid = record.thread,
+            target = escape_json(&record.target),
+        );
+    }
+    buf.push_str("],\"displayTimeUnit\":\"ms\"}");
+    buf
+}
+
+pub(crate) fn escape_json(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use std::{sync::Arc, time::Duration};
+
+    fn span(name: &'static str, depth: usize, start: u64, us: u64) -> SpanRecord {
+        SpanRecord {
+            name,
+            target: Arc::from("//icons:ic_home"),
+            of_target: true,
+            thread: 3,
+            depth,
+            start: Duration::from_micros(start),
+            duration: Duration::from_micros(us),
+        }
+    }
+
+    #[test]
+    fn to_chrome_trace_string__nested_spans_with_equal_bounds__EXPECT__properly_nested_events() {
+        // Given
+        let records = vec![
+            span("materialize", 1, 10, 5),
+            span("import_svg", 0, 0, 15),
+            span("get_remote_image", 1, 0, 10),
+        ];
+
+        // When
+        let trace = to_chrome_trace_string(&records);
+
+        // Then
+        let phases = trace
+            .split(r#""name":""#)
+            .skip(1)
+            .filter(|it| !it.starts_with("thread_name") && !it.starts_with("worker-"))
+            .map(|it| {
+                let name = it.split('"').next().unwrap();
+                let phase = it.split(r#""ph":""#).nth(1).unwrap().split('"').next();
+                format!("{}:{}", phase.unwrap(), name)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "B:import_svg",
+                "B:get_remote_image",
+                "E:get_remote_image",
+                "B:materialize",
+                "E:materialize",
+                "E:import_svg",
+            ],
+            phases,
+        );
+        assert!(trace.contains(r#""tid":3,"args":{"name":"worker-3"}"#));
+    }
+}
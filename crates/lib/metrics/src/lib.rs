@@ -1,7 +1,12 @@
+mod chrome;
 mod metrics;
+mod otlp;
 use dashmap::DashMap;
 pub use metrics::*;
+pub use otlp::*;
 mod prom;
+mod spans;
+pub use spans::*;
 use std::{ops::Deref, sync::Arc};
 
 #[derive(Default, Clone)]
@@ -18,6 +23,9 @@ impl Deref for Metrics {
 pub struct MetricsCollector {
     durations: DashMap<&'static str, Arc<Duration>>,
     counters: DashMap<&'static str, Arc<Counter>>,
+    gauges: DashMap<&'static str, Arc<Gauge>>,
+    histograms: DashMap<&'static str, Arc<Histogram>>,
+    spans: Spans,
 }
 
 impl MetricsCollector {
@@ -28,4 +36,26 @@ impl MetricsCollector {
     pub fn counter(&self, name: &'static str) -> Arc<Counter> {
         self.counters.entry(name).or_default().value().clone()
     }
+
+    pub fn gauge(&self, name: &'static str) -> Arc<Gauge> {
+        self.gauges.entry(name).or_default().value().clone()
+    }
+
+    /// Histogram with [`DEFAULT_BUCKETS`]
+    pub fn histogram(&self, name: &'static str) -> Arc<Histogram> {
+        self.histograms.entry(name).or_default().value().clone()
+    }
+
+    /// Histogram with custom buckets, they are ignored if the histogram already exists
+    pub fn histogram_with_buckets(&self, name: &'static str, buckets: &[f64]) -> Arc<Histogram> {
+        self.histograms
+            .entry(name)
+            .or_insert_with(|| Arc::new(Histogram::with_buckets(buckets)))
+            .value()
+            .clone()
+    }
+
+    pub fn spans(&self) -> &Spans {
+        &self.spans
+    }
 }
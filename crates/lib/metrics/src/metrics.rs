@@ -1,5 +1,5 @@
 use std::{
-    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    sync::atomic::{AtomicI64, AtomicU64, AtomicUsize, Ordering},
     time::Instant,
 };
 
@@ -56,3 +56,123 @@ impl<'a> Drop for DurationRecorder<'a> {
 }
 
 // endregion: DURATION
+
+// region: GAUGE
+
+#[derive(Default)]
+pub struct Gauge(AtomicI64);
+
+impl Gauge {
+    pub fn set(&self, value: i64) {
+        self.0.store(value, Ordering::SeqCst);
+    }
+
+    pub fn add(&self, delta: i64) {
+        self.0.fetch_add(delta, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> i64 {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+// endregion: GAUGE
+
+// region: HISTOGRAM
+
+/// Default buckets, suitable for durations in seconds
+pub const DEFAULT_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+pub struct Histogram {
+    /// Upper bounds of the buckets in ascending order, `+Inf` is implicit
+    buckets: Vec<f64>,
+    /// Number of observations per bucket, not cumulative, the last one is `+Inf`
+    counts: Vec<AtomicU64>,
+    /// Bits of the `f64` sum of all observations
+    sum: AtomicU64,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self::with_buckets(DEFAULT_BUCKETS)
+    }
+}
+
+impl Histogram {
+    pub fn with_buckets(buckets: &[f64]) -> Self {
+        let mut buckets = buckets
+            .iter()
+            .copied()
+            .filter(|it| it.is_finite())
+            .collect::<Vec<_>>();
+        buckets.sort_by(f64::total_cmp);
+        buckets.dedup();
+        Self {
+            counts: (0..=buckets.len()).map(|_| AtomicU64::new(0)).collect(),
+            buckets,
+            sum: AtomicU64::new(0f64.to_bits()),
+        }
+    }
+
+    pub fn observe(&self, value: f64) {
+        let idx = self.buckets.partition_point(|bound| *bound < value);
+        self.counts[idx].fetch_add(1, Ordering::Relaxed);
+        let _ = self
+            .sum
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |bits| {
+                Some((f64::from_bits(bits) + value).to_bits())
+            });
+    }
+
+    pub fn buckets(&self) -> &[f64] {
+        &self.buckets
+    }
+
+    /// Cumulative number of observations less than or equal to each bucket bound,
+    /// the last item is the total count
+    pub fn cumulative_counts(&self) -> Vec<u64> {
+        let mut total = 0;
+        self.counts
+            .iter()
+            .map(|it| {
+                total += it.load(Ordering::SeqCst);
+                total
+            })
+            .collect()
+    }
+
+    pub fn count(&self) -> u64 {
+        self.counts.iter().map(|it| it.load(Ordering::SeqCst)).sum()
+    }
+
+    pub fn sum(&self) -> f64 {
+        f64::from_bits(self.sum.load(Ordering::SeqCst))
+    }
+}
+
+// endregion: HISTOGRAM
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn Histogram__observe__EXPECT__cumulative_buckets() {
+        // Given
+        let histogram = Histogram::with_buckets(&[10.0, 1.0, 5.0]);
+
+        // When
+        for value in [0.5, 1.0, 3.0, 7.0, 100.0] {
+            histogram.observe(value);
+        }
+
+        // Then
+        assert_eq!(&[1.0, 5.0, 10.0], histogram.buckets());
+        assert_eq!(vec![2, 3, 4, 5], histogram.cumulative_counts());
+        assert_eq!(5, histogram.count());
+        assert_eq!(111.5, histogram.sum());
+    }
+}
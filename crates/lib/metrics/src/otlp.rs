@@ -0,0 +1,218 @@
+use crate::{MetricsCollector, chrome::escape_json};
+use dashmap::DashMap;
+use log::{debug, warn};
+use std::{
+    fmt::Write as _,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const OTLP_ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+const OTLP_METRICS_ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_METRICS_ENDPOINT";
+const OTLP_HEADERS_ENV: &str = "OTEL_EXPORTER_OTLP_HEADERS";
+const OTLP_METRICS_PATH: &str = "/v1/metrics";
+const OTLP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Url to push metrics to, if configured.
+///
+/// The standard OpenTelemetry environment variables take precedence over
+/// the endpoint from the workspace config.
+pub fn otlp_metrics_url(configured_endpoint: Option<&str>) -> Option<String> {
+    resolve_otlp_metrics_url(
+        std::env::var(OTLP_METRICS_ENDPOINT_ENV).ok(),
+        std::env::var(OTLP_ENDPOINT_ENV).ok(),
+        configured_endpoint,
+    )
+}
+
+fn resolve_otlp_metrics_url(
+    env_metrics_endpoint: Option<String>,
+    env_endpoint: Option<String>,
+    configured_endpoint: Option<&str>,
+) -> Option<String> {
+    if let Some(url) = env_metrics_endpoint.filter(|it| !it.is_empty()) {
+        // signal-specific endpoint is used as is
+        return Some(url);
+    }
+    let base = env_endpoint
+        .filter(|it| !it.is_empty())
+        .or_else(|| configured_endpoint.map(str::to_owned))?;
+    Some(format!("{}{OTLP_METRICS_PATH}", base.trim_end_matches('/')))
+}
+
+impl MetricsCollector {
+    /// Pushes metrics to the OpenTelemetry collector using OTLP/HTTP with JSON encoding
+    pub fn export_to_otlp(&self, labels: Option<&[(&'static str, &'static str)]>, url: &str) {
+        if let Err(e) = self.try_export_to_otlp(labels, url) {
+            warn!("Unable to push metrics to `{url}`: {e}")
+        }
+    }
+
+    pub fn try_export_to_otlp(
+        &self,
+        labels: Option<&[(&'static str, &'static str)]>,
+        url: &str,
+    ) -> Result<(), ureq::Error> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let body = to_otlp_json_string(self, labels.unwrap_or_default(), now);
+
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .timeout_global(Some(OTLP_TIMEOUT))
+            .build()
+            .into();
+        let mut request = agent.post(url).header("Content-Type", "application/json");
+        let headers = std::env::var(OTLP_HEADERS_ENV).unwrap_or_default();
+        for (key, value) in headers.split(',').filter_map(|it| it.split_once('=')) {
+            request = request.header(key.trim(), value.trim());
+        }
+        request.send(body)?;
+        debug!("Metrics pushed to `{url}`");
+        Ok(())
+    }
+}
+
+fn to_otlp_json_string(
+    metrics: &MetricsCollector,
+    labels: &[(&'static str, &'static str)],
+    time_unix_nano: u128,
+) -> String {
+    let mut attributes = String::from("[");
+    for (idx, (key, value)) in labels.iter().enumerate() {
+        if idx > 0 {
+            attributes.push(',');
+        }
+        let _ = write!(
+            attributes,
+            r#"{{"key":"{}","value":{{"stringValue":"{}"}}}}"#,
+            escape_json(key),
+            escape_json(value),
+        );
+    }
+    attributes.push(']');
+
+    let mut buf = String::with_capacity(4096);
+    buf.push_str(r#"{"resourceMetrics":[{"resource":{"attributes":[{"key":"service.name","value":{"stringValue":"figx"}}]},"scopeMetrics":[{"scope":{"name":"figx"},"metrics":["#);
+    let mut first = true;
+    // durations are gauges: each run reports its own value
+    to_otlp_metrics(&mut buf, &mut first, &metrics.durations, |name, d| {
+        format!(
+            r#"{{"name":"{name}","unit":"ms","gauge":{{"dataPoints":[{{"asInt":"{}","timeUnixNano":"{time_unix_nano}","attributes":{attributes}}}]}}}}"#,
+            d.get().as_millis(),
+        )
+    });
+    to_otlp_metrics(&mut buf, &mut first, &metrics.counters, |name, c| {
+        format!(
+            r#"{{"name":"{name}","sum":{{"aggregationTemporality":2,"isMonotonic":true,"dataPoints":[{{"asInt":"{}","timeUnixNano":"{time_unix_nano}","attributes":{attributes}}}]}}}}"#,
+            c.get(),
+        )
+    });
+    to_otlp_metrics(&mut buf, &mut first, &metrics.gauges, |name, g| {
+        format!(
+            r#"{{"name":"{name}","gauge":{{"dataPoints":[{{"asInt":"{}","timeUnixNano":"{time_unix_nano}","attributes":{attributes}}}]}}}}"#,
+            g.get(),
+        )
+    });
+    to_otlp_metrics(&mut buf, &mut first, &metrics.histograms, |name, h| {
+        let cumulative = h.cumulative_counts();
+        let bucket_counts = cumulative
+            .iter()
+            .scan(0, |prev, it| {
+                let count = it - *prev;
+                *prev = *it;
+                Some(format!(r#""{count}""#))
+            })
+            .collect::<Vec<_>>();
+        let bounds = h.buckets().iter().map(f64::to_string).collect::<Vec<_>>();
+        format!(
+            r#"{{"name":"{name}","histogram":{{"aggregationTemporality":2,"dataPoints":[{{"count":"{}","sum":{},"bucketCounts":[{}],"explicitBounds":[{}],"timeUnixNano":"{time_unix_nano}","attributes":{attributes}}}]}}}}"#,
+            cumulative.last().unwrap_or(&0),
+            h.sum(),
+            bucket_counts.join(","),
+            bounds.join(","),
+        )
+    });
+    buf.push_str("]}]}]}");
+    buf
+}
+
+fn to_otlp_metrics<T>(
+    buf: &mut String,
+    first: &mut bool,
+    metrics: &DashMap<&'static str, Arc<T>>,
+    ser: impl Fn(&str, &T) -> String,
+) {
+    for entry in metrics.iter() {
+        if !*first {
+            buf.push(',');
+        }
+        *first = false;
+        buf.push_str(&ser(&escape_json(entry.key()), entry.value()));
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn resolve_otlp_metrics_url__env_and_config__EXPECT__env_has_priority() {
+        // Given
+        let configured = Some("http://config:4318/");
+
+        // When
+        let from_config = resolve_otlp_metrics_url(None, None, configured);
+        let from_env = resolve_otlp_metrics_url(None, Some("http://env:4318".into()), configured);
+        let from_signal_env = resolve_otlp_metrics_url(
+            Some("http://env:4318/custom".into()),
+            Some("http://env:4318".into()),
+            configured,
+        );
+        let nothing = resolve_otlp_metrics_url(None, Some(String::new()), None);
+
+        // Then
+        assert_eq!(Some("http://config:4318/v1/metrics".into()), from_config);
+        assert_eq!(Some("http://env:4318/v1/metrics".into()), from_env);
+        assert_eq!(Some("http://env:4318/custom".into()), from_signal_env);
+        assert_eq!(None, nothing);
+    }
+
+    #[test]
+    fn to_otlp_json_string__counter_and_duration__EXPECT__sum_and_gauge() {
+        // Given
+        let metrics = MetricsCollector::default();
+        metrics.counter("figx_targets_evaluated").set(7);
+        drop(metrics.duration("figx_full_duration").record());
+
+        // When
+        let json = to_otlp_json_string(&metrics, &[("command", "import")], 42);
+
+        // Then
+        assert!(json.contains(
+            r#"{"name":"figx_targets_evaluated","sum":{"aggregationTemporality":2,"isMonotonic":true,"dataPoints":[{"asInt":"7","timeUnixNano":"42","attributes":[{"key":"command","value":{"stringValue":"import"}}]}]}}"#
+        ));
+        assert!(json.contains(r#"{"name":"figx_full_duration","unit":"ms","gauge":"#));
+        assert!(json.ends_with("]}]}]}"));
+    }
+
+    #[test]
+    fn to_otlp_json_string__histogram__EXPECT__non_cumulative_bucket_counts() {
+        // Given
+        let metrics = MetricsCollector::default();
+        let histogram = metrics.histogram_with_buckets("figx_image_size_bytes", &[100.0, 1000.0]);
+        histogram.observe(50.0);
+        histogram.observe(60.0);
+        histogram.observe(5000.0);
+
+        // When
+        let json = to_otlp_json_string(&metrics, &[], 42);
+
+        // Then
+        assert!(json.contains(
+            r#""count":"3","sum":5110,"bucketCounts":["2","0","1"],"explicitBounds":[100,1000]"#
+        ));
+    }
+}
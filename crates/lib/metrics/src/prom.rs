@@ -1,10 +1,9 @@
-use crate::MetricsCollector;
+use crate::{Histogram, MetricsCollector};
 use dashmap::DashMap;
 use log::warn;
-use std::{fs::File, io::Write, path::Path, sync::Arc};
+use std::{fmt::Write as _, fs::File, io::Write, path::Path, sync::Arc};
 
 impl MetricsCollector {
-
     pub fn export_as_prometheus(
         &self,
         labels: Option<&[(&'static str, &'static str)]>,
@@ -20,43 +19,132 @@ impl MetricsCollector {
         labels: Option<&[(&'static str, &'static str)]>,
         path: &Path,
     ) -> std::io::Result<()> {
-        let mut buf = String::with_capacity(8192);
-        to_prometheus_string(&mut buf, &self.durations, labels, |d| {
-            d.get().as_millis().to_string()
-        });
-        to_prometheus_string(&mut buf, &self.counters, labels, |c| c.get().to_string());
+        let buf = self.to_prometheus(labels);
         let mut file = File::create(path)?;
         file.write_all(buf.as_bytes())
     }
+
+    /// Same as [`Self::export_as_prometheus`], but for serving the metrics over HTTP
+    pub fn to_prometheus(&self, labels: Option<&[(&'static str, &'static str)]>) -> String {
+        to_prometheus_string(self, labels.unwrap_or_default())
+    }
+}
+
+fn to_prometheus_string(
+    metrics: &MetricsCollector,
+    labels: &[(&'static str, &'static str)],
+) -> String {
+    let mut buf = String::with_capacity(8192);
+    // durations are measured once per run, so they are gauges
+    to_prometheus_samples(&mut buf, &metrics.durations, "gauge", labels, |d| {
+        d.get().as_millis().to_string()
+    });
+    to_prometheus_samples(&mut buf, &metrics.counters, "counter", labels, |c| {
+        c.get().to_string()
+    });
+    to_prometheus_samples(&mut buf, &metrics.gauges, "gauge", labels, |g| {
+        g.get().to_string()
+    });
+    for entry in metrics.histograms.iter() {
+        to_prometheus_histogram(&mut buf, entry.key(), entry.value(), labels);
+    }
+    buf
 }
 
-fn to_prometheus_string<T>(
+fn to_prometheus_samples<T>(
     buf: &mut String,
     metrics: &DashMap<&'static str, Arc<T>>,
-    labels: Option<&[(&'static str, &'static str)]>,
+    metric_type: &str,
+    labels: &[(&'static str, &'static str)],
     ser: impl Fn(&T) -> String,
 ) {
     for entry in metrics.iter() {
         let key = entry.key();
-        let value = entry.value();
-
-        buf.push_str(key);
-        if let Some(labels) = labels {
-            buf.push('{');
-            for (idx, (k, v)) in labels.iter().enumerate() {
-                if idx > 0 {
-                    buf.push(',');
-                }
-                buf.push_str(k);
-                buf.push_str(r#"=""#);
-                buf.push_str(v);
-                buf.push('"');
-            }
-            buf.push_str("} ");
-        } else {
-            buf.push(' ');
+        let _ = writeln!(buf, "# TYPE {key} {metric_type}");
+        let _ = writeln!(
+            buf,
+            "{key}{} {}",
+            format_labels(labels, None),
+            ser(entry.value())
+        );
+    }
+}
+
+fn to_prometheus_histogram(
+    buf: &mut String,
+    key: &str,
+    histogram: &Histogram,
+    labels: &[(&'static str, &'static str)],
+) {
+    let _ = writeln!(buf, "# TYPE {key} histogram");
+    let counts = histogram.cumulative_counts();
+    let bounds = histogram
+        .buckets()
+        .iter()
+        .map(|it| it.to_string())
+        .chain(std::iter::once("+Inf".to_string()));
+    for (bound, count) in bounds.zip(&counts) {
+        let labels = format_labels(labels, Some(&bound));
+        let _ = writeln!(buf, "{key}_bucket{labels} {count}");
+    }
+    let labels = format_labels(labels, None);
+    let _ = writeln!(buf, "{key}_sum{labels} {}", histogram.sum());
+    let _ = writeln!(buf, "{key}_count{labels} {}", counts.last().unwrap_or(&0));
+}
+
+fn format_labels(labels: &[(&'static str, &'static str)], le: Option<&str>) -> String {
+    let le = le.map(|it| ("le", it));
+    let labels = labels.iter().copied().chain(le).collect::<Vec<_>>();
+    if labels.is_empty() {
+        return String::new();
+    }
+    let mut buf = String::from("{");
+    for (idx, (k, v)) in labels.iter().enumerate() {
+        if idx > 0 {
+            buf.push(',');
+        }
+        let _ = write!(buf, r#"{k}="{v}""#);
+    }
+    buf.push('}');
+    buf
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn to_prometheus_string__all_metric_types__EXPECT__type_annotations() {
+        // Given
+        let metrics = MetricsCollector::default();
+        metrics.counter("figx_targets_evaluated").set(3);
+        metrics.gauge("figx_jobs").set(8);
+        let histogram = metrics.histogram_with_buckets("figx_image_size_bytes", &[100.0, 1000.0]);
+        histogram.observe(50.0);
+        histogram.observe(500.0);
+
+        // When
+        let prom = to_prometheus_string(&metrics, &[("command", "import")]);
+
+        // Then
+        let expected = [
+            "# TYPE figx_targets_evaluated counter",
+            r#"figx_targets_evaluated{command="import"} 3"#,
+            "# TYPE figx_jobs gauge",
+            r#"figx_jobs{command="import"} 8"#,
+            "# TYPE figx_image_size_bytes histogram",
+            r#"figx_image_size_bytes_bucket{command="import",le="100"} 1"#,
+            r#"figx_image_size_bytes_bucket{command="import",le="1000"} 2"#,
+            r#"figx_image_size_bytes_bucket{command="import",le="+Inf"} 2"#,
+            r#"figx_image_size_bytes_sum{command="import"} 550"#,
+            r#"figx_image_size_bytes_count{command="import"} 2"#,
+        ];
+        for line in expected {
+            assert!(
+                prom.lines().any(|it| it == line),
+                "missing `{line}` in:\n{prom}"
+            );
         }
-        buf.push_str(&ser(&value));
-        buf.push('\n');
     }
 }
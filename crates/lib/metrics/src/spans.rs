@@ -0,0 +1,214 @@
+use std::{
+    cell::RefCell,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+/// Records the wall time of every action, if enabled.
+///
+/// Spans opened on the same thread are nested: the child span inherits
+/// the target of its parent, so only the outermost span names the target.
+pub struct Spans {
+    enabled: AtomicBool,
+    origin: Instant,
+    records: Mutex<Vec<SpanRecord>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SpanRecord {
+    /// Name of the action, e.g. `materialize`
+    pub name: &'static str,
+    /// Qualified name of the target the action belongs to
+    pub target: Arc<str>,
+    /// Whether the action is a part of the evaluation of the target,
+    /// otherwise `target` names what the action works on, e.g. an indexed remote
+    pub of_target: bool,
+    /// Sequential number of the thread which executed the action
+    pub thread: usize,
+    /// Nesting level of the span, zero for the outermost one
+    pub depth: usize,
+    /// Offset from the creation of the metrics collector
+    pub start: Duration,
+    pub duration: Duration,
+}
+
+pub struct SpanRecorder<'a> {
+    spans: Option<&'a Spans>,
+    name: &'static str,
+    target: Arc<str>,
+    of_target: bool,
+    depth: usize,
+    start: Instant,
+}
+
+static NEXT_THREAD_NUMBER: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static THREAD_NUMBER: usize = NEXT_THREAD_NUMBER.fetch_add(1, Ordering::Relaxed);
+    static TARGETS: RefCell<Vec<(Arc<str>, bool)>> = const { RefCell::new(Vec::new()) };
+}
+
+impl Default for Spans {
+    fn default() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            origin: Instant::now(),
+            records: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl Spans {
+    pub fn enable(&self) {
+        self.enabled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Opens the outermost span of the target
+    pub fn target_span(&self, name: &'static str, target: impl Into<Arc<str>>) -> SpanRecorder<'_> {
+        if !self.is_enabled() {
+            return SpanRecorder::disabled(name);
+        }
+        self.open(name, target.into(), true)
+    }
+
+    /// Opens the outermost span of the work shared by targets, e.g. indexing of a remote
+    pub fn subject_span(
+        &self,
+        name: &'static str,
+        subject: impl Into<Arc<str>>,
+    ) -> SpanRecorder<'_> {
+        if !self.is_enabled() {
+            return SpanRecorder::disabled(name);
+        }
+        self.open(name, subject.into(), false)
+    }
+
+    /// Opens the span nested into the current one on this thread
+    pub fn span(&self, name: &'static str) -> SpanRecorder<'_> {
+        if !self.is_enabled() {
+            return SpanRecorder::disabled(name);
+        }
+        let parent = TARGETS.with_borrow(|it| it.last().cloned());
+        let (target, of_target) = parent.unwrap_or_else(|| (Arc::from(""), false));
+        self.open(name, target, of_target)
+    }
+
+    /// Recorded spans sorted by start time
+    pub fn records(&self) -> Vec<SpanRecord> {
+        let mut records = self.records.lock().unwrap().clone();
+        records.sort_by_key(|it| it.start);
+        records
+    }
+
+    fn open(&self, name: &'static str, target: Arc<str>, of_target: bool) -> SpanRecorder<'_> {
+        let depth = TARGETS.with_borrow_mut(|it| {
+            it.push((target.clone(), of_target));
+            it.len() - 1
+        });
+        SpanRecorder {
+            spans: Some(self),
+            name,
+            target,
+            of_target,
+            depth,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl SpanRecorder<'_> {
+    fn disabled(name: &'static str) -> Self {
+        Self {
+            spans: None,
+            name,
+            target: Arc::from(""),
+            of_target: false,
+            depth: 0,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Drop for SpanRecorder<'_> {
+    fn drop(&mut self) {
+        let Some(spans) = self.spans else {
+            return;
+        };
+        TARGETS.with_borrow_mut(|it| it.pop());
+        let record = SpanRecord {
+            name: self.name,
+            target: self.target.clone(),
+            of_target: self.of_target,
+            thread: THREAD_NUMBER.with(|it| *it),
+            depth: self.depth,
+            start: self.start.duration_since(spans.origin),
+            duration: self.start.elapsed(),
+        };
+        spans.records.lock().unwrap().push(record);
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn span__nested_into_target_span__EXPECT__inherits_target() {
+        // Given
+        let spans = Spans::default();
+        spans.enable();
+
+        // When
+        {
+            let _root = spans.target_span("import_png", "//icons:ic_home");
+            let _child = spans.span("materialize");
+        }
+
+        // Then
+        let records = spans.records();
+        assert_eq!(2, records.len());
+        let child = records.iter().find(|it| it.name == "materialize").unwrap();
+        assert_eq!("//icons:ic_home", child.target.as_ref());
+        assert_eq!(1, child.depth);
+        assert!(child.of_target);
+    }
+
+    #[test]
+    fn span__nested_into_subject_span__EXPECT__not_of_target() {
+        // Given
+        let spans = Spans::default();
+        spans.enable();
+
+        // When
+        {
+            let _root = spans.subject_span("index_remote", "design");
+            let _child = spans.span("fetch_nodes");
+        }
+
+        // Then
+        let records = spans.records();
+        assert_eq!(2, records.len());
+        assert!(records.iter().all(|it| !it.of_target));
+        assert!(records.iter().all(|it| it.target.as_ref() == "design"));
+    }
+
+    #[test]
+    fn span__disabled__EXPECT__nothing_recorded() {
+        // Given
+        let spans = Spans::default();
+
+        // When
+        drop(spans.target_span("import_png", "//icons:ic_home"));
+
+        // Then
+        assert!(spans.records().is_empty());
+    }
+}
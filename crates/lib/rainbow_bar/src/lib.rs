@@ -2,8 +2,8 @@
 //! # Rainbow Progress Bar
 //!
 //! A customizable terminal progress bar that automatically selects the most colorful rendering
-//! based on terminal capabilities (monochrome, ANSI, or Xterm 256-color). It can also be
-//! manually configured via [`ProgressBarOptions`].
+//! based on terminal capabilities (monochrome, ANSI, Xterm 256-color or 24-bit truecolor).
+//! It can also be manually configured via [`ProgressBarOptions`].
 //!
 //! ## Usage
 //!
@@ -18,22 +18,81 @@
 //! println!("{pb}");
 //! ```
 //!
-//! For animated bars (Xterm only), call [`ProgressBar::update_anim_state()`] between frames.
+//! For animated bars (Xterm and TrueColor only), call [`ProgressBar::update_anim_state()`]
+//! between frames.
 //!
 //! ## Palette Auto-Detection
 //!
-//! The bar automatically detects the best color mode using `supports-color`.
+//! The bar automatically detects the best color mode using `supports-color` and the
+//! `COLORTERM` environment variable.
 //!
 //! Supported palettes:
 //! - Monochrome (fallback)
 //! - ANSI (8-bit color)
 //! - Xterm (256-color with rainbow animation)
+//! - TrueColor (24-bit color with smooth gradient animation)
+//!
+//! ## Custom Gradients
+//!
+//! In TrueColor mode the bar is painted with a [`Gradient`]. The default one is a rainbow,
+//! but downstream tools can brand the bar with their own colors:
+//! ```
+//! use lib_rainbow_bar::{Gradient, Palette, ProgressBar, ProgressBarOptions, Rgb};
+//!
+//! let pb = ProgressBar::new(ProgressBarOptions {
+//!     override_palette: Some(Palette::TrueColor),
+//!     override_gradient: Some(Gradient::new(&[Rgb(0x8A, 0x2B, 0xE2), Rgb(0x00, 0xBF, 0xFF)])),
+//!     ..Default::default()
+//! });
+//! ```
+//!
+//! ## ETA and Transfer Rate
+//!
+//! Call [`ProgressBar::sample_rates()`] every frame to keep the moving averages of the
+//! progress and of [`ProgressBar::transferred_bytes`] up to date. Once they are known,
+//! the bar shows the estimated time left and the transfer rate after the counter:
+//! ```text
+//! ━━━━━━━━━━━━━━━━━━━━╸                    42/100 · ETA 1m 05s · 3.4 MB/s
+//! ```
+//!
+//! ## Indeterminate Mode
+//!
+//! When the total is unknown, e.g. while a stream of unknown length is consumed, set
+//! [`ProgressBar::indeterminate`] and the bar shows a segment bouncing between its ends
+//! on every [`ProgressBar::update_anim_state()`] instead of the progress.
+//!
+//! ## Templates
+//!
+//! The default layout is the bar followed by the counter. Pass a template to lay it out
+//! differently, the placeholders are `{spinner}`, `{bar}`, `{pos}`, `{len}`, `{msg}`,
+//! `{eta}` and `{rate}`:
+//! ```
+//! use lib_rainbow_bar::{Palette, ProgressBar, ProgressBarOptions};
+//!
+//! let mut pb = ProgressBar::new(ProgressBarOptions {
+//!     bar_width: 10,
+//!     override_palette: Some(Palette::Monochrome),
+//!     template: Some("{spinner} {bar} {pos}/{len} {msg}".to_owned()),
+//!     ..Default::default()
+//! });
+//! pb.max = 100;
+//! pb.current = 50;
+//! pb.message = "ic_star".to_owned();
+//! assert_eq!("⠋ ━━━━━      50/100 ic_star", pb.to_string());
+//! ```
 //!
 //! ## Entry Point
 //! - [`ProgressBar::new()`] is the main constructor.
 
-use std::fmt::Display;
+use std::{fmt::Display, time::Instant};
 use supports_color::ColorLevel;
+use unicode_width::UnicodeWidthStr;
+
+mod rate;
+pub use rate::RateMeter;
+use rate::{format_eta, format_transfer_rate};
+mod template;
+use template::{Part, Template};
 
 /// A terminal progress bar that adapts to the terminal's color capabilities.
 ///
@@ -44,10 +103,20 @@ pub struct ProgressBar {
     pub max: usize,
     /// The current progress value
     pub current: usize,
+    /// Total number of bytes transferred so far, shown as the transfer rate if not zero
+    pub transferred_bytes: u64,
+    /// The maximum is unknown, so a bouncing segment is drawn instead of the progress
+    pub indeterminate: bool,
+    /// Text shown in place of `{msg}` of the template
+    pub message: String,
     width: usize,
     palette: Palette,
     ansi_colors: (u8, u8),
+    gradient: Gradient,
     anim_state: usize,
+    completion_rate: RateMeter,
+    transfer_rate: RateMeter,
+    template: Option<Template>,
 }
 
 /// Configuration options for [`ProgressBar`].
@@ -60,6 +129,11 @@ pub struct ProgressBarOptions {
     pub override_palette: Option<Palette>,
     /// Override ANSI foreground colors: (bar, track)
     pub override_ansi_colors: Option<(u8, u8)>,
+    /// Override the gradient used in [`Palette::TrueColor`] mode
+    pub override_gradient: Option<Gradient>,
+    /// Layout of the bar, e.g. `"{spinner} {bar} {pos}/{len} {msg}"`,
+    /// the bar with the counter if not set
+    pub template: Option<String>,
 }
 
 /// Available color palettes for rendering the progress bar.
@@ -68,6 +142,7 @@ pub struct ProgressBarOptions {
 /// - [`Palette::Monochrome`] – No colors; uses plain Unicode characters.
 /// - [`Palette::Ansi`] – Basic 8-color ANSI escape codes.
 /// - [`Palette::Xterm`] – Full 256-color Xterm palette with animated rainbow effects.
+/// - [`Palette::TrueColor`] – 24-bit RGB colors with smooth animated [`Gradient`].
 ///
 /// This is usually auto-selected based on the terminal’s color support, but can be overridden
 /// manually via [`ProgressBarOptions`].
@@ -78,6 +153,69 @@ pub enum Palette {
     Ansi,
     /// Renders the bar using 256-color Xterm codes and a rainbow animation
     Xterm,
+    /// Renders the bar using 24-bit RGB codes and a smooth gradient animation
+    TrueColor,
+}
+
+/// A 24-bit RGB color
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+/// A looped sequence of color stops used to paint the bar in [`Palette::TrueColor`] mode.
+///
+/// Colors between the stops are linearly interpolated, and the last stop blends back
+/// into the first one, so the animation has no visible seam.
+#[derive(Clone, Debug)]
+pub struct Gradient {
+    stops: Vec<Rgb>,
+}
+
+impl Gradient {
+    /// Creates a gradient from the given color stops.
+    ///
+    /// An empty slice falls back to the [`Gradient::rainbow()`] gradient.
+    pub fn new(stops: &[Rgb]) -> Self {
+        if stops.is_empty() {
+            return Self::rainbow();
+        }
+        Self {
+            stops: stops.to_vec(),
+        }
+    }
+
+    /// The default gradient: all hues of the rainbow
+    pub fn rainbow() -> Self {
+        Self {
+            stops: vec![
+                Rgb(255, 0, 0),
+                Rgb(255, 255, 0),
+                Rgb(0, 255, 0),
+                Rgb(0, 255, 255),
+                Rgb(0, 0, 255),
+                Rgb(255, 0, 255),
+            ],
+        }
+    }
+
+    /// Returns the color at position `t`, where `t` is a fraction of the whole loop.
+    ///
+    /// Values outside of `0.0..1.0` are wrapped around.
+    pub fn sample(&self, t: f32) -> Rgb {
+        let n = self.stops.len();
+        let pos = t.rem_euclid(1.0) * n as f32;
+        let idx = (pos as usize).min(n - 1);
+        let frac = pos - idx as f32;
+        let Rgb(r1, g1, b1) = self.stops[idx];
+        let Rgb(r2, g2, b2) = self.stops[(idx + 1) % n];
+        let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * frac).round() as u8;
+        Rgb(lerp(r1, r2), lerp(g1, g2), lerp(b1, b2))
+    }
+}
+
+impl Default for Gradient {
+    fn default() -> Self {
+        Self::rainbow()
+    }
 }
 
 impl Default for ProgressBarOptions {
@@ -86,6 +224,8 @@ impl Default for ProgressBarOptions {
             bar_width: 40,
             override_palette: None,
             override_ansi_colors: None,
+            override_gradient: None,
+            template: None,
         }
     }
 }
@@ -101,9 +241,13 @@ impl ProgressBar {
         196, 202, 208, 214, 220, 226, 190, 154, 118, 82, 46, 47, 48, 49, 50, 51, 45, 39, 33, 27,
         21, 57, 93, 129, 165, 201, 200, 199, 198, 197,
     ];
+    /// Frames of the `{spinner}` placeholder
+    const SPINNER_FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
     /// Looped sequence of the shades of grey
     const XTERM_COLORS_TRACK: &'static [u8; 10] =
         &[235, 236, 237, 238, 239, 240, 239, 238, 237, 236];
+    /// Number of bar cells the gradient is stretched over in TrueColor mode
+    const TRUECOLOR_GRADIENT_PERIOD: usize = 30;
     const RESET_STYLE: u8 = 0;
 
     /// Creates a new [`ProgressBar`] using the given options.
@@ -113,11 +257,16 @@ impl ProgressBar {
         Self {
             max: 0,
             current: 0,
+            transferred_bytes: 0,
+            indeterminate: false,
+            message: String::new(),
             width: opts.bar_width,
             palette: opts.override_palette.unwrap_or_else(|| {
                 match supports_color::on_cached(supports_color::Stream::Stderr) {
                     None => Palette::Monochrome,
                     Some(l) => match l {
+                        ColorLevel { has_16m: true, .. } => Palette::TrueColor,
+                        _ if colorterm_supports_truecolor() => Palette::TrueColor,
                         ColorLevel { has_256: true, .. } => Palette::Xterm,
                         ColorLevel {
                             has_basic: true, ..
@@ -129,37 +278,173 @@ impl ProgressBar {
             ansi_colors: opts
                 .override_ansi_colors
                 .unwrap_or_else(|| (Self::ANSI_COLOR_BAR, Self::ANSI_COLOR_TRACK)),
+            gradient: opts.override_gradient.unwrap_or_default(),
             anim_state: 0,
+            completion_rate: RateMeter::default(),
+            transfer_rate: RateMeter::default(),
+            template: opts.template.as_deref().map(Template::parse),
         }
     }
 
     /// Updates the internal animation state.
     ///
-    /// Call this in a render loop to animate the bar in Xterm mode.
+    /// Call this in a render loop to animate the bar in Xterm and TrueColor modes.
     pub fn update_anim_state(&mut self) {
         self.anim_state = self.anim_state.wrapping_add(1);
     }
 
-    /// Returns the total width of the rendered progress bar string.
+    /// Updates the moving averages of the progress and of the transferred bytes.
     ///
-    /// This includes the progress fraction (e.g. `" 42/100"`).
+    /// Call this in a render loop after updating `current` and `transferred_bytes`,
+    /// the ETA and the transfer rate are shown once enough samples are recorded.
+    pub fn sample_rates(&mut self, now: Instant) {
+        self.completion_rate.sample(self.current as u64, now);
+        self.transfer_rate.sample(self.transferred_bytes, now);
+    }
+
+    /// Returns the total width of the rendered progress bar string in terminal columns.
+    ///
+    /// This includes the progress fraction (e.g. `" 42/100"`) and the ETA with the transfer rate,
+    /// or everything the template lays out. Wide chars, e.g. CJK ones in the message, count twice.
     /// This not includes the ansi escape codes or any control symbols.
     pub fn len(&self) -> usize {
-        let number1_len = self.max.checked_ilog10().unwrap_or(0) + 1;
-        let number2_len = self.current.checked_ilog10().unwrap_or(0) + 1;
-        // +2 because of space ' ' and '/' delimeter
-        self.width + number1_len as usize + number2_len as usize + 2
+        visible_len(&self.to_string())
+    }
+
+    /// ETA and transfer rate after the counter, e.g. `" · ETA 12s · 3.4 MB/s"`
+    fn stats(&self) -> String {
+        let mut stats = String::new();
+        if let Some(eta) = self.eta() {
+            stats.push_str(&format!(" · ETA {}", format_eta(eta)));
+        }
+        if let Some(rate) = self.transfer_rate() {
+            stats.push_str(&format!(" · {}", format_transfer_rate(rate)));
+        }
+        stats
+    }
+
+    fn eta(&self) -> Option<std::time::Duration> {
+        let remaining = self.max.saturating_sub(self.current) as u64;
+        if self.indeterminate || self.current == 0 || remaining == 0 {
+            return None;
+        }
+        self.completion_rate.eta(remaining)
+    }
+
+    fn transfer_rate(&self) -> Option<f64> {
+        match self.transferred_bytes {
+            0 => None,
+            _ => self.transfer_rate.per_sec(),
+        }
+    }
+
+    fn fmt_bar(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.indeterminate {
+            return self.fmt_bouncing(f);
+        }
+        let percent = match (self.current, self.max) {
+            (0, _) | (_, 0) => 0.0,
+            (c, m) => c as f32 / m as f32,
+        };
+        match self.palette {
+            Palette::Monochrome => self.fmt_monochrome(f, percent),
+            Palette::Ansi => self.fmt_ansi(f, percent),
+            Palette::Xterm => self.fmt_xterm(f, percent),
+            Palette::TrueColor => self.fmt_truecolor(f, percent),
+        }
+    }
+
+    /// Segment of a fifth of the bar moving back and forth with the animation
+    fn fmt_bouncing(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = self.width;
+        let segment = (width / 5).clamp(1, width.max(1));
+        let travel = width.saturating_sub(segment);
+        let offset = match travel {
+            0 => 0,
+            travel => match self.anim_state % (2 * travel) {
+                pos if pos <= travel => pos,
+                pos => 2 * travel - pos,
+            },
+        };
+        let (bar_color, track_color) = self.ansi_colors;
+        let track_grey = xterm_grey_to_rgb(Self::XTERM_COLORS_TRACK[2]);
+        for i in 0..width {
+            let filled = (offset..offset + segment).contains(&i);
+            match (&self.palette, filled) {
+                (Palette::Monochrome, true) => write!(f, "━")?,
+                (Palette::Monochrome, false) => write!(f, " ")?,
+                (Palette::Ansi, true) => write!(f, "\x1b[{bar_color}m━")?,
+                (Palette::Ansi, false) => write!(f, "\x1b[{track_color}m━")?,
+                (Palette::Xterm, true) => {
+                    let color = Self::XTERM_COLORS_BAR[(i + self.anim_state) % 30];
+                    write!(f, "\x1b[38;5;{color}m━")?
+                }
+                (Palette::Xterm, false) => {
+                    write!(f, "\x1b[38;5;{}m━", Self::XTERM_COLORS_TRACK[2])?
+                }
+                (Palette::TrueColor, true) => {
+                    let t = ((i + self.anim_state) % Self::TRUECOLOR_GRADIENT_PERIOD) as f32
+                        / Self::TRUECOLOR_GRADIENT_PERIOD as f32;
+                    let Rgb(r, g, b) = self.gradient.sample(t);
+                    write!(f, "\x1b[38;2;{r};{g};{b}m━")?
+                }
+                (Palette::TrueColor, false) => write!(f, "\x1b[38;2;{0};{0};{0}m━", track_grey)?,
+            }
+        }
+        match self.palette {
+            Palette::Monochrome => Ok(()),
+            _ => write!(f, "\x1b[{}m", Self::RESET_STYLE),
+        }
+    }
+
+    fn fmt_template(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        template: &Template,
+    ) -> std::fmt::Result {
+        for part in &template.parts {
+            match part {
+                Part::Literal(text) => f.write_str(text)?,
+                Part::Spinner => {
+                    let frame = Self::SPINNER_FRAMES[self.anim_state % Self::SPINNER_FRAMES.len()];
+                    write!(f, "{frame}")?
+                }
+                Part::Bar => self.fmt_bar(f)?,
+                Part::Pos => write!(f, "{}", self.current)?,
+                Part::Len if self.indeterminate => f.write_str("?")?,
+                Part::Len => write!(f, "{}", self.max)?,
+                Part::Msg => f.write_str(&self.message)?,
+                Part::Eta => {
+                    if let Some(eta) = self.eta() {
+                        f.write_str(&format_eta(eta))?
+                    }
+                }
+                Part::Rate => {
+                    if let Some(rate) = self.transfer_rate() {
+                        f.write_str(&format_transfer_rate(rate))?
+                    }
+                }
+            }
+        }
+        Ok(())
     }
 
     #[inline]
     fn fmt_monochrome(&self, f: &mut std::fmt::Formatter<'_>, percent: f32) -> std::fmt::Result {
         let ProgressBar {
-            max,
-            current,
+            max: _,
+            current: _,
             width,
             palette: _,
             ansi_colors: _,
+            gradient: _,
             anim_state: _,
+            transferred_bytes: _,
+            completion_rate: _,
+            transfer_rate: _,
+            indeterminate: _,
+            message: _,
+            template: _,
         } = *self;
         let f2x = match (width as f32 * percent * 2.0) as usize {
             0 if percent > 0.0 => 1,
@@ -176,18 +461,25 @@ impl ProgressBar {
             let w = width.saturating_sub(f1x + 1);
             write!(f, "{0:━>f1x$}╸{0: >w$}", "")?;
         }
-        write!(f, " {current}/{max}")
+        Ok(())
     }
 
     #[inline]
     fn fmt_ansi(&self, f: &mut std::fmt::Formatter<'_>, percent: f32) -> std::fmt::Result {
         let ProgressBar {
-            max,
-            current,
+            max: _,
+            current: _,
             width,
             palette: _,
             ansi_colors: (bar_color, track_color),
+            gradient: _,
             anim_state: _,
+            transferred_bytes: _,
+            completion_rate: _,
+            transfer_rate: _,
+            indeterminate: _,
+            message: _,
+            template: _,
         } = *self;
         let f2x = match (width as f32 * percent * 2.0) as usize {
             0 if percent > 0.0 => 1,
@@ -209,18 +501,25 @@ impl ProgressBar {
             },
         )?;
 
-        write!(f, " {current}/{max}")
+        Ok(())
     }
 
     #[inline]
     fn fmt_xterm(&self, f: &mut std::fmt::Formatter<'_>, percent: f32) -> std::fmt::Result {
         let ProgressBar {
-            max,
-            current,
+            max: _,
+            current: _,
             width,
             palette: _,
             ansi_colors: _,
+            gradient: _,
             anim_state,
+            transferred_bytes: _,
+            completion_rate: _,
+            transfer_rate: _,
+            indeterminate: _,
+            message: _,
+            template: _,
         } = *self;
         let f2x = match (width as f32 * percent * 2.0) as usize {
             0 if percent > 0.0 => 1,
@@ -249,22 +548,103 @@ impl ProgressBar {
             write!(f, "\x1b[38;5;{track_color}m━")?;
         }
 
-        write!(f, "\x1b[{}m {current}/{max}", Self::RESET_STYLE)
+        write!(f, "\x1b[{}m", Self::RESET_STYLE)
     }
+
+    #[inline]
+    fn fmt_truecolor(&self, f: &mut std::fmt::Formatter<'_>, percent: f32) -> std::fmt::Result {
+        let ProgressBar {
+            max: _,
+            current: _,
+            width,
+            palette: _,
+            ansi_colors: _,
+            ref gradient,
+            anim_state,
+            transferred_bytes: _,
+            completion_rate: _,
+            transfer_rate: _,
+            indeterminate: _,
+            message: _,
+            template: _,
+        } = *self;
+        let f2x = match (width as f32 * percent * 2.0) as usize {
+            0 if percent > 0.0 => 1,
+            val => val,
+        };
+        let f1x = f2x / 2;
+
+        let track_grey = xterm_grey_to_rgb(if percent == 0.0 {
+            Self::XTERM_COLORS_TRACK[anim_state % 10]
+        } else {
+            Self::XTERM_COLORS_TRACK[2]
+        });
+        let period = Self::TRUECOLOR_GRADIENT_PERIOD as f32;
+        let rev_anim_state = usize::MAX / 2 - anim_state;
+        let bar_color = |i: usize| {
+            let Rgb(r, g, b) = gradient
+                .sample(((i + rev_anim_state) % Self::TRUECOLOR_GRADIENT_PERIOD) as f32 / period);
+            format!("\x1b[38;2;{r};{g};{b}m")
+        };
+
+        for i in 0..f1x {
+            write!(f, "{}━", bar_color(i))?;
+        }
+        if f2x % 2 == 1 {
+            write!(f, "{}╸", bar_color(f1x))?;
+        } else if f1x < width {
+            write!(f, "\x1b[38;2;{0};{0};{0}m╺", track_grey)?;
+        }
+        for _ in f1x..width.saturating_sub(1) {
+            write!(f, "\x1b[38;2;{0};{0};{0}m━", track_grey)?;
+        }
+
+        write!(f, "\x1b[{}m", Self::RESET_STYLE)
+    }
+}
+
+/// Checks the `COLORTERM` environment variable for explicit 24-bit color support
+fn colorterm_supports_truecolor() -> bool {
+    std::env::var("COLORTERM")
+        .map(|it| it.eq_ignore_ascii_case("truecolor") || it.eq_ignore_ascii_case("24bit"))
+        .unwrap_or(false)
+}
+
+/// Converts one of the Xterm grayscale ramp colors (232..=255) to its RGB grey level
+fn xterm_grey_to_rgb(color: u8) -> u8 {
+    8 + 10 * color.saturating_sub(232)
+}
+
+/// Number of terminal columns the rendered bar takes, wide chars of the message
+/// take two of them and the escape sequences take none
+fn visible_len(rendered: &str) -> usize {
+    let mut text = String::with_capacity(rendered.len());
+    let mut chars = rendered.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // skip the CSI sequence up to its final byte, e.g. `ESC[38;5;196m`
+            chars
+                .by_ref()
+                .skip(1)
+                .find(|c| ('\x40'..='\x7e').contains(c));
+        } else {
+            text.push(c);
+        }
+    }
+    text.width()
 }
 
 impl Display for ProgressBar {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let percent = match (self.current, self.max) {
-            (0, _) | (_, 0) => 0.0,
-            (c, m) => c as f32 / m as f32,
-        };
-
-        match self.palette {
-            Palette::Monochrome => self.fmt_monochrome(f, percent),
-            Palette::Ansi => self.fmt_ansi(f, percent),
-            Palette::Xterm => self.fmt_xterm(f, percent),
+        if let Some(template) = &self.template {
+            return self.fmt_template(f, template);
         }
+        self.fmt_bar(f)?;
+        match self.indeterminate {
+            true => write!(f, " {}", self.current)?,
+            false => write!(f, " {}/{}", self.current, self.max)?,
+        }
+        f.write_str(&self.stats())
     }
 }
 
@@ -321,6 +701,39 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_truecolor_in_action() {
+        let mut pb = ProgressBar::new(ProgressBarOptions {
+            override_palette: Some(Palette::TrueColor),
+            ..Default::default()
+        });
+        pb.max = 146;
+        for _ in 0..10 {
+            pb.update_anim_state();
+            eprint!("\r{pb} ");
+            thread::sleep(Duration::from_millis(50));
+        }
+        for i in 0..=146 {
+            pb.current = i;
+            pb.update_anim_state();
+            eprint!("\r{pb} ");
+            thread::sleep(Duration::from_millis(50));
+        }
+    }
+
+    #[test]
+    fn test_gradient_sample_stops_and_midpoints() {
+        // Given
+        let gradient = Gradient::new(&[Rgb(0, 0, 0), Rgb(200, 100, 50)]);
+
+        // Then
+        assert_eq!(Rgb(0, 0, 0), gradient.sample(0.0));
+        assert_eq!(Rgb(100, 50, 25), gradient.sample(0.25));
+        assert_eq!(Rgb(200, 100, 50), gradient.sample(0.5));
+        assert_eq!(Rgb(100, 50, 25), gradient.sample(0.75));
+        assert_eq!(Rgb(0, 0, 0), gradient.sample(1.0));
+    }
+
     #[test]
     fn test_monochrome_progress_0of0() {
         // Given
@@ -420,4 +833,94 @@ mod test {
         assert_eq!("━━━━━━━━━━ 100/100", output);
         assert_eq!(length, output.chars().count());
     }
+
+    #[test]
+    fn test_monochrome_indeterminate_bounces_between_ends() {
+        // Given
+        let mut pb = ProgressBar::new(ProgressBarOptions {
+            bar_width: 10,
+            override_palette: Some(Palette::Monochrome),
+            ..Default::default()
+        });
+        pb.indeterminate = true;
+        pb.current = 7;
+
+        // When
+        let mut frames = Vec::new();
+        for _ in 0..11 {
+            frames.push(pb.to_string());
+            pb.update_anim_state();
+        }
+
+        // Then
+        assert_eq!("━━         7", frames[0]);
+        assert_eq!("   ━━      7", frames[3]);
+        assert_eq!("        ━━ 7", frames[8]);
+        assert_eq!("      ━━   7", frames[10]);
+        assert_eq!(pb.len(), frames[0].chars().count());
+    }
+
+    #[test]
+    fn test_truecolor_template_len_without_escape_codes() {
+        // Given
+        let mut pb = ProgressBar::new(ProgressBarOptions {
+            bar_width: 10,
+            override_palette: Some(Palette::TrueColor),
+            template: Some("{spinner} [{bar}] {pos}/{len} {msg}".to_owned()),
+            ..Default::default()
+        });
+        pb.indeterminate = true;
+        pb.current = 3;
+        pb.message = "fetching".to_owned();
+
+        // When
+        let output = pb.to_string();
+
+        // Then
+        assert!(output.starts_with("⠋ [\x1b[38;2;"));
+        assert!(output.ends_with("\x1b[0m] 3/? fetching"));
+        assert_eq!("⠋ [] 3/? fetching".chars().count() + 10, pb.len());
+    }
+
+    #[test]
+    fn test_template_len_with_wide_message() {
+        // Given
+        let mut pb = ProgressBar::new(ProgressBarOptions {
+            bar_width: 10,
+            override_palette: Some(Palette::Ansi),
+            template: Some("{bar} {msg}".to_owned()),
+            ..Default::default()
+        });
+        pb.message = "图标 ⭐".to_owned();
+
+        // When
+        let length = pb.len();
+
+        // Then
+        assert_eq!(10 + 1 + 7, length);
+    }
+
+    #[test]
+    fn test_monochrome_progress_with_eta_and_transfer_rate() {
+        // Given
+        let mut pb = ProgressBar::new(ProgressBarOptions {
+            bar_width: 10,
+            override_palette: Some(Palette::Monochrome),
+            ..Default::default()
+        });
+        let start = Instant::now();
+        pb.max = 100;
+        pb.sample_rates(start);
+
+        // When
+        pb.current = 50;
+        pb.transferred_bytes = 6_800_000;
+        pb.sample_rates(start + Duration::from_secs(2));
+        let output = pb.to_string();
+        let length = pb.len();
+
+        // Then
+        assert_eq!("━━━━━      50/100 · ETA 2s · 3.4 MB/s", output);
+        assert_eq!(length, output.chars().count());
+    }
 }
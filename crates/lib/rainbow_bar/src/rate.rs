@@ -0,0 +1,153 @@
+use std::time::{Duration, Instant};
+
+/// Exponential moving average of how fast a counter grows, e.g. completed items
+/// or transferred bytes per second.
+///
+/// Feed it the running total with [`RateMeter::sample()`] as often as convenient:
+/// samples closer than [`RateMeter::MIN_SAMPLE_INTERVAL`] to the previous one are skipped,
+/// so redrawing the bar at a high frame rate doesn't make the average jumpy.
+#[derive(Clone, Debug, Default)]
+pub struct RateMeter {
+    last_sample: Option<(Instant, u64)>,
+    per_sec: Option<f64>,
+}
+
+impl RateMeter {
+    /// Samples closer to each other than this are skipped
+    pub const MIN_SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+    /// Older samples lose weight over this time, shorter windows follow bursts too closely
+    const SMOOTHING_WINDOW: Duration = Duration::from_secs(5);
+
+    /// Records the `total` value of the counter at the moment `now`
+    pub fn sample(&mut self, total: u64, now: Instant) {
+        let Some((last_time, last_total)) = self.last_sample else {
+            self.last_sample = Some((now, total));
+            return;
+        };
+        let elapsed = now.saturating_duration_since(last_time);
+        if elapsed < Self::MIN_SAMPLE_INTERVAL {
+            return;
+        }
+        let elapsed_secs = elapsed.as_secs_f64();
+        let instant_rate = total.saturating_sub(last_total) as f64 / elapsed_secs;
+        // the weight of the new sample depends on how much time it covers
+        let alpha = 1.0 - (-elapsed_secs / Self::SMOOTHING_WINDOW.as_secs_f64()).exp();
+        self.per_sec = Some(match self.per_sec {
+            Some(rate) => rate + alpha * (instant_rate - rate),
+            None => instant_rate,
+        });
+        self.last_sample = Some((now, total));
+    }
+
+    /// Average growth of the counter per second, `None` until two samples are recorded
+    pub fn per_sec(&self) -> Option<f64> {
+        self.per_sec
+    }
+
+    /// Time left until the counter grows by `remaining` at the average rate,
+    /// `None` if the rate is unknown or the counter has stalled
+    pub fn eta(&self, remaining: u64) -> Option<Duration> {
+        match self.per_sec {
+            Some(rate) if rate > f64::EPSILON => {
+                Some(Duration::from_secs_f64(remaining as f64 / rate))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// E.g. `45s`, `2m 05s` or `1h 20m`
+pub(crate) fn format_eta(eta: Duration) -> String {
+    let secs = eta.as_secs();
+    match (secs / 3600, secs % 3600 / 60, secs % 60) {
+        (0, 0, s) => format!("{s}s"),
+        (0, m, s) => format!("{m}m {s:02}s"),
+        (h, m, _) => format!("{h}h {m:02}m"),
+    }
+}
+
+/// E.g. `512 B/s`, `12.3 kB/s` or `3.4 MB/s`, in decimal units as network speeds usually are
+pub(crate) fn format_transfer_rate(bytes_per_sec: f64) -> String {
+    if bytes_per_sec < 1_000.0 {
+        format!("{bytes_per_sec:.0} B/s")
+    } else if bytes_per_sec < 1_000_000.0 {
+        format!("{:.1} kB/s", bytes_per_sec / 1_000.0)
+    } else {
+        format!("{:.1} MB/s", bytes_per_sec / 1_000_000.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rate_meter_unknown_until_second_sample() {
+        // Given
+        let mut meter = RateMeter::default();
+        let start = Instant::now();
+
+        // When
+        meter.sample(10, start);
+
+        // Then
+        assert_eq!(None, meter.per_sec());
+        assert_eq!(None, meter.eta(10));
+    }
+
+    #[test]
+    fn test_rate_meter_steady_rate_and_eta() {
+        // Given
+        let mut meter = RateMeter::default();
+        let start = Instant::now();
+
+        // When
+        for i in 0..=10 {
+            meter.sample(i * 5, start + Duration::from_secs(i));
+        }
+
+        // Then
+        assert_eq!(Some(5.0), meter.per_sec());
+        assert_eq!(Some(Duration::from_secs(4)), meter.eta(20));
+    }
+
+    #[test]
+    fn test_rate_meter_skips_frequent_samples() {
+        // Given
+        let mut meter = RateMeter::default();
+        let start = Instant::now();
+        meter.sample(0, start);
+
+        // When
+        meter.sample(100, start + Duration::from_millis(50));
+
+        // Then
+        assert_eq!(None, meter.per_sec());
+    }
+
+    #[test]
+    fn test_rate_meter_smooths_bursts() {
+        // Given
+        let mut meter = RateMeter::default();
+        let start = Instant::now();
+        meter.sample(0, start);
+        meter.sample(10, start + Duration::from_secs(1));
+
+        // When
+        meter.sample(110, start + Duration::from_secs(2));
+
+        // Then
+        let rate = meter.per_sec().unwrap();
+        assert!(rate > 10.0 && rate < 100.0, "rate {rate} is not smoothed");
+    }
+
+    #[test]
+    fn test_format_eta_and_transfer_rate() {
+        assert_eq!("45s", format_eta(Duration::from_secs(45)));
+        assert_eq!("2m 05s", format_eta(Duration::from_secs(125)));
+        assert_eq!("1h 20m", format_eta(Duration::from_secs(4800)));
+        assert_eq!("512 B/s", format_transfer_rate(512.0));
+        assert_eq!("12.3 kB/s", format_transfer_rate(12_300.0));
+        assert_eq!("3.4 MB/s", format_transfer_rate(3_400_000.0));
+    }
+}
@@ -0,0 +1,109 @@
+/// Layout of the [`ProgressBar`](crate::ProgressBar) parsed from a template string,
+/// e.g. `"{spinner} {bar} {pos}/{len} {msg}"`
+pub(crate) struct Template {
+    pub(crate) parts: Vec<Part>,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum Part {
+    Literal(String),
+    /// `{spinner}`, a single animated char
+    Spinner,
+    /// `{bar}`, the bar itself
+    Bar,
+    /// `{pos}`, the current value
+    Pos,
+    /// `{len}`, the maximum value, or `?` in the indeterminate mode
+    Len,
+    /// `{msg}`, the message of the bar
+    Msg,
+    /// `{eta}`, the estimated time left, empty until known
+    Eta,
+    /// `{rate}`, the transfer rate, empty until known
+    Rate,
+}
+
+impl Template {
+    /// Unknown placeholders are kept as they are, so a typo is visible on the screen
+    pub(crate) fn parse(template: &str) -> Self {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            literal.push_str(&rest[..start]);
+            let Some(len) = rest[start..].find('}') else {
+                literal.push_str(&rest[start..]);
+                rest = "";
+                break;
+            };
+            let placeholder = &rest[start..start + len + 1];
+            let part = match placeholder {
+                "{spinner}" => Part::Spinner,
+                "{bar}" => Part::Bar,
+                "{pos}" => Part::Pos,
+                "{len}" => Part::Len,
+                "{msg}" => Part::Msg,
+                "{eta}" => Part::Eta,
+                "{rate}" => Part::Rate,
+                unknown => {
+                    literal.push_str(unknown);
+                    rest = &rest[start + len + 1..];
+                    continue;
+                }
+            };
+            if !literal.is_empty() {
+                parts.push(Part::Literal(std::mem::take(&mut literal)));
+            }
+            parts.push(part);
+            rest = &rest[start + len + 1..];
+        }
+        literal.push_str(rest);
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+        Self { parts }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_template_parse_placeholders_and_literals() {
+        // When
+        let template = Template::parse("{spinner} {bar} {pos}/{len} {msg}");
+
+        // Then
+        assert_eq!(
+            vec![
+                Part::Spinner,
+                Part::Literal(" ".to_owned()),
+                Part::Bar,
+                Part::Literal(" ".to_owned()),
+                Part::Pos,
+                Part::Literal("/".to_owned()),
+                Part::Len,
+                Part::Literal(" ".to_owned()),
+                Part::Msg,
+            ],
+            template.parts,
+        );
+    }
+
+    #[test]
+    fn test_template_parse_unknown_and_unclosed_placeholders() {
+        // When
+        let template = Template::parse("[{bar}] {speed} {pos");
+
+        // Then
+        assert_eq!(
+            vec![
+                Part::Literal("[".to_owned()),
+                Part::Bar,
+                Part::Literal("] {speed} {pos".to_owned()),
+            ],
+            template.parts,
+        );
+    }
+}
@@ -0,0 +1,86 @@
+/// Hard keywords of Kotlin, they cannot be used as identifiers without backticks
+const KOTLIN_HARD_KEYWORDS: &str = "as break class continue do else false for fun if in \
+    interface is null object package return super this throw true try typealias typeof \
+    val var when while";
+
+/// Characters which are not allowed in JVM names even inside backticks
+const JVM_FORBIDDEN_CHARS: &[char] = &['.', ';', '[', ']', '/', '<', '>', ':', '\\', '`'];
+
+/// Checks whether the `name` can be used as a Kotlin identifier as is
+pub fn is_kotlin_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    let starts_correctly = match chars.next() {
+        Some(c) => c.is_alphabetic() || c == '_',
+        None => false,
+    };
+    starts_correctly
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+        && !KOTLIN_HARD_KEYWORDS.split_whitespace().any(|kw| kw == name)
+}
+
+/// Checks whether the `name` can be used as a Kotlin identifier at least in backticks
+pub fn is_escapable_kotlin_identifier(name: &str) -> bool {
+    !name.trim().is_empty() && !name.contains(JVM_FORBIDDEN_CHARS)
+}
+
+/// Returns the `name` as is if it is a valid identifier, otherwise wraps it in backticks
+pub fn escape_kotlin_identifier(name: &str) -> String {
+    if is_kotlin_identifier(name) {
+        name.to_owned()
+    } else {
+        format!("`{name}`")
+    }
+}
+
+/// Prepends the `prefix` to the `name` if the name starts with a digit
+pub fn prefix_kotlin_identifier(name: &str, prefix: Option<&str>) -> String {
+    match prefix {
+        Some(prefix) if name.starts_with(|c: char| c.is_ascii_digit()) => {
+            format!("{prefix}{name}")
+        }
+        _ => name.to_owned(),
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn escape_kotlin_identifier__invalid_names__EXPECT__backticks() {
+        // When
+        let valid = escape_kotlin_identifier("ArrowBack");
+        let digit = escape_kotlin_identifier("24px");
+        let keyword = escape_kotlin_identifier("object");
+        let hyphen = escape_kotlin_identifier("arrow-back");
+
+        // Then
+        assert_eq!("ArrowBack", valid);
+        assert_eq!("`24px`", digit);
+        assert_eq!("`object`", keyword);
+        assert_eq!("`arrow-back`", hyphen);
+    }
+
+    #[test]
+    fn prefix_kotlin_identifier__name_starts_with_digit__EXPECT__prefixed_name() {
+        // When
+        let digit = prefix_kotlin_identifier("24px", Some("Ic"));
+        let letter = prefix_kotlin_identifier("Arrow", Some("Ic"));
+        let no_prefix = prefix_kotlin_identifier("24px", None);
+
+        // Then
+        assert_eq!("Ic24px", digit);
+        assert_eq!("Arrow", letter);
+        assert_eq!("24px", no_prefix);
+    }
+
+    #[test]
+    fn is_escapable_kotlin_identifier__jvm_forbidden_chars__EXPECT__false() {
+        // Then
+        assert!(is_escapable_kotlin_identifier("arrow back"));
+        assert!(!is_escapable_kotlin_identifier("arrow.back"));
+        assert!(!is_escapable_kotlin_identifier("arrow/back"));
+        assert!(!is_escapable_kotlin_identifier(""));
+    }
+}
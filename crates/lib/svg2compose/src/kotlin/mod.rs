@@ -4,5 +4,7 @@ mod code_block;
 pub use code_block::*;
 mod property_spec;
 pub use property_spec::*;
+mod identifier;
+pub use identifier::*;
 mod util;
 pub use util::*;
@@ -4,6 +4,7 @@ use std::collections::HashSet;
 pub struct PropertySpec {
     pub name: String,
     pub type_name: String,
+    pub kdoc: Option<String>,
     pub imports: HashSet<String>,
     pub annotations: Vec<String>,
     pub getter: Option<CodeBlock>,
@@ -18,6 +19,7 @@ impl PropertySpec {
         PropertySpecBuilder {
             name: name.as_ref().to_string(),
             type_name: type_name.as_ref().to_string(),
+            kdoc: None,
             imports: HashSet::new(),
             annotations: Vec::new(),
             getter: None,
@@ -32,6 +34,7 @@ impl PropertySpec {
 pub struct PropertySpecBuilder {
     name: String,
     type_name: String,
+    kdoc: Option<String>,
     imports: HashSet<String>,
     annotations: Vec<String>,
     getter: Option<CodeBlock>,
@@ -48,6 +51,11 @@ impl PropertySpecBuilder {
         self
     }
 
+    pub fn kdoc<S: AsRef<str>>(mut self, s: S) -> Self {
+        self.kdoc = Some(s.as_ref().to_string());
+        self
+    }
+
     pub fn add_annotation<S: AsRef<str>>(mut self, s: S) -> Self {
         self.annotations.push(s.as_ref().to_string());
         self
@@ -82,6 +90,7 @@ impl PropertySpecBuilder {
         PropertySpec {
             name: self.name,
             type_name: self.type_name,
+            kdoc: self.kdoc,
             imports: self.imports,
             annotations: self.annotations,
             getter: self.getter,
@@ -98,6 +107,7 @@ impl From<PropertySpec> for CodeBlock {
         let PropertySpec {
             name,
             type_name,
+            kdoc,
             imports,
             annotations,
             getter,
@@ -112,14 +122,17 @@ impl From<PropertySpec> for CodeBlock {
         } else {
             format!("{} ", modifiers.join(" "))
         };
+        let kdoc = kdoc.map(|it| kdoc_lines(&it)).unwrap_or_default();
         let mut result = if let Some(cb) = initializer {
             Self::builder()
+                .add_statements(&kdoc)
                 .add_statements(&annotations)
                 .add_statement(format!("{modifiers}{keyword} {name}: {type_name} = "))
                 .no_new_line()
                 .add_code_block(cb)
         } else {
             Self::builder()
+                .add_statements(&kdoc)
                 .add_statements(&annotations)
                 .add_statement(format!("{modifiers}{keyword} {name}: {type_name}"))
         };
@@ -139,3 +152,45 @@ impl From<PropertySpec> for CodeBlock {
             .build()
     }
 }
+
+/// Wraps the text into the KDoc comment, the comment is never closed by the text itself
+fn kdoc_lines(text: &str) -> Vec<String> {
+    let mut lines = vec!["/**".to_string()];
+    for line in text.trim().lines() {
+        let line = line.trim_end().replace("*/", "*&#47;");
+        if line.is_empty() {
+            lines.push(" *".to_string());
+        } else {
+            lines.push(format!(" * {line}"));
+        }
+    }
+    lines.push(" */".to_string());
+    lines
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn kdoc_lines__multiline_text_with_comment_end__EXPECT__escaped_kdoc() {
+        // Given
+        let text = "Arrow icon */\n\nSource: Figma";
+
+        // When
+        let lines = kdoc_lines(text);
+
+        // Then
+        assert_eq!(
+            vec![
+                "/**",
+                " * Arrow icon *&#47;",
+                " *",
+                " * Source: Figma",
+                " */"
+            ],
+            lines
+        );
+    }
+}
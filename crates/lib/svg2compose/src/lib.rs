@@ -2,9 +2,11 @@ use crate::kotlin::CodeBlock;
 use codegen::iv_builder::*;
 use kotlin::FileSpec;
 use lib_image_vector::ImageVector;
-use vec2compose::BackingFieldComposableSpec;
+use std::collections::HashMap;
+use vec2compose::{BackingFieldComposableSpec, property_name};
 
 mod kotlin;
+pub use kotlin::{is_escapable_kotlin_identifier, is_kotlin_identifier};
 mod vec2compose;
 mod codegen {
     pub(crate) mod iv_builder;
@@ -22,6 +24,17 @@ pub struct SvgToComposeOptions {
     pub color_mappings: Vec<ColorMapping>,
     pub preview: Option<ComposePreview>,
     pub composable_get: bool,
+    /// Template of the property name, `{name}` is replaced with the image name
+    pub property_name_template: Option<String>,
+    /// Fully qualified name of the object the property is declared on,
+    /// e.g. `androidx.compose.material.icons.Icons.Filled`
+    pub receiver_object: Option<String>,
+    /// Generate code for the `commonMain` source set of Compose Multiplatform
+    pub multiplatform: bool,
+    /// Prefix of the names starting with a digit, otherwise such names are escaped with backticks
+    pub identifier_prefix: Option<String>,
+    /// Image name => KDoc of its property, e.g. description of the Figma component
+    pub kdoc: HashMap<String, String>,
 }
 
 pub struct ColorMapping {
@@ -36,19 +49,39 @@ pub struct ComposePreview {
 }
 
 pub fn transform_svg_to_compose(svg: &[u8], options: SvgToComposeOptions) -> Result<Vec<u8>> {
-    let tree = usvg::Tree::from_data(svg, &Default::default())?;
-    let mut image_vector: ImageVector = tree.try_into()?;
-    image_vector.name = options.image_name.to_owned();
-    let iv_code_block = codegen_iv_builder(image_vector, &options.color_mappings)?;
-    let output = backing_field_template(iv_code_block, options);
-    Ok(output.into_bytes())
+    let image_name = options.image_name.to_owned();
+    transform_svgs_to_compose(&[(&image_name, svg)], options)
 }
 
-fn backing_field_template(iv_code_block: CodeBlock, options: SvgToComposeOptions) -> String {
-    let cb: FileSpec = BackingFieldComposableSpec {
-        options,
-        iv_code_block,
+/// Transforms several images into a single file with a property and a preview
+/// for every image. `options.image_name` is ignored, names of images are used instead.
+pub fn transform_svgs_to_compose(
+    images: &[(&str, &[u8])],
+    options: SvgToComposeOptions,
+) -> Result<Vec<u8>> {
+    let mut iv_code_blocks = Vec::with_capacity(images.len());
+    for (image_name, svg) in images {
+        let property_name = property_name(&options, image_name);
+        if !is_escapable_kotlin_identifier(&property_name) {
+            return Err(format!(
+                "`{property_name}` cannot be used as a Kotlin identifier even with backticks"
+            )
+            .into());
+        }
+        let tree = usvg::Tree::from_data(svg, &Default::default())?;
+        let mut image_vector: ImageVector = tree.try_into()?;
+        image_vector.name = image_name.to_string();
+        let iv_code_block = codegen_iv_builder(image_vector, &options.color_mappings)?;
+        iv_code_blocks.push((image_name.to_string(), iv_code_block));
     }
-    .into();
+    let output = backing_field_template(iv_code_blocks, options);
+    Ok(output.into_bytes())
+}
+
+fn backing_field_template(
+    images: Vec<(String, CodeBlock)>,
+    options: SvgToComposeOptions,
+) -> String {
+    let cb: FileSpec = BackingFieldComposableSpec { options, images }.into();
     cb.to_string().trim_end().to_string()
 }
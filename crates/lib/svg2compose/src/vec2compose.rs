@@ -3,116 +3,186 @@ use crate::kotlin::*;
 
 pub struct BackingFieldComposableSpec {
     pub options: SvgToComposeOptions,
-    pub iv_code_block: CodeBlock,
+    /// Image name => `ImageVector` builder code, a property is generated for every image
+    pub images: Vec<(String, CodeBlock)>,
 }
 
 impl From<BackingFieldComposableSpec> for FileSpec {
     fn from(value: BackingFieldComposableSpec) -> Self {
-        let BackingFieldComposableSpec {
-            options,
-            iv_code_block,
-        } = value;
-        let SvgToComposeOptions {
-            image_name,
-            package,
-            kotlin_explicit_api,
-            extension_target,
-            file_suppress_lint,
-            color_mappings: _,
-            preview,
-            composable_get,
-        } = options;
-
-        let backing_field_name = uncapitalize(&image_name);
-
-        // region: determine extension target
-        let (public_property_name, additional_import) = match &extension_target {
-            Some(fq_name) => {
-                if let Some((_, simple_name)) = fq_name.rsplit_once(".") {
-                    (format!("{simple_name}.{image_name}"), Some(fq_name))
-                } else {
-                    (format!("{fq_name}.{image_name}"), None)
-                }
+        let BackingFieldComposableSpec { options, images } = value;
+        let mut file =
+            Self::builder(&options.package).add_suppressions(options.file_suppress_lint.clone());
+        for (image_name, iv_code_block) in images {
+            for member in image_members(&options, &image_name, iv_code_block) {
+                file = file.add_member(member);
             }
-            None => (image_name.to_owned(), None),
-        };
-        // endregion: determine extension target
-
-        let public_property = PropertySpec::builder(&public_property_name, "ImageVector")
-            .require_import("androidx.compose.ui.graphics.vector.ImageVector")
-            .touch(|it| match additional_import {
-                Some(import) => it.require_import(import),
-                None => it,
-            })
-            .touch(|it| match kotlin_explicit_api {
-                true => it.add_modifier("public"),
-                false => it,
-            })
-            .getter(
-                CodeBlock::builder()
-                    .touch(|it| {
-                        if composable_get {
-                            it.begin_control_flow("@Composable get()")
-                                .require_import("androidx.compose.runtime.Composable")
-                        } else {
-                            it.begin_control_flow("get()")
-                        }
-                    })
-                    .begin_control_flow(format!("if (_{backing_field_name} != null)"))
-                    .add_statement(format!("return _{backing_field_name}!!"))
-                    .end_control_flow()
-                    .add_code_block(
-                        CodeBlock::builder()
-                            .add_statement(format!("_{backing_field_name} = "))
-                            .no_new_line()
-                            .add_code_block(iv_code_block)
-                            .build(),
-                    )
-                    .add_statement(format!("return _{backing_field_name}!!"))
-                    .end_control_flow()
-                    .build(),
-            )
-            .build();
+        }
+        file.build()
+    }
+}
 
-        let backing_field = PropertySpec::builder(format!("_{backing_field_name}"), "ImageVector?")
-            .require_import("androidx.compose.ui.graphics.vector.ImageVector")
-            .initializer(CodeBlock::builder().add_statement("null").build())
-            .add_modifier("private")
-            .mutable()
-            .build();
+/// Public property, its backing field and preview of the single image
+fn image_members(
+    options: &SvgToComposeOptions,
+    image_name: &str,
+    iv_code_block: CodeBlock,
+) -> [CodeBlock; 3] {
+    let SvgToComposeOptions {
+        image_name: _,
+        package: _,
+        kotlin_explicit_api,
+        extension_target,
+        file_suppress_lint: _,
+        color_mappings: _,
+        preview,
+        composable_get,
+        property_name_template: _,
+        receiver_object,
+        multiplatform,
+        identifier_prefix,
+        kdoc,
+    } = options;
 
-        let preview_fun = if let Some(preview) = preview {
-            let code = preview.code.replace("{name}", &image_name);
-            CodeBlock::builder()
-                .require_imports(&preview.imports)
-                .add_statement(code)
-                .build()
-        } else {
+    let property_name = property_name(options, image_name);
+    let backing_field_name =
+        escape_kotlin_identifier(&format!("_{}", uncapitalize(&property_name)));
+    let preview_fun_name = escape_kotlin_identifier(&format!(
+        "{}Preview",
+        prefix_kotlin_identifier(image_name, identifier_prefix.as_deref())
+    ));
+    let property_name = escape_kotlin_identifier(&property_name);
+
+    // region: determine extension target
+    let (public_property_name, additional_import) = match (&receiver_object, &extension_target) {
+        (Some(fq_name), _) => {
+            let (import, receiver) = split_receiver_object(fq_name);
+            (format!("{receiver}.{property_name}"), import)
+        }
+        (None, Some(fq_name)) => {
+            if let Some((_, simple_name)) = fq_name.rsplit_once(".") {
+                (
+                    format!("{simple_name}.{property_name}"),
+                    Some(fq_name.to_owned()),
+                )
+            } else {
+                (format!("{fq_name}.{property_name}"), None)
+            }
+        }
+        (None, None) => (property_name.to_owned(), None),
+    };
+    // endregion: determine extension target
+
+    let public_property = PropertySpec::builder(&public_property_name, "ImageVector")
+        .require_import("androidx.compose.ui.graphics.vector.ImageVector")
+        .touch(|it| match additional_import {
+            Some(import) => it.require_import(import),
+            None => it,
+        })
+        .touch(|it| match kdoc.get(image_name) {
+            Some(kdoc) => it.kdoc(kdoc),
+            None => it,
+        })
+        .touch(|it| match *kotlin_explicit_api {
+            true => it.add_modifier("public"),
+            false => it,
+        })
+        .getter(
             CodeBlock::builder()
-                .add_statement("@Preview(showBackground = true)")
-                .add_statement("@Composable")
-                .begin_control_flow(format!("private fun {image_name}Preview() {{"))
-                .add_statement("Icon(")
-                .indent()
-                .add_statement(format!("imageVector = {public_property_name},"))
-                .add_statement("contentDescription = null,")
-                .unindent()
-                .add_statement(")")
+                .touch(|it| {
+                    if *composable_get {
+                        it.begin_control_flow("@Composable get()")
+                            .require_import("androidx.compose.runtime.Composable")
+                    } else {
+                        it.begin_control_flow("get()")
+                    }
+                })
+                .begin_control_flow(format!("if ({backing_field_name} != null)"))
+                .add_statement(format!("return {backing_field_name}!!"))
                 .end_control_flow()
-                .require_imports(&[
-                    "androidx.compose.material3.Icon",
-                    "androidx.compose.runtime.Composable",
-                    "androidx.compose.ui.tooling.preview.Preview",
-                ])
-                .build()
-        };
+                .add_code_block(
+                    CodeBlock::builder()
+                        .add_statement(format!("{backing_field_name} = "))
+                        .no_new_line()
+                        .add_code_block(iv_code_block)
+                        .build(),
+                )
+                .add_statement(format!("return {backing_field_name}!!"))
+                .end_control_flow()
+                .build(),
+        )
+        .build();
+
+    let backing_field = PropertySpec::builder(&backing_field_name, "ImageVector?")
+        .require_import("androidx.compose.ui.graphics.vector.ImageVector")
+        .initializer(CodeBlock::builder().add_statement("null").build())
+        .add_modifier("private")
+        .mutable()
+        .build();
 
-        Self::builder(package)
-            .add_suppressions(file_suppress_lint)
-            .add_member(public_property.into())
-            .add_member(backing_field.into())
-            .add_member(preview_fun)
+    let preview_fun = if let Some(preview) = preview {
+        let code = preview.code.replace("{name}", image_name);
+        CodeBlock::builder()
+            .require_imports(&preview.imports)
+            .add_statement(code)
+            .build()
+    } else {
+        // the Multiplatform preview annotation has no parameters
+        let (preview_annotation, preview_import) = if *multiplatform {
+            (
+                "@Preview",
+                "org.jetbrains.compose.ui.tooling.preview.Preview",
+            )
+        } else {
+            (
+                "@Preview(showBackground = true)",
+                "androidx.compose.ui.tooling.preview.Preview",
+            )
+        };
+        CodeBlock::builder()
+            .add_statement(preview_annotation)
+            .add_statement("@Composable")
+            .begin_control_flow(format!("private fun {preview_fun_name}() {{"))
+            .add_statement("Icon(")
+            .indent()
+            .add_statement(format!("imageVector = {public_property_name},"))
+            .add_statement("contentDescription = null,")
+            .unindent()
+            .add_statement(")")
+            .end_control_flow()
+            .require_imports(&[
+                "androidx.compose.material3.Icon",
+                "androidx.compose.runtime.Composable",
+                preview_import,
+            ])
             .build()
+    };
+
+    [public_property.into(), backing_field.into(), preview_fun]
+}
+
+/// Name of the public property of the image, not escaped yet
+pub(crate) fn property_name(options: &SvgToComposeOptions, image_name: &str) -> String {
+    let property_name = match &options.property_name_template {
+        Some(template) => template.replace("{name}", image_name),
+        None => image_name.to_owned(),
+    };
+    prefix_kotlin_identifier(&property_name, options.identifier_prefix.as_deref())
+}
+
+/// Splits the fully qualified name of the receiver into the import of the outermost
+/// class and the receiver expression, e.g. `com.example.Icons.Filled` becomes
+/// `com.example.Icons` and `Icons.Filled`. Packages are expected to be lowercase.
+fn split_receiver_object(fq_name: &str) -> (Option<String>, String) {
+    let segments = fq_name.split('.').collect::<Vec<_>>();
+    let first_class = segments
+        .iter()
+        .position(|it| it.starts_with(|c: char| c.is_uppercase()))
+        .unwrap_or(segments.len() - 1);
+    let receiver = segments[first_class..].join(".");
+    if first_class == 0 {
+        (None, receiver)
+    } else {
+        (Some(segments[..=first_class].join(".")), receiver)
     }
 }
 
@@ -123,3 +193,85 @@ fn uncapitalize(s: &str) -> String {
         Some(f) => f.to_lowercase().chain(c).collect(),
     }
 }
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn split_receiver_object__nested_object__EXPECT__import_of_outermost_class() {
+        // Given
+        let fq_name = "androidx.compose.material.icons.Icons.Filled";
+
+        // When
+        let (import, receiver) = split_receiver_object(fq_name);
+
+        // Then
+        assert_eq!(
+            Some("androidx.compose.material.icons.Icons".to_string()),
+            import
+        );
+        assert_eq!("Icons.Filled", receiver);
+    }
+
+    #[test]
+    fn split_receiver_object__lowercase_or_simple_name__EXPECT__last_segment_or_no_import() {
+        // When
+        let lowercase = split_receiver_object("com.example.icons");
+        let simple = split_receiver_object("AppIcons");
+
+        // Then
+        assert_eq!(
+            (Some("com.example.icons".to_string()), "icons".to_string()),
+            lowercase
+        );
+        assert_eq!((None, "AppIcons".to_string()), simple);
+    }
+
+    #[test]
+    fn BackingFieldComposableSpec__multiplatform_combined_images__EXPECT__multiplatform_preview_of_every_image()
+     {
+        // Given
+        let options = SvgToComposeOptions {
+            image_name: String::new(),
+            package: "com.example".to_owned(),
+            kotlin_explicit_api: false,
+            extension_target: None,
+            file_suppress_lint: Vec::new(),
+            color_mappings: Vec::new(),
+            preview: None,
+            composable_get: false,
+            property_name_template: None,
+            receiver_object: None,
+            multiplatform: true,
+            identifier_prefix: None,
+            kdoc: Default::default(),
+        };
+        let image = || {
+            CodeBlock::builder()
+                .add_statement("ImageVector.Builder()")
+                .build()
+        };
+        let images = vec![
+            ("Star".to_owned(), image()),
+            ("StarBig".to_owned(), image()),
+        ];
+
+        // When
+        let file: FileSpec = BackingFieldComposableSpec { options, images }.into();
+        let code = file.to_string();
+
+        // Then
+        assert_eq!(
+            1,
+            code.matches("import org.jetbrains.compose.ui.tooling.preview.Preview\n")
+                .count()
+        );
+        assert!(!code.contains("androidx.compose.ui.tooling.preview.Preview"));
+        assert_eq!(2, code.matches("@Preview\n").count());
+        assert!(!code.contains("showBackground"));
+        assert!(code.contains("private fun StarPreview()"));
+        assert!(code.contains("private fun StarBigPreview()"));
+    }
+}
@@ -15,11 +15,16 @@ pub struct SvgToDrawableOptions {
     /// Add `<?xml version="1.0" encoding="UTF-8"?>` declaration to the XML output
     pub xml_declaration: bool,
     pub auto_mirrored: bool,
+    /// Paint the whole image with a single color, for the monochrome layer of themed icons
+    pub monochrome: bool,
 }
 
 pub fn transform_svg_to_drawable(svg: &[u8], options: SvgToDrawableOptions) -> Result<Vec<u8>> {
     let tree = usvg::Tree::from_data(svg, &Default::default())?;
-    let image_vector: ImageVector = tree.try_into()?;
+    let mut image_vector: ImageVector = tree.try_into()?;
+    if options.monochrome {
+        image_vector = image_vector.into_monochrome(&colorsys::Rgb::new(0.0, 0.0, 0.0, None));
+    }
     let output = codegen_xml(image_vector, options)?;
     Ok(output.into_bytes())
 }
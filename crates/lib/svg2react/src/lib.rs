@@ -0,0 +1,509 @@
+use lib_image_vector::{
+    Cap, Color, Command, FillType, GroupNode, ImageVector, Join, LinearGradient, Node, PathNode,
+    Point, RadialGradient, Stroke,
+};
+use std::fmt::Write;
+
+pub type Result<T> = std::result::Result<T, Error>;
+pub type Error = Box<dyn std::error::Error + Send + Sync>;
+
+const INDENT: &str = "  ";
+
+pub struct SvgToReactOptions {
+    /// Name of the generated component, must be a valid PascalCase identifier
+    pub component_name: String,
+    /// Replace all solid colors of the image with `currentColor`,
+    /// so the color can be changed with the `color` prop
+    pub current_color: bool,
+}
+
+pub fn transform_svg_to_react(svg: &[u8], options: SvgToReactOptions) -> Result<Vec<u8>> {
+    let tree = usvg::Tree::from_data(svg, &Default::default())?;
+    let image_vector: ImageVector = tree.try_into()?;
+    let output = codegen_tsx(image_vector, &options)?;
+    Ok(output.into_bytes())
+}
+
+fn codegen_tsx(iv: ImageVector, options: &SvgToReactOptions) -> Result<String> {
+    let ImageVector {
+        name: _,
+        width,
+        height,
+        viewport_width,
+        viewport_height,
+        nodes,
+    } = iv;
+    let name = &options.component_name;
+    let mut ctx = CodegenContext {
+        options,
+        defs: String::new(),
+        defs_count: 0,
+    };
+    let mut body = String::new();
+    for node in &nodes {
+        codegen_node(&mut ctx, &mut body, node, 3)?;
+    }
+
+    let mut w = String::with_capacity(body.len() + ctx.defs.len() + 1024);
+    writeln!(w, "// Generated by figx. Do not edit manually.")?;
+    writeln!(w, "import type {{ SVGProps }} from \"react\";")?;
+    writeln!(w)?;
+    writeln!(
+        w,
+        "export interface {name}Props extends SVGProps<SVGSVGElement> {{"
+    )?;
+    writeln!(w, "{INDENT}size?: number | string;")?;
+    if options.current_color {
+        writeln!(w, "{INDENT}color?: string;")?;
+    }
+    writeln!(w, "}}")?;
+    writeln!(w)?;
+    let color_prop = if options.current_color {
+        " color = \"currentColor\","
+    } else {
+        ""
+    };
+    writeln!(
+        w,
+        "export function {name}({{ size = {},{color_prop} ...props }}: {name}Props) {{",
+        fmt3(height)
+    )?;
+    writeln!(w, "{INDENT}return (")?;
+    writeln!(w, "{INDENT}{INDENT}<svg")?;
+    writeln!(
+        w,
+        "{INDENT}{INDENT}{INDENT}xmlns=\"http://www.w3.org/2000/svg\""
+    )?;
+    // the width of the non-square image is computed from the viewBox
+    if width == height {
+        writeln!(w, "{INDENT}{INDENT}{INDENT}width={{size}}")?;
+    }
+    writeln!(w, "{INDENT}{INDENT}{INDENT}height={{size}}")?;
+    writeln!(
+        w,
+        "{INDENT}{INDENT}{INDENT}viewBox=\"0 0 {} {}\"",
+        fmt3(viewport_width),
+        fmt3(viewport_height)
+    )?;
+    writeln!(w, "{INDENT}{INDENT}{INDENT}fill=\"none\"")?;
+    if options.current_color {
+        writeln!(w, "{INDENT}{INDENT}{INDENT}color={{color}}")?;
+    }
+    writeln!(w, "{INDENT}{INDENT}{INDENT}{{...props}}")?;
+    writeln!(w, "{INDENT}{INDENT}>")?;
+    if !ctx.defs.is_empty() {
+        writeln!(w, "{INDENT}{INDENT}{INDENT}<defs>")?;
+        w.push_str(&ctx.defs);
+        writeln!(w, "{INDENT}{INDENT}{INDENT}</defs>")?;
+    }
+    w.push_str(&body);
+    writeln!(w, "{INDENT}{INDENT}</svg>")?;
+    writeln!(w, "{INDENT});")?;
+    writeln!(w, "}}")?;
+    writeln!(w)?;
+    writeln!(w, "export default {name};")?;
+    Ok(w)
+}
+
+struct CodegenContext<'a> {
+    options: &'a SvgToReactOptions,
+    /// Gradients and clip paths referenced by the elements via `url(#id)`
+    defs: String,
+    defs_count: usize,
+}
+
+impl CodegenContext<'_> {
+    fn next_def_id(&mut self, kind: &str) -> String {
+        self.defs_count += 1;
+        format!("{}_{kind}_{}", self.options.component_name, self.defs_count)
+    }
+}
+
+fn codegen_node(ctx: &mut CodegenContext, w: &mut String, node: &Node, depth: usize) -> Result<()> {
+    match node {
+        Node::Path(path) => codegen_path_node(ctx, w, path, depth),
+        Node::Group(group) => codegen_group_node(ctx, w, group, depth),
+    }
+}
+
+fn codegen_group_node(
+    ctx: &mut CodegenContext,
+    w: &mut String,
+    group: &GroupNode,
+    depth: usize,
+) -> Result<()> {
+    let GroupNode {
+        name: _,
+        nodes,
+        rotate,
+        pivot,
+        translation,
+        scale,
+        clip_path_data,
+    } = group;
+    let indent = INDENT.repeat(depth);
+    write!(w, "{indent}<g")?;
+
+    // same order of the transformations as in the Android VectorDrawable
+    let mut transforms = Vec::new();
+    if translation.x != 0.0 || translation.y != 0.0 || pivot.x != 0.0 || pivot.y != 0.0 {
+        transforms.push(format!(
+            "translate({} {})",
+            fmt3(translation.x + pivot.x),
+            fmt3(translation.y + pivot.y)
+        ));
+    }
+    if *rotate != 0.0 {
+        transforms.push(format!("rotate({})", fmt3(rotate.to_degrees())));
+    }
+    if scale.x != 1.0 || scale.y != 1.0 {
+        transforms.push(format!("scale({} {})", fmt3(scale.x), fmt3(scale.y)));
+    }
+    if pivot.x != 0.0 || pivot.y != 0.0 {
+        transforms.push(format!("translate({} {})", fmt3(-pivot.x), fmt3(-pivot.y)));
+    }
+    if !transforms.is_empty() {
+        write!(w, " transform=\"{}\"", transforms.join(" "))?;
+    }
+
+    if let Some(clip_path_data) = clip_path_data {
+        let id = ctx.next_def_id("clip");
+        let def_indent = INDENT.repeat(4);
+        writeln!(ctx.defs, "{def_indent}<clipPath id=\"{id}\">")?;
+        writeln!(
+            ctx.defs,
+            "{def_indent}{INDENT}<path d=\"{}\" />",
+            path_data(clip_path_data)
+        )?;
+        writeln!(ctx.defs, "{def_indent}</clipPath>")?;
+        write!(w, " clipPath=\"url(#{id})\"")?;
+    }
+    writeln!(w, ">")?;
+
+    for node in nodes {
+        codegen_node(ctx, w, node, depth + 1)?;
+    }
+    writeln!(w, "{indent}</g>")?;
+    Ok(())
+}
+
+fn codegen_path_node(
+    ctx: &mut CodegenContext,
+    w: &mut String,
+    path: &PathNode,
+    depth: usize,
+) -> Result<()> {
+    let PathNode {
+        fill_type,
+        fill_color,
+        commands,
+        alpha,
+        stroke,
+    } = path;
+    let indent = INDENT.repeat(depth);
+    write!(w, "{indent}<path d=\"{}\"", path_data(commands))?;
+
+    match fill_color {
+        Some(color) => write!(w, " fill=\"{}\"", paint(ctx, color)?)?,
+        None => write!(w, " fill=\"none\"")?,
+    }
+    if let FillType::EvenOdd = fill_type {
+        write!(w, " fillRule=\"evenodd\" clipRule=\"evenodd\"")?;
+    }
+    if *alpha != 1.0 {
+        write!(w, " fillOpacity={{{}}}", fmt3(*alpha))?;
+    }
+    codegen_stroke(ctx, w, stroke)?;
+    writeln!(w, " />")?;
+    Ok(())
+}
+
+fn codegen_stroke(ctx: &mut CodegenContext, w: &mut String, stroke: &Stroke) -> Result<()> {
+    let Some(color) = &stroke.color else {
+        return Ok(());
+    };
+    write!(w, " stroke=\"{}\"", paint(ctx, color)?)?;
+    write!(w, " strokeWidth={{{}}}", fmt3(stroke.width))?;
+    match stroke.cap {
+        Cap::Butt => (), // default
+        Cap::Round => write!(w, " strokeLinecap=\"round\"")?,
+        Cap::Square => write!(w, " strokeLinecap=\"square\"")?,
+    }
+    match stroke.join {
+        Join::Miter => (), // default
+        Join::Bevel => write!(w, " strokeLinejoin=\"bevel\"")?,
+        Join::Round => write!(w, " strokeLinejoin=\"round\"")?,
+    }
+    if stroke.miter != 4.0 {
+        write!(w, " strokeMiterlimit={{{}}}", fmt3(stroke.miter))?;
+    }
+    if stroke.alpha != 1.0 {
+        write!(w, " strokeOpacity={{{}}}", fmt3(stroke.alpha))?;
+    }
+    Ok(())
+}
+
+/// Value of the `fill` or `stroke` attribute, gradients are declared in `<defs>`
+fn paint(ctx: &mut CodegenContext, color: &Color) -> Result<String> {
+    match color {
+        Color::SolidColor(_) if ctx.options.current_color => Ok("currentColor".to_string()),
+        Color::SolidColor(rgb) => Ok(hex_rgba(rgb)),
+        Color::LinearGradient(g) => {
+            let id = ctx.next_def_id("linear");
+            codegen_linear_gradient(&mut ctx.defs, &id, g)?;
+            Ok(format!("url(#{id})"))
+        }
+        Color::RadialGradient(g) => {
+            let id = ctx.next_def_id("radial");
+            codegen_radial_gradient(&mut ctx.defs, &id, g)?;
+            Ok(format!("url(#{id})"))
+        }
+    }
+}
+
+fn codegen_linear_gradient(w: &mut String, id: &str, g: &LinearGradient) -> Result<()> {
+    let indent = INDENT.repeat(4);
+    writeln!(
+        w,
+        "{indent}<linearGradient id=\"{id}\" x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" gradientUnits=\"userSpaceOnUse\">",
+        fmt3(g.start_x),
+        fmt3(g.start_y),
+        fmt3(g.end_x),
+        fmt3(g.end_y),
+    )?;
+    for stop in &g.stops {
+        codegen_stop(w, stop.offset, &stop.color)?;
+    }
+    writeln!(w, "{indent}</linearGradient>")?;
+    Ok(())
+}
+
+fn codegen_radial_gradient(w: &mut String, id: &str, g: &RadialGradient) -> Result<()> {
+    let indent = INDENT.repeat(4);
+    writeln!(
+        w,
+        "{indent}<radialGradient id=\"{id}\" cx=\"{}\" cy=\"{}\" r=\"{}\" gradientUnits=\"userSpaceOnUse\">",
+        fmt3(g.center_x),
+        fmt3(g.center_y),
+        fmt3(g.gradient_radius),
+    )?;
+    for stop in &g.stops {
+        codegen_stop(w, stop.offset, &stop.color)?;
+    }
+    writeln!(w, "{indent}</radialGradient>")?;
+    Ok(())
+}
+
+fn codegen_stop(w: &mut String, offset: f32, color: &colorsys::Rgb) -> Result<()> {
+    let indent = INDENT.repeat(5);
+    writeln!(
+        w,
+        "{indent}<stop offset=\"{}\" stopColor=\"{}\" />",
+        fmt3(offset),
+        hex_rgba(color)
+    )?;
+    Ok(())
+}
+
+fn path_data(commands: &[Command]) -> String {
+    let mut path_data = String::new();
+    for command in commands {
+        let _ = match command {
+            Command::MoveTo(Point { x, y }) => write!(path_data, "M{},{}", fmt3(*x), fmt3(*y)),
+            Command::LineTo(Point { x, y }) => write!(path_data, "L{},{}", fmt3(*x), fmt3(*y)),
+            Command::CurveTo(p1, p2, p3) => write!(
+                path_data,
+                "C{},{} {},{} {},{}",
+                fmt3(p1.x),
+                fmt3(p1.y),
+                fmt3(p2.x),
+                fmt3(p2.y),
+                fmt3(p3.x),
+                fmt3(p3.y)
+            ),
+            Command::QuadraticBezierTo(p1, p2) => write!(
+                path_data,
+                "Q{},{} {},{}",
+                fmt3(p1.x),
+                fmt3(p1.y),
+                fmt3(p2.x),
+                fmt3(p2.y)
+            ),
+            Command::Close => write!(path_data, "Z"),
+        };
+    }
+    path_data
+}
+
+/// Format a float without trailing zeros (3 decimal places)
+fn fmt3(x: f32) -> f32 {
+    (x * 1000.0).round() / 1000.0
+}
+
+fn hex_rgba(color: &colorsys::Rgb) -> String {
+    use colorsys::ColorAlpha;
+    let a = (color.alpha() * 255.0).round() as u8;
+    let r = (color.red().round()) as u8;
+    let g = (color.green().round()) as u8;
+    let b = (color.blue().round()) as u8;
+    if a == 255 {
+        format!("#{r:02X}{g:02X}{b:02X}")
+    } else {
+        format!("#{r:02X}{g:02X}{b:02X}{a:02X}")
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn transform_svg_to_react__current_color__EXPECT__color_prop_and_rewritten_fill() {
+        // Given
+        let svg = r##"
+        <svg xmlns="http://www.w3.org/2000/svg" width="24" height="24" viewBox="0 0 24 24">
+            <path d="M0 0 L10 0 L10 10 Z" fill="#FF0000"/>
+        </svg>
+        "##;
+        let options = SvgToReactOptions {
+            component_name: "IcClose".to_string(),
+            current_color: true,
+        };
+
+        // When
+        let tsx = transform_svg_to_react(svg.as_bytes(), options).unwrap();
+        let tsx = String::from_utf8(tsx).unwrap();
+
+        // Then
+        assert!(tsx.contains("export interface IcCloseProps extends SVGProps<SVGSVGElement> {"));
+        assert!(tsx.contains("export function IcClose({ size = 24, color = \"currentColor\", ...props }: IcCloseProps) {"));
+        assert!(tsx.contains("fill=\"currentColor\""));
+        assert!(!tsx.contains("#FF0000"));
+    }
+
+    fn transform(svg: &str, current_color: bool) -> String {
+        let options = SvgToReactOptions {
+            component_name: "IcTest".to_string(),
+            current_color,
+        };
+        let tsx = transform_svg_to_react(svg.as_bytes(), options).unwrap();
+        String::from_utf8(tsx).unwrap()
+    }
+
+    #[test]
+    fn transform_svg_to_react__no_current_color__EXPECT__original_colors_and_no_color_prop() {
+        // Given
+        let svg = r##"
+        <svg xmlns="http://www.w3.org/2000/svg" width="24" height="24" viewBox="0 0 24 24">
+            <path d="M0 0 L10 0 L10 10 Z" fill="#FF0000"/>
+        </svg>
+        "##;
+
+        // When
+        let tsx = transform(svg, false);
+
+        // Then
+        assert!(tsx.contains("export function IcTest({ size = 24, ...props }: IcTestProps) {"));
+        assert!(tsx.contains("fill=\"#FF0000\""));
+        assert!(!tsx.contains("color?: string;"));
+        assert!(!tsx.contains("currentColor"));
+    }
+
+    #[test]
+    fn transform_svg_to_react__gradients__EXPECT__declared_in_defs_and_referenced_by_id() {
+        // Given
+        let svg = r##"
+        <svg xmlns="http://www.w3.org/2000/svg" width="24" height="24" viewBox="0 0 24 24">
+            <defs>
+                <linearGradient id="a" x1="0" y1="0" x2="24" y2="0" gradientUnits="userSpaceOnUse">
+                    <stop offset="0" stop-color="#FF0000"/>
+                    <stop offset="1" stop-color="#0000FF"/>
+                </linearGradient>
+                <radialGradient id="b" cx="12" cy="12" r="12" gradientUnits="userSpaceOnUse">
+                    <stop offset="0" stop-color="#00FF00"/>
+                    <stop offset="1" stop-color="#000000"/>
+                </radialGradient>
+            </defs>
+            <path d="M0 0 L24 0 L24 12 Z" fill="url(#a)"/>
+            <path d="M0 12 L24 12 L24 24 Z" fill="url(#b)"/>
+        </svg>
+        "##;
+
+        // When
+        let tsx = transform(svg, true);
+
+        // Then
+        let defs_start = tsx.find("<defs>").unwrap();
+        let defs_end = tsx.find("</defs>").unwrap();
+        let defs = &tsx[defs_start..defs_end];
+        assert!(defs.contains("<linearGradient id=\"IcTest_linear_1\""));
+        assert!(defs.contains("<radialGradient id=\"IcTest_radial_2\""));
+        assert!(defs.contains("stopColor=\"#FF0000\""));
+        assert!(defs.contains("stopColor=\"#0000FF\""));
+        assert!(defs.contains("stopColor=\"#00FF00\""));
+        assert!(tsx.contains("fill=\"url(#IcTest_linear_1)\""));
+        assert!(tsx.contains("fill=\"url(#IcTest_radial_2)\""));
+        // gradients are never replaced with the current color
+        assert!(!tsx.contains("fill=\"currentColor\""));
+    }
+
+    #[test]
+    fn transform_svg_to_react__strokes__EXPECT__stroke_attributes() {
+        // Given
+        let svg = r##"
+        <svg xmlns="http://www.w3.org/2000/svg" width="24" height="24" viewBox="0 0 24 24">
+            <path d="M2 2 L22 22" fill="none" stroke="#112233" stroke-width="2"
+                stroke-linecap="round" stroke-linejoin="bevel" stroke-opacity="0.5"/>
+        </svg>
+        "##;
+
+        // When
+        let tsx = transform(svg, false);
+
+        // Then
+        assert!(tsx.contains(
+            "fill=\"none\" stroke=\"#112233\" strokeWidth={2} strokeLinecap=\"round\" strokeLinejoin=\"bevel\" strokeOpacity={0.5}"
+        ));
+    }
+
+    #[test]
+    fn transform_svg_to_react__evenodd_fill_rule__EXPECT__fill_and_clip_rules() {
+        // Given
+        let svg = r##"
+        <svg xmlns="http://www.w3.org/2000/svg" width="24" height="24" viewBox="0 0 24 24">
+            <path d="M0 0 L24 0 L24 24 L0 24 Z M6 6 L18 6 L18 18 L6 18 Z" fill="#000000" fill-rule="evenodd"/>
+            <path d="M0 0 L10 0 L10 10 Z" fill="#000000"/>
+        </svg>
+        "##;
+
+        // When
+        let tsx = transform(svg, false);
+
+        // Then
+        assert_eq!(
+            1,
+            tsx.matches("fillRule=\"evenodd\" clipRule=\"evenodd\"")
+                .count()
+        );
+    }
+
+    #[test]
+    fn transform_svg_to_react__non_square_image__EXPECT__only_height_bound_to_size() {
+        // Given
+        let svg = r##"
+        <svg xmlns="http://www.w3.org/2000/svg" width="32" height="16" viewBox="0 0 32 16">
+            <path d="M0 0 L32 0 L32 16 Z" fill="#000000"/>
+        </svg>
+        "##;
+
+        // When
+        let tsx = transform(svg, false);
+
+        // Then
+        assert!(tsx.contains("export function IcTest({ size = 16, ...props }: IcTestProps) {"));
+        assert!(tsx.contains("height={size}"));
+        assert!(!tsx.contains("width={size}"));
+        assert!(tsx.contains("viewBox=\"0 0 32 16\""));
+    }
+}
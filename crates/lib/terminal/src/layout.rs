@@ -0,0 +1,266 @@
+//! Width math of the lines drawn to the terminal.
+//!
+//! Lines are measured in terminal columns, skipping ANSI escape sequences, and are cut
+//! between grapheme clusters only, so a combining accent or a part of an emoji sequence
+//! is never left dangling at the edge of the screen. Wide chars, e.g. CJK ideographs
+//! and emoji, take two columns.
+
+use std::borrow::Cow;
+use unicode_width::UnicodeWidthChar;
+
+const ESC: char = '\x1b';
+const RESET_STYLE: &str = "\x1b[0m";
+const ELLIPSIS: &str = "...";
+
+/// Number of columns the line takes on the screen, escape sequences take none
+pub fn visible_width(line: &str) -> usize {
+    segments(line)
+        .filter_map(|it| match it {
+            Segment::Text(cluster) => Some(cluster_width(cluster)),
+            Segment::Escape(_) => None,
+        })
+        .sum()
+}
+
+/// Cuts the plain text to `max_width` columns, marking the cut with `...`
+pub fn truncate_to_width(text: &str, max_width: usize) -> Cow<'_, str> {
+    if visible_width(text) <= max_width {
+        return Cow::Borrowed(text);
+    }
+    let budget = max_width.saturating_sub(ELLIPSIS.len());
+    let mut result = String::with_capacity(max_width);
+    let mut width = 0;
+    for cluster in graphemes(text) {
+        width += cluster_width(cluster);
+        if width > budget {
+            break;
+        }
+        result.push_str(cluster);
+    }
+    result.push_str(&ELLIPSIS[..ELLIPSIS.len().min(max_width)]);
+    Cow::Owned(result)
+}
+
+/// Cuts the styled line to `max_width` columns, so that it never wraps.
+/// Escape sequences are kept and the style is reset after the cut.
+pub(crate) fn clip_styled(line: &str, max_width: usize) -> Cow<'_, str> {
+    if visible_width(line) <= max_width {
+        return Cow::Borrowed(line);
+    }
+    let mut result = String::with_capacity(line.len());
+    let mut width = 0;
+    for segment in segments(line) {
+        match segment {
+            Segment::Escape(seq) => result.push_str(seq),
+            Segment::Text(cluster) => {
+                width += cluster_width(cluster);
+                if width > max_width {
+                    break;
+                }
+                result.push_str(cluster);
+            }
+        }
+    }
+    result.push_str(RESET_STYLE);
+    Cow::Owned(result)
+}
+
+/// Number of screen rows the lines of the given widths take after being wrapped
+/// by a terminal `columns` wide
+pub(crate) fn wrapped_rows(widths: &[usize], columns: Option<usize>) -> usize {
+    match columns {
+        Some(columns) if columns > 0 => widths
+            .iter()
+            .map(|width| width.div_ceil(columns).max(1))
+            .sum(),
+        _ => widths.len(),
+    }
+}
+
+// region: segmentation
+
+enum Segment<'a> {
+    Text(&'a str),
+    Escape(&'a str),
+}
+
+/// Splits the line into grapheme clusters and escape sequences
+fn segments(line: &str) -> impl Iterator<Item = Segment<'_>> {
+    let mut rest = line;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let len = match rest.starts_with(ESC) {
+            true => escape_len(rest),
+            false => cluster_len(rest),
+        };
+        let (segment, tail) = rest.split_at(len);
+        rest = tail;
+        Some(match segment.starts_with(ESC) {
+            true => Segment::Escape(segment),
+            false => Segment::Text(segment),
+        })
+    })
+}
+
+fn graphemes(text: &str) -> impl Iterator<Item = &str> {
+    segments(text).map(|it| match it {
+        Segment::Text(it) | Segment::Escape(it) => it,
+    })
+}
+
+/// Length of the CSI sequence, e.g. `ESC [ 38;5;196 m`, or of a lone `ESC` with the next char
+fn escape_len(text: &str) -> usize {
+    let mut chars = text.char_indices().skip(1);
+    match chars.next() {
+        Some((_, '[')) => chars
+            .find(|(_, c)| ('\x40'..='\x7e').contains(c))
+            .map(|(i, c)| i + c.len_utf8())
+            .unwrap_or(text.len()),
+        Some((i, c)) => i + c.len_utf8(),
+        None => text.len(),
+    }
+}
+
+/// Length of the first grapheme cluster of the text.
+///
+/// Covers the clusters met in resource names: base chars with combining marks, emoji
+/// with variation selectors and skin tones, ZWJ sequences and flags.
+fn cluster_len(text: &str) -> usize {
+    let mut chars = text.char_indices().peekable();
+    let Some((_, first)) = chars.next() else {
+        return 0;
+    };
+    let mut regional_indicators = usize::from(is_regional_indicator(first));
+    let mut joined = false;
+    while let Some(&(i, c)) = chars.peek() {
+        let extends = if joined {
+            true
+        } else if c == '\u{200D}' {
+            joined = true;
+            chars.next();
+            continue;
+        } else if is_regional_indicator(c) {
+            regional_indicators += 1;
+            regional_indicators == 2
+        } else {
+            is_extending(c)
+        };
+        if !extends || c == ESC {
+            return i;
+        }
+        joined = false;
+        chars.next();
+    }
+    text.len()
+}
+
+fn is_extending(c: char) -> bool {
+    matches!(c,
+        '\u{0300}'..='\u{036F}'
+        | '\u{1AB0}'..='\u{1AFF}'
+        | '\u{1DC0}'..='\u{1DFF}'
+        | '\u{20D0}'..='\u{20FF}'
+        | '\u{FE00}'..='\u{FE0F}'
+        | '\u{FE20}'..='\u{FE2F}'
+        | '\u{1F3FB}'..='\u{1F3FF}'
+        | '\u{E0020}'..='\u{E007F}'
+    )
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+/// Width of the cluster is the width of its base char, the marks attached to it
+/// take no room of their own
+fn cluster_width(cluster: &str) -> usize {
+    let mut chars = cluster.chars();
+    let Some(first) = chars.next() else {
+        return 0;
+    };
+    // emoji presentation and flags are wide even if their base chars are not
+    let is_flag = is_regional_indicator(first) && chars.next().is_some();
+    if is_flag || cluster.contains('\u{FE0F}') {
+        return 2;
+    }
+    first.width().unwrap_or(0)
+}
+
+// endregion: segmentation
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn visible_width__styled_line__EXPECT__escapes_not_counted() {
+        // Then
+        assert_eq!(5, visible_width("\x1b[1m\x1b[32mhello\x1b[0m"));
+        assert_eq!(3, visible_width("\x1b[38;2;255;0;0m━━━\x1b[0m"));
+    }
+
+    #[test]
+    fn visible_width__combining_marks_and_emoji_sequences__EXPECT__columns_of_base_chars() {
+        // Then
+        assert_eq!(4, visible_width("cafe\u{0301}"));
+        assert_eq!(2, visible_width("\u{1F469}\u{200D}\u{1F4BB}"));
+        assert_eq!(2, visible_width("\u{1F44D}\u{1F3FD}"));
+        assert_eq!(4, visible_width("\u{1F1E9}\u{1F1EA}\u{1F1EB}\u{1F1F7}"));
+        assert_eq!(2, visible_width("\u{2764}\u{FE0F}"));
+    }
+
+    #[test]
+    fn visible_width__cjk_text__EXPECT__two_columns_per_char() {
+        // Then
+        assert_eq!(7, visible_width("图标_星"));
+        assert_eq!(4, visible_width("\x1b[1mアイ\x1b[0m"));
+    }
+
+    #[test]
+    fn truncate_to_width__wide_char_at_cut__EXPECT__char_not_split() {
+        // When
+        let truncated = truncate_to_width("图标图标图标", 8);
+
+        // Then
+        assert_eq!("图标...", truncated);
+        assert!(visible_width(&truncated) <= 8);
+    }
+
+    #[test]
+    fn truncate_to_width__long_text__EXPECT__cut_with_ellipsis() {
+        // Then
+        assert_eq!("ic_st...", truncate_to_width("ic_star, ic_moon", 8));
+        assert_eq!("ic_star", truncate_to_width("ic_star", 7));
+        assert_eq!("..", truncate_to_width("ic_star", 2));
+    }
+
+    #[test]
+    fn truncate_to_width__cut_inside_cluster__EXPECT__cluster_kept_whole() {
+        // When
+        let truncated = truncate_to_width("e\u{0301}e\u{0301}e\u{0301}e\u{0301}e\u{0301}", 4);
+
+        // Then
+        assert_eq!("e\u{0301}...", truncated);
+    }
+
+    #[test]
+    fn clip_styled__wider_than_terminal__EXPECT__escapes_kept_and_style_reset() {
+        // When
+        let clipped = clip_styled("\x1b[32mImporting\x1b[0m ━━━━ 1/2", 11);
+
+        // Then
+        assert_eq!("\x1b[32mImporting\x1b[0m ━\x1b[0m", clipped);
+        assert_eq!(11, visible_width(&clipped));
+    }
+
+    #[test]
+    fn wrapped_rows__terminal_shrunk__EXPECT__wrapped_lines_counted() {
+        // Then
+        assert_eq!(3, wrapped_rows(&[79, 0, 20], Some(80)));
+        assert_eq!(4, wrapped_rows(&[79, 0, 20], Some(40)));
+        assert_eq!(3, wrapped_rows(&[79, 0, 20], None));
+    }
+}
@@ -0,0 +1,214 @@
+//! # Terminal
+//!
+//! The single owner of the process's stderr. Every renderer (logger, progress bar,
+//! dashboard) writes through this crate instead of touching stderr directly.
+//!
+//! The terminal is split into two regions:
+//! - **log area**: append-only lines scrolling up, see [`write_log`];
+//! - **progress area**: a few lines pinned to the bottom, redrawn in place, see [`draw_progress`].
+//!
+//! Each operation is performed as a single buffered write under one lock, so the progress
+//! area is erased and redrawn around every log line without flicker or interleaving.
+//!
+//! The width of the terminal is queried on every redraw, so a resize is picked up with
+//! the next frame: progress lines are clipped to the new width and never wrap, while the
+//! lines drawn before the resize are erased along with the rows the terminal wrapped
+//! them into.
+
+use crossterm::{
+    cursor::{MoveToColumn, MoveUp},
+    queue,
+    style::Print,
+    terminal::{Clear, ClearType},
+};
+use std::{
+    io::{Write, stderr},
+    sync::{LazyLock, Mutex},
+};
+use terminal_size::{Height, Width};
+
+mod layout;
+pub use layout::{truncate_to_width, visible_width};
+
+static TERMINAL: LazyLock<Mutex<Terminal>> = LazyLock::new(Default::default);
+
+#[derive(Default)]
+struct Terminal {
+    /// Content of the progress area, one item per line
+    progress: Vec<String>,
+    /// Visible widths of the lines of the progress area currently on the screen
+    drawn_widths: Vec<usize>,
+}
+
+impl Terminal {
+    /// `columns` is the current width of the terminal, which may differ from the one
+    /// the progress area was drawn with
+    fn erase_progress(
+        &mut self,
+        out: &mut impl Write,
+        columns: Option<usize>,
+    ) -> std::io::Result<()> {
+        let rows = layout::wrapped_rows(&self.drawn_widths, columns);
+        if rows > 1 {
+            queue!(out, MoveUp((rows - 1) as u16))?;
+        }
+        if rows > 0 {
+            queue!(out, MoveToColumn(0), Clear(ClearType::FromCursorDown))?;
+        }
+        self.drawn_widths.clear();
+        Ok(())
+    }
+
+    fn print_progress(
+        &mut self,
+        out: &mut impl Write,
+        columns: Option<usize>,
+    ) -> std::io::Result<()> {
+        for (i, line) in self.progress.iter().enumerate() {
+            if i > 0 {
+                queue!(out, Print("\r\n"))?;
+            }
+            // the last column is left free, some terminals wrap as soon as it is filled
+            let line = match columns {
+                Some(columns) => layout::clip_styled(line, columns.saturating_sub(1)),
+                None => line.into(),
+            };
+            queue!(out, Print(&line), Clear(ClearType::UntilNewLine))?;
+            self.drawn_widths.push(visible_width(&line));
+        }
+        Ok(())
+    }
+}
+
+/// Prints a line to the log area, keeping the progress area below it.
+pub fn write_log(line: &str) {
+    let mut terminal = TERMINAL.lock().unwrap();
+    let columns = terminal_width();
+    let mut buf = Vec::with_capacity(line.len() + 64);
+    let _ = terminal.erase_progress(&mut buf, columns);
+    let _ = queue!(
+        buf,
+        MoveToColumn(0),
+        Print(line),
+        Clear(ClearType::UntilNewLine),
+        Print('\n'),
+    );
+    let _ = terminal.print_progress(&mut buf, columns);
+    flush(&buf);
+}
+
+/// Prints a line to the log area as is, without control sequences, so that
+/// machine-readable logs stay parseable. Nothing must be drawn in the progress area.
+pub fn write_raw_log(line: &str) {
+    let _terminal = TERMINAL.lock().unwrap();
+    let mut buf = Vec::with_capacity(line.len() + 1);
+    buf.extend_from_slice(line.as_bytes());
+    buf.push(b'\n');
+    flush(&buf);
+}
+
+/// Replaces the content of the progress area and redraws it in place.
+pub fn draw_progress(lines: Vec<String>) {
+    let mut terminal = TERMINAL.lock().unwrap();
+    let columns = terminal_width();
+    let mut buf = Vec::with_capacity(256);
+    let _ = terminal.erase_progress(&mut buf, columns);
+    terminal.progress = lines;
+    let _ = terminal.print_progress(&mut buf, columns);
+    flush(&buf);
+}
+
+/// Erases the progress area from the screen and forgets its content.
+pub fn clear_progress() {
+    let mut terminal = TERMINAL.lock().unwrap();
+    let columns = terminal_width();
+    let mut buf = Vec::with_capacity(32);
+    let _ = terminal.erase_progress(&mut buf, columns);
+    terminal.progress.clear();
+    flush(&buf);
+}
+
+/// Width of the terminal attached to stderr, if any
+pub fn terminal_width() -> Option<usize> {
+    terminal_size::terminal_size_of(stderr()).map(|(Width(w), _)| w as usize)
+}
+
+/// Height of the terminal attached to stderr, if any
+pub fn terminal_height() -> Option<usize> {
+    terminal_size::terminal_size_of(stderr()).map(|(_, Height(h))| h as usize)
+}
+
+fn flush(buf: &[u8]) {
+    let mut stderr = stderr().lock();
+    let _ = stderr.write_all(buf);
+    let _ = stderr.flush();
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn erase_progress__multiline_area__EXPECT__cursor_moved_to_first_line() {
+        // Given
+        let mut terminal = Terminal {
+            progress: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            drawn_widths: vec![1, 1, 1],
+        };
+        let mut buf = Vec::new();
+
+        // When
+        terminal.erase_progress(&mut buf, Some(80)).unwrap();
+
+        // Then
+        assert_eq!("\x1b[2A\x1b[1G\x1b[J", String::from_utf8(buf).unwrap());
+        assert!(terminal.drawn_widths.is_empty());
+    }
+
+    #[test]
+    fn erase_progress__terminal_narrowed_after_draw__EXPECT__wrapped_rows_erased() {
+        // Given
+        let mut terminal = Terminal {
+            progress: vec!["a".repeat(79), "b".to_string()],
+            drawn_widths: vec![79, 1],
+        };
+        let mut buf = Vec::new();
+
+        // When
+        terminal.erase_progress(&mut buf, Some(40)).unwrap();
+
+        // Then
+        assert_eq!("\x1b[2A\x1b[1G\x1b[J", String::from_utf8(buf).unwrap());
+    }
+
+    #[test]
+    fn print_progress__line_wider_than_terminal__EXPECT__line_clipped() {
+        // Given
+        let mut terminal = Terminal {
+            progress: vec!["a".repeat(100)],
+            drawn_widths: Vec::new(),
+        };
+        let mut buf = Vec::new();
+
+        // When
+        terminal.print_progress(&mut buf, Some(40)).unwrap();
+
+        // Then
+        assert_eq!(vec![39], terminal.drawn_widths);
+        assert!(!String::from_utf8(buf).unwrap().contains(&"a".repeat(40)));
+    }
+
+    #[test]
+    fn erase_progress__nothing_drawn__EXPECT__no_output() {
+        // Given
+        let mut terminal = Terminal::default();
+        let mut buf = Vec::new();
+
+        // When
+        terminal.erase_progress(&mut buf, Some(80)).unwrap();
+
+        // Then
+        assert!(buf.is_empty());
+    }
+}
@@ -1,16 +1,18 @@
-use crate::{Error, EvalContext, Result};
-use lib_cache::CacheKey;
+use crate::{Error, EvalContext, Result, actions::transform_cache_key};
 use lib_label::Label;
 use log::info;
 
 const WEBP_TRANSFORM_TAG: u8 = 0x02;
 
 pub fn convert_png_to_webp(ctx: &EvalContext, args: ConvertPngToWebpArgs) -> Result<Vec<u8>> {
+    let _span = ctx.eval_args.metrics.spans().span("convert_png_to_webp");
     // construct unique cache key
-    let cache_key = CacheKey::builder()
-        .set_tag(WEBP_TRANSFORM_TAG)
+    let cache_key = transform_cache_key(WEBP_TRANSFORM_TAG)
         .write(args.bytes)
         .write_str(&args.quality.to_string())
+        .write_bool(args.lossless)
+        .write_str(&args.alpha_quality.to_string())
+        .write_u64(args.method as u64)
         .build();
 
     // return cached value if it exists
@@ -30,11 +32,18 @@ pub fn convert_png_to_webp(ctx: &EvalContext, args: ConvertPngToWebpArgs) -> Res
     );
     let png = image::load_from_memory_with_format(args.bytes, image::ImageFormat::Png)?;
     let encoder = webp::Encoder::from_image(&png).map_err(|_| Error::WebpCreate)?; // fails if img is not RBG8 or RBGA8
-    let webp = if args.quality == 100.0 {
-        encoder.encode_lossless()
-    } else {
-        encoder.encode(args.quality)
-    };
+    let mut config = webp::WebPConfig::new().map_err(|_| Error::WebpCreate)?;
+    // quality 100 has always meant lossless encoding
+    config.lossless = (args.lossless || args.quality == 100.0) as i32;
+    config.quality = args.quality;
+    config.alpha_quality = args.alpha_quality.round() as i32;
+    config.method = args.method as i32;
+    let webp = encoder.encode_advanced(&config).map_err(|e| {
+        Error::ConversionError(format!(
+            "unable to encode webp `{}` {}: {e:?}",
+            args.label, args.variant_name
+        ))
+    })?;
 
     // remember result to cache
     ctx.cache.put_bytes(&cache_key, &webp)?;
@@ -43,6 +52,11 @@ pub fn convert_png_to_webp(ctx: &EvalContext, args: ConvertPngToWebpArgs) -> Res
 
 pub struct ConvertPngToWebpArgs<'a> {
     pub quality: f32,
+    pub lossless: bool,
+    /// Quality of the alpha channel, from 0 to 100
+    pub alpha_quality: f32,
+    /// Encoder method, from 0 (fastest) to 6 (smallest)
+    pub method: u8,
     pub bytes: &'a [u8],
     pub label: &'a Label,
     pub variant_name: &'a str,
@@ -1,38 +1,33 @@
 use crate::EvalContext;
 use crate::Result;
+use crate::actions::KeepIntermediateArgs;
+use crate::actions::keep_intermediate;
+use crate::actions::transform_cache_key;
+use crate::actions::validation::ensure_strokes_visible;
 use lib_cache::CacheKey;
+use lib_cache::CacheKeyBuilder;
 use lib_label::Label;
 use lib_svg2compose::SvgToComposeOptions;
 use log::info;
 use phase_loading::ColorMapping;
 use phase_loading::ComposePreview;
+use std::collections::HashMap;
 
 const COMPOSE_TRANSFORM_TAG: u8 = 0x03;
 
 pub fn convert_svg_to_compose(ctx: &EvalContext, args: ConvertSvgToComposeArgs) -> Result<Vec<u8>> {
-    // construct unique cache key
-    let mut cache_key = CacheKey::builder()
-        .set_tag(COMPOSE_TRANSFORM_TAG)
-        .write(args.svg)
-        .write_str(args.package)
-        .write_bool(args.kotlin_explicit_api)
-        .write_str(args.extension_target.as_deref().unwrap_or_default())
-        .write_str(&args.file_suppress_lint.join(",").to_string());
-
-    for mapping in args.color_mappings {
-        cache_key = cache_key.write_str(&mapping.from).write_str(&mapping.to)
+    let _span = ctx.eval_args.metrics.spans().span("convert_svg_to_compose");
+    // validate before the cache lookup, so that the warning is repeated on every build;
+    // image vectors are drawn in dp, one dp is one pixel on `mdpi` screens
+    for (_, svg) in args.images {
+        ensure_strokes_visible(svg, 1.0, args.label, args.variant_name);
     }
 
-    if let Some(preview) = args.preview {
-        cache_key = cache_key
-            .write_str(&preview.imports.join(","))
-            .write_str(&preview.code)
-    }
-
-    let cache_key = cache_key.build();
+    let cache_key = compose_cache_key(&args);
 
     // return cached value if it exists
     if let Some(compose) = ctx.cache.get_bytes(&cache_key)? {
+        keep_generated(ctx, &args, &compose);
         return Ok(compose);
     }
 
@@ -45,10 +40,10 @@ pub fn convert_svg_to_compose(ctx: &EvalContext, args: ConvertSvgToComposeArgs)
             format!(" ({})", args.variant_name)
         }
     );
-    let compose = lib_svg2compose::transform_svg_to_compose(
-        args.svg,
+    let compose = lib_svg2compose::transform_svgs_to_compose(
+        args.images,
         SvgToComposeOptions {
-            image_name: args.name.to_owned(),
+            image_name: String::new(),
             package: args.package.to_owned(),
             kotlin_explicit_api: args.kotlin_explicit_api,
             extension_target: args.extension_target.to_owned(),
@@ -70,6 +65,11 @@ pub fn convert_svg_to_compose(ctx: &EvalContext, args: ConvertSvgToComposeArgs)
                     code: domain.code.to_owned(),
                 }),
             composable_get: args.composable_get,
+            property_name_template: args.property_name_template.to_owned(),
+            receiver_object: args.receiver_object.to_owned(),
+            multiplatform: args.multiplatform,
+            identifier_prefix: args.identifier_prefix.to_owned(),
+            kdoc: args.kdocs.to_owned(),
         },
     )
     .map_err(|err| {
@@ -81,19 +81,157 @@ pub fn convert_svg_to_compose(ctx: &EvalContext, args: ConvertSvgToComposeArgs)
 
     // remember result to cache
     ctx.cache.put_bytes(&cache_key, &compose)?;
+    keep_generated(ctx, &args, &compose);
     Ok(compose)
 }
 
+/// Builds the cache key of the generated file.
+///
+/// Every string is written with its length and every optional string with a presence
+/// byte, so that values of adjacent fields can't be shifted into each other.
+fn compose_cache_key(args: &ConvertSvgToComposeArgs) -> CacheKey {
+    let mut cache_key = transform_cache_key(COMPOSE_TRANSFORM_TAG);
+    for (name, svg) in args.images {
+        cache_key = write_string(cache_key, name)
+            .write_usize(svg.len())
+            .write(svg);
+    }
+    cache_key = write_string(cache_key, args.package).write_bool(args.kotlin_explicit_api);
+    cache_key = write_optional_string(cache_key, args.extension_target);
+    cache_key =
+        write_string(cache_key, &args.file_suppress_lint.join(",")).write_bool(args.composable_get);
+    cache_key = write_optional_string(cache_key, args.property_name_template);
+    cache_key = write_optional_string(cache_key, args.receiver_object);
+    cache_key = cache_key.write_bool(args.multiplatform);
+    cache_key = write_optional_string(cache_key, args.identifier_prefix);
+
+    let mut kdocs = args.kdocs.iter().collect::<Vec<_>>();
+    kdocs.sort();
+    cache_key = cache_key.write_usize(kdocs.len());
+    for (name, kdoc) in kdocs {
+        cache_key = write_string(write_string(cache_key, name), kdoc);
+    }
+
+    cache_key = cache_key.write_usize(args.color_mappings.len());
+    for mapping in args.color_mappings {
+        cache_key = write_string(write_string(cache_key, &mapping.from), &mapping.to);
+    }
+
+    match args.preview {
+        Some(preview) => {
+            cache_key = write_string(cache_key.write_u8(1), &preview.imports.join(","));
+            cache_key = write_string(cache_key, &preview.code);
+        }
+        None => cache_key = cache_key.write_u8(0),
+    }
+
+    cache_key.build()
+}
+
+fn write_string(cache_key: CacheKeyBuilder, value: &str) -> CacheKeyBuilder {
+    cache_key.write_usize(value.len()).write_str(value)
+}
+
+fn write_optional_string(cache_key: CacheKeyBuilder, value: &Option<String>) -> CacheKeyBuilder {
+    match value {
+        Some(value) => write_string(cache_key.write_u8(1), value),
+        None => cache_key.write_u8(0),
+    }
+}
+
+fn keep_generated(ctx: &EvalContext, args: &ConvertSvgToComposeArgs, code: &[u8]) {
+    keep_intermediate(
+        ctx,
+        KeepIntermediateArgs {
+            label: args.label,
+            variant_name: args.variant_name,
+            file_name: "generated.kt",
+            bytes: code,
+        },
+    );
+}
+
 pub struct ConvertSvgToComposeArgs<'a> {
     pub label: &'a Label,
     pub variant_name: &'a str,
-    pub name: &'a str,
+    /// Output name => SVG, all images are generated into the same file
+    pub images: &'a [(&'a str, &'a [u8])],
     pub package: &'a str,
     pub kotlin_explicit_api: bool,
     pub extension_target: &'a Option<String>,
     pub file_suppress_lint: &'a [String],
     pub color_mappings: &'a [ColorMapping],
     pub preview: &'a Option<ComposePreview>,
-    pub svg: &'a [u8],
     pub composable_get: bool,
+    pub property_name_template: &'a Option<String>,
+    pub receiver_object: &'a Option<String>,
+    pub multiplatform: bool,
+    pub identifier_prefix: &'a Option<String>,
+    /// Image name => KDoc of its property
+    pub kdocs: &'a HashMap<String, String>,
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    fn cache_key_of(
+        property_name_template: Option<&str>,
+        receiver_object: Option<&str>,
+        identifier_prefix: Option<&str>,
+    ) -> CacheKey {
+        let label = Label::from_package_and_name("icons", "ic_star").unwrap();
+        let images: [(&str, &[u8]); 1] = [("Star", b"<svg/>")];
+        compose_cache_key(&ConvertSvgToComposeArgs {
+            label: &label,
+            variant_name: "",
+            images: &images,
+            package: "com.example",
+            kotlin_explicit_api: false,
+            extension_target: &None,
+            file_suppress_lint: &[],
+            color_mappings: &[],
+            preview: &None,
+            composable_get: false,
+            property_name_template: &property_name_template.map(str::to_owned),
+            receiver_object: &receiver_object.map(str::to_owned),
+            multiplatform: false,
+            identifier_prefix: &identifier_prefix.map(str::to_owned),
+            kdocs: &HashMap::new(),
+        })
+    }
+
+    #[test]
+    fn compose_cache_key__same_args__EXPECT__same_keys() {
+        // When
+        let key1 = cache_key_of(Some("{name}Icon"), Some("Icons"), None);
+        let key2 = cache_key_of(Some("{name}Icon"), Some("Icons"), None);
+
+        // Then
+        assert_eq!(key1, key2);
+    }
+
+    #[test]
+    fn compose_cache_key__value_shifted_between_fields__EXPECT__different_keys() {
+        // When
+        let key1 = cache_key_of(Some("ab"), Some("c"), None);
+        let key2 = cache_key_of(Some("a"), Some("bc"), None);
+
+        // Then
+        assert_ne!(key1, key2);
+    }
+
+    #[test]
+    fn compose_cache_key__absent_and_empty_values__EXPECT__different_keys() {
+        // When
+        let key1 = cache_key_of(None, None, None);
+        let key2 = cache_key_of(Some(""), None, None);
+        let key3 = cache_key_of(None, None, Some(""));
+
+        // Then
+        assert_ne!(key1, key2);
+        assert_ne!(key1, key3);
+        assert_ne!(key2, key3);
+    }
 }
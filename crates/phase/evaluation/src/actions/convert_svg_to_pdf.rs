@@ -0,0 +1,102 @@
+use crate::{
+    Error, EvalContext, Result,
+    actions::{KeepIntermediateArgs, keep_intermediate, transform_cache_key},
+};
+use lib_label::Label;
+use log::info;
+use svg2pdf::{ConversionOptions, PageOptions, usvg::Tree};
+
+const SVG2PDF_TRANSFORM_TAG: u8 = 0x0D;
+
+pub fn convert_svg_to_pdf(ctx: &EvalContext, args: ConvertSvgToPdfArgs) -> Result<Vec<u8>> {
+    let _span = ctx.eval_args.metrics.spans().span("convert_svg_to_pdf");
+    // construct unique cache key
+    let cache_key = transform_cache_key(SVG2PDF_TRANSFORM_TAG)
+        .write(args.svg)
+        .build();
+
+    // return cached value if it exists
+    if let Some(pdf) = ctx.cache.get_bytes(&cache_key)? {
+        keep_converted(ctx, &args, &pdf);
+        return Ok(pdf);
+    }
+
+    // otherwise, do transform
+    info!(target: "Converting", "SVG to PDF: `{label}`{variant}",
+        label = args.label.fitted(40),
+        variant = if args.variant_name.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", args.variant_name)
+        }
+    );
+    let pdf = render_pdf(args.svg).map_err(|err| {
+        Error::ConversionError(format!(
+            "unable to convert SVG to PDF ({}): {err}",
+            args.label,
+        ))
+    })?;
+
+    // remember result to cache
+    ctx.cache.put_bytes(&cache_key, &pdf)?;
+    keep_converted(ctx, &args, &pdf);
+    Ok(pdf)
+}
+
+fn keep_converted(ctx: &EvalContext, args: &ConvertSvgToPdfArgs, pdf: &[u8]) {
+    keep_intermediate(
+        ctx,
+        KeepIntermediateArgs {
+            label: args.label,
+            variant_name: args.variant_name,
+            file_name: "converted.pdf",
+            bytes: pdf,
+        },
+    );
+}
+
+fn render_pdf(svg: &[u8]) -> std::result::Result<Vec<u8>, String> {
+    let tree =
+        Tree::from_data(svg, &Default::default()).map_err(|e| format!("invalid svg: {e}"))?;
+    svg2pdf::to_pdf(&tree, ConversionOptions::default(), PageOptions::default())
+        .map_err(|e| e.to_string())
+}
+
+pub struct ConvertSvgToPdfArgs<'a> {
+    pub label: &'a Label,
+    pub variant_name: &'a str,
+    pub svg: &'a [u8],
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn render_pdf__simple_svg__EXPECT__vector_pdf_document() {
+        // Given
+        let svg = r#"<svg width="24" height="24" viewBox="0 0 24 24" xmlns="http://www.w3.org/2000/svg">
+<path d="M4 4H20V20H4Z" fill="red"/>
+</svg>"#;
+
+        // When
+        let pdf = render_pdf(svg.as_bytes()).unwrap();
+
+        // Then
+        assert!(pdf.starts_with(b"%PDF-"));
+        assert!(!pdf.windows(b"/Image".len()).any(|w| w == b"/Image"));
+    }
+
+    #[test]
+    fn render_pdf__invalid_svg__EXPECT__error() {
+        // Given
+        let svg = "not an svg";
+
+        // When
+        let result = render_pdf(svg.as_bytes());
+
+        // Then
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,74 @@
+use lib_label::Label;
+use lib_svg2react::SvgToReactOptions;
+use log::info;
+
+use crate::{
+    EvalContext, Result,
+    actions::{KeepIntermediateArgs, keep_intermediate, transform_cache_key},
+};
+
+const REACT_TRANSFORM_TAG: u8 = 0x0A;
+
+pub fn convert_svg_to_react(ctx: &EvalContext, args: ConvertSvgToReactArgs) -> Result<Vec<u8>> {
+    let _span = ctx.eval_args.metrics.spans().span("convert_svg_to_react");
+    // construct unique cache key
+    let cache_key = transform_cache_key(REACT_TRANSFORM_TAG)
+        .write(args.svg)
+        .write_str(args.component_name)
+        .write_bool(args.current_color)
+        .build();
+
+    // return cached value if it exists
+    if let Some(tsx) = ctx.cache.get_bytes(&cache_key)? {
+        keep_generated(ctx, &args, &tsx);
+        return Ok(tsx);
+    }
+
+    // otherwise, do transform
+    info!(target: "Converting", "SVG to React: `{label}`{variant}",
+        label = args.label.fitted(40),
+        variant = if args.variant_name.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", args.variant_name)
+        }
+    );
+    let tsx = lib_svg2react::transform_svg_to_react(
+        args.svg,
+        SvgToReactOptions {
+            component_name: args.component_name.to_owned(),
+            current_color: args.current_color,
+        },
+    )
+    .map_err(|err| {
+        crate::Error::ConversionError(format!(
+            "unable to convert SVG to React component ({}): {err}",
+            args.label,
+        ))
+    })?;
+
+    // remember result to cache
+    ctx.cache.put_bytes(&cache_key, &tsx)?;
+    keep_generated(ctx, &args, &tsx);
+    Ok(tsx)
+}
+
+fn keep_generated(ctx: &EvalContext, args: &ConvertSvgToReactArgs, code: &[u8]) {
+    keep_intermediate(
+        ctx,
+        KeepIntermediateArgs {
+            label: args.label,
+            variant_name: args.variant_name,
+            file_name: "generated.tsx",
+            bytes: code,
+        },
+    );
+}
+
+pub struct ConvertSvgToReactArgs<'a> {
+    pub label: &'a Label,
+    pub variant_name: &'a str,
+    pub component_name: &'a str,
+    pub current_color: bool,
+    pub svg: &'a [u8],
+}
@@ -1,9 +1,14 @@
-use lib_cache::CacheKey;
 use lib_label::Label;
 use lib_svg2drawable::SvgToDrawableOptions;
 use log::info;
 
-use crate::{EvalContext, Result};
+use crate::{
+    EvalContext, Result,
+    actions::{
+        KeepIntermediateArgs, keep_intermediate, transform_cache_key,
+        validation::ensure_strokes_visible,
+    },
+};
 
 const AVD_TRANSFORM_TAG: u8 = 0x09;
 
@@ -11,10 +16,21 @@ pub fn convert_svg_to_vector_drawable(
     ctx: &EvalContext,
     args: ConvertSvgToVectorDrawableArgs,
 ) -> Result<Vec<u8>> {
+    let _span = ctx
+        .eval_args
+        .metrics
+        .spans()
+        .span("convert_svg_to_vector_drawable");
+    // validate before the cache lookup, so that the warning is repeated on every build
+    if !args.monochrome {
+        // drawables are scaled to the density of the screen, `mdpi` is the lowest common one
+        ensure_strokes_visible(args.svg, 1.0, args.label, args.variant_name);
+    }
+
     // construct unique cache key
-    let cache_key = CacheKey::builder()
-        .set_tag(AVD_TRANSFORM_TAG)
+    let cache_key = transform_cache_key(AVD_TRANSFORM_TAG)
         .write(args.svg)
+        .write_bool(args.monochrome)
         .build();
 
     // return cached value if it exists
@@ -23,19 +39,21 @@ pub fn convert_svg_to_vector_drawable(
     // }
 
     // otherwise, do transform
-    info!(target: "Converting", "SVG to Android Drawable: `{label}`{variant}",
+    info!(target: "Converting", "SVG to Android Drawable: `{label}`{variant}{monochrome}",
         label = args.label.fitted(40),
         variant = if args.variant_name.is_empty() {
             String::new()
         } else {
             format!(" ({})", args.variant_name)
-        }
+        },
+        monochrome = if args.monochrome { " (monochrome)" } else { "" },
     );
     let xml = lib_svg2drawable::transform_svg_to_drawable(
         args.svg,
         SvgToDrawableOptions {
             xml_declaration: false,
             auto_mirrored: args.auto_mirrored,
+            monochrome: args.monochrome,
         },
     )
     .map_err(|err| {
@@ -47,6 +65,19 @@ pub fn convert_svg_to_vector_drawable(
 
     // remember result to cache
     ctx.cache.put_bytes(&cache_key, &xml)?;
+    keep_intermediate(
+        ctx,
+        KeepIntermediateArgs {
+            label: args.label,
+            variant_name: args.variant_name,
+            file_name: if args.monochrome {
+                "generated_monochrome.xml"
+            } else {
+                "generated.xml"
+            },
+            bytes: &xml,
+        },
+    );
     Ok(xml)
 }
 
@@ -54,5 +85,6 @@ pub struct ConvertSvgToVectorDrawableArgs<'a> {
     pub label: &'a Label,
     pub variant_name: &'a str,
     pub auto_mirrored: bool,
+    pub monochrome: bool,
     pub svg: &'a [u8],
 }
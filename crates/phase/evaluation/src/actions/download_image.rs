@@ -2,7 +2,17 @@ use crate::{EvalContext, Result};
 use phase_loading::RemoteSource;
 
 pub fn download_image(ctx: &EvalContext, args: DownloadImageArgs) -> Result<Vec<u8>> {
-    ctx.figma_repository.download(args.remote, args.url)
+    let _span = ctx.eval_args.metrics.spans().span("download_image");
+    let repository = ctx.figma_repository.clone();
+    let remote = args.remote.clone();
+    let url = args.url.to_owned();
+    let (image, downloaded) = ctx.io.run(move || repository.download(&remote, &url))?;
+    if downloaded {
+        ctx.observer
+            .on_image_downloaded(&args.remote.id, image.len());
+    }
+    ctx.metrics.image_size.observe(image.len() as f64);
+    Ok(image)
 }
 
 pub struct DownloadImageArgs<'a> {
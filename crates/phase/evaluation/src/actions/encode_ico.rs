@@ -0,0 +1,74 @@
+/// Packs square PNG images into an ICO file.
+///
+/// Entries are stored as PNG, which is supported by every browser and by Windows
+/// since Vista, so no BMP encoding is needed. Sizes must not exceed 256 pixels.
+pub fn encode_ico(images: &[(u32, &[u8])]) -> Vec<u8> {
+    const HEADER_SIZE: usize = 6;
+    const ENTRY_SIZE: usize = 16;
+    let data_size = images.iter().map(|(_, png)| png.len()).sum::<usize>();
+    let mut ico = Vec::with_capacity(HEADER_SIZE + ENTRY_SIZE * images.len() + data_size);
+
+    // ICONDIR: reserved, type (1 = icon), number of images
+    ico.extend_from_slice(&0u16.to_le_bytes());
+    ico.extend_from_slice(&1u16.to_le_bytes());
+    ico.extend_from_slice(&(images.len() as u16).to_le_bytes());
+
+    let mut offset = HEADER_SIZE + ENTRY_SIZE * images.len();
+    for (size, png) in images {
+        // ICONDIRENTRY: 0 stands for 256 pixels
+        let side = if *size >= 256 { 0 } else { *size as u8 };
+        ico.push(side); // width
+        ico.push(side); // height
+        ico.push(0); // no palette
+        ico.push(0); // reserved
+        ico.extend_from_slice(&1u16.to_le_bytes()); // color planes
+        ico.extend_from_slice(&32u16.to_le_bytes()); // bits per pixel
+        ico.extend_from_slice(&(png.len() as u32).to_le_bytes());
+        ico.extend_from_slice(&(offset as u32).to_le_bytes());
+        offset += png.len();
+    }
+    for (_, png) in images {
+        ico.extend_from_slice(png);
+    }
+    ico
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    fn u16_at(bytes: &[u8], at: usize) -> u16 {
+        u16::from_le_bytes([bytes[at], bytes[at + 1]])
+    }
+
+    fn u32_at(bytes: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+    }
+
+    #[test]
+    fn encode_ico__two_images__EXPECT__valid_directory_and_data() {
+        // Given
+        let small = b"png16".as_slice();
+        let large = b"png256!".as_slice();
+
+        // When
+        let ico = encode_ico(&[(16, small), (256, large)]);
+
+        // Then
+        assert_eq!(
+            (0, 1, 2),
+            (u16_at(&ico, 0), u16_at(&ico, 2), u16_at(&ico, 4))
+        );
+        // first entry
+        assert_eq!((16, 16), (ico[6], ico[7]));
+        assert_eq!(32, u16_at(&ico, 6 + 6));
+        assert_eq!(5, u32_at(&ico, 6 + 8));
+        assert_eq!(38, u32_at(&ico, 6 + 12));
+        // second entry, 256 is stored as 0
+        assert_eq!((0, 0), (ico[22], ico[23]));
+        assert_eq!(7, u32_at(&ico, 22 + 8));
+        assert_eq!(43, u32_at(&ico, 22 + 12));
+        assert_eq!(b"png16png256!", &ico[38..]);
+    }
+}
@@ -12,11 +12,13 @@ pub fn export_image(
     on_export_start: impl FnOnce(),
     on_cache_hit: impl FnOnce(),
 ) -> Result<DownloadUrl> {
+    let _span = ctx.eval_args.metrics.spans().span("export_image");
     ctx.figma_repository.export(
         args.remote,
         args.node,
         args.format,
         args.scale,
+        args.use_absolute_bounds,
         on_export_start,
         on_cache_hit,
     )
@@ -27,4 +29,5 @@ pub struct ExportImageArgs<'a> {
     pub node: &'a NodeMetadata,
     pub format: &'a str,
     pub scale: f32,
+    pub use_absolute_bounds: bool,
 }
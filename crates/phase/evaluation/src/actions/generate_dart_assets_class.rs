@@ -0,0 +1,141 @@
+use crate::{
+    EvalContext, Result,
+    actions::materialize::{MaterializeArgs, materialize},
+};
+use log::info;
+use phase_loading::{Profile, Workspace};
+use std::{collections::BTreeMap, fmt::Write, path::PathBuf};
+
+/// Generates Dart classes with string constants for every asset of the
+/// `flutter` profiles that have `dart_class` specified.
+///
+/// All resources of a package sharing the same `dart_file` and `dart_class`
+/// end up in the same class.
+pub fn generate_dart_assets_classes(ctx: &EvalContext, ws: &Workspace) -> Result<()> {
+    let _span = ctx
+        .eval_args
+        .metrics
+        .spans()
+        .span("generate_dart_assets_classes");
+    let mut classes = BTreeMap::<(PathBuf, String), BTreeMap<String, String>>::new();
+    for res in ws.packages.iter().flat_map(|pkg| &pkg.resources) {
+        let Profile::Flutter(profile) = res.profile.as_ref() else {
+            continue;
+        };
+        let Some(class_name) = &profile.dart_class else {
+            continue;
+        };
+        let name = res.attrs.label.name.as_ref();
+        let asset_path = profile
+            .assets_dir
+            .join(res.output_base_name())
+            .with_extension("png")
+            .to_string_lossy()
+            .replace('\\', "/");
+        classes
+            .entry((
+                res.attrs.package_dir.join(&profile.dart_file),
+                class_name.to_owned(),
+            ))
+            .or_default()
+            .insert(dart_constant_name(name), asset_path);
+    }
+
+    for ((dart_file, class_name), assets) in classes {
+        let code = dart_assets_class(&class_name, &assets);
+        let output_dir = dart_file.parent().unwrap_or(&dart_file);
+        let file_name = dart_file
+            .file_stem()
+            .map(|it| it.to_string_lossy().to_string())
+            .unwrap_or_default();
+        materialize(
+            ctx,
+            MaterializeArgs {
+                output_dir,
+                file_name: &file_name,
+                file_extension: "dart",
+                bytes: code.as_bytes(),
+                post_process: None,
+                cause: &format!("dart_class {class_name}"),
+                provenance: None,
+            },
+            || info!(target: "Writing", "`{class_name}` to file"),
+        )?;
+    }
+    Ok(())
+}
+
+fn dart_assets_class(class_name: &str, assets: &BTreeMap<String, String>) -> String {
+    let mut code = String::with_capacity(64 * (assets.len() + 4));
+    let _ = writeln!(code, "// Generated by figx. Do not edit manually.");
+    let _ = writeln!(code);
+    let _ = writeln!(code, "class {class_name} {{");
+    let _ = writeln!(code, "  {class_name}._();");
+    for (constant, path) in assets {
+        let _ = writeln!(code);
+        let _ = writeln!(code, "  static const String {constant} = '{path}';");
+    }
+    let _ = writeln!(code, "}}");
+    code
+}
+
+/// Converts resource name like `ic_arrow-back` into lowerCamelCase `icArrowBack`
+fn dart_constant_name(name: &str) -> String {
+    let mut output = String::with_capacity(name.len());
+    let mut upper_next = false;
+    for c in name.chars() {
+        if !c.is_ascii_alphanumeric() {
+            upper_next = !output.is_empty();
+        } else if upper_next {
+            output.push(c.to_ascii_uppercase());
+            upper_next = false;
+        } else if output.is_empty() {
+            output.push(c.to_ascii_lowercase());
+        } else {
+            output.push(c);
+        }
+    }
+    match output.chars().next() {
+        Some(c) if c.is_ascii_digit() => format!("asset{output}"),
+        _ => output,
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dart_constant_name__snake_and_kebab_case__EXPECT__lower_camel_case() {
+        assert_eq!("icArrowBack", dart_constant_name("ic_arrow-back"));
+        assert_eq!("logo", dart_constant_name("Logo"));
+        assert_eq!("asset24Close", dart_constant_name("24_close"));
+    }
+
+    #[test]
+    fn dart_assets_class__two_assets__EXPECT__sorted_constants() {
+        // Given
+        let assets = [
+            ("logo".to_string(), "assets/logo.png".to_string()),
+            ("icClose".to_string(), "assets/ic_close.png".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        let expected = "// Generated by figx. Do not edit manually.\n\
+            \n\
+            class AppAssets {\n\
+            \x20 AppAssets._();\n\
+            \n\
+            \x20 static const String icClose = 'assets/ic_close.png';\n\
+            \n\
+            \x20 static const String logo = 'assets/logo.png';\n\
+            }\n";
+
+        // When
+        let actual = dart_assets_class("AppAssets", &assets);
+
+        // Then
+        assert_eq!(expected, actual);
+    }
+}
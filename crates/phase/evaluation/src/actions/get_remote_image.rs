@@ -1,15 +1,43 @@
 use super::{
+    KeepIntermediateArgs, RenderSvgToPngArgs,
     download_image::DownloadImageArgs,
     export_image::{ExportImageArgs, export_image},
+    keep_intermediate, render_svg_to_png, scaled_file_name,
 };
-use crate::{EvalContext, Result, actions::download_image::download_image, figma::NodeMetadata};
+use crate::{
+    EvalContext, Result,
+    actions::download_image::download_image,
+    figma::{FigmaRepository, NodeMetadata},
+};
+use image::{ImageFormat, imageops::FilterType};
+use lib_cache::Cache;
 use lib_label::Label;
-use log::info;
-use phase_loading::RemoteSource;
-use std::sync::Arc;
+use log::{info, warn};
+use phase_loading::{ExportScale, RemoteSource};
+use std::{io::Cursor, sync::Arc};
 
 /// Shortcut action
 pub fn get_remote_image(ctx: &EvalContext, args: GetRemoteImageArgs) -> Result<Vec<u8>> {
+    let _span = ctx.eval_args.metrics.spans().span("get_remote_image");
+    ctx.eval_args.cancellation.check()?;
+    let image = if args.format == "png" && args.scale > ExportScale::FIGMA_MAX {
+        get_png_beyond_figma_scale(ctx, &args)?
+    } else {
+        export_and_download(ctx, &args)?
+    };
+    keep_intermediate(
+        ctx,
+        KeepIntermediateArgs {
+            label: args.label,
+            variant_name: args.variant_name,
+            file_name: &scaled_file_name("figma", args.scale, args.format),
+            bytes: &image,
+        },
+    );
+    Ok(image)
+}
+
+fn export_and_download(ctx: &EvalContext, args: &GetRemoteImageArgs) -> Result<Vec<u8>> {
     download_image(
         ctx,
         DownloadImageArgs {
@@ -21,6 +49,7 @@ pub fn get_remote_image(ctx: &EvalContext, args: GetRemoteImageArgs) -> Result<V
                     format: args.format,
                     scale: args.scale,
                     node: args.node,
+                    use_absolute_bounds: args.use_absolute_bounds,
                 },
                 || {
                     info!(target: "Downloading", "{format} for `{label}`{variant}",
@@ -39,9 +68,97 @@ pub fn get_remote_image(ctx: &EvalContext, args: GetRemoteImageArgs) -> Result<V
     )
 }
 
+/// Whether [`get_remote_image`] would take the image from the cache without any request
+/// to the remote, e.g. to explain the import before running it
+pub fn is_remote_image_cached(
+    cache: &Cache,
+    remote: &RemoteSource,
+    node: &NodeMetadata,
+    format: &str,
+    scale: f32,
+    use_absolute_bounds: bool,
+) -> Result<bool> {
+    // mirrors the choice of `get_png_beyond_figma_scale`
+    let (format, scale) = match format == "png" && scale > ExportScale::FIGMA_MAX {
+        true if !node.uses_raster_paints => ("svg", 1.0),
+        true => ("png", ExportScale::FIGMA_MAX),
+        false => (format, scale),
+    };
+    FigmaRepository::is_image_cached(
+        cache,
+        &remote.file_key,
+        node,
+        format,
+        scale,
+        use_absolute_bounds,
+    )
+}
+
+/// Figma refuses to export images with scale above 4. Vector nodes are exported
+/// as SVG and rendered locally, nodes with raster fills are exported with the
+/// maximum scale and upscaled. The SVG is exported with the same bounds as the
+/// PNG would be, so the rendered image has the same size and clipping.
+///
+/// The fetch only puts the exported image to the cache, so it is returned as is.
+fn get_png_beyond_figma_scale(ctx: &EvalContext, args: &GetRemoteImageArgs) -> Result<Vec<u8>> {
+    if !args.node.uses_raster_paints {
+        let svg = export_and_download(
+            ctx,
+            &GetRemoteImageArgs {
+                format: "svg",
+                scale: 1.0,
+                ..*args
+            },
+        )?;
+        if ctx.eval_args.fetch {
+            return Ok(svg);
+        }
+        return render_svg_to_png(
+            ctx,
+            RenderSvgToPngArgs {
+                label: args.label,
+                variant_name: args.variant_name,
+                svg: &svg,
+                zoom: Some(args.scale),
+            },
+        );
+    }
+    let png = export_and_download(
+        ctx,
+        &GetRemoteImageArgs {
+            scale: ExportScale::FIGMA_MAX,
+            ..*args
+        },
+    )?;
+    if ctx.eval_args.fetch {
+        return Ok(png);
+    }
+    warn!(
+        target: "Export",
+        "`{label}` has raster fills and cannot be exported with scale {scale}, upscaling it from {max}x locally",
+        label = args.label.fitted(50),
+        scale = args.scale,
+        max = ExportScale::FIGMA_MAX,
+    );
+    let image = image::load_from_memory_with_format(&png, ImageFormat::Png)?;
+    let factor = args.scale / ExportScale::FIGMA_MAX;
+    let upscaled = image.resize_exact(
+        (image.width() as f32 * factor).round() as u32,
+        (image.height() as f32 * factor).round() as u32,
+        FilterType::Lanczos3,
+    );
+    let mut bytes = Cursor::new(Vec::new());
+    upscaled.write_to(&mut bytes, ImageFormat::Png)?;
+    Ok(bytes.into_inner())
+}
+
+#[derive(Clone, Copy)]
 pub struct GetRemoteImageArgs<'a> {
     pub label: &'a Label,
     pub remote: &'a Arc<RemoteSource>,
+    /// Export the full dimensions of the node instead of its render bounds,
+    /// content outside of the node (e.g. outside strokes) is clipped
+    pub use_absolute_bounds: bool,
     pub node: &'a NodeMetadata,
     pub format: &'a str,
     pub scale: f32,
@@ -1,4 +1,6 @@
 use crate::EvalContext;
+use crate::MaterializedFile;
+use crate::Provenance;
 use crate::Result;
 use crate::Target;
 use crate::actions::ConvertSvgToVectorDrawableArgs;
@@ -8,12 +10,22 @@ use crate::actions::get_remote_image;
 use crate::actions::materialize::MaterializeArgs;
 use crate::actions::materialize::materialize;
 use crate::actions::validation::ensure_is_vector_node;
+use crate::actions::validation::ensure_vector_complexity;
 use crate::figma::NodeMetadata;
+use crate::import_with_fallback;
 use log::debug;
 use log::info;
 use phase_loading::AndroidDrawableProfile;
 
-pub fn import_android_drawable(ctx: &EvalContext, args: ImportAndroidDrawableArgs) -> Result<()> {
+pub fn import_android_drawable(
+    ctx: &EvalContext,
+    args: ImportAndroidDrawableArgs,
+) -> Result<Option<MaterializedFile>> {
+    let _span = ctx
+        .eval_args
+        .metrics
+        .spans()
+        .target_span("import_android_drawable", args.target.qualified_name());
     let ImportAndroidDrawableArgs {
         node,
         target,
@@ -29,14 +41,18 @@ pub fn import_android_drawable(ctx: &EvalContext, args: ImportAndroidDrawableArg
         GetRemoteImageArgs {
             label: &target.attrs.label,
             remote: &target.attrs.remote,
+            use_absolute_bounds: target.attrs.use_absolute_bounds,
             node,
             format: "svg",
             scale: 1.0, // always the same yes
             variant_name: &variant_name,
         },
     )?;
+    if let Some(fallback) = ensure_vector_complexity(&svg, &profile.limits, &target.attrs.label)? {
+        return import_with_fallback(ctx, &target, node, fallback);
+    }
     if ctx.eval_args.fetch {
-        return Ok(());
+        return Ok(None);
     }
 
     let vector_drawable = convert_svg_to_vector_drawable(
@@ -46,6 +62,7 @@ pub fn import_android_drawable(ctx: &EvalContext, args: ImportAndroidDrawableArg
             label: &target.attrs.label,
             variant_name: &variant_name,
             auto_mirrored: profile.auto_mirrored,
+            monochrome: false,
         },
     )?;
 
@@ -66,18 +83,55 @@ pub fn import_android_drawable(ctx: &EvalContext, args: ImportAndroidDrawableArg
         .map(|it| format!(" ({it})"))
         .unwrap_or_default();
     let label = target.attrs.label.fitted(50);
-    materialize(
+    let mut file = materialize(
         ctx,
         MaterializeArgs {
             output_dir: &output_dir,
             file_name: target.output_name(),
             file_extension: "xml",
             bytes: &vector_drawable,
+            post_process: profile.post_process.as_ref(),
+            cause: &target.qualified_name(),
+            provenance: Some(Provenance {
+                label: &target.attrs.label,
+                node: Some(node),
+            }),
         },
         || info!(target: "Writing", "`{label}`{variant} to file"),
     )?;
 
-    Ok(())
+    // themed icons are tinted by the system, so the night variant needs no monochrome layer
+    if profile.monochrome && variant_name.is_empty() {
+        let monochrome_drawable = convert_svg_to_vector_drawable(
+            ctx,
+            ConvertSvgToVectorDrawableArgs {
+                svg: &svg,
+                label: &target.attrs.label,
+                variant_name: &variant_name,
+                auto_mirrored: profile.auto_mirrored,
+                monochrome: true,
+            },
+        )?;
+        let monochrome_file = materialize(
+            ctx,
+            MaterializeArgs {
+                output_dir: &output_dir,
+                file_name: &format!("{}_monochrome", target.output_name()),
+                file_extension: "xml",
+                bytes: &monochrome_drawable,
+                post_process: profile.post_process.as_ref(),
+                cause: &target.qualified_name(),
+                provenance: Some(Provenance {
+                    label: &target.attrs.label,
+                    node: Some(node),
+                }),
+            },
+            || info!(target: "Writing", "`{label}`{variant} (monochrome) to file"),
+        )?;
+        file.merge(monochrome_file);
+    }
+
+    Ok(Some(file))
 }
 
 pub struct ImportAndroidDrawableArgs<'a> {
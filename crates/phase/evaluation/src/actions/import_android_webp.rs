@@ -1,4 +1,5 @@
 use crate::EvalContext;
+use crate::MaterializedFile;
 use crate::Result;
 use crate::Target;
 use crate::actions::GetRemoteImageArgs;
@@ -9,13 +10,21 @@ use crate::actions::materialize::MaterializeArgs;
 use crate::actions::materialize::materialize;
 use crate::actions::render_svg_to_png::RenderSvgToPngArgs;
 use crate::actions::render_svg_to_png::render_svg_to_png;
-use crate::actions::validation::ensure_is_vector_node;
+use crate::actions::validation::{RasterFormat, ensure_is_vector_node, ensure_valid_raster};
 use crate::figma::NodeMetadata;
 use log::debug;
 use log::info;
 use phase_loading::AndroidWebpProfile;
 
-pub fn import_android_webp(ctx: &EvalContext, args: ImportAndroidWebpArgs) -> Result<()> {
+pub fn import_android_webp(
+    ctx: &EvalContext,
+    args: ImportAndroidWebpArgs,
+) -> Result<Option<MaterializedFile>> {
+    let _span = ctx
+        .eval_args
+        .metrics
+        .spans()
+        .target_span("import_android_webp", args.target.qualified_name());
     let ImportAndroidWebpArgs {
         node,
         target,
@@ -32,6 +41,7 @@ pub fn import_android_webp(ctx: &EvalContext, args: ImportAndroidWebpArgs) -> Re
             GetRemoteImageArgs {
                 label: &target.attrs.label,
                 remote: &target.attrs.remote,
+                use_absolute_bounds: target.attrs.use_absolute_bounds,
                 node,
                 format: "png",
                 scale,
@@ -39,7 +49,7 @@ pub fn import_android_webp(ctx: &EvalContext, args: ImportAndroidWebpArgs) -> Re
             },
         )?;
         if ctx.eval_args.fetch {
-            return Ok(());
+            return Ok(None);
         }
         png
     } else {
@@ -49,6 +59,7 @@ pub fn import_android_webp(ctx: &EvalContext, args: ImportAndroidWebpArgs) -> Re
             GetRemoteImageArgs {
                 label: &target.attrs.label,
                 remote: &target.attrs.remote,
+                use_absolute_bounds: target.attrs.use_absolute_bounds,
                 node,
                 format: "svg",
                 scale: 1.0,       // always the same yes
@@ -56,7 +67,7 @@ pub fn import_android_webp(ctx: &EvalContext, args: ImportAndroidWebpArgs) -> Re
             },
         )?;
         if ctx.eval_args.fetch {
-            return Ok(());
+            return Ok(None);
         }
         render_svg_to_png(
             ctx,
@@ -72,11 +83,24 @@ pub fn import_android_webp(ctx: &EvalContext, args: ImportAndroidWebpArgs) -> Re
         ctx,
         ConvertPngToWebpArgs {
             quality: *profile.quality,
+            lossless: profile.lossless,
+            alpha_quality: *profile.alpha_quality,
+            method: *profile.method,
             bytes: &png,
             label: &target.attrs.label,
             variant_name: &variant_name,
         },
     )?;
+    if ctx.eval_args.validate_images {
+        ensure_valid_raster(
+            &webp,
+            RasterFormat::Webp,
+            node.export_size(target.attrs.use_absolute_bounds),
+            scale,
+            &target.attrs.label,
+            &variant_name,
+        )?;
+    }
     let output_dir = target
         .attrs
         .package_dir
@@ -85,17 +109,20 @@ pub fn import_android_webp(ctx: &EvalContext, args: ImportAndroidWebpArgs) -> Re
 
     let variant = &variant_name;
     let label = target.attrs.label.fitted(50);
-    materialize(
+    let file = materialize(
         ctx,
         MaterializeArgs {
             output_dir: &output_dir,
-            file_name: target.attrs.label.name.as_ref(), // always the same name
+            file_name: target.output_name(), // always the same name
             file_extension: "webp",
             bytes: &webp,
+            post_process: profile.post_process.as_ref(),
+            cause: &target.qualified_name(),
+            provenance: None,
         },
         || info!(target: "Writing", "`{label}` ({variant}) to file"),
     )?;
-    Ok(())
+    Ok(Some(file))
 }
 
 pub struct ImportAndroidWebpArgs<'a> {
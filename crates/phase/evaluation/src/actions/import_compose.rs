@@ -3,18 +3,32 @@ use super::{
     materialize::{MaterializeArgs, materialize},
 };
 use crate::{
-    EvalContext, Result, Target,
+    Error, EvalContext, MaterializedFile, Provenance, Result, Target,
     actions::{
         convert_svg_to_compose::{ConvertSvgToComposeArgs, convert_svg_to_compose},
-        validation::ensure_is_vector_node,
+        validation::{ensure_is_vector_node, ensure_kotlin_identifier, ensure_vector_complexity},
     },
     figma::NodeMetadata,
+    import_with_fallback,
 };
+use dashmap::DashMap;
 use log::{debug, info, warn};
-use phase_loading::ComposeProfile;
-use std::path::{Path, PathBuf};
+use phase_loading::{ColorMapping, ComposeProfile, RemoteSource};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
-pub fn import_compose(ctx: &EvalContext, args: ImportComposeArgs) -> Result<()> {
+pub fn import_compose(
+    ctx: &EvalContext,
+    args: ImportComposeArgs,
+) -> Result<Option<MaterializedFile>> {
+    let _span = ctx
+        .eval_args
+        .metrics
+        .spans()
+        .target_span("import_compose", args.target.qualified_name());
     let ImportComposeArgs {
         node,
         target,
@@ -24,7 +38,7 @@ pub fn import_compose(ctx: &EvalContext, args: ImportComposeArgs) -> Result<()>
     let variant_name = target.id.clone().unwrap_or_default();
 
     debug!(target: "Import", "compose: {}", target.attrs.label.name);
-    let output_dir = get_output_dir_for_compose_profile(profile, &target.attrs.package_dir);
+    let output_dir = profile.output_dir(&target.attrs.package_dir);
     let package = get_kotlin_package(&output_dir).unwrap_or_default();
 
     if let (None, true) = (&profile.package, package.is_empty()) {
@@ -32,26 +46,94 @@ pub fn import_compose(ctx: &EvalContext, args: ImportComposeArgs) -> Result<()>
     }
 
     ensure_is_vector_node(&node, node_name, &target.attrs.label, false);
+    let property_name = match &profile.property_name_template {
+        Some(template) => template.replace("{name}", target.output_name()),
+        None => target.output_name().to_owned(),
+    };
+    ensure_kotlin_identifier(
+        &property_name,
+        profile.identifier_prefix.as_deref(),
+        &target.attrs.label,
+    );
     let svg = &get_remote_image(
         ctx,
         GetRemoteImageArgs {
             label: &target.attrs.label,
             remote: &target.attrs.remote,
+            use_absolute_bounds: target.attrs.use_absolute_bounds,
             node,
             format: "svg",
             scale: 1.0,
             variant_name: &variant_name,
         },
     )?;
+
+    // in combined mode the file is generated by the last downloaded variant
+    let combined = profile.combine_variants && target.id.is_some();
+    if let Some(fallback) = ensure_vector_complexity(svg, &profile.limits, &target.attrs.label)? {
+        if combined {
+            return Err(Error::VectorTooComplex(format!(
+                "raster fallback of resource `{}` cannot be used with `combine_variants`",
+                target.attrs.label
+            )));
+        }
+        return import_with_fallback(ctx, &target, node, fallback);
+    }
+    // requested before the fetch-only return, so the responses get into the cache and mirror
+    let color_mappings =
+        resolve_color_mappings(ctx, &target.attrs.remote, &profile.color_mappings)?;
+    let kdoc = match profile.document_sources {
+        true => Some(get_source_kdoc(ctx, &target.attrs.remote, node)),
+        false => None,
+    };
     if ctx.eval_args.fetch {
-        return Ok(());
+        return Ok(None);
     }
+    // variants are bundled into the file named after the resource itself
+    let base_name = target
+        .profile
+        .naming()
+        .apply(target.attrs.label.name.as_ref());
+    let (file_name, images) = match &target.id {
+        Some(id) if combined => {
+            let output_file = output_dir.join(&base_name);
+            let variant = BundledVariant {
+                id: id.to_owned(),
+                output_name: target.output_name().to_owned(),
+                svg: svg.to_owned(),
+                kdoc,
+            };
+            let Some(variants) =
+                ctx.compose_bundles
+                    .add(output_file, variant, count_variants(profile))
+            else {
+                return Ok(None);
+            };
+            let images = variants
+                .into_iter()
+                .map(|it| (it.output_name, it.svg, it.kdoc))
+                .collect::<Vec<_>>();
+            (base_name.as_str(), images)
+        }
+        _ => (
+            target.output_name(),
+            vec![(target.output_name().to_owned(), svg.to_owned(), kdoc)],
+        ),
+    };
+    let kdocs = images
+        .iter()
+        .filter_map(|(name, _, kdoc)| Some((name.to_owned(), kdoc.to_owned()?)))
+        .collect::<HashMap<_, _>>();
+    let images = images
+        .iter()
+        .map(|(name, svg, _)| (name.as_str(), svg.as_slice()))
+        .collect::<Vec<_>>();
     let compose = convert_svg_to_compose(
         ctx,
         ConvertSvgToComposeArgs {
             label: &target.attrs.label,
-            variant_name: &variant_name,
-            name: target.output_name(),
+            variant_name: if combined { "" } else { &variant_name },
+            images: &images,
             package: match profile.package.as_ref() {
                 None => &package,
                 Some(package) => package,
@@ -59,31 +141,41 @@ pub fn import_compose(ctx: &EvalContext, args: ImportComposeArgs) -> Result<()>
             kotlin_explicit_api: profile.kotlin_explicit_api,
             extension_target: &profile.extension_target,
             file_suppress_lint: &profile.file_suppress_lint,
-            svg,
-            color_mappings: &profile.color_mappings,
+            color_mappings: &color_mappings,
             preview: &profile.preview,
             composable_get: profile.composable_get,
+            property_name_template: &profile.property_name_template,
+            receiver_object: &profile.receiver_object,
+            multiplatform: profile.multiplatform,
+            identifier_prefix: &profile.identifier_prefix,
+            kdocs: &kdocs,
         },
     )?;
 
-    let variant = target
-        .id
-        .as_ref()
-        .map(|it| format!(" ({it})"))
-        .unwrap_or_default();
+    let variant = match &target.id {
+        Some(id) if !combined => format!(" ({id})"),
+        _ => String::new(),
+    };
     let label = target.attrs.label.fitted(50);
-    materialize(
+    let file = materialize(
         ctx,
         MaterializeArgs {
             output_dir: &output_dir,
-            file_name: target.output_name(),
+            file_name,
             file_extension: "kt",
             bytes: &compose,
+            post_process: profile.post_process.as_ref(),
+            cause: &target.qualified_name(),
+            // variants bundled into one file come from different nodes
+            provenance: Some(Provenance {
+                label: &target.attrs.label,
+                node: (!combined).then_some(node),
+            }),
         },
         || info!(target: "Writing", "`{label}`{variant} to file"),
     )?;
 
-    Ok(())
+    Ok(Some(file))
 }
 
 pub struct ImportComposeArgs<'a> {
@@ -102,14 +194,61 @@ impl<'a> ImportComposeArgs<'a> {
     }
 }
 
-pub fn get_output_dir_for_compose_profile(p: &ComposeProfile, abs_package_dir: &Path) -> PathBuf {
-    let kt_src_dir = &p.src_dir;
-    let kt_package = match &p.package {
-        Some(package) => package.replace('.', "/"),
-        None => String::new(),
-    };
-    // {abs_package_dir}/{kt_src_dir}/{pkg_dir}
-    abs_package_dir.join(kt_src_dir).join(kt_package)
+/// Downloaded variants of the resources generated into a single file,
+/// collected until all variants of the resource are there
+#[derive(Default)]
+pub struct ComposeBundles(DashMap<PathBuf, Vec<BundledVariant>>);
+
+pub struct BundledVariant {
+    id: String,
+    output_name: String,
+    svg: Vec<u8>,
+    kdoc: Option<String>,
+}
+
+impl ComposeBundles {
+    /// Adds the variant to the bundle of the output file and returns all variants
+    /// of the bundle sorted by id, if the added variant was the last one
+    fn add(
+        &self,
+        output_file: PathBuf,
+        variant: BundledVariant,
+        expected: usize,
+    ) -> Option<Vec<BundledVariant>> {
+        let mut bundle = self.0.entry(output_file.clone()).or_default();
+        bundle.push(variant);
+        if bundle.len() < expected {
+            return None;
+        }
+        drop(bundle);
+        let (_, mut variants) = self.0.remove(&output_file)?;
+        variants.sort_by(|a, b| a.id.cmp(&b.id));
+        Some(variants)
+    }
+
+    /// Output files of the bundles still waiting for some of their variants
+    /// along with the ids of the variants that are there
+    pub fn incomplete(&self) -> Vec<(PathBuf, Vec<String>)> {
+        let mut incomplete = self
+            .0
+            .iter()
+            .map(|bundle| {
+                let mut ids = bundle.iter().map(|it| it.id.clone()).collect::<Vec<_>>();
+                ids.sort();
+                (bundle.key().clone(), ids)
+            })
+            .collect::<Vec<_>>();
+        incomplete.sort();
+        incomplete
+    }
+}
+
+/// Number of the variants requested by the profile
+fn count_variants(profile: &ComposeProfile) -> usize {
+    match &profile.variants {
+        Some(variants) => variants.active().count(),
+        None => 1,
+    }
 }
 
 pub fn get_kotlin_package(output_dir: &Path) -> Option<String> {
@@ -149,3 +288,114 @@ fn dir_to_package(dir: &Path) -> String {
     dir.to_string_lossy()
         .replace(std::path::MAIN_SEPARATOR, ".")
 }
+
+/// KDoc with the description of the Figma component and the link to its node
+fn get_source_kdoc(ctx: &EvalContext, remote: &RemoteSource, node: &NodeMetadata) -> String {
+    let description = match ctx
+        .figma_repository
+        .component_descriptions(remote, ctx.eval_args.refetch)
+    {
+        Ok(descriptions) => descriptions.get(&node.id).cloned(),
+        Err(e) => {
+            warn!(target: "Import", "unable to get component descriptions of {remote}: {e}");
+            None
+        }
+    };
+    let link = format!(
+        "Figma: [{name}](https://www.figma.com/design/{file_key}?node-id={node_id})",
+        name = node.name,
+        file_key = remote.file_key,
+        node_id = node.id.replace(':', "-"),
+    );
+    match description {
+        Some(description) => format!("{}\n\n{link}", description.trim()),
+        None => link,
+    }
+}
+
+/// Replaces Figma variable references (`@color/Primary`) in `from` with their hex values
+fn resolve_color_mappings<'a>(
+    ctx: &EvalContext,
+    remote: &RemoteSource,
+    color_mappings: &'a [ColorMapping],
+) -> Result<Cow<'a, [ColorMapping]>> {
+    if !color_mappings.iter().any(|it| it.from.starts_with('@')) {
+        return Ok(Cow::Borrowed(color_mappings));
+    }
+    let variables = ctx
+        .figma_repository
+        .color_variables(remote, ctx.eval_args.refetch)?;
+    let mut resolved = Vec::with_capacity(color_mappings.len());
+    for mapping in color_mappings {
+        let Some(name) = mapping.from.strip_prefix('@') else {
+            resolved.push(mapping.clone());
+            continue;
+        };
+        let Some(hex) = variables.get(name) else {
+            return Err(Error::ResolveVariable(format!(
+                "color variable `{name}` was not found in remote {remote}"
+            )));
+        };
+        debug!(target: "Import", "color variable `{name}` resolved to {hex}");
+        resolved.push(ColorMapping {
+            from: hex.to_owned(),
+            ..mapping.clone()
+        });
+    }
+    Ok(Cow::Owned(resolved))
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    fn variant(id: &str) -> BundledVariant {
+        BundledVariant {
+            id: id.to_owned(),
+            output_name: format!("Star{id}"),
+            svg: b"<svg/>".to_vec(),
+            kdoc: None,
+        }
+    }
+
+    #[test]
+    fn ComposeBundles__all_variants_added__EXPECT__sorted_bundle_from_last_one() {
+        // Given
+        let bundles = ComposeBundles::default();
+        let file = PathBuf::from("Star");
+
+        // When
+        let first = bundles.add(file.clone(), variant("Outlined"), 2);
+        let last = bundles.add(file.clone(), variant("Filled"), 2);
+
+        // Then
+        assert!(first.is_none());
+        let ids = last
+            .unwrap()
+            .into_iter()
+            .map(|it| it.id)
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["Filled", "Outlined"], ids);
+        assert!(bundles.incomplete().is_empty());
+    }
+
+    #[test]
+    fn ComposeBundles__variant_failed__EXPECT__incomplete_bundle() {
+        // Given
+        let bundles = ComposeBundles::default();
+        let file = PathBuf::from("Star");
+
+        // When
+        let bundle = bundles.add(file.clone(), variant("Outlined"), 3);
+        let bundle_after_failure = bundles.add(file.clone(), variant("Filled"), 3);
+
+        // Then
+        assert!(bundle.is_none());
+        assert!(bundle_after_failure.is_none());
+        assert_eq!(
+            vec![(file, vec!["Filled".to_string(), "Outlined".to_string()])],
+            bundles.incomplete(),
+        );
+    }
+}
@@ -0,0 +1,183 @@
+use super::{
+    GetRemoteImageArgs, RenderFaviconArgs, encode_ico, get_remote_image,
+    materialize::{MaterializeArgs, materialize},
+    render_favicon,
+    validation::ensure_is_vector_node,
+};
+use crate::{EvalContext, MaterializedFile, Result, Target, figma::NodeMetadata};
+use log::{debug, info};
+use phase_loading::FaviconProfile;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+pub fn import_favicon(
+    ctx: &EvalContext,
+    args: ImportFaviconArgs,
+) -> Result<Option<MaterializedFile>> {
+    let _span = ctx
+        .eval_args
+        .metrics
+        .spans()
+        .target_span("import_favicon", args.target.qualified_name());
+    let ImportFaviconArgs {
+        node,
+        target,
+        profile,
+    } = args;
+    let node_name = target.figma_name();
+    let variant_name = target.id.clone().unwrap_or_default();
+
+    debug!(target: "Import", "favicon: {}", target.attrs.label.name);
+    ensure_is_vector_node(node, node_name, &target.attrs.label, true);
+    let svg = get_remote_image(
+        ctx,
+        GetRemoteImageArgs {
+            label: &target.attrs.label,
+            remote: &target.attrs.remote,
+            use_absolute_bounds: target.attrs.use_absolute_bounds,
+            node,
+            format: "svg",
+            scale: 1.0,
+            variant_name: &variant_name,
+        },
+    )?;
+    if ctx.eval_args.fetch {
+        return Ok(None);
+    }
+
+    // every size is rendered from the vector source, downscaling the largest
+    // image makes small favicons blurry
+    let pngs = profile
+        .sizes
+        .par_iter()
+        .map(|size| {
+            let png = render_favicon(
+                ctx,
+                RenderFaviconArgs {
+                    label: &target.attrs.label,
+                    variant_name: &variant_name,
+                    svg: &svg,
+                    size: **size,
+                },
+            )?;
+            Ok((**size, png))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let ico = encode_ico(
+        &pngs
+            .iter()
+            .map(|(size, png)| (*size, png.as_slice()))
+            .collect::<Vec<_>>(),
+    );
+
+    let variant = target
+        .id
+        .as_ref()
+        .map(|it| format!(" ({it})"))
+        .unwrap_or_default();
+    let label = target.attrs.label.fitted(50);
+    let output_dir = target.attrs.package_dir.join(&profile.output_dir);
+    let mut file = materialize(
+        ctx,
+        MaterializeArgs {
+            output_dir: &output_dir,
+            file_name: target.output_name(),
+            file_extension: "ico",
+            bytes: &ico,
+            post_process: profile.post_process.as_ref(),
+            cause: &target.qualified_name(),
+            provenance: None,
+        },
+        || info!(target: "Writing", "`{label}`{variant} to file"),
+    )?;
+
+    // PNG images and the manifest are reported as a part of the ICO file
+    let mut extra_files = Vec::with_capacity(pngs.len() + 1);
+    for (size, png) in &pngs {
+        extra_files.push((
+            FaviconProfile::png_file_name(target.output_name(), *size),
+            "png",
+            png.clone(),
+        ));
+    }
+    if profile.webmanifest {
+        let manifest = webmanifest(target.output_name(), &pngs);
+        extra_files.push((target.output_name().to_owned(), "webmanifest", manifest));
+    }
+    for (file_name, file_extension, bytes) in &extra_files {
+        let extra = materialize(
+            ctx,
+            MaterializeArgs {
+                output_dir: &output_dir,
+                file_name,
+                file_extension,
+                bytes,
+                post_process: None,
+                cause: &target.qualified_name(),
+                provenance: None,
+            },
+            || info!(target: "Writing", "`{label}`{variant} {file_name}.{file_extension} to file"),
+        )?;
+        file.merge(extra);
+    }
+
+    Ok(Some(file))
+}
+
+/// Web app manifest with the `icons` of the PNG images, to be merged into `site.webmanifest`
+fn webmanifest(output_name: &str, pngs: &[(u32, Vec<u8>)]) -> Vec<u8> {
+    let icons = pngs
+        .iter()
+        .map(|(size, _)| {
+            serde_json::json!({
+                "src": format!("{}.png", FaviconProfile::png_file_name(output_name, *size)),
+                "sizes": format!("{size}x{size}"),
+                "type": "image/png",
+            })
+        })
+        .collect::<Vec<_>>();
+    let manifest = serde_json::json!({ "icons": icons });
+    let mut bytes = serde_json::to_vec_pretty(&manifest).expect("valid json");
+    bytes.push(b'\n');
+    bytes
+}
+
+pub struct ImportFaviconArgs<'a> {
+    node: &'a NodeMetadata,
+    target: Target<'a>,
+    profile: &'a FaviconProfile,
+}
+
+impl<'a> ImportFaviconArgs<'a> {
+    pub fn new(node: &'a NodeMetadata, target: Target<'a>, profile: &'a FaviconProfile) -> Self {
+        Self {
+            node,
+            target,
+            profile,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn webmanifest__two_sizes__EXPECT__icons_with_png_file_names() {
+        // Given
+        let pngs = vec![(32, Vec::new()), (256, Vec::new())];
+
+        // When
+        let manifest = webmanifest("favicon", &pngs);
+
+        // Then
+        let manifest: serde_json::Value = serde_json::from_slice(&manifest).unwrap();
+        let expected = serde_json::json!({
+            "icons": [
+                { "src": "favicon-32x32.png", "sizes": "32x32", "type": "image/png" },
+                { "src": "favicon-256x256.png", "sizes": "256x256", "type": "image/png" },
+            ]
+        });
+        assert_eq!(expected, manifest);
+    }
+}
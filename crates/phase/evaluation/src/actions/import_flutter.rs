@@ -0,0 +1,144 @@
+use crate::{
+    EvalContext, MaterializedFile, Result, Target,
+    actions::{
+        render_svg_to_png::{RenderSvgToPngArgs, render_svg_to_png},
+        validation::{RasterFormat, ensure_is_vector_node, ensure_valid_raster},
+    },
+    figma::NodeMetadata,
+};
+use log::{debug, info};
+use phase_loading::FlutterProfile;
+
+use super::{
+    GetRemoteImageArgs, OptimizePngArgs, get_remote_image,
+    materialize::{MaterializeDensitiesArgs, materialize_densities},
+    optimize_png,
+};
+
+pub fn import_flutter(
+    ctx: &EvalContext,
+    args: ImportFlutterArgs,
+) -> Result<Option<MaterializedFile>> {
+    let _span = ctx
+        .eval_args
+        .metrics
+        .spans()
+        .target_span("import_flutter", args.target.qualified_name());
+    let ImportFlutterArgs {
+        node,
+        target,
+        profile,
+    } = args;
+    let node_name = target.figma_name();
+
+    debug!(target: "Import", "flutter: {}", target.attrs.label.name);
+    let mut images = Vec::with_capacity(profile.scales.len());
+    if profile.legacy_loader {
+        for scale in &profile.scales {
+            let png = get_remote_image(
+                ctx,
+                GetRemoteImageArgs {
+                    label: &target.attrs.label,
+                    remote: &target.attrs.remote,
+                    use_absolute_bounds: target.attrs.use_absolute_bounds,
+                    node,
+                    format: "png",
+                    scale: **scale,
+                    variant_name: &FlutterProfile::density_dir_name(**scale),
+                },
+            )?;
+            images.push((**scale, png));
+        }
+        if ctx.eval_args.fetch {
+            return Ok(None);
+        }
+    } else {
+        ensure_is_vector_node(&node, node_name, &target.attrs.label, true);
+        let svg = get_remote_image(
+            ctx,
+            GetRemoteImageArgs {
+                label: &target.attrs.label,
+                remote: &target.attrs.remote,
+                use_absolute_bounds: target.attrs.use_absolute_bounds,
+                node,
+                format: "svg",
+                scale: 1.0,       // always the same yes
+                variant_name: "", // no variant yes
+            },
+        )?;
+        if ctx.eval_args.fetch {
+            return Ok(None);
+        }
+        for scale in &profile.scales {
+            let png = render_svg_to_png(
+                ctx,
+                RenderSvgToPngArgs {
+                    label: &target.attrs.label,
+                    variant_name: &FlutterProfile::density_dir_name(**scale),
+                    svg: &svg,
+                    zoom: if **scale != 1.0 { Some(**scale) } else { None },
+                },
+            )?;
+            images.push((**scale, png));
+        }
+    }
+
+    if profile.optimize {
+        for (scale, png) in images.iter_mut() {
+            *png = optimize_png(
+                ctx,
+                OptimizePngArgs {
+                    label: &target.attrs.label,
+                    variant_name: &FlutterProfile::density_dir_name(*scale),
+                    bytes: png,
+                    level: *profile.optimization_level,
+                },
+            )?;
+        }
+    }
+
+    if ctx.eval_args.validate_images {
+        for (scale, png) in &images {
+            ensure_valid_raster(
+                png,
+                RasterFormat::Png,
+                node.export_size(target.attrs.use_absolute_bounds),
+                *scale,
+                &target.attrs.label,
+                &FlutterProfile::density_dir_name(*scale),
+            )?;
+        }
+    }
+
+    let assets_dir = target.attrs.package_dir.join(&profile.assets_dir);
+    let label = target.attrs.label.fitted(50);
+    let file = materialize_densities(
+        ctx,
+        MaterializeDensitiesArgs {
+            assets_dir: &assets_dir,
+            file_name: target.output_name(),
+            file_extension: "png",
+            images: &images,
+            post_process: profile.post_process.as_ref(),
+            cause: &target.qualified_name(),
+        },
+        |dir| info!(target: "Writing", "`{label}` ({dir}) to file"),
+    )?;
+    Ok(Some(file))
+}
+
+pub struct ImportFlutterArgs<'a> {
+    node: &'a NodeMetadata,
+    target: Target<'a>,
+    profile: &'a FlutterProfile,
+}
+
+impl<'a> ImportFlutterArgs<'a> {
+    pub fn new(node: &'a NodeMetadata, target: Target<'a>, profile: &'a FlutterProfile) -> Self {
+        Self {
+            node,
+            target,
+            profile,
+        }
+    }
+}
@@ -1,12 +1,19 @@
 use super::{
-    GetRemoteImageArgs, get_remote_image,
+    ConvertSvgToPdfArgs, GetRemoteImageArgs, RenderSvgToPngArgs, convert_svg_to_pdf,
+    ensure_vector_pdf, get_remote_image,
     materialize::{MaterializeArgs, materialize},
+    render_svg_to_png,
 };
-use crate::{EvalContext, Result, Target, figma::NodeMetadata};
+use crate::{EvalContext, MaterializedFile, Result, Target, figma::NodeMetadata};
 use log::{debug, info};
 use phase_loading::PdfProfile;
 
-pub fn import_pdf(ctx: &EvalContext, args: ImportPdfArgs) -> Result<()> {
+pub fn import_pdf(ctx: &EvalContext, args: ImportPdfArgs) -> Result<Option<MaterializedFile>> {
+    let _span = ctx
+        .eval_args
+        .metrics
+        .spans()
+        .target_span("import_pdf", args.target.qualified_name());
     let ImportPdfArgs {
         node,
         target,
@@ -15,20 +22,86 @@ pub fn import_pdf(ctx: &EvalContext, args: ImportPdfArgs) -> Result<()> {
     let variant_name = target.id.clone().unwrap_or_default();
 
     debug!(target: "Import", "pdf: {}", target.attrs.label.name);
-    let pdf = &get_remote_image(
-        ctx,
-        GetRemoteImageArgs {
-            label: &target.attrs.label,
-            remote: &target.attrs.remote,
-            node,
-            format: "pdf",
-            scale: 1.0,
-            variant_name: &variant_name,
-        },
-    )?;
-    if ctx.eval_args.fetch {
-        return Ok(());
-    }
+    let (pdf, previews) = if profile.local_renderer {
+        // a single SVG export is enough both for the document and its previews
+        let svg = get_remote_image(
+            ctx,
+            GetRemoteImageArgs {
+                label: &target.attrs.label,
+                remote: &target.attrs.remote,
+                use_absolute_bounds: target.attrs.use_absolute_bounds,
+                node,
+                format: "svg",
+                scale: 1.0,
+                variant_name: &variant_name,
+            },
+        )?;
+        if ctx.eval_args.fetch {
+            return Ok(None);
+        }
+        let pdf = convert_svg_to_pdf(
+            ctx,
+            ConvertSvgToPdfArgs {
+                label: &target.attrs.label,
+                variant_name: &variant_name,
+                svg: &svg,
+            },
+        )?;
+        let previews = profile
+            .preview_scales
+            .iter()
+            .map(|scale| {
+                let png = render_svg_to_png(
+                    ctx,
+                    RenderSvgToPngArgs {
+                        label: &target.attrs.label,
+                        variant_name: &variant_name,
+                        svg: &svg,
+                        zoom: Some(**scale),
+                    },
+                )?;
+                Ok((**scale, png))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        (pdf, previews)
+    } else {
+        let pdf = get_remote_image(
+            ctx,
+            GetRemoteImageArgs {
+                label: &target.attrs.label,
+                remote: &target.attrs.remote,
+                use_absolute_bounds: target.attrs.use_absolute_bounds,
+                node,
+                format: "pdf",
+                scale: 1.0,
+                variant_name: &variant_name,
+            },
+        )?;
+        let previews = profile
+            .preview_scales
+            .iter()
+            .map(|scale| {
+                let png = get_remote_image(
+                    ctx,
+                    GetRemoteImageArgs {
+                        label: &target.attrs.label,
+                        remote: &target.attrs.remote,
+                        use_absolute_bounds: target.attrs.use_absolute_bounds,
+                        node,
+                        format: "png",
+                        scale: **scale,
+                        variant_name: &variant_name,
+                    },
+                )?;
+                Ok((**scale, png))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if ctx.eval_args.fetch {
+            return Ok(None);
+        }
+        (pdf, previews)
+    };
+    ensure_vector_pdf(&pdf, node, profile.allow_raster, &target.attrs.label)?;
 
     let variant = target
         .id
@@ -36,18 +109,42 @@ pub fn import_pdf(ctx: &EvalContext, args: ImportPdfArgs) -> Result<()> {
         .map(|it| format!(" ({it})"))
         .unwrap_or_default();
     let label = target.attrs.label.fitted(50);
-    materialize(
+    let output_dir = target.attrs.package_dir.join(&profile.output_dir);
+    let mut file = materialize(
         ctx,
         MaterializeArgs {
-            output_dir: &target.attrs.package_dir.join(&profile.output_dir),
+            output_dir: &output_dir,
             file_name: target.output_name(),
             file_extension: "pdf",
-            bytes: pdf,
+            bytes: &pdf,
+            post_process: profile.post_process.as_ref(),
+            cause: &target.qualified_name(),
+            provenance: None,
         },
         || info!(target: "Writing", "`{label}`{variant} to file"),
     )?;
 
-    Ok(())
+    // previews are reported as a part of the PDF file
+    for (scale, png) in &previews {
+        let file_name = PdfProfile::preview_file_name(target.output_name(), *scale);
+        let preview = materialize(
+            ctx,
+            MaterializeArgs {
+                output_dir: &output_dir,
+                file_name: &file_name,
+                file_extension: "png",
+                bytes: png,
+                // previews are not the resource itself
+                post_process: None,
+                cause: &target.qualified_name(),
+                provenance: None,
+            },
+            || info!(target: "Writing", "`{label}`{variant} preview @{scale}x to file"),
+        )?;
+        file.merge(preview);
+    }
+
+    Ok(Some(file))
 }
 
 pub struct ImportPdfArgs<'a> {
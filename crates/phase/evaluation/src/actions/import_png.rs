@@ -1,8 +1,8 @@
 use crate::{
-    EvalContext, Result, Target,
+    EvalContext, MaterializedFile, Provenance, Result, Target,
     actions::{
         render_svg_to_png::{RenderSvgToPngArgs, render_svg_to_png},
-        validation::ensure_is_vector_node,
+        validation::{RasterFormat, ensure_is_vector_node, ensure_valid_raster},
     },
     figma::NodeMetadata,
 };
@@ -10,11 +10,17 @@ use log::{debug, info};
 use phase_loading::PngProfile;
 
 use super::{
-    GetRemoteImageArgs, get_remote_image,
+    GetRemoteImageArgs, OptimizePngArgs, get_remote_image,
     materialize::{MaterializeArgs, materialize},
+    optimize_png,
 };
 
-pub fn import_png(ctx: &EvalContext, args: ImportPngArgs) -> Result<()> {
+pub fn import_png(ctx: &EvalContext, args: ImportPngArgs) -> Result<Option<MaterializedFile>> {
+    let _span = ctx
+        .eval_args
+        .metrics
+        .spans()
+        .target_span("import_png", args.target.qualified_name());
     let ImportPngArgs {
         node,
         target,
@@ -31,6 +37,7 @@ pub fn import_png(ctx: &EvalContext, args: ImportPngArgs) -> Result<()> {
             GetRemoteImageArgs {
                 label: &target.attrs.label,
                 remote: &target.attrs.remote,
+                use_absolute_bounds: target.attrs.use_absolute_bounds,
                 node,
                 format: "png",
                 scale,
@@ -38,7 +45,7 @@ pub fn import_png(ctx: &EvalContext, args: ImportPngArgs) -> Result<()> {
             },
         )?;
         if ctx.eval_args.fetch {
-            return Ok(());
+            return Ok(None);
         }
         png
     } else {
@@ -48,6 +55,7 @@ pub fn import_png(ctx: &EvalContext, args: ImportPngArgs) -> Result<()> {
             GetRemoteImageArgs {
                 label: &target.attrs.label,
                 remote: &target.attrs.remote,
+                use_absolute_bounds: target.attrs.use_absolute_bounds,
                 node: &node,
                 format: "svg",
                 scale: 1.0,       // always the same yes
@@ -55,7 +63,7 @@ pub fn import_png(ctx: &EvalContext, args: ImportPngArgs) -> Result<()> {
             },
         )?;
         if ctx.eval_args.fetch {
-            return Ok(());
+            return Ok(None);
         }
         render_svg_to_png(
             ctx,
@@ -68,24 +76,54 @@ pub fn import_png(ctx: &EvalContext, args: ImportPngArgs) -> Result<()> {
         )?
     };
 
+    let png = if profile.optimize {
+        optimize_png(
+            ctx,
+            OptimizePngArgs {
+                label: &target.attrs.label,
+                variant_name: &variant_name,
+                bytes: &png,
+                level: *profile.optimization_level,
+            },
+        )?
+    } else {
+        png
+    };
+    if ctx.eval_args.validate_images {
+        ensure_valid_raster(
+            &png,
+            RasterFormat::Png,
+            node.export_size(target.attrs.use_absolute_bounds),
+            scale,
+            &target.attrs.label,
+            &variant_name,
+        )?;
+    }
+
     let variant = target
         .id
         .as_ref()
         .map(|it| format!(" ({it})"))
         .unwrap_or_default();
     let label = target.attrs.label.fitted(50);
-    materialize(
+    let file = materialize(
         ctx,
         MaterializeArgs {
             output_dir: &target.attrs.package_dir.join(&profile.output_dir),
             file_name: &target.output_name(),
             file_extension: "png",
             bytes: &png,
+            post_process: profile.post_process.as_ref(),
+            cause: &target.qualified_name(),
+            provenance: Some(Provenance {
+                label: &target.attrs.label,
+                node: Some(node),
+            }),
         },
         || info!(target: "Writing", "`{label}`{variant} to file"),
     )?;
 
-    Ok(())
+    Ok(Some(file))
 }
 
 pub struct ImportPngArgs<'a> {
@@ -0,0 +1,97 @@
+use super::{
+    GetRemoteImageArgs, get_remote_image,
+    materialize::{MaterializeArgs, materialize},
+};
+use crate::{
+    EvalContext, MaterializedFile, Result, Target,
+    actions::{
+        convert_svg_to_react::{ConvertSvgToReactArgs, convert_svg_to_react},
+        validation::ensure_is_vector_node,
+    },
+    figma::NodeMetadata,
+};
+use log::{debug, info};
+use phase_loading::ReactProfile;
+
+pub fn import_react(ctx: &EvalContext, args: ImportReactArgs) -> Result<Option<MaterializedFile>> {
+    let _span = ctx
+        .eval_args
+        .metrics
+        .spans()
+        .target_span("import_react", args.target.qualified_name());
+    let ImportReactArgs {
+        node,
+        target,
+        profile,
+    } = args;
+    let node_name = target.figma_name();
+    let variant_name = target.id.clone().unwrap_or_default();
+
+    debug!(target: "Import", "react: {}", target.attrs.label.name);
+    ensure_is_vector_node(&node, node_name, &target.attrs.label, false);
+    let svg = get_remote_image(
+        ctx,
+        GetRemoteImageArgs {
+            label: &target.attrs.label,
+            remote: &target.attrs.remote,
+            use_absolute_bounds: target.attrs.use_absolute_bounds,
+            node,
+            format: "svg",
+            scale: 1.0,
+            variant_name: &variant_name,
+        },
+    )?;
+    if ctx.eval_args.fetch {
+        return Ok(None);
+    }
+
+    let component_name = ReactProfile::component_name(target.output_name());
+    let tsx = convert_svg_to_react(
+        ctx,
+        ConvertSvgToReactArgs {
+            label: &target.attrs.label,
+            variant_name: &variant_name,
+            component_name: &component_name,
+            current_color: profile.current_color,
+            svg: &svg,
+        },
+    )?;
+
+    let variant = target
+        .id
+        .as_ref()
+        .map(|it| format!(" ({it})"))
+        .unwrap_or_default();
+    let label = target.attrs.label.fitted(50);
+    let file = materialize(
+        ctx,
+        MaterializeArgs {
+            output_dir: &target.attrs.package_dir.join(&profile.output_dir),
+            file_name: &component_name,
+            file_extension: "tsx",
+            bytes: &tsx,
+            post_process: profile.post_process.as_ref(),
+            cause: &target.qualified_name(),
+            provenance: None,
+        },
+        || info!(target: "Writing", "`{label}`{variant} to file"),
+    )?;
+
+    Ok(Some(file))
+}
+
+pub struct ImportReactArgs<'a> {
+    node: &'a NodeMetadata,
+    target: Target<'a>,
+    profile: &'a ReactProfile,
+}
+
+impl<'a> ImportReactArgs<'a> {
+    pub fn new(node: &'a NodeMetadata, target: Target<'a>, profile: &'a ReactProfile) -> Self {
+        Self {
+            node,
+            target,
+            profile,
+        }
+    }
+}
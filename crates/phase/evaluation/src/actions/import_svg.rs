@@ -1,6 +1,6 @@
 use super::{GetRemoteImageArgs, get_remote_image};
 use crate::{
-    EvalContext, Result, Target,
+    EvalContext, MaterializedFile, Provenance, Result, Target,
     actions::{
         materialize::{MaterializeArgs, materialize},
         validation::ensure_is_vector_node,
@@ -10,7 +10,12 @@ use crate::{
 use log::{debug, info};
 use phase_loading::SvgProfile;
 
-pub fn import_svg(ctx: &EvalContext, args: ImportSvgArgs) -> Result<()> {
+pub fn import_svg(ctx: &EvalContext, args: ImportSvgArgs) -> Result<Option<MaterializedFile>> {
+    let _span = ctx
+        .eval_args
+        .metrics
+        .spans()
+        .target_span("import_svg", args.target.qualified_name());
     let ImportSvgArgs {
         node,
         target,
@@ -26,6 +31,7 @@ pub fn import_svg(ctx: &EvalContext, args: ImportSvgArgs) -> Result<()> {
         GetRemoteImageArgs {
             label: &target.attrs.label,
             remote: &target.attrs.remote,
+            use_absolute_bounds: target.attrs.use_absolute_bounds,
             node,
             format: "svg",
             scale: 1.0,
@@ -33,7 +39,7 @@ pub fn import_svg(ctx: &EvalContext, args: ImportSvgArgs) -> Result<()> {
         },
     )?;
     if ctx.eval_args.fetch {
-        return Ok(());
+        return Ok(None);
     }
 
     let variant = target
@@ -42,18 +48,24 @@ pub fn import_svg(ctx: &EvalContext, args: ImportSvgArgs) -> Result<()> {
         .map(|it| format!(" ({it})"))
         .unwrap_or_default();
     let label = target.attrs.label.fitted(50);
-    materialize(
+    let file = materialize(
         ctx,
         MaterializeArgs {
             output_dir: &target.attrs.package_dir.join(&profile.output_dir),
             file_name: target.output_name(),
             file_extension: "svg",
             bytes: &svg,
+            post_process: profile.post_process.as_ref(),
+            cause: &target.qualified_name(),
+            provenance: Some(Provenance {
+                label: &target.attrs.label,
+                node: Some(node),
+            }),
         },
         || info!(target: "Writing", "`{label}`{variant} to file"),
     )?;
 
-    Ok(())
+    Ok(Some(file))
 }
 
 pub struct ImportSvgArgs<'a> {
@@ -0,0 +1,100 @@
+use super::{GetRemoteImageArgs, RenderSvgToPngArgs, get_remote_image, render_svg_to_png};
+use crate::{
+    Error, EvalContext, MaterializedFile, Result, Target,
+    actions::materialize::{MaterializeArgs, materialize},
+    digest,
+    figma::NodeMetadata,
+};
+use log::{debug, info};
+use resvg::usvg::Tree;
+use std::path::Path;
+
+/// Size of the longest side of a thumbnail in pixels
+const THUMBNAIL_SIZE: f32 = 96.0;
+
+pub fn import_thumbnail(
+    ctx: &EvalContext,
+    args: ImportThumbnailArgs,
+) -> Result<Option<MaterializedFile>> {
+    let _span = ctx
+        .eval_args
+        .metrics
+        .spans()
+        .target_span("import_thumbnail", args.target.qualified_name());
+    let ImportThumbnailArgs {
+        node,
+        target,
+        output_dir,
+    } = args;
+    let variant_name = target.id.clone().unwrap_or_default();
+
+    debug!(target: "Import", "thumbnail: {}", target.attrs.label.name);
+    // svg is the cheapest export and it renders raster fills too
+    let svg = get_remote_image(
+        ctx,
+        GetRemoteImageArgs {
+            label: &target.attrs.label,
+            remote: &target.attrs.remote,
+            use_absolute_bounds: target.attrs.use_absolute_bounds,
+            node,
+            format: "svg",
+            scale: 1.0,
+            variant_name: &variant_name,
+        },
+    )?;
+    if ctx.eval_args.fetch {
+        return Ok(None);
+    }
+
+    let size = Tree::from_data(&svg, &Default::default())
+        .map_err(|e| Error::RenderSvg(format!("invalid svg `{}`: {e}", target.attrs.label)))?
+        .size();
+    let zoom = THUMBNAIL_SIZE / size.width().max(size.height()).max(1.0);
+    let png = render_svg_to_png(
+        ctx,
+        RenderSvgToPngArgs {
+            label: &target.attrs.label,
+            variant_name: &variant_name,
+            svg: &svg,
+            zoom: Some(zoom),
+        },
+    )?;
+
+    let label = target.attrs.label.fitted(50);
+    let file = materialize(
+        ctx,
+        MaterializeArgs {
+            output_dir,
+            file_name: &thumbnail_file_name(&target),
+            file_extension: "png",
+            bytes: &png,
+            post_process: None,
+            cause: &target.qualified_name(),
+            provenance: None,
+        },
+        || info!(target: "Writing", "thumbnail of `{label}`"),
+    )?;
+
+    Ok(Some(file))
+}
+
+/// Name of the thumbnail file without extension, unique for every target
+pub fn thumbnail_file_name(target: &Target) -> String {
+    format!("{:016x}", digest(target.qualified_name().as_bytes()))
+}
+
+pub struct ImportThumbnailArgs<'a> {
+    node: &'a NodeMetadata,
+    target: Target<'a>,
+    output_dir: &'a Path,
+}
+
+impl<'a> ImportThumbnailArgs<'a> {
+    pub fn new(node: &'a NodeMetadata, target: Target<'a>, output_dir: &'a Path) -> Self {
+        Self {
+            node,
+            target,
+            output_dir,
+        }
+    }
+}
@@ -3,18 +3,23 @@ use super::{
     materialize::{MaterializeArgs, materialize},
 };
 use crate::{
-    EvalContext, Result, Target,
+    EvalContext, MaterializedFile, Result, Target,
     actions::{
         convert_png_to_webp::{ConvertPngToWebpArgs, convert_png_to_webp},
         render_svg_to_png::{RenderSvgToPngArgs, render_svg_to_png},
-        validation::ensure_is_vector_node,
+        validation::{RasterFormat, ensure_is_vector_node, ensure_valid_raster},
     },
     figma::NodeMetadata,
 };
 use log::{debug, info};
 use phase_loading::WebpProfile;
 
-pub fn import_webp(ctx: &EvalContext, args: ImportWebpArgs) -> Result<()> {
+pub fn import_webp(ctx: &EvalContext, args: ImportWebpArgs) -> Result<Option<MaterializedFile>> {
+    let _span = ctx
+        .eval_args
+        .metrics
+        .spans()
+        .target_span("import_webp", args.target.qualified_name());
     let ImportWebpArgs {
         node,
         target,
@@ -31,6 +36,7 @@ pub fn import_webp(ctx: &EvalContext, args: ImportWebpArgs) -> Result<()> {
             GetRemoteImageArgs {
                 label: &target.attrs.label,
                 remote: &target.attrs.remote,
+                use_absolute_bounds: target.attrs.use_absolute_bounds,
                 node,
                 format: "png",
                 scale,
@@ -38,7 +44,7 @@ pub fn import_webp(ctx: &EvalContext, args: ImportWebpArgs) -> Result<()> {
             },
         )?;
         if ctx.eval_args.fetch {
-            return Ok(());
+            return Ok(None);
         }
         png
     } else {
@@ -48,6 +54,7 @@ pub fn import_webp(ctx: &EvalContext, args: ImportWebpArgs) -> Result<()> {
             GetRemoteImageArgs {
                 label: &target.attrs.label,
                 remote: &target.attrs.remote,
+                use_absolute_bounds: target.attrs.use_absolute_bounds,
                 node: &node,
                 format: "svg",
                 scale: 1.0,       // always the same yes
@@ -55,7 +62,7 @@ pub fn import_webp(ctx: &EvalContext, args: ImportWebpArgs) -> Result<()> {
             },
         )?;
         if ctx.eval_args.fetch {
-            return Ok(());
+            return Ok(None);
         }
         render_svg_to_png(
             ctx,
@@ -71,11 +78,24 @@ pub fn import_webp(ctx: &EvalContext, args: ImportWebpArgs) -> Result<()> {
         ctx,
         ConvertPngToWebpArgs {
             quality: *args.profile.quality,
+            lossless: args.profile.lossless,
+            alpha_quality: *args.profile.alpha_quality,
+            method: *args.profile.method,
             bytes: &png,
             label: &target.attrs.label,
             variant_name: &variant_name,
         },
     )?;
+    if ctx.eval_args.validate_images {
+        ensure_valid_raster(
+            webp,
+            RasterFormat::Webp,
+            node.export_size(target.attrs.use_absolute_bounds),
+            scale,
+            &target.attrs.label,
+            &variant_name,
+        )?;
+    }
 
     let variant = target
         .id
@@ -83,18 +103,21 @@ pub fn import_webp(ctx: &EvalContext, args: ImportWebpArgs) -> Result<()> {
         .map(|it| format!(" ({it})"))
         .unwrap_or_default();
     let label = target.attrs.label.fitted(50);
-    materialize(
+    let file = materialize(
         ctx,
         MaterializeArgs {
             output_dir: &target.attrs.package_dir.join(&profile.output_dir),
             file_name: target.output_name(),
             file_extension: "webp",
             bytes: webp,
+            post_process: profile.post_process.as_ref(),
+            cause: &target.qualified_name(),
+            provenance: None,
         },
         || info!(target: "Writing", "`{label}`{variant} to file"),
     )?;
 
-    Ok(())
+    Ok(Some(file))
 }
 
 pub struct ImportWebpArgs<'a> {
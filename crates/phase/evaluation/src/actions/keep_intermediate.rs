@@ -0,0 +1,45 @@
+use crate::EvalContext;
+use lib_label::Label;
+use log::{debug, warn};
+
+/// Writes an intermediate artifact of the target to the `--keep-intermediates`
+/// directory, e.g. `{dir}/icons/ic_star/night/figma.svg`. Does nothing if the
+/// directory is not set. Failures are reported but never fail the target.
+pub fn keep_intermediate(ctx: &EvalContext, args: KeepIntermediateArgs) {
+    let Some(dir) = &ctx.eval_args.intermediates_dir else {
+        return;
+    };
+    let package_dir = match args.label.package.external() {
+        // artifacts of external resources don't mix with the ones of the same local package
+        Some(external) => dir.join(format!("@{external}")).join(&*args.label.package),
+        None => dir.join(&*args.label.package),
+    };
+    let mut target_dir = package_dir.join(args.label.name.to_string());
+    if !args.variant_name.is_empty() {
+        target_dir = target_dir.join(args.variant_name);
+    }
+    let path = target_dir.join(args.file_name);
+    debug!(target: "Intermediates", "writing `{}`", path.display());
+    let result =
+        std::fs::create_dir_all(&target_dir).and_then(|_| std::fs::write(&path, args.bytes));
+    if let Err(e) = result {
+        warn!(target: "Intermediates", "unable to write `{}`: {e}", path.display());
+    }
+}
+
+pub struct KeepIntermediateArgs<'a> {
+    pub label: &'a Label,
+    pub variant_name: &'a str,
+    /// Name of the artifact file inside the target directory, e.g. `rendered.png`
+    pub file_name: &'a str,
+    pub bytes: &'a [u8],
+}
+
+/// File name of the artifact with the scale suffix, e.g. `figma@2x.png`
+pub(crate) fn scaled_file_name(stem: &str, scale: f32, extension: &str) -> String {
+    if scale == 1.0 {
+        format!("{stem}.{extension}")
+    } else {
+        format!("{stem}@{scale}x.{extension}")
+    }
+}
@@ -1,7 +1,11 @@
-use crate::{EvalContext, Result, get_file_digest, get_file_fingerprint};
+use crate::{
+    AuditAction, EvalContext, MaterializedFile, Provenance, Result, actions::post_process,
+    get_file_digest, get_file_fingerprint, provenance::stamp,
+};
 use bincode::{Decode, Encode};
 use lib_cache::CacheKey;
 use log::debug;
+use phase_loading::{FlutterProfile, PostProcessCommand};
 use std::path::Path;
 
 const FILE_DIGEST_TAG: u8 = 0x01;
@@ -10,14 +14,29 @@ pub fn materialize(
     ctx: &EvalContext,
     args: MaterializeArgs,
     on_execute: impl FnOnce(),
-) -> Result<()> {
+) -> Result<MaterializedFile> {
+    let _span = ctx.eval_args.metrics.spans().span("materialize");
+    // post-processing would change the file after its digest is stamped
+    let stamped = match (&args.provenance, args.post_process) {
+        (Some(provenance), None) if ctx.stamp_provenance => {
+            stamp(args.bytes, args.file_extension, provenance)
+        }
+        _ => None,
+    };
+    let bytes = stamped.as_deref().unwrap_or(args.bytes);
     // construct unique cache key
     let cache_key = CacheKey::builder()
         .set_tag(FILE_DIGEST_TAG)
-        .write(args.bytes)
+        .write(bytes)
         .write_str(args.file_extension)
         .write_str(args.file_name)
         .write(args.output_dir.to_string_lossy().as_bytes())
+        .write_str(
+            &args
+                .post_process
+                .map(|it| it.to_string())
+                .unwrap_or_default(),
+        )
         .build();
 
     let output_file = args
@@ -26,38 +45,124 @@ pub fn materialize(
         .with_extension(args.file_extension);
 
     // check if file already materialized
-    if output_file.exists() {
+    let exists = output_file.exists();
+    if exists {
         let cached_file_metadata = ctx.cache.get::<FileMetadata>(&cache_key)?;
 
         // firstly check fingerprint
         let actual_file_fingerprint = get_file_fingerprint(&output_file)?;
         match (&cached_file_metadata, actual_file_fingerprint) {
-            (Some(cached), actual) if cached.fingerprint == actual => return Ok(()),
+            (Some(cached), actual) if cached.fingerprint == actual => {
+                return Ok(MaterializedFile::cached(output_file, bytes));
+            }
             _ => (),
         }
 
         // next check digest
         let actual_file_digest = get_file_digest(&output_file)?;
         match (&cached_file_metadata, actual_file_digest) {
-            (Some(cached), actual) if cached.digest == actual => return Ok(()),
+            (Some(cached), actual) if cached.digest == actual => {
+                return Ok(MaterializedFile::cached(output_file, bytes));
+            }
             _ => (),
         }
     }
 
+    // never start writing new files after cancellation, but let the started ones finish
+    ctx.eval_args.cancellation.check()?;
     on_execute();
     debug!(target: "Materialize", "{}", output_file.display());
-    std::fs::create_dir_all(args.output_dir)?;
-    std::fs::write(&output_file, args.bytes)?;
-
-    // remember file digest
-    ctx.cache.put::<FileMetadata>(
-        &cache_key,
-        &FileMetadata {
-            fingerprint: get_file_fingerprint(&output_file)?,
-            digest: get_file_digest(&output_file)?,
+    let cache = ctx.cache.clone();
+    let audit = ctx.audit.clone();
+    let cause = args.cause.to_owned();
+    let post_process_command = args.post_process.cloned();
+    // in check mode the file is written elsewhere, so it says nothing about the workspace one
+    let remember_file = !ctx.eval_args.check;
+    ctx.writer.write(
+        args.cause,
+        output_file.clone(),
+        bytes,
+        move |output_file| match &post_process_command {
+            Some(command) => post_process(command, output_file),
+            None => Ok(()),
+        },
+        // the post-processed file, linked to the content store, is the one to be
+        // checked next time
+        move |output_file| {
+            let action = match exists {
+                true => AuditAction::Overwrite,
+                false => AuditAction::Create,
+            };
+            audit.record(action, output_file, &cause);
+            if !remember_file {
+                return Ok(());
+            }
+            // remember file digest
+            cache.put::<FileMetadata>(
+                &cache_key,
+                &FileMetadata {
+                    fingerprint: get_file_fingerprint(output_file)?,
+                    digest: get_file_digest(output_file)?,
+                },
+            )?;
+            Ok(())
         },
     )?;
-    Ok(())
+    Ok(MaterializedFile {
+        path: output_file,
+        bytes: bytes.len(),
+        written: true,
+        extra_paths: Vec::new(),
+    })
+}
+
+/// Writes every image of a multi-density asset into Flutter's resolution-aware
+/// layout: the `1.0x` image goes to `assets_dir` itself, the rest to `assets_dir/<N>x`.
+///
+/// Returns the main `1.0x` file (or the first one, if there is no `1.0x` image)
+/// with the total size of all written images.
+pub fn materialize_densities(
+    ctx: &EvalContext,
+    args: MaterializeDensitiesArgs,
+    on_execute: impl Fn(&str),
+) -> Result<MaterializedFile> {
+    let mut files = Vec::with_capacity(args.images.len());
+    for (scale, bytes) in args.images {
+        let dir_name = FlutterProfile::density_dir_name(*scale);
+        let output_dir = if *scale == 1.0 {
+            args.assets_dir.to_path_buf()
+        } else {
+            args.assets_dir.join(&dir_name)
+        };
+        let file = materialize(
+            ctx,
+            MaterializeArgs {
+                output_dir: &output_dir,
+                file_name: args.file_name,
+                file_extension: args.file_extension,
+                bytes,
+                post_process: args.post_process,
+                cause: args.cause,
+                provenance: None,
+            },
+            || on_execute(&dir_name),
+        )?;
+        files.push((*scale, file));
+    }
+    let main_idx = files
+        .iter()
+        .position(|(scale, _)| *scale == 1.0)
+        .unwrap_or_default();
+    let mut files = files.into_iter().map(|(_, file)| file).collect::<Vec<_>>();
+    assert!(
+        !files.is_empty(),
+        "validated on loading phase: scales cannot be empty"
+    );
+    let mut main_file = files.remove(main_idx);
+    for file in files {
+        main_file.merge(file);
+    }
+    Ok(main_file)
 }
 
 #[derive(Encode, Decode)]
@@ -71,4 +176,20 @@ pub struct MaterializeArgs<'a> {
     pub file_name: &'a str,
     pub file_extension: &'a str,
     pub bytes: &'a [u8],
+    /// Command run on the file after it is written
+    pub post_process: Option<&'a PostProcessCommand>,
+    /// What the file is written for, usually the qualified name of the target
+    pub cause: &'a str,
+    /// Stamped into the file if the workspace asks for it and the format allows
+    pub provenance: Option<Provenance<'a>>,
+}
+
+pub struct MaterializeDensitiesArgs<'a> {
+    pub assets_dir: &'a Path,
+    pub file_name: &'a str,
+    pub file_extension: &'a str,
+    /// Scale factor => image bytes
+    pub images: &'a [(f32, Vec<u8>)],
+    pub post_process: Option<&'a PostProcessCommand>,
+    pub cause: &'a str,
 }
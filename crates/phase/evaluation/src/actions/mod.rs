@@ -3,8 +3,18 @@ mod convert_png_to_webp;
 pub use convert_png_to_webp::*;
 mod convert_svg_to_compose;
 pub use convert_svg_to_compose::*;
+mod convert_svg_to_pdf;
+pub use convert_svg_to_pdf::*;
+mod convert_svg_to_react;
+pub use convert_svg_to_react::*;
 mod convert_svg_to_vector_drawable;
 pub use convert_svg_to_vector_drawable::*;
+mod encode_ico;
+pub use encode_ico::*;
+mod optimize_png;
+pub use optimize_png::*;
+mod render_favicon;
+pub use render_favicon::*;
 mod render_svg_to_png;
 pub use render_svg_to_png::*;
 // endregion: transform actions
@@ -14,8 +24,14 @@ mod download_image;
 pub use download_image::*;
 mod export_image;
 pub use export_image::*;
+mod generate_dart_assets_class;
+pub use generate_dart_assets_class::*;
+mod keep_intermediate;
+pub use keep_intermediate::*;
 mod materialize;
 pub use materialize::*;
+mod post_process;
+pub use post_process::*;
 // endregion: io actions
 
 // region: root actions
@@ -25,12 +41,20 @@ mod import_android_webp;
 pub use import_android_webp::*;
 mod import_compose;
 pub use import_compose::*;
+mod import_favicon;
+pub use import_favicon::*;
+mod import_flutter;
+pub use import_flutter::*;
 mod import_pdf;
 pub use import_pdf::*;
 mod import_png;
 pub use import_png::*;
+mod import_react;
+pub use import_react::*;
 mod import_svg;
 pub use import_svg::*;
+mod import_thumbnail;
+pub use import_thumbnail::*;
 mod import_webp;
 pub use import_webp::*;
 // endregion: root action
@@ -40,4 +64,6 @@ mod validation;
 pub use validation::*;
 mod get_remote_image;
 pub use get_remote_image::*;
+mod transform_key;
+pub use transform_key::*;
 // endregion: utils
@@ -0,0 +1,59 @@
+use crate::{Error, EvalContext, Result, actions::transform_cache_key};
+use lib_label::Label;
+use log::{debug, info};
+
+const OPTIMIZE_PNG_TAG: u8 = 0x0B;
+
+/// Losslessly recompresses the PNG. The original is returned if it is already smaller.
+pub fn optimize_png(ctx: &EvalContext, args: OptimizePngArgs) -> Result<Vec<u8>> {
+    let _span = ctx.eval_args.metrics.spans().span("optimize_png");
+    // construct unique cache key
+    let cache_key = transform_cache_key(OPTIMIZE_PNG_TAG)
+        .write(args.bytes)
+        .write_u64(args.level as u64)
+        .build();
+
+    // return cached value if it exists
+    if let Some(png) = ctx.cache.get_bytes(&cache_key)? {
+        return Ok(png);
+    }
+
+    // otherwise, do transform
+    info!(
+        target: "Optimizing", "PNG: `{label}`{variant}",
+        label = args.label.fitted(50),
+        variant = if args.variant_name.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", args.variant_name)
+        }
+    );
+    let options = oxipng::Options::from_preset(args.level);
+    let optimized = oxipng::optimize_from_memory(args.bytes, &options).map_err(|e| {
+        Error::ConversionError(format!(
+            "unable to optimize png `{}` {}: {e}",
+            args.label, args.variant_name
+        ))
+    })?;
+    let png = if optimized.len() < args.bytes.len() {
+        debug!(
+            target: "Optimizing",
+            "`{}`: {} -> {} bytes", args.label, args.bytes.len(), optimized.len()
+        );
+        optimized
+    } else {
+        args.bytes.to_vec()
+    };
+
+    // remember result to cache
+    ctx.cache.put_bytes(&cache_key, &png)?;
+    Ok(png)
+}
+
+pub struct OptimizePngArgs<'a> {
+    pub label: &'a Label,
+    pub variant_name: &'a str,
+    pub bytes: &'a [u8],
+    /// oxipng preset, from 0 (fastest) to 6 (smallest)
+    pub level: u8,
+}
@@ -0,0 +1,152 @@
+use crate::{Error, Result};
+use log::debug;
+use phase_loading::PostProcessCommand;
+use std::{
+    io::Read,
+    path::Path,
+    process::{Child, Command, Stdio},
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+/// Maximum time the post-processing command may take per file
+const POST_PROCESS_TIMEOUT: Duration = Duration::from_secs(60);
+/// Interval of checking whether the command has exited
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+/// Maximum time to wait for the rest of stderr after the command has exited
+const STDERR_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Runs the external program on the written file, e.g. `svgo` or `pngquant`.
+/// The program may modify the file in place. The command fails if the program
+/// exits with a non-zero code or does not exit in time, its stderr is reported then.
+pub fn post_process(command: &PostProcessCommand, output: &Path) -> Result<()> {
+    debug!(target: "Post-processing", "`{}`: {command}", output.display());
+    let mut child = Command::new(command.program())
+        .args(command.args(output))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| {
+            Error::PostProcess(format!(
+                "unable to run `{}` for `{}`: {e}",
+                command.program(),
+                output.display()
+            ))
+        })?;
+
+    // read stderr concurrently, otherwise the program may block on the full pipe
+    let mut stderr = child.stderr.take().expect("stderr is piped");
+    let (stderr_tx, stderr_rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut buf = String::new();
+        let _ = stderr.read_to_string(&mut buf);
+        let _ = stderr_tx.send(buf);
+    });
+
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) if started.elapsed() <= POST_PROCESS_TIMEOUT => {
+                std::thread::sleep(POLL_INTERVAL)
+            }
+            Ok(None) => {
+                kill_and_wait(&mut child);
+                break None;
+            }
+            Err(e) => {
+                kill_and_wait(&mut child);
+                return Err(e.into());
+            }
+        }
+    };
+    // processes spawned by the program may keep the pipe open long after it exits
+    let stderr = stderr_rx.recv_timeout(STDERR_TIMEOUT).unwrap_or_default();
+    let stderr = stderr.trim();
+
+    let reason = match status {
+        Some(status) if status.success() => return Ok(()),
+        Some(status) => format!("`{command}` failed with {status}"),
+        None => format!(
+            "`{command}` did not finish in {}s",
+            POST_PROCESS_TIMEOUT.as_secs()
+        ),
+    };
+    Err(Error::PostProcess(format!(
+        "{reason} while post-processing `{}`{stderr}",
+        output.display(),
+        stderr = if stderr.is_empty() {
+            String::new()
+        } else {
+            format!(":\n{stderr}")
+        }
+    )))
+}
+
+/// Never leaves the program running, whatever has happened
+fn kill_and_wait(child: &mut Child) {
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+#[cfg(all(test, unix))]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    fn command(args: &[&str]) -> PostProcessCommand {
+        let toml = format!("cmd = {args:?}");
+        let mut value = toml_span::parse(&toml).unwrap();
+        let mut th = toml_span::de_helpers::TableHelper::new(&mut value).unwrap();
+        th.required::<PostProcessCommand>("cmd").unwrap()
+    }
+
+    #[test]
+    fn post_process__successful_command__EXPECT__file_modified() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("ic_star.svg");
+        std::fs::write(&output, "<svg/>").unwrap();
+        let command = command(&["sh", "-c", "printf optimized > \"$0\"", "{output}"]);
+
+        // When
+        post_process(&command, &output).unwrap();
+
+        // Then
+        assert_eq!("optimized", std::fs::read_to_string(&output).unwrap());
+    }
+
+    #[test]
+    fn post_process__failing_command__EXPECT__error_with_stderr() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("ic_star.svg");
+        let command = command(&["sh", "-c", "echo 'invalid svg' >&2; exit 3"]);
+
+        // When
+        let result = post_process(&command, &output);
+
+        // Then
+        match result {
+            Err(Error::PostProcess(message)) => assert!(message.contains("invalid svg")),
+            _ => panic!("expected post-processing error"),
+        }
+    }
+
+    #[test]
+    fn post_process__background_process_holds_stderr__EXPECT__not_blocked_by_it() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("ic_star.svg");
+        let command = command(&["sh", "-c", "sleep 30 & exit 0"]);
+
+        // When
+        let started = Instant::now();
+        let result = post_process(&command, &output);
+
+        // Then
+        assert!(result.is_ok());
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{
+    Error, EvalContext, Result,
+    actions::{KeepIntermediateArgs, keep_intermediate, transform_cache_key},
+};
+use lib_label::Label;
+use log::info;
+use resvg::usvg::{Transform, Tree};
+
+const RENDER_FAVICON_TAG: u8 = 0x0C;
+
+/// Renders the SVG into a square PNG of the given size. The image is scaled to fit
+/// and centered, so non-square nodes are padded with transparent pixels.
+pub fn render_favicon(ctx: &EvalContext, args: RenderFaviconArgs) -> Result<Vec<u8>> {
+    let _span = ctx.eval_args.metrics.spans().span("render_favicon");
+    // construct unique cache key
+    let cache_key = transform_cache_key(RENDER_FAVICON_TAG)
+        .write(args.svg)
+        .write_u64(args.size as u64)
+        .build();
+
+    // return cached value if it exists
+    if let Some(png) = ctx.cache.get_bytes(&cache_key)? {
+        keep_rendered_favicon(ctx, &args, &png);
+        return Ok(png);
+    }
+
+    // otherwise, do transform
+    info!(
+        target: "Rendering", "{size}px favicon: `{label}`{variant}",
+        size = args.size,
+        label = args.label.fitted(50),
+        variant = if args.variant_name.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", args.variant_name)
+        }
+    );
+    let tree = Tree::from_data(args.svg, &Default::default()).map_err(|e| {
+        Error::RenderSvg(format!(
+            "invalid svg `{}` {}: {e}",
+            args.label, args.variant_name
+        ))
+    })?;
+    let png = render_square(&tree, args.size)
+        .map_err(|e| {
+            Error::RenderSvg(format!(
+                "cannot render favicon `{}` {}: {e}",
+                args.label, args.variant_name
+            ))
+        })?
+        .encode_png()
+        .map_err(|e| {
+            Error::RenderSvg(format!(
+                "cannot encode rendered favicon to png `{}` {}: {e}",
+                args.label, args.variant_name
+            ))
+        })?;
+
+    // remember result to cache
+    ctx.cache.put_bytes(&cache_key, &png)?;
+    keep_rendered_favicon(ctx, &args, &png);
+    Ok(png)
+}
+
+fn keep_rendered_favicon(ctx: &EvalContext, args: &RenderFaviconArgs, png: &[u8]) {
+    keep_intermediate(
+        ctx,
+        KeepIntermediateArgs {
+            label: args.label,
+            variant_name: args.variant_name,
+            file_name: &format!("rendered-{}px.png", args.size),
+            bytes: png,
+        },
+    );
+}
+
+fn render_square(tree: &Tree, size: u32) -> std::result::Result<resvg::tiny_skia::Pixmap, String> {
+    let mut pixmap = resvg::tiny_skia::Pixmap::new(size, size)
+        .ok_or_else(|| format!("invalid favicon size {size}"))?;
+    let (width, height) = (tree.size().width(), tree.size().height());
+    let side = size as f32;
+    let zoom = side / width.max(height);
+    let ts = Transform::from_row(
+        zoom,
+        0.0,
+        0.0,
+        zoom,
+        (side - width * zoom) / 2.0,
+        (side - height * zoom) / 2.0,
+    );
+    resvg::render(tree, ts, &mut pixmap.as_mut());
+    Ok(pixmap)
+}
+
+pub struct RenderFaviconArgs<'a> {
+    pub label: &'a Label,
+    pub variant_name: &'a str,
+    pub svg: &'a [u8],
+    /// Side of the square image in pixels
+    pub size: u32,
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    /// Wide 24x12 logo filled with black
+    const WIDE_SVG: &str = r#"<svg width="24" height="12" viewBox="0 0 24 12" xmlns="http://www.w3.org/2000/svg">
+<rect x="0" y="0" width="24" height="12" fill="black"/>
+</svg>"#;
+
+    #[test]
+    fn render_square__wide_svg__EXPECT__fitted_and_centered_vertically() {
+        // Given
+        let tree = Tree::from_data(WIDE_SVG.as_bytes(), &Default::default()).unwrap();
+
+        // When
+        let pixmap = render_square(&tree, 16).unwrap();
+
+        // Then
+        assert_eq!((16, 16), (pixmap.width(), pixmap.height()));
+        assert_eq!(0, pixmap.pixel(8, 1).unwrap().alpha(), "padding above");
+        assert_eq!(
+            255,
+            pixmap.pixel(8, 8).unwrap().alpha(),
+            "logo in the middle"
+        );
+        assert_eq!(0, pixmap.pixel(8, 14).unwrap().alpha(), "padding below");
+        assert_eq!(
+            255,
+            pixmap.pixel(0, 8).unwrap().alpha(),
+            "no padding at the sides"
+        );
+    }
+}
@@ -1,5 +1,10 @@
-use crate::{Error, EvalContext, Result};
-use lib_cache::CacheKey;
+use crate::{
+    Error, EvalContext, Result,
+    actions::{
+        KeepIntermediateArgs, keep_intermediate, scaled_file_name, transform_cache_key,
+        validation::{ensure_renders_like_figma, ensure_strokes_visible},
+    },
+};
 use lib_label::Label;
 use log::info;
 use resvg::usvg::Transform;
@@ -8,19 +13,27 @@ use resvg::usvg::Tree;
 const RESVG_TRANSFORM_TAG: u8 = 0x04;
 
 pub fn render_svg_to_png(ctx: &EvalContext, args: RenderSvgToPngArgs) -> Result<Vec<u8>> {
+    let _span = ctx.eval_args.metrics.spans().span("render_svg_to_png");
     // construct unique cache key
-    let cache_key = CacheKey::builder()
-        .set_tag(RESVG_TRANSFORM_TAG)
+    let cache_key = transform_cache_key(RESVG_TRANSFORM_TAG)
         .write(args.svg)
         .write_str(&args.zoom.unwrap_or(1.0).to_string())
         .build();
 
     // return cached value if it exists
     if let Some(png) = ctx.cache.get_bytes(&cache_key)? {
+        keep_rendered_png(ctx, &args, &png);
         return Ok(png);
     }
 
     // otherwise, do transform
+    ensure_renders_like_figma(args.svg, args.label, args.variant_name);
+    ensure_strokes_visible(
+        args.svg,
+        args.zoom.unwrap_or(1.0),
+        args.label,
+        args.variant_name,
+    );
     info!(
         target: "Rendering", "PNG: `{label}`{variant}",
         label = args.label.fitted(50),
@@ -53,9 +66,22 @@ pub fn render_svg_to_png(ctx: &EvalContext, args: RenderSvgToPngArgs) -> Result<
 
     // remember result to cache
     ctx.cache.put_bytes(&cache_key, &png)?;
+    keep_rendered_png(ctx, &args, &png);
     Ok(png.to_vec())
 }
 
+fn keep_rendered_png(ctx: &EvalContext, args: &RenderSvgToPngArgs, png: &[u8]) {
+    keep_intermediate(
+        ctx,
+        KeepIntermediateArgs {
+            label: args.label,
+            variant_name: args.variant_name,
+            file_name: &scaled_file_name("rendered", args.zoom.unwrap_or(1.0), "png"),
+            bytes: png,
+        },
+    );
+}
+
 fn render_svg(
     tree: &Tree,
     zoom: Option<f32>,
@@ -87,3 +113,43 @@ pub struct RenderSvgToPngArgs<'a> {
     pub svg: &'a [u8],
     pub zoom: Option<f32>,
 }
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    /// 24x24 frame with a stroke extending 2px past its edges, the way Figma
+    /// exports it with `use_absolute_bounds`
+    const OVERFLOWING_STROKE_SVG: &str = r#"<svg width="24" height="24" viewBox="0 0 24 24" fill="none" xmlns="http://www.w3.org/2000/svg">
+<rect x="0" y="0" width="24" height="24" stroke="black" stroke-width="4"/>
+</svg>"#;
+
+    #[test]
+    fn render_svg__stroke_outside_of_bounds__EXPECT__same_size_as_figma_png_export() {
+        // Given
+        let tree = Tree::from_data(OVERFLOWING_STROKE_SVG.as_bytes(), &Default::default()).unwrap();
+
+        // When
+        let pixmap = render_svg(&tree, Some(2.0)).unwrap();
+
+        // Then
+        // Figma exports the PNG of these bounds with scale 2 as 48x48
+        assert_eq!((48, 48), (pixmap.width(), pixmap.height()));
+    }
+
+    #[test]
+    fn render_svg__stroke_outside_of_bounds__EXPECT__stroke_clipped_at_bounds() {
+        // Given
+        let tree = Tree::from_data(OVERFLOWING_STROKE_SVG.as_bytes(), &Default::default()).unwrap();
+
+        // When
+        let pixmap = render_svg(&tree, None).unwrap();
+
+        // Then
+        let corner = pixmap.pixel(0, 0).unwrap();
+        let center = pixmap.pixel(12, 12).unwrap();
+        assert_eq!(255, corner.alpha(), "inner half of the stroke is visible");
+        assert_eq!(0, center.alpha(), "content is not shrunk to fit the stroke");
+    }
+}
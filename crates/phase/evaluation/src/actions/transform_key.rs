@@ -0,0 +1,34 @@
+use lib_cache::{CacheKey, CacheKeyBuilder};
+
+/// Starts the cache key of a transformation result.
+///
+/// Transformations change between figx versions, so the results of one
+/// version are never reused by another one, unlike the downloaded images.
+pub fn transform_cache_key(tag: u8) -> CacheKeyBuilder {
+    CacheKey::builder()
+        .set_tag(tag)
+        .write_str(env!("CARGO_PKG_VERSION"))
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn transform_cache_key__same_tag_and_input__EXPECT__differs_from_unversioned_key() {
+        // When
+        let versioned = transform_cache_key(0x03).write_str("<svg/>").build();
+        let unversioned = CacheKey::builder()
+            .set_tag(0x03)
+            .write_str("<svg/>")
+            .build();
+
+        // Then
+        assert_ne!(versioned, unversioned);
+        assert_eq!(
+            versioned,
+            transform_cache_key(0x03).write_str("<svg/>").build()
+        );
+    }
+}
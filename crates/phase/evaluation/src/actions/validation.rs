@@ -1,6 +1,9 @@
-use crate::figma::NodeMetadata;
+use crate::{Error, Result, Target, figma::NodeMetadata};
+use lib_image_vector::ImageVector;
 use lib_label::Label;
-use log::warn;
+use log::{debug, info, warn};
+use phase_loading::{LimitAction, Profile, VectorLimits};
+use resvg::usvg::{Group, Node, Tree};
 
 pub fn ensure_is_vector_node(
     node: &NodeMetadata,
@@ -25,3 +28,475 @@ pub fn ensure_is_vector_node(
         )
     }
 }
+
+/// Fails if the size of the node in Figma differs from the `expect` attribute
+/// of the resource, e.g. when a designer resized the component unexpectedly.
+///
+/// Sizes are compared with a tolerance of half a point, aspect ratios of 1%.
+pub fn ensure_expected_dimensions(node: &NodeMetadata, target: &Target) -> Result<()> {
+    let expect = &target.attrs.expect;
+    if expect.width.is_none() && expect.height.is_none() && expect.aspect_ratio.is_none() {
+        return Ok(());
+    }
+    let Some((width, height)) = node.bounds else {
+        debug!(target: "Validation", "size of the node `{}` is unknown", node.name);
+        return Ok(());
+    };
+    let mut mismatches = Vec::new();
+    for (name, expected, actual) in [
+        ("width", expect.width, width),
+        ("height", expect.height, height),
+    ] {
+        match expected {
+            Some(expected) if (expected - actual).abs() > 0.5 => {
+                mismatches.push(format!("{name} is {actual}, expected {expected}"))
+            }
+            _ => (),
+        }
+    }
+    match expect.aspect_ratio {
+        Some(expected) if height <= 0.0 || ((width / height) / expected - 1.0).abs() > 0.01 => {
+            mismatches.push(format!(
+                "aspect ratio is {width}:{height}, expected {expected}"
+            ))
+        }
+        _ => (),
+    }
+    if mismatches.is_empty() {
+        return Ok(());
+    }
+    Err(Error::UnexpectedDimensions {
+        node_name: node.name.to_owned(),
+        mismatches,
+        file: target.attrs.diag.file.to_path_buf(),
+        span: target.attrs.diag.definition_span.clone(),
+    })
+}
+
+/// Warns if the SVG uses features which the local renderer is likely to draw
+/// differently from the PNG exported by Figma.
+///
+/// The check is a heuristic over the SVG markup, it does not compare rendered images.
+pub fn ensure_renders_like_figma(svg: &[u8], label: &Label, variant_name: &str) {
+    let features = sniff_svg_features(svg);
+    if features.is_empty() {
+        return;
+    }
+    let variant = if variant_name.is_empty() {
+        String::new()
+    } else {
+        format!(" ({variant_name})")
+    };
+    warn!(
+        "Local rendering of resource {label}{variant} may differ from Figma export\n{}\n{}",
+        features
+            .iter()
+            .map(|it| format!("- The SVG contains {it}"))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        "Consider enabling the `legacy_loader = true` option for this resource or for the entire profile."
+    )
+}
+
+/// Warns if some strokes of the SVG are thinner than one device pixel when the image
+/// is drawn at `scale`, such strokes look blurry on screens of low density.
+pub fn ensure_strokes_visible(svg: &[u8], scale: f32, label: &Label, variant_name: &str) {
+    let image_vector = match Tree::from_data(svg, &Default::default())
+        .map_err(|e| e.to_string())
+        .and_then(|tree| ImageVector::try_from(tree).map_err(|e| e.to_string()))
+    {
+        Ok(image_vector) => image_vector,
+        Err(e) => {
+            debug!(target: "Validation", "unable to check strokes of {label}: {e}");
+            return;
+        }
+    };
+    let widths = image_vector.thin_strokes(scale);
+    let Some(thinnest) = widths.iter().copied().reduce(f32::min) else {
+        return;
+    };
+    let variant = if variant_name.is_empty() {
+        String::new()
+    } else {
+        format!(" ({variant_name})")
+    };
+    warn!(
+        "Strokes of resource {label}{variant} may look blurry\n{}\n{}",
+        format!(
+            "- {} strokes are thinner than one pixel at scale {scale}, the thinnest one is {thinnest:.2}px",
+            widths.len(),
+        ),
+        "Consider making the strokes thicker in Figma or importing the resource at a larger scale."
+    )
+}
+
+/// Names of the SVG features rendered differently by resvg and Figma, found in the `svg`
+fn sniff_svg_features(svg: &[u8]) -> Vec<&'static str> {
+    let svg = String::from_utf8_lossy(svg);
+    let has_blend_modes = svg.match_indices("mix-blend-mode").any(|(i, pattern)| {
+        let value = svg[i + pattern.len()..]
+            .trim_start_matches(|c: char| c == ':' || c == '=' || c == '"' || c.is_whitespace());
+        !value.starts_with("normal")
+    });
+    [
+        (svg.contains("<filter"), "filters (shadows or blurs)"),
+        (has_blend_modes, "blend modes"),
+        (svg.contains("<image"), "raster images"),
+    ]
+    .into_iter()
+    .filter_map(|(found, name)| found.then_some(name))
+    .collect()
+}
+
+/// Warns if the Kotlin property of the image cannot be named after the resource as is.
+///
+/// Such names are prefixed with `identifier_prefix` (if they start with a digit)
+/// or escaped with backticks during the code generation.
+pub fn ensure_kotlin_identifier(property_name: &str, prefix: Option<&str>, label: &Label) {
+    if lib_svg2compose::is_kotlin_identifier(property_name) {
+        return;
+    }
+    let fix = match prefix {
+        Some(prefix)
+            if property_name.starts_with(|c: char| c.is_ascii_digit())
+                && lib_svg2compose::is_kotlin_identifier(&format!("{prefix}{property_name}")) =>
+        {
+            format!("- The name will be prefixed: `{prefix}{property_name}`")
+        }
+        _ => format!("- The name will be escaped with backticks: `{property_name}`"),
+    };
+    warn!(
+        "Resource {label} does not produce a valid Kotlin identifier\n{}\n{fix}\n{}",
+        format!(
+            "- `{property_name}` is a keyword, starts with a digit or contains special characters"
+        ),
+        "Consider renaming the resource or setting the `identifier_prefix` option of the profile."
+    )
+}
+
+/// Checks the complexity of the vector image against the limits of the profile.
+///
+/// Returns the raster profile to import the resource with, if the limits
+/// are exceeded and the fallback profile is configured.
+pub fn ensure_vector_complexity<'a>(
+    svg: &[u8],
+    limits: &'a VectorLimits,
+    label: &Label,
+) -> Result<Option<&'a Profile>> {
+    if limits.max_paths.is_none() && limits.max_commands.is_none() {
+        return Ok(None);
+    }
+    let tree = Tree::from_data(svg, &Default::default())
+        .map_err(|e| Error::ConversionError(format!("invalid svg `{label}`: {e}")))?;
+    let (paths, commands) = count_paths_and_commands(tree.root());
+    let exceeded = [
+        ("paths", paths, limits.max_paths),
+        ("commands", commands, limits.max_commands),
+    ]
+    .into_iter()
+    .filter_map(|(name, actual, max)| {
+        max.filter(|max| actual > *max)
+            .map(|max| format!("- The image contains {actual} {name}, the limit is {max}"))
+    })
+    .collect::<Vec<_>>();
+    if exceeded.is_empty() {
+        return Ok(None);
+    }
+
+    let details = exceeded.join("\n");
+    match &limits.on_exceeded {
+        LimitAction::Warn => {
+            warn!("Vector image of resource {label} is too complex\n{details}");
+            Ok(None)
+        }
+        LimitAction::Fail => Err(Error::VectorTooComplex(format!(
+            "vector image of resource `{label}` is too complex\n{details}"
+        ))),
+        LimitAction::Fallback(profile) => {
+            info!(target: "Fallback", "`{}` to raster image", label.fitted(50));
+            Ok(Some(profile.as_ref()))
+        }
+    }
+}
+
+/// Checks that the PDF is a pure vector image: the node has no raster fills,
+/// and the exported document contains no image objects.
+///
+/// Fails if raster images are not allowed, warns if they are not mentioned at all.
+pub fn ensure_vector_pdf(
+    pdf: &[u8],
+    node: &NodeMetadata,
+    allow_raster: Option<bool>,
+    label: &Label,
+) -> Result<()> {
+    if allow_raster == Some(true) {
+        return Ok(());
+    }
+    let reason = if node.uses_raster_paints {
+        format!(
+            "- The `{}` node in Figma contains embedded raster images",
+            node.name
+        )
+    } else if pdf_contains_images(pdf) {
+        "- The exported PDF contains raster images".to_string()
+    } else {
+        return Ok(());
+    };
+    if allow_raster.is_none() {
+        warn!(
+            target: "Validation",
+            "PDF of resource `{label}` is not a vector image, set `allow_raster` to accept or reject it:\n{reason}",
+        );
+        return Ok(());
+    }
+    Err(Error::RasterPdf(format!(
+        "PDF of resource `{label}` is not a vector image\n{reason}"
+    )))
+}
+
+pub enum RasterFormat {
+    Png,
+    Webp,
+}
+
+/// Fails if the raster image cannot be decoded, or if its size differs from
+/// the `export_size` of the node multiplied by `scale` by more than a pixel.
+///
+/// Catches truncated downloads and renderer regressions before the files get
+/// into the app builds.
+pub fn ensure_valid_raster(
+    bytes: &[u8],
+    format: RasterFormat,
+    export_size: Option<(f32, f32)>,
+    scale: f32,
+    label: &Label,
+    variant_name: &str,
+) -> Result<()> {
+    let variant = if variant_name.is_empty() {
+        String::new()
+    } else {
+        format!(" ({variant_name})")
+    };
+    let actual = match format {
+        RasterFormat::Png => image::load_from_memory_with_format(bytes, image::ImageFormat::Png)
+            .map(|it| (it.width(), it.height()))
+            .ok(),
+        RasterFormat::Webp => webp::Decoder::new(bytes)
+            .decode()
+            .map(|it| (it.width(), it.height())),
+    };
+    let Some((width, height)) = actual else {
+        return Err(Error::InvalidImage(format!(
+            "image of resource `{label}`{variant} cannot be decoded"
+        )));
+    };
+    let Some(expected) = export_size.map(|size| expected_pixel_size(size, scale)) else {
+        return Ok(());
+    };
+    if width.abs_diff(expected.0) > 1 || height.abs_diff(expected.1) > 1 {
+        return Err(Error::InvalidImage(format!(
+            "image of resource `{label}`{variant} is {width}x{height}, expected {}x{}",
+            expected.0, expected.1,
+        )));
+    }
+    Ok(())
+}
+
+fn expected_pixel_size((width, height): (f32, f32), scale: f32) -> (u32, u32) {
+    (
+        (width * scale).round().max(1.0) as u32,
+        (height * scale).round().max(1.0) as u32,
+    )
+}
+
+/// Looks for image XObjects, e.g. `/Subtype /Image`, in the uncompressed
+/// object dictionaries of the document
+fn pdf_contains_images(pdf: &[u8]) -> bool {
+    const SUBTYPE: &[u8] = b"/Subtype";
+    pdf.windows(SUBTYPE.len())
+        .enumerate()
+        .filter(|(_, window)| *window == SUBTYPE)
+        .any(|(i, _)| {
+            let rest = &pdf[i + SUBTYPE.len()..];
+            let start = rest
+                .iter()
+                .position(|b| !b.is_ascii_whitespace())
+                .unwrap_or(rest.len());
+            rest[start..].starts_with(b"/Image")
+        })
+}
+
+fn count_paths_and_commands(group: &Group) -> (usize, usize) {
+    group
+        .children()
+        .iter()
+        .fold((0, 0), |(paths, commands), node| match node {
+            Node::Group(group) => {
+                let (p, c) = count_paths_and_commands(group);
+                (paths + p, commands + c)
+            }
+            Node::Path(path) => (paths + 1, commands + path.data().verbs().len()),
+            _ => (paths, commands),
+        })
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn count_paths_and_commands__nested_groups__EXPECT__all_paths_counted() {
+        // Given
+        let svg = r#"
+        <svg xmlns="http://www.w3.org/2000/svg" width="24" height="24">
+            <path d="M0 0 L10 0 L10 10 Z"/>
+            <g opacity="0.5">
+                <path d="M12 12 L20 12 L20 20 Z"/>
+            </g>
+        </svg>
+        "#;
+        let tree = Tree::from_data(svg.as_bytes(), &Default::default()).unwrap();
+
+        // When
+        let (paths, commands) = count_paths_and_commands(tree.root());
+
+        // Then
+        assert_eq!(2, paths);
+        assert_eq!(8, commands);
+    }
+
+    #[test]
+    fn sniff_svg_features__figma_effects__EXPECT__all_features_found() {
+        // Given
+        let svg = r##"
+        <svg xmlns="http://www.w3.org/2000/svg" width="24" height="24">
+            <g filter="url(#filter0_d)" style="mix-blend-mode:multiply">
+                <rect width="24" height="24" fill="url(#pattern0)"/>
+            </g>
+            <defs>
+                <filter id="filter0_d"><feGaussianBlur stdDeviation="2"/></filter>
+                <pattern id="pattern0"><use xlink:href="#image0"/></pattern>
+                <image id="image0" width="24" height="24" xlink:href="data:image/png;base64,"/>
+            </defs>
+        </svg>
+        "##;
+
+        // When
+        let features = sniff_svg_features(svg.as_bytes());
+
+        // Then
+        assert_eq!(
+            vec!["filters (shadows or blurs)", "blend modes", "raster images"],
+            features
+        );
+    }
+
+    #[test]
+    fn sniff_svg_features__plain_paths_and_normal_blend_mode__EXPECT__nothing_found() {
+        // Given
+        let svg = r#"
+        <svg xmlns="http://www.w3.org/2000/svg" width="24" height="24">
+            <g style="mix-blend-mode: normal">
+                <path d="M0 0 L10 0 L10 10 Z"/>
+            </g>
+        </svg>
+        "#;
+
+        // When
+        let features = sniff_svg_features(svg.as_bytes());
+
+        // Then
+        assert!(features.is_empty());
+    }
+
+    #[test]
+    fn ensure_valid_raster__truncated_png__EXPECT__error() {
+        // Given
+        let label = Label::from_package_and_name("icons", "ic_star").unwrap();
+        let png = encode_png(24, 24);
+
+        // When
+        let result = ensure_valid_raster(
+            &png[..png.len() / 2],
+            RasterFormat::Png,
+            None,
+            1.0,
+            &label,
+            "",
+        );
+
+        // Then
+        assert!(matches!(result, Err(Error::InvalidImage(_))));
+    }
+
+    #[test]
+    fn ensure_valid_raster__png_size_vs_node_size__EXPECT__one_pixel_tolerance() {
+        // Given
+        let label = Label::from_package_and_name("icons", "ic_star").unwrap();
+        let png = encode_png(48, 48);
+
+        // When
+        let rounded =
+            ensure_valid_raster(&png, RasterFormat::Png, Some((24.3, 24.0)), 2.0, &label, "");
+        let wrong =
+            ensure_valid_raster(&png, RasterFormat::Png, Some((24.0, 24.0)), 3.0, &label, "");
+
+        // Then
+        assert!(rounded.is_ok());
+        assert!(matches!(wrong, Err(Error::InvalidImage(_))));
+    }
+
+    fn encode_png(width: u32, height: u32) -> Vec<u8> {
+        let mut png = std::io::Cursor::new(Vec::new());
+        image::RgbaImage::new(width, height)
+            .write_to(&mut png, image::ImageFormat::Png)
+            .unwrap();
+        png.into_inner()
+    }
+
+    #[test]
+    fn pdf_contains_images__image_xobject__EXPECT__true() {
+        // Given
+        let pdf = b"%PDF-1.4\n5 0 obj\n<< /Type /XObject /Subtype /Image /Width 24 >>\nstream";
+        let pdf_compact = b"%PDF-1.4\n5 0 obj\n<</Type/XObject/Subtype/Image/Width 24>>\nstream";
+
+        // Then
+        assert!(pdf_contains_images(pdf));
+        assert!(pdf_contains_images(pdf_compact));
+    }
+
+    #[test]
+    fn pdf_contains_images__vector_only__EXPECT__false() {
+        // Given
+        let pdf =
+            b"%PDF-1.4\n5 0 obj\n<< /Type /XObject /Subtype /Form /BBox [0 0 24 24] >>\nstream";
+
+        // Then
+        assert!(!pdf_contains_images(pdf));
+    }
+
+    #[test]
+    fn ensure_vector_pdf__raster_fills__EXPECT__fails_only_if_raster_is_disallowed() {
+        // Given
+        let label = Label::from_package_and_name("icons", "ic_star").unwrap();
+        let node = NodeMetadata {
+            id: "1:2".to_string(),
+            name: "ic_star".to_string(),
+            hash: 0,
+            uses_raster_paints: true,
+            bounds: None,
+            render_bounds: None,
+        };
+
+        // When
+        let unspecified = ensure_vector_pdf(b"%PDF-1.7", &node, None, &label);
+        let allowed = ensure_vector_pdf(b"%PDF-1.7", &node, Some(true), &label);
+        let disallowed = ensure_vector_pdf(b"%PDF-1.7", &node, Some(false), &label);
+
+        // Then
+        assert!(unspecified.is_ok(), "only warned");
+        assert!(allowed.is_ok());
+        assert!(matches!(disallowed, Err(Error::RasterPdf(_))));
+    }
+}
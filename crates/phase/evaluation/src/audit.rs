@@ -0,0 +1,144 @@
+use log::warn;
+use serde::Serialize;
+use std::{
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Append-only log of every file figx creates, overwrites or deletes in the workspace.
+///
+/// Each line is a JSON object with the time of the change in Unix milliseconds,
+/// the id of the run (a single figx invocation), the action, the path relative
+/// to the workspace and the cause of the change, usually the target label.
+/// The log is never truncated by figx, rotate it yourself if needed.
+pub struct AuditLog {
+    file: Mutex<Option<File>>,
+    workspace_dir: PathBuf,
+    run: String,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Create,
+    Overwrite,
+    Delete,
+}
+
+#[derive(Serialize)]
+struct AuditRecord<'a> {
+    time: u128,
+    run: &'a str,
+    action: AuditAction,
+    path: String,
+    cause: &'a str,
+}
+
+impl Default for AuditLog {
+    fn default() -> Self {
+        Self {
+            file: Mutex::new(None),
+            workspace_dir: PathBuf::new(),
+            run: new_run_id(),
+        }
+    }
+}
+
+impl AuditLog {
+    /// Name of the audit log in the `.figx-out` directory
+    pub const FILE_NAME: &str = "audit.jsonl";
+
+    /// Opens the log at `path` for appending. Nothing is recorded if the file
+    /// cannot be opened, the failure is reported as a warning.
+    pub fn open(path: &Path, workspace_dir: &Path) -> Self {
+        let file = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| File::options().create(true).append(true).open(path));
+        let file = match file {
+            Ok(file) => Some(file),
+            Err(e) => {
+                warn!(target: "Audit", "unable to open `{}`: {e}", path.display());
+                None
+            }
+        };
+        Self {
+            file: Mutex::new(file),
+            workspace_dir: workspace_dir.to_path_buf(),
+            run: new_run_id(),
+        }
+    }
+
+    /// Appends the change of the file at `path` caused by `cause`
+    pub fn record(&self, action: AuditAction, path: &Path, cause: &str) {
+        let mut file = self.file.lock().unwrap();
+        let Some(file) = file.as_mut() else {
+            return;
+        };
+        let record = AuditRecord {
+            time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis(),
+            run: &self.run,
+            action,
+            path: path
+                .strip_prefix(&self.workspace_dir)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .replace('\\', "/"),
+            cause,
+        };
+        let mut line = serde_json::to_vec(&record).expect("audit record is serializable");
+        line.push(b'\n');
+        // a single write per line, so concurrent figx processes don't mix records
+        if let Err(e) = file.write_all(&line) {
+            warn!(target: "Audit", "unable to record `{}`: {e}", record.path);
+        }
+    }
+}
+
+fn new_run_id() -> String {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    format!("{millis:x}-{}", std::process::id())
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn AuditLog__records_of_two_runs__EXPECT__appended_with_relative_paths() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".figx-out").join(AuditLog::FILE_NAME);
+        let output = dir.path().join("res").join("ic_star.xml");
+
+        // When
+        let first = AuditLog::open(&path, dir.path());
+        first.record(AuditAction::Create, &output, "//:ic_star");
+        drop(first);
+        let second = AuditLog::open(&path, dir.path());
+        second.record(AuditAction::Delete, &output, "//:ic_star");
+
+        // Then
+        let records = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|it| serde_json::from_str::<serde_json::Value>(it).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(2, records.len());
+        assert_eq!("create", records[0]["action"]);
+        assert_eq!("delete", records[1]["action"]);
+        assert_eq!("res/ic_star.xml", records[0]["path"]);
+        assert_eq!("//:ic_star", records[1]["cause"]);
+        assert!(records[0]["run"].as_str().is_some_and(|it| !it.is_empty()));
+    }
+}
@@ -0,0 +1,77 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+/// Cooperative cancellation flag shared between the evaluation and its initiator.
+///
+/// Targets that have not started yet are skipped after cancellation,
+/// and in-flight targets stop at the nearest action boundary.
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+    /// Number of the running operations which check the token
+    watchers: Arc<AtomicUsize>,
+}
+
+/// Keeps the token watched until dropped, see [`CancellationToken::watch`]
+pub struct WatchGuard(Arc<AtomicUsize>);
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Marks the token as checked by a running operation, until the guard is dropped.
+    /// Nothing would notice the cancellation of an unwatched token, so the initiator
+    /// has to stop the process itself then.
+    pub fn watch(&self) -> WatchGuard {
+        self.watchers.fetch_add(1, Ordering::SeqCst);
+        WatchGuard(self.watchers.clone())
+    }
+
+    pub fn is_watched(&self) -> bool {
+        self.watchers.load(Ordering::SeqCst) > 0
+    }
+
+    /// Returns [`crate::Error::Cancelled`] if cancellation was requested
+    pub fn check(&self) -> crate::Result<()> {
+        if self.is_cancelled() {
+            Err(crate::Error::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Drop for WatchGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn CancellationToken__watch_guard_dropped__EXPECT__not_watched() {
+        // Given
+        let token = CancellationToken::default();
+        let clone = token.clone();
+
+        // When
+        let guard = clone.watch();
+        let watched = token.is_watched();
+        drop(guard);
+
+        // Then
+        assert!(watched);
+        assert!(!token.is_watched());
+    }
+}
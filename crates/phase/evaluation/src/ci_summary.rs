@@ -0,0 +1,143 @@
+use crate::{BuildReport, Result, report::TargetReport, report::TargetStatus};
+use std::{fmt::Write as _, io::Write as _, path::Path};
+
+/// Environment variable with the path of the job summary file on GitHub Actions
+pub const GITHUB_STEP_SUMMARY: &str = "GITHUB_STEP_SUMMARY";
+
+/// Longer lists of failures and changed files are collapsed to keep the summary readable
+const MAX_LISTED_FILES: usize = 100;
+
+/// Appends the markdown summary of the run to the job summary file, if figx runs on
+/// GitHub Actions. Does nothing otherwise.
+pub fn write_github_step_summary(
+    report: &BuildReport,
+    process: &str,
+    duration: &str,
+    workspace_dir: &Path,
+) -> Result<()> {
+    let Some(path) = std::env::var_os(GITHUB_STEP_SUMMARY).filter(|it| !it.is_empty()) else {
+        return Ok(());
+    };
+    let markdown =
+        report.with_entries(|entries| render_summary(entries, process, duration, workspace_dir));
+    // the file is shared by all steps of the job, so never truncate it
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    file.write_all(markdown.as_bytes())?;
+    Ok(())
+}
+
+/// Summary titled after the `process` of the run, e.g. `Fetching`
+fn render_summary(
+    entries: &[TargetReport],
+    process: &str,
+    duration: &str,
+    workspace_dir: &Path,
+) -> String {
+    let count = |status: TargetStatus| entries.iter().filter(|it| it.status == status).count();
+    let mut md = String::new();
+    let _ = writeln!(md, "### figx: {process}\n");
+    let _ = writeln!(md, "| Targets | Exported | Cached | Failed | Duration |");
+    let _ = writeln!(md, "|--------:|---------:|-------:|-------:|---------:|");
+    let _ = writeln!(
+        md,
+        "| {} | {} | {} | {} | {duration} |\n",
+        entries.len(),
+        count(TargetStatus::Exported),
+        count(TargetStatus::Cached),
+        count(TargetStatus::Failed),
+    );
+
+    let failed = entries
+        .iter()
+        .filter(|it| it.status == TargetStatus::Failed)
+        .collect::<Vec<_>>();
+    if !failed.is_empty() {
+        let _ = writeln!(md, "#### Failed targets\n");
+        let _ = writeln!(md, "| Target | Error |");
+        let _ = writeln!(md, "|--------|-------|");
+        for entry in &failed {
+            let _ = writeln!(
+                md,
+                "| `{}` | {} |",
+                target_name(entry),
+                escape_cell(entry.error.as_deref().unwrap_or_default()),
+            );
+        }
+        md.push('\n');
+    }
+
+    let changed = entries
+        .iter()
+        .filter(|it| it.status == TargetStatus::Exported)
+        .filter_map(|it| it.output.as_ref())
+        .map(|it| it.path.strip_prefix(workspace_dir).unwrap_or(&it.path))
+        .collect::<Vec<_>>();
+    if !changed.is_empty() {
+        let _ = writeln!(
+            md,
+            "<details><summary>{} changed files</summary>\n",
+            changed.len()
+        );
+        for path in changed.iter().take(MAX_LISTED_FILES) {
+            let _ = writeln!(md, "- `{}`", path.display());
+        }
+        if changed.len() > MAX_LISTED_FILES {
+            let _ = writeln!(md, "- ...and {} more", changed.len() - MAX_LISTED_FILES);
+        }
+        let _ = writeln!(md, "\n</details>\n");
+    }
+    md
+}
+
+fn target_name(entry: &TargetReport) -> String {
+    match &entry.variant {
+        Some(variant) => format!("{} ({variant})", entry.label),
+        None => entry.label.to_owned(),
+    }
+}
+
+/// Table cells cannot contain line breaks and unescaped pipes
+fn escape_cell(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('|', "\\|")
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use crate::report::OutputReport;
+    use std::path::PathBuf;
+
+    #[test]
+    fn render_summary__mixed_statuses__EXPECT__counts_failures_and_changed_files() {
+        // Given
+        let mut failed = TargetReport::stub("//b:icon", TargetStatus::Failed);
+        failed.error = Some("node not found |\nin remote".to_string());
+        let mut exported = TargetReport::stub("//a:icon", TargetStatus::Exported);
+        exported.output = Some(OutputReport {
+            path: PathBuf::from("/ws/a/icon.svg"),
+            bytes: 42,
+        });
+        let entries = [
+            failed,
+            exported,
+            TargetReport::stub("//a:logo", TargetStatus::Cached),
+        ];
+
+        // When
+        let md = render_summary(&entries, "Checking", "3 sec", Path::new("/ws"));
+
+        // Then
+        assert!(md.starts_with("### figx: Checking\n"));
+        assert!(md.contains("| 3 | 1 | 1 | 1 | 3 sec |"));
+        assert!(md.contains("| `//b:icon` | node not found \\| in remote |"));
+        assert!(md.contains("<details><summary>1 changed files</summary>"));
+        assert!(md.contains("- `a/icon.svg`"));
+    }
+}
@@ -0,0 +1,200 @@
+use crate::{DigestAlgorithm, Result, digest};
+use log::debug;
+use phase_loading::OutputLinkMode;
+use std::{
+    io::ErrorKind,
+    path::{Component, Path, PathBuf},
+};
+
+/// Store of output files addressed by the digest of their content.
+///
+/// Every written output is moved to the store and linked back to its path,
+/// so identical outputs (e.g. variants sharing the same image) take the disk
+/// space only once. Linked files must never be modified in place, so the
+/// writer unlinks them before writing new content, see [`ContentStore::unlink`].
+pub struct ContentStore {
+    dir: PathBuf,
+    mode: OutputLinkMode,
+}
+
+impl ContentStore {
+    pub fn new(dir: PathBuf, mode: OutputLinkMode) -> Self {
+        Self { dir, mode }
+    }
+
+    /// Removes the file at `path` if it exists, so writing it doesn't modify
+    /// the stored object it may be linked to
+    pub fn unlink(&self, path: &Path) -> Result<()> {
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Moves the written file at `path` to the store, or reuses the stored object
+    /// with the same content, and links it back to `path`.
+    ///
+    /// Files which cannot be linked are copied from the store.
+    pub fn absorb(&self, path: &Path) -> Result<()> {
+        let bytes = std::fs::read(path)?;
+        let object = self.object_path(&bytes, path);
+        match std::fs::read(&object) {
+            Ok(stored) if stored == bytes => std::fs::remove_file(path)?,
+            // digest collision, it's safer to keep the file as is
+            Ok(_) => return Ok(()),
+            Err(_) => {
+                if let Some(dir) = object.parent() {
+                    std::fs::create_dir_all(dir)?;
+                }
+                std::fs::rename(path, &object)?;
+            }
+        }
+        if let Err(e) = self.link(&object, path) {
+            debug!(target: "Materialize", "unable to link `{}`, copying it: {e}", path.display());
+            std::fs::copy(&object, path)?;
+        }
+        Ok(())
+    }
+
+    /// `<store>/<first two chars of digest>/<digest>.<extension of the output>`.
+    /// The digest is prefixed with the algorithm id, so objects survive its change.
+    fn object_path(&self, bytes: &[u8], output: &Path) -> PathBuf {
+        let name = format!(
+            "{:02x}{:016x}",
+            DigestAlgorithm::CURRENT.id(),
+            digest(bytes)
+        );
+        let object = self.dir.join(&name[..2]).join(&name);
+        match output.extension() {
+            Some(extension) => object.with_extension(extension),
+            None => object,
+        }
+    }
+
+    fn link(&self, object: &Path, path: &Path) -> std::io::Result<()> {
+        match self.mode {
+            OutputLinkMode::Hardlink => std::fs::hard_link(object, path),
+            OutputLinkMode::Symlink => {
+                // relative links keep working when the workspace is moved
+                let target = path
+                    .parent()
+                    .and_then(|dir| relative_path(dir, object))
+                    .unwrap_or_else(|| object.to_path_buf());
+                symlink(&target, path)
+            }
+        }
+    }
+}
+
+/// Path of `to` relative to the `from` directory, both must be absolute
+fn relative_path(from: &Path, to: &Path) -> Option<PathBuf> {
+    if !from.is_absolute() || !to.is_absolute() {
+        return None;
+    }
+    let from = from.components().collect::<Vec<_>>();
+    let to = to.components().collect::<Vec<_>>();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+    // different roots, e.g. disks on Windows
+    if common == 0 {
+        return None;
+    }
+    let mut path = PathBuf::new();
+    for _ in common..from.len() {
+        path.push(Component::ParentDir);
+    }
+    for component in &to[common..] {
+        path.push(component);
+    }
+    Some(path)
+}
+
+#[cfg(unix)]
+fn symlink(target: &Path, path: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, path)
+}
+
+/// Creating symlinks on Windows requires the developer mode or admin rights,
+/// the file is copied if it fails
+#[cfg(windows)]
+fn symlink(target: &Path, path: &Path) -> std::io::Result<()> {
+    std::os::windows::fs::symlink_file(target, path)
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ContentStore__identical_outputs__EXPECT__single_stored_object() {
+        // Given
+        let workspace = tempfile::tempdir().unwrap();
+        let store = ContentStore::new(workspace.path().join("cas"), OutputLinkMode::Hardlink);
+        let first = workspace.path().join("drawable").join("ic_star.xml");
+        let second = workspace.path().join("drawable-night").join("ic_star.xml");
+
+        // When
+        for path in [&first, &second] {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "<vector/>").unwrap();
+            store.absorb(path).unwrap();
+        }
+
+        // Then
+        let objects = std::fs::read_dir(workspace.path().join("cas"))
+            .unwrap()
+            .flat_map(|dir| std::fs::read_dir(dir.unwrap().path()).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(1, objects.len());
+        assert_eq!("<vector/>", std::fs::read_to_string(&first).unwrap());
+        assert_eq!("<vector/>", std::fs::read_to_string(&second).unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn ContentStore__symlink_mode__EXPECT__relative_link_and_unlink_keeps_object() {
+        // Given
+        let workspace = tempfile::tempdir().unwrap();
+        let store = ContentStore::new(workspace.path().join("cas"), OutputLinkMode::Symlink);
+        let path = workspace.path().join("res").join("ic_star.svg");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "<svg/>").unwrap();
+
+        // When
+        store.absorb(&path).unwrap();
+        let link = std::fs::read_link(&path).unwrap();
+        store.unlink(&path).unwrap();
+        std::fs::write(&path, "<svg></svg>").unwrap();
+
+        // Then
+        assert!(link.starts_with(".."));
+        let object = path.parent().unwrap().join(link);
+        assert_eq!("<svg/>", std::fs::read_to_string(object).unwrap());
+    }
+
+    #[test]
+    fn relative_path__sibling_directories__EXPECT__path_through_common_parent() {
+        // Given
+        let root = std::env::temp_dir();
+        let from = root.join("app").join("res");
+        let to = root
+            .join(".figx-out")
+            .join("cas")
+            .join("ab")
+            .join("abc.xml");
+
+        // When
+        let path = relative_path(&from, &to).unwrap();
+
+        // Then
+        assert_eq!(
+            Path::new("..")
+                .join("..")
+                .join(".figx-out")
+                .join("cas")
+                .join("ab")
+                .join("abc.xml"),
+            path
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use crate::{
+    MaterializedFile, Target, figma::NodeMetadata, get_file_fingerprint, get_target_digest,
+};
+use bincode::{Decode, Encode};
+use lib_cache::{Cache, CacheKey, Digester};
+use log::{debug, warn};
+use phase_loading::Profile;
+use std::{
+    hash::Hasher,
+    path::{Path, PathBuf},
+};
+
+const TARGET_STATE_TAG: u8 = 0x0E;
+
+/// Remembers what every target was imported from, so that targets whose Figma node,
+/// profile and figx version are unchanged skip the export, download and transformations
+/// entirely, as long as their files are untouched.
+///
+/// Finer caches of the actions still work for the dirty targets, e.g. an unchanged image
+/// is not downloaded again when only the profile of the target has changed.
+#[derive(Default)]
+pub struct DirtyTracker {
+    /// `None` if the outputs cannot be trusted, e.g. when they are not written right away
+    cache: Option<Cache>,
+    /// Stamped files differ from the unstamped ones, so switching it makes targets dirty
+    stamp_provenance: bool,
+    workspace_dir: PathBuf,
+}
+
+#[derive(Encode, Decode)]
+struct TargetState {
+    digest: u64,
+    /// Main file of the target, followed by the other ones
+    outputs: Vec<OutputFile>,
+    bytes: usize,
+}
+
+#[derive(Encode, Decode)]
+struct OutputFile {
+    path: String,
+    fingerprint: u64,
+}
+
+impl DirtyTracker {
+    pub fn new(cache: Cache, stamp_provenance: bool, workspace_dir: &Path) -> Self {
+        Self {
+            cache: Some(cache),
+            stamp_provenance,
+            workspace_dir: workspace_dir.to_path_buf(),
+        }
+    }
+
+    /// Digest of the inputs of the target, `None` if some of them are not known
+    /// from the node alone, so the target is always imported
+    pub fn digest(&self, target: &Target, node: &NodeMetadata) -> Option<u64> {
+        self.cache.as_ref()?;
+        if !is_trackable(target) {
+            return None;
+        }
+        let mut hasher = Digester::new();
+        hasher.write_u64(get_target_digest(target, node, &self.workspace_dir));
+        hasher.write_u8(self.stamp_provenance as u8);
+        Some(hasher.digest())
+    }
+
+    /// Returns the files of the target, if it is imported from the same inputs
+    /// and none of its files has changed since then
+    pub fn clean_output(&self, target: &str, digest: u64) -> Option<MaterializedFile> {
+        let cache = self.cache.as_ref()?;
+        let state = match cache.get::<TargetState>(&state_key(target)) {
+            Ok(state) => state?,
+            Err(e) => {
+                warn!(target: "Dirty", "unable to read the state of `{target}`: {e}");
+                return None;
+            }
+        };
+        if state.digest != digest {
+            debug!(target: "Dirty", "`{target}` has changed since the last import");
+            return None;
+        }
+        for output in &state.outputs {
+            let fingerprint = get_file_fingerprint(Path::new(&output.path)).ok();
+            if fingerprint != Some(output.fingerprint) {
+                debug!(target: "Dirty", "`{}` of `{target}` is modified or missing", output.path);
+                return None;
+            }
+        }
+        let mut paths = state.outputs.into_iter().map(|it| PathBuf::from(it.path));
+        Some(MaterializedFile {
+            path: paths.next()?,
+            bytes: state.bytes,
+            written: false,
+            extra_paths: paths.collect(),
+        })
+    }
+
+    /// Remembers the inputs of the imported target along with its files
+    pub fn record(&self, target: &str, digest: u64, file: &MaterializedFile) {
+        let Some(cache) = &self.cache else {
+            return;
+        };
+        let mut outputs = Vec::with_capacity(1 + file.extra_paths.len());
+        for path in std::iter::once(&file.path).chain(&file.extra_paths) {
+            let Ok(fingerprint) = get_file_fingerprint(path) else {
+                // cannot be verified next time
+                return;
+            };
+            outputs.push(OutputFile {
+                path: path.to_string_lossy().into_owned(),
+                fingerprint,
+            });
+        }
+        let state = TargetState {
+            digest,
+            outputs,
+            bytes: file.bytes,
+        };
+        if let Err(e) = cache.put(&state_key(target), &state) {
+            warn!(target: "Dirty", "unable to save the state of `{target}`: {e}");
+        }
+    }
+}
+
+fn state_key(target: &str) -> CacheKey {
+    CacheKey::builder()
+        .set_tag(TARGET_STATE_TAG)
+        .write_str(target)
+        .build()
+}
+
+/// Variants bundled into a single file are written by the last of them, and the
+/// Compose code may depend on the variables and descriptions of the whole file
+fn is_trackable(target: &Target) -> bool {
+    match target.profile {
+        Profile::Compose(profile) => {
+            !(profile.combine_variants && target.id.is_some())
+                && !profile.document_sources
+                && !profile
+                    .color_mappings
+                    .iter()
+                    .any(|it| it.from.starts_with('@'))
+        }
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use crate::setup_cache;
+
+    fn imported_file(path: PathBuf) -> MaterializedFile {
+        std::fs::write(&path, "<svg/>").unwrap();
+        MaterializedFile {
+            path,
+            bytes: 6,
+            written: true,
+            extra_paths: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn DirtyTracker__same_digest_and_untouched_file__EXPECT__clean_output() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let tracker = DirtyTracker::new(
+            setup_cache(&dir.path().join("cache")).unwrap(),
+            false,
+            dir.path(),
+        );
+        let file = imported_file(dir.path().join("ic_star.svg"));
+
+        // When
+        tracker.record("//icons:ic_star", 42, &file);
+
+        // Then
+        let clean = tracker.clean_output("//icons:ic_star", 42).unwrap();
+        assert_eq!(file.path, clean.path);
+        assert!(!clean.written);
+        assert!(tracker.clean_output("//icons:ic_star", 43).is_none());
+        assert!(tracker.clean_output("//icons:ic_moon", 42).is_none());
+    }
+
+    #[test]
+    fn DirtyTracker__output_file_removed__EXPECT__dirty() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let tracker = DirtyTracker::new(
+            setup_cache(&dir.path().join("cache")).unwrap(),
+            false,
+            dir.path(),
+        );
+        let file = imported_file(dir.path().join("ic_star.svg"));
+        tracker.record("//icons:ic_star", 42, &file);
+
+        // When
+        std::fs::remove_file(&file.path).unwrap();
+
+        // Then
+        assert!(tracker.clean_output("//icons:ic_star", 42).is_none());
+    }
+
+    #[test]
+    fn DirtyTracker__disabled__EXPECT__nothing_is_clean() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let tracker = DirtyTracker::default();
+        let file = imported_file(dir.path().join("ic_star.svg"));
+
+        // When
+        tracker.record("//icons:ic_star", 42, &file);
+
+        // Then
+        assert!(tracker.clean_output("//icons:ic_star", 42).is_none());
+    }
+}
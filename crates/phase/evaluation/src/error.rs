@@ -21,9 +21,54 @@ pub enum Error {
         file: PathBuf,
         span: Range<usize>,
     },
+    /// Size of the node differs from the `expect` attribute of the resource
+    UnexpectedDimensions {
+        node_name: String,
+        mismatches: Vec<String>,
+        file: PathBuf,
+        span: Range<usize>,
+    },
     SvgToCompose(lib_svg2compose::Error),
     RenderSvg(String),
     ConversionError(String),
+    VectorTooComplex(String),
+    RasterPdf(String),
+    /// Imported raster image is corrupted or has unexpected dimensions
+    InvalidImage(String),
+    PostProcess(String),
+    /// Workspace files which differ from the imported ones in check mode
+    OutOfDate(Vec<PathBuf>),
+    ResolveVariable(String),
+    /// Variants bundled into one file were not all imported, so it is not written
+    IncompleteBundle(String),
+    TargetsFailed(Vec<TargetFailure>),
+    Cancelled,
+}
+
+/// Error of a single target (or a whole remote) collected in keep-going mode
+#[derive(Debug)]
+pub struct TargetFailure {
+    /// Target label with variant, or remote identifier
+    pub target: String,
+    pub error: Error,
+    /// Log records emitted while the target was evaluated
+    pub logs: Vec<String>,
+}
+
+impl Error {
+    /// Whether the error belongs to a single target, rather than to its remote
+    /// or to the whole evaluation
+    pub fn is_target_failure(&self) -> bool {
+        !matches!(
+            self,
+            Self::Cache(_)
+                | Self::FigmaApiNetwork(_)
+                | Self::IndexingRemote(_)
+                | Self::OutOfDate(_)
+                | Self::TargetsFailed(_)
+                | Self::Cancelled
+        )
+    }
 }
 
 impl Display for Error {
@@ -90,3 +135,29 @@ impl<'a> From<&Target<'a>> for Error {
         }
     }
 }
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn Error__is_target_failure__EXPECT__only_errors_of_single_targets() {
+        // Given
+        let target_errors = [
+            Error::ExportImage("no image".to_owned()),
+            Error::RenderSvg("invalid svg".to_owned()),
+            Error::IO(std::io::Error::other("unable to write")),
+        ];
+        let remote_errors = [
+            Error::IndexingRemote("timeout".to_owned()),
+            Error::OutOfDate(Vec::new()),
+            Error::TargetsFailed(Vec::new()),
+            Error::Cancelled,
+        ];
+
+        // Then
+        assert!(target_errors.iter().all(Error::is_target_failure));
+        assert!(!remote_errors.iter().any(Error::is_target_failure));
+    }
+}
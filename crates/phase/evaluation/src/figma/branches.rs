@@ -0,0 +1,44 @@
+use crate::{Error, Result};
+use lib_figma_fluent::FigmaApi;
+use log::debug;
+use phase_loading::{RemoteSource, Workspace};
+use std::{collections::HashMap, sync::Arc};
+
+/// Replaces the file keys of the remotes with the keys of their branches,
+/// so that the rest of the evaluation fetches the branches as regular files
+pub fn resolve_branches(ws: &mut Workspace, api: &FigmaApi) -> Result<()> {
+    // remote id => remote with the key of the branch
+    let mut resolved = HashMap::<String, Arc<RemoteSource>>::new();
+    for remote in ws.remotes.iter().filter(|it| it.branch.is_some()) {
+        let branch = remote.branch.as_deref().unwrap_or_default();
+        debug!(target: "Branches", "resolving branch `{branch}` of remote `{}`", remote.id);
+        let response = api.get_file_branches(&remote.access_token, &remote.file_key)?;
+        let Some(key) = response.branch_key(branch) else {
+            return Err(Error::IndexingRemote(format!(
+                "branch `{branch}` is not found in the file of remote `{}`",
+                remote.id
+            )));
+        };
+        let remote_on_branch = RemoteSource {
+            file_key: key.to_owned(),
+            ..remote.as_ref().clone()
+        };
+        resolved.insert(remote.id.clone(), Arc::new(remote_on_branch));
+    }
+    if resolved.is_empty() {
+        return Ok(());
+    }
+
+    for remote in ws.remotes.iter_mut() {
+        if let Some(it) = resolved.get(remote.id.as_str()) {
+            *remote = it.clone();
+        }
+    }
+    let resources = ws.packages.iter_mut().flat_map(|it| &mut it.resources);
+    for res in resources {
+        if let Some(it) = resolved.get(res.attrs.remote.id.as_str()) {
+            res.attrs.remote = it.clone();
+        }
+    }
+    Ok(())
+}
@@ -1,17 +1,23 @@
 use crate::{
     Error, Result,
-    figma::{NodeMetadata, RemoteMetadata},
+    figma::{NodeMetadata, RemoteMetadata, RemoteThrottle},
 };
 use dashmap::DashMap;
 use lib_cache::{Cache, CacheKey};
-use lib_figma_fluent::{FigmaApi, GetFileNodesStreamQueryParameters};
+use lib_figma_fluent::{
+    FigmaApi, GetFileNodesScanQueryParameters, GetFileNodesStreamQueryParameters, ScannedNodeDto,
+};
 use log::debug;
 use phase_loading::RemoteSource;
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+};
 
 pub struct RemoteIndex {
     api: FigmaApi,
     cache: Cache,
+    throttle: RemoteThrottle,
     index: Arc<DashMap<String, NodeMetadata>>,
 }
 
@@ -21,56 +27,172 @@ pub enum Subscription<'a> {
 }
 
 #[must_use]
-pub struct SubscriptionHandle(CacheKey, Arc<DashMap<String, NodeMetadata>>, Cache);
+pub struct SubscriptionHandle(
+    CacheKey,
+    Arc<DashMap<String, NodeMetadata>>,
+    Cache,
+    Option<(CacheKey, String)>,
+);
+
+/// Components with the wanted names found by the shallow scan of the remote
+struct ShallowScan {
+    /// Version of the file the components belong to
+    version: Option<String>,
+    /// Component id => name
+    components: Vec<(String, String)>,
+}
 
 impl RemoteIndex {
     pub const REMOTE_SOURCE_TAG: u8 = 0x42;
+    pub const SCANNED_VERSION_TAG: u8 = 0x48;
 
-    pub fn new(api: FigmaApi, cache: Cache) -> Self {
+    pub fn new(api: FigmaApi, cache: Cache, throttle: RemoteThrottle) -> Self {
         Self {
             api,
             cache,
+            throttle,
             index: Arc::new(DashMap::with_capacity(1024)),
         }
     }
 
+    /// Whether [`RemoteIndex::subscribe`] would return the index from cache
+    pub fn is_cached(&self, remote: &RemoteSource, wanted_names: &BTreeSet<String>) -> bool {
+        match Self::get_cached(&self.cache, remote) {
+            Ok(Some(metadata)) => is_complete(remote, &metadata, wanted_names),
+            _ => false,
+        }
+    }
+
+    /// Index of the remote cached by the previous run, if any, the network is never touched
+    pub fn get_cached(cache: &Cache, remote: &RemoteSource) -> Result<Option<RemoteMetadata>> {
+        let container_node_ids = remote.container_node_ids.to_string_id_list();
+        let cache_key = Self::cache_key(remote, &container_node_ids);
+        Ok(cache.get::<RemoteMetadata>(&cache_key)?)
+    }
+
+    fn cache_key(remote: &RemoteSource, container_node_ids: &[String]) -> CacheKey {
+        Self::remote_key(Self::REMOTE_SOURCE_TAG, remote, container_node_ids)
+    }
+
+    /// Key of the file version the shallow index of the remote belongs to
+    fn version_key(remote: &RemoteSource, container_node_ids: &[String]) -> CacheKey {
+        Self::remote_key(Self::SCANNED_VERSION_TAG, remote, container_node_ids)
+    }
+
+    fn remote_key(tag: u8, remote: &RemoteSource, container_node_ids: &[String]) -> CacheKey {
+        let mut cache_key = CacheKey::builder()
+            .set_tag(tag)
+            .write_str(&remote.file_key)
+            .write_str(&container_node_ids.join(","));
+        // shallow indexes contain only the components fetched so far, so they
+        // must not be mistaken for the complete ones
+        if let Some(depth) = remote.shallow_depth {
+            cache_key = cache_key.write_u32(depth);
+        }
+        cache_key.build()
+    }
+
     /// This function  must be called from one thread per remote only
+    ///
+    /// `wanted_names` are used only by remotes with `shallow_depth`: the index of such
+    /// remote contains nothing but the components wanted by this and previous runs.
     pub fn subscribe<'a>(
         &'a self,
         remote: &'a RemoteSource,
         refetch: bool,
+        wanted_names: &BTreeSet<String>,
     ) -> Result<(SubscriptionHandle, Subscription<'a>)> {
         let container_node_ids = remote.container_node_ids.to_string_id_list();
-        // construct unique cache key
-        let cache_key = CacheKey::builder()
-            .set_tag(Self::REMOTE_SOURCE_TAG)
-            .write_str(&remote.file_key)
-            .write_str(&container_node_ids.join(","))
-            .build();
+        let cache_key = Self::cache_key(remote, &container_node_ids);
 
         // return cached value if it exists
-        if !refetch {
-            if let Some(metadata) = self.cache.get::<RemoteMetadata>(&cache_key)? {
+        let cached = match self.cache.get::<RemoteMetadata>(&cache_key)? {
+            Some(metadata) if !refetch && is_complete(remote, &metadata, wanted_names) => {
                 return Ok((
-                    SubscriptionHandle(cache_key, self.index.clone(), self.cache.clone()),
+                    SubscriptionHandle(cache_key, self.index.clone(), self.cache.clone(), None),
                     Subscription::FromCache(metadata.name_to_node),
                 ));
             }
-        }
+            cached => cached,
+        };
 
         debug!(target: "Updating", "remote index {remote}");
-        let stream = self.api.get_file_nodes_stream(
-            &remote.access_token,
-            &remote.file_key,
-            GetFileNodesStreamQueryParameters {
-                // TODO: fix this leak
-                ids: Some(container_node_ids.leak()),
-                geometry: Some("paths"),
-                ..Default::default()
-            },
-        )?;
-
-        let iter = stream.filter_map(|item| match item {
+        let Some(depth) = remote.shallow_depth else {
+            let stream = self.fetch_nodes(remote, container_node_ids)?;
+            return Ok((
+                SubscriptionHandle(cache_key, self.index.clone(), self.cache.clone(), None),
+                Subscription::FromRemote(Box::new(stream)),
+            ));
+        };
+
+        let version_key = Self::version_key(remote, &container_node_ids);
+        let scan = self.scan_wanted_components(remote, container_node_ids, depth, wanted_names)?;
+        let cached_version = self.cache.get::<String>(&version_key)?;
+        let cached_nodes = match (cached, &scan.version, cached_version) {
+            (Some(metadata), Some(version), Some(cached_version)) if *version == cached_version => {
+                metadata.name_to_node
+            }
+            // Figma does not tell which nodes have changed between versions of the file,
+            // so nothing fetched for another version can be reused
+            _ => HashMap::new(),
+        };
+        let (unchanged, changed_ids) = diff_scanned_components(&cached_nodes, scan.components);
+        debug!(
+            target: "Updating",
+            "remote {remote}: {} of {} found components have changed since the last fetch",
+            changed_ids.len(),
+            changed_ids.len() + unchanged.len(),
+        );
+        // components which are not wanted this time are kept for the next runs, while
+        // the wanted ones are added along with the fetched nodes
+        for (name, node) in cached_nodes {
+            if !wanted_names.contains(&name) {
+                self.index.insert(name, node);
+            }
+        }
+        let unchanged = unchanged.into_iter().map(|node| -> Result<NodeMetadata> {
+            self.index.insert(node.name.to_owned(), node.clone());
+            Ok(node)
+        });
+        let handle = SubscriptionHandle(
+            cache_key,
+            self.index.clone(),
+            self.cache.clone(),
+            scan.version.map(|version| (version_key, version)),
+        );
+        if changed_ids.is_empty() {
+            // nothing to fetch, wanted targets not found by the scan will be reported as not found
+            return Ok((handle, Subscription::FromRemote(Box::new(unchanged))));
+        }
+        let stream = self.fetch_nodes(remote, changed_ids)?;
+        Ok((
+            handle,
+            Subscription::FromRemote(Box::new(unchanged.chain(stream))),
+        ))
+    }
+
+    /// Fetches entire subtrees of the nodes with the given ids and streams the visible
+    /// components found in them, each name is streamed only once
+    fn fetch_nodes<'a>(
+        &'a self,
+        remote: &'a RemoteSource,
+        ids: Vec<String>,
+    ) -> Result<impl Iterator<Item = Result<NodeMetadata>> + Send + 'a> {
+        // TODO: fix this leak
+        let ids: &'static [String] = ids.leak();
+        let stream = self.throttle.request(&remote.file_key, &remote.retry, || {
+            self.api.get_file_nodes_stream(
+                &remote.access_token,
+                &remote.file_key,
+                GetFileNodesStreamQueryParameters {
+                    ids: Some(ids),
+                    geometry: Some("paths"),
+                    ..Default::default()
+                },
+            )
+        })?;
+
+        Ok(stream.filter_map(|item| match item {
             Ok(node) => {
                 // Ignore nodes which are not components or are not visible, do not store them in the index
                 if node.r#type != "COMPONENT" || !node.visible {
@@ -81,6 +203,8 @@ impl RemoteIndex {
                     name: node.name,
                     hash: node.hash,
                     uses_raster_paints: node.has_raster_fills,
+                    bounds: node.bounds.map(|it| (it.width, it.height)),
+                    render_bounds: node.render_bounds.map(|it| (it.width, it.height)),
                 };
                 if !self.index.contains_key(&node.name) {
                     self.index.insert(node.name.to_owned(), node.clone());
@@ -90,18 +214,98 @@ impl RemoteIndex {
                 }
             }
             Err(e) => Some(Err(Error::IndexingRemote(e.to_string()))),
-        });
+        }))
+    }
 
-        Ok((
-            SubscriptionHandle(cache_key, self.index.clone(), self.cache.clone()),
-            Subscription::FromRemote(Box::new(iter)),
-        ))
+    /// First phase of the shallow fetch: scans the containers `depth` levels deep and
+    /// returns ids of the visible components with wanted names, so that only the changed
+    /// ones are fetched with the whole subtree later.
+    fn scan_wanted_components(
+        &self,
+        remote: &RemoteSource,
+        container_node_ids: Vec<String>,
+        depth: u32,
+        wanted_names: &BTreeSet<String>,
+    ) -> Result<ShallowScan> {
+        debug!(target: "Updating", "scanning remote {remote} {depth} levels deep");
+        let response = self.throttle.request(&remote.file_key, &remote.retry, || {
+            self.api.get_file_nodes_scan(
+                &remote.access_token,
+                &remote.file_key,
+                GetFileNodesScanQueryParameters {
+                    ids: Some(&container_node_ids),
+                    depth: Some(depth as i32),
+                    ..Default::default()
+                },
+            )
+        })?;
+        let mut components = Vec::new();
+        for root in response.nodes.values() {
+            collect_wanted_components(&root.document, wanted_names, &mut components);
+        }
+        debug!(
+            target: "Updating",
+            "remote {remote}: {} of {} wanted components found by the shallow scan",
+            components.len(),
+            wanted_names.len(),
+        );
+        Ok(ShallowScan {
+            version: response.version,
+            components,
+        })
     }
 }
 
+/// Shallow indexes contain only the components fetched so far, so they are complete
+/// only if every wanted component has been fetched
+fn is_complete(
+    remote: &RemoteSource,
+    metadata: &RemoteMetadata,
+    wanted_names: &BTreeSet<String>,
+) -> bool {
+    remote.shallow_depth.is_none()
+        || wanted_names
+            .iter()
+            .all(|it| metadata.name_to_node.contains_key(it))
+}
+
+fn collect_wanted_components(
+    node: &ScannedNodeDto,
+    wanted_names: &BTreeSet<String>,
+    components: &mut Vec<(String, String)>,
+) {
+    // invisible subtrees are not indexed by the deep fetch as well
+    if !node.visible {
+        return;
+    }
+    if node.r#type == "COMPONENT" && wanted_names.contains(&node.name) {
+        components.push((node.id.to_owned(), node.name.to_owned()));
+    }
+    for child in &node.children {
+        collect_wanted_components(child, wanted_names, components);
+    }
+}
+
+/// Splits the scanned components into the cached nodes which are still up to date
+/// and ids of the components which must be fetched again
+fn diff_scanned_components(
+    cached_nodes: &HashMap<String, NodeMetadata>,
+    scanned: Vec<(String, String)>,
+) -> (Vec<NodeMetadata>, Vec<String>) {
+    let mut unchanged = Vec::new();
+    let mut changed_ids = Vec::new();
+    for (id, name) in scanned {
+        match cached_nodes.get(&name) {
+            Some(node) if node.id == id => unchanged.push(node.clone()),
+            _ => changed_ids.push(id),
+        }
+    }
+    (unchanged, changed_ids)
+}
+
 impl SubscriptionHandle {
     pub fn commit_cache(self) -> Result<()> {
-        let SubscriptionHandle(cache_key, index, cache) = self;
+        let SubscriptionHandle(cache_key, index, cache, version) = self;
 
         let metadata = RemoteMetadata {
             name_to_node: index
@@ -111,6 +315,107 @@ impl SubscriptionHandle {
         };
         // remember result to cache
         cache.put::<RemoteMetadata>(&cache_key, &metadata)?;
+        if let Some((version_key, version)) = version {
+            cache.put::<String>(&version_key, &version)?;
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    fn node(id: &str, name: &str, r#type: &str, visible: bool) -> ScannedNodeDto {
+        ScannedNodeDto {
+            id: id.to_owned(),
+            name: name.to_owned(),
+            visible,
+            children: Vec::new(),
+            r#type: r#type.to_owned(),
+        }
+    }
+
+    #[test]
+    fn collect_wanted_components__mixed_tree__EXPECT__only_visible_wanted_components() {
+        // Given
+        let mut hidden_frame = node("0:2", "Hidden", "FRAME", false);
+        hidden_frame.children = vec![node("0:3", "Icon / Coffee", "COMPONENT", true)];
+        let mut root = node("0:1", "Icons", "FRAME", true);
+        root.children = vec![
+            hidden_frame,
+            node("0:4", "Icon / Coffee", "COMPONENT", true),
+            node("0:5", "Icon / Leaf", "COMPONENT", true),
+            node("0:6", "Icon / Tea", "FRAME", true),
+        ];
+        let wanted_names: BTreeSet<_> = ["Icon / Coffee", "Icon / Tea"]
+            .into_iter()
+            .map(str::to_owned)
+            .collect();
+
+        // When
+        let mut components = Vec::new();
+        collect_wanted_components(&root, &wanted_names, &mut components);
+
+        // Then
+        assert_eq!(
+            vec![("0:4".to_string(), "Icon / Coffee".to_string())],
+            components
+        );
+    }
+
+    fn metadata(id: &str, name: &str, hash: u64) -> NodeMetadata {
+        NodeMetadata {
+            id: id.to_owned(),
+            name: name.to_owned(),
+            hash,
+            uses_raster_paints: false,
+            bounds: None,
+            render_bounds: None,
+        }
+    }
+
+    #[test]
+    fn diff_scanned_components__cached_index__EXPECT__only_new_and_moved_components_changed() {
+        // Given
+        let cached_nodes = HashMap::from([
+            (
+                "Icon / Coffee".to_owned(),
+                metadata("0:4", "Icon / Coffee", 1),
+            ),
+            ("Icon / Leaf".to_owned(), metadata("0:5", "Icon / Leaf", 2)),
+        ]);
+        let scanned = vec![
+            ("0:4".to_owned(), "Icon / Coffee".to_owned()),
+            ("0:9".to_owned(), "Icon / Leaf".to_owned()),
+            ("0:7".to_owned(), "Icon / Tea".to_owned()),
+        ];
+
+        // When
+        let (unchanged, changed_ids) = diff_scanned_components(&cached_nodes, scanned);
+
+        // Then
+        assert_eq!(
+            vec![("0:4", 1)],
+            unchanged
+                .iter()
+                .map(|it| (it.id.as_str(), it.hash))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(vec!["0:9".to_string(), "0:7".to_string()], changed_ids);
+    }
+
+    #[test]
+    fn diff_scanned_components__no_cached_index__EXPECT__all_components_changed() {
+        // Given
+        let scanned = vec![("0:4".to_owned(), "Icon / Coffee".to_owned())];
+
+        // When
+        let (unchanged, changed_ids) = diff_scanned_components(&HashMap::new(), scanned);
+
+        // Then
+        assert!(unchanged.is_empty());
+        assert_eq!(vec!["0:4".to_string()], changed_ids);
+    }
+}
@@ -4,4 +4,12 @@ pub use models::*;
 pub use repository::*;
 mod batching;
 pub use batching::*;
+mod branches;
+pub use branches::*;
 pub mod indexing;
+mod retry;
+pub(crate) use retry::*;
+mod throttle;
+pub use throttle::*;
+mod variables;
+pub use variables::*;
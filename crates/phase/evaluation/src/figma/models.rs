@@ -12,4 +12,19 @@ pub struct NodeMetadata {
     pub name: String,
     pub hash: u64,
     pub uses_raster_paints: bool,
+    /// Width and height of the node in Figma
+    pub bounds: Option<(f32, f32)>,
+    /// Width and height of the node including effects and strokes,
+    /// exported images have this size unless absolute bounds are used
+    pub render_bounds: Option<(f32, f32)>,
+}
+
+impl NodeMetadata {
+    /// Size of the image exported from the node, in points
+    pub fn export_size(&self, use_absolute_bounds: bool) -> Option<(f32, f32)> {
+        match use_absolute_bounds {
+            true => self.bounds,
+            false => self.render_bounds.or(self.bounds),
+        }
+    }
 }
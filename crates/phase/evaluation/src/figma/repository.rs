@@ -1,20 +1,23 @@
-use super::{Batched, Batcher, NodeMetadata};
+use super::{
+    Batched, Batcher, NodeMetadata, RemoteThrottle, color_variables_to_hex, retry_delays,
+    should_retry,
+};
 use crate::{Error, Result};
 use dashmap::DashMap;
 use key_mutex::KeyMutex;
 use lib_cache::{Cache, CacheKey};
-use lib_figma_fluent::{FigmaApi, GetImageQueryParameters, GetImageResponse};
+use lib_figma_fluent::{
+    FigmaApi, GetFileNodesScanQueryParameters, GetImageQueryParameters, GetImageResponse,
+};
 use log::{debug, warn};
 use phase_loading::RemoteSource;
-use retry::delay::Fixed;
+use retry::OperationResult;
 use retry::retry_with_index;
-use retry::{OperationResult, delay::jitter};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::sync::LazyLock;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
-use ureq::Error::Io;
-use ureq::Error::StatusCode;
 
 static FIGMA_500_NOTIFICATION: LazyLock<()> = LazyLock::new(
     || warn!(target: "FigmaRepository", "It looks like we DDoSed the Figma REST API — slowing down a bit..."),
@@ -24,23 +27,32 @@ static FIGMA_500_NOTIFICATION: LazyLock<()> = LazyLock::new(
 pub struct FigmaRepository {
     api: FigmaApi,
     batched_api: Arc<DashMap<BatchKey, ExportImgBatcher>>,
+    /// File key => resolved color variables, requested once per evaluation
+    color_variables: Arc<DashMap<String, Arc<ColorVariables>>>,
+    /// Cache key of the file and container nodes => descriptions of components,
+    /// requested once per evaluation
+    component_descriptions: Arc<DashMap<CacheKey, Arc<ComponentDescriptions>>>,
     cache: Cache,
     locks: KeyMutex<CacheKey, ()>,
+    throttle: RemoteThrottle,
 }
 
 pub struct BatchedApi {
     api: FigmaApi,
+    throttle: RemoteThrottle,
     remote: Arc<RemoteSource>,
     format: String,
     scale: f32,
+    use_absolute_bounds: bool,
 }
 
 #[derive(Eq, PartialEq, Hash, Debug, Clone)]
 pub struct BatchKey(String);
 
 impl BatchKey {
-    pub fn from(file_key: &str, format: &str, scale: f32) -> Self {
-        Self(format!("{file_key}:{format}:{scale}"))
+    pub fn from(file_key: &str, format: &str, scale: f32, use_absolute_bounds: bool) -> Self {
+        let bounds = if use_absolute_bounds { "abs" } else { "render" };
+        Self(format!("{file_key}:{format}:{scale}:{bounds}"))
     }
 }
 
@@ -48,38 +60,49 @@ pub type ExportImgBatcher = Batcher<String, BatchedApi, lib_figma_fluent::Result
 
 pub type DownloadUrl = String;
 
+/// Variable name => `RRGGBB`
+pub type ColorVariables = HashMap<String, String>;
+
+/// Component node ID => non-empty description
+pub type ComponentDescriptions = HashMap<String, String>;
+
 impl FigmaRepository {
     pub const REMOTE_SOURCE_TAG: u8 = 0x42;
     pub const EXPORTED_IMAGE_TAG: u8 = 0x43;
     pub const DOWNLOADED_IMAGE_TAG: u8 = 0x44;
+    pub const COLOR_VARIABLES_TAG: u8 = 0x45;
+    pub const COMPONENT_DESCRIPTIONS_TAG: u8 = 0x46;
 
     pub fn new(api: FigmaApi, cache: Cache) -> Self {
         Self {
             api,
             batched_api: Arc::new(DashMap::new()),
+            color_variables: Arc::new(DashMap::new()),
+            component_descriptions: Arc::new(DashMap::new()),
             cache,
             locks: KeyMutex::new(),
+            throttle: RemoteThrottle::default(),
         }
     }
 
+    /// Rate limiter shared by all requests to the remotes, including the indexing ones
+    pub fn throttle(&self) -> &RemoteThrottle {
+        &self.throttle
+    }
+
     pub fn export(
         &self,
         remote: &Arc<RemoteSource>,
         node: &NodeMetadata,
         format: &str,
         scale: f32,
+        use_absolute_bounds: bool,
         on_export_start: impl FnOnce(),
         on_cache_hit: impl FnOnce(),
     ) -> Result<DownloadUrl> {
         // construct unique cache key
-        let cache_key = CacheKey::builder()
-            .set_tag(Self::EXPORTED_IMAGE_TAG)
-            .write_str(&remote.file_key)
-            .write_str(&node.id)
-            .write_u64(node.hash)
-            .write_str(format)
-            .write_str(&scale.to_string())
-            .build();
+        let cache_key =
+            Self::exported_image_key(&remote.file_key, node, format, scale, use_absolute_bounds);
 
         // return cached value if it exists
         if let Some(url) = self.cache.get::<DownloadUrl>(&cache_key)? {
@@ -97,19 +120,21 @@ impl FigmaRepository {
 
         // otherwise, request value from remote
         on_export_start();
-        let batch_key = BatchKey::from(&remote.file_key, &format, scale);
+        let batch_key = BatchKey::from(&remote.file_key, &format, scale, use_absolute_bounds);
 
         // Avoid DashMap's entry locking
         if let None = self.batched_api.get(&batch_key) {
             // Build batcher outside DashMap lock
             let new_batcher = Batcher::new(
-                100,
+                remote.export_batch_size,
                 Duration::from_millis(2000),
                 BatchedApi {
                     api: self.api.clone(),
+                    throttle: self.throttle.clone(),
                     remote: remote.clone(),
                     format: format.to_owned(),
                     scale: scale,
+                    use_absolute_bounds,
                 },
             );
             self.batched_api.insert(batch_key.clone(), new_batcher);
@@ -123,7 +148,7 @@ impl FigmaRepository {
             .expect("Value always exists");
         let no_requested_node_attempts = Arc::new(AtomicUsize::new(0));
 
-        let response = retry_with_index(Fixed::from_millis(5000).map(jitter), |attempt| {
+        let response = retry_with_index(retry_delays(&remote.retry), |attempt| {
             if attempt > 1 {
                 debug!(target: "FigmaRepository" ,"retrying request: attempt #{}", attempt - 1);
             };
@@ -141,28 +166,9 @@ impl FigmaRepository {
                     }
                 }
                 Ok(result) => OperationResult::Ok(result.to_owned()),
-                Err(e) => match e {
-                    lib_figma_fluent::Error::RateLimit {
-                        retry_after_sec,
-                        figma_plan_tier,
-                        figma_limit_type,
-                    } => {
-                        warn!(target: "RateLimit", "{retry_after_sec}s, {figma_plan_tier}, {figma_limit_type}");
-                        OperationResult::Err(Error::ExportImage(e.to_string()))
-                    }
-                    lib_figma_fluent::Error::Ureq(e) => match &e {
-                        StatusCode(500..=599) => {
-                            debug!(target: "FigmaRepository", "figma server error: {e}");
-                            let _ = &*FIGMA_500_NOTIFICATION;
-                            OperationResult::Retry(Error::ExportImage(e.to_string()))
-                        }
-                        Io(err) if matches!(err.kind(), std::io::ErrorKind::UnexpectedEof) => {
-                            debug!(target: "FigmaRepository", "figma disconnected: {e}");
-                            let _ = &*FIGMA_500_NOTIFICATION;
-                            OperationResult::Retry(Error::ExportImage(e.to_string()))
-                        }
-                        _ => OperationResult::Err(Error::ExportImage(e.to_string())),
-                    },
+                Err(e) => match self.on_request_failure(remote, e) {
+                    true => OperationResult::Retry(Error::ExportImage(e.to_string())),
+                    false => OperationResult::Err(Error::ExportImage(e.to_string())),
                 },
             }
         });
@@ -196,16 +202,167 @@ impl FigmaRepository {
         Ok(url.to_owned())
     }
 
-    pub fn download(&self, remote: &RemoteSource, url: &str) -> Result<Vec<u8>> {
-        // construct unique cache key
-        let cache_key = CacheKey::builder()
+    /// Whether the image would be exported and downloaded from the cache by [`Self::export`]
+    /// and [`Self::download`], the network is never touched
+    pub fn is_image_cached(
+        cache: &Cache,
+        file_key: &str,
+        node: &NodeMetadata,
+        format: &str,
+        scale: f32,
+        use_absolute_bounds: bool,
+    ) -> Result<bool> {
+        let cache_key =
+            Self::exported_image_key(file_key, node, format, scale, use_absolute_bounds);
+        match cache.get::<DownloadUrl>(&cache_key)? {
+            Some(url) => Ok(cache.contains_key(&Self::downloaded_image_key(&url))?),
+            None => Ok(false),
+        }
+    }
+
+    fn exported_image_key(
+        file_key: &str,
+        node: &NodeMetadata,
+        format: &str,
+        scale: f32,
+        use_absolute_bounds: bool,
+    ) -> CacheKey {
+        CacheKey::builder()
+            .set_tag(Self::EXPORTED_IMAGE_TAG)
+            .write_str(file_key)
+            .write_str(&node.id)
+            .write_u64(node.hash)
+            .write_str(format)
+            .write_str(&scale.to_string())
+            .write_bool(use_absolute_bounds)
+            .build()
+    }
+
+    fn downloaded_image_key(url: &str) -> CacheKey {
+        CacheKey::builder()
             .set_tag(Self::DOWNLOADED_IMAGE_TAG)
             .write_str(url)
+            .build()
+    }
+
+    /// Color variables of the remote file resolved in the default mode of their collections.
+    pub fn color_variables(
+        &self,
+        remote: &RemoteSource,
+        refetch: bool,
+    ) -> Result<Arc<ColorVariables>> {
+        if let Some(variables) = self.color_variables.get(&remote.file_key) {
+            return Ok(variables.clone());
+        }
+        // construct unique cache key
+        let cache_key = CacheKey::builder()
+            .set_tag(Self::COLOR_VARIABLES_TAG)
+            .write_str(&remote.file_key)
             .build();
 
+        // this section will be accessed by only one thread for one file
+        let _lock = self.locks.lock(cache_key.clone()).unwrap();
+        if let Some(variables) = self.color_variables.get(&remote.file_key) {
+            return Ok(variables.clone());
+        }
+
+        // return cached value if it exists
+        let cached = match refetch {
+            true => None,
+            false => self.cache.get::<ColorVariables>(&cache_key)?,
+        };
+        let variables = match cached {
+            Some(variables) => variables,
+            None => {
+                debug!(target: "FigmaRepository", "requesting local variables of {remote}");
+                let response = self
+                    .throttle
+                    .request(&remote.file_key, &remote.retry, || {
+                        self.api
+                            .get_local_variables(&remote.access_token, &remote.file_key)
+                    })
+                    .map_err(|e| Error::ResolveVariable(format!("{remote}: {}", e.error)))?;
+                let variables = color_variables_to_hex(&response.meta);
+                // remember result to cache
+                self.cache.put::<ColorVariables>(&cache_key, &variables)?;
+                variables
+            }
+        };
+        let variables = Arc::new(variables);
+        self.color_variables
+            .insert(remote.file_key.to_owned(), variables.clone());
+        Ok(variables)
+    }
+
+    /// Descriptions of the components inside the container nodes of the remote.
+    pub fn component_descriptions(
+        &self,
+        remote: &RemoteSource,
+        refetch: bool,
+    ) -> Result<Arc<ComponentDescriptions>> {
+        let container_node_ids = remote.container_node_ids.to_string_id_list();
+        // construct unique cache key
+        let cache_key = CacheKey::builder()
+            .set_tag(Self::COMPONENT_DESCRIPTIONS_TAG)
+            .write_str(&remote.file_key)
+            .write_str(&container_node_ids.join(","))
+            .build();
+        if let Some(descriptions) = self.component_descriptions.get(&cache_key) {
+            return Ok(descriptions.clone());
+        }
+
+        // this section will be accessed by only one thread for one file
+        let _lock = self.locks.lock(cache_key.clone()).unwrap();
+        if let Some(descriptions) = self.component_descriptions.get(&cache_key) {
+            return Ok(descriptions.clone());
+        }
+
+        // return cached value if it exists
+        let cached = match refetch {
+            true => None,
+            false => self.cache.get::<ComponentDescriptions>(&cache_key)?,
+        };
+        let descriptions = match cached {
+            Some(descriptions) => descriptions,
+            None => {
+                debug!(target: "FigmaRepository", "requesting component descriptions of {remote}");
+                let response = self.throttle.request(&remote.file_key, &remote.retry, || {
+                    self.api.get_file_nodes_scan(
+                        &remote.access_token,
+                        &remote.file_key,
+                        GetFileNodesScanQueryParameters {
+                            ids: Some(&container_node_ids),
+                            ..Default::default()
+                        },
+                    )
+                })?;
+                let descriptions = response
+                    .nodes
+                    .into_values()
+                    .flat_map(|it| it.components)
+                    .filter(|(_, metadata)| !metadata.description.trim().is_empty())
+                    .map(|(id, metadata)| (id, metadata.description))
+                    .collect::<ComponentDescriptions>();
+                // remember result to cache
+                self.cache
+                    .put::<ComponentDescriptions>(&cache_key, &descriptions)?;
+                descriptions
+            }
+        };
+        let descriptions = Arc::new(descriptions);
+        self.component_descriptions
+            .insert(cache_key, descriptions.clone());
+        Ok(descriptions)
+    }
+
+    /// Returns the image and whether it is received from the remote instead of the cache
+    pub fn download(&self, remote: &RemoteSource, url: &str) -> Result<(Vec<u8>, bool)> {
+        // construct unique cache key
+        let cache_key = Self::downloaded_image_key(url);
+
         // return cached value if it exists
         if let Some(image) = self.cache.get_bytes(&cache_key)? {
-            return Ok(image);
+            return Ok((image, false));
         }
 
         // this section will be accessed by only one thread for one node
@@ -213,32 +370,17 @@ impl FigmaRepository {
 
         // return cached value if it exists
         if let Some(image) = self.cache.get_bytes(&cache_key)? {
-            return Ok(image);
+            return Ok((image, false));
         }
 
         // otherwise, request value from remote
-        let response = retry_with_index(Fixed::from_millis(250).map(jitter), |_| {
-            match self.api.download_resource(&remote.access_token, url) {
+        let response = retry_with_index(retry_delays(&remote.retry), |_| {
+            self.throttle.wait(&remote.file_key);
+            match self.api.download_resource(url) {
                 Ok(value) => OperationResult::Ok(value),
-                Err(e) => match &e {
-                    lib_figma_fluent::Error::RateLimit {
-                        retry_after_sec: _,
-                        figma_plan_tier: _,
-                        figma_limit_type: _,
-                    } => OperationResult::Retry(Error::ExportImage(e.to_string())),
-                    lib_figma_fluent::Error::Ureq(e) => match e {
-                        StatusCode(500..=599) => {
-                            debug!(target: "FigmaRepository", "figma server error: {e}");
-                            let _ = &*FIGMA_500_NOTIFICATION;
-                            OperationResult::Retry(Error::ExportImage(e.to_string()))
-                        }
-                        Io(err) if matches!(err.kind(), std::io::ErrorKind::UnexpectedEof) => {
-                            debug!(target: "FigmaRepository", "figma disconnected: {e}");
-                            let _ = &*FIGMA_500_NOTIFICATION;
-                            OperationResult::Retry(Error::ExportImage(e.to_string()))
-                        }
-                        _ => OperationResult::Err(Error::ExportImage(e.to_string())),
-                    },
+                Err(e) => match self.on_request_failure(remote, &e) {
+                    true => OperationResult::Retry(Error::ExportImage(e.to_string())),
+                    false => OperationResult::Err(Error::ExportImage(e.to_string())),
                 },
             }
         });
@@ -247,7 +389,29 @@ impl FigmaRepository {
         // remember result to cache
         self.cache.put_bytes(&cache_key, &bytes)?;
         // return result and release lock
-        Ok(bytes.to_vec())
+        Ok((bytes.to_vec(), true))
+    }
+
+    /// Reacts to the failed request to the `remote` and tells whether it should be retried
+    fn on_request_failure(&self, remote: &RemoteSource, e: &lib_figma_fluent::Error) -> bool {
+        if let lib_figma_fluent::Error::RateLimit {
+            retry_after_sec,
+            figma_plan_tier,
+            figma_limit_type,
+        } = e
+        {
+            warn!(target: "RateLimit", "{retry_after_sec}s, {figma_plan_tier}, {figma_limit_type}");
+            self.throttle.pause(
+                &remote.file_key,
+                Duration::from_secs(*retry_after_sec as u64),
+            );
+        }
+        let retry = should_retry(&remote.retry, e);
+        if retry && matches!(e, lib_figma_fluent::Error::Ureq(_)) {
+            debug!(target: "FigmaRepository", "figma request failed: {e}");
+            let _ = &*FIGMA_500_NOTIFICATION;
+        }
+        retry
     }
 }
 
@@ -255,11 +419,16 @@ impl Batched<String, lib_figma_fluent::Result<GetImageResponse>> for BatchedApi
     fn execute(&self, ids: Vec<String>) -> lib_figma_fluent::Result<GetImageResponse> {
         let BatchedApi {
             api,
+            throttle,
             remote,
             format,
             scale,
+            use_absolute_bounds,
         } = self;
-        debug!(target: "FigmaRepository", "Batched request: ids=[{}]; format={format}; scale={scale}", ids.join(","));
+        // the same node may be requested by several targets at once, e.g. by variants
+        let ids = dedup_preserving_order(ids);
+        throttle.wait(&remote.file_key);
+        debug!(target: "FigmaRepository", "Batched request: ids=[{}]; format={format}; scale={scale}; use_absolute_bounds={use_absolute_bounds}", ids.join(","));
         Ok(api.get_image(
             &remote.access_token,
             &remote.file_key,
@@ -267,8 +436,90 @@ impl Batched<String, lib_figma_fluent::Result<GetImageResponse>> for BatchedApi
                 ids: Some(&ids),
                 scale: Some(*scale),
                 format: Some(format),
+                use_absolute_bounds: Some(*use_absolute_bounds).filter(|it| *it),
                 ..Default::default()
             },
         )?)
     }
 }
+
+fn dedup_preserving_order(ids: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::with_capacity(ids.len());
+    ids.into_iter()
+        .filter(|id| seen.insert(id.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dedup_preserving_order__repeated_ids__EXPECT__each_id_requested_once() {
+        // Given
+        let ids = ["1:2", "3:4", "1:2", "5:6", "3:4"]
+            .map(str::to_owned)
+            .to_vec();
+
+        // When
+        let ids = dedup_preserving_order(ids);
+
+        // Then
+        assert_eq!(vec!["1:2", "3:4", "5:6"], ids);
+    }
+
+    #[test]
+    fn BatchKey__different_bounds__EXPECT__different_batches() {
+        // Given
+        let render_bounds = BatchKey::from("key", "svg", 1.0, false);
+        let absolute_bounds = BatchKey::from("key", "svg", 1.0, true);
+
+        // Then
+        assert_ne!(render_bounds, absolute_bounds);
+        assert_eq!(render_bounds, BatchKey::from("key", "svg", 1.0, false));
+    }
+
+    #[test]
+    fn is_image_cached__export_then_download__EXPECT__cached_only_when_downloaded() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let cache = crate::setup_cache(dir.path()).unwrap();
+        let node = NodeMetadata {
+            id: "1:2".to_owned(),
+            name: "Star".to_owned(),
+            hash: 0xABCD,
+            uses_raster_paints: false,
+            bounds: None,
+            render_bounds: None,
+        };
+        let is_cached =
+            || FigmaRepository::is_image_cached(&cache, "FILE", &node, "svg", 1.0, false).unwrap();
+        let url = "https://figma.example/star.svg".to_owned();
+
+        // When
+        let not_exported = is_cached();
+        let export_key = FigmaRepository::exported_image_key("FILE", &node, "svg", 1.0, false);
+        cache.put::<DownloadUrl>(&export_key, &url).unwrap();
+        let not_downloaded = is_cached();
+        let download_key = FigmaRepository::downloaded_image_key(&url);
+        cache.put_bytes(&download_key, b"<svg/>").unwrap();
+        let downloaded = is_cached();
+
+        // Then
+        assert!(!not_exported);
+        assert!(!not_downloaded);
+        assert!(downloaded);
+    }
+
+    #[test]
+    fn downloaded_image_key__EXPECT__independent_of_figx_version() {
+        // When
+        let key = FigmaRepository::downloaded_image_key("https://figma.example/star.svg");
+
+        // Then
+        // tag, digest algorithm and xxh64 of the url only: downloaded images
+        // are reused after figx upgrades, unlike the transformation results
+        assert_eq!("4401D91D38107D1CE671", format!("{key:?}"));
+    }
+}
@@ -0,0 +1,104 @@
+use log::debug;
+use phase_loading::{RetryCondition, RetryPolicy};
+use retry::delay::jitter;
+use retry::{OperationResult, retry_with_index};
+use std::io::ErrorKind;
+use std::time::Duration;
+use ureq::Error::{Io, StatusCode, Timeout};
+
+/// Delays before each retry allowed by the `policy`: exponential backoff with jitter,
+/// capped by the `max_backoff`.
+pub(crate) fn retry_delays(policy: &RetryPolicy) -> impl Iterator<Item = Duration> + use<> {
+    let max_backoff = policy.max_backoff;
+    std::iter::successors(Some(policy.initial_backoff), move |delay| {
+        Some(delay.saturating_mul(2).min(max_backoff))
+    })
+    .map(jitter)
+    .take(policy.max_attempts.saturating_sub(1))
+}
+
+/// Whether the request failed with the `error` is worth retrying according to the `policy`
+pub(crate) fn should_retry(policy: &RetryPolicy, error: &lib_figma_fluent::Error) -> bool {
+    let condition = match error {
+        lib_figma_fluent::Error::RateLimit { .. } => RetryCondition::RateLimit,
+        lib_figma_fluent::Error::Ureq(StatusCode(500..=599)) => RetryCondition::ServerError,
+        lib_figma_fluent::Error::Ureq(Timeout(_)) => RetryCondition::Timeout,
+        lib_figma_fluent::Error::Ureq(Io(e))
+            if matches!(
+                e.kind(),
+                ErrorKind::UnexpectedEof
+                    | ErrorKind::TimedOut
+                    | ErrorKind::ConnectionReset
+                    | ErrorKind::ConnectionAborted
+            ) =>
+        {
+            RetryCondition::Timeout
+        }
+        _ => return false,
+    };
+    policy.retry_on.contains(&condition)
+}
+
+/// Performs the `request` until it succeeds or the `policy` gives up
+pub(crate) fn with_retry<T>(
+    policy: &RetryPolicy,
+    mut request: impl FnMut() -> lib_figma_fluent::Result<T>,
+) -> Result<T, retry::Error<lib_figma_fluent::Error>> {
+    retry_with_index(retry_delays(policy), |attempt| match request() {
+        Ok(value) => OperationResult::Ok(value),
+        Err(e) if should_retry(policy, &e) => {
+            debug!(target: "FigmaRepository", "attempt #{attempt} failed, retrying: {e}");
+            OperationResult::Retry(e)
+        }
+        Err(e) => OperationResult::Err(e),
+    })
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn retry_delays__default_policy__EXPECT__capped_delay_for_each_retry() {
+        // Given
+        let policy = RetryPolicy {
+            max_attempts: 6,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(500),
+            ..Default::default()
+        };
+
+        // When
+        let delays = retry_delays(&policy).collect::<Vec<_>>();
+
+        // Then
+        assert_eq!(5, delays.len());
+        let bounds = [100, 200, 400, 500, 500].map(Duration::from_millis);
+        for (delay, bound) in delays.into_iter().zip(bounds) {
+            assert!(delay <= bound, "{delay:?} > {bound:?}");
+        }
+    }
+
+    #[test]
+    fn should_retry__conditions_from_policy__EXPECT__only_listed_errors_retried() {
+        // Given
+        let policy = RetryPolicy {
+            retry_on: vec![RetryCondition::RateLimit],
+            ..Default::default()
+        };
+        let rate_limit = lib_figma_fluent::Error::RateLimit {
+            retry_after_sec: 1,
+            figma_plan_tier: String::new(),
+            figma_limit_type: String::new(),
+        };
+        let server_error = lib_figma_fluent::Error::Ureq(StatusCode(502));
+        let not_found = lib_figma_fluent::Error::Ureq(StatusCode(404));
+
+        // Then
+        assert!(should_retry(&policy, &rate_limit));
+        assert!(!should_retry(&policy, &server_error));
+        assert!(!should_retry(&policy, &not_found));
+        assert!(should_retry(&RetryPolicy::default(), &server_error));
+    }
+}
@@ -0,0 +1,189 @@
+use super::with_retry;
+use dashmap::DashMap;
+use log::debug;
+use phase_loading::RetryPolicy;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// Rate limiter of every Figma file on its own.
+///
+/// When Figma responds with 429 to a request for a file, the following
+/// requests for the same file wait until the `Retry-After` time passes,
+/// while the requests for other files go on as usual.
+#[derive(Clone)]
+pub struct RemoteThrottle {
+    /// File key => time until which the requests are paused
+    paused_until: Arc<DashMap<String, Instant>>,
+    clock: Arc<dyn Clock>,
+}
+
+/// Source of the time the throttle waits for
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+    fn sleep(&self, duration: Duration);
+}
+
+struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration)
+    }
+}
+
+impl Default for RemoteThrottle {
+    fn default() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+}
+
+impl RemoteThrottle {
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            paused_until: Arc::new(DashMap::new()),
+            clock,
+        }
+    }
+
+    /// Blocks the current thread while the requests for the file are paused
+    pub fn wait(&self, file_key: &str) {
+        // the pause may be prolonged by another request while waiting
+        while let Some(until) = self.paused_until.get(file_key).map(|it| *it) {
+            let now = self.clock.now();
+            if until <= now {
+                return;
+            }
+            debug!(target: "FigmaRepository", "waiting {:?} for the rate limit of {file_key}", until - now);
+            self.clock.sleep(until - now);
+        }
+    }
+
+    /// Pauses the requests for the file for `retry_after`
+    pub fn pause(&self, file_key: &str, retry_after: Duration) {
+        let until = self.clock.now() + retry_after;
+        self.paused_until
+            .entry(file_key.to_owned())
+            .and_modify(|it| *it = (*it).max(until))
+            .or_insert(until);
+    }
+
+    /// Performs the `request` for the file as soon as its requests are not paused,
+    /// retrying it according to the `policy`. Rate limited attempts pause the file.
+    pub fn request<T>(
+        &self,
+        file_key: &str,
+        policy: &RetryPolicy,
+        mut request: impl FnMut() -> lib_figma_fluent::Result<T>,
+    ) -> Result<T, retry::Error<lib_figma_fluent::Error>> {
+        with_retry(policy, || {
+            self.wait(file_key);
+            let result = request();
+            if let Err(lib_figma_fluent::Error::RateLimit {
+                retry_after_sec, ..
+            }) = &result
+            {
+                self.pause(file_key, Duration::from_secs(*retry_after_sec as u64));
+            }
+            result
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use phase_loading::RetryCondition;
+    use std::sync::Mutex;
+
+    /// Clock which moves only when slept on, remembering every sleep
+    struct ManualClock {
+        now: Mutex<Instant>,
+        sleeps: Mutex<Vec<Duration>>,
+    }
+
+    impl ManualClock {
+        fn new() -> Arc<Self> {
+            Arc::new(Self {
+                now: Mutex::new(Instant::now()),
+                sleeps: Mutex::new(Vec::new()),
+            })
+        }
+
+        fn sleeps(&self) -> Vec<Duration> {
+            self.sleeps.lock().unwrap().clone()
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Instant {
+            *self.now.lock().unwrap()
+        }
+
+        fn sleep(&self, duration: Duration) {
+            *self.now.lock().unwrap() += duration;
+            self.sleeps.lock().unwrap().push(duration);
+        }
+    }
+
+    fn rate_limit(retry_after_sec: u32) -> lib_figma_fluent::Error {
+        lib_figma_fluent::Error::RateLimit {
+            retry_after_sec,
+            figma_plan_tier: "starter".to_owned(),
+            figma_limit_type: "low".to_owned(),
+        }
+    }
+
+    #[test]
+    fn RemoteThrottle__paused_file__EXPECT__only_its_requests_wait() {
+        // Given
+        let clock = ManualClock::new();
+        let throttle = RemoteThrottle::with_clock(clock.clone());
+        throttle.pause("paused", Duration::from_secs(3));
+
+        // When
+        throttle.wait("other");
+        let other_sleeps = clock.sleeps();
+        throttle.wait("paused");
+        throttle.wait("paused");
+
+        // Then
+        assert!(other_sleeps.is_empty());
+        assert_eq!(vec![Duration::from_secs(3)], clock.sleeps());
+    }
+
+    #[test]
+    fn RemoteThrottle__rate_limited_request__EXPECT__retried_after_pause() {
+        // Given
+        let clock = ManualClock::new();
+        let throttle = RemoteThrottle::with_clock(clock.clone());
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+            retry_on: vec![RetryCondition::RateLimit],
+        };
+        let mut attempts = 0;
+
+        // When
+        let result = throttle.request("file", &policy, || {
+            attempts += 1;
+            match attempts {
+                1 => Err(rate_limit(5)),
+                _ => Ok(attempts),
+            }
+        });
+        let other_file = throttle.request("other", &policy, || Ok(0));
+
+        // Then
+        assert_eq!(2, result.unwrap());
+        assert_eq!(0, other_file.unwrap());
+        assert_eq!(vec![Duration::from_secs(5)], clock.sleeps());
+    }
+}
@@ -0,0 +1,98 @@
+use lib_figma_fluent::{LocalVariablesMetaDto, RgbaDto, VariableValueDto};
+use std::collections::HashMap;
+
+/// Aliases may reference other aliases, but not endlessly
+const MAX_ALIAS_DEPTH: usize = 16;
+
+/// Resolves all `COLOR` variables of the file to HEX colors in the default mode
+/// of their collections.
+///
+/// Returns: Variable name => `RRGGBB`
+pub fn color_variables_to_hex(meta: &LocalVariablesMetaDto) -> HashMap<String, String> {
+    meta.variables
+        .values()
+        .filter(|it| it.resolved_type == "COLOR")
+        .filter_map(|it| Some((it.name.to_owned(), resolve_color(meta, &it.id, 0)?)))
+        .collect()
+}
+
+fn resolve_color(meta: &LocalVariablesMetaDto, variable_id: &str, depth: usize) -> Option<String> {
+    if depth > MAX_ALIAS_DEPTH {
+        return None;
+    }
+    let variable = meta.variables.get(variable_id)?;
+    let collection = meta
+        .variable_collections
+        .get(&variable.variable_collection_id)?;
+    match variable.values_by_mode.get(&collection.default_mode_id)? {
+        VariableValueDto::Color(rgba) => Some(to_hex(rgba)),
+        VariableValueDto::Alias(alias) => resolve_color(meta, &alias.id, depth + 1),
+        VariableValueDto::Other(_) => None,
+    }
+}
+
+fn to_hex(c: &RgbaDto) -> String {
+    let channel = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!(
+        "{:02X}{:02X}{:02X}",
+        channel(c.r),
+        channel(c.g),
+        channel(c.b)
+    )
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use lib_figma_fluent::GetLocalVariablesResponse;
+
+    #[test]
+    fn color_variables_to_hex__colors_and_aliases__EXPECT__resolved_in_default_mode() {
+        // Given
+        let json = r#"{
+            "meta": {
+                "variables": {
+                    "VariableID:1": {
+                        "id": "VariableID:1",
+                        "name": "palette/Blue",
+                        "variableCollectionId": "C:1",
+                        "resolvedType": "COLOR",
+                        "valuesByMode": {
+                            "1:0": { "r": 0.0, "g": 0.4, "b": 1.0, "a": 1.0 },
+                            "1:1": { "r": 1.0, "g": 1.0, "b": 1.0, "a": 1.0 }
+                        }
+                    },
+                    "VariableID:2": {
+                        "id": "VariableID:2",
+                        "name": "color/Primary",
+                        "variableCollectionId": "C:1",
+                        "resolvedType": "COLOR",
+                        "valuesByMode": {
+                            "1:0": { "type": "VARIABLE_ALIAS", "id": "VariableID:1" }
+                        }
+                    },
+                    "VariableID:3": {
+                        "id": "VariableID:3",
+                        "name": "spacing/Small",
+                        "variableCollectionId": "C:1",
+                        "resolvedType": "FLOAT",
+                        "valuesByMode": { "1:0": 4 }
+                    }
+                },
+                "variableCollections": {
+                    "C:1": { "id": "C:1", "name": "Theme", "defaultModeId": "1:0" }
+                }
+            }
+        }"#;
+        let response: GetLocalVariablesResponse = serde_json::from_str(json).unwrap();
+
+        // When
+        let colors = color_variables_to_hex(&response.meta);
+
+        // Then
+        assert_eq!(2, colors.len());
+        assert_eq!("0066FF", colors["palette/Blue"]);
+        assert_eq!("0066FF", colors["color/Primary"]);
+    }
+}
@@ -6,7 +6,25 @@ use std::{
     time::UNIX_EPOCH,
 };
 
+use crate::{
+    Result, Target,
+    figma::{NodeMetadata, indexing::RemoteIndex},
+    setup_cache, targets_from_resource,
+};
+use lib_cache::Digester;
 use log::warn;
+use ordermap::OrderMap;
+use phase_loading::{
+    AndroidDensity, AndroidDrawableProfile, AndroidWebpProfile, ColorMapping, ComposePreview,
+    ComposeProfile, ExportScale, FaviconProfile, FaviconSize, FlutterProfile, LimitAction,
+    NameCase, NamingRules, PdfProfile, PngOptimizationLevel, PngProfile, PostProcessCommand,
+    Profile, ReactProfile, ResourceVariant, ResourceVariants, SingleNamePattern, SvgProfile,
+    VectorLimits, WebpMethod, WebpProfile, WebpQuality, Workspace,
+};
+use std::collections::BTreeSet;
+
+// all digests are computed with the algorithm versioned by the cache
+pub use lib_cache::{DigestAlgorithm, digest};
 
 /// Generate a fingerprint for a file based on metadata
 ///
@@ -43,11 +61,11 @@ pub fn get_file_fingerprint(path: &Path) -> std::io::Result<u64> {
         })
         .as_millis();
     // Generate CacheKey for this file
-    let mut hasher = xxhash_rust::xxh64::Xxh64::default();
+    let mut hasher = Digester::new();
     hasher.write(path.to_string_lossy().as_bytes());
     hasher.write_u64(metadata.len());
     hasher.write_u128(last_modified);
-    Ok(hasher.finish())
+    Ok(hasher.digest())
 }
 
 /// Generate a content-based digest for a file
@@ -73,7 +91,7 @@ pub fn get_file_fingerprint(path: &Path) -> std::io::Result<u64> {
 pub fn get_file_digest(path: &Path) -> std::io::Result<u64> {
     let input = File::open(path)?;
     let mut reader = BufReader::new(input);
-    let mut hasher = xxhash_rust::xxh64::Xxh64::default();
+    let mut hasher = Digester::new();
     let mut buffer = [0; 1024];
 
     loop {
@@ -83,9 +101,561 @@ pub fn get_file_digest(path: &Path) -> std::io::Result<u64> {
         }
         hasher.write(&buffer[..count]);
     }
-    Ok(hasher.finish())
+    Ok(hasher.digest())
+}
+
+// region: Target digest
+
+/// Digest of the target, which changes whenever its outputs may change
+pub struct TargetDigest {
+    /// Label of the resource followed by the variant name, if any
+    pub target: String,
+    /// `None` if the Figma node of the target is not in the cached index of its remote
+    pub digest: Option<u64>,
+}
+
+/// Computes digests of all targets of the workspace without a single network request.
+///
+/// Hashes of the Figma nodes are taken from the remote indexes cached by the previous
+/// `fetch` or `import`, so the digests of never indexed targets are unknown.
+pub fn get_target_digests(ws: &Workspace) -> Result<Vec<TargetDigest>> {
+    let cache = setup_cache(&ws.context.cache_dir)?;
+    let mut remote_to_targets = OrderMap::<_, Vec<Target>>::new();
+    for res in ws.packages.iter().flat_map(|pkg| &pkg.resources) {
+        remote_to_targets
+            .entry(&res.attrs.remote)
+            .or_default()
+            .extend(targets_from_resource(res));
+    }
+
+    let mut digests = Vec::new();
+    for (remote, targets) in remote_to_targets {
+        let index = RemoteIndex::get_cached(&cache, remote)?;
+        for target in targets {
+            let node = index
+                .as_ref()
+                .and_then(|it| it.name_to_node.get(target.figma_name()));
+            digests.push(TargetDigest {
+                target: target.qualified_name(),
+                digest: node
+                    .map(|node| get_target_digest(&target, node, &ws.context.workspace_dir)),
+            });
+        }
+    }
+    Ok(digests)
+}
+
+/// Generate a digest of everything the outputs of the target depend on
+///
+/// Combines the hash of the Figma node with the effective profile of the resource,
+/// the variant of the target and the figx version, as transformations may produce other
+/// files in other versions. The package is hashed relative to the workspace, so the digest
+/// is stable between runs and machines, and can be used as a cache key by external build systems.
+pub fn get_target_digest(target: &Target, node: &NodeMetadata, workspace_dir: &Path) -> u64 {
+    let mut hasher = Digester::new();
+    env!("CARGO_PKG_VERSION").digest(&mut hasher);
+    target.attrs.remote.file_key.digest(&mut hasher);
+    node.id.digest(&mut hasher);
+    hasher.write_u64(node.hash);
+    target.attrs.label.to_string().digest(&mut hasher);
+    target
+        .attrs
+        .package_dir
+        .strip_prefix(workspace_dir)
+        .unwrap_or(&target.attrs.package_dir)
+        .digest(&mut hasher);
+    target.id.digest(&mut hasher);
+    target.figma_name().digest(&mut hasher);
+    target.output_name().digest(&mut hasher);
+    target.scale.digest(&mut hasher);
+    target.attrs.use_absolute_bounds.digest(&mut hasher);
+    target.profile.digest(&mut hasher);
+    hasher.digest()
+}
+
+/// Values which can be written to the digest of a target.
+///
+/// Every value is written along with its length or presence, so that
+/// different sequences of values never produce the same bytes.
+trait Digestible {
+    fn digest(&self, hasher: &mut Digester);
+}
+
+impl<T: Digestible + ?Sized> Digestible for &T {
+    fn digest(&self, hasher: &mut Digester) {
+        (**self).digest(hasher)
+    }
+}
+
+impl Digestible for str {
+    fn digest(&self, hasher: &mut Digester) {
+        hasher.write_u64(self.len() as u64);
+        hasher.write(self.as_bytes());
+    }
+}
+
+impl Digestible for String {
+    fn digest(&self, hasher: &mut Digester) {
+        self.as_str().digest(hasher)
+    }
+}
+
+impl Digestible for Path {
+    fn digest(&self, hasher: &mut Digester) {
+        self.to_string_lossy().as_ref().digest(hasher)
+    }
+}
+
+impl Digestible for std::path::PathBuf {
+    fn digest(&self, hasher: &mut Digester) {
+        self.as_path().digest(hasher)
+    }
+}
+
+impl Digestible for bool {
+    fn digest(&self, hasher: &mut Digester) {
+        hasher.write_u8(*self as u8)
+    }
+}
+
+impl Digestible for f32 {
+    fn digest(&self, hasher: &mut Digester) {
+        hasher.write_u32(self.to_bits())
+    }
+}
+
+impl Digestible for usize {
+    fn digest(&self, hasher: &mut Digester) {
+        hasher.write_u64(*self as u64)
+    }
+}
+
+impl<T: Digestible> Digestible for Option<T> {
+    fn digest(&self, hasher: &mut Digester) {
+        match self {
+            Some(value) => {
+                hasher.write_u8(1);
+                value.digest(hasher);
+            }
+            None => hasher.write_u8(0),
+        }
+    }
+}
+
+impl<T: Digestible> Digestible for [T] {
+    fn digest(&self, hasher: &mut Digester) {
+        hasher.write_u64(self.len() as u64);
+        self.iter().for_each(|it| it.digest(hasher));
+    }
+}
+
+impl<T: Digestible> Digestible for Vec<T> {
+    fn digest(&self, hasher: &mut Digester) {
+        self.as_slice().digest(hasher)
+    }
+}
+
+macro_rules! digestible_deref {
+    ($($t:ty),+) => {
+        $(impl Digestible for $t {
+            fn digest(&self, hasher: &mut Digester) {
+                (**self).digest(hasher)
+            }
+        })+
+    };
 }
 
+digestible_deref!(ExportScale, WebpQuality);
+
+impl Digestible for WebpMethod {
+    fn digest(&self, hasher: &mut Digester) {
+        hasher.write_u8(**self)
+    }
+}
+
+impl Digestible for PngOptimizationLevel {
+    fn digest(&self, hasher: &mut Digester) {
+        hasher.write_u8(**self)
+    }
+}
+
+impl Digestible for FaviconSize {
+    fn digest(&self, hasher: &mut Digester) {
+        hasher.write_u32(**self)
+    }
+}
+
+impl Digestible for SingleNamePattern {
+    fn digest(&self, hasher: &mut Digester) {
+        self.as_ref().digest(hasher)
+    }
+}
+
+impl Digestible for PostProcessCommand {
+    fn digest(&self, hasher: &mut Digester) {
+        self.to_string().digest(hasher)
+    }
+}
+
+impl Digestible for NamingRules {
+    fn digest(&self, hasher: &mut Digester) {
+        let NamingRules {
+            strip,
+            case,
+            prefix,
+            suffix,
+        } = self;
+        strip.digest(hasher);
+        case.digest(hasher);
+        prefix.digest(hasher);
+        suffix.digest(hasher);
+    }
+}
+
+impl Digestible for NameCase {
+    fn digest(&self, hasher: &mut Digester) {
+        use NameCase::*;
+        hasher.write_u8(match self {
+            Snake => 0,
+            Kebab => 1,
+            Camel => 2,
+            Pascal => 3,
+        });
+    }
+}
+
+impl Digestible for AndroidDensity {
+    fn digest(&self, hasher: &mut Digester) {
+        use AndroidDensity::*;
+        hasher.write_u8(match self {
+            LDPI => 0,
+            MDPI => 1,
+            HDPI => 2,
+            XHDPI => 3,
+            XXHDPI => 4,
+            XXXHDPI => 5,
+        })
+    }
+}
+
+impl Digestible for ResourceVariants {
+    fn digest(&self, hasher: &mut Digester) {
+        hasher.write_u64(self.all_variants.len() as u64);
+        for (id, variant) in &self.all_variants {
+            id.digest(hasher);
+            variant.digest(hasher);
+        }
+        // the order of the set is random, sort it to keep the digest stable
+        let use_variants = self.use_variants.as_ref().map(|it| {
+            it.iter()
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect::<Vec<_>>()
+        });
+        use_variants.digest(hasher);
+    }
+}
+
+impl Digestible for ResourceVariant {
+    fn digest(&self, hasher: &mut Digester) {
+        let ResourceVariant {
+            output_name,
+            figma_name,
+            scale,
+        } = self;
+        output_name.digest(hasher);
+        figma_name.digest(hasher);
+        scale.digest(hasher);
+    }
+}
+
+impl Digestible for ColorMapping {
+    fn digest(&self, hasher: &mut Digester) {
+        let ColorMapping { from, to, imports } = self;
+        from.digest(hasher);
+        to.digest(hasher);
+        imports.digest(hasher);
+    }
+}
+
+impl Digestible for ComposePreview {
+    fn digest(&self, hasher: &mut Digester) {
+        let ComposePreview { imports, code } = self;
+        imports.digest(hasher);
+        code.digest(hasher);
+    }
+}
+
+impl Digestible for VectorLimits {
+    fn digest(&self, hasher: &mut Digester) {
+        let VectorLimits {
+            max_paths,
+            max_commands,
+            on_exceeded,
+        } = self;
+        max_paths.digest(hasher);
+        max_commands.digest(hasher);
+        match on_exceeded {
+            LimitAction::Warn => hasher.write_u8(0),
+            LimitAction::Fail => hasher.write_u8(1),
+            LimitAction::Fallback(profile) => {
+                hasher.write_u8(2);
+                profile.as_ref().digest(hasher);
+            }
+        }
+    }
+}
+
+/// Writes all fields of the profile, destructuring keeps the digest in sync with the profile
+macro_rules! digest_fields {
+    ($hasher:ident, $($field:ident),+) => {
+        $($field.digest($hasher);)+
+    };
+}
+
+impl Digestible for Profile {
+    fn digest(&self, hasher: &mut Digester) {
+        use Profile::*;
+        self.kind().as_str().digest(hasher);
+        match self {
+            Png(PngProfile {
+                remote_id,
+                scale,
+                output_dir,
+                variants,
+                legacy_loader,
+                optimize,
+                optimization_level,
+                post_process,
+                naming,
+            }) => digest_fields!(
+                hasher,
+                remote_id,
+                scale,
+                output_dir,
+                variants,
+                legacy_loader,
+                optimize,
+                optimization_level,
+                post_process,
+                naming
+            ),
+            Svg(SvgProfile {
+                remote_id,
+                output_dir,
+                variants,
+                post_process,
+                naming,
+            }) => digest_fields!(
+                hasher,
+                remote_id,
+                output_dir,
+                variants,
+                post_process,
+                naming
+            ),
+            Pdf(PdfProfile {
+                remote_id,
+                output_dir,
+                allow_raster,
+                preview_scales,
+                local_renderer,
+                variants,
+                post_process,
+                naming,
+            }) => digest_fields!(
+                hasher,
+                remote_id,
+                output_dir,
+                allow_raster,
+                preview_scales,
+                local_renderer,
+                variants,
+                post_process,
+                naming
+            ),
+            Webp(WebpProfile {
+                remote_id,
+                scale,
+                quality,
+                lossless,
+                alpha_quality,
+                method,
+                output_dir,
+                variants,
+                legacy_loader,
+                post_process,
+                naming,
+            }) => digest_fields!(
+                hasher,
+                remote_id,
+                scale,
+                quality,
+                lossless,
+                alpha_quality,
+                method,
+                output_dir,
+                variants,
+                legacy_loader,
+                post_process,
+                naming
+            ),
+            Compose(ComposeProfile {
+                remote_id,
+                src_dir,
+                package,
+                kotlin_explicit_api,
+                extension_target,
+                file_suppress_lint,
+                color_mappings,
+                preview,
+                variants,
+                composable_get,
+                property_name_template,
+                receiver_object,
+                combine_variants,
+                limits,
+                multiplatform,
+                identifier_prefix,
+                document_sources,
+                post_process,
+                naming,
+            }) => digest_fields!(
+                hasher,
+                remote_id,
+                src_dir,
+                package,
+                kotlin_explicit_api,
+                extension_target,
+                file_suppress_lint,
+                color_mappings,
+                preview,
+                variants,
+                composable_get,
+                property_name_template,
+                receiver_object,
+                combine_variants,
+                limits,
+                multiplatform,
+                identifier_prefix,
+                document_sources,
+                post_process,
+                naming
+            ),
+            AndroidWebp(AndroidWebpProfile {
+                remote_id,
+                android_res_dir,
+                quality,
+                lossless,
+                alpha_quality,
+                method,
+                scales,
+                night,
+                dark_source,
+                legacy_loader,
+                post_process,
+                naming,
+            }) => digest_fields!(
+                hasher,
+                remote_id,
+                android_res_dir,
+                quality,
+                lossless,
+                alpha_quality,
+                method,
+                scales,
+                night,
+                dark_source,
+                legacy_loader,
+                post_process,
+                naming
+            ),
+            AndroidDrawable(AndroidDrawableProfile {
+                remote_id,
+                android_res_dir,
+                night,
+                dark_source,
+                auto_mirrored,
+                monochrome,
+                limits,
+                post_process,
+                naming,
+            }) => digest_fields!(
+                hasher,
+                remote_id,
+                android_res_dir,
+                night,
+                dark_source,
+                auto_mirrored,
+                monochrome,
+                limits,
+                post_process,
+                naming
+            ),
+            Flutter(FlutterProfile {
+                remote_id,
+                assets_dir,
+                scales,
+                dart_class,
+                dart_file,
+                legacy_loader,
+                optimize,
+                optimization_level,
+                post_process,
+                naming,
+            }) => digest_fields!(
+                hasher,
+                remote_id,
+                assets_dir,
+                scales,
+                dart_class,
+                dart_file,
+                legacy_loader,
+                optimize,
+                optimization_level,
+                post_process,
+                naming
+            ),
+            React(ReactProfile {
+                remote_id,
+                output_dir,
+                current_color,
+                variants,
+                post_process,
+                naming,
+            }) => digest_fields!(
+                hasher,
+                remote_id,
+                output_dir,
+                current_color,
+                variants,
+                post_process,
+                naming
+            ),
+            Favicon(FaviconProfile {
+                remote_id,
+                output_dir,
+                sizes,
+                webmanifest,
+                variants,
+                post_process,
+                naming,
+            }) => digest_fields!(
+                hasher,
+                remote_id,
+                output_dir,
+                sizes,
+                webmanifest,
+                variants,
+                post_process,
+                naming
+            ),
+        }
+    }
+}
+
+// endregion: Target digest
+
 #[cfg(test)]
 #[allow(non_snake_case)]
 mod test {
@@ -114,4 +684,18 @@ mod test {
         let fingerprint = get_file_digest(&file_path).unwrap();
         assert_eq!("9157857784689950130", format!("{:?}", fingerprint));
     }
+
+    #[test]
+    fn Digestible__same_bytes_split_differently__EXPECT__different_digests() {
+        // Given
+        let digest_of = |values: &[&str]| {
+            let mut hasher = Digester::new();
+            values.digest(&mut hasher);
+            hasher.digest()
+        };
+
+        // Then
+        assert_ne!(digest_of(&["ab", "c"]), digest_of(&["a", "bc"]));
+        assert_eq!(digest_of(&["ab", "c"]), digest_of(&["ab", "c"]));
+    }
 }
@@ -0,0 +1,168 @@
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender, TryRecvError, bounded, unbounded};
+use log::debug;
+use rayon::Yield;
+use std::{
+    any::Any,
+    cell::Cell,
+    panic::{AssertUnwindSafe, catch_unwind, resume_unwind},
+    thread::JoinHandle,
+    time::Duration,
+};
+
+/// How long a rayon thread with nothing else to do waits before looking for new work
+const IDLE_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Targets started by a waiting thread may wait for their own requests, each of them
+/// on top of the stack of the previous one, so the depth is limited
+const MAX_NESTED_WAITS: usize = 8;
+
+thread_local! {
+    static NESTED_WAITS: Cell<usize> = const { Cell::new(0) };
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+type JobResult<T> = std::result::Result<T, Box<dyn Any + Send>>;
+
+/// Dedicated threads for the blocking network requests, e.g. downloads of the images.
+///
+/// Rayon threads are left for the CPU-bound transformations: while a request is in
+/// the lane, the rayon thread that sent it imports other targets, so the number of
+/// requests in flight is limited by the lane instead of the number of CPUs.
+#[derive(Default)]
+pub struct IoLane {
+    /// `None` if the lane has no threads, then requests are made by the calling thread
+    jobs: Option<Sender<Job>>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl IoLane {
+    pub fn new(num_threads: usize) -> Self {
+        if num_threads == 0 {
+            return Self::default();
+        }
+        debug!(target: "Setup", "set I/O lane concurrency to {num_threads}");
+        let (jobs, pending) = unbounded::<Job>();
+        let threads = (0..num_threads)
+            .map(|i| {
+                let pending = pending.clone();
+                std::thread::Builder::new()
+                    .name(format!("figx-io-{i}"))
+                    .spawn(move || pending.iter().for_each(|job| job()))
+                    .expect("failed to spawn I/O thread")
+            })
+            .collect();
+        Self {
+            jobs: Some(jobs),
+            threads,
+        }
+    }
+
+    /// Runs the blocking `job` in the lane and returns its result.
+    ///
+    /// Must not be called while holding locks: the calling rayon thread may start other
+    /// targets in the meantime, and they may need the same lock.
+    pub fn run<T: Send + 'static>(&self, job: impl FnOnce() -> T + Send + 'static) -> T {
+        let Some(jobs) = &self.jobs else {
+            return job();
+        };
+        let (tx, rx) = bounded::<JobResult<T>>(1);
+        let job: Job = Box::new(move || {
+            // the thread must survive the panic to serve other requests
+            let _ = tx.send(catch_unwind(AssertUnwindSafe(job)));
+        });
+        jobs.send(job)
+            .expect("I/O lane is alive as long as it is referenced");
+        match wait(rx) {
+            Ok(result) => result,
+            Err(panic) => resume_unwind(panic),
+        }
+    }
+}
+
+impl Drop for IoLane {
+    fn drop(&mut self) {
+        // threads finish once there are no more senders
+        self.jobs.take();
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn wait<T>(rx: Receiver<JobResult<T>>) -> JobResult<T> {
+    let nested_waits = NESTED_WAITS.get();
+    let may_yield = rayon::current_thread_index().is_some() && nested_waits < MAX_NESTED_WAITS;
+    loop {
+        if !may_yield {
+            return rx.recv().expect("I/O job always responds");
+        }
+        match rx.try_recv() {
+            Ok(result) => return result,
+            Err(TryRecvError::Disconnected) => unreachable!("I/O job always responds"),
+            Err(TryRecvError::Empty) => {}
+        }
+        NESTED_WAITS.set(nested_waits + 1);
+        let yielded = rayon::yield_now();
+        NESTED_WAITS.set(nested_waits);
+        if !matches!(yielded, Some(Yield::Executed)) {
+            match rx.recv_timeout(IDLE_POLL_INTERVAL) {
+                Ok(result) => return result,
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => unreachable!("I/O job always responds"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+    #[test]
+    fn IoLane__many_jobs_from_rayon__EXPECT__all_results_returned() {
+        // Given
+        let lane = IoLane::new(4);
+
+        // When
+        let results = (0..64)
+            .into_par_iter()
+            .map(|i| {
+                lane.run(move || {
+                    std::thread::sleep(Duration::from_millis(1));
+                    i * 2
+                })
+            })
+            .collect::<Vec<_>>();
+
+        // Then
+        assert_eq!((0..64).map(|i| i * 2).collect::<Vec<_>>(), results);
+    }
+
+    #[test]
+    fn IoLane__without_threads__EXPECT__job_runs_on_caller() {
+        // Given
+        let lane = IoLane::default();
+        let caller = std::thread::current().id();
+
+        // When
+        let thread = lane.run(|| std::thread::current().id());
+
+        // Then
+        assert_eq!(caller, thread);
+    }
+
+    #[test]
+    fn IoLane__panicking_job__EXPECT__panic_propagated_and_lane_alive() {
+        // Given
+        let lane = IoLane::new(1);
+
+        // When
+        let result = catch_unwind(AssertUnwindSafe(|| lane.run(|| -> i32 { panic!("boom") })));
+
+        // Then
+        assert!(result.is_err());
+        assert_eq!(42, lane.run(|| 42));
+    }
+}
@@ -0,0 +1,260 @@
+use crate::{MaterializedFile, Result, get_file_digest};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs::File,
+    hash::Hasher,
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+/// Append-only log of the import: the planned targets first, then every
+/// completed target with the hash of its node and the digest of its output file.
+///
+/// The journal is removed when the import succeeds. After a crash or an
+/// interruption it lets the next import skip the targets which were already
+/// completed, if the configuration and their nodes have not changed and their
+/// files are intact.
+#[derive(Default)]
+pub struct ImportJournal {
+    file: Mutex<Option<File>>,
+    path: Option<PathBuf>,
+    /// Targets completed by the interrupted import
+    resumable: HashMap<String, CompletedTarget>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum JournalRecord {
+    Planned {
+        config_digest: u64,
+        targets: Vec<String>,
+    },
+    Completed(CompletedTarget),
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct CompletedTarget {
+    target: String,
+    /// Hash of the node the target was imported from
+    node_hash: u64,
+    /// Main output file of the target and its digest
+    file: Option<(PathBuf, u64)>,
+}
+
+impl ImportJournal {
+    /// Starts a new journal at `path`. If `resume` is set, the targets completed
+    /// according to the previous journal are carried over.
+    pub fn start(
+        path: &Path,
+        config_digest: u64,
+        targets: &[String],
+        resume: bool,
+    ) -> Result<Self> {
+        let resumable = match resume {
+            true => read_resumable(path, config_digest),
+            false => HashMap::new(),
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut file = File::create(path)?;
+        let planned = JournalRecord::Planned {
+            config_digest,
+            targets: targets.to_vec(),
+        };
+        write_record(&mut file, &planned)?;
+        // a crash during the resumed import must not lose them
+        for completed in resumable.values() {
+            write_record(&mut file, &JournalRecord::Completed(completed.clone()))?;
+        }
+        Ok(Self {
+            file: Mutex::new(Some(file)),
+            path: Some(path.to_path_buf()),
+            resumable,
+        })
+    }
+
+    /// Returns `Some` with the output file of the target (if it has one), if the
+    /// target was completed by the interrupted import from the same node and the
+    /// file is intact
+    pub fn resumed(&self, target: &str, node_hash: u64) -> Option<Option<MaterializedFile>> {
+        let completed = self.resumable.get(target)?;
+        if completed.node_hash != node_hash {
+            return None;
+        }
+        let Some((path, digest)) = &completed.file else {
+            return Some(None);
+        };
+        let metadata = path.metadata().ok()?;
+        if get_file_digest(path).ok()? != *digest {
+            return None;
+        }
+        Some(Some(MaterializedFile {
+            path: path.clone(),
+            bytes: metadata.len() as usize,
+            written: false,
+            extra_paths: Vec::new(),
+        }))
+    }
+
+    pub fn complete(&self, target: &str, node_hash: u64, file: Option<&MaterializedFile>) {
+        let mut guard = self.file.lock().unwrap();
+        let Some(journal) = guard.as_mut() else {
+            return;
+        };
+        let file = match file {
+            None => None,
+            Some(it) => match get_file_digest(&it.path) {
+                Ok(digest) => Some((it.path.clone(), digest)),
+                // cannot be verified on resume
+                Err(_) => return,
+            },
+        };
+        let record = JournalRecord::Completed(CompletedTarget {
+            target: target.to_owned(),
+            node_hash,
+            file,
+        });
+        if let Err(e) = write_record(journal, &record) {
+            warn!(target: "Journal", "unable to record `{target}`: {e}");
+        }
+    }
+
+    /// Removes the journal after the successful import, there is nothing to resume
+    pub fn remove(&self) {
+        self.file.lock().unwrap().take();
+        if let Some(path) = &self.path {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+fn write_record(file: &mut File, record: &JournalRecord) -> std::io::Result<()> {
+    let mut line = serde_json::to_vec(record)?;
+    line.push(b'\n');
+    file.write_all(&line)?;
+    file.flush()
+}
+
+fn read_resumable(path: &Path, config_digest: u64) -> HashMap<String, CompletedTarget> {
+    let Ok(file) = File::open(path) else {
+        warn!(target: "Resume", "no interrupted import to resume");
+        return HashMap::new();
+    };
+    let mut records = BufReader::new(file)
+        .lines()
+        .map_while(|line| line.ok())
+        // the last line may be cut off by the crash
+        .map_while(|line| serde_json::from_str::<JournalRecord>(&line).ok());
+    match records.next() {
+        Some(JournalRecord::Planned {
+            config_digest: planned,
+            ..
+        }) if planned == config_digest => (),
+        _ => {
+            warn!(target: "Resume", "configuration has changed since the interrupted import, importing everything");
+            return HashMap::new();
+        }
+    }
+    let resumable = records
+        .filter_map(|record| match record {
+            JournalRecord::Completed(it) => Some((it.target.clone(), it)),
+            JournalRecord::Planned { .. } => None,
+        })
+        .collect::<HashMap<_, _>>();
+    debug!(target: "Resume", "{} completed targets in the journal", resumable.len());
+    resumable
+}
+
+/// Digest of the configuration files, resuming is only safe if none of them has changed
+pub fn config_digest(files: &[&Path]) -> u64 {
+    let mut hasher = lib_cache::Digester::new();
+    for file in files {
+        hasher.write(file.to_string_lossy().as_bytes());
+        hasher.write_u64(get_file_digest(file).unwrap_or_default());
+    }
+    hasher.digest()
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    fn targets() -> Vec<String> {
+        vec!["//icons:ic_star".to_string(), "//icons:ic_moon".to_string()]
+    }
+
+    #[test]
+    fn ImportJournal__resume_after_crash__EXPECT__intact_completed_targets_skipped() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let journal_path = dir.path().join("import-journal.jsonl");
+        let star = dir.path().join("ic_star.svg");
+        let moon = dir.path().join("ic_moon.svg");
+        std::fs::write(&star, "<svg/>").unwrap();
+        std::fs::write(&moon, "<svg/>").unwrap();
+        let journal = ImportJournal::start(&journal_path, 42, &targets(), false).unwrap();
+        for (target, path) in [("//icons:ic_star", &star), ("//icons:ic_moon", &moon)] {
+            let file = MaterializedFile {
+                path: path.clone(),
+                bytes: 6,
+                written: true,
+                extra_paths: Vec::new(),
+            };
+            journal.complete(target, 1, Some(&file));
+        }
+        drop(journal);
+        std::fs::write(&moon, "<svg>changed</svg>").unwrap();
+
+        // When
+        let journal = ImportJournal::start(&journal_path, 42, &targets(), true).unwrap();
+
+        // Then
+        assert!(journal.resumed("//icons:ic_star", 1).is_some());
+        assert!(
+            journal.resumed("//icons:ic_moon", 1).is_none(),
+            "file was changed"
+        );
+    }
+
+    #[test]
+    fn ImportJournal__resume_with_changed_config__EXPECT__nothing_skipped() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let journal_path = dir.path().join("import-journal.jsonl");
+        let journal = ImportJournal::start(&journal_path, 42, &targets(), false).unwrap();
+        journal.complete("//icons:ic_star", 1, None);
+        drop(journal);
+
+        // When
+        let journal = ImportJournal::start(&journal_path, 43, &targets(), true).unwrap();
+
+        // Then
+        assert!(journal.resumed("//icons:ic_star", 1).is_none());
+    }
+
+    #[test]
+    fn ImportJournal__resume_with_changed_node__EXPECT__changed_target_not_skipped() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let journal_path = dir.path().join("import-journal.jsonl");
+        let journal = ImportJournal::start(&journal_path, 42, &targets(), false).unwrap();
+        journal.complete("//icons:ic_star", 1, None);
+        journal.complete("//icons:ic_moon", 2, None);
+        drop(journal);
+
+        // When
+        let journal = ImportJournal::start(&journal_path, 42, &targets(), true).unwrap();
+
+        // Then
+        assert!(journal.resumed("//icons:ic_star", 1).is_some());
+        assert!(
+            journal.resumed("//icons:ic_moon", 3).is_none(),
+            "node was changed in Figma"
+        );
+    }
+}
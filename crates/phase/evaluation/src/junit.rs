@@ -0,0 +1,168 @@
+use crate::{BuildReport, Result, report::TargetReport, report::TargetStatus};
+use std::{collections::BTreeMap, fmt::Write as _, path::Path, time::Duration};
+
+/// Writes the outcome of every target as a JUnit XML report, so that CI systems show
+/// failed imports in their test UI.
+///
+/// Every remote is a test suite and every target is a test case of it. Targets which
+/// were up to date in the cache are reported as skipped.
+pub fn write_junit_report(report: &BuildReport, path: &Path, duration: Duration) -> Result<()> {
+    let xml = report.with_entries(|entries| render_junit(entries, duration));
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, xml)?;
+    Ok(())
+}
+
+fn render_junit(entries: &[TargetReport], duration: Duration) -> String {
+    let mut suites: BTreeMap<&str, Vec<&TargetReport>> = BTreeMap::new();
+    for entry in entries {
+        suites.entry(entry.remote.as_str()).or_default().push(entry);
+    }
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"figx import\" {} time=\"{:.3}\">",
+        counters(entries.iter()),
+        duration.as_secs_f64(),
+    );
+    for (remote, mut cases) in suites {
+        cases.sort_by(|a, b| (&a.label, &a.variant).cmp(&(&b.label, &b.variant)));
+        let time = cases.iter().map(|it| it.duration_ms).sum::<u128>();
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{}\" {} time=\"{:.3}\">",
+            escape(remote),
+            counters(cases.iter().copied()),
+            time as f64 / 1000.0,
+        );
+        for case in cases {
+            render_test_case(&mut xml, case);
+        }
+        let _ = writeln!(xml, "  </testsuite>");
+    }
+    let _ = writeln!(xml, "</testsuites>");
+    xml
+}
+
+fn render_test_case(xml: &mut String, case: &TargetReport) {
+    let name = match &case.variant {
+        Some(variant) => format!("{} ({variant})", case.label),
+        None => case.label.to_owned(),
+    };
+    // package of the label, e.g. `//icons` for `//icons:ic_star`
+    let classname = case
+        .label
+        .rsplit_once(':')
+        .map_or("", |(package, _)| package);
+    let _ = write!(
+        xml,
+        "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+        escape(&name),
+        escape(classname),
+        case.duration_ms as f64 / 1000.0,
+    );
+    match case.status {
+        TargetStatus::Exported => {
+            let _ = writeln!(xml, "/>");
+        }
+        TargetStatus::Cached => {
+            let _ = writeln!(
+                xml,
+                ">\n      <skipped message=\"up to date\"/>\n    </testcase>"
+            );
+        }
+        TargetStatus::Failed => {
+            let error = case.error.as_deref().unwrap_or("unknown error");
+            let message = error.lines().next().unwrap_or_default();
+            let _ = writeln!(
+                xml,
+                ">\n      <failure message=\"{}\">{}</failure>",
+                escape(message),
+                escape(error),
+            );
+            if !case.logs.is_empty() {
+                let _ = writeln!(
+                    xml,
+                    "      <system-out>{}</system-out>",
+                    escape(&case.logs.join("\n"))
+                );
+            }
+            let _ = writeln!(xml, "    </testcase>");
+        }
+    }
+}
+
+fn counters<'a>(cases: impl Iterator<Item = &'a TargetReport>) -> String {
+    let (mut tests, mut failures, mut skipped) = (0, 0, 0);
+    for case in cases {
+        tests += 1;
+        match case.status {
+            TargetStatus::Failed => failures += 1,
+            TargetStatus::Cached => skipped += 1,
+            TargetStatus::Exported => (),
+        }
+    }
+    format!("tests=\"{tests}\" failures=\"{failures}\" errors=\"0\" skipped=\"{skipped}\"")
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // control characters are not allowed in XML 1.0 at all
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => (),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    fn entry(label: &str, remote: &str, status: TargetStatus) -> TargetReport {
+        TargetReport {
+            remote: remote.to_string(),
+            duration_ms: 1500,
+            ..TargetReport::stub(label, status)
+        }
+    }
+
+    #[test]
+    fn render_junit__mixed_statuses__EXPECT__suite_per_remote_and_case_per_target() {
+        // Given
+        let mut failed = entry("//icons:ic_star", "icons", TargetStatus::Failed);
+        failed.error = Some("node `Star` <not found>\nin remote".to_string());
+        let entries = [
+            failed,
+            entry("//icons:ic_moon", "icons", TargetStatus::Exported),
+            entry("//images:logo", "images", TargetStatus::Cached),
+        ];
+
+        // When
+        let xml = render_junit(&entries, Duration::from_millis(4200));
+
+        // Then
+        assert!(xml.contains(
+            r#"<testsuites name="figx import" tests="3" failures="1" errors="0" skipped="1" time="4.200">"#
+        ));
+        assert!(xml.contains(
+            r#"<testsuite name="icons" tests="2" failures="1" errors="0" skipped="0" time="3.000">"#
+        ));
+        assert!(
+            xml.contains(r#"<testcase name="//icons:ic_moon" classname="//icons" time="1.500"/>"#)
+        );
+        assert!(xml.contains(r#"<failure message="node `Star` &lt;not found&gt;">"#));
+        assert!(xml.contains(r#"<skipped message="up to date"/>"#));
+        assert!(xml.find("ic_moon").unwrap() < xml.find("ic_star").unwrap());
+    }
+}
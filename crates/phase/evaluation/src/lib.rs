@@ -7,36 +7,72 @@ use crossbeam_channel::unbounded;
 use dashmap::DashMap;
 use figma::FigmaRepository;
 use lib_cache::{Cache, CacheConfig};
-use lib_dashboard::{
-    InitDashboardParams, init_dashboard, lifecycle, shutdown_dashboard, track_progress,
-};
+use lib_dashboard::{capture_logs, lifecycle, with_log_context};
 use lib_figma_fluent::FigmaApi;
-use lib_metrics::{Counter, Metrics};
-use log::{debug, error, trace};
+use lib_metrics::{Counter, Histogram, Metrics};
+use log::{debug, error, trace, warn};
 use ordermap::OrderMap;
-use phase_loading::{RemoteSource, Workspace};
-use rayon::iter::{IntoParallelIterator, ParallelBridge, ParallelIterator};
+use phase_loading::{ProfileKind, RemoteSource, Workspace};
+use rayon::iter::{ParallelBridge, ParallelIterator};
 use std::{
     cmp::min,
-    collections::{HashMap, HashSet},
-    path::Path,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
     thread::available_parallelism,
     time::Duration,
 };
 
 pub mod actions;
+mod audit;
+mod cancellation;
+mod ci_summary;
+mod content_store;
+mod dirty;
 mod error;
 pub mod figma;
 mod hashing;
+mod io_lane;
+mod journal;
+mod junit;
+mod manifest;
+mod observer;
+mod profile;
+mod provenance;
+mod report;
+mod scheduling;
+mod summary;
+pub use summary::ImportSummary;
 // pub use actions_old::*;
+pub use audit::{AuditAction, AuditLog};
+pub use cancellation::*;
+pub use content_store::ContentStore;
+pub use dirty::DirtyTracker;
 pub use error::*;
 pub use hashing::*;
+pub use io_lane::IoLane;
+pub use journal::ImportJournal;
+pub use manifest::{ImportManifest, OrphanReason, OrphanedFile};
+pub use observer::*;
+pub use profile::*;
+pub use provenance::{
+    Provenance, ProvenanceCheck, ProvenanceRecord, check_provenance, is_stampable,
+};
+pub use report::*;
 mod targets;
 pub use targets::*;
+mod usage;
+pub use usage::RemoteUsage;
+mod writer;
+pub use writer::{FileWriter, WriteFailure};
 
 use crate::{
-    actions::{ImportAndroidDrawableArgs, import_android_drawable},
+    actions::{
+        ComposeBundles, ImportAndroidDrawableArgs, ImportFaviconArgs, ImportFlutterArgs,
+        ImportReactArgs, ImportThumbnailArgs, ensure_expected_dimensions,
+        generate_dart_assets_classes, import_android_drawable, import_favicon, import_flutter,
+        import_react, import_thumbnail,
+    },
     figma::{
         NodeMetadata,
         indexing::{RemoteIndex, Subscription, SubscriptionHandle},
@@ -49,31 +85,101 @@ pub struct EvalContext {
     pub figma_repository: FigmaRepository,
     pub cache: Cache,
     pub metrics: EvalMetrics,
+    pub report: BuildReport,
+    pub failures: Arc<Mutex<Vec<TargetFailure>>>,
+    pub compose_bundles: Arc<ComposeBundles>,
+    pub writer: Arc<FileWriter>,
+    pub journal: Arc<ImportJournal>,
+    pub dirty: Arc<DirtyTracker>,
+    /// Threads for the downloads, so they don't occupy the rayon ones
+    pub io: Arc<IoLane>,
+    pub observer: Arc<dyn ExecutionObserver>,
+    pub manifest: Arc<ImportManifest>,
+    pub audit: Arc<AuditLog>,
+    /// Stamp the generated files with their provenance, see [`Provenance`]
+    pub stamp_provenance: bool,
 }
 
 #[derive(Clone)]
 pub struct EvalMetrics {
     pub targets_evaluated: Arc<Counter>,
     pub targets_from_cache: Arc<Counter>,
+    /// Evaluation time of every target in seconds
+    pub target_duration: Arc<Histogram>,
+    /// Size of every image received from remote in bytes
+    pub image_size: Arc<Histogram>,
+    /// Outcomes of the targets by the type of their profile, see [`ImportSummary`]
+    pub summary: Arc<ImportSummary>,
 }
 
+/// Buckets of image sizes, from tiny icons to large illustrations
+const IMAGE_SIZE_BUCKETS: &[f64] = &[
+    1024.0, 4096.0, 16384.0, 65536.0, 262144.0, 1048576.0, 4194304.0,
+];
+
 #[derive(Default)]
 pub struct EvalArgs {
     pub fetch: bool,
     pub refetch: bool,
+    pub keep_going: bool,
+    /// Number of targets transformed in parallel, `0` to take it from the workspace or detect
     pub concurrency: usize,
+    /// Number of downloads in flight, `0` to take it from the workspace or derive from `concurrency`
+    pub network_concurrency: usize,
     pub metrics: Metrics,
+    pub cancellation: CancellationToken,
+    /// Where to write `build-report.json` after evaluation, if needed
+    pub report_path: Option<PathBuf>,
+    /// Records the outcomes of the targets into this report, e.g. to read them after evaluation
+    pub build_report: Option<BuildReport>,
+    /// Where to write the JUnit XML report of the targets after evaluation, if needed
+    pub junit_report_path: Option<PathBuf>,
+    /// Figma API client, may be backed by a mirror
+    pub figma_api: FigmaApi,
+    /// Render thumbnails of targets to this directory instead of importing them
+    pub gallery_dir: Option<PathBuf>,
+    /// Write intermediate artifacts of every target to this directory for debugging
+    pub intermediates_dir: Option<PathBuf>,
+    /// Write files from a dedicated thread, grouped by directory
+    pub write_queue: bool,
+    /// Where to keep the journal of completed targets during the import, if needed
+    pub journal_path: Option<PathBuf>,
+    /// Skip targets completed by the interrupted import, see [`ImportJournal`]
+    pub resume: bool,
+    /// Where to keep the list of all imported files, see [`ImportManifest`]
+    pub manifest_path: Option<PathBuf>,
+    /// Compare imported files with the workspace ones instead of writing them
+    pub check: bool,
+    /// Where to append the changes of workspace files, see [`AuditLog`]
+    pub audit_path: Option<PathBuf>,
+    /// Decode imported raster images and check their dimensions before writing them
+    pub validate_images: bool,
+    /// Gets notified about the progress, the dashboard is shown if not set
+    pub observer: Option<Arc<dyn ExecutionObserver>>,
 }
 
 /// Maximum number of parallel jobs if user doesn't specify it explicitly
 const MAX_NUM_THREADS: usize = 8;
 
-pub fn evaluate(ws: Workspace, args: EvalArgs) -> Result<()> {
+/// Downloads mostly wait for the network, so there are more of them than the parallel jobs
+const IO_THREADS_PER_JOB: usize = 4;
+
+/// Maximum number of Figma files indexed at the same time
+const MAX_PARALLEL_FILES: usize = 4;
+
+pub fn evaluate(mut ws: Workspace, args: EvalArgs) -> Result<()> {
+    let _watch = args.cancellation.watch();
     let metrics = args.metrics.clone();
     let evaluation_duration = metrics.duration("figx_evaluation_duration");
     let _instant = evaluation_duration.record();
     // setup rayon thread pool
-    set_up_rayon(args.concurrency);
+    set_up_rayon(match args.concurrency {
+        0 => ws.jobs.cpu.unwrap_or(0),
+        jobs => jobs,
+    });
+    metrics
+        .gauge("figx_jobs")
+        .set(rayon::current_num_threads() as i64);
     let requested_remotes = ws
         .packages
         .iter()
@@ -87,6 +193,13 @@ pub fn evaluate(ws: Workspace, args: EvalArgs) -> Result<()> {
 
     // region: exec
 
+    // branches are resolved before the targets, which are grouped by the remotes
+    let branches_api = match &ws.network.ca_bundle {
+        Some(ca_bundle) => args.figma_api.clone().with_ca_bundle(ca_bundle)?,
+        None => args.figma_api.clone(),
+    };
+    figma::resolve_branches(&mut ws, &branches_api)?;
+
     let mut remote_to_resources = OrderMap::<Arc<RemoteSource>, Vec<Target>>::new();
     let mut requested_targets = 0usize;
     let mut loaded_packages = 0usize;
@@ -105,52 +218,142 @@ pub fn evaluate(ws: Workspace, args: EvalArgs) -> Result<()> {
         .counter("figx_targets_requested")
         .set(requested_targets);
 
-    lifecycle!(
-        target: "@Requested",
-        "{tn} target{tp} from {rn} remote{rp} ({pn} package{pp} loaded)",
-        tn = requested_targets,
-        tp = if requested_targets == 1 { "" } else { "s" },
-        rn = requested_remotes,
-        rp = if requested_remotes == 1 { "" } else { "s" },
-        pn = loaded_packages,
-        pp = if loaded_packages == 1 { "" } else { "s" },
-    );
-    init_dashboard(InitDashboardParams {
+    let requested_remote_sources = remote_to_resources.keys().cloned().collect::<Vec<_>>();
+    let planned_targets = remote_to_resources
+        .values()
+        .flatten()
+        .map(Target::qualified_name)
+        .collect::<Vec<_>>();
+    let ctx = init_eval_context(&ws, args, &metrics, &planned_targets)?;
+    // tiny runs of cached targets finish in a blink, progress UIs would only flicker
+    let quiet = is_tiny_cached_run(&ctx, &ws, &remote_to_resources);
+    ctx.observer.on_evaluation_started(&EvaluationMeta {
         requested_targets,
         requested_remotes,
         loaded_packages,
-        process_name: if args.fetch { "Fetching" } else { "Importing" },
+        process_name: process_name(&ctx.eval_args),
+        tiny_cached_run: quiet,
+        remote_targets: remote_targets(&remote_to_resources),
     });
 
-    let ctx = init_eval_context(&ws, args, &metrics)?;
-    let result = remote_to_resources
-        .into_iter()
-        .par_bridge()
-        .map(|(remote, targets)| {
-            let index = RemoteIndex::new(FigmaApi::default(), ctx.cache.clone());
-            let (handle, subscription) = index.subscribe(
-                remote.as_ref(),
-                ctx.eval_args.fetch || ctx.eval_args.refetch,
-            )?;
-            match subscription {
-                Subscription::FromCache(name_to_node) => {
-                    execute_with_cached_index(&ctx, targets, name_to_node)
-                }
-                Subscription::FromRemote(stream) => {
-                    execute_with_streaming_index(&ctx, targets, stream, handle, remote.clone())
-                }
-            }
-        })
-        .collect::<Result<Vec<_>>>();
+    // remotes are indexed by a bounded pool of threads outside of the rayon one, so heavy
+    // indexing requests of different files run at the same time, while the targets of
+    // all remotes share the rayon pool
+    let files =
+        scheduling::group_by_file(remote_to_resources, |(remote, _)| remote.file_key.as_str());
+    let result = std::thread::scope(|s| {
+        let (tx, rx) = unbounded();
+        let workers = min(files.len(), MAX_PARALLEL_FILES);
+        for remotes in files {
+            let _ = tx.send(remotes);
+        }
+        drop(tx);
+        let file_threads = (0..workers)
+            .map(|_| {
+                let ctx = &ctx;
+                let rx = rx.clone();
+                // remotes of one file are indexed one after another, sharing its rate limit
+                s.spawn(move || {
+                    for remotes in rx.iter() {
+                        for (remote, targets) in remotes {
+                            import_remote(ctx, remote, targets)?;
+                        }
+                    }
+                    Ok(())
+                })
+            })
+            .collect::<Vec<_>>();
+        file_threads
+            .into_iter()
+            .map(|thread| {
+                thread.join().unwrap_or_else(|_| {
+                    Err(Error::IO(std::io::Error::other("remote thread panicked")))
+                })
+            })
+            .collect::<Result<Vec<_>>>()
+    });
+    let result = match result {
+        Ok(_) if !ctx.eval_args.cancellation.is_cancelled() => report_incomplete_bundles(&ctx),
+        result => result.map(|_| ()),
+    };
+    let result = match ctx.writer.finish() {
+        Ok(unpublished) => {
+            let published = fail_unpublished_targets(&ctx, unpublished);
+            result.and(published)
+        }
+        Err(e) if result.is_ok() => Err(e),
+        Err(_) => result,
+    };
 
     // endregion: exec
     drop(_instant);
-    shutdown_dashboard();
+    ctx.observer.on_evaluation_finished();
+
+    let remote_usage = usage::remote_usage(&requested_remote_sources, &ctx.eval_args.figma_api);
+    usage::record_usage_metrics(&metrics, &remote_usage);
+    usage::log_usage_summary(&remote_usage);
+    if !quiet {
+        log_import_summary(&ctx, evaluation_duration.get(), &remote_usage);
+    }
+
+    let report_result = ctx.eval_args.report_path.as_ref().map(|path| {
+        ctx.report
+            .write(path, evaluation_duration.get(), &remote_usage)
+    });
+    if let Some(Err(e)) = report_result {
+        warn!(target: "Report", "unable to write build report: {e}");
+    }
+    let junit_result = ctx
+        .eval_args
+        .junit_report_path
+        .as_ref()
+        .map(|path| junit::write_junit_report(&ctx.report, path, evaluation_duration.get()));
+    if let Some(Err(e)) = junit_result {
+        warn!(target: "Report", "unable to write JUnit report: {e}");
+    }
+    if let Err(e) = ci_summary::write_github_step_summary(
+        &ctx.report,
+        process_name(&ctx.eval_args),
+        &format_duration(evaluation_duration.get()),
+        &ws.context.workspace_dir,
+    ) {
+        warn!(target: "Report", "unable to write GitHub step summary: {e}");
+    }
 
     // Извлекаем ошибку, если она была
+    let failures = std::mem::take(&mut *ctx.failures.lock().unwrap());
+
+    // labels are imported completely only if nothing failed
+    let completed =
+        result.is_ok() && failures.is_empty() && !ctx.eval_args.cancellation.is_cancelled();
+    let imported_labels = match completed {
+        true => ws
+            .packages
+            .iter()
+            .flat_map(|pkg| &pkg.resources)
+            .map(|res| res.attrs.label.to_string())
+            .collect::<Vec<_>>(),
+        false => Vec::new(),
+    };
+    if let Err(e) = ctx
+        .manifest
+        .finish(imported_labels.iter().map(String::as_str))
+    {
+        warn!(target: "Manifest", "unable to save the manifest of imported files: {e}");
+    }
     match result {
         Err(e) => Err(e),
+        Ok(_) if ctx.eval_args.cancellation.is_cancelled() => Err(Error::Cancelled),
+        Ok(_) if !failures.is_empty() => Err(Error::TargetsFailed(failures)),
         Ok(_) => {
+            if !ctx.eval_args.fetch && ctx.eval_args.gallery_dir.is_none() {
+                generate_dart_assets_classes(&ctx, &ws)?;
+            }
+            ctx.journal.remove();
+            let out_of_date = ctx.writer.out_of_date();
+            if !out_of_date.is_empty() {
+                return Err(Error::OutOfDate(out_of_date));
+            }
             let time = format_duration(evaluation_duration.get());
             let targets_count = ctx.metrics.targets_evaluated.get();
             lifecycle!(
@@ -163,20 +366,63 @@ pub fn evaluate(ws: Workspace, args: EvalArgs) -> Result<()> {
     }
 }
 
+/// Indexes the remote and imports its targets as soon as their nodes are indexed
+fn import_remote(
+    ctx: &EvalContext,
+    remote: Arc<RemoteSource>,
+    targets: Vec<Target<'_>>,
+) -> Result<()> {
+    ctx.eval_args.cancellation.check()?;
+    let index = RemoteIndex::new(
+        ctx.eval_args.figma_api.clone(),
+        ctx.cache.clone(),
+        ctx.figma_repository.throttle().clone(),
+    );
+    let wanted_names = targets
+        .iter()
+        .map(|it| it.figma_name().to_owned())
+        .collect::<BTreeSet<_>>();
+    let result = index
+        .subscribe(
+            remote.as_ref(),
+            ctx.eval_args.fetch || ctx.eval_args.refetch,
+            &wanted_names,
+        )
+        .and_then(|(handle, subscription)| match subscription {
+            Subscription::FromCache(name_to_node) => {
+                ctx.observer.on_remote_indexed(&remote.id);
+                execute_with_cached_index(ctx, targets, name_to_node)
+            }
+            Subscription::FromRemote(stream) => {
+                execute_with_streaming_index(ctx, targets, stream, handle, remote.clone())
+            }
+        });
+    keep_going_or_fail(ctx, remote.to_string(), result, Vec::new())
+}
+
 fn execute_with_cached_index(
     ctx: &EvalContext,
-    targets: Vec<Target>,
+    mut targets: Vec<Target>,
     name_to_node: HashMap<String, NodeMetadata>,
 ) -> Result<()> {
-    targets.into_par_iter().try_for_each(|target| {
-        let tracker = track_progress(target.attrs.label.name.to_string());
-        let node = name_to_node
-            .get(target.figma_name())
-            .ok_or_else::<Error, _>(|| (&target).into())?;
-        let result = import_target(target, ctx, &node);
+    scheduling::sort_slowest_first(&mut targets, &name_to_node);
+    // bridged, so that free threads pick the remaining targets in this order instead of
+    // splitting the list into chunks up front
+    targets.into_iter().par_bridge().try_for_each(|target| {
+        ctx.eval_args.cancellation.check()?;
+        let report = ctx.report.start(&target);
+        let qualified_name = target.qualified_name();
+        let meta = TargetMeta::of(&target, &qualified_name);
+        ctx.observer.on_target_started(&meta);
+        let (result, logs) = match name_to_node.get(target.figma_name()) {
+            Some(node) => capture_logs(|| import_with_log_context(target, ctx, node)),
+            None => (Err((&target).into()), Vec::new()),
+        };
+        let elapsed = report.finish(&result, &logs);
+        ctx.metrics.target_duration.observe(elapsed.as_secs_f64());
         ctx.metrics.targets_evaluated.increment();
-        tracker.mark_as_done();
-        result
+        notify_target_done(ctx, &meta, &result, elapsed);
+        keep_going_or_fail(ctx, qualified_name, result.map(|_| ()), logs)
     })
 }
 
@@ -198,26 +444,52 @@ fn execute_with_streaming_index(
 
     let (tx, rx) = unbounded::<(Vec<Target>, NodeMetadata)>();
     let indexing_error: Arc<Mutex<Option<Error>>> = Default::default();
-    let import_result = rayon::scope(|s| {
+    let requested_targets = name_to_targets
+        .iter()
+        .map(|it| it.value().len())
+        .sum::<usize>();
+    // indexing blocks on network, so it must not occupy a thread of the rayon pool
+    let import_result = std::thread::scope(|s| {
         let indexing_error = Arc::clone(&indexing_error);
         let name_to_targets = Arc::clone(&name_to_targets);
-        s.spawn(move |_| {
-            for node in stream {
-                let node = match node {
-                    Ok(node) => node,
-                    Err(e) => {
-                        *indexing_error.lock().unwrap() = Some(e);
+        s.spawn(move || {
+            let metrics = &ctx.eval_args.metrics;
+            let _span = metrics
+                .spans()
+                .subject_span("index_remote", remote.to_string());
+            let mut progress = IndexingProgress {
+                indexed_nodes: 0,
+                found_targets: 0,
+                requested_targets,
+            };
+            ctx.observer.on_remote_indexing(&remote.id, &progress);
+            let index = || {
+                for node in stream {
+                    if ctx.eval_args.cancellation.is_cancelled() {
+                        // the index is incomplete, so it must not be saved to the cache
                         return;
                     }
-                };
-                if let Some((_, targets)) = name_to_targets.remove(&node.name) {
-                    let _ = tx.send((targets, node.clone()));
+                    let node = match node {
+                        Ok(node) => node,
+                        Err(e) => {
+                            *indexing_error.lock().unwrap() = Some(e);
+                            return;
+                        }
+                    };
+                    progress.indexed_nodes += 1;
+                    if let Some((_, targets)) = name_to_targets.remove(&node.name) {
+                        progress.found_targets += targets.len();
+                        let _ = tx.send((targets, node.clone()));
+                    }
+                    ctx.observer.on_remote_indexing(&remote.id, &progress);
                 }
-            }
-            if let Err(e) = handle.commit_cache() {
-                error!("Unable to save indexed remote `{remote}` data to cache");
-                *indexing_error.lock().unwrap() = Some(e)
-            }
+                if let Err(e) = handle.commit_cache() {
+                    error!("Unable to save indexed remote `{remote}` data to cache");
+                    *indexing_error.lock().unwrap() = Some(e)
+                }
+            };
+            index();
+            ctx.observer.on_remote_indexed(&remote.id);
         });
 
         rx.iter().par_bridge().try_for_each(|(targets, node)| {
@@ -225,20 +497,37 @@ fn execute_with_streaming_index(
             // So we dedicate one thread entirely to process them sequentially
             // TODO: find a more efficient solution
             for target in targets {
-                let tracker = track_progress(target.attrs.label.name.to_string());
-                import_target(target, ctx, &node)?;
+                ctx.eval_args.cancellation.check()?;
+                let report = ctx.report.start(&target);
+                let qualified_name = target.qualified_name();
+                let meta = TargetMeta::of(&target, &qualified_name);
+                ctx.observer.on_target_started(&meta);
+                let (result, logs) = capture_logs(|| import_with_log_context(target, ctx, &node));
+                let elapsed = report.finish(&result, &logs);
+                ctx.metrics.target_duration.observe(elapsed.as_secs_f64());
+                notify_target_done(ctx, &meta, &result, elapsed);
+                keep_going_or_fail(ctx, qualified_name, result.map(|_| ()), logs)?;
                 ctx.metrics.targets_evaluated.increment();
-                tracker.mark_as_done();
             }
             Ok(())
         })
     });
 
     // show NODE NOT FOUND error if needed
-    if indexing_error.lock().unwrap().is_none() && import_result.is_ok() {
+    let cancelled = ctx.eval_args.cancellation.is_cancelled();
+    if indexing_error.lock().unwrap().is_none() && import_result.is_ok() && !cancelled {
         for entry in name_to_targets.iter() {
             for res in entry.value() {
-                return Err(res.into());
+                let result: Result<Option<MaterializedFile>> = Err(res.into());
+                ctx.report.start(res).finish(&result, &[]);
+                let qualified_name = res.qualified_name();
+                notify_target_done(
+                    ctx,
+                    &TargetMeta::of(res, &qualified_name),
+                    &result,
+                    Duration::ZERO,
+                );
+                keep_going_or_fail(ctx, res.qualified_name(), result.map(|_| ()), Vec::new())?;
             }
         }
     }
@@ -249,8 +538,67 @@ fn execute_with_streaming_index(
     }
 }
 
-fn import_target(target: Target<'_>, ctx: &EvalContext, node: &NodeMetadata) -> Result<()> {
+/// Attaches the label and the remote of the target to the JSON logs of its import
+fn import_with_log_context(
+    target: Target<'_>,
+    ctx: &EvalContext,
+    node: &NodeMetadata,
+) -> Result<Option<MaterializedFile>> {
+    let label = target.attrs.label.to_string();
+    let remote = Arc::clone(&target.attrs.remote);
+    with_log_context(&label, &remote.id, || import_or_resume(target, ctx, node))
+}
+
+/// Skips the target if it was completed by the interrupted import or nothing has
+/// changed since the last one, otherwise imports it and records it to the journal
+fn import_or_resume(
+    target: Target<'_>,
+    ctx: &EvalContext,
+    node: &NodeMetadata,
+) -> Result<Option<MaterializedFile>> {
+    let qualified_name = target.qualified_name();
+    let label = target.attrs.label.to_string();
+    if let Some(file) = ctx.journal.resumed(&qualified_name, node.hash) {
+        debug!(target: "Resume", "`{qualified_name}` was completed by the interrupted import");
+        ctx.metrics.targets_from_cache.increment();
+        // other files of the target are not known
+        ctx.manifest.skip(&label);
+        return Ok(file);
+    }
+    ensure_expected_dimensions(node, &target)?;
+    let digest = ctx.dirty.digest(&target, node);
+    let clean_output = digest.and_then(|it| ctx.dirty.clean_output(&qualified_name, it));
+    let file = match clean_output {
+        Some(file) => {
+            debug!(target: "Dirty", "`{qualified_name}` is unchanged since the last import");
+            ctx.metrics.targets_from_cache.increment();
+            Some(file)
+        }
+        None => {
+            let file = import_target(target, ctx, node)?;
+            if let (Some(digest), Some(file)) = (digest, &file) {
+                ctx.dirty.record(&qualified_name, digest, file);
+            }
+            file
+        }
+    };
+    ctx.journal
+        .complete(&qualified_name, node.hash, file.as_ref());
+    if let Some(file) = &file {
+        ctx.manifest.record(&label, file);
+    }
+    Ok(file)
+}
+
+fn import_target(
+    target: Target<'_>,
+    ctx: &EvalContext,
+    node: &NodeMetadata,
+) -> Result<Option<MaterializedFile>> {
     use phase_loading::Profile::*;
+    if let Some(dir) = &ctx.eval_args.gallery_dir {
+        return import_thumbnail(&ctx, ImportThumbnailArgs::new(node, target, dir));
+    }
     match target.profile {
         Png(png_profile) => import_png(&ctx, ImportPngArgs::new(node, target, png_profile)),
         Svg(svg_profile) => import_svg(&ctx, ImportSvgArgs::new(node, target, svg_profile)),
@@ -267,6 +615,125 @@ fn import_target(target: Target<'_>, ctx: &EvalContext, node: &NodeMetadata) ->
             &ctx,
             ImportAndroidDrawableArgs::new(node, target, android_drawable_profile),
         ),
+        Flutter(flutter_profile) => {
+            import_flutter(&ctx, ImportFlutterArgs::new(node, target, flutter_profile))
+        }
+        React(react_profile) => {
+            import_react(&ctx, ImportReactArgs::new(node, target, react_profile))
+        }
+        Favicon(favicon_profile) => {
+            import_favicon(&ctx, ImportFaviconArgs::new(node, target, favicon_profile))
+        }
+    }
+}
+
+/// Imports the target with the raster profile instead of the vector one.
+///
+/// Files of all the fallback targets are reported as a single file of the original target.
+pub(crate) fn import_with_fallback(
+    ctx: &EvalContext,
+    target: &Target<'_>,
+    node: &NodeMetadata,
+    fallback: &phase_loading::Profile,
+) -> Result<Option<MaterializedFile>> {
+    let mut output: Option<MaterializedFile> = None;
+    for target in fallback_targets(target, fallback) {
+        let Some(file) = import_target(target, ctx, node)? else {
+            continue;
+        };
+        match output.as_mut() {
+            Some(output) => output.merge(file),
+            None => output = Some(file),
+        }
+    }
+    Ok(output)
+}
+
+/// Tells the observer whether the target is imported or failed
+fn notify_target_done(
+    ctx: &EvalContext,
+    meta: &TargetMeta,
+    result: &Result<Option<MaterializedFile>>,
+    elapsed: Duration,
+) {
+    let (status, bytes_written) = match result {
+        Ok(Some(file)) if file.written => (TargetStatus::Exported, file.bytes),
+        Ok(_) => (TargetStatus::Cached, 0),
+        Err(_) => (TargetStatus::Failed, 0),
+    };
+    ctx.metrics
+        .summary
+        .record(meta.profile_kind, status, bytes_written, elapsed);
+    match result {
+        Ok(_) => ctx.observer.on_target_finished(meta, status, elapsed),
+        Err(e) => ctx.observer.on_target_failed(meta, e),
+    }
+}
+
+/// Table of the targets by profile type, under a single label so it stays in one piece
+fn log_import_summary(ctx: &EvalContext, wall_time: Duration, usage: &[RemoteUsage]) {
+    let lines = ctx.metrics.summary.table(wall_time, usage);
+    if !lines.is_empty() {
+        lifecycle!(target: "@Summary", "{}", lines.join(&format!("\n{: >13}", "")));
+    }
+}
+
+/// Staged files are published after their targets are done, so the targets whose
+/// files are not in the workspace are failed afterwards
+fn fail_unpublished_targets(ctx: &EvalContext, unpublished: Vec<WriteFailure>) -> Result<()> {
+    for failure in &unpublished {
+        ctx.manifest.forget(&failure.path);
+    }
+    for WriteFailure { target, error, .. } in unpublished {
+        keep_going_or_fail(ctx, target, Err(error), Vec::new())?;
+    }
+    Ok(())
+}
+
+/// Variants bundled into one file are written along with the last of them, so the
+/// bundles left without some variants by their failures must be reported explicitly
+fn report_incomplete_bundles(ctx: &EvalContext) -> Result<()> {
+    for (file, imported) in ctx.compose_bundles.incomplete() {
+        let error = Error::IncompleteBundle(format!(
+            "{} is not written, as only variants {} of it were imported",
+            file.display(),
+            imported.join(", "),
+        ));
+        keep_going_or_fail(ctx, file.display().to_string(), Err(error), Vec::new())?;
+    }
+    Ok(())
+}
+
+/// In keep-going mode remembers the failure and lets the evaluation continue,
+/// otherwise returns the error with the logs captured while evaluating the target.
+///
+/// Errors of the remote or the whole evaluation are returned as is, as they are not
+/// caused by the target they were encountered in
+fn keep_going_or_fail(
+    ctx: &EvalContext,
+    target: String,
+    result: Result<()>,
+    logs: Vec<String>,
+) -> Result<()> {
+    match result {
+        Err(Error::Cancelled) => Err(Error::Cancelled),
+        Err(error) if ctx.eval_args.keep_going => {
+            error!(target: "Import", "`{target}` failed, continuing evaluation");
+            ctx.failures.lock().unwrap().push(TargetFailure {
+                target,
+                error,
+                logs,
+            });
+            Ok(())
+        }
+        Err(error) if error.is_target_failure() && !logs.is_empty() => {
+            Err(Error::TargetsFailed(vec![TargetFailure {
+                target,
+                error,
+                logs,
+            }]))
+        }
+        result => result,
     }
 }
 
@@ -298,9 +765,153 @@ pub fn setup_cache(dir: &Path) -> Result<Cache> {
     )?)
 }
 
-fn init_eval_context(ws: &Workspace, args: EvalArgs, metrics: &Metrics) -> Result<EvalContext> {
-    let api = FigmaApi::default();
+fn process_name(args: &EvalArgs) -> &'static str {
+    if args.fetch {
+        "Fetching"
+    } else if args.gallery_dir.is_some() {
+        "Rendering"
+    } else if args.check {
+        "Checking"
+    } else {
+        "Importing"
+    }
+}
+
+/// Number of targets of every remote, remotes with the same id are counted together
+fn remote_targets(
+    remote_to_resources: &OrderMap<Arc<RemoteSource>, Vec<Target>>,
+) -> Vec<(String, usize)> {
+    let mut remote_targets = BTreeMap::<String, usize>::new();
+    for (remote, targets) in remote_to_resources {
+        *remote_targets.entry(remote.id.clone()).or_default() += targets.len();
+    }
+    remote_targets.into_iter().collect()
+}
+
+/// Runs of cheaper targets are imported without the dashboard, if nothing is expected
+/// to be fetched for them, e.g. `figx import //pkg:one_icon`
+const TINY_RUN_BUDGET: usize = 10;
+
+/// Share of [`TINY_RUN_BUDGET`] taken by a cached target of the profile kind.
+/// Vector code is generated in no time, while rasters are decoded and checked
+/// for every density, so fewer of them are imported without a progress bar.
+fn tiny_run_cost(kind: ProfileKind) -> usize {
+    use ProfileKind::*;
+    match kind {
+        Svg | Pdf | Compose | AndroidDrawable | React => 2,
+        Png | Webp | Favicon => 3,
+        AndroidWebp | Flutter => 5,
+    }
+}
+
+/// Cheap prediction made before the evaluation: the indexes of all remotes are cached
+/// and all outputs are in place already. Misprediction costs the progress bar only.
+fn is_tiny_cached_run(
+    ctx: &EvalContext,
+    ws: &Workspace,
+    remote_to_resources: &OrderMap<Arc<RemoteSource>, Vec<Target>>,
+) -> bool {
+    let args = &ctx.eval_args;
+    let cost = remote_to_resources
+        .values()
+        .flatten()
+        .map(|it| tiny_run_cost(it.profile.kind()))
+        .sum::<usize>();
+    if cost == 0
+        || cost >= TINY_RUN_BUDGET
+        || args.fetch
+        || args.refetch
+        || args.check
+        || args.gallery_dir.is_some()
+    {
+        return false;
+    }
+    let indexes_cached = remote_to_resources.iter().all(|(remote, targets)| {
+        let wanted_names = targets
+            .iter()
+            .map(|it| it.figma_name().to_owned())
+            .collect::<BTreeSet<_>>();
+        RemoteIndex::new(
+            args.figma_api.clone(),
+            ctx.cache.clone(),
+            ctx.figma_repository.throttle().clone(),
+        )
+        .is_cached(remote, &wanted_names)
+    });
+    indexes_cached
+        && ws
+            .packages
+            .iter()
+            .flat_map(|pkg| &pkg.resources)
+            .flat_map(|res| res.expected_outputs())
+            .all(|path| path.exists())
+}
+
+fn init_eval_context(
+    ws: &Workspace,
+    mut args: EvalArgs,
+    metrics: &Metrics,
+    planned_targets: &[String],
+) -> Result<EvalContext> {
     let cache = setup_cache(&ws.context.cache_dir)?;
+    let io_threads = match args.network_concurrency {
+        0 => ws
+            .jobs
+            .network
+            .unwrap_or(rayon::current_num_threads() * IO_THREADS_PER_JOB),
+        jobs => jobs,
+    };
+    args.figma_api = args.figma_api.clone().with_http_cache(cache.clone());
+    if let Some(ca_bundle) = &ws.network.ca_bundle {
+        args.figma_api = args.figma_api.clone().with_ca_bundle(ca_bundle)?;
+    }
+    let api = args.figma_api.clone();
+    let staging_dir = ws
+        .output
+        .staging
+        .then(|| ws.context.cache_dir.join("staging"));
+    let store = ws
+        .output
+        .content_addressed
+        .map(|mode| ContentStore::new(ws.context.out_dir.join("cas"), mode));
+    let writer = match args.check {
+        true => FileWriter::checking(ws.context.cache_dir.join("check"))?,
+        false => FileWriter::new(args.write_queue, staging_dir, store)?,
+    };
+    let writer = Arc::new(writer);
+    let journal = Arc::new(init_journal(ws, &args, &writer, planned_targets)?);
+    // the files must be written by the time the target is recorded, and
+    // the targets are imported for real when their outputs are not the goal
+    let dirty = match args.fetch
+        || args.check
+        || args.gallery_dir.is_some()
+        || args.intermediates_dir.is_some()
+        || writer.is_deferred()
+    {
+        true => DirtyTracker::default(),
+        false => DirtyTracker::new(
+            cache.clone(),
+            ws.output.provenance,
+            &ws.context.workspace_dir,
+        ),
+    };
+    let manifest = match &args.manifest_path {
+        Some(path) if !args.fetch && !args.check && args.gallery_dir.is_none() => {
+            ImportManifest::start(path, &ws.context.workspace_dir)
+        }
+        _ => ImportManifest::default(),
+    };
+    let audit = match &args.audit_path {
+        Some(path) if !args.fetch && !args.check && args.gallery_dir.is_none() => {
+            AuditLog::open(path, &ws.context.workspace_dir)
+        }
+        _ => AuditLog::default(),
+    };
+    let report = args.build_report.clone().unwrap_or_default();
+    let observer = args
+        .observer
+        .clone()
+        .unwrap_or_else(|| Arc::new(DashboardObserver::default()));
     Ok(EvalContext {
         eval_args: Arc::new(args),
         figma_repository: FigmaRepository::new(api, cache.clone()),
@@ -308,10 +919,49 @@ fn init_eval_context(ws: &Workspace, args: EvalArgs, metrics: &Metrics) -> Resul
         metrics: EvalMetrics {
             targets_evaluated: metrics.counter("figx_targets_evaluated"),
             targets_from_cache: metrics.counter("figx_targets_from_cache"),
+            target_duration: metrics.histogram("figx_target_duration_seconds"),
+            image_size: metrics.histogram_with_buckets("figx_image_size_bytes", IMAGE_SIZE_BUCKETS),
+            summary: Default::default(),
         },
+        report,
+        failures: Default::default(),
+        compose_bundles: Default::default(),
+        writer,
+        journal,
+        dirty: Arc::new(dirty),
+        io: Arc::new(IoLane::new(io_threads)),
+        observer,
+        manifest: Arc::new(manifest),
+        audit: Arc::new(audit),
+        stamp_provenance: ws.output.provenance,
     })
 }
 
+fn init_journal(
+    ws: &Workspace,
+    args: &EvalArgs,
+    writer: &FileWriter,
+    planned_targets: &[String],
+) -> Result<ImportJournal> {
+    let Some(path) = &args.journal_path else {
+        return Ok(ImportJournal::default());
+    };
+    if args.fetch || args.check || args.gallery_dir.is_some() {
+        return Ok(ImportJournal::default());
+    }
+    if writer.is_deferred() {
+        // files may still be missing when their targets are completed
+        if args.resume {
+            warn!(target: "Resume", "resuming is not supported with output staging");
+        }
+        return Ok(ImportJournal::default());
+    }
+    let mut config_files = vec![ws.context.workspace_file.as_path()];
+    config_files.extend(ws.context.fig_files.iter().map(|it| it.fig_file.as_path()));
+    let config_digest = journal::config_digest(&config_files);
+    ImportJournal::start(path, config_digest, planned_targets, args.resume)
+}
+
 fn format_duration(duration: Duration) -> String {
     let total_millis = duration.as_millis();
 
@@ -0,0 +1,296 @@
+use crate::{MaterializedFile, Result};
+use dashmap::DashSet;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+const MANIFEST_VERSION: u32 = 1;
+
+/// Every file materialized in the workspace with the label of its resource.
+///
+/// Each import is a new run. A file remembers the last run that produced it,
+/// a label remembers the last run that imported it completely. A file is
+/// orphaned when its label is gone from the workspace or when its label was
+/// imported after the file was produced, e.g. the resource was renamed.
+#[derive(Default)]
+pub struct ImportManifest {
+    path: Option<PathBuf>,
+    workspace_dir: PathBuf,
+    run: u64,
+    state: Mutex<ManifestDto>,
+    /// Labels not imported completely by this run, e.g. resumed ones
+    incomplete_labels: DashSet<String>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct ManifestDto {
+    version: u32,
+    run: u64,
+    /// Label => last run that imported it completely
+    labels: BTreeMap<String, u64>,
+    /// Path relative to the workspace => entry
+    files: BTreeMap<String, FileEntryDto>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FileEntryDto {
+    label: String,
+    run: u64,
+}
+
+/// Output file which no resource of the workspace produces anymore
+pub struct OrphanedFile {
+    pub path: PathBuf,
+    pub label: String,
+    pub reason: OrphanReason,
+}
+
+pub enum OrphanReason {
+    /// The resource was removed from `.fig.toml`
+    LabelRemoved,
+    /// The resource is still there, but it writes other files now
+    NoLongerProduced,
+}
+
+impl ImportManifest {
+    /// Name of the manifest file in the `.figx-out` directory
+    pub const FILE_NAME: &str = "manifest.json";
+
+    /// Opens the manifest at `path` to record a new run
+    pub fn start(path: &Path, workspace_dir: &Path) -> Self {
+        let state = read_manifest(path);
+        Self {
+            path: Some(path.to_path_buf()),
+            workspace_dir: workspace_dir.to_path_buf(),
+            run: state.run + 1,
+            state: Mutex::new(state),
+            incomplete_labels: DashSet::new(),
+        }
+    }
+
+    /// Opens the manifest at `path` without starting a new run
+    pub fn open(path: &Path, workspace_dir: &Path) -> Self {
+        let state = read_manifest(path);
+        Self {
+            path: Some(path.to_path_buf()),
+            workspace_dir: workspace_dir.to_path_buf(),
+            run: state.run,
+            state: Mutex::new(state),
+            incomplete_labels: DashSet::new(),
+        }
+    }
+
+    /// Remembers all files of the target
+    pub fn record(&self, label: &str, file: &MaterializedFile) {
+        if self.path.is_none() {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        for path in file.paths() {
+            let entry = FileEntryDto {
+                label: label.to_owned(),
+                run: self.run,
+            };
+            state.files.insert(self.relative_key(path), entry);
+        }
+    }
+
+    /// Marks the label as one whose files were not all produced by this run
+    pub fn skip(&self, label: &str) {
+        self.incomplete_labels.insert(label.to_owned());
+    }
+
+    /// Saves the manifest, `imported_labels` are the labels imported completely by this run
+    pub fn finish<'a>(&self, imported_labels: impl IntoIterator<Item = &'a str>) -> Result<()> {
+        {
+            let mut state = self.state.lock().unwrap();
+            for label in imported_labels {
+                if !self.incomplete_labels.contains(label) {
+                    state.labels.insert(label.to_owned(), self.run);
+                }
+            }
+        }
+        self.save()
+    }
+
+    pub fn orphans(&self, workspace_labels: &HashSet<String>) -> Vec<OrphanedFile> {
+        let state = self.state.lock().unwrap();
+        state
+            .files
+            .iter()
+            .filter_map(|(key, entry)| {
+                let reason = match state.labels.get(&entry.label) {
+                    _ if !workspace_labels.contains(&entry.label) => OrphanReason::LabelRemoved,
+                    Some(label_run) if *label_run > entry.run => OrphanReason::NoLongerProduced,
+                    _ => return None,
+                };
+                Some(OrphanedFile {
+                    path: self.workspace_dir.join(key),
+                    label: entry.label.clone(),
+                    reason,
+                })
+            })
+            .collect()
+    }
+
+    /// Names of the external workspaces whose resources produced the files, e.g. `ds`
+    /// for `@ds//icons:star`
+    pub fn externals(&self) -> BTreeSet<String> {
+        let state = self.state.lock().unwrap();
+        state
+            .files
+            .values()
+            .filter_map(|it| it.label.strip_prefix('@')?.split_once("//"))
+            .map(|(name, _)| name.to_owned())
+            .collect()
+    }
+
+    /// Stops tracking the file, e.g. after it was deleted
+    pub fn forget(&self, path: &Path) {
+        let key = self.relative_key(path);
+        self.state.lock().unwrap().files.remove(&key);
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let mut state = self.state.lock().unwrap();
+        state.version = MANIFEST_VERSION;
+        state.run = self.run;
+        // labels without files are not needed anymore
+        let labels_in_use = state
+            .files
+            .values()
+            .map(|it| it.label.clone())
+            .collect::<HashSet<_>>();
+        state
+            .labels
+            .retain(|label, _| labels_in_use.contains(label));
+        let json = serde_json::to_vec_pretty(&*state).map_err(std::io::Error::other)?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, json)?;
+        debug!(target: "Manifest", "{} files tracked", state.files.len());
+        Ok(())
+    }
+
+    fn relative_key(&self, path: &Path) -> String {
+        path.strip_prefix(&self.workspace_dir)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+}
+
+fn read_manifest(path: &Path) -> ManifestDto {
+    let Ok(json) = std::fs::read(path) else {
+        return ManifestDto::default();
+    };
+    match serde_json::from_slice::<ManifestDto>(&json) {
+        Ok(dto) if dto.version == MANIFEST_VERSION => dto,
+        _ => {
+            warn!(target: "Manifest", "unable to read `{}`, starting over", path.display());
+            ManifestDto::default()
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    fn file(path: PathBuf) -> MaterializedFile {
+        MaterializedFile {
+            path,
+            bytes: 0,
+            written: true,
+            extra_paths: Vec::new(),
+        }
+    }
+
+    fn labels(labels: &[&str]) -> HashSet<String> {
+        labels.iter().map(|it| it.to_string()).collect()
+    }
+
+    #[test]
+    fn ImportManifest__renamed_and_removed_resources__EXPECT__their_files_orphaned() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let ws = dir.path();
+        let manifest_path = ws.join(".figx-out").join("manifest.json");
+        let manifest = ImportManifest::start(&manifest_path, ws);
+        manifest.record("//icons:ic_star", &file(ws.join("icons/ic_star.svg")));
+        manifest.record("//icons:ic_moon", &file(ws.join("icons/ic_moon.svg")));
+        manifest.record("//icons:ic_sun", &file(ws.join("icons/ic_sun.svg")));
+        manifest
+            .finish(["//icons:ic_star", "//icons:ic_moon", "//icons:ic_sun"])
+            .unwrap();
+
+        // When
+        let manifest = ImportManifest::start(&manifest_path, ws);
+        manifest.record(
+            "//icons:ic_star",
+            &file(ws.join("icons/ic_star_filled.svg")),
+        );
+        manifest.finish(["//icons:ic_star"]).unwrap();
+        let manifest = ImportManifest::open(&manifest_path, ws);
+        let orphans = manifest.orphans(&labels(&["//icons:ic_star", "//icons:ic_sun"]));
+
+        // Then
+        let orphans = orphans
+            .iter()
+            .map(|it| (it.path.clone(), it.label.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (ws.join("icons/ic_moon.svg"), "//icons:ic_moon"),
+                (ws.join("icons/ic_star.svg"), "//icons:ic_star"),
+            ],
+            orphans,
+        );
+    }
+
+    #[test]
+    fn ImportManifest__files_of_external_resources__EXPECT__their_externals() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let ws = dir.path();
+        let manifest = ImportManifest::start(&ws.join("manifest.json"), ws);
+
+        // When
+        manifest.record("//icons:ic_star", &file(ws.join("icons/ic_star.svg")));
+        manifest.record("@ds//icons:ic_moon", &file(ws.join("ds/ic_moon.svg")));
+        manifest.record("@ds//icons:ic_sun", &file(ws.join("ds/ic_sun.svg")));
+
+        // Then
+        assert_eq!(BTreeSet::from(["ds".to_owned()]), manifest.externals());
+    }
+
+    #[test]
+    fn ImportManifest__incomplete_label__EXPECT__previous_files_kept() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let ws = dir.path();
+        let manifest_path = ws.join("manifest.json");
+        let manifest = ImportManifest::start(&manifest_path, ws);
+        manifest.record("//:ic_star", &file(ws.join("ic_star.svg")));
+        manifest.finish(["//:ic_star"]).unwrap();
+
+        // When
+        let manifest = ImportManifest::start(&manifest_path, ws);
+        manifest.skip("//:ic_star");
+        manifest.record("//:ic_star", &file(ws.join("ic_star_night.svg")));
+        manifest.finish(["//:ic_star"]).unwrap();
+
+        // Then
+        let manifest = ImportManifest::open(&manifest_path, ws);
+        assert!(manifest.orphans(&labels(&["//:ic_star"])).is_empty());
+    }
+}
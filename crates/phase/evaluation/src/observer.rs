@@ -0,0 +1,194 @@
+use crate::{Error, Target, TargetStatus};
+use dashmap::DashMap;
+use lib_dashboard::{
+    InProgressItem, InitDashboardParams, RemoteProgressItem, TargetOutcome, init_dashboard,
+    lifecycle, record_download, shutdown_dashboard, track_progress, track_remote,
+};
+use lib_label::Label;
+use phase_loading::ProfileKind;
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+/// Gets notified about the progress of the evaluation.
+///
+/// The CLI shows it on the dashboard, see [`DashboardObserver`], while embedders may
+/// plug their own progress UI. Methods are called from many threads at once.
+pub trait ExecutionObserver: Send + Sync {
+    fn on_evaluation_started(&self, _meta: &EvaluationMeta) {}
+
+    fn on_evaluation_finished(&self) {}
+
+    /// Remote is being indexed, called again for every indexed node
+    fn on_remote_indexing(&self, _remote_id: &str, _progress: &IndexingProgress) {}
+
+    /// Index of the remote is received, or taken from the cache without indexing
+    fn on_remote_indexed(&self, _remote_id: &str) {}
+
+    fn on_target_started(&self, _meta: &TargetMeta) {}
+
+    /// Image of `bytes` size is received from a remote, images taken from cache are not reported
+    fn on_image_downloaded(&self, _remote_id: &str, _bytes: usize) {}
+
+    /// Target is imported, `status` tells whether its files are written or up to date
+    fn on_target_finished(&self, _meta: &TargetMeta, _status: TargetStatus, _elapsed: Duration) {}
+
+    /// Target is failed, even if the evaluation goes on with `keep_going`
+    fn on_target_failed(&self, _meta: &TargetMeta, _error: &Error) {}
+}
+
+pub struct EvaluationMeta {
+    pub requested_targets: usize,
+    pub requested_remotes: usize,
+    pub loaded_packages: usize,
+    /// What the evaluation does with the targets, e.g. `Importing` or `Checking`
+    pub process_name: &'static str,
+    /// Ids of the requested remotes with the number of their targets, sorted by id
+    pub remote_targets: Vec<(String, usize)>,
+    /// All targets are few and likely cached, so the evaluation will be over in a blink
+    pub tiny_cached_run: bool,
+}
+
+pub struct IndexingProgress {
+    pub indexed_nodes: usize,
+    pub found_targets: usize,
+    pub requested_targets: usize,
+}
+
+pub struct TargetMeta<'a> {
+    pub label: &'a Label,
+    /// Label along with the variant, e.g. `//icons:star (hdpi)`, unique within the evaluation
+    pub qualified_name: &'a str,
+    /// Id of the profile the resource is declared under, e.g. `png`
+    pub profile_id: &'a str,
+    /// Type of the profile, e.g. `png` for a custom profile which extends it
+    pub profile_kind: ProfileKind,
+    pub remote_id: &'a str,
+}
+
+impl<'a> TargetMeta<'a> {
+    pub(crate) fn of(target: &Target<'a>, qualified_name: &'a str) -> Self {
+        Self {
+            label: &target.attrs.label,
+            qualified_name,
+            profile_id: &target.attrs.profile_id,
+            profile_kind: target.profile.kind(),
+            remote_id: &target.attrs.remote.id,
+        }
+    }
+}
+
+/// Shows the progress of the evaluation on the dashboard of the CLI
+#[derive(Default)]
+pub struct DashboardObserver {
+    /// Qualified name of the target => its line on the dashboard and start time
+    targets: DashMap<String, (InProgressItem, Instant)>,
+    remotes: DashMap<String, RemoteProgressItem>,
+    /// Tiny runs are not shown, the dashboard would only flicker
+    hidden: AtomicBool,
+}
+
+impl ExecutionObserver for DashboardObserver {
+    fn on_evaluation_started(&self, meta: &EvaluationMeta) {
+        if meta.tiny_cached_run {
+            self.hidden.store(true, Ordering::SeqCst);
+            return;
+        }
+        let EvaluationMeta {
+            requested_targets,
+            requested_remotes,
+            loaded_packages,
+            process_name,
+            ..
+        } = *meta;
+        lifecycle!(
+            target: "@Requested",
+            "{tn} target{tp} from {rn} remote{rp} ({pn} package{pp} loaded)",
+            tn = requested_targets,
+            tp = if requested_targets == 1 { "" } else { "s" },
+            rn = requested_remotes,
+            rp = if requested_remotes == 1 { "" } else { "s" },
+            pn = loaded_packages,
+            pp = if loaded_packages == 1 { "" } else { "s" },
+        );
+        init_dashboard(InitDashboardParams {
+            requested_targets,
+            requested_remotes,
+            loaded_packages,
+            process_name,
+        });
+        for (remote_id, targets) in &meta.remote_targets {
+            self.remotes
+                .insert(remote_id.clone(), track_remote(remote_id.clone(), *targets));
+        }
+    }
+
+    fn on_evaluation_finished(&self) {
+        self.remotes.clear();
+        if !self.hidden.load(Ordering::SeqCst) {
+            shutdown_dashboard();
+        }
+    }
+
+    fn on_remote_indexing(&self, remote_id: &str, progress: &IndexingProgress) {
+        let item = self
+            .remotes
+            .entry(remote_id.to_owned())
+            .or_insert_with(|| track_remote(remote_id.to_owned(), progress.requested_targets));
+        item.set_status(format!(
+            "indexing, {} components, {}/{} targets found",
+            progress.indexed_nodes, progress.found_targets, progress.requested_targets,
+        ));
+    }
+
+    fn on_remote_indexed(&self, remote_id: &str) {
+        if let Some(item) = self.remotes.get(remote_id) {
+            item.finish_fetch();
+        }
+    }
+
+    fn on_target_started(&self, meta: &TargetMeta) {
+        self.targets.insert(
+            meta.qualified_name.to_owned(),
+            (track_target(meta), Instant::now()),
+        );
+    }
+
+    fn on_image_downloaded(&self, remote_id: &str, bytes: usize) {
+        record_download(bytes);
+        if let Some(item) = self.remotes.get(remote_id) {
+            item.add_downloaded_bytes(bytes);
+        }
+    }
+
+    fn on_target_finished(&self, meta: &TargetMeta, status: TargetStatus, elapsed: Duration) {
+        let outcome = match status {
+            TargetStatus::Exported => TargetOutcome::Exported,
+            TargetStatus::Cached => TargetOutcome::Cached,
+            TargetStatus::Failed => TargetOutcome::Failed,
+        };
+        if let Some((_, (item, _))) = self.targets.remove(meta.qualified_name) {
+            item.finish(outcome, elapsed);
+        }
+        if let Some(item) = self.remotes.get(meta.remote_id) {
+            item.finish_target();
+        }
+    }
+
+    fn on_target_failed(&self, meta: &TargetMeta, _error: &Error) {
+        // failed targets are counted as done, the error is reported at the end
+        match self.targets.remove(meta.qualified_name) {
+            Some((_, (item, started))) => item.finish(TargetOutcome::Failed, started.elapsed()),
+            // targets without a node fail before they are started
+            None => track_target(meta).finish(TargetOutcome::Failed, Duration::ZERO),
+        }
+        if let Some(item) = self.remotes.get(meta.remote_id) {
+            item.finish_target();
+        }
+    }
+}
+
+fn track_target(meta: &TargetMeta) -> InProgressItem {
+    track_progress(meta.label.name.to_string(), meta.qualified_name.to_owned())
+}
@@ -0,0 +1,212 @@
+use lib_metrics::SpanRecord;
+use std::{collections::HashSet, time::Duration};
+
+/// Per-action wall time breakdown printed by `figx import --profile`
+pub struct ProfileReport {
+    critical_path: Vec<SpanRecord>,
+    actions: Vec<ActionStats>,
+    slowest: Vec<SpanRecord>,
+    targets: usize,
+    threads: usize,
+    spans: usize,
+}
+
+struct ActionStats {
+    name: &'static str,
+    calls: usize,
+    total: Duration,
+    max: Duration,
+}
+
+impl ProfileReport {
+    pub fn from_spans(records: &[SpanRecord], top_n: usize) -> Self {
+        // The longest target is the critical path: targets are evaluated in parallel,
+        // so the evaluation can't finish faster than its slowest target
+        let critical_path = records
+            .iter()
+            .filter(|it| it.depth == 0 && it.of_target)
+            .max_by_key(|it| it.duration)
+            .map(|root| {
+                let end = root.start + root.duration;
+                records
+                    .iter()
+                    .filter(|it| it.thread == root.thread && it.target == root.target)
+                    .filter(|it| it.start >= root.start && it.start + it.duration <= end)
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        let mut actions: Vec<ActionStats> = Vec::new();
+        for record in records {
+            match actions.iter_mut().find(|it| it.name == record.name) {
+                Some(stats) => {
+                    stats.calls += 1;
+                    stats.total += record.duration;
+                    stats.max = stats.max.max(record.duration);
+                }
+                None => actions.push(ActionStats {
+                    name: record.name,
+                    calls: 1,
+                    total: record.duration,
+                    max: record.duration,
+                }),
+            }
+        }
+        actions.sort_by(|a, b| b.total.cmp(&a.total));
+
+        // outermost spans cover the whole target, nested ones are more interesting
+        let mut slowest = records
+            .iter()
+            .filter(|it| it.depth > 0)
+            .cloned()
+            .collect::<Vec<_>>();
+        slowest.sort_by(|a, b| b.duration.cmp(&a.duration));
+        slowest.truncate(top_n);
+
+        Self {
+            critical_path,
+            actions,
+            slowest,
+            targets: records
+                .iter()
+                .filter(|it| it.depth == 0 && it.of_target)
+                .map(|it| &it.target)
+                .collect::<HashSet<_>>()
+                .len(),
+            threads: records
+                .iter()
+                .map(|it| it.thread)
+                .collect::<HashSet<_>>()
+                .len(),
+            spans: records.len(),
+        }
+    }
+
+    pub fn print(&self) {
+        println!(
+            "\nProfile: {} actions, {} targets, {} threads",
+            self.spans, self.targets, self.threads,
+        );
+        if self.spans == 0 {
+            return;
+        }
+
+        if let Some(root) = self.critical_path.first() {
+            println!(
+                "\nCritical path: `{}` {}",
+                root.target,
+                format_ms(root.duration)
+            );
+            for span in &self.critical_path {
+                let indent = "  ".repeat(span.depth + 1);
+                let width = 40usize.saturating_sub(indent.len());
+                println!(
+                    "{indent}{:<width$} {:>12}",
+                    span.name,
+                    format_ms(span.duration)
+                );
+            }
+        }
+
+        println!(
+            "\nActions by total time:\n  {:<38} {:>6} {:>12} {:>12} {:>12}",
+            "ACTION", "CALLS", "TOTAL", "AVG", "MAX",
+        );
+        for stats in &self.actions {
+            println!(
+                "  {:<38} {:>6} {:>12} {:>12} {:>12}",
+                stats.name,
+                stats.calls,
+                format_ms(stats.total),
+                format_ms(stats.total / stats.calls as u32),
+                format_ms(stats.max),
+            );
+        }
+
+        if !self.slowest.is_empty() {
+            println!("\nTop {} slowest actions:", self.slowest.len());
+            for span in &self.slowest {
+                println!(
+                    "  {:<38} {:>12}  {}",
+                    span.name,
+                    format_ms(span.duration),
+                    span.target
+                );
+            }
+        }
+    }
+}
+
+fn format_ms(duration: Duration) -> String {
+    format!("{:.1} ms", duration.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use std::sync::Arc;
+
+    fn span(name: &'static str, target: &str, depth: usize, start: u64, ms: u64) -> SpanRecord {
+        SpanRecord {
+            name,
+            target: Arc::from(target),
+            of_target: true,
+            thread: 0,
+            depth,
+            start: Duration::from_millis(start),
+            duration: Duration::from_millis(ms),
+        }
+    }
+
+    #[test]
+    fn from_spans__two_targets__EXPECT__longest_target_is_critical_path() {
+        // Given
+        let records = vec![
+            span("import_png", "//a:icon", 0, 0, 10),
+            span("materialize", "//a:icon", 1, 5, 2),
+            span("import_png", "//b:icon", 0, 10, 30),
+            span("download_image", "//b:icon", 1, 11, 20),
+            span("materialize", "//b:icon", 1, 31, 5),
+        ];
+
+        // When
+        let report = ProfileReport::from_spans(&records, 2);
+
+        // Then
+        let path = report
+            .critical_path
+            .iter()
+            .map(|it| it.name)
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["import_png", "download_image", "materialize"], path);
+        assert_eq!("import_png", report.actions[0].name);
+        assert_eq!(2, report.actions[0].calls);
+        assert_eq!(vec!["download_image", "materialize"], {
+            report.slowest.iter().map(|it| it.name).collect::<Vec<_>>()
+        });
+        assert_eq!(2, report.targets);
+    }
+
+    #[test]
+    fn from_spans__remote_indexing__EXPECT__not_counted_as_target() {
+        // Given
+        let records = vec![
+            SpanRecord {
+                of_target: false,
+                ..span("index_remote", "design", 0, 0, 100)
+            },
+            span("import_png", "//a:icon", 0, 100, 10),
+            span("materialize", "//a:icon", 1, 105, 2),
+        ];
+
+        // When
+        let report = ProfileReport::from_spans(&records, 2);
+
+        // Then
+        assert_eq!(1, report.targets);
+        assert_eq!("import_png", report.critical_path[0].name);
+        assert_eq!("index_remote", report.actions[0].name);
+    }
+}
@@ -0,0 +1,366 @@
+use crate::figma::NodeMetadata;
+use lib_cache::Digester;
+use lib_label::Label;
+use std::hash::Hasher;
+
+/// Prefix of the provenance record in text files and the keyword of the PNG chunk
+const MARKER: &str = "figx-provenance";
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+/// Length, type and CRC of a PNG chunk
+const PNG_CHUNK_OVERHEAD: usize = 12;
+
+/// Where the generated file comes from, stamped into it with `[output] provenance`.
+///
+/// The record holds the digest of the rest of the file, so hand edits are detected
+/// by [`check_provenance`] without the cache or the Figma file.
+pub struct Provenance<'a> {
+    pub label: &'a Label,
+    /// `None` if the file is generated from several nodes, e.g. a bundle of variants
+    pub node: Option<&'a NodeMetadata>,
+}
+
+/// Provenance record read from a stamped file
+#[derive(Debug, PartialEq)]
+pub struct ProvenanceRecord {
+    pub label: String,
+    pub node_id: Option<String>,
+    /// Hash of the node content the file is generated from
+    pub node_hash: Option<String>,
+    pub figx_version: String,
+    pub digest: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ProvenanceCheck {
+    /// The content matches the digest of the record
+    Intact(ProvenanceRecord),
+    /// The content has changed since the file was generated
+    Edited(ProvenanceRecord),
+    /// The file has no provenance record, or its format cannot hold one
+    Unstamped,
+}
+
+/// Formats of the files which can hold the provenance record
+pub fn is_stampable(extension: &str) -> bool {
+    matches!(extension, "kt" | "xml" | "svg" | "png")
+}
+
+/// Returns the file with the provenance record, `None` if the format cannot hold it
+pub(crate) fn stamp(bytes: &[u8], extension: &str, provenance: &Provenance) -> Option<Vec<u8>> {
+    let record = record_line(provenance, &content_digest(&[bytes]));
+    match extension {
+        "kt" => Some(splice(bytes, 0, format!("// {record}\n").as_bytes())),
+        "xml" | "svg" => {
+            let offset = xml_prolog_end(bytes);
+            Some(splice(
+                bytes,
+                offset,
+                format!("<!-- {record} -->\n").as_bytes(),
+            ))
+        }
+        "png" => {
+            let offset = png_first_chunk_end(bytes)?;
+            Some(splice(bytes, offset, &png_text_chunk(&record)))
+        }
+        _ => None,
+    }
+}
+
+/// Reads the provenance record of the file and checks the file against it
+pub fn check_provenance(bytes: &[u8], extension: &str) -> ProvenanceCheck {
+    let stamped = match extension {
+        "kt" => text_record(bytes, 0, "// ", "\n"),
+        "xml" | "svg" => text_record(bytes, xml_prolog_end(bytes), "<!-- ", " -->\n"),
+        "png" => png_record(bytes),
+        _ => None,
+    };
+    let Some((record, content)) = stamped else {
+        return ProvenanceCheck::Unstamped;
+    };
+    let Some(record) = parse_record(&record) else {
+        return ProvenanceCheck::Unstamped;
+    };
+    match content_digest(&content) == record.digest {
+        true => ProvenanceCheck::Intact(record),
+        false => ProvenanceCheck::Edited(record),
+    }
+}
+
+// region: record
+
+fn record_line(provenance: &Provenance, digest: &str) -> String {
+    let mut line = format!("{MARKER}: label={}", provenance.label);
+    if let Some(node) = provenance.node {
+        line.push_str(&format!(" node={} node_hash={:016x}", node.id, node.hash));
+    }
+    line.push_str(&format!(
+        " figx={} digest={digest}",
+        env!("CARGO_PKG_VERSION")
+    ));
+    line
+}
+
+fn parse_record(line: &str) -> Option<ProvenanceRecord> {
+    let fields = line.strip_prefix(MARKER)?.strip_prefix(": ")?;
+    let field = |key: &str| {
+        fields
+            .split(' ')
+            .find_map(|it| it.strip_prefix(key)?.strip_prefix('='))
+            .map(str::to_owned)
+    };
+    Some(ProvenanceRecord {
+        label: field("label")?,
+        node_id: field("node"),
+        node_hash: field("node_hash"),
+        figx_version: field("figx")?,
+        digest: field("digest")?,
+    })
+}
+
+/// Digest of the file without the provenance record, in parts to avoid copying it
+fn content_digest(parts: &[&[u8]]) -> String {
+    let mut hasher = Digester::new();
+    for part in parts {
+        hasher.write(part);
+    }
+    format!("{:016x}", hasher.digest())
+}
+
+// endregion: record
+
+// region: text formats
+
+fn splice(bytes: &[u8], offset: usize, insertion: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(bytes.len() + insertion.len());
+    result.extend_from_slice(&bytes[..offset]);
+    result.extend_from_slice(insertion);
+    result.extend_from_slice(&bytes[offset..]);
+    result
+}
+
+/// Comments cannot precede the XML declaration, so the record goes right after it
+fn xml_prolog_end(bytes: &[u8]) -> usize {
+    if !bytes.starts_with(b"<?xml") {
+        return 0;
+    }
+    let Some(end) = bytes.windows(2).position(|it| it == b"?>") else {
+        return 0;
+    };
+    match bytes.get(end + 2) {
+        Some(b'\n') => end + 3,
+        _ => end + 2,
+    }
+}
+
+/// Returns the record found at `offset` and the content around it
+fn text_record<'a>(
+    bytes: &'a [u8],
+    offset: usize,
+    prefix: &str,
+    suffix: &str,
+) -> Option<(String, [&'a [u8]; 2])> {
+    let rest = bytes[offset..].strip_prefix(prefix.as_bytes())?;
+    if !rest.starts_with(MARKER.as_bytes()) {
+        return None;
+    }
+    let len = rest
+        .windows(suffix.len())
+        .position(|it| it == suffix.as_bytes())?;
+    let record = std::str::from_utf8(&rest[..len]).ok()?.to_owned();
+    let after = &rest[len + suffix.len()..];
+    Some((record, [&bytes[..offset], after]))
+}
+
+// endregion: text formats
+
+// region: png
+
+/// The record goes right after `IHDR`, which must be the first chunk
+fn png_first_chunk_end(bytes: &[u8]) -> Option<usize> {
+    let chunk = bytes.strip_prefix(PNG_SIGNATURE)?;
+    let len = u32::from_be_bytes(chunk.get(..4)?.try_into().ok()?) as usize;
+    let end = PNG_SIGNATURE.len() + PNG_CHUNK_OVERHEAD + len;
+    (end <= bytes.len()).then_some(end)
+}
+
+fn png_text_chunk(record: &str) -> Vec<u8> {
+    let (keyword, text) = record
+        .split_once(": ")
+        .expect("record always starts with the marker");
+    let mut body = Vec::with_capacity(4 + record.len());
+    body.extend_from_slice(b"tEXt");
+    body.extend_from_slice(keyword.as_bytes());
+    body.push(0);
+    body.extend_from_slice(text.as_bytes());
+    let mut chunk = Vec::with_capacity(body.len() + 8);
+    chunk.extend_from_slice(&((body.len() - 4) as u32).to_be_bytes());
+    chunk.extend_from_slice(&body);
+    chunk.extend_from_slice(&crc32(&body).to_be_bytes());
+    chunk
+}
+
+fn png_record(bytes: &[u8]) -> Option<(String, [&[u8]; 2])> {
+    let mut offset = PNG_SIGNATURE.len();
+    if !bytes.starts_with(PNG_SIGNATURE) {
+        return None;
+    }
+    while offset + PNG_CHUNK_OVERHEAD <= bytes.len() {
+        let len = u32::from_be_bytes(bytes[offset..offset + 4].try_into().ok()?) as usize;
+        let end = offset + PNG_CHUNK_OVERHEAD + len;
+        let chunk_type = &bytes[offset + 4..offset + 8];
+        let data = bytes.get(offset + 8..end - 4)?;
+        if chunk_type == b"tEXt" {
+            if let Some(text) = data.strip_prefix(format!("{MARKER}\0").as_bytes()) {
+                let text = std::str::from_utf8(text).ok()?;
+                let record = format!("{MARKER}: {text}");
+                return Some((record, [&bytes[..offset], &bytes[end..]]));
+            }
+        }
+        offset = end;
+    }
+    None
+}
+
+/// CRC-32 of the PNG chunks, the records are tiny, so no lookup table is needed
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = match crc & 1 {
+                1 => (crc >> 1) ^ 0xEDB8_8320,
+                _ => crc >> 1,
+            };
+        }
+    }
+    !crc
+}
+
+// endregion: png
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    fn node() -> NodeMetadata {
+        NodeMetadata {
+            id: "1:2".to_owned(),
+            name: "Star".to_owned(),
+            hash: 0xABCD,
+            uses_raster_paints: false,
+            bounds: None,
+            render_bounds: None,
+        }
+    }
+
+    fn stamped(bytes: &[u8], extension: &str) -> Vec<u8> {
+        let label = Label::from_package_and_name("icons", "ic_star").unwrap();
+        let node = node();
+        let provenance = Provenance {
+            label: &label,
+            node: Some(&node),
+        };
+        stamp(bytes, extension, &provenance).unwrap()
+    }
+
+    fn png() -> Vec<u8> {
+        let mut bytes = Vec::new();
+        image::RgbaImage::new(2, 2)
+            .write_to(
+                &mut std::io::Cursor::new(&mut bytes),
+                image::ImageFormat::Png,
+            )
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn stamp__kotlin_file__EXPECT__intact_record_on_first_line() {
+        // Given
+        let kt = b"package icons\n";
+
+        // When
+        let bytes = stamped(kt, "kt");
+
+        // Then
+        let text = String::from_utf8(bytes.clone()).unwrap();
+        assert!(text.starts_with("// figx-provenance: label=//icons:ic_star node=1:2 "));
+        assert!(text.ends_with("\npackage icons\n"));
+        let ProvenanceCheck::Intact(record) = check_provenance(&bytes, "kt") else {
+            panic!("record expected to be intact");
+        };
+        assert_eq!("//icons:ic_star", record.label);
+        assert_eq!(Some("1:2".to_owned()), record.node_id);
+        assert_eq!(Some("000000000000abcd".to_owned()), record.node_hash);
+    }
+
+    #[test]
+    fn stamp__xml_with_declaration__EXPECT__record_after_declaration() {
+        // Given
+        let xml = b"<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<vector/>\n";
+
+        // When
+        let bytes = stamped(xml, "xml");
+
+        // Then
+        let text = String::from_utf8(bytes.clone()).unwrap();
+        assert!(
+            text.starts_with("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<!-- figx-provenance: ")
+        );
+        assert!(matches!(
+            check_provenance(&bytes, "xml"),
+            ProvenanceCheck::Intact(_)
+        ));
+    }
+
+    #[test]
+    fn check_provenance__edited_svg__EXPECT__edited() {
+        // Given
+        let bytes = stamped(b"<svg width=\"24\"/>", "svg");
+        let text = String::from_utf8(bytes).unwrap();
+
+        // When
+        let edited = text.replace("24", "48");
+
+        // Then
+        assert!(matches!(
+            check_provenance(edited.as_bytes(), "svg"),
+            ProvenanceCheck::Edited(_)
+        ));
+    }
+
+    #[test]
+    fn stamp__png__EXPECT__decodable_image_with_intact_record() {
+        // Given
+        let png = png();
+
+        // When
+        let bytes = stamped(&png, "png");
+
+        // Then
+        assert!(image::load_from_memory(&bytes).is_ok());
+        assert!(matches!(
+            check_provenance(&bytes, "png"),
+            ProvenanceCheck::Intact(_)
+        ));
+    }
+
+    #[test]
+    fn check_provenance__file_without_record__EXPECT__unstamped() {
+        // Then
+        assert_eq!(
+            ProvenanceCheck::Unstamped,
+            check_provenance(b"package icons\n", "kt")
+        );
+        assert_eq!(ProvenanceCheck::Unstamped, check_provenance(&png(), "png"));
+        assert_eq!(ProvenanceCheck::Unstamped, check_provenance(b"%PDF", "pdf"));
+    }
+
+    #[test]
+    fn crc32__known_input__EXPECT__reference_value() {
+        // Then
+        assert_eq!(0xCBF4_3926, crc32(b"123456789"));
+    }
+}
@@ -0,0 +1,301 @@
+use crate::{RemoteUsage, Result, Target};
+use serde::Serialize;
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Collects the outcome of every evaluated target and writes it
+/// as a machine-readable `build-report.json` after evaluation.
+#[derive(Clone, Default)]
+pub struct BuildReport {
+    entries: Arc<Mutex<Vec<TargetReport>>>,
+}
+
+/// File produced by the `materialize` action
+pub struct MaterializedFile {
+    pub path: PathBuf,
+    pub bytes: usize,
+    pub written: bool,
+    /// Other files of the target, e.g. previews or densities
+    pub extra_paths: Vec<PathBuf>,
+}
+
+#[derive(Serialize)]
+struct BuildReportDto<'a> {
+    version: u32,
+    duration_ms: u128,
+    summary: SummaryDto,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    remotes: &'a [RemoteUsage],
+    targets: &'a [TargetReport],
+}
+
+#[derive(Serialize, Default)]
+struct SummaryDto {
+    total: usize,
+    exported: usize,
+    cached: usize,
+    failed: usize,
+}
+
+/// Outcome of a single target
+#[derive(Serialize, Clone, Debug)]
+pub struct TargetReport {
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
+    pub remote: String,
+    pub status: TargetStatus,
+    pub duration_ms: u128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<OutputReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Warnings logged while evaluating the target, e.g. about blurry strokes
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub logs: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct OutputReport {
+    pub path: PathBuf,
+    pub bytes: usize,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetStatus {
+    Cached,
+    Exported,
+    Failed,
+}
+
+/// Handle returned by [`BuildReport::start`], measures target evaluation time
+pub struct TargetReportHandle {
+    report: BuildReport,
+    label: String,
+    variant: Option<String>,
+    remote: String,
+    started: Instant,
+}
+
+impl MaterializedFile {
+    pub(crate) fn cached(path: PathBuf, bytes: &[u8]) -> Self {
+        Self {
+            path,
+            bytes: bytes.len(),
+            written: false,
+            extra_paths: Vec::new(),
+        }
+    }
+
+    /// Reports another file of the same target as a part of this one
+    pub(crate) fn merge(&mut self, other: MaterializedFile) {
+        self.bytes += other.bytes;
+        self.written |= other.written;
+        self.extra_paths.push(other.path);
+        self.extra_paths.extend(other.extra_paths);
+    }
+
+    /// Paths of all files of the target, the main one goes first
+    pub fn paths(&self) -> impl Iterator<Item = &PathBuf> {
+        std::iter::once(&self.path).chain(&self.extra_paths)
+    }
+}
+
+impl BuildReport {
+    pub fn start(&self, target: &Target<'_>) -> TargetReportHandle {
+        TargetReportHandle {
+            report: self.clone(),
+            label: target.attrs.label.to_string(),
+            variant: target.id.clone(),
+            remote: target.attrs.remote.id.clone(),
+            started: Instant::now(),
+        }
+    }
+
+    /// Outcomes of the targets recorded so far, in the order they are finished
+    pub fn targets(&self) -> Vec<TargetReport> {
+        self.entries.lock().unwrap().clone()
+    }
+
+    /// Gives other reporters, e.g. the CI summary, a look at the recorded targets
+    pub(crate) fn with_entries<R>(&self, f: impl FnOnce(&[TargetReport]) -> R) -> R {
+        f(&self.entries.lock().unwrap())
+    }
+
+    pub fn write(&self, path: &Path, duration: Duration, remotes: &[RemoteUsage]) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.sort_by(|a, b| (&a.label, &a.variant).cmp(&(&b.label, &b.variant)));
+
+        let mut summary = SummaryDto {
+            total: entries.len(),
+            ..Default::default()
+        };
+        for entry in entries.iter() {
+            match entry.status {
+                TargetStatus::Cached => summary.cached += 1,
+                TargetStatus::Exported => summary.exported += 1,
+                TargetStatus::Failed => summary.failed += 1,
+            }
+        }
+        let dto = BuildReportDto {
+            version: 1,
+            duration_ms: duration.as_millis(),
+            summary,
+            remotes,
+            targets: &entries,
+        };
+        let json = serde_json::to_string_pretty(&dto)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+}
+
+impl TargetReportHandle {
+    /// Records the outcome of the target and returns the time it took
+    pub fn finish(self, result: &Result<Option<MaterializedFile>>, logs: &[String]) -> Duration {
+        let elapsed = self.started.elapsed();
+        let (status, output, error) = match result {
+            Ok(Some(file)) => (
+                if file.written {
+                    TargetStatus::Exported
+                } else {
+                    TargetStatus::Cached
+                },
+                Some(OutputReport {
+                    path: file.path.clone(),
+                    bytes: file.bytes,
+                }),
+                None,
+            ),
+            Ok(None) => (TargetStatus::Cached, None, None),
+            Err(e) => (TargetStatus::Failed, None, Some(e.to_string())),
+        };
+        let warnings = logs
+            .iter()
+            .filter_map(|it| it.strip_prefix("warn "))
+            .map(str::to_owned)
+            .collect();
+        // the action trail is only interesting for failed targets
+        let logs = match status {
+            TargetStatus::Failed => logs.to_vec(),
+            _ => Vec::new(),
+        };
+        self.report.entries.lock().unwrap().push(TargetReport {
+            label: self.label,
+            variant: self.variant,
+            remote: self.remote,
+            status,
+            duration_ms: elapsed.as_millis(),
+            output,
+            error,
+            warnings,
+            logs,
+        });
+        elapsed
+    }
+}
+
+#[cfg(test)]
+impl TargetReport {
+    /// Entry of the target of the `design` remote, without output, error and logs
+    pub(crate) fn stub(label: &str, status: TargetStatus) -> Self {
+        Self {
+            label: label.to_string(),
+            variant: None,
+            remote: "design".to_string(),
+            status,
+            duration_ms: 0,
+            output: None,
+            error: None,
+            warnings: Vec::new(),
+            logs: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn write__mixed_statuses__EXPECT__valid_summary() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("build-report.json");
+        let report = BuildReport::default();
+        {
+            let mut entries = report.entries.lock().unwrap();
+            entries.push(TargetReport::stub("//b:icon", TargetStatus::Failed));
+            entries.push(TargetReport::stub("//a:icon", TargetStatus::Exported));
+            entries.push(TargetReport::stub("//a:logo", TargetStatus::Cached));
+        }
+
+        // When
+        let remotes = [RemoteUsage {
+            remotes: vec!["design".to_string()],
+            file_key: "abc".to_string(),
+            document_bytes: 1024,
+            document_requests: 1,
+            export_requests: 2,
+            exported_nodes: 3,
+            tier1_requests: 3,
+            tier2_requests: 0,
+        }];
+        report
+            .write(&path, Duration::from_millis(42), &remotes)
+            .unwrap();
+
+        // Then
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(42, json["duration_ms"]);
+        assert_eq!(3, json["summary"]["total"]);
+        assert_eq!(1, json["summary"]["exported"]);
+        assert_eq!(1, json["summary"]["cached"]);
+        assert_eq!(1, json["summary"]["failed"]);
+        assert_eq!(1024, json["remotes"][0]["document_bytes"]);
+        assert_eq!(3, json["remotes"][0]["tier1_requests"]);
+        assert_eq!("//a:icon", json["targets"][0]["label"]);
+        assert_eq!("failed", json["targets"][2]["status"]);
+    }
+
+    #[test]
+    fn finish__warnings_logged__EXPECT__warnings_reported_for_successful_target() {
+        // Given
+        let report = BuildReport::default();
+        let handle = TargetReportHandle {
+            report: report.clone(),
+            label: "//a:icon".to_string(),
+            variant: None,
+            remote: "design".to_string(),
+            started: Instant::now(),
+        };
+        let logs = [
+            "debug [Figma API] get_image called".to_string(),
+            "warn [Validation] strokes may look blurry".to_string(),
+        ];
+
+        // When
+        handle.finish(&Ok(None), &logs);
+
+        // Then
+        let entries = report.entries.lock().unwrap();
+        assert_eq!(
+            vec!["[Validation] strokes may look blurry".to_string()],
+            entries[0].warnings
+        );
+        assert!(entries[0].logs.is_empty());
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{Target, figma::NodeMetadata};
+use ordermap::OrderMap;
+use phase_loading::{ExportScale, Profile};
+use std::collections::HashMap;
+
+/// Size of the node if it is unknown, a typical icon
+const DEFAULT_NODE_SIZE: (f32, f32) = (24.0, 24.0);
+
+/// Images beyond the Figma scale are rendered or upscaled locally after the download
+const LOCAL_RENDER_FACTOR: f32 = 2.0;
+
+/// Orders the targets so that the slowest ones are started first.
+///
+/// Targets are picked up by the free threads in this order, so a huge illustration
+/// doesn't start last and keep the import running long after the icons are done.
+pub(crate) fn sort_slowest_first(
+    targets: &mut [Target],
+    name_to_node: &HashMap<String, NodeMetadata>,
+) {
+    // targets without a node fail right away, so they go last
+    let cost = |target: &Target| match name_to_node.get(target.figma_name()) {
+        Some(node) => estimated_cost(target, node),
+        None => 0.0,
+    };
+    // costs are never negative, so their bits are ordered as the numbers;
+    // stable, so the targets of the same cost keep the order of the fig-files
+    targets.sort_by_cached_key(|target| std::cmp::Reverse(cost(target).to_bits()));
+}
+
+/// Groups the items by the Figma file they belong to, in the order of their first
+/// appearance. Requests for one file share its rate limit, so items of the same
+/// file are better processed one after another instead of competing for it.
+pub(crate) fn group_by_file<T>(
+    items: impl IntoIterator<Item = T>,
+    file_key: impl Fn(&T) -> &str,
+) -> Vec<Vec<T>> {
+    let mut groups = OrderMap::<String, Vec<T>>::new();
+    for item in items {
+        groups
+            .entry(file_key(&item).to_owned())
+            .or_default()
+            .push(item);
+    }
+    groups.into_values().collect()
+}
+
+/// Rough relative cost of the target in pixels to export, download and encode.
+///
+/// Raster images cost more with every pixel of every scale, while vectors are
+/// exported once and their cost barely depends on the size.
+fn estimated_cost(target: &Target, node: &NodeMetadata) -> f32 {
+    use Profile::*;
+    let size = node
+        .export_size(target.attrs.use_absolute_bounds)
+        .unwrap_or(DEFAULT_NODE_SIZE);
+    match target.profile {
+        Png(p) => raster_cost(size, [target.scale.unwrap_or(*p.scale)]),
+        Webp(p) => raster_cost(size, [target.scale.unwrap_or(*p.scale)]),
+        AndroidWebp(_) => raster_cost(size, [target.scale.unwrap_or(1.0)]),
+        Flutter(p) => raster_cost(size, p.scales.iter().map(|it| **it)),
+        // all sizes are rendered locally from a single SVG
+        Favicon(p) => vector_cost(DEFAULT_NODE_SIZE) * p.sizes.len() as f32,
+        _ => vector_cost(size),
+    }
+}
+
+fn raster_cost(size: (f32, f32), scales: impl IntoIterator<Item = f32>) -> f32 {
+    let area = size.0 * size.1;
+    scales
+        .into_iter()
+        .map(|scale| match scale > ExportScale::FIGMA_MAX {
+            true => area * scale * scale * LOCAL_RENDER_FACTOR,
+            false => area * scale * scale,
+        })
+        .sum()
+}
+
+fn vector_cost(size: (f32, f32)) -> f32 {
+    size.0.max(size.1)
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn raster_cost__more_scales_and_pixels__EXPECT__higher_cost() {
+        // Given
+        let icon = (24.0, 24.0);
+        let illustration = (360.0, 240.0);
+
+        // Then
+        assert!(raster_cost(icon, [1.0, 2.0, 3.0]) > raster_cost(icon, [1.0]));
+        assert!(raster_cost(illustration, [1.0]) > raster_cost(icon, [4.0]));
+        assert!(raster_cost(icon, [8.0]) > raster_cost(icon, [4.0, 4.0, 4.0, 4.0]));
+    }
+
+    #[test]
+    fn vector_cost__large_vector__EXPECT__cheaper_than_same_raster() {
+        // Given
+        let size = (360.0, 240.0);
+
+        // Then
+        assert!(vector_cost(size) < raster_cost(size, [1.0]));
+    }
+
+    #[test]
+    fn group_by_file__remotes_of_same_file__EXPECT__grouped_in_order_of_appearance() {
+        // Given
+        let remotes = [("icons", "A"), ("logos", "B"), ("illustrations", "A")];
+
+        // When
+        let groups = group_by_file(remotes, |(_, file_key)| *file_key);
+
+        // Then
+        assert_eq!(
+            vec![
+                vec![("icons", "A"), ("illustrations", "A")],
+                vec![("logos", "B")],
+            ],
+            groups
+        );
+    }
+}
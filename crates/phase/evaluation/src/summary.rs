@@ -0,0 +1,183 @@
+use crate::{RemoteUsage, TargetStatus, format_duration, usage::format_bytes};
+use phase_loading::ProfileKind;
+use std::{
+    collections::BTreeMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Outcomes of the targets grouped by the type of their profile, printed as a table
+/// at the end of the import
+#[derive(Default)]
+pub struct ImportSummary {
+    rows: Mutex<BTreeMap<&'static str, SummaryRow>>,
+}
+
+#[derive(Default, Clone, Copy)]
+struct SummaryRow {
+    targets: usize,
+    cached: usize,
+    exported: usize,
+    failed: usize,
+    bytes_written: u64,
+    /// Start of the first target of the group and the end of the last one
+    span: Option<(Instant, Instant)>,
+}
+
+impl ImportSummary {
+    pub(crate) fn record(
+        &self,
+        kind: ProfileKind,
+        status: TargetStatus,
+        bytes_written: usize,
+        elapsed: Duration,
+    ) {
+        let finished = Instant::now();
+        let started = finished.checked_sub(elapsed).unwrap_or(finished);
+        let mut rows = self.rows.lock().unwrap();
+        let row = rows.entry(kind.as_str()).or_default();
+        row.targets += 1;
+        match status {
+            TargetStatus::Exported => row.exported += 1,
+            TargetStatus::Cached => row.cached += 1,
+            TargetStatus::Failed => row.failed += 1,
+        }
+        row.bytes_written += bytes_written as u64;
+        row.span = Some(match row.span {
+            Some((first, last)) => (first.min(started), last.max(finished)),
+            None => (started, finished),
+        });
+    }
+
+    /// Lines of the table, one row per profile type and the total one, followed by
+    /// the number of requests sent to the Figma API. Empty if no target is evaluated.
+    pub(crate) fn table(&self, wall_time: Duration, usage: &[RemoteUsage]) -> Vec<String> {
+        let rows = self.rows.lock().unwrap();
+        if rows.is_empty() {
+            return Vec::new();
+        }
+        let total = rows
+            .values()
+            .fold(SummaryRow::default(), |acc, it| SummaryRow {
+                targets: acc.targets + it.targets,
+                cached: acc.cached + it.cached,
+                exported: acc.exported + it.exported,
+                failed: acc.failed + it.failed,
+                bytes_written: acc.bytes_written + it.bytes_written,
+                span: None,
+            });
+        let mut cells = vec![
+            [
+                "profile", "targets", "cached", "exported", "failed", "written", "time",
+            ]
+            .map(str::to_owned),
+        ];
+        for (kind, row) in rows.iter() {
+            let wall_time = row
+                .span
+                .map(|(first, last)| last - first)
+                .unwrap_or_default();
+            cells.push(row_cells(kind, row, wall_time));
+        }
+        if rows.len() > 1 {
+            cells.push(row_cells("total", &total, wall_time));
+        }
+
+        let widths: [usize; 7] =
+            std::array::from_fn(|col| cells.iter().map(|it| it[col].len()).max().unwrap_or(0));
+        let mut lines = cells
+            .iter()
+            .map(|row| {
+                let mut line = format!("{:<w$}", row[0], w = widths[0]);
+                for (cell, width) in row.iter().zip(widths).skip(1) {
+                    line.push_str(&format!("  {cell:>width$}"));
+                }
+                line
+            })
+            .collect::<Vec<_>>();
+        let document_requests = usage.iter().map(|it| it.document_requests).sum::<usize>();
+        let export_requests = usage.iter().map(|it| it.export_requests).sum::<usize>();
+        lines.push(format!(
+            "Figma API: {document_requests} document request{dp}, {export_requests} export request{ep}",
+            dp = if document_requests == 1 { "" } else { "s" },
+            ep = if export_requests == 1 { "" } else { "s" },
+        ));
+        lines
+    }
+}
+
+fn row_cells(name: &str, row: &SummaryRow, wall_time: Duration) -> [String; 7] {
+    [
+        name.to_owned(),
+        row.targets.to_string(),
+        row.cached.to_string(),
+        row.exported.to_string(),
+        row.failed.to_string(),
+        format_bytes(row.bytes_written),
+        format_duration(wall_time),
+    ]
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn table__two_profile_types__EXPECT__row_per_type_and_total() {
+        // Given
+        let summary = ImportSummary::default();
+        summary.record(
+            ProfileKind::Png,
+            TargetStatus::Exported,
+            2048,
+            Duration::ZERO,
+        );
+        summary.record(ProfileKind::Png, TargetStatus::Cached, 0, Duration::ZERO);
+        summary.record(
+            ProfileKind::Compose,
+            TargetStatus::Failed,
+            0,
+            Duration::ZERO,
+        );
+
+        // When
+        let lines = summary.table(Duration::from_secs(3), &[]);
+
+        // Then
+        assert_eq!(
+            vec![
+                "profile  targets  cached  exported  failed  written   time",
+                "compose        1       0         0       1      0 B  0 sec",
+                "png            2       1         1       0  2.0 KiB  0 sec",
+                "total          3       1         1       1  2.0 KiB  3 sec",
+                "Figma API: 0 document requests, 0 export requests",
+            ],
+            lines,
+        );
+    }
+
+    #[test]
+    fn table__single_profile_type__EXPECT__no_total_row() {
+        // Given
+        let summary = ImportSummary::default();
+        summary.record(ProfileKind::Svg, TargetStatus::Cached, 0, Duration::ZERO);
+
+        // When
+        let lines = summary.table(Duration::from_secs(1), &[]);
+
+        // Then
+        assert_eq!(3, lines.len());
+        assert!(lines[1].starts_with("svg "));
+    }
+
+    #[test]
+    fn table__nothing_evaluated__EXPECT__empty() {
+        // Then
+        assert!(
+            ImportSummary::default()
+                .table(Duration::ZERO, &[])
+                .is_empty()
+        );
+    }
+}
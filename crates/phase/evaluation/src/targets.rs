@@ -1,6 +1,5 @@
 use phase_loading::{
-    AndroidDensity, AndroidDrawableProfile, AndroidWebpProfile, Profile, Resource, ResourceAttrs,
-    ResourceVariants,
+    AndroidDrawableProfile, AndroidWebpProfile, Profile, Resource, ResourceAttrs, SingleNamePattern,
 };
 
 pub struct Target<'a> {
@@ -24,43 +23,37 @@ impl<'a> Target<'a> {
             .as_deref()
             .unwrap_or_else(|| self.attrs.label.name.as_ref())
     }
+
+    /// Label of the resource followed by the variant name, if any
+    pub fn qualified_name(&self) -> String {
+        match &self.id {
+            Some(id) if !id.is_empty() => format!("{} ({id})", self.attrs.label),
+            _ => self.attrs.label.to_string(),
+        }
+    }
 }
 
 pub fn targets_from_resource(res: &Resource) -> Vec<Target<'_>> {
-    use phase_loading::Profile::*;
     let variants = match res.profile.as_ref() {
-        Png(p) => p.variants.as_ref(),
-        Svg(p) => p.variants.as_ref(),
-        Pdf(p) => p.variants.as_ref(),
-        Webp(p) => p.variants.as_ref(),
-        Compose(p) => p.variants.as_ref(),
-        AndroidWebp(p) => return android_webp_targets(res, p),
-        AndroidDrawable(p) => return android_drawable_targets(res, p),
+        Profile::AndroidWebp(p) => return android_webp_targets(res, p),
+        Profile::AndroidDrawable(p) => return android_drawable_targets(res, p),
+        profile => profile.variants(),
     };
 
+    let base_name = res.output_base_name();
     match variants {
         None => vec![Target {
             id: None,
             attrs: &res.attrs,
             profile: &res.profile,
             figma_name: None,
-            output_name: None,
+            output_name: Some(base_name),
             scale: None,
         }],
-        Some(ResourceVariants {
-            all_variants,
-            use_variants,
-        }) => all_variants
-            .iter()
-            .filter(|(k, _)| match use_variants {
-                None => true,
-                Some(only) => only.contains(*k),
-            })
+        Some(variants) => variants
+            .active()
             .map(|(k, v)| {
-                let output_name = v
-                    .output_name
-                    .as_ref()
-                    .replace("{base}", &res.attrs.label.name.as_ref());
+                let output_name = v.output_name.as_ref().replace("{base}", &base_name);
                 let figma_name = v
                     .figma_name
                     .as_ref()
@@ -84,23 +77,48 @@ pub fn targets_from_resource(res: &Resource) -> Vec<Target<'_>> {
     }
 }
 
+/// Targets of the raster profile used instead of the vector one for the same resource
+pub fn fallback_targets<'a>(target: &Target<'a>, fallback: &'a Profile) -> Vec<Target<'a>> {
+    let night = target.id.as_deref() == Some("night");
+    match fallback {
+        Profile::AndroidWebp(p) => p
+            .scales
+            .iter()
+            .map(|density| Target {
+                id: Some(if night {
+                    format!("night-{}", density.name())
+                } else {
+                    density.name().to_string()
+                }),
+                attrs: target.attrs,
+                profile: fallback,
+                figma_name: Some(target.figma_name().to_owned()),
+                output_name: Some(target.output_name().to_owned()),
+                scale: Some(density.scale_factor()),
+            })
+            .collect(),
+        _ => vec![Target {
+            id: target.id.clone().filter(|it| !it.is_empty()),
+            attrs: target.attrs,
+            profile: fallback,
+            figma_name: Some(target.figma_name().to_owned()),
+            output_name: Some(target.output_name().to_owned()),
+            scale: target.scale,
+        }],
+    }
+}
+
 fn android_webp_targets<'a>(res: &'a Resource, profile: &'a AndroidWebpProfile) -> Vec<Target<'a>> {
     let scales = &profile.scales;
-    let themes: &[_] = if let Some(night_variant) = &profile.night {
-        let light_variant = &res.attrs.node_name;
-        let night_variant = night_variant.as_ref().replace("{base}", &light_variant);
-        &[(light_variant.to_owned(), false), (night_variant, true)]
-    } else {
-        let light_variant = &res.attrs.node_name;
-        &[(light_variant.to_owned(), false)]
-    };
-    let all_variants = cartesian_product(scales, themes);
+    let themes = theme_pairs(res, &profile.night, &profile.dark_source);
+    let all_variants = cartesian_product(scales, &themes);
+    let base_name = res.output_base_name();
 
     all_variants
         .into_iter()
         .map(|(density, (figma_name, night))| {
-            let factor = scale_factor(density);
-            let density_name = density_name(density);
+            let factor = density.scale_factor();
+            let density_name = density.name();
             let variant_name = if !night {
                 format!("{density_name}")
             } else {
@@ -112,13 +130,32 @@ fn android_webp_targets<'a>(res: &'a Resource, profile: &'a AndroidWebpProfile)
                 attrs: &res.attrs,
                 profile: &res.profile,
                 figma_name: Some(figma_name.to_owned()),
-                output_name: Some(res.attrs.label.name.to_string()),
+                output_name: Some(base_name.clone()),
                 scale: Some(factor),
             }
         })
         .collect()
 }
 
+/// Figma node names of the light asset and its dark pair, if any.
+/// Explicit `dark_source` of the resource takes precedence over the `night` pattern.
+fn theme_pairs(
+    res: &Resource,
+    night: &Option<SingleNamePattern>,
+    dark_source: &Option<String>,
+) -> Vec<(String, bool)> {
+    let light_variant = &res.attrs.node_name;
+    let night_variant = match (dark_source, night) {
+        (Some(dark_source), _) => Some(dark_source.to_owned()),
+        (None, Some(night)) => Some(night.as_ref().replace("{base}", light_variant)),
+        (None, None) => None,
+    };
+    match night_variant {
+        Some(night_variant) => vec![(light_variant.to_owned(), false), (night_variant, true)],
+        None => vec![(light_variant.to_owned(), false)],
+    }
+}
+
 pub fn cartesian_product<'a, A, B>(list_a: &'a [A], list_b: &'a [B]) -> Vec<(&'a A, &'a B)> {
     list_a
         .iter()
@@ -126,43 +163,13 @@ pub fn cartesian_product<'a, A, B>(list_a: &'a [A], list_b: &'a [B]) -> Vec<(&'a
         .collect()
 }
 
-pub fn scale_factor(d: &AndroidDensity) -> f32 {
-    use AndroidDensity::*;
-    match d {
-        LDPI => 0.75,
-        MDPI => 1.0,
-        HDPI => 1.5,
-        XHDPI => 2.0,
-        XXHDPI => 3.0,
-        XXXHDPI => 4.0,
-    }
-}
-
-pub fn density_name(d: &AndroidDensity) -> &str {
-    use AndroidDensity::*;
-    match d {
-        LDPI => "ldpi",
-        MDPI => "mdpi",
-        HDPI => "hdpi",
-        XHDPI => "xhdpi",
-        XXHDPI => "xxhdpi",
-        XXXHDPI => "xxxhdpi",
-    }
-}
-
 pub fn android_drawable_targets<'a>(
     res: &'a Resource,
     profile: &'a AndroidDrawableProfile,
 ) -> Vec<Target<'a>> {
-    let themes: &[_] = if let Some(night_variant) = &profile.night {
-        let light_variant = &res.attrs.node_name;
-        let night_variant = night_variant.as_ref().replace("{base}", &light_variant);
-        &[(light_variant.to_owned(), false), (night_variant, true)]
-    } else {
-        let light_variant = &res.attrs.node_name;
-        &[(light_variant.to_owned(), false)]
-    };
+    let themes = theme_pairs(res, &profile.night, &profile.dark_source);
     let all_variants = themes;
+    let base_name = res.output_base_name();
 
     all_variants
         .into_iter()
@@ -178,7 +185,7 @@ pub fn android_drawable_targets<'a>(
                 attrs: &res.attrs,
                 profile: &res.profile,
                 figma_name: Some(figma_name.to_owned()),
-                output_name: Some(res.attrs.label.name.to_string()),
+                output_name: Some(base_name.clone()),
                 scale: Some(1.0),
             }
         })
@@ -0,0 +1,137 @@
+use lib_dashboard::lifecycle;
+use lib_figma_fluent::FigmaApi;
+use lib_metrics::Metrics;
+use log::warn;
+use phase_loading::RemoteSource;
+use serde::Serialize;
+use std::{collections::BTreeMap, sync::Arc};
+
+/// Documents larger than this are reported, they slow down every fetch
+const LARGE_DOCUMENT_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Figma REST API usage of a single design file during the run
+#[derive(Serialize)]
+pub struct RemoteUsage {
+    /// Ids of the remotes pointing to the file
+    pub remotes: Vec<String>,
+    pub file_key: String,
+    pub document_bytes: u64,
+    pub document_requests: usize,
+    pub export_requests: usize,
+    pub exported_nodes: usize,
+    /// Estimated consumption of the Tier 1 rate limit
+    pub tier1_requests: usize,
+    /// Estimated consumption of the Tier 2 rate limit
+    pub tier2_requests: usize,
+}
+
+/// Usage of every requested remote which was not served entirely from cache
+pub(crate) fn remote_usage(remotes: &[Arc<RemoteSource>], api: &FigmaApi) -> Vec<RemoteUsage> {
+    let mut file_to_remotes = BTreeMap::<&str, Vec<String>>::new();
+    for remote in remotes {
+        let ids = file_to_remotes.entry(&remote.file_key).or_default();
+        if !ids.contains(&remote.id) {
+            ids.push(remote.id.clone());
+        }
+    }
+    let usage = api.usage().snapshot();
+    file_to_remotes
+        .into_iter()
+        .filter_map(|(file_key, remotes)| {
+            let file_usage = usage.get(file_key)?;
+            Some(RemoteUsage {
+                remotes,
+                file_key: file_key.to_owned(),
+                document_bytes: file_usage.document_bytes,
+                document_requests: file_usage.document_requests,
+                export_requests: file_usage.export_requests,
+                exported_nodes: file_usage.exported_nodes,
+                tier1_requests: file_usage.tier1_requests(),
+                tier2_requests: file_usage.tier2_requests(),
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn record_usage_metrics(metrics: &Metrics, usage: &[RemoteUsage]) {
+    let sum = |f: fn(&RemoteUsage) -> usize| usage.iter().map(f).sum::<usize>();
+    metrics
+        .counter("figx_remote_document_bytes")
+        .set(usage.iter().map(|it| it.document_bytes).sum::<u64>() as usize);
+    metrics.gauge("figx_remote_document_max_bytes").set(
+        usage
+            .iter()
+            .map(|it| it.document_bytes)
+            .max()
+            .unwrap_or_default() as i64,
+    );
+    metrics
+        .counter("figx_remote_document_requests")
+        .set(sum(|it| it.document_requests));
+    metrics
+        .counter("figx_remote_export_requests")
+        .set(sum(|it| it.export_requests));
+    metrics
+        .counter("figx_remote_exported_nodes")
+        .set(sum(|it| it.exported_nodes));
+    metrics
+        .counter("figx_figma_api_tier1_requests")
+        .set(sum(|it| it.tier1_requests));
+    metrics
+        .counter("figx_figma_api_tier2_requests")
+        .set(sum(|it| it.tier2_requests));
+}
+
+/// One line per remote at the end of the run, plus a warning for huge documents
+pub(crate) fn log_usage_summary(usage: &[RemoteUsage]) {
+    for it in usage {
+        let remotes = it.remotes.join(", ");
+        let document = match it.document_requests {
+            0 => "document cached".to_string(),
+            _ => format!("document {}", format_bytes(it.document_bytes)),
+        };
+        lifecycle!(
+            target: "@Remote",
+            "{remotes}: {document}, {en} export request{ep} for {nn} node{np}, ~{t1} Tier 1 and {t2} Tier 2 API requests",
+            en = it.export_requests,
+            ep = if it.export_requests == 1 { "" } else { "s" },
+            nn = it.exported_nodes,
+            np = if it.exported_nodes == 1 { "" } else { "s" },
+            t1 = it.tier1_requests,
+            t2 = it.tier2_requests,
+        );
+        if it.document_bytes > LARGE_DOCUMENT_BYTES {
+            warn!(
+                target: "Remote",
+                "document of `{remotes}` is {}, consider narrowing its `container_node_ids` or splitting the design file",
+                format_bytes(it.document_bytes),
+            );
+        }
+    }
+}
+
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    const KIB: f64 = 1024.0;
+    const MIB: f64 = KIB * 1024.0;
+    let bytes = bytes as f64;
+    if bytes < KIB {
+        format!("{bytes} B")
+    } else if bytes < MIB {
+        format!("{:.1} KiB", bytes / KIB)
+    } else {
+        format!("{:.1} MiB", bytes / MIB)
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn format_bytes__different_sizes__EXPECT__human_readable_units() {
+        assert_eq!("512 B", format_bytes(512));
+        assert_eq!("1.5 KiB", format_bytes(1536));
+        assert_eq!("120.0 MiB", format_bytes(120 * 1024 * 1024));
+    }
+}
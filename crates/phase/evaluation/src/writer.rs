@@ -0,0 +1,701 @@
+use crate::{ContentStore, Error, Result};
+use crossbeam_channel::{Receiver, Sender, bounded, unbounded};
+use dashmap::DashSet;
+use lib_dashboard::lifecycle;
+use log::{debug, info, warn};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread::JoinHandle,
+};
+use tempfile::TempDir;
+
+/// Writes materialized files to the disk.
+///
+/// Every output directory is created once per evaluation instead of once per file.
+/// With the write queue enabled, files are written by a dedicated thread, grouped
+/// by directory, and each touched directory is synced once when the queue is drained.
+/// Every write still waits for its file, so a failed write fails its target.
+/// On network filesystems this saves a lot of syscalls for directories with hundreds
+/// of files, like Android `drawable-*` ones.
+///
+/// With staging enabled, files are written to the local staging directory and
+/// moved to the workspace in one final phase, see [`FileWriter::finish`]. Targets of
+/// the files which could not be published are only known then, so they are returned
+/// as [`WriteFailure`]s.
+///
+/// With the content store, every written file is moved to the store and linked back,
+/// see [`ContentStore`].
+///
+/// In check mode the workspace is never touched: files are written to the check
+/// directory and compared with the workspace ones, see [`FileWriter::checking`].
+#[derive(Default)]
+pub struct FileWriter {
+    created_dirs: DashSet<PathBuf>,
+    queue: Mutex<Option<WriteQueue>>,
+    staging: Mutex<Option<Staging>>,
+    staged_count: AtomicUsize,
+    check: Option<Check>,
+    store: Option<Arc<ContentStore>>,
+}
+
+struct Check {
+    /// Removed along with the writer
+    dir: TempDir,
+    out_of_date: Mutex<Vec<PathBuf>>,
+}
+
+struct Staging {
+    dir: TempDir,
+    files: Vec<StagedFile>,
+}
+
+struct StagedFile {
+    staged_path: PathBuf,
+    job: WriteJob,
+}
+
+struct WriteQueue {
+    tx: Sender<WriteJob>,
+    thread: JoinHandle<()>,
+}
+
+struct WriteJob {
+    /// Qualified name of the target the file is written for
+    target: String,
+    path: PathBuf,
+    bytes: Vec<u8>,
+    /// Called right after the file is written
+    on_written: Box<dyn FnOnce(&Path) -> Result<()> + Send>,
+    /// Called once the written file is moved to the content store and linked back
+    on_stored: Box<dyn FnOnce(&Path) -> Result<()> + Send>,
+    /// Receives the result of the write from the write queue
+    done: Option<Sender<Result<()>>>,
+}
+
+/// File of the target which was not written to the workspace
+#[derive(Debug)]
+pub struct WriteFailure {
+    pub target: String,
+    pub path: PathBuf,
+    pub error: Error,
+}
+
+impl FileWriter {
+    /// Files are staged in `staging_dir` if it is set, the write queue is not used then
+    pub fn new(
+        write_queue: bool,
+        staging_dir: Option<PathBuf>,
+        store: Option<ContentStore>,
+    ) -> Result<Self> {
+        let store = store.map(Arc::new);
+        let staging = match staging_dir {
+            Some(dir) => Some(Staging {
+                dir: run_dir(&dir)?,
+                files: Vec::new(),
+            }),
+            None => None,
+        };
+        let queue = (write_queue && staging.is_none()).then(|| {
+            let (tx, rx) = unbounded::<WriteJob>();
+            let store = store.clone();
+            let thread = std::thread::spawn(move || process_write_queue(rx, store));
+            WriteQueue { tx, thread }
+        });
+        Ok(Self {
+            created_dirs: DashSet::new(),
+            queue: Mutex::new(queue),
+            staging: Mutex::new(staging),
+            staged_count: AtomicUsize::new(0),
+            check: None,
+            store,
+        })
+    }
+
+    /// Writer that only verifies the workspace files: every file is written to
+    /// `check_dir`, post-processed there and compared with the workspace one byte-for-byte.
+    /// Files which are missing or differ are collected, see [`FileWriter::out_of_date`].
+    pub fn checking(check_dir: PathBuf) -> Result<Self> {
+        Ok(Self {
+            check: Some(Check {
+                dir: run_dir(&check_dir)?,
+                out_of_date: Mutex::new(Vec::new()),
+            }),
+            ..Default::default()
+        })
+    }
+
+    /// Workspace files which don't match the imported ones, sorted by path.
+    /// Always empty unless the writer is created with [`FileWriter::checking`].
+    pub fn out_of_date(&self) -> Vec<PathBuf> {
+        let mut paths = self
+            .check
+            .as_ref()
+            .map(|check| check.out_of_date.lock().unwrap().clone())
+            .unwrap_or_default();
+        paths.sort();
+        paths.dedup();
+        paths
+    }
+
+    /// True if files are not written right away, so they may be missing
+    /// until [`FileWriter::finish`] is called
+    pub fn is_deferred(&self) -> bool {
+        self.staging.lock().unwrap().is_some()
+    }
+
+    /// Writes the file of the `target`, stages it or puts it into the write queue,
+    /// depending on the mode. `on_written` is called once the file is at its final path,
+    /// e.g. to post-process it, and `on_stored` once it is in its final form: moved to
+    /// the content store and linked back, if the store is used.
+    pub fn write(
+        &self,
+        target: &str,
+        path: PathBuf,
+        bytes: &[u8],
+        on_written: impl FnOnce(&Path) -> Result<()> + Send + 'static,
+        on_stored: impl FnOnce(&Path) -> Result<()> + Send + 'static,
+    ) -> Result<()> {
+        if let Some(check) = &self.check {
+            return self.check_now(check, path, bytes, on_written, on_stored);
+        }
+        let staging_dir = self
+            .staging
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|it| it.dir.path().to_path_buf());
+        if let Some(staging_dir) = staging_dir {
+            let staged_path =
+                staging_dir.join(self.staged_count.fetch_add(1, Ordering::SeqCst).to_string());
+            std::fs::write(&staged_path, bytes)?;
+            let job = WriteJob {
+                target: target.to_owned(),
+                path,
+                bytes: Vec::new(),
+                on_written: Box::new(on_written),
+                on_stored: Box::new(on_stored),
+                done: None,
+            };
+            match self.staging.lock().unwrap().as_mut() {
+                Some(staging) => staging.files.push(StagedFile { staged_path, job }),
+                // already published, publish this one too
+                None => return self.publish(StagedFile { staged_path, job }),
+            }
+            return Ok(());
+        }
+        let tx = self.queue.lock().unwrap().as_ref().map(|it| it.tx.clone());
+        if let Some(tx) = tx {
+            let (done_tx, done_rx) = bounded(1);
+            let job = WriteJob {
+                target: target.to_owned(),
+                path,
+                bytes: bytes.to_vec(),
+                on_written: Box::new(on_written),
+                on_stored: Box::new(on_stored),
+                done: Some(done_tx),
+            };
+            if let Err(e) = tx.send(job) {
+                // the writer thread is gone, fall back to writing in place
+                let job = e.into_inner();
+                return self.write_now(job.path, &job.bytes, job.on_written, job.on_stored);
+            }
+            return done_rx.recv().unwrap_or_else(|_| {
+                Err(Error::IO(std::io::Error::other(
+                    "write queue thread panicked",
+                )))
+            });
+        }
+        self.write_now(path, bytes, on_written, on_stored)
+    }
+
+    /// Waits until the write queue is drained and moves the staged files to the
+    /// workspace. Files written after that are written in place.
+    ///
+    /// Returns the staged files which could not be published along with their targets.
+    pub fn finish(&self) -> Result<Vec<WriteFailure>> {
+        if let Some(WriteQueue { tx, thread }) = self.queue.lock().unwrap().take() {
+            drop(tx);
+            thread
+                .join()
+                .map_err(|_| Error::IO(std::io::Error::other("write queue thread panicked")))?;
+        }
+        let staging = self.staging.lock().unwrap().take();
+        match staging {
+            Some(staging) => Ok(self.publish_staged(staging)),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Produces the file next to the other checked ones, keeping its name,
+    /// so post-processing commands see the same extension
+    fn check_now(
+        &self,
+        check: &Check,
+        path: PathBuf,
+        bytes: &[u8],
+        on_written: impl FnOnce(&Path) -> Result<()>,
+        on_stored: impl FnOnce(&Path) -> Result<()>,
+    ) -> Result<()> {
+        let checked_dir = check
+            .dir
+            .path()
+            .join(self.staged_count.fetch_add(1, Ordering::SeqCst).to_string());
+        std::fs::create_dir_all(&checked_dir)?;
+        let checked_path = checked_dir.join(path.file_name().unwrap_or_default());
+        std::fs::write(&checked_path, bytes)?;
+        let result = on_written(&checked_path)
+            .and_then(|_| on_stored(&checked_path))
+            .and_then(|_| Ok(std::fs::read(&checked_path)?));
+        let _ = std::fs::remove_dir_all(&checked_dir);
+        let expected = result?;
+        match std::fs::read(&path) {
+            Ok(actual) if actual == expected => (),
+            _ => {
+                debug!(target: "Check", "out of date: {}", path.display());
+                check.out_of_date.lock().unwrap().push(path);
+            }
+        }
+        Ok(())
+    }
+
+    fn publish_staged(&self, staging: Staging) -> Vec<WriteFailure> {
+        let Staging { dir, files } = staging;
+        let total = files.len();
+        let mut by_dir = BTreeMap::<PathBuf, Vec<StagedFile>>::new();
+        for file in files {
+            let dir = file
+                .job
+                .path
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_default();
+            by_dir.entry(dir).or_default().push(file);
+        }
+        let mut published = 0;
+        let mut failures = Vec::new();
+        for (target_dir, files) in by_dir {
+            info!(
+                target: "Publishing",
+                "{published}/{total} files: `{}`", target_dir.display(),
+            );
+            for file in files {
+                let target = file.job.target.clone();
+                let path = file.job.path.clone();
+                if let Err(error) = self.publish(file) {
+                    failures.push(WriteFailure {
+                        target,
+                        path,
+                        error,
+                    });
+                }
+                published += 1;
+            }
+        }
+        let dir_path = dir.path().to_path_buf();
+        if let Err(e) = dir.close() {
+            warn!(target: "Publishing", "unable to clean up `{}`: {e}", dir_path.display());
+        }
+        if total > 0 {
+            lifecycle!(
+                target: "@Published",
+                "{total} staged file{s} to the workspace",
+                s = if total == 1 { "" } else { "s" },
+            );
+        }
+        failures
+    }
+
+    /// Moves the staged file to its final path. Staging directory is usually on
+    /// another device, so the file is copied if it cannot be renamed.
+    fn publish(&self, file: StagedFile) -> Result<()> {
+        let StagedFile { staged_path, job } = file;
+        self.ensure_parent_dir(&job.path)?;
+        if std::fs::rename(&staged_path, &job.path).is_err() {
+            if let Some(store) = &self.store {
+                store.unlink(&job.path)?;
+            }
+            std::fs::copy(&staged_path, &job.path)?;
+        }
+        store_written(
+            self.store.as_deref(),
+            &job.path,
+            job.on_written,
+            job.on_stored,
+        )
+    }
+
+    fn ensure_parent_dir(&self, path: &Path) -> Result<()> {
+        match path.parent() {
+            Some(dir) if !self.created_dirs.contains(dir) => {
+                std::fs::create_dir_all(dir)?;
+                self.created_dirs.insert(dir.to_path_buf());
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    fn write_now(
+        &self,
+        path: PathBuf,
+        bytes: &[u8],
+        on_written: impl FnOnce(&Path) -> Result<()>,
+        on_stored: impl FnOnce(&Path) -> Result<()>,
+    ) -> Result<()> {
+        self.ensure_parent_dir(&path)?;
+        if let Some(store) = &self.store {
+            store.unlink(&path)?;
+        }
+        std::fs::write(&path, bytes)?;
+        store_written(self.store.as_deref(), &path, on_written, on_stored)
+    }
+}
+
+/// Finishes the file written to `path`: the written file is processed first, as the
+/// stored objects must never be modified, and the stored one is reported as final
+fn store_written(
+    store: Option<&ContentStore>,
+    path: &Path,
+    on_written: impl FnOnce(&Path) -> Result<()>,
+    on_stored: impl FnOnce(&Path) -> Result<()>,
+) -> Result<()> {
+    on_written(path)?;
+    if let Some(store) = store {
+        store.absorb(path)?;
+    }
+    on_stored(path)
+}
+
+/// Directory of this run inside `parent`, unique among the concurrent runs and removed
+/// even if the run fails, so interrupted runs do not pile up their files there
+fn run_dir(parent: &Path) -> Result<TempDir> {
+    std::fs::create_dir_all(parent)?;
+    Ok(tempfile::Builder::new().prefix("run-").tempdir_in(parent)?)
+}
+
+fn process_write_queue(rx: Receiver<WriteJob>, store: Option<Arc<ContentStore>>) {
+    let mut created_dirs = BTreeSet::new();
+    while let Ok(job) = rx.recv() {
+        // take everything queued so far and write it directory by directory
+        let mut batch = BTreeMap::<PathBuf, Vec<WriteJob>>::new();
+        for job in std::iter::once(job).chain(rx.try_iter()) {
+            let dir = job.path.parent().map(Path::to_path_buf).unwrap_or_default();
+            batch.entry(dir).or_default().push(job);
+        }
+        debug!(target: "Materialize", "writing {} directories from the queue", batch.len());
+        for (dir, jobs) in batch {
+            if write_batch(&dir, jobs, !created_dirs.contains(&dir), store.as_deref()) {
+                created_dirs.insert(dir);
+            }
+        }
+    }
+    for dir in &created_dirs {
+        if let Err(e) = sync_dir(dir) {
+            warn!(target: "Materialize", "unable to sync `{}`: {e}", dir.display());
+        }
+    }
+}
+
+/// Writes the files of one directory and reports the result of every write
+/// to its job. Returns false if the directory could not be created
+fn write_batch(
+    dir: &Path,
+    jobs: Vec<WriteJob>,
+    create_dir: bool,
+    store: Option<&ContentStore>,
+) -> bool {
+    let created = match create_dir && !dir.as_os_str().is_empty() {
+        true => std::fs::create_dir_all(dir),
+        false => Ok(()),
+    };
+    for job in jobs {
+        let result = match &created {
+            Err(e) => Err(Error::IO(std::io::Error::new(e.kind(), e.to_string()))),
+            Ok(_) => store
+                .map_or(Ok(()), |store| store.unlink(&job.path))
+                .and_then(|_| Ok(std::fs::write(&job.path, &job.bytes)?))
+                .and_then(|_| store_written(store, &job.path, job.on_written, job.on_stored)),
+        };
+        if let Err(e) = &result {
+            debug!(target: "Materialize", "unable to write `{}` of `{}`: {e}", job.path.display(), job.target);
+        }
+        if let Some(done) = job.done {
+            let _ = done.send(result);
+        }
+    }
+    created.is_ok()
+}
+
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> std::io::Result<()> {
+    if dir.as_os_str().is_empty() {
+        return Ok(());
+    }
+    std::fs::File::open(dir)?.sync_all()
+}
+
+/// Directories cannot be opened for syncing on Windows, their entries are
+/// flushed together with the files.
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use crate::get_file_fingerprint;
+    use phase_loading::OutputLinkMode;
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    #[test]
+    fn FileWriter__write_queue__EXPECT__all_files_written_after_finish() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let writer = FileWriter::new(true, None, None).unwrap();
+        let written = Arc::new(AtomicUsize::new(0));
+
+        // When
+        for i in 0..100 {
+            let written = written.clone();
+            let path = dir
+                .path()
+                .join(format!("drawable-{}", i % 3))
+                .join(format!("{i}.xml"));
+            writer
+                .write(
+                    "//icons:ic_star",
+                    path,
+                    i.to_string().as_bytes(),
+                    move |_| {
+                        written.fetch_add(1, Ordering::SeqCst);
+                        Ok(())
+                    },
+                    |_| Ok(()),
+                )
+                .unwrap();
+        }
+        writer.finish().unwrap();
+
+        // Then
+        assert_eq!(100, written.load(Ordering::SeqCst));
+        let content = std::fs::read_to_string(dir.path().join("drawable-1").join("42.xml"));
+        assert_eq!("42", content.unwrap());
+    }
+
+    #[test]
+    fn FileWriter__without_write_queue__EXPECT__file_written_in_place() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let writer = FileWriter::new(false, None, None).unwrap();
+        let path = dir.path().join("a").join("b").join("file.txt");
+
+        // When
+        writer
+            .write(
+                "//icons:ic_star",
+                path.clone(),
+                b"hello",
+                |_| Ok(()),
+                |_| Ok(()),
+            )
+            .unwrap();
+
+        // Then
+        assert_eq!("hello", std::fs::read_to_string(&path).unwrap());
+        assert!(
+            writer
+                .created_dirs
+                .contains(&dir.path().join("a").join("b"))
+        );
+    }
+
+    #[test]
+    fn FileWriter__staging__EXPECT__files_published_on_finish() {
+        // Given
+        let staging = tempfile::tempdir().unwrap();
+        let workspace = tempfile::tempdir().unwrap();
+        let writer = FileWriter::new(false, Some(staging.path().to_path_buf()), None).unwrap();
+        let path = workspace
+            .path()
+            .join("res")
+            .join("drawable")
+            .join("ic_star.xml");
+
+        // When
+        writer
+            .write(
+                "//icons:ic_star",
+                path.clone(),
+                b"<vector/>",
+                |_| Ok(()),
+                |_| Ok(()),
+            )
+            .unwrap();
+        let exists_before_finish = path.exists();
+        let failures = writer.finish().unwrap();
+
+        // Then
+        assert!(!exists_before_finish);
+        assert!(failures.is_empty());
+        assert_eq!("<vector/>", std::fs::read_to_string(&path).unwrap());
+        assert!(
+            std::fs::read_dir(staging.path()).unwrap().next().is_none(),
+            "staging directory is cleaned up"
+        );
+    }
+
+    #[test]
+    fn FileWriter__checking__EXPECT__workspace_untouched_and_stale_files_reported() {
+        // Given
+        let check = tempfile::tempdir().unwrap();
+        let workspace = tempfile::tempdir().unwrap();
+        let up_to_date = workspace.path().join("up_to_date.svg");
+        let changed = workspace.path().join("changed.svg");
+        let missing = workspace.path().join("missing.svg");
+        std::fs::write(&up_to_date, b"<svg/>").unwrap();
+        std::fs::write(&changed, b"<svg/>").unwrap();
+        let writer = FileWriter::checking(check.path().to_path_buf()).unwrap();
+
+        // When
+        for path in [&up_to_date, &changed, &missing] {
+            let bytes: &[u8] = if *path == changed {
+                b"<svg></svg>"
+            } else {
+                b"<svg/>"
+            };
+            writer
+                .write(
+                    "//icons:ic_star",
+                    path.clone(),
+                    bytes,
+                    |_| Ok(()),
+                    |_| Ok(()),
+                )
+                .unwrap();
+        }
+        writer.finish().unwrap();
+
+        // Then
+        assert_eq!(vec![changed.clone(), missing.clone()], writer.out_of_date());
+        assert_eq!("<svg/>", std::fs::read_to_string(&changed).unwrap());
+        assert!(!missing.exists());
+    }
+
+    #[test]
+    fn FileWriter__write_queue_fails_to_write__EXPECT__error_returned_to_target() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let not_a_dir = dir.path().join("drawable");
+        std::fs::write(&not_a_dir, b"").unwrap();
+        let writer = FileWriter::new(true, None, None).unwrap();
+
+        // When
+        let failed = writer.write(
+            "//icons:ic_star",
+            not_a_dir.join("ic_star.xml"),
+            b"<vector/>",
+            |_| Ok(()),
+            |_| Ok(()),
+        );
+        let written = writer.write(
+            "//icons:ic_moon",
+            dir.path().join("ic_moon.xml"),
+            b"<vector/>",
+            |_| Ok(()),
+            |_| Ok(()),
+        );
+        writer.finish().unwrap();
+
+        // Then
+        assert!(failed.is_err());
+        assert!(written.is_ok());
+    }
+
+    #[test]
+    fn FileWriter__staged_file_not_published__EXPECT__failure_of_its_target() {
+        // Given
+        let staging = tempfile::tempdir().unwrap();
+        let workspace = tempfile::tempdir().unwrap();
+        let not_a_dir = workspace.path().join("drawable");
+        std::fs::write(&not_a_dir, b"").unwrap();
+        let writer = FileWriter::new(false, Some(staging.path().to_path_buf()), None).unwrap();
+        let unpublished = not_a_dir.join("ic_star.xml");
+        let published = workspace.path().join("ic_moon.xml");
+
+        // When
+        writer
+            .write(
+                "//icons:ic_star",
+                unpublished.clone(),
+                b"<vector/>",
+                |_| Ok(()),
+                |_| Ok(()),
+            )
+            .unwrap();
+        writer
+            .write(
+                "//icons:ic_moon",
+                published.clone(),
+                b"<vector/>",
+                |_| Ok(()),
+                |_| Ok(()),
+            )
+            .unwrap();
+        let failures = writer.finish().unwrap();
+
+        // Then
+        assert_eq!(1, failures.len());
+        assert_eq!("//icons:ic_star", failures[0].target);
+        assert_eq!(unpublished, failures[0].path);
+        assert!(published.exists());
+    }
+
+    #[test]
+    fn FileWriter__content_store__EXPECT__linked_files_reported_as_stored() {
+        // Given
+        let store = tempfile::tempdir().unwrap();
+        let workspace = tempfile::tempdir().unwrap();
+        let store = ContentStore::new(store.path().to_path_buf(), OutputLinkMode::Hardlink);
+        let writer = FileWriter::new(false, None, Some(store)).unwrap();
+        let paths = [
+            workspace.path().join("ic_star.svg"),
+            workspace.path().join("ic_star_copy.svg"),
+        ];
+        let fingerprints = Arc::new(Mutex::new(Vec::new()));
+
+        // When
+        for path in &paths {
+            let fingerprints = fingerprints.clone();
+            writer
+                .write(
+                    "//icons:ic_star",
+                    path.clone(),
+                    b"<svg/>",
+                    |_| Ok(()),
+                    move |path| {
+                        let fingerprint = get_file_fingerprint(path)?;
+                        fingerprints.lock().unwrap().push(fingerprint);
+                        Ok(())
+                    },
+                )
+                .unwrap();
+        }
+
+        // Then
+        let actual = paths
+            .iter()
+            .map(|path| get_file_fingerprint(path).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(actual, *fingerprints.lock().unwrap());
+    }
+}
@@ -0,0 +1,171 @@
+//! End-to-end tests of the whole pipeline against the fake Figma server:
+//! load → fetch → export → transform → materialize.
+
+use figma_fake_server::{FakeFigmaServer, FakeNode};
+use lib_figma_fluent::FigmaApi;
+use lib_label::LabelPattern;
+use phase_evaluation::{EvalArgs, ExecutionObserver, TargetMeta, TargetStatus};
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+const STAR_SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" width="24" height="24"><path d="M12 2l3 7h7l-6 5 2 8-6-4-6 4 2-8-6-5h7z" fill="#000"/></svg>"##;
+const MOON_SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" width="16" height="16"><circle cx="8" cy="8" r="6" fill="#000"/></svg>"##;
+
+fn fake_server() -> FakeFigmaServer {
+    FakeFigmaServer::start().file(
+        "FILE",
+        [
+            FakeNode::component("1:2", "Star").with_image("svg", STAR_SVG),
+            FakeNode::component("1:3", "Moon").with_image("svg", MOON_SVG),
+        ],
+    )
+}
+
+fn create_workspace(dir: &Path) {
+    std::fs::write(
+        dir.join(".figtree.toml"),
+        r#"
+[remotes.design]
+default = true
+file_key = "FILE"
+container_node_ids = ["0:1"]
+access_token = "token"
+"#,
+    )
+    .unwrap();
+    std::fs::create_dir_all(dir.join("icons")).unwrap();
+    std::fs::write(
+        dir.join("icons").join(".fig.toml"),
+        r#"
+[svg]
+ic_star = "Star"
+
+[png]
+ic_moon = { name = "Moon", scale = 2.0 }
+"#,
+    )
+    .unwrap();
+}
+
+fn import(dir: &Path, server: &FakeFigmaServer) {
+    let pattern = LabelPattern::try_from(vec!["//...".to_string()]).unwrap();
+    let ws = phase_loading::load_workspace_in(dir, pattern, false).unwrap();
+    let args = EvalArgs {
+        concurrency: 2,
+        figma_api: FigmaApi::default().with_base_url(server.url()),
+        ..Default::default()
+    };
+    phase_evaluation::evaluate(ws, args).unwrap();
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn evaluate__svg_and_png_resources__EXPECT__files_materialized() {
+    // Given
+    let server = fake_server();
+    let dir = tempfile::tempdir().unwrap();
+    create_workspace(dir.path());
+
+    // When
+    import(dir.path(), &server);
+
+    // Then
+    let svg = std::fs::read_to_string(dir.path().join("icons").join("ic_star.svg")).unwrap();
+    assert_eq!(STAR_SVG, svg);
+    let png = image::open(dir.path().join("icons").join("ic_moon.png")).unwrap();
+    assert_eq!((32, 32), (png.width(), png.height()));
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn evaluate__second_import__EXPECT__images_not_exported_again() {
+    // Given
+    let server = fake_server();
+    let dir = tempfile::tempdir().unwrap();
+    create_workspace(dir.path());
+    import(dir.path(), &server);
+    let exports = server.requests_to("/v1/images/FILE");
+    let downloads = server.requests_to("/images/FILE");
+
+    // When
+    import(dir.path(), &server);
+
+    // Then
+    assert!(exports > 0);
+    assert_eq!(exports, server.requests_to("/v1/images/FILE"));
+    assert_eq!(downloads, server.requests_to("/images/FILE"));
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn evaluate__unknown_node__EXPECT__error() {
+    // Given
+    let server = FakeFigmaServer::start().file("FILE", []);
+    let dir = tempfile::tempdir().unwrap();
+    create_workspace(dir.path());
+    let pattern = LabelPattern::try_from(vec!["//...".to_string()]).unwrap();
+    let ws = phase_loading::load_workspace_in(dir.path(), pattern, false).unwrap();
+    let args = EvalArgs {
+        figma_api: FigmaApi::default().with_base_url(server.url()),
+        ..Default::default()
+    };
+
+    // When
+    let result = phase_evaluation::evaluate(ws, args);
+
+    // Then
+    assert!(result.is_err());
+}
+
+#[derive(Default)]
+struct RecordingObserver {
+    events: Mutex<Vec<String>>,
+}
+
+impl ExecutionObserver for RecordingObserver {
+    fn on_target_started(&self, meta: &TargetMeta) {
+        let event = format!("started {}", meta.qualified_name);
+        self.events.lock().unwrap().push(event);
+    }
+
+    fn on_target_finished(&self, meta: &TargetMeta, _status: TargetStatus, _elapsed: Duration) {
+        let event = format!("finished {}", meta.qualified_name);
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn evaluate__custom_observer__EXPECT__notified_about_every_target() {
+    // Given
+    let server = fake_server();
+    let dir = tempfile::tempdir().unwrap();
+    create_workspace(dir.path());
+    let observer = Arc::new(RecordingObserver::default());
+    let pattern = LabelPattern::try_from(vec!["//...".to_string()]).unwrap();
+    let ws = phase_loading::load_workspace_in(dir.path(), pattern, false).unwrap();
+    let args = EvalArgs {
+        figma_api: FigmaApi::default().with_base_url(server.url()),
+        observer: Some(observer.clone()),
+        ..Default::default()
+    };
+
+    // When
+    phase_evaluation::evaluate(ws, args).unwrap();
+
+    // Then
+    let mut events = observer.events.lock().unwrap().clone();
+    events.sort();
+    assert_eq!(
+        vec![
+            "finished //icons:ic_moon",
+            "finished //icons:ic_star",
+            "started //icons:ic_moon",
+            "started //icons:ic_star",
+        ],
+        events
+    );
+}
@@ -0,0 +1,735 @@
+//! Typed accessors answering common questions about profiles and resources,
+//! so that consumers of the loading phase do not match over all profile variants.
+
+use crate::{
+    AndroidDensity, ComposeProfile, FaviconProfile, FlutterProfile, NameCase, NamingRules,
+    PdfProfile, Profile, ReactProfile, Resource, ResourceVariant, ResourceVariants,
+};
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// Kind of the profile, displayed the same way it is named in the config
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ProfileKind {
+    Png,
+    Svg,
+    Pdf,
+    Webp,
+    Compose,
+    AndroidWebp,
+    AndroidDrawable,
+    Flutter,
+    React,
+    Favicon,
+}
+
+impl ProfileKind {
+    pub const ALL: [ProfileKind; 10] = [
+        Self::Png,
+        Self::Svg,
+        Self::Pdf,
+        Self::Webp,
+        Self::Compose,
+        Self::AndroidWebp,
+        Self::AndroidDrawable,
+        Self::Flutter,
+        Self::React,
+        Self::Favicon,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        use ProfileKind::*;
+        match self {
+            Png => "png",
+            Svg => "svg",
+            Pdf => "pdf",
+            Webp => "webp",
+            Compose => "compose",
+            AndroidWebp => "android-webp",
+            AndroidDrawable => "android-drawable",
+            Flutter => "flutter",
+            React => "react",
+            Favicon => "favicon",
+        }
+    }
+}
+
+impl FromStr for ProfileKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|it| it.as_str() == s)
+            .ok_or_else(|| {
+                let kinds = Self::ALL.map(|it| it.as_str()).join(", ");
+                format!("unknown profile type `{s}`, expected one of: {kinds}")
+            })
+    }
+}
+
+impl Display for ProfileKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Profile {
+    pub fn kind(&self) -> ProfileKind {
+        use Profile::*;
+        match self {
+            Png(_) => ProfileKind::Png,
+            Svg(_) => ProfileKind::Svg,
+            Pdf(_) => ProfileKind::Pdf,
+            Webp(_) => ProfileKind::Webp,
+            Compose(_) => ProfileKind::Compose,
+            AndroidWebp(_) => ProfileKind::AndroidWebp,
+            AndroidDrawable(_) => ProfileKind::AndroidDrawable,
+            Flutter(_) => ProfileKind::Flutter,
+            React(_) => ProfileKind::React,
+            Favicon(_) => ProfileKind::Favicon,
+        }
+    }
+
+    /// Variants declared by the profile. Android profiles derive their variants
+    /// from densities and themes instead, so they have none.
+    pub fn variants(&self) -> Option<&ResourceVariants> {
+        use Profile::*;
+        match self {
+            Png(p) => p.variants.as_ref(),
+            Svg(p) => p.variants.as_ref(),
+            Pdf(p) => p.variants.as_ref(),
+            Webp(p) => p.variants.as_ref(),
+            Compose(p) => p.variants.as_ref(),
+            React(p) => p.variants.as_ref(),
+            Favicon(p) => p.variants.as_ref(),
+            AndroidWebp(_) | AndroidDrawable(_) | Flutter(_) => None,
+        }
+    }
+
+    pub fn naming(&self) -> &NamingRules {
+        use Profile::*;
+        match self {
+            Png(p) => &p.naming,
+            Svg(p) => &p.naming,
+            Pdf(p) => &p.naming,
+            Webp(p) => &p.naming,
+            Compose(p) => &p.naming,
+            AndroidWebp(p) => &p.naming,
+            AndroidDrawable(p) => &p.naming,
+            Flutter(p) => &p.naming,
+            React(p) => &p.naming,
+            Favicon(p) => &p.naming,
+        }
+    }
+}
+
+impl NamingRules {
+    /// Output name of the resource with the given name
+    pub fn apply(&self, name: &str) -> String {
+        let mut name = name.to_owned();
+        for pattern in &self.strip {
+            name = name.replace(pattern, "");
+        }
+        let name = match self.case {
+            Some(case) => case.apply(&name),
+            None => name,
+        };
+        format!("{}{name}{}", self.prefix, self.suffix)
+    }
+}
+
+impl NameCase {
+    /// Converts the name split into words by non-alphanumeric characters and camel case humps,
+    /// e.g. `Icon / ArrowLeft 24` in snake case is `icon_arrow_left_24`
+    pub fn apply(self, name: &str) -> String {
+        let words = split_words(name);
+        let capitalize = |word: &&str| {
+            let (first, rest) = word.split_at(1);
+            first.to_ascii_uppercase() + &rest.to_ascii_lowercase()
+        };
+        match self {
+            NameCase::Snake => join_lowercase(&words, "_"),
+            NameCase::Kebab => join_lowercase(&words, "-"),
+            NameCase::Camel => words
+                .iter()
+                .enumerate()
+                .map(|(i, word)| match i {
+                    0 => word.to_ascii_lowercase(),
+                    _ => capitalize(word),
+                })
+                .collect(),
+            NameCase::Pascal => words.iter().map(capitalize).collect(),
+        }
+    }
+}
+
+fn split_words(name: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = None;
+    let mut prev = None;
+    for (i, c) in name.char_indices() {
+        if !c.is_ascii_alphanumeric() {
+            if let Some(start) = start.take() {
+                words.push(&name[start..i]);
+            }
+            prev = None;
+            continue;
+        }
+        let hump = c.is_ascii_uppercase()
+            && prev.is_some_and(|it: char| it.is_ascii_lowercase() || it.is_ascii_digit());
+        if hump {
+            if let Some(start) = start.take() {
+                words.push(&name[start..i]);
+            }
+        }
+        start.get_or_insert(i);
+        prev = Some(c);
+    }
+    if let Some(start) = start {
+        words.push(&name[start..]);
+    }
+    words
+}
+
+fn join_lowercase(words: &[&str], separator: &str) -> String {
+    words
+        .iter()
+        .map(|it| it.to_ascii_lowercase())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+impl ResourceVariants {
+    /// Variants which are actually imported, in the order of their names
+    pub fn active(&self) -> impl Iterator<Item = (&String, &ResourceVariant)> {
+        self.all_variants
+            .iter()
+            .filter(|(k, _)| match &self.use_variants {
+                None => true,
+                Some(only) => only.contains(*k),
+            })
+    }
+}
+
+impl AndroidDensity {
+    pub fn name(&self) -> &'static str {
+        use AndroidDensity::*;
+        match self {
+            LDPI => "ldpi",
+            MDPI => "mdpi",
+            HDPI => "hdpi",
+            XHDPI => "xhdpi",
+            XXHDPI => "xxhdpi",
+            XXXHDPI => "xxxhdpi",
+        }
+    }
+
+    pub fn scale_factor(&self) -> f32 {
+        use AndroidDensity::*;
+        match self {
+            LDPI => 0.75,
+            MDPI => 1.0,
+            HDPI => 1.5,
+            XHDPI => 2.0,
+            XXHDPI => 3.0,
+            XXXHDPI => 4.0,
+        }
+    }
+}
+
+const MULTIPLATFORM_SRC_DIR: &str = "src/commonMain/kotlin";
+
+impl ComposeProfile {
+    /// Directory of the generated Kotlin files of the package at `abs_package_dir`
+    pub fn output_dir(&self, abs_package_dir: &Path) -> PathBuf {
+        // multiplatform code goes to the common source set, unless specified otherwise
+        let kt_src_dir = if self.multiplatform && self.src_dir.as_os_str().is_empty() {
+            Path::new(MULTIPLATFORM_SRC_DIR)
+        } else {
+            self.src_dir.as_path()
+        };
+        let kt_package = match &self.package {
+            Some(package) => package.replace('.', "/"),
+            None => String::new(),
+        };
+        // {abs_package_dir}/{kt_src_dir}/{pkg_dir}
+        abs_package_dir.join(kt_src_dir).join(kt_package)
+    }
+}
+
+impl FlutterProfile {
+    /// Name of the Flutter resolution-aware directory, e.g. `2.0x` or `1.5x`
+    pub fn density_dir_name(scale: f32) -> String {
+        if scale.fract() == 0.0 {
+            format!("{scale:.1}x")
+        } else {
+            format!("{scale}x")
+        }
+    }
+}
+
+impl PdfProfile {
+    /// File name of the PNG preview with the extension, as the scale may contain a dot,
+    /// e.g. `doc.png` or `doc@1.5x.png`
+    pub fn preview_file_name(output_name: &str, scale: f32) -> String {
+        if scale == 1.0 {
+            format!("{output_name}.png")
+        } else {
+            format!("{output_name}@{scale}x.png")
+        }
+    }
+}
+
+impl FaviconProfile {
+    /// File name of the PNG image without extension, e.g. `favicon-32x32`
+    pub fn png_file_name(output_name: &str, size: u32) -> String {
+        format!("{output_name}-{size}x{size}")
+    }
+}
+
+impl ReactProfile {
+    /// Converts resource name like `ic_arrow-back` into PascalCase `IcArrowBack`
+    pub fn component_name(output_name: &str) -> String {
+        let mut output = String::with_capacity(output_name.len());
+        let mut upper_next = true;
+        for c in output_name.chars() {
+            if !c.is_ascii_alphanumeric() {
+                upper_next = true;
+            } else if upper_next {
+                output.push(c.to_ascii_uppercase());
+                upper_next = false;
+            } else {
+                output.push(c);
+            }
+        }
+        match output.chars().next() {
+            Some(c) if c.is_ascii_digit() => format!("Icon{output}"),
+            _ => output,
+        }
+    }
+}
+
+impl Resource {
+    /// Scale the node of the `variant` is exported with, or `None` if the profile
+    /// exports several scales of every node, like Android densities
+    pub fn effective_scale(&self, variant: Option<&str>) -> Option<f32> {
+        use Profile::*;
+        let variant_scale = variant
+            .and_then(|id| self.profile.variants()?.all_variants.get(id))
+            .and_then(|it| it.scale.as_deref().copied());
+        match self.profile.as_ref() {
+            Png(p) => Some(variant_scale.unwrap_or(*p.scale)),
+            Webp(p) => Some(variant_scale.unwrap_or(*p.scale)),
+            AndroidWebp(_) | Flutter(_) | Favicon(_) => None,
+            _ => Some(1.0),
+        }
+    }
+
+    /// Every file written for the resource: one per variant, density or theme, followed
+    /// by its auxiliary files, like previews or monochrome drawables.
+    ///
+    /// The Dart class of Flutter assets is shared by the whole package, so it is not listed.
+    pub fn expected_outputs(&self) -> Vec<PathBuf> {
+        use Profile::*;
+        let package_dir = &self.attrs.package_dir;
+        let name = self.output_base_name();
+        let files = |dir: PathBuf, extension: &str| -> Vec<PathBuf> {
+            self.output_names()
+                .into_iter()
+                .map(|it| dir.join(format!("{it}.{extension}")))
+                .collect()
+        };
+        match self.profile.as_ref() {
+            Png(p) => files(package_dir.join(&p.output_dir), "png"),
+            Svg(p) => files(package_dir.join(&p.output_dir), "svg"),
+            Pdf(p) => {
+                let dir = package_dir.join(&p.output_dir);
+                let mut outputs = Vec::new();
+                for it in self.output_names() {
+                    outputs.push(dir.join(format!("{it}.pdf")));
+                    for scale in &p.preview_scales {
+                        outputs.push(dir.join(PdfProfile::preview_file_name(&it, **scale)));
+                    }
+                }
+                outputs
+            }
+            Webp(p) => files(package_dir.join(&p.output_dir), "webp"),
+            Favicon(p) => {
+                let dir = package_dir.join(&p.output_dir);
+                let mut outputs = Vec::new();
+                for it in self.output_names() {
+                    outputs.push(dir.join(format!("{it}.ico")));
+                    for size in &p.sizes {
+                        let png = FaviconProfile::png_file_name(&it, **size);
+                        outputs.push(dir.join(format!("{png}.png")));
+                    }
+                    if p.webmanifest {
+                        outputs.push(dir.join(format!("{it}.webmanifest")));
+                    }
+                }
+                outputs
+            }
+            React(p) => self
+                .output_names()
+                .into_iter()
+                .map(|it| {
+                    let component_name = ReactProfile::component_name(&it);
+                    package_dir
+                        .join(&p.output_dir)
+                        .join(format!("{component_name}.tsx"))
+                })
+                .collect(),
+            Compose(p) if p.combine_variants && p.variants.is_some() => {
+                vec![p.output_dir(package_dir).join(format!("{name}.kt"))]
+            }
+            Compose(p) => files(p.output_dir(package_dir), "kt"),
+            AndroidWebp(p) => {
+                let themed = p.night.is_some() || p.dark_source.is_some();
+                let mut outputs = Vec::new();
+                for density in &p.scales {
+                    for prefix in themes(themed) {
+                        outputs.push(
+                            package_dir
+                                .join(&p.android_res_dir)
+                                .join(format!("drawable-{prefix}{}", density.name()))
+                                .join(format!("{name}.webp")),
+                        );
+                    }
+                }
+                outputs
+            }
+            AndroidDrawable(p) => {
+                let themed = p.night.is_some() || p.dark_source.is_some();
+                let mut outputs = Vec::new();
+                for prefix in themes(themed) {
+                    let dir = match prefix {
+                        "" => package_dir.join(&p.android_res_dir).join("drawable"),
+                        _ => package_dir
+                            .join(&p.android_res_dir)
+                            .join(format!("drawable-{}", prefix.trim_end_matches('-'))),
+                    };
+                    outputs.push(dir.join(format!("{name}.xml")));
+                    // themed icons are tinted by the system, only the light one has a monochrome layer
+                    if p.monochrome && prefix.is_empty() {
+                        outputs.push(dir.join(format!("{name}_monochrome.xml")));
+                    }
+                }
+                outputs
+            }
+            Flutter(p) => p
+                .scales
+                .iter()
+                .map(|scale| {
+                    let assets_dir = package_dir.join(&p.assets_dir);
+                    let dir = if **scale == 1.0 {
+                        assets_dir
+                    } else {
+                        assets_dir.join(FlutterProfile::density_dir_name(**scale))
+                    };
+                    dir.join(format!("{name}.png"))
+                })
+                .collect(),
+        }
+    }
+
+    /// Name of the resource transformed by the naming rules of its profile,
+    /// the output names of the variants are derived from it
+    pub fn output_base_name(&self) -> String {
+        self.profile.naming().apply(self.attrs.label.name.as_ref())
+    }
+
+    /// Output names of the active variants, or the base output name of the resource
+    fn output_names(&self) -> Vec<String> {
+        let name = self.output_base_name();
+        match self.profile.variants() {
+            None => vec![name],
+            Some(variants) => variants
+                .active()
+                .map(|(_, v)| v.output_name.as_ref().replace("{base}", &name))
+                .collect(),
+        }
+    }
+}
+
+/// Prefixes of the Android resource qualifiers of the light and the dark theme
+fn themes(themed: bool) -> Vec<&'static str> {
+    match themed {
+        true => vec!["", "night-"],
+        false => vec![""],
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use crate::{
+        AndroidDrawableProfile, AndroidWebpProfile, ExpectedDimensions, ExportScale, FaviconSize,
+        NodeIdList, PngProfile, RemoteSource, ResourceAttrs, ResourceDiagnostics, ResourceFilter,
+        RetryPolicy, SingleNamePattern,
+    };
+    use lib_label::{Label, TagPattern};
+    use std::{collections::BTreeMap, sync::Arc};
+
+    fn resource(profile: Profile) -> Resource {
+        Resource {
+            attrs: ResourceAttrs {
+                label: Label::from_package_and_name("icons", "ic_coffee").unwrap(),
+                profile_id: "png".to_owned(),
+                remote: Arc::new(RemoteSource {
+                    id: "design".to_owned(),
+                    file_key: "abcdefg".to_owned(),
+                    branch: None,
+                    container_node_ids: NodeIdList::Plain(vec!["0:1".to_owned()]),
+                    access_token: String::new(),
+                    shallow_depth: None,
+                    export_batch_size: RemoteSource::DEFAULT_EXPORT_BATCH_SIZE,
+                    retry: RetryPolicy::default(),
+                }),
+                node_name: "Coffee".to_owned(),
+                use_absolute_bounds: false,
+                expect: ExpectedDimensions::default(),
+                tags: Vec::new(),
+                package_dir: PathBuf::from("/ws/icons"),
+                diag: ResourceDiagnostics {
+                    file: Arc::new(PathBuf::from("/ws/icons/.fig.toml")),
+                    definition_span: 0..0,
+                },
+            },
+            profile: Arc::new(profile),
+        }
+    }
+
+    fn variant(output_name: &str, scale: Option<f32>) -> ResourceVariant {
+        ResourceVariant {
+            output_name: SingleNamePattern(output_name.to_owned()),
+            figma_name: SingleNamePattern("{base}".to_owned()),
+            scale: scale.map(ExportScale),
+        }
+    }
+
+    #[test]
+    fn ProfileKind__from_str__EXPECT__same_names_as_in_config() {
+        for kind in ProfileKind::ALL {
+            assert_eq!(Ok(kind), kind.as_str().parse::<ProfileKind>());
+        }
+        assert!("jpeg".parse::<ProfileKind>().is_err());
+    }
+
+    #[test]
+    fn ResourceFilter__profile_and_remote__EXPECT__both_must_match() {
+        // Given
+        let res = resource(Profile::Png(PngProfile::default()));
+        let filter = |profiles: &[ProfileKind], remotes: &[&str]| ResourceFilter {
+            profiles: profiles.to_vec(),
+            remotes: remotes.iter().map(|it| it.to_string()).collect(),
+            ..Default::default()
+        };
+
+        // Then
+        assert!(filter(&[], &[]).matches(&res));
+        assert!(filter(&[ProfileKind::Png], &["design"]).matches(&res));
+        assert!(!filter(&[ProfileKind::Compose], &["design"]).matches(&res));
+        assert!(!filter(&[ProfileKind::Png, ProfileKind::Svg], &["icons"]).matches(&res));
+    }
+
+    #[test]
+    fn ResourceFilter__tags__EXPECT__matched_by_resource_tags() {
+        // Given
+        let mut res = resource(Profile::Png(PngProfile::default()));
+        res.attrs.tags = vec!["critical".to_owned()];
+        let filter = |patterns: &[&str]| ResourceFilter {
+            tags: patterns
+                .iter()
+                .map(|it| TagPattern::from_str(it).unwrap())
+                .collect(),
+            ..Default::default()
+        };
+
+        // Then
+        assert!(filter(&["tag:critical"]).matches(&res));
+        assert!(filter(&["-tag:marketing"]).matches(&res));
+        assert!(!filter(&["tag:marketing"]).matches(&res));
+        assert!(!filter(&["tag:critical", "-tag:critical"]).matches(&res));
+    }
+
+    #[test]
+    fn Resource__png_with_variants__EXPECT__output_and_scale_per_active_variant() {
+        // Given
+        let res = resource(Profile::Png(PngProfile {
+            output_dir: PathBuf::from("img"),
+            scale: ExportScale(2.0),
+            variants: Some(ResourceVariants {
+                all_variants: BTreeMap::from([
+                    ("big".to_owned(), variant("{base}_big", Some(4.0))),
+                    ("small".to_owned(), variant("{base}_small", None)),
+                    ("unused".to_owned(), variant("{base}_unused", None)),
+                ]),
+                use_variants: Some(["big".to_owned(), "small".to_owned()].into()),
+            }),
+            ..Default::default()
+        }));
+
+        // When
+        let outputs = res.expected_outputs();
+
+        // Then
+        assert_eq!(
+            vec![
+                PathBuf::from("/ws/icons/img/ic_coffee_big.png"),
+                PathBuf::from("/ws/icons/img/ic_coffee_small.png"),
+            ],
+            outputs
+        );
+        assert_eq!(Some(4.0), res.effective_scale(Some("big")));
+        assert_eq!(Some(2.0), res.effective_scale(Some("small")));
+        assert_eq!(ProfileKind::Png, res.profile.kind());
+    }
+
+    #[test]
+    fn Resource__android_webp_with_night__EXPECT__output_per_density_and_theme() {
+        // Given
+        let res = resource(Profile::AndroidWebp(AndroidWebpProfile {
+            scales: vec![AndroidDensity::MDPI, AndroidDensity::XHDPI],
+            night: Some(SingleNamePattern("{base} / dark".to_owned())),
+            ..Default::default()
+        }));
+
+        // When
+        let outputs = res.expected_outputs();
+
+        // Then
+        assert_eq!(
+            vec![
+                PathBuf::from("/ws/icons/src/main/res/drawable-mdpi/ic_coffee.webp"),
+                PathBuf::from("/ws/icons/src/main/res/drawable-night-mdpi/ic_coffee.webp"),
+                PathBuf::from("/ws/icons/src/main/res/drawable-xhdpi/ic_coffee.webp"),
+                PathBuf::from("/ws/icons/src/main/res/drawable-night-xhdpi/ic_coffee.webp"),
+            ],
+            outputs
+        );
+        assert_eq!(None, res.effective_scale(None));
+        assert_eq!("android-webp", res.profile.kind().to_string());
+    }
+
+    #[test]
+    fn ReactProfile__component_name__snake_and_kebab_case__EXPECT__pascal_case() {
+        assert_eq!("IcArrowBack", ReactProfile::component_name("ic_arrow-back"));
+        assert_eq!("ArrowBack", ReactProfile::component_name("ArrowBack"));
+        assert_eq!("Icon24Close", ReactProfile::component_name("24_close"));
+    }
+
+    #[test]
+    fn NameCase__apply__node_names__EXPECT__names_in_case() {
+        // Given
+        let name = "Arrow / ArrowLeft 24";
+
+        // Then
+        assert_eq!("arrow_arrow_left_24", NameCase::Snake.apply(name));
+        assert_eq!("arrow-arrow-left-24", NameCase::Kebab.apply(name));
+        assert_eq!("arrowArrowLeft24", NameCase::Camel.apply(name));
+        assert_eq!("ArrowArrowLeft24", NameCase::Pascal.apply(name));
+        assert_eq!("", NameCase::Snake.apply(" / "));
+    }
+
+    #[test]
+    fn Resource__output_base_name__naming_rules__EXPECT__stripped_prefixed_name_in_case() {
+        // Given
+        let res = resource(Profile::Png(PngProfile {
+            naming: NamingRules {
+                strip: vec!["ic_".to_owned()],
+                case: Some(NameCase::Pascal),
+                prefix: "Ic".to_owned(),
+                suffix: "24".to_owned(),
+            },
+            ..Default::default()
+        }));
+
+        // Then
+        assert_eq!("IcCoffee24", res.output_base_name());
+        assert_eq!(
+            vec![PathBuf::from("/ws/icons/IcCoffee24.png")],
+            res.expected_outputs()
+        );
+    }
+
+    #[test]
+    fn Resource__multiplatform_compose_with_combined_variants__EXPECT__single_file_in_common_source_set()
+     {
+        // Given
+        let res = resource(Profile::Compose(ComposeProfile {
+            package: Some("com.example".to_owned()),
+            multiplatform: true,
+            combine_variants: true,
+            variants: Some(ResourceVariants {
+                all_variants: BTreeMap::from([
+                    ("big".to_owned(), variant("{base}_big", None)),
+                    ("small".to_owned(), variant("{base}_small", None)),
+                ]),
+                use_variants: None,
+            }),
+            ..Default::default()
+        }));
+
+        // When
+        let outputs = res.expected_outputs();
+
+        // Then
+        assert_eq!(
+            vec![PathBuf::from(
+                "/ws/icons/src/commonMain/kotlin/com/example/ic_coffee.kt"
+            )],
+            outputs
+        );
+    }
+
+    #[test]
+    fn Resource__auxiliary_files__EXPECT__listed_after_main_files() {
+        // Given
+        let favicon = resource(Profile::Favicon(FaviconProfile {
+            sizes: vec![FaviconSize(16), FaviconSize(32)],
+            webmanifest: true,
+            ..Default::default()
+        }));
+        let pdf = resource(Profile::Pdf(PdfProfile {
+            preview_scales: vec![ExportScale(1.0), ExportScale(1.5)],
+            ..Default::default()
+        }));
+        let drawable = resource(Profile::AndroidDrawable(AndroidDrawableProfile {
+            monochrome: true,
+            night: Some(SingleNamePattern("{base} / dark".to_owned())),
+            ..Default::default()
+        }));
+
+        // Then
+        assert_eq!(
+            vec![
+                PathBuf::from("/ws/icons/ic_coffee.ico"),
+                PathBuf::from("/ws/icons/ic_coffee-16x16.png"),
+                PathBuf::from("/ws/icons/ic_coffee-32x32.png"),
+                PathBuf::from("/ws/icons/ic_coffee.webmanifest"),
+            ],
+            favicon.expected_outputs()
+        );
+        assert_eq!(
+            vec![
+                PathBuf::from("/ws/icons/ic_coffee.pdf"),
+                PathBuf::from("/ws/icons/ic_coffee.png"),
+                PathBuf::from("/ws/icons/ic_coffee@1.5x.png"),
+            ],
+            pdf.expected_outputs()
+        );
+        assert_eq!(
+            vec![
+                PathBuf::from("/ws/icons/src/main/res/drawable/ic_coffee.xml"),
+                PathBuf::from("/ws/icons/src/main/res/drawable/ic_coffee_monochrome.xml"),
+                PathBuf::from("/ws/icons/src/main/res/drawable-night/ic_coffee.xml"),
+            ],
+            drawable.expected_outputs()
+        );
+    }
+}
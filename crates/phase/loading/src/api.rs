@@ -2,8 +2,9 @@ use std::{
     collections::{BTreeMap, HashSet},
     fmt::{Debug, Display},
     ops::{Deref, Range},
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 
 use lib_label::Label;
@@ -19,6 +20,57 @@ pub struct Workspace {
     pub remotes: Vec<Arc<RemoteSource>>,
     pub profiles: Vec<Arc<Profile>>,
     pub packages: Vec<Package>,
+    /// Names of the external workspaces declared in `[externals]`, loaded or not
+    pub externals: Vec<String>,
+    pub metrics: MetricsConfig,
+    pub output: OutputConfig,
+    pub network: NetworkConfig,
+    pub jobs: JobsConfig,
+}
+
+/// Where to push metrics in addition to the `metrics.prom` file
+#[derive(Default)]
+pub struct MetricsConfig {
+    /// Base url of the OpenTelemetry collector, e.g. `http://localhost:4318`
+    pub otlp_endpoint: Option<String>,
+}
+
+/// How figx connects to the Figma API. Proxies are taken from the environment
+#[derive(Default)]
+pub struct NetworkConfig {
+    /// PEM bundle of root certificates to trust instead of the bundled ones
+    pub ca_bundle: Option<PathBuf>,
+}
+
+/// Parallelism of the import, unset values are chosen by figx
+#[derive(Default)]
+pub struct JobsConfig {
+    /// Number of requests to the Figma API in flight, mostly downloads of the images
+    pub network: Option<usize>,
+    /// Number of targets transformed in parallel, e.g. encoded to WebP or converted to Compose
+    pub cpu: Option<usize>,
+}
+
+/// How outputs are written to the workspace
+#[derive(Default)]
+pub struct OutputConfig {
+    /// Write outputs to the local cache directory first and copy them to the
+    /// workspace in a final batch. Faster when the workspace is on a network share
+    pub staging: bool,
+    /// Keep outputs in the content-addressed store and link them into the packages
+    pub content_addressed: Option<OutputLinkMode>,
+    /// Absolute directory the outputs are written to instead of the packages,
+    /// see [`Workspace::relocate_outputs`]
+    pub base: Option<PathBuf>,
+    /// Stamp the generated files with their provenance, so hand edits can be detected
+    pub provenance: bool,
+}
+
+/// How files of the content-addressed store are linked into the workspace
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum OutputLinkMode {
+    Hardlink,
+    Symlink,
 }
 
 pub struct InvocationContext {
@@ -41,8 +93,56 @@ pub struct LoadedFigFile {
 pub struct RemoteSource {
     pub id: RemoteId,
     pub file_key: String,
+    /// Name of the branch of the file, whose key replaces `file_key` before the fetch
+    pub branch: Option<String>,
     pub container_node_ids: NodeIdList,
     pub access_token: String,
+    /// If set, components are looked up only this deep below the container nodes,
+    /// and only the requested ones are fetched entirely
+    pub shallow_depth: Option<u32>,
+    /// Max number of nodes exported with a single request to the images endpoint
+    pub export_batch_size: usize,
+    /// How failed requests to this remote are retried
+    pub retry: RetryPolicy,
+}
+
+impl RemoteSource {
+    pub const DEFAULT_EXPORT_BATCH_SIZE: usize = 100;
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one
+    pub max_attempts: usize,
+    /// Delay before the first retry, doubled on every next one
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub retry_on: Vec<RetryCondition>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum RetryCondition {
+    /// `429 Too Many Requests`
+    RateLimit,
+    /// Any `5xx` status code
+    ServerError,
+    /// Timed out or interrupted connection
+    Timeout,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 10,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(30),
+            retry_on: vec![
+                RetryCondition::RateLimit,
+                RetryCondition::ServerError,
+                RetryCondition::Timeout,
+            ],
+        }
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Hash)]
@@ -98,6 +198,9 @@ pub enum Profile {
     Compose(ComposeProfile),
     AndroidWebp(AndroidWebpProfile),
     AndroidDrawable(AndroidDrawableProfile),
+    Flutter(FlutterProfile),
+    React(ReactProfile),
+    Favicon(FaviconProfile),
 }
 
 impl Profile {
@@ -111,13 +214,32 @@ impl Profile {
             Compose(p) => p.remote_id.as_str(),
             AndroidWebp(p) => p.remote_id.as_str(),
             AndroidDrawable(p) => p.remote_id.as_str(),
+            Flutter(p) => p.remote_id.as_str(),
+            React(p) => p.remote_id.as_str(),
+            Favicon(p) => p.remote_id.as_str(),
+        }
+    }
+
+    pub fn post_process(&self) -> Option<&PostProcessCommand> {
+        use Profile::*;
+        match self {
+            Png(p) => p.post_process.as_ref(),
+            Svg(p) => p.post_process.as_ref(),
+            Pdf(p) => p.post_process.as_ref(),
+            Webp(p) => p.post_process.as_ref(),
+            Compose(p) => p.post_process.as_ref(),
+            AndroidWebp(p) => p.post_process.as_ref(),
+            AndroidDrawable(p) => p.post_process.as_ref(),
+            Flutter(p) => p.post_process.as_ref(),
+            React(p) => p.post_process.as_ref(),
+            Favicon(p) => p.post_process.as_ref(),
         }
     }
 
     pub fn vector(&self) -> bool {
         use Profile::*;
         match self {
-            Png(_) | Webp(_) | AndroidWebp(_) => false,
+            Png(_) | Webp(_) | AndroidWebp(_) | Flutter(_) => false,
             _ => true,
         }
     }
@@ -132,6 +254,11 @@ pub struct PngProfile {
     pub output_dir: PathBuf,
     pub variants: Option<ResourceVariants>,
     pub legacy_loader: bool,
+    /// Losslessly recompress PNGs before writing them
+    pub optimize: bool,
+    pub optimization_level: PngOptimizationLevel,
+    pub post_process: Option<PostProcessCommand>,
+    pub naming: NamingRules,
 }
 
 impl Default for PngProfile {
@@ -142,6 +269,10 @@ impl Default for PngProfile {
             output_dir: PathBuf::new(),
             variants: None,
             legacy_loader: false,
+            optimize: false,
+            optimization_level: PngOptimizationLevel::default(),
+            post_process: None,
+            naming: NamingRules::default(),
         }
     }
 }
@@ -155,6 +286,8 @@ pub struct SvgProfile {
     pub remote_id: RemoteId,
     pub output_dir: PathBuf,
     pub variants: Option<ResourceVariants>,
+    pub post_process: Option<PostProcessCommand>,
+    pub naming: NamingRules,
 }
 
 impl Default for SvgProfile {
@@ -163,6 +296,8 @@ impl Default for SvgProfile {
             remote_id: String::new(),
             output_dir: PathBuf::new(),
             variants: None,
+            post_process: None,
+            naming: NamingRules::default(),
         }
     }
 }
@@ -175,7 +310,15 @@ impl Default for SvgProfile {
 pub struct PdfProfile {
     pub remote_id: RemoteId,
     pub output_dir: PathBuf,
+    /// Accept PDF files with embedded raster images, `None` accepts them with a warning
+    pub allow_raster: Option<bool>,
+    /// Scales of PNG previews written next to the PDF file
+    pub preview_scales: Vec<ExportScale>,
+    /// Convert the SVG export to PDF locally instead of requesting a PDF export
+    pub local_renderer: bool,
     pub variants: Option<ResourceVariants>,
+    pub post_process: Option<PostProcessCommand>,
+    pub naming: NamingRules,
 }
 
 impl Default for PdfProfile {
@@ -183,7 +326,12 @@ impl Default for PdfProfile {
         Self {
             remote_id: String::new(),
             output_dir: PathBuf::new(),
+            allow_raster: None,
+            preview_scales: Vec::new(),
+            local_renderer: false,
             variants: None,
+            post_process: None,
+            naming: NamingRules::default(),
         }
     }
 }
@@ -197,9 +345,15 @@ pub struct WebpProfile {
     pub remote_id: RemoteId,
     pub scale: ExportScale,
     pub quality: WebpQuality,
+    /// Encode losslessly regardless of `quality`
+    pub lossless: bool,
+    pub alpha_quality: WebpQuality,
+    pub method: WebpMethod,
     pub output_dir: PathBuf,
     pub variants: Option<ResourceVariants>,
     pub legacy_loader: bool,
+    pub post_process: Option<PostProcessCommand>,
+    pub naming: NamingRules,
 }
 
 impl Default for WebpProfile {
@@ -208,9 +362,14 @@ impl Default for WebpProfile {
             remote_id: String::new(),
             scale: ExportScale::default(),
             quality: WebpQuality::default(),
+            lossless: false,
+            alpha_quality: WebpQuality::default(),
+            method: WebpMethod::default(),
             output_dir: PathBuf::new(),
             variants: None,
             legacy_loader: false,
+            post_process: None,
+            naming: NamingRules::default(),
         }
     }
 }
@@ -231,6 +390,21 @@ pub struct ComposeProfile {
     pub preview: Option<ComposePreview>,
     pub variants: Option<ResourceVariants>,
     pub composable_get: bool,
+    /// Template of the generated property name, `{name}` is replaced with the output name
+    pub property_name_template: Option<String>,
+    /// Fully qualified name of the object the generated property is declared on
+    pub receiver_object: Option<String>,
+    /// Generate all variants of the resource into a single file
+    pub combine_variants: bool,
+    pub limits: VectorLimits,
+    /// Target Compose Multiplatform instead of Android-only Compose
+    pub multiplatform: bool,
+    /// Prefix of the Kotlin identifiers starting with a digit
+    pub identifier_prefix: Option<String>,
+    /// Add description and link of the Figma component as KDoc of the property
+    pub document_sources: bool,
+    pub post_process: Option<PostProcessCommand>,
+    pub naming: NamingRules,
 }
 
 #[derive(Clone)]
@@ -261,6 +435,15 @@ impl Default for ComposeProfile {
             preview: None,
             variants: None,
             composable_get: false,
+            property_name_template: None,
+            receiver_object: None,
+            combine_variants: false,
+            limits: VectorLimits::default(),
+            multiplatform: false,
+            identifier_prefix: None,
+            document_sources: false,
+            post_process: None,
+            naming: NamingRules::default(),
         }
     }
 }
@@ -274,9 +457,17 @@ pub struct AndroidWebpProfile {
     pub remote_id: RemoteId,
     pub android_res_dir: PathBuf,
     pub quality: WebpQuality,
+    /// Encode losslessly regardless of `quality`
+    pub lossless: bool,
+    pub alpha_quality: WebpQuality,
+    pub method: WebpMethod,
     pub scales: Vec<AndroidDensity>,
     pub night: Option<SingleNamePattern>,
+    /// Name of the Figma node with the dark variant, takes precedence over `night`
+    pub dark_source: Option<String>,
     pub legacy_loader: bool,
+    pub post_process: Option<PostProcessCommand>,
+    pub naming: NamingRules,
 }
 
 impl Default for AndroidWebpProfile {
@@ -286,9 +477,15 @@ impl Default for AndroidWebpProfile {
             remote_id: String::new(),
             android_res_dir: PathBuf::from("src/main/res"),
             quality: WebpQuality::default(),
+            lossless: false,
+            alpha_quality: WebpQuality::default(),
+            method: WebpMethod::default(),
             scales: vec![MDPI, HDPI, XHDPI, XXHDPI, XXXHDPI],
             night: None,
+            dark_source: None,
             legacy_loader: false,
+            post_process: None,
+            naming: NamingRules::default(),
         }
     }
 }
@@ -313,7 +510,14 @@ pub struct AndroidDrawableProfile {
     pub remote_id: RemoteId,
     pub android_res_dir: PathBuf,
     pub night: Option<SingleNamePattern>,
+    /// Name of the Figma node with the dark variant, takes precedence over `night`
+    pub dark_source: Option<String>,
     pub auto_mirrored: bool,
+    /// Also emit a single-color `{name}_monochrome` drawable for Android 13 themed icons
+    pub monochrome: bool,
+    pub limits: VectorLimits,
+    pub post_process: Option<PostProcessCommand>,
+    pub naming: NamingRules,
 }
 
 impl Default for AndroidDrawableProfile {
@@ -322,13 +526,166 @@ impl Default for AndroidDrawableProfile {
             remote_id: String::new(),
             android_res_dir: PathBuf::from("src/main/res"),
             night: None,
+            dark_source: None,
             auto_mirrored: false,
+            monochrome: false,
+            limits: VectorLimits::default(),
+            post_process: None,
+            naming: NamingRules::default(),
         }
     }
 }
 
 // endregion: ANDROID-DRAWABLE Profile
 
+// region: FLUTTER Profile
+
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub struct FlutterProfile {
+    pub remote_id: RemoteId,
+    pub assets_dir: PathBuf,
+    pub scales: Vec<ExportScale>,
+    /// Name of the generated Dart class with asset paths, if any
+    pub dart_class: Option<String>,
+    pub dart_file: PathBuf,
+    pub legacy_loader: bool,
+    /// Losslessly recompress PNGs before writing them
+    pub optimize: bool,
+    pub optimization_level: PngOptimizationLevel,
+    pub post_process: Option<PostProcessCommand>,
+    pub naming: NamingRules,
+}
+
+impl Default for FlutterProfile {
+    fn default() -> Self {
+        Self {
+            remote_id: String::new(),
+            assets_dir: PathBuf::from("assets"),
+            scales: [1.0, 1.5, 2.0, 3.0, 4.0].map(ExportScale).to_vec(),
+            dart_class: None,
+            dart_file: PathBuf::from("lib/gen/assets.dart"),
+            legacy_loader: false,
+            optimize: false,
+            optimization_level: PngOptimizationLevel::default(),
+            post_process: None,
+            naming: NamingRules::default(),
+        }
+    }
+}
+
+// endregion: FLUTTER Profile
+
+// region: REACT Profile
+
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub struct ReactProfile {
+    pub remote_id: RemoteId,
+    pub output_dir: PathBuf,
+    /// Replace solid colors with `currentColor` and add the `color` prop
+    pub current_color: bool,
+    pub variants: Option<ResourceVariants>,
+    pub post_process: Option<PostProcessCommand>,
+    pub naming: NamingRules,
+}
+
+impl Default for ReactProfile {
+    fn default() -> Self {
+        Self {
+            remote_id: String::new(),
+            output_dir: PathBuf::new(),
+            current_color: false,
+            variants: None,
+            post_process: None,
+            naming: NamingRules::default(),
+        }
+    }
+}
+
+// endregion: REACT Profile
+
+// region: FAVICON Profile
+
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub struct FaviconProfile {
+    pub remote_id: RemoteId,
+    pub output_dir: PathBuf,
+    /// Sizes of the square images packed into the `.ico` file and written as PNG files
+    pub sizes: Vec<FaviconSize>,
+    /// Write the `icons` snippet for `site.webmanifest` next to the images
+    pub webmanifest: bool,
+    pub variants: Option<ResourceVariants>,
+    pub post_process: Option<PostProcessCommand>,
+    pub naming: NamingRules,
+}
+
+impl Default for FaviconProfile {
+    fn default() -> Self {
+        Self {
+            remote_id: String::new(),
+            output_dir: PathBuf::new(),
+            sizes: [16, 32, 48, 256].map(FaviconSize).to_vec(),
+            webmanifest: true,
+            variants: None,
+            post_process: None,
+            naming: NamingRules::default(),
+        }
+    }
+}
+
+// endregion: FAVICON Profile
+
+// region: VECTOR-LIMITS
+
+/// Complexity limits of the vector images, checked before the code generation
+#[derive(Clone, Default)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub struct VectorLimits {
+    pub max_paths: Option<usize>,
+    pub max_commands: Option<usize>,
+    pub on_exceeded: LimitAction,
+}
+
+/// What to do with the vector image which exceeds the limits
+#[derive(Clone, Default)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub enum LimitAction {
+    #[default]
+    Warn,
+    Fail,
+    /// Import the resource with the specified raster profile instead
+    Fallback(Arc<Profile>),
+}
+
+// endregion: VECTOR-LIMITS
+
+// region: NAMING
+
+/// Rules the names of the output files are derived from the resource names by,
+/// applied in the order of the fields
+#[derive(Clone, Default)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub struct NamingRules {
+    /// Substrings removed from the name, e.g. `Icon`
+    pub strip: Vec<String>,
+    pub case: Option<NameCase>,
+    pub prefix: String,
+    pub suffix: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NameCase {
+    /// `arrow_left`
+    Snake,
+    /// `arrow-left`
+    Kebab,
+    /// `arrowLeft`
+    Camel,
+    /// `ArrowLeft`
+    Pascal,
+}
+
+// endregion: NAMING
+
 // region VARIANTS-API
 
 #[derive(Clone)]
@@ -368,12 +725,31 @@ pub struct Resource {
 
 pub struct ResourceAttrs {
     pub label: Label,
+    /// Id of the profile the resource is declared under in `.fig.toml`, e.g. `png`
+    pub profile_id: String,
     pub remote: Arc<RemoteSource>,
     pub node_name: String,
+    /// Export the node with its full dimensions instead of its render bounds
+    pub use_absolute_bounds: bool,
+    /// Size of the node in Figma the resource relies on
+    pub expect: ExpectedDimensions,
+    /// Tags declared in `.fig.toml`, e.g. `critical`
+    pub tags: Vec<String>,
     pub package_dir: PathBuf,
     pub diag: ResourceDiagnostics,
 }
 
+/// Dimensions of the Figma node in points, checked before the import.
+/// Unset dimensions are not checked.
+#[derive(Clone, Default)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub struct ExpectedDimensions {
+    pub width: Option<f32>,
+    pub height: Option<f32>,
+    /// Width divided by height
+    pub aspect_ratio: Option<f32>,
+}
+
 pub struct ResourceDiagnostics {
     pub file: Arc<PathBuf>,
     pub definition_span: Range<usize>,
@@ -384,6 +760,15 @@ pub struct ResourceDiagnostics {
 #[derive(Clone, Copy, PartialEq, PartialOrd)]
 pub struct ExportScale(pub(crate) f32);
 
+impl ExportScale {
+    /// Minimum scale supported by the Figma images endpoint
+    pub const FIGMA_MIN: f32 = 0.01;
+    /// Maximum scale supported by the Figma images endpoint, larger scales are rendered locally
+    pub const FIGMA_MAX: f32 = 4.0;
+    /// Maximum scale figx is able to render locally
+    pub const MAX: f32 = 16.0;
+}
+
 impl Default for ExportScale {
     fn default() -> Self {
         Self(1.0)
@@ -409,6 +794,107 @@ impl Display for ExportScale {
     }
 }
 
+/// Preset of the lossless PNG recompression, from 0 (fastest) to 6 (smallest)
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PngOptimizationLevel(pub(crate) u8);
+
+impl Default for PngOptimizationLevel {
+    fn default() -> Self {
+        Self(2)
+    }
+}
+
+impl Deref for PngOptimizationLevel {
+    type Target = u8;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Debug for PngOptimizationLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+/// Side of the square favicon image in pixels, from 1 to 256 (the maximum of the ICO format)
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FaviconSize(pub(crate) u32);
+
+impl Deref for FaviconSize {
+    type Target = u32;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Display for FaviconSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Debug for FaviconSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+/// External program run on every file written by the profile, e.g. `["svgo", "{output}"]`.
+/// `{output}` in the arguments is replaced with the path of the written file.
+#[derive(Clone, PartialEq, Eq)]
+pub struct PostProcessCommand(pub(crate) Vec<String>);
+
+impl PostProcessCommand {
+    pub fn program(&self) -> &str {
+        &self.0[0]
+    }
+
+    /// Arguments of the program with `{output}` replaced with the path
+    pub fn args(&self, output: &Path) -> Vec<String> {
+        let output = output.to_string_lossy();
+        self.0[1..]
+            .iter()
+            .map(|arg| arg.replace("{output}", &output))
+            .collect()
+    }
+}
+
+impl Display for PostProcessCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.join(" "))
+    }
+}
+
+impl Debug for PostProcessCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+/// Compression method of the WebP encoder, from 0 (fastest) to 6 (smallest)
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct WebpMethod(pub(crate) u8);
+
+impl Default for WebpMethod {
+    fn default() -> Self {
+        Self(4)
+    }
+}
+
+impl Deref for WebpMethod {
+    type Target = u8;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Debug for WebpMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, PartialOrd)]
 pub struct WebpQuality(pub(crate) f32);
 
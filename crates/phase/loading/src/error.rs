@@ -1,5 +1,5 @@
 use lib_label::PackageParsingError;
-use std::path::PathBuf;
+use std::{ops::Range, path::PathBuf};
 use toml_span::Span;
 
 pub type Result<T> = ::std::result::Result<T, Error>;
@@ -19,6 +19,9 @@ pub enum Error {
     WorkspaceRemoteNoAccessToken(String, PathBuf, Span),
     WorkspaceRemoteEmptyKeychain(String, PathBuf, Span),
     WorkspaceRemoteKeychainError(lib_auth::Error),
+    WorkspaceExternalUnknown(String),
+    WorkspaceRemoteUnknown(String),
+    WorkspaceExternalNotFound(String, PathBuf),
     // endregion: Workspace
 
     // region: FigFiles
@@ -26,9 +29,19 @@ pub enum Error {
     FigRead(std::io::Error),
     FigParse(toml_span::DeserError, PathBuf),
     FigInvalidPackage(PackageParsingError),
+    /// Two resources write the same file
+    FigDuplicateOutput(PathBuf, Box<[DuplicateOutputWriter; 2]>),
     // endregion: FigFiles
 }
 
+/// Resource writing a file, with its declaration in `.fig.toml`
+#[derive(Debug)]
+pub struct DuplicateOutputWriter {
+    pub label: String,
+    pub file: PathBuf,
+    pub span: Range<usize>,
+}
+
 // region: Internal
 
 impl Error {
@@ -0,0 +1,47 @@
+use crate::{Error, ProfileKind, RemoteId, Resource, Result, Workspace};
+use lib_label::{TagPattern, tags_match};
+
+/// Narrows down the resources matched by the label pattern, e.g. to import only
+/// the Compose icons of one remote in a huge mono-repo.
+///
+/// Empty lists match everything.
+#[derive(Clone, Default, Debug)]
+pub struct ResourceFilter {
+    pub profiles: Vec<ProfileKind>,
+    pub remotes: Vec<RemoteId>,
+    /// Entered along with the label patterns, see [`lib_label::split_tag_patterns`]
+    pub tags: Vec<TagPattern>,
+}
+
+impl ResourceFilter {
+    pub fn is_empty(&self) -> bool {
+        self.profiles.is_empty() && self.remotes.is_empty() && self.tags.is_empty()
+    }
+
+    pub fn matches(&self, res: &Resource) -> bool {
+        let profile_matches =
+            self.profiles.is_empty() || self.profiles.contains(&res.profile.kind());
+        let remote_matches = self.remotes.is_empty() || self.remotes.contains(&res.attrs.remote.id);
+        profile_matches && remote_matches && tags_match(&self.tags, &res.attrs.tags)
+    }
+}
+
+impl Workspace {
+    /// Keeps only the resources matched by the `filter`.
+    /// Packages left without resources are dropped as well.
+    pub fn retain_resources(&mut self, filter: &ResourceFilter) -> Result<()> {
+        if filter.is_empty() {
+            return Ok(());
+        }
+        for id in &filter.remotes {
+            if !self.remotes.iter().any(|it| &it.id == id) {
+                return Err(Error::WorkspaceRemoteUnknown(id.to_owned()));
+            }
+        }
+        for pkg in &mut self.packages {
+            pkg.resources.retain(|res| filter.matches(res));
+        }
+        self.packages.retain(|pkg| !pkg.resources.is_empty());
+        Ok(())
+    }
+}
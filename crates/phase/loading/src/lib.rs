@@ -6,63 +6,59 @@ use toml_span::Value;
 use util::{FileWithParentDir, find_file_in_ancestors, find_files_in_child_dirs};
 use workspace::parse_workspace;
 
+mod accessors;
 mod api;
 mod error;
+mod filter;
 mod parser;
+mod relocation;
 mod util;
 mod workspace;
 
+pub use accessors::*;
 pub use api::*;
 pub use error::*;
+pub use filter::*;
 
-static WORKSPACE_FILE_NAME: &str = ".figtree.toml";
+pub(crate) static WORKSPACE_FILE_NAME: &str = ".figtree.toml";
+pub static LOCAL_WORKSPACE_FILE_NAME: &str = ".figtree.local.toml";
 static RESOURCES_FILE_NAME: &str = ".fig.toml";
 static OUT_DIR: &str = ".figx-out";
+/// Directory in [`OUT_DIR`], where `figx scan` saves the scanned remotes
+static SCANS_DIR: &str = "scans";
 static CACHE_DIR: &str = ".figx-out/caches";
 
 pub fn load_invocation_context() -> Result<InvocationContext> {
-    debug!("Restoring invocation context...");
     let working_dir = std::env::current_dir().map_err(|_| Error::InitInaccessibleCurrentWorkDir)?;
+    load_invocation_context_in(&working_dir)
+}
+
+/// Same as [`load_invocation_context`], but as if figx was invoked in `working_dir`
+pub fn load_invocation_context_in(working_dir: &Path) -> Result<InvocationContext> {
+    debug!("Restoring invocation context...");
     // Looking for workspace marker in this dir and it's ancestors
-    let ws_file = find_workspace_file(&working_dir)?;
+    let ws_file = find_workspace_file(working_dir)?;
     // Looking recursively for fig files in workspace directory and children directories
     // FIXME: Cannot start traversing from the current directory because, if the user queries
     //        an absolute package like `//path/to:resource`, we need to know about packages
     //        other than our own.
-    let fig_files = find_fig_files(&ws_file.parent_dir)?;
+    let fig_files = load_fig_files(&ws_file.parent_dir, None)?;
 
     let current_dir = working_dir
         .strip_prefix(&ws_file.parent_dir)
         .expect("`parent_dir` is ALWAYS subdir of `ws_file.parent_dir`")
         .to_path_buf();
-
-    let mut loaded_fig_files: Vec<LoadedFigFile> = Vec::new();
-    let mut current_package = None;
-    for FileWithParentDir { file, parent_dir } in fig_files {
-        let package = PackageLabel::with_path(
-            parent_dir
-                .strip_prefix(&ws_file.parent_dir)
-                .expect("`f.parent_dir` is ALWAYS subdir of `ws_file.parent_dir`"),
-        )
-        .map_err(Error::FigInvalidPackage)?;
-
-        if *package == current_dir {
-            current_package = Some(package.clone())
-        }
-
-        loaded_fig_files.push(LoadedFigFile {
-            package,
-            fig_dir: parent_dir,
-            fig_file: file,
-        });
-    }
+    let current_package = fig_files
+        .iter()
+        .find(|f| *f.package == current_dir)
+        .map(|f| f.package.clone());
 
     Ok(InvocationContext {
         workspace_dir: ws_file.parent_dir.clone(),
         workspace_file: ws_file.file,
         current_dir,
         current_package,
-        fig_files: loaded_fig_files,
+        fig_files,
         out_dir: ws_file.parent_dir.join(OUT_DIR),
         cache_dir: ws_file.parent_dir.join(CACHE_DIR),
     })
@@ -72,19 +68,37 @@ pub fn load_workspace(
     pattern: LabelPattern,
     ignore_missing_access_token: bool,
 ) -> Result<Workspace> {
-    let invocation_ctx = load_invocation_context()?;
+    let working_dir = std::env::current_dir().map_err(|_| Error::InitInaccessibleCurrentWorkDir)?;
+    load_workspace_in(&working_dir, pattern, ignore_missing_access_token)
+}
+
+/// Same as [`load_workspace`], but as if figx was invoked in `working_dir`
+pub fn load_workspace_in(
+    working_dir: &Path,
+    pattern: LabelPattern,
+    ignore_missing_access_token: bool,
+) -> Result<Workspace> {
+    let invocation_ctx = load_invocation_context_in(working_dir)?;
     debug!("Loading workspace...");
     let ws_file = invocation_ctx.workspace_file.clone();
-    parse_workspace(invocation_ctx, pattern, ignore_missing_access_token).map_err(|e| match e {
-        Error::WorkspaceParse(e, _) => Error::WorkspaceParse(e, ws_file),
-        Error::WorkspaceRemoteNoAccessToken(id, _, span) => {
-            Error::WorkspaceRemoteNoAccessToken(id, ws_file, span)
+    parse_workspace(invocation_ctx, pattern, ignore_missing_access_token)
+        .map_err(|e| with_workspace_file(e, &ws_file))
+}
+
+/// Attaches workspace file path to the errors which don't know it yet
+pub(crate) fn with_workspace_file(err: Error, ws_file: &Path) -> Error {
+    match err {
+        Error::WorkspaceParse(e, path) if path.as_os_str().is_empty() => {
+            Error::WorkspaceParse(e, ws_file.to_owned())
+        }
+        Error::WorkspaceRemoteNoAccessToken(id, path, span) if path.as_os_str().is_empty() => {
+            Error::WorkspaceRemoteNoAccessToken(id, ws_file.to_owned(), span)
         }
-        Error::WorkspaceRemoteEmptyKeychain(id, _, span) => {
-            Error::WorkspaceRemoteEmptyKeychain(id, ws_file, span)
+        Error::WorkspaceRemoteEmptyKeychain(id, path, span) if path.as_os_str().is_empty() => {
+            Error::WorkspaceRemoteEmptyKeychain(id, ws_file.to_owned(), span)
         }
         e => e,
-    })
+    }
 }
 
 fn find_workspace_file(start_dir: &Path) -> Result<FileWithParentDir> {
@@ -92,6 +106,31 @@ fn find_workspace_file(start_dir: &Path) -> Result<FileWithParentDir> {
     find_file_in_ancestors(WORKSPACE_FILE_NAME, start_dir).ok_or(Error::InitNotInWorkspace)
 }
 
+/// Loads all fig-files of the workspace located in `ws_dir`.
+/// Packages of external workspaces are labeled with the external workspace name.
+pub(crate) fn load_fig_files(ws_dir: &Path, external: Option<&str>) -> Result<Vec<LoadedFigFile>> {
+    let mut loaded_fig_files: Vec<LoadedFigFile> = Vec::new();
+    for FileWithParentDir { file, parent_dir } in find_fig_files(ws_dir)? {
+        let package = PackageLabel::with_path(
+            parent_dir
+                .strip_prefix(ws_dir)
+                .expect("`f.parent_dir` is ALWAYS subdir of `ws_dir`"),
+        )
+        .map_err(Error::FigInvalidPackage)?;
+        let package = match external {
+            Some(external) => package.with_external(external),
+            None => package,
+        };
+
+        loaded_fig_files.push(LoadedFigFile {
+            package,
+            fig_dir: parent_dir,
+            fig_file: file,
+        });
+    }
+    Ok(loaded_fig_files)
+}
+
 fn find_fig_files(start_dir: &Path) -> Result<Vec<FileWithParentDir>> {
     debug!("Seeking fig files...");
     find_files_in_child_dirs(RESOURCES_FILE_NAME, start_dir)
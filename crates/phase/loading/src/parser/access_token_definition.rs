@@ -1,5 +1,6 @@
 use toml_span::{Deserialize, ErrorKind, Value, de_helpers::TableHelper, value::ValueInner};
 
+#[derive(Clone)]
 #[cfg_attr(test, derive(PartialEq, Debug))]
 pub(crate) enum AccessTokenDefinitionDto {
     Explicit(String),
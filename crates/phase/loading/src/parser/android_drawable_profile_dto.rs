@@ -1,4 +1,5 @@
-use crate::{CanBeExtendedBy, SingleNamePattern};
+use super::{NamingRulesDto, VectorLimitsDto};
+use crate::{CanBeExtendedBy, PostProcessCommand, SingleNamePattern};
 use std::{collections::HashSet, path::PathBuf};
 
 #[derive(Default)]
@@ -7,7 +8,12 @@ pub(crate) struct AndroidDrawableProfileDto {
     pub remote_id: Option<String>,
     pub android_res_dir: Option<PathBuf>,
     pub night: Option<SingleNamePattern>,
+    pub dark_source: Option<String>,
     pub auto_mirrored: Option<bool>,
+    pub monochrome: Option<bool>,
+    pub limits: Option<VectorLimitsDto>,
+    pub post_process: Option<PostProcessCommand>,
+    pub naming: Option<NamingRulesDto>,
 }
 
 impl CanBeExtendedBy<Self> for AndroidDrawableProfileDto {
@@ -24,7 +30,26 @@ impl CanBeExtendedBy<Self> for AndroidDrawableProfileDto {
                 .or(self.android_res_dir.as_ref())
                 .cloned(),
             night: another.night.as_ref().or(self.night.as_ref()).cloned(),
+            dark_source: another
+                .dark_source
+                .as_ref()
+                .or(self.dark_source.as_ref())
+                .cloned(),
             auto_mirrored: another.auto_mirrored.or(self.auto_mirrored),
+            monochrome: another.monochrome.or(self.monochrome),
+            limits: match (self.limits.as_ref(), another.limits.as_ref()) {
+                (Some(base), Some(another)) => Some(base.extend(another)),
+                (base, another) => another.or(base).cloned(),
+            },
+            post_process: another
+                .post_process
+                .as_ref()
+                .or(self.post_process.as_ref())
+                .cloned(),
+            naming: match (self.naming.as_ref(), another.naming.as_ref()) {
+                (Some(this), Some(another)) => Some(this.extend(another)),
+                (this, another) => another.or(this).cloned(),
+            },
         }
     }
 }
@@ -51,7 +76,12 @@ mod de {
             let remote_id = th.optional_s::<String>("remote");
             let android_res_dir = th.optional::<String>("android_res_dir").map(PathBuf::from);
             let night = th.optional("night");
+            let dark_source = th.optional("dark_source");
             let auto_mirrored = th.optional("auto_mirrored");
+            let monochrome = th.optional("monochrome");
+            let limits = th.optional("limits");
+            let post_process = th.optional::<PostProcessCommand>("post_process");
+            let naming = th.optional::<NamingRulesDto>("naming");
             th.finalize(None)?;
             // endregion: extract
 
@@ -63,7 +93,12 @@ mod de {
                 remote_id,
                 android_res_dir,
                 night,
+                dark_source,
                 auto_mirrored,
+                monochrome,
+                limits,
+                post_process,
+                naming,
             })
         }
     }
@@ -85,14 +120,26 @@ mod test {
         remote = "figma"
         android_res_dir = "src/main/res"
         night = "{base} / dark"
+        dark_source = "Illustration / Dark"
         auto_mirrored = false
+        monochrome = true
+        limits = { max_commands = 2000 }
         "#;
         let declared_remote_ids: HashSet<_> = ["figma".to_string()].into_iter().collect();
         let expected_dto = AndroidDrawableProfileDto {
             remote_id: Some("figma".to_string()),
             android_res_dir: Some(PathBuf::from("src/main/res")),
             night: Some(SingleNamePattern("{base} / dark".to_string())),
+            dark_source: Some("Illustration / Dark".to_string()),
             auto_mirrored: Some(false),
+            monochrome: Some(true),
+            limits: Some(VectorLimitsDto {
+                max_paths: None,
+                max_commands: Some(2000),
+                on_exceeded: None,
+            }),
+            post_process: None,
+            naming: None,
         };
 
         // When
@@ -116,7 +163,12 @@ mod test {
             remote_id: None,
             android_res_dir: None,
             night: None,
+            dark_source: None,
             auto_mirrored: None,
+            monochrome: None,
+            limits: None,
+            post_process: None,
+            naming: None,
         };
 
         // When
@@ -3,7 +3,8 @@ use std::{
     path::PathBuf,
 };
 
-use crate::{CanBeExtendedBy, SingleNamePattern, WebpQuality};
+use super::NamingRulesDto;
+use crate::{CanBeExtendedBy, PostProcessCommand, SingleNamePattern, WebpMethod, WebpQuality};
 
 #[derive(Default)]
 #[cfg_attr(test, derive(PartialEq, Debug))]
@@ -11,9 +12,15 @@ pub(crate) struct AndroidWebpProfileDto {
     pub remote_id: Option<String>,
     pub android_res_dir: Option<PathBuf>,
     pub quality: Option<WebpQuality>,
+    pub lossless: Option<bool>,
+    pub alpha_quality: Option<WebpQuality>,
+    pub method: Option<WebpMethod>,
     pub densities: Option<BTreeSet<AndroidDensityDto>>,
     pub night: Option<SingleNamePattern>,
+    pub dark_source: Option<String>,
     pub legacy_loader: Option<bool>,
+    pub post_process: Option<PostProcessCommand>,
+    pub naming: Option<NamingRulesDto>,
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
@@ -41,13 +48,30 @@ impl CanBeExtendedBy<Self> for AndroidWebpProfileDto {
                 .or(self.android_res_dir.as_ref())
                 .cloned(),
             quality: another.quality.or(self.quality),
+            lossless: another.lossless.or(self.lossless),
+            alpha_quality: another.alpha_quality.or(self.alpha_quality),
+            method: another.method.or(self.method),
             densities: another
                 .densities
                 .as_ref()
                 .or(self.densities.as_ref())
                 .cloned(),
             night: another.night.as_ref().or(self.night.as_ref()).cloned(),
+            dark_source: another
+                .dark_source
+                .as_ref()
+                .or(self.dark_source.as_ref())
+                .cloned(),
             legacy_loader: another.legacy_loader.or(self.legacy_loader),
+            post_process: another
+                .post_process
+                .as_ref()
+                .or(self.post_process.as_ref())
+                .cloned(),
+            naming: match (self.naming.as_ref(), another.naming.as_ref()) {
+                (Some(this), Some(another)) => Some(this.extend(another)),
+                (this, another) => another.or(this).cloned(),
+            },
         }
     }
 }
@@ -59,7 +83,7 @@ pub(crate) struct AndroidWebpProfileDtoContext<'a> {
 mod de {
     use super::*;
     use crate::parser::util::validate_remote_id;
-    use crate::{ParseWithContext, WebpQuality};
+    use crate::{ParseWithContext, WebpMethod, WebpQuality};
     use toml_span::Deserialize;
     use toml_span::de_helpers::{TableHelper, expected};
 
@@ -75,11 +99,17 @@ mod de {
             let remote_id = th.optional_s::<String>("remote");
             let android_res_dir = th.optional::<String>("android_res_dir").map(PathBuf::from);
             let quality = th.optional::<WebpQuality>("quality");
+            let lossless = th.optional::<bool>("lossless");
+            let alpha_quality = th.optional::<WebpQuality>("alpha_quality");
+            let method = th.optional::<WebpMethod>("method");
             let densities = th
                 .optional::<Vec<AndroidDensityDto>>("densities")
                 .map(|vec| vec.into_iter().collect::<BTreeSet<_>>());
             let night = th.optional("night");
+            let dark_source = th.optional("dark_source");
             let legacy_loader = th.optional::<bool>("legacy_loader");
+            let post_process = th.optional::<PostProcessCommand>("post_process");
+            let naming = th.optional::<NamingRulesDto>("naming");
             th.finalize(None)?;
             // endregion: extract
 
@@ -91,9 +121,15 @@ mod de {
                 remote_id,
                 android_res_dir,
                 quality,
+                lossless,
+                alpha_quality,
+                method,
                 densities,
                 night,
+                dark_source,
                 legacy_loader,
+                post_process,
+                naming,
             })
         }
     }
@@ -129,8 +165,12 @@ mod test {
         remote = "figma"
         android_res_dir = "src/main/res"
         quality = 100
+        lossless = true
+        alpha_quality = 80
+        method = 6
         densities = ["ldpi", "mdpi", "hdpi", "xhdpi", "xxhdpi", "xxxhdpi"]
         night = "{base} / dark"
+        dark_source = "Illustration / Dark"
         legacy_loader = false
         "#;
         let declared_remote_ids: HashSet<_> = ["figma".to_string()].into_iter().collect();
@@ -138,6 +178,9 @@ mod test {
             remote_id: Some("figma".to_string()),
             android_res_dir: Some(PathBuf::from("src/main/res")),
             quality: Some(WebpQuality(100.0)),
+            lossless: Some(true),
+            alpha_quality: Some(WebpQuality(80.0)),
+            method: Some(WebpMethod(6)),
             densities: {
                 use AndroidDensityDto::*;
                 Some(
@@ -147,7 +190,10 @@ mod test {
                 )
             },
             night: Some(SingleNamePattern("{base} / dark".to_string())),
+            dark_source: Some("Illustration / Dark".to_string()),
             legacy_loader: Some(false),
+            post_process: None,
+            naming: None,
         };
 
         // When
@@ -171,9 +217,15 @@ mod test {
             remote_id: None,
             android_res_dir: None,
             quality: None,
+            lossless: None,
+            alpha_quality: None,
+            method: None,
             densities: None,
             night: None,
+            dark_source: None,
             legacy_loader: None,
+            post_process: None,
+            naming: None,
         };
 
         // When
@@ -0,0 +1,456 @@
+use super::{ResourceDto, ResourcesDto};
+use crate::{NameCase, Profile, RemoteSource};
+use log::warn;
+use ordermap::OrderMap;
+use std::{collections::HashMap, path::Path, sync::Arc};
+use toml_span::{Span, Spanned};
+
+/// Resources declared in `[bulk]` section of the fig-file, at most one declaration per profile
+#[derive(Default)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub(crate) struct BulkDto(pub OrderMap<String, BulkDeclarationDto>);
+
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub(crate) struct BulkDeclarationDto {
+    /// Id of the remote, whose nodes are listed by `figx scan`
+    pub from_scan: Spanned<String>,
+    /// Glob the names of the nodes should match, e.g. `Icon / *`
+    pub name_filter: Option<String>,
+    /// Case of the names of the resources, snake case by default
+    pub rename: NameCase,
+    pub profile: Arc<Profile>,
+    pub def_span: Span,
+}
+
+/// Output of `figx scan` for a single remote
+struct ScanFileDto {
+    nodes: Vec<ScannedNodeDto>,
+}
+
+struct ScannedNodeDto {
+    name: String,
+}
+
+impl BulkDto {
+    /// Adds a resource for every scanned node matching the declarations.
+    ///
+    /// Resources declared explicitly take precedence over the ones with the same name declared in bulk.
+    pub fn expand_into(
+        self,
+        resources: &mut ResourcesDto,
+        scans_dir: &Path,
+        remotes: &OrderMap<String, Arc<RemoteSource>>,
+    ) -> std::result::Result<(), toml_span::DeserError> {
+        for (profile_name, bulk) in self.0 {
+            // the first remote is the default one
+            let remote_id = match bulk.profile.remote_id() {
+                "" => remotes
+                    .keys()
+                    .next()
+                    .map(String::as_str)
+                    .unwrap_or_default(),
+                remote_id => remote_id,
+            };
+            if remote_id != bulk.from_scan.value {
+                return Err(custom_error(
+                    format!(
+                        "profile `{profile_name}` imports from remote `{remote_id}`, \
+                        but the scan is of remote `{}`",
+                        bulk.from_scan.value,
+                    ),
+                    bulk.from_scan.span,
+                ));
+            }
+            let ScanFileDto { nodes } = read_scan_file(scans_dir, &bulk.from_scan)?;
+            let prefix = bulk.name_filter.as_deref().map(literal_prefix);
+            let android_resource = matches!(
+                bulk.profile.as_ref(),
+                Profile::AndroidWebp(_) | Profile::AndroidDrawable(_)
+            );
+
+            let output = resources.0.entry(profile_name).or_default();
+            // names of the nodes by the names of the resources declared in bulk
+            let mut expanded = HashMap::<String, String>::new();
+            for node in nodes {
+                if let Some(name_filter) = &bulk.name_filter {
+                    if !fast_glob::glob_match(name_filter, &node.name) {
+                        continue;
+                    }
+                }
+                let name = prefix
+                    .and_then(|it| node.name.strip_prefix(it))
+                    .unwrap_or(&node.name);
+                let res_name = bulk.rename.apply(name);
+                if res_name.is_empty() {
+                    continue;
+                }
+                if let Some(other_node_name) = expanded.get(&res_name) {
+                    return Err(custom_error(
+                        format!(
+                            "nodes `{other_node_name}` and `{}` are both named `{res_name}`, \
+                            narrow down `name_filter` or declare the resource explicitly",
+                            node.name,
+                        ),
+                        bulk.def_span,
+                    ));
+                }
+                if output.contains_key(&res_name) {
+                    continue;
+                }
+                if android_resource {
+                    let output_name = bulk.profile.naming().apply(&res_name);
+                    super::util::validate_android_resource_name(&output_name, bulk.def_span)?;
+                }
+                expanded.insert(res_name.clone(), node.name.clone());
+                output.insert(
+                    res_name,
+                    ResourceDto {
+                        node_name: node.name,
+                        use_absolute_bounds: false,
+                        expect: None,
+                        tags: Vec::new(),
+                        profile: bulk.profile.clone(),
+                        override_profile: None,
+                        def_span: bulk.def_span,
+                    },
+                );
+            }
+            if expanded.is_empty() {
+                warn!(
+                    "No nodes of scan `{}` match `{}`",
+                    bulk.from_scan.value,
+                    bulk.name_filter.as_deref().unwrap_or("*"),
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Part of the glob before the first wildcard, it is not repeated in the names of the resources
+fn literal_prefix(glob: &str) -> &str {
+    let end = glob.find(['*', '?', '[', '{']).unwrap_or(glob.len());
+    &glob[..end]
+}
+
+fn read_scan_file(
+    scans_dir: &Path,
+    remote_id: &Spanned<String>,
+) -> std::result::Result<ScanFileDto, toml_span::DeserError> {
+    use toml_span::Deserialize;
+
+    let path = scans_dir.join(format!("{}.toml", remote_id.value));
+    let error = |reason: String| {
+        custom_error(
+            format!("{reason}, run `figx scan {}` to update it", remote_id.value),
+            remote_id.span,
+        )
+    };
+    let string = std::fs::read_to_string(&path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => custom_error(
+            format!(
+                "remote `{id}` is not scanned, run `figx scan {id}` and commit `{}`, \
+                so that the workspace loads on fresh checkouts",
+                path.display(),
+                id = remote_id.value,
+            ),
+            remote_id.span,
+        ),
+        _ => error(format!("unable to read scan `{}`: {e}", path.display())),
+    })?;
+    let parse = || -> std::result::Result<_, toml_span::DeserError> {
+        let mut value = toml_span::parse(&string)?;
+        ScanFileDto::deserialize(&mut value)
+    };
+    parse().map_err(|_| error(format!("scan `{}` is malformed", path.display())))
+}
+
+fn custom_error(message: String, span: Span) -> toml_span::DeserError {
+    toml_span::Error::from((toml_span::ErrorKind::Custom(message.into()), span)).into()
+}
+
+mod de {
+    use super::*;
+    use crate::{ParseWithContext, parser::ResourcesDtoContext};
+    use toml_span::{Deserialize, ErrorKind, de_helpers::TableHelper};
+
+    /// The only version of the scan format `figx scan` writes
+    const SCAN_FILE_VERSION: i64 = 1;
+
+    impl<'de> ParseWithContext<'de> for BulkDto {
+        type Context = ResourcesDtoContext<'de>;
+
+        fn parse_with_ctx(
+            value: &mut toml_span::Value<'de>,
+            ctx: Self::Context,
+        ) -> std::result::Result<Self, toml_span::DeserError> {
+            let mut th = TableHelper::new(value)?;
+            let mut declarations = OrderMap::new();
+
+            for (profile_key, value) in th.table.iter_mut() {
+                let profile_name = profile_key.to_string();
+                let Some(profile) = ctx.profiles.get(&profile_name) else {
+                    return Err(custom_error(
+                        format!("undeclared profile '{profile_name}' used here"),
+                        profile_key.span,
+                    ));
+                };
+
+                // region: extract
+                let def_span = value.span;
+                let mut th = TableHelper::new(value)?;
+                let from_scan = th.required_s::<String>("from_scan")?;
+                let name_filter = th.optional::<String>("name_filter");
+                let rename = th.optional::<NameCase>("rename");
+                th.finalize(None)?;
+                // endregion: extract
+
+                // region: validate
+                if !ctx.declared_remote_ids.contains(&from_scan.value) {
+                    return Err(custom_error(
+                        format!(
+                            "remote `{}` is not declared in the workspace",
+                            from_scan.value
+                        ),
+                        from_scan.span,
+                    ));
+                }
+                // endregion: validate
+
+                declarations.insert(
+                    profile_name,
+                    BulkDeclarationDto {
+                        from_scan,
+                        name_filter,
+                        rename: rename.unwrap_or(NameCase::Snake),
+                        profile: profile.clone(),
+                        def_span,
+                    },
+                );
+            }
+
+            Ok(Self(declarations))
+        }
+    }
+
+    impl<'de> Deserialize<'de> for ScanFileDto {
+        fn deserialize(value: &mut toml_span::Value<'de>) -> Result<Self, toml_span::DeserError> {
+            let mut th = TableHelper::new(value)?;
+            let version = th.required_s::<i64>("version")?;
+            let nodes = th.optional::<Vec<ScannedNodeDto>>("node");
+            th.finalize(None)?;
+            if version.value != SCAN_FILE_VERSION {
+                return Err(custom_error(
+                    format!("unsupported scan version {}", version.value),
+                    version.span,
+                ));
+            }
+            Ok(Self {
+                nodes: nodes.unwrap_or_default(),
+            })
+        }
+    }
+
+    impl<'de> Deserialize<'de> for ScannedNodeDto {
+        fn deserialize(value: &mut toml_span::Value<'de>) -> Result<Self, toml_span::DeserError> {
+            let mut th = TableHelper::new(value)?;
+            let name = th.required::<String>("name")?;
+            // other attributes of the node are not needed to declare resources
+            th.finalize(Some(value))?;
+            Ok(Self { name })
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use crate::{AndroidWebpProfile, NodeIdList, RetryPolicy, SvgProfile};
+
+    #[test]
+    fn literal_prefix__globs__EXPECT__part_before_first_wildcard() {
+        // Then
+        assert_eq!("Icon / ", literal_prefix("Icon / *"));
+        assert_eq!("Icon / ", literal_prefix("Icon / {Filled,Outlined} / *"));
+        assert_eq!("Logo", literal_prefix("Logo"));
+    }
+
+    static SCAN: &str = r#"
+        version = 1
+        [[node]]
+        name = "Icon / Arrow Left"
+        [[node]]
+        name = "Icon / Star"
+        [[node]]
+        name = "Logo"
+    "#;
+
+    fn remotes() -> OrderMap<String, Arc<RemoteSource>> {
+        let remote = RemoteSource {
+            id: "design".to_owned(),
+            file_key: "FILE".to_owned(),
+            branch: None,
+            container_node_ids: NodeIdList::Plain(vec!["0:1".to_owned()]),
+            access_token: String::new(),
+            shallow_depth: None,
+            export_batch_size: RemoteSource::DEFAULT_EXPORT_BATCH_SIZE,
+            retry: RetryPolicy::default(),
+        };
+        [("design".to_owned(), Arc::new(remote))]
+            .into_iter()
+            .collect()
+    }
+
+    fn bulk(profile: Profile, name_filter: Option<&str>) -> BulkDto {
+        let declaration = BulkDeclarationDto {
+            from_scan: Spanned::with_span("design".to_owned(), Span::new(0, 1)),
+            name_filter: name_filter.map(str::to_owned),
+            rename: NameCase::Snake,
+            profile: Arc::new(profile),
+            def_span: Span::new(0, 1),
+        };
+        BulkDto([("icons".to_owned(), declaration)].into_iter().collect())
+    }
+
+    fn resource(node_name: &str) -> ResourceDto {
+        ResourceDto {
+            node_name: node_name.to_owned(),
+            use_absolute_bounds: false,
+            expect: None,
+            tags: Vec::new(),
+            profile: Arc::new(Profile::Svg(SvgProfile::default())),
+            override_profile: None,
+            def_span: Span::new(0, 1),
+        }
+    }
+
+    fn expand(
+        bulk: BulkDto,
+        resources: &mut ResourcesDto,
+        scan: Option<&str>,
+    ) -> std::result::Result<(), toml_span::DeserError> {
+        let dir = tempfile::tempdir().unwrap();
+        if let Some(scan) = scan {
+            std::fs::write(dir.path().join("design.toml"), scan).unwrap();
+        }
+        bulk.expand_into(resources, dir.path(), &remotes())
+    }
+
+    /// Resource names of the profile mapped to the node names
+    fn declared(resources: &ResourcesDto) -> Vec<(String, String)> {
+        resources.0["icons"]
+            .iter()
+            .map(|(name, res)| (name.clone(), res.node_name.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn BulkDto__name_filter__EXPECT__matching_nodes_without_literal_prefix() {
+        // Given
+        let bulk = bulk(Profile::Svg(SvgProfile::default()), Some("Icon / *"));
+        let mut resources = ResourcesDto::default();
+
+        // When
+        expand(bulk, &mut resources, Some(SCAN)).unwrap();
+
+        // Then
+        assert_eq!(
+            vec![
+                ("arrow_left".to_owned(), "Icon / Arrow Left".to_owned()),
+                ("star".to_owned(), "Icon / Star".to_owned()),
+            ],
+            declared(&resources),
+        );
+    }
+
+    #[test]
+    fn BulkDto__no_name_filter__EXPECT__all_nodes() {
+        // Given
+        let bulk = bulk(Profile::Svg(SvgProfile::default()), None);
+        let mut resources = ResourcesDto::default();
+
+        // When
+        expand(bulk, &mut resources, Some(SCAN)).unwrap();
+
+        // Then
+        let names = declared(&resources)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["icon_arrow_left", "icon_star", "logo"], names);
+    }
+
+    #[test]
+    fn BulkDto__explicit_resource_with_same_name__EXPECT__explicit_one_kept() {
+        // Given
+        let bulk = bulk(Profile::Svg(SvgProfile::default()), Some("Icon / *"));
+        let mut resources = ResourcesDto::default();
+        resources
+            .0
+            .entry("icons".to_owned())
+            .or_default()
+            .insert("star".to_owned(), resource("Star Filled"));
+
+        // When
+        expand(bulk, &mut resources, Some(SCAN)).unwrap();
+
+        // Then
+        assert_eq!(
+            vec![
+                ("star".to_owned(), "Star Filled".to_owned()),
+                ("arrow_left".to_owned(), "Icon / Arrow Left".to_owned()),
+            ],
+            declared(&resources),
+        );
+    }
+
+    #[test]
+    fn BulkDto__nodes_with_same_resource_name__EXPECT__error() {
+        // Given
+        let scan =
+            "version = 1\n[[node]]\nname = \"Icon / Star\"\n[[node]]\nname = \"Icon / star\"\n";
+        let bulk = bulk(Profile::Svg(SvgProfile::default()), Some("Icon / *"));
+        let mut resources = ResourcesDto::default();
+
+        // When
+        let result = expand(bulk, &mut resources, Some(scan));
+
+        // Then
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn BulkDto__invalid_android_resource_name__EXPECT__error() {
+        // Given
+        let scan = "version = 1\n[[node]]\nname = \"Icon / 24 Close\"\n";
+        let bulk = bulk(
+            Profile::AndroidWebp(AndroidWebpProfile::default()),
+            Some("Icon / *"),
+        );
+        let mut resources = ResourcesDto::default();
+
+        // When
+        let result = expand(bulk, &mut resources, Some(scan));
+
+        // Then
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn BulkDto__missing_scan__EXPECT__error_asking_to_scan() {
+        // Given
+        let bulk = bulk(Profile::Svg(SvgProfile::default()), None);
+        let mut resources = ResourcesDto::default();
+
+        // When
+        let result = expand(bulk, &mut resources, None);
+
+        // Then
+        let error = result.unwrap_err().errors.remove(0);
+        let toml_span::ErrorKind::Custom(message) = error.kind else {
+            panic!("custom error expected");
+        };
+        assert!(message.contains("run `figx scan design`"), "{message}");
+    }
+}
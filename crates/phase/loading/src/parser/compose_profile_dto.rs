@@ -3,9 +3,9 @@ use std::{
     path::PathBuf,
 };
 
-use crate::CanBeExtendedBy;
+use crate::{CanBeExtendedBy, PostProcessCommand};
 
-use super::VariantsDto;
+use super::{NamingRulesDto, VariantsDto, VectorLimitsDto};
 
 #[derive(Default)]
 #[cfg_attr(test, derive(PartialEq, Debug))]
@@ -20,6 +20,15 @@ pub(crate) struct ComposeProfileDto {
     pub preview: Option<ComposePreviewDto>,
     pub variants: Option<VariantsDto>,
     pub composable_get: Option<bool>,
+    pub property_name_template: Option<String>,
+    pub receiver_object: Option<String>,
+    pub combine_variants: Option<bool>,
+    pub limits: Option<VectorLimitsDto>,
+    pub multiplatform: Option<bool>,
+    pub identifier_prefix: Option<String>,
+    pub document_sources: Option<bool>,
+    pub post_process: Option<PostProcessCommand>,
+    pub naming: Option<NamingRulesDto>,
 }
 
 impl CanBeExtendedBy<ComposeProfileDto> for ComposeProfileDto {
@@ -56,6 +65,37 @@ impl CanBeExtendedBy<ComposeProfileDto> for ComposeProfileDto {
                 _ => None,
             },
             composable_get: another.composable_get.or(self.composable_get),
+            property_name_template: another
+                .property_name_template
+                .as_ref()
+                .or(self.property_name_template.as_ref())
+                .cloned(),
+            receiver_object: another
+                .receiver_object
+                .as_ref()
+                .or(self.receiver_object.as_ref())
+                .cloned(),
+            combine_variants: another.combine_variants.or(self.combine_variants),
+            limits: match (self.limits.as_ref(), another.limits.as_ref()) {
+                (Some(base), Some(another)) => Some(base.extend(another)),
+                (base, another) => another.or(base).cloned(),
+            },
+            multiplatform: another.multiplatform.or(self.multiplatform),
+            identifier_prefix: another
+                .identifier_prefix
+                .as_ref()
+                .or(self.identifier_prefix.as_ref())
+                .cloned(),
+            document_sources: another.document_sources.or(self.document_sources),
+            post_process: another
+                .post_process
+                .as_ref()
+                .or(self.post_process.as_ref())
+                .cloned(),
+            naming: match (self.naming.as_ref(), another.naming.as_ref()) {
+                (Some(this), Some(another)) => Some(this.extend(another)),
+                (this, another) => another.or(this).cloned(),
+            },
         }
     }
 }
@@ -83,8 +123,8 @@ mod de {
     use super::*;
     use crate::ParseWithContext;
     use crate::parser::util::validate_remote_id;
-    use toml_span::Deserialize;
     use toml_span::de_helpers::TableHelper;
+    use toml_span::{Deserialize, ErrorKind};
 
     impl<'de> ParseWithContext<'de> for ComposeProfileDto {
         type Context = ComposeProfileDtoContext<'de>;
@@ -107,11 +147,60 @@ mod de {
             let preview = th.optional("preview");
             let variants = th.optional::<VariantsDto>("variants");
             let composable_get = th.optional("composable_get");
+            let property_name_template = th.optional_s::<String>("property_name_template");
+            let receiver_object = th.optional_s::<String>("receiver_object");
+            let combine_variants = th.optional("combine_variants");
+            let limits = th.optional("limits");
+            let multiplatform = th.optional("multiplatform");
+            let identifier_prefix = th.optional_s::<String>("identifier_prefix");
+            let document_sources = th.optional("document_sources");
+            let post_process = th.optional::<PostProcessCommand>("post_process");
+            let naming = th.optional::<NamingRulesDto>("naming");
             th.finalize(None)?;
             // endregion: extract
 
             // region: validate
             let remote_id = validate_remote_id(remote_id, ctx.declared_remote_ids)?;
+            let template_wo_placeholder = property_name_template
+                .as_ref()
+                .filter(|it| !it.value.contains("{name}"));
+            if let Some(template) = template_wo_placeholder {
+                return Err(toml_span::Error::from((
+                    ErrorKind::Custom(
+                        "property name template should contain `{name}` placeholder".into(),
+                    ),
+                    template.span,
+                ))
+                .into());
+            }
+            let invalid_receiver = receiver_object.as_ref().filter(|it| {
+                !it.value.split('.').all(|segment| {
+                    !segment.is_empty() && segment.chars().all(|c| c.is_alphanumeric() || c == '_')
+                })
+            });
+            if let Some(receiver) = invalid_receiver {
+                return Err(toml_span::Error::from((
+                    ErrorKind::Custom(
+                        "receiver object should be a fully qualified name, e.g. `com.example.Icons.Filled`".into(),
+                    ),
+                    receiver.span,
+                ))
+                .into());
+            }
+            let invalid_prefix = identifier_prefix.as_ref().filter(|it| {
+                let mut chars = it.value.chars();
+                !chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+                    || !chars.all(|c| c.is_alphanumeric() || c == '_')
+            });
+            if let Some(prefix) = invalid_prefix {
+                return Err(toml_span::Error::from((
+                    ErrorKind::Custom(
+                        "identifier prefix should start with a letter and contain only letters, digits and `_`".into(),
+                    ),
+                    prefix.span,
+                ))
+                .into());
+            }
             // endregion: validate
 
             Ok(Self {
@@ -125,6 +214,15 @@ mod de {
                 preview,
                 variants,
                 composable_get,
+                property_name_template: property_name_template.map(|it| it.value),
+                receiver_object: receiver_object.map(|it| it.value),
+                combine_variants,
+                limits,
+                multiplatform,
+                identifier_prefix: identifier_prefix.map(|it| it.value),
+                document_sources,
+                post_process,
+                naming,
             })
         }
     }
@@ -132,12 +230,27 @@ mod de {
     impl<'de> Deserialize<'de> for ColorMappingDto {
         fn deserialize(value: &mut toml_span::Value<'de>) -> Result<Self, toml_span::DeserError> {
             let mut th = TableHelper::new(value)?;
-            let from = th.required("from")?;
+            let from = th.required_s::<String>("from")?;
             let to = th.required("to")?;
             let imports = th.optional("imports").unwrap_or_default();
             th.finalize(None)?;
 
-            Ok(Self { from, to, imports })
+            // `@name` references a color variable of the Figma file
+            if from.value == "@" {
+                return Err(toml_span::Error::from((
+                    ErrorKind::Custom(
+                        "color variable name is missing, e.g. `@color/Primary`".into(),
+                    ),
+                    from.span,
+                ))
+                .into());
+            }
+
+            Ok(Self {
+                from: from.value,
+                to,
+                imports,
+            })
         }
     }
 
@@ -158,6 +271,7 @@ mod de {
 mod test {
 
     use super::*;
+    use crate::parser::LimitActionDto;
     use crate::{ParseWithContext, variant_dto};
     use ordermap::ordermap;
     use toml_span::Span;
@@ -177,6 +291,13 @@ mod test {
         preview.imports = ["com.example.Preview"]
         preview.code = "lorem ipsum dolor sit amet"
         composable_get = false
+        property_name_template = "{name}Icon"
+        receiver_object = "com.example.Icons.Filled"
+        combine_variants = true
+        limits = { max_paths = 100, on_exceeded = "fail" }
+        multiplatform = true
+        identifier_prefix = "Ic"
+        document_sources = true
         variants.small = { output_name = "{base}Small", figma_name = "{base} / small", scale = 1.0 }
         variants.big = { output_name = "{base}Big", figma_name = "{base} / big", scale = 2.0 }
         variants.use = ["small", "big"]
@@ -199,6 +320,17 @@ mod test {
                 code: "lorem ipsum dolor sit amet".to_string(),
             }),
             composable_get: Some(false),
+            property_name_template: Some("{name}Icon".to_string()),
+            receiver_object: Some("com.example.Icons.Filled".to_string()),
+            combine_variants: Some(true),
+            limits: Some(VectorLimitsDto {
+                max_paths: Some(100),
+                max_commands: None,
+                on_exceeded: Some(LimitActionDto::Fail),
+            }),
+            multiplatform: Some(true),
+            identifier_prefix: Some("Ic".to_string()),
+            document_sources: Some(true),
             variants: Some(VariantsDto {
                 all_variants: Some(ordermap! {
                     // alphabetic keys sorting because of BTreeMap under the hood of the toml parser
@@ -207,6 +339,8 @@ mod test {
                 }),
                 use_variants: Some(vec!["small".to_string(), "big".to_string()]),
             }),
+            post_process: None,
+            naming: None,
         };
 
         // When
@@ -236,7 +370,16 @@ mod test {
             color_mappings: None,
             preview: None,
             composable_get: None,
+            property_name_template: None,
+            receiver_object: None,
+            combine_variants: None,
+            limits: None,
+            multiplatform: None,
+            identifier_prefix: None,
+            document_sources: None,
             variants: None,
+            post_process: None,
+            naming: None,
         };
 
         // When
@@ -276,6 +419,32 @@ mod test {
         }
     }
 
+    #[test]
+    fn ComposeProfileDto__template_wo_placeholder__EXPECT__error_with_correct_span() {
+        // Given
+        let toml = unindent(
+            r#"
+                remote = "figma"
+                property_name_template = "Icon"
+            "#,
+        );
+        let declared_remote_ids: HashSet<_> = ["figma".to_string()].into_iter().collect();
+        let err_spans = [Span::new(43, 47)];
+
+        // When
+        let mut value = toml_span::parse(&toml).unwrap();
+        let ctx = ComposeProfileDtoContext {
+            declared_remote_ids: &declared_remote_ids,
+        };
+        let actual_err = ComposeProfileDto::parse_with_ctx(&mut value, ctx).unwrap_err();
+
+        // Then
+        assert_eq!(err_spans.len(), actual_err.errors.len());
+        for (expected_span, actual_err) in err_spans.into_iter().zip(actual_err.errors) {
+            assert_eq!(expected_span, actual_err.span);
+        }
+    }
+
     #[test]
     fn ComposeProfileDto__valid_undeclared_key__EXPECT__error_with_correct_span() {
         // Given
@@ -311,4 +480,71 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn ComposeProfileDto__multiplatform_extended_by_combine_variants__EXPECT__both_kept() {
+        // Given
+        let declared_remote_ids: HashSet<_> = ["figma".to_string()].into_iter().collect();
+        let parse = |toml: &str| {
+            let mut value = toml_span::parse(toml).unwrap();
+            let ctx = ComposeProfileDtoContext {
+                declared_remote_ids: &declared_remote_ids,
+            };
+            ComposeProfileDto::parse_with_ctx(&mut value, ctx).unwrap()
+        };
+        let base = parse(r#"multiplatform = true"#);
+        let another = parse(
+            r#"
+            combine_variants = true
+            variants.small = { output_name = "{base}Small", figma_name = "{base} / small" }
+            "#,
+        );
+
+        // When
+        let extended = base.extend(&another);
+
+        // Then
+        assert_eq!(Some(true), extended.multiplatform);
+        assert_eq!(Some(true), extended.combine_variants);
+        assert!(extended.variants.is_some());
+    }
+
+    #[test]
+    fn ColorMappingDto__variable_reference__EXPECT__valid_dto() {
+        // Given
+        let toml = r#"
+        from = "@color/Primary"
+        to = "MaterialTheme.colorScheme.primary"
+        imports = ["androidx.compose.material3.MaterialTheme"]
+        "#;
+        let expected_dto = ColorMappingDto {
+            from: "@color/Primary".to_string(),
+            to: "MaterialTheme.colorScheme.primary".to_string(),
+            imports: vec!["androidx.compose.material3.MaterialTheme".to_string()],
+        };
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let actual_dto =
+            <ColorMappingDto as toml_span::Deserialize>::deserialize(&mut value).unwrap();
+
+        // Then
+        assert_eq!(expected_dto, actual_dto);
+    }
+
+    #[test]
+    fn ColorMappingDto__empty_variable_reference__EXPECT__error() {
+        // Given
+        let toml = r#"
+        from = "@"
+        to = "MaterialTheme.colorScheme.primary"
+        "#;
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let actual = <ColorMappingDto as toml_span::Deserialize>::deserialize(&mut value);
+
+        // Then
+        assert!(actual.is_err());
+    }
 }
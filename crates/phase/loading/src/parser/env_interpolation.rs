@@ -0,0 +1,201 @@
+use std::borrow::Cow;
+use toml_span::{DeserError, ErrorKind, Value, value::ValueInner};
+
+/// Replaces `${VAR}` and `${VAR:-fallback}` in all string values of the parsed config
+/// with the values of environment variables. `$${` is kept as a literal `${`.
+///
+/// Runs before the DTOs are parsed, so every option of the config supports interpolation.
+pub(crate) fn interpolate_env(value: &mut Value<'_>) -> std::result::Result<(), DeserError> {
+    let mut errors = Vec::new();
+    interpolate_value(value, &|name| std::env::var(name).ok(), &mut errors);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(DeserError { errors })
+    }
+}
+
+fn interpolate_value(
+    value: &mut Value<'_>,
+    lookup: &dyn Fn(&str) -> Option<String>,
+    errors: &mut Vec<toml_span::Error>,
+) {
+    let span = value.span;
+    match value.take() {
+        ValueInner::String(string) if string.contains('$') => {
+            match interpolate_str(&string, lookup) {
+                Ok(interpolated) => value.set(ValueInner::String(Cow::Owned(interpolated))),
+                Err(message) => {
+                    errors.push(toml_span::Error::from((
+                        ErrorKind::Custom(message.into()),
+                        span,
+                    )));
+                    value.set(ValueInner::String(string));
+                }
+            }
+        }
+        ValueInner::Array(mut array) => {
+            for value in array.iter_mut() {
+                interpolate_value(value, lookup, errors);
+            }
+            value.set(ValueInner::Array(array));
+        }
+        ValueInner::Table(mut table) => {
+            for (_, value) in table.iter_mut() {
+                interpolate_value(value, lookup, errors);
+            }
+            value.set(ValueInner::Table(table));
+        }
+        inner => value.set(inner),
+    }
+}
+
+fn interpolate_str(
+    string: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> std::result::Result<String, String> {
+    let mut output = String::with_capacity(string.len());
+    let mut rest = string;
+    while let Some(start) = rest.find('$') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if let Some(stripped) = rest.strip_prefix("$${") {
+            output.push_str("${");
+            rest = stripped;
+            continue;
+        }
+        let Some(stripped) = rest.strip_prefix("${") else {
+            // a lonely `$` is not an interpolation
+            output.push('$');
+            rest = &rest[1..];
+            continue;
+        };
+        let Some(end) = stripped.find('}') else {
+            return Err("unterminated `${`, expected `}` after the variable name".to_string());
+        };
+        let (name, fallback) = match stripped[..end].split_once(":-") {
+            Some((name, fallback)) => (name, Some(fallback)),
+            None => (&stripped[..end], None),
+        };
+        if !is_valid_var_name(name) {
+            return Err(format!(
+                "invalid environment variable name `{name}`, expected latin letters, digits and `_`"
+            ));
+        }
+        // like in shell, `:-` also replaces variables which are set but empty
+        match (lookup(name).filter(|it| !it.is_empty()), fallback) {
+            (Some(value), _) => output.push_str(&value),
+            (None, Some(fallback)) => output.push_str(fallback),
+            (None, None) => {
+                return Err(format!(
+                    "environment variable `{name}` is not set, \
+                    set it or provide a fallback with `${{{name}:-fallback}}`"
+                ));
+            }
+        }
+        rest = &stripped[end + 1..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+fn is_valid_var_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use toml_span::Span;
+    use unindent::unindent;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "FIGMA_FILE_KEY" => Some("abcdef".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn interpolate_str__variables_and_fallbacks__EXPECT__replaced() {
+        // Then
+        assert_eq!(
+            Ok("abcdef".to_string()),
+            interpolate_str("${FIGMA_FILE_KEY}", &lookup)
+        );
+        assert_eq!(
+            Ok("res/abcdef/drawable".to_string()),
+            interpolate_str("res/${FIGMA_FILE_KEY}/drawable", &lookup),
+        );
+        assert_eq!(
+            Ok("res".to_string()),
+            interpolate_str("${OUT_DIR:-res}", &lookup)
+        );
+        assert_eq!(
+            Ok("res".to_string()),
+            interpolate_str("${EMPTY:-res}", &lookup)
+        );
+        assert_eq!(Ok("".to_string()), interpolate_str("${OUT_DIR:-}", &lookup));
+    }
+
+    #[test]
+    fn interpolate_str__escaped_and_lonely_dollars__EXPECT__kept() {
+        // Then
+        assert_eq!(
+            Ok("${HOME}".to_string()),
+            interpolate_str("$${HOME}", &lookup)
+        );
+        assert_eq!(
+            Ok("$5 and $".to_string()),
+            interpolate_str("$5 and $", &lookup)
+        );
+    }
+
+    #[test]
+    fn interpolate_str__invalid_input__EXPECT__error() {
+        // Then
+        assert!(interpolate_str("${FIGMA_FILE_KEY", &lookup).is_err());
+        assert!(interpolate_str("${}", &lookup).is_err());
+        assert!(interpolate_str("${FILE-KEY}", &lookup).is_err());
+        assert!(interpolate_str("${OUT_DIR}", &lookup).is_err());
+    }
+
+    #[test]
+    fn interpolate_value__missing_variable__EXPECT__error_with_correct_span() {
+        // Given
+        let toml = unindent(
+            r#"
+                [remotes.design]
+                file_key = "${FIGMA_FILE_KEY}"
+                container_node_ids = ["${NODE_ID}"]
+            "#,
+        );
+        let mut errors = Vec::new();
+
+        // When
+        let mut value = toml_span::parse(&toml).unwrap();
+        interpolate_value(&mut value, &lookup, &mut errors);
+
+        // Then
+        assert_eq!(1, errors.len());
+        assert_eq!(Span::new(71, 81), errors[0].span);
+        let remote = &value
+            .as_table()
+            .unwrap()
+            .get("remotes")
+            .unwrap()
+            .as_table()
+            .unwrap();
+        let file_key = remote
+            .get("design")
+            .unwrap()
+            .as_table()
+            .unwrap()
+            .get("file_key");
+        assert_eq!(Some("abcdef"), file_key.and_then(|it| it.as_str()));
+    }
+}
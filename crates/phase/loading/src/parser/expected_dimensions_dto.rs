@@ -0,0 +1,146 @@
+use crate::ExpectedDimensions;
+
+#[derive(Default, Clone)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub(crate) struct ExpectedDimensionsDto {
+    pub width: Option<f32>,
+    pub height: Option<f32>,
+    pub aspect_ratio: Option<f32>,
+}
+
+impl From<ExpectedDimensionsDto> for ExpectedDimensions {
+    fn from(value: ExpectedDimensionsDto) -> Self {
+        Self {
+            width: value.width,
+            height: value.height,
+            aspect_ratio: value.aspect_ratio,
+        }
+    }
+}
+
+mod de {
+    use super::*;
+    use toml_span::de_helpers::TableHelper;
+    use toml_span::{Deserialize, ErrorKind, Spanned, value::ValueInner};
+
+    /// Number of points, both `24` and `24.0` are allowed
+    struct Dimension(f32);
+
+    impl<'de> Deserialize<'de> for Dimension {
+        fn deserialize(value: &mut toml_span::Value<'de>) -> Result<Self, toml_span::DeserError> {
+            match value.take() {
+                ValueInner::Float(value) => Ok(Self(value as f32)),
+                ValueInner::Integer(value) => Ok(Self(value as f32)),
+                other => Err(toml_span::Error::from((
+                    ErrorKind::Wanted {
+                        expected: "a number",
+                        found: other.type_str(),
+                    },
+                    value.span,
+                ))
+                .into()),
+            }
+        }
+    }
+
+    impl<'de> Deserialize<'de> for ExpectedDimensionsDto {
+        fn deserialize(value: &mut toml_span::Value<'de>) -> Result<Self, toml_span::DeserError> {
+            // region: extract
+            let mut th = TableHelper::new(value)?;
+            let width = th.optional_s::<Dimension>("width");
+            let height = th.optional_s::<Dimension>("height");
+            let aspect_ratio = th.optional_s::<Dimension>("aspect_ratio");
+            th.finalize(None)?;
+            // endregion: extract
+
+            // region: validate
+            for dimension in [&width, &height, &aspect_ratio].into_iter().flatten() {
+                if dimension.value.0 <= 0.0 {
+                    return Err(toml_span::Error::from((
+                        ErrorKind::Custom("expected dimension must be greater than zero".into()),
+                        dimension.span,
+                    ))
+                    .into());
+                }
+            }
+            if let (Some(_), Some(_), Some(aspect_ratio)) = (&width, &height, &aspect_ratio) {
+                return Err(toml_span::Error::from((
+                    ErrorKind::Custom(
+                        "`aspect_ratio` cannot be used together with both `width` and `height`"
+                            .into(),
+                    ),
+                    aspect_ratio.span,
+                ))
+                .into());
+            }
+            // endregion: validate
+
+            let value = |it: Option<Spanned<Dimension>>| it.map(|it| it.value.0);
+            Ok(Self {
+                width: value(width),
+                height: value(height),
+                aspect_ratio: value(aspect_ratio),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use toml_span::Span;
+    use toml_span::de_helpers::TableHelper;
+
+    #[test]
+    fn ExpectedDimensionsDto__valid_toml__EXPECT__valid_dto() {
+        // Given
+        let toml = r#"
+        icon = { width = 24, height = 24 }
+        banner = { aspect_ratio = 1.5 }
+        "#;
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let mut th = TableHelper::new(&mut value).unwrap();
+        let icon = th.required::<ExpectedDimensionsDto>("icon").unwrap();
+        let banner = th.required::<ExpectedDimensionsDto>("banner").unwrap();
+
+        // Then
+        assert_eq!(
+            ExpectedDimensionsDto {
+                width: Some(24.0),
+                height: Some(24.0),
+                aspect_ratio: None,
+            },
+            icon,
+        );
+        assert_eq!(
+            ExpectedDimensionsDto {
+                width: None,
+                height: None,
+                aspect_ratio: Some(1.5),
+            },
+            banner,
+        );
+    }
+
+    #[test]
+    fn ExpectedDimensionsDto__invalid_toml__EXPECT__error_with_correct_span() {
+        // Given
+        let toml = r#"
+        e1 = { width = 0 }
+        e2 = { width = 24, height = 24, aspect_ratio = 1 }
+        "#;
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let mut th = TableHelper::new(&mut value).unwrap();
+        let e1 = th.required::<ExpectedDimensionsDto>("e1").unwrap_err();
+        let e2 = th.required::<ExpectedDimensionsDto>("e2").unwrap_err();
+
+        // Then
+        assert_eq!(Span::new(24, 25), e1.span);
+        assert_eq!(Span::new(83, 84), e2.span);
+    }
+}
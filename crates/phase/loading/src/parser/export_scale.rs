@@ -2,29 +2,33 @@ mod de {
     use crate::ExportScale;
     use toml_span::{Deserialize, ErrorKind};
 
+    fn scale_error(span: toml_span::Span) -> toml_span::DeserError {
+        toml_span::Error {
+            kind: ErrorKind::Custom(
+                format!(
+                    "scale must be a number from {} to {}",
+                    ExportScale::FIGMA_MIN,
+                    ExportScale::MAX
+                )
+                .into(),
+            ),
+            span,
+            line_info: None,
+        }
+        .into()
+    }
+
     impl<'de> Deserialize<'de> for ExportScale {
         fn deserialize(value: &mut toml_span::Value<'de>) -> Result<Self, toml_span::DeserError> {
             let scale = match value.take() {
                 toml_span::value::ValueInner::Float(value) => value as f32,
                 toml_span::value::ValueInner::Integer(value) => value as f32,
-                _ => {
-                    return Err(toml_span::Error {
-                        kind: ErrorKind::Custom("scale mut be a number from 0.1 to 4".into()),
-                        span: value.span,
-                        line_info: None,
-                    }
-                    .into());
-                }
+                _ => return Err(scale_error(value.span)),
             };
-            match scale {
-                0.1..=4.0 => Ok(ExportScale(scale)),
-                _ => Err(toml_span::Error {
-                    kind: ErrorKind::Custom("scale mut be a number from 0.1 to 4".into()),
-                    span: value.span,
-                    line_info: None,
-                }
-                .into()),
+            if !(ExportScale::FIGMA_MIN..=ExportScale::MAX).contains(&scale) {
+                return Err(scale_error(value.span));
             }
+            Ok(ExportScale(scale))
         }
     }
 }
@@ -33,22 +37,25 @@ mod de {
 #[allow(non_snake_case)]
 mod test {
 
-    use toml_span::de_helpers::TableHelper;
     use crate::ExportScale;
+    use toml_span::de_helpers::TableHelper;
 
     #[test]
     fn ExportScale__valid_toml__EXPECT__valid_value() {
         // Given
         let toml = r#"
-        s1 = 0.1
+        s1 = 0.01
         s2 = 2
         s3 = 4.0
         s4 = 0
-        s5 = 4.1
+        s5 = 16.1
+        s6 = 8
+        s7 = 0.001
         "#;
-        let scale1 = ExportScale(0.1);
+        let scale1 = ExportScale(0.01);
         let scale2 = ExportScale(2.0);
         let scale3 = ExportScale(4.0);
+        let scale6 = ExportScale(8.0);
 
         // When
         let mut value = toml_span::parse(toml).unwrap();
@@ -60,5 +67,7 @@ mod test {
         assert_eq!(scale3, th.required::<ExportScale>("s3").unwrap());
         assert!(th.required::<ExportScale>("s4").is_err());
         assert!(th.required::<ExportScale>("s5").is_err());
+        assert_eq!(scale6, th.required::<ExportScale>("s6").unwrap());
+        assert!(th.required::<ExportScale>("s7").is_err());
     }
 }
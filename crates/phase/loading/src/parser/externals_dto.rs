@@ -0,0 +1,161 @@
+use ordermap::OrderMap;
+use std::path::PathBuf;
+
+#[derive(Default)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub(crate) struct ExternalsDto(pub OrderMap<String, ExternalDto>);
+
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub(crate) struct ExternalDto {
+    /// Path to the external workspace root, relative to the current workspace root
+    pub path: PathBuf,
+}
+
+mod de {
+    use super::*;
+    use crate::ParseWithContext;
+    use toml_span::{ErrorKind, de_helpers::TableHelper};
+
+    impl<'de> ParseWithContext<'de> for ExternalsDto {
+        type Context = ();
+
+        fn parse_with_ctx(
+            value: &mut toml_span::Value<'de>,
+            _ctx: Self::Context,
+        ) -> std::result::Result<Self, toml_span::DeserError> {
+            // region: extract
+            let mut th = TableHelper::new(value)?;
+            let mut externals = OrderMap::with_capacity(th.table.len()); // ordermap for deterministic order
+            for (key, value) in th.table.iter_mut() {
+                let only_allowed_chars = key
+                    .name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+                if key.name.is_empty() || !only_allowed_chars {
+                    return Err(toml_span::Error::from((
+                        ErrorKind::Custom(
+                            "external name should contain only latin letters, digits, `-` and `_`"
+                                .into(),
+                        ),
+                        key.span,
+                    ))
+                    .into());
+                }
+                let external = ExternalDto::parse_with_ctx(value, ())?;
+                externals.insert(key.to_string(), external);
+            }
+            th.finalize(Some(value))?;
+            // endregion: extract
+
+            Ok(Self(externals))
+        }
+    }
+
+    impl<'de> ParseWithContext<'de> for ExternalDto {
+        type Context = ();
+
+        fn parse_with_ctx(
+            value: &mut toml_span::Value<'de>,
+            _ctx: Self::Context,
+        ) -> std::result::Result<Self, toml_span::DeserError> {
+            // region: extract
+            let mut th = TableHelper::new(value)?;
+            let path = th.required_s::<String>("path")?;
+            th.finalize(None)?;
+            // endregion: extract
+
+            // region: validate
+            if path.value.is_empty() {
+                return Err(toml_span::Error::from((
+                    ErrorKind::Custom("path of the external workspace cannot be empty".into()),
+                    path.span,
+                ))
+                .into());
+            }
+            // endregion: validate
+
+            Ok(Self {
+                path: PathBuf::from(path.value),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use crate::ParseWithContext;
+    use toml_span::Span;
+    use unindent::unindent;
+
+    #[test]
+    fn ExternalsDto__parse_valid_externals__EXPECT__valid_dto() {
+        // Given
+        let toml = unindent(
+            r#"
+                design_system = { path = "../design-system" }
+                brand = { path = "vendor/brand" }
+            "#,
+        );
+        let expected_dto = {
+            let mut externals = OrderMap::new();
+            externals.insert(
+                "design_system".to_owned(),
+                ExternalDto {
+                    path: PathBuf::from("../design-system"),
+                },
+            );
+            externals.insert(
+                "brand".to_owned(),
+                ExternalDto {
+                    path: PathBuf::from("vendor/brand"),
+                },
+            );
+            ExternalsDto(externals)
+        };
+
+        // When
+        let mut value = toml_span::parse(&toml).unwrap();
+        let actual_dto = ExternalsDto::parse_with_ctx(&mut value, ()).unwrap();
+
+        // Then
+        assert_eq!(expected_dto, actual_dto);
+    }
+
+    #[test]
+    fn ExternalsDto__parse_external_w_invalid_name__EXPECT__error_with_correct_span() {
+        // Given
+        let toml = unindent(
+            r#"
+                "design.system" = { path = "../design-system" }
+            "#,
+        );
+        let expected_spans = [Span::new(1, 14)];
+
+        // When
+        let mut value = toml_span::parse(&toml).unwrap();
+        let actual_err = ExternalsDto::parse_with_ctx(&mut value, ()).unwrap_err();
+
+        // Then
+        for (err, expected_span) in actual_err.errors.iter().zip(expected_spans) {
+            assert_eq!(expected_span, err.span);
+        }
+    }
+
+    #[test]
+    fn ExternalDto__parse_external_w_empty_path__EXPECT__error_with_correct_span() {
+        // Given
+        let toml = r#"path = """#;
+        let expected_spans = [Span::new(7, 8)];
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let actual_err = ExternalDto::parse_with_ctx(&mut value, ()).unwrap_err();
+
+        // Then
+        for (err, expected_span) in actual_err.errors.iter().zip(expected_spans) {
+            assert_eq!(expected_span, err.span);
+        }
+    }
+}
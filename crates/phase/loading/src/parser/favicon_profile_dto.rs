@@ -0,0 +1,153 @@
+use super::{NamingRulesDto, VariantsDto};
+use crate::{CanBeExtendedBy, FaviconSize, PostProcessCommand};
+use std::{collections::HashSet, path::PathBuf};
+
+#[derive(Default)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub(crate) struct FaviconProfileDto {
+    pub remote_id: Option<String>,
+    pub output_dir: Option<PathBuf>,
+    pub sizes: Option<Vec<FaviconSize>>,
+    pub webmanifest: Option<bool>,
+    pub variants: Option<VariantsDto>,
+    pub post_process: Option<PostProcessCommand>,
+    pub naming: Option<NamingRulesDto>,
+}
+
+impl CanBeExtendedBy<Self> for FaviconProfileDto {
+    fn extend(&self, another: &Self) -> Self {
+        Self {
+            remote_id: another
+                .remote_id
+                .as_ref()
+                .or(self.remote_id.as_ref())
+                .cloned(),
+            output_dir: another
+                .output_dir
+                .as_ref()
+                .or(self.output_dir.as_ref())
+                .cloned(),
+            sizes: another.sizes.as_ref().or(self.sizes.as_ref()).cloned(),
+            webmanifest: another.webmanifest.or(self.webmanifest),
+            variants: match (another.variants.as_ref(), self.variants.as_ref()) {
+                (Some(another), Some(this)) => Some(another.extend(this)),
+                (Some(another), None) => Some(another.clone()),
+                (None, Some(this)) => Some(this.clone()),
+                _ => None,
+            },
+            post_process: another
+                .post_process
+                .as_ref()
+                .or(self.post_process.as_ref())
+                .cloned(),
+            naming: match (self.naming.as_ref(), another.naming.as_ref()) {
+                (Some(this), Some(another)) => Some(this.extend(another)),
+                (this, another) => another.or(this).cloned(),
+            },
+        }
+    }
+}
+
+pub(crate) struct FaviconProfileDtoContext<'a> {
+    pub declared_remote_ids: &'a HashSet<String>,
+}
+
+mod de {
+    use super::*;
+    use crate::ParseWithContext;
+    use crate::parser::util::validate_remote_id;
+    use toml_span::de_helpers::TableHelper;
+
+    impl<'de> ParseWithContext<'de> for FaviconProfileDto {
+        type Context = FaviconProfileDtoContext<'de>;
+
+        fn parse_with_ctx(
+            value: &mut toml_span::Value<'de>,
+            ctx: Self::Context,
+        ) -> std::result::Result<Self, toml_span::DeserError> {
+            // region: extract
+            let mut th = TableHelper::new(value)?;
+            let remote_id = th.optional_s::<String>("remote");
+            let output_dir = th.optional::<String>("output_dir").map(PathBuf::from);
+            let sizes = th.optional::<Vec<FaviconSize>>("sizes");
+            let webmanifest = th.optional::<bool>("webmanifest");
+            let variants = th.optional::<VariantsDto>("variants");
+            let post_process = th.optional::<PostProcessCommand>("post_process");
+            let naming = th.optional::<NamingRulesDto>("naming");
+            th.finalize(None)?;
+            // endregion: extract
+
+            // region: validate
+            let remote_id = validate_remote_id(remote_id, ctx.declared_remote_ids)?;
+            // endregion: validate
+
+            Ok(Self {
+                remote_id,
+                output_dir,
+                sizes,
+                webmanifest,
+                variants,
+                post_process,
+                naming,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+
+    use super::*;
+    use crate::ParseWithContext;
+
+    #[test]
+    fn FaviconProfileDto__valid_fully_defined_toml__EXPECT__valid_dto() {
+        // Given
+        let toml = r#"
+        remote = "figma"
+        output_dir = "public"
+        sizes = [16, 32, 180]
+        webmanifest = false
+        "#;
+        let declared_remote_ids: HashSet<_> = ["figma".to_string()].into_iter().collect();
+        let expected_dto = FaviconProfileDto {
+            remote_id: Some("figma".to_string()),
+            output_dir: Some(PathBuf::from("public")),
+            sizes: Some(vec![FaviconSize(16), FaviconSize(32), FaviconSize(180)]),
+            webmanifest: Some(false),
+            variants: None,
+            post_process: None,
+            naming: None,
+        };
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let ctx = FaviconProfileDtoContext {
+            declared_remote_ids: &declared_remote_ids,
+        };
+        let actual_dto = FaviconProfileDto::parse_with_ctx(&mut value, ctx).unwrap();
+
+        // Then
+        assert_eq!(expected_dto, actual_dto);
+    }
+
+    #[test]
+    fn FaviconProfileDto__valid_empty_toml__EXPECT__valid_dto() {
+        // Given
+        let toml = r#"
+        "#;
+        let declared_remote_ids: HashSet<_> = ["figma".to_string()].into_iter().collect();
+        let expected_dto = FaviconProfileDto::default();
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let ctx = FaviconProfileDtoContext {
+            declared_remote_ids: &declared_remote_ids,
+        };
+        let actual_dto = FaviconProfileDto::parse_with_ctx(&mut value, ctx).unwrap();
+
+        // Then
+        assert_eq!(expected_dto, actual_dto);
+    }
+}
@@ -0,0 +1,59 @@
+mod de {
+    use toml_span::{Deserialize, ErrorKind};
+
+    use crate::FaviconSize;
+
+    impl<'de> Deserialize<'de> for FaviconSize {
+        fn deserialize(value: &mut toml_span::Value<'de>) -> Result<Self, toml_span::DeserError> {
+            match value.take() {
+                toml_span::value::ValueInner::Integer(size @ 1..=256) => {
+                    Ok(FaviconSize(size as u32))
+                }
+                _ => Err(toml_span::Error {
+                    kind: ErrorKind::Custom("favicon size must be an integer from 1 to 256".into()),
+                    span: value.span,
+                    line_info: None,
+                }
+                .into()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+
+    use toml_span::de_helpers::TableHelper;
+
+    use crate::FaviconSize;
+
+    #[test]
+    fn FaviconSize__valid_toml__EXPECT__valid_value() {
+        // Given
+        let toml = r#"
+        size1 = 16
+        size2 = 256
+        size3 = 0
+        size4 = 512
+        size5 = "32"
+        "#;
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let mut th = TableHelper::new(&mut value).unwrap();
+
+        // Then
+        assert_eq!(
+            FaviconSize(16),
+            th.required::<FaviconSize>("size1").unwrap()
+        );
+        assert_eq!(
+            FaviconSize(256),
+            th.required::<FaviconSize>("size2").unwrap()
+        );
+        assert!(th.required::<FaviconSize>("size3").is_err());
+        assert!(th.required::<FaviconSize>("size4").is_err());
+        assert!(th.required::<FaviconSize>("size5").is_err());
+    }
+}
@@ -0,0 +1,225 @@
+use std::{collections::HashSet, path::PathBuf};
+
+use super::NamingRulesDto;
+use crate::{CanBeExtendedBy, ExportScale, PngOptimizationLevel, PostProcessCommand};
+
+#[derive(Default)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub(crate) struct FlutterProfileDto {
+    pub remote_id: Option<String>,
+    pub assets_dir: Option<PathBuf>,
+    pub scales: Option<Vec<ExportScale>>,
+    pub dart_class: Option<String>,
+    pub dart_file: Option<PathBuf>,
+    pub legacy_loader: Option<bool>,
+    pub optimize: Option<bool>,
+    pub optimization_level: Option<PngOptimizationLevel>,
+    pub post_process: Option<PostProcessCommand>,
+    pub naming: Option<NamingRulesDto>,
+}
+
+impl CanBeExtendedBy<Self> for FlutterProfileDto {
+    fn extend(&self, another: &Self) -> Self {
+        Self {
+            remote_id: another
+                .remote_id
+                .as_ref()
+                .or(self.remote_id.as_ref())
+                .cloned(),
+            assets_dir: another
+                .assets_dir
+                .as_ref()
+                .or(self.assets_dir.as_ref())
+                .cloned(),
+            scales: another.scales.as_ref().or(self.scales.as_ref()).cloned(),
+            dart_class: another
+                .dart_class
+                .as_ref()
+                .or(self.dart_class.as_ref())
+                .cloned(),
+            dart_file: another
+                .dart_file
+                .as_ref()
+                .or(self.dart_file.as_ref())
+                .cloned(),
+            legacy_loader: another.legacy_loader.or(self.legacy_loader),
+            optimize: another.optimize.or(self.optimize),
+            optimization_level: another.optimization_level.or(self.optimization_level),
+            post_process: another
+                .post_process
+                .as_ref()
+                .or(self.post_process.as_ref())
+                .cloned(),
+            naming: match (self.naming.as_ref(), another.naming.as_ref()) {
+                (Some(this), Some(another)) => Some(this.extend(another)),
+                (this, another) => another.or(this).cloned(),
+            },
+        }
+    }
+}
+
+pub(crate) struct FlutterProfileDtoContext<'a> {
+    pub declared_remote_ids: &'a HashSet<String>,
+}
+
+mod de {
+    use super::*;
+    use crate::ParseWithContext;
+    use crate::parser::util::{validate_non_empty, validate_remote_id};
+    use toml_span::ErrorKind;
+    use toml_span::de_helpers::TableHelper;
+
+    impl<'de> ParseWithContext<'de> for FlutterProfileDto {
+        type Context = FlutterProfileDtoContext<'de>;
+
+        fn parse_with_ctx(
+            value: &mut toml_span::Value<'de>,
+            ctx: Self::Context,
+        ) -> std::result::Result<Self, toml_span::DeserError> {
+            // region: extract
+            let mut th = TableHelper::new(value)?;
+            let remote_id = th.optional_s::<String>("remote");
+            let assets_dir = th.optional::<String>("assets_dir").map(PathBuf::from);
+            let scales = th.optional_s::<Vec<ExportScale>>("scales");
+            let dart_class = th.optional_s::<String>("dart_class");
+            let dart_file = th.optional::<String>("dart_file").map(PathBuf::from);
+            let legacy_loader = th.optional::<bool>("legacy_loader");
+            let optimize = th.optional::<bool>("optimize");
+            let optimization_level = th.optional::<PngOptimizationLevel>("optimization_level");
+            let post_process = th.optional::<PostProcessCommand>("post_process");
+            let naming = th.optional::<NamingRulesDto>("naming");
+            th.finalize(None)?;
+            // endregion: extract
+
+            // region: validate
+            let remote_id = validate_remote_id(remote_id, ctx.declared_remote_ids)?;
+            let scales = validate_non_empty(scales, || "scales list cannot be empty".to_string())?;
+            let invalid_class = dart_class
+                .as_ref()
+                .filter(|it| !is_dart_identifier(&it.value));
+            if let Some(class) = invalid_class {
+                return Err(toml_span::Error::from((
+                    ErrorKind::Custom("dart class name should be a valid identifier".into()),
+                    class.span,
+                ))
+                .into());
+            }
+            // endregion: validate
+
+            Ok(Self {
+                remote_id,
+                assets_dir,
+                scales,
+                dart_class: dart_class.map(|it| it.value),
+                dart_file,
+                legacy_loader,
+                optimize,
+                optimization_level,
+                post_process,
+                naming,
+            })
+        }
+    }
+
+    fn is_dart_identifier(name: &str) -> bool {
+        let mut chars = name.chars();
+        match chars.next() {
+            Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '$' => (),
+            _ => return false,
+        }
+        chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+
+    use super::*;
+    use crate::ParseWithContext;
+    use toml_span::Span;
+    use unindent::unindent;
+
+    #[test]
+    fn FlutterProfileDto__valid_fully_defined_toml__EXPECT__valid_dto() {
+        // Given
+        let toml = r#"
+        remote = "figma"
+        assets_dir = "assets/icons"
+        scales = [1, 2, 3]
+        dart_class = "AppIcons"
+        dart_file = "lib/gen/app_icons.dart"
+        legacy_loader = false
+        optimize = true
+        optimization_level = 3
+        "#;
+        let declared_remote_ids: HashSet<_> = ["figma".to_string()].into_iter().collect();
+        let expected_dto = FlutterProfileDto {
+            remote_id: Some("figma".to_string()),
+            assets_dir: Some(PathBuf::from("assets/icons")),
+            scales: Some(vec![ExportScale(1.0), ExportScale(2.0), ExportScale(3.0)]),
+            dart_class: Some("AppIcons".to_string()),
+            dart_file: Some(PathBuf::from("lib/gen/app_icons.dart")),
+            legacy_loader: Some(false),
+            optimize: Some(true),
+            optimization_level: Some(PngOptimizationLevel(3)),
+            post_process: None,
+            naming: None,
+        };
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let ctx = FlutterProfileDtoContext {
+            declared_remote_ids: &declared_remote_ids,
+        };
+        let actual_dto = FlutterProfileDto::parse_with_ctx(&mut value, ctx).unwrap();
+
+        // Then
+        assert_eq!(expected_dto, actual_dto);
+    }
+
+    #[test]
+    fn FlutterProfileDto__valid_empty_toml__EXPECT__valid_dto() {
+        // Given
+        let toml = r#"
+        "#;
+        let declared_remote_ids: HashSet<_> = ["figma".to_string()].into_iter().collect();
+        let expected_dto = FlutterProfileDto::default();
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let ctx = FlutterProfileDtoContext {
+            declared_remote_ids: &declared_remote_ids,
+        };
+        let actual_dto = FlutterProfileDto::parse_with_ctx(&mut value, ctx).unwrap();
+
+        // Then
+        assert_eq!(expected_dto, actual_dto);
+    }
+
+    #[test]
+    fn FlutterProfileDto__invalid_dart_class__EXPECT__error_with_correct_span() {
+        // Given
+        let toml = unindent(
+            r#"
+                remote = "figma"
+                dart_class = "App-Icons"
+            "#,
+        );
+        let declared_remote_ids: HashSet<_> = ["figma".to_string()].into_iter().collect();
+        let err_spans = [Span::new(31, 40)];
+
+        // When
+        let mut value = toml_span::parse(&toml).unwrap();
+        let ctx = FlutterProfileDtoContext {
+            declared_remote_ids: &declared_remote_ids,
+        };
+        let actual_err = FlutterProfileDto::parse_with_ctx(&mut value, ctx).unwrap_err();
+
+        // Then
+        assert_eq!(err_spans.len(), actual_err.errors.len());
+        for (expected_span, actual_err) in err_spans.into_iter().zip(actual_err.errors) {
+            assert_eq!(expected_span, actual_err.span);
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use super::{ProfilesDto, ProfilesDtoContext};
+use std::{collections::HashSet, path::PathBuf};
+use toml_span::Spanned;
+
+/// Files listed in `include` of the workspace file
+#[derive(Default)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub(crate) struct IncludeDto {
+    /// Paths relative to the workspace root, in order of inclusion
+    pub files: Vec<Spanned<PathBuf>>,
+    /// Remotes declared in the workspace file, the included profiles may refer to them
+    pub declared_remote_ids: HashSet<String>,
+}
+
+/// Content of an included file, which can only share profiles
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub(crate) struct IncludedFileDto {
+    pub profiles: ProfilesDto,
+}
+
+mod de {
+    use super::*;
+    use crate::ParseWithContext;
+    use toml_span::{ErrorKind, de_helpers::TableHelper};
+
+    impl<'de> ParseWithContext<'de> for IncludeDto {
+        type Context = ();
+
+        /// Takes `include` out of the workspace file, leaving the rest of it untouched
+        fn parse_with_ctx(
+            value: &mut toml_span::Value<'de>,
+            _ctx: Self::Context,
+        ) -> std::result::Result<Self, toml_span::DeserError> {
+            // region: extract
+            let mut th = TableHelper::new(value)?;
+            let files = th
+                .optional::<Vec<Spanned<String>>>("include")
+                .unwrap_or_default();
+            let declared_remote_ids = match th.table.get("remotes").and_then(|it| it.as_table()) {
+                Some(remotes) => remotes
+                    .iter()
+                    .map(|(key, _)| key.name.to_string())
+                    .collect(),
+                None => HashSet::new(),
+            };
+            th.finalize(Some(value))?;
+            // endregion: extract
+
+            // region: validate
+            for (i, file) in files.iter().enumerate() {
+                if file.value.is_empty() {
+                    return Err(toml_span::Error::from((
+                        ErrorKind::Custom("path of the included file cannot be empty".into()),
+                        file.span,
+                    ))
+                    .into());
+                }
+                if files[..i].iter().any(|it| it.value == file.value) {
+                    return Err(toml_span::Error::from((
+                        ErrorKind::Custom("file is already included".into()),
+                        file.span,
+                    ))
+                    .into());
+                }
+            }
+            // endregion: validate
+
+            Ok(Self {
+                files: files
+                    .into_iter()
+                    .map(|it| Spanned::with_span(PathBuf::from(it.value), it.span))
+                    .collect(),
+                declared_remote_ids,
+            })
+        }
+    }
+
+    impl<'de> ParseWithContext<'de> for IncludedFileDto {
+        type Context = ProfilesDtoContext<'de>;
+
+        fn parse_with_ctx(
+            value: &mut toml_span::Value<'de>,
+            ctx: Self::Context,
+        ) -> std::result::Result<Self, toml_span::DeserError> {
+            // region: extract
+            let mut th = TableHelper::new(value)?;
+            let profiles = th.take("profiles");
+            th.finalize(None)?;
+            // endregion: extract
+
+            // region: validate
+            let profiles = match profiles {
+                Some((_, mut value)) => ProfilesDto::parse_with_ctx(&mut value, ctx)?,
+                None => {
+                    return Err(toml_span::Error::from((
+                        ErrorKind::Custom("included file should define `profiles`".into()),
+                        value.span,
+                    ))
+                    .into());
+                }
+            };
+            // endregion: validate
+
+            Ok(Self { profiles })
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use crate::ParseWithContext;
+    use toml_span::Span;
+    use unindent::unindent;
+
+    #[test]
+    fn IncludeDto__workspace_with_include__EXPECT__include_taken_out() {
+        // Given
+        let toml = unindent(
+            r#"
+                include = ["tools/figx/common-profiles.toml"]
+
+                [remotes.design]
+                file_key = "abcdef"
+                container_node_ids = ["1:2"]
+            "#,
+        );
+
+        // When
+        let mut value = toml_span::parse(&toml).unwrap();
+        let dto = IncludeDto::parse_with_ctx(&mut value, ()).unwrap();
+
+        // Then
+        assert_eq!(
+            vec![PathBuf::from("tools/figx/common-profiles.toml")],
+            dto.files.into_iter().map(|it| it.value).collect::<Vec<_>>(),
+        );
+        assert!(dto.declared_remote_ids.contains("design"));
+        let table = value.as_table().unwrap();
+        assert!(table.get("include").is_none());
+        assert!(table.get("remotes").is_some());
+    }
+
+    #[test]
+    fn IncludeDto__same_file_twice__EXPECT__error_with_correct_span() {
+        // Given
+        let toml = r#"include = ["a.toml", "a.toml"]"#;
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let err = IncludeDto::parse_with_ctx(&mut value, ()).unwrap_err();
+
+        // Then
+        assert_eq!(Span::new(22, 28), err.errors[0].span);
+    }
+}
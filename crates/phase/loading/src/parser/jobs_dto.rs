@@ -0,0 +1,127 @@
+use crate::CanBeExtendedBy;
+
+#[derive(Default)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub(crate) struct JobsDto {
+    /// Number of requests to the Figma API in flight
+    pub network: Option<usize>,
+    /// Number of targets transformed in parallel
+    pub cpu: Option<usize>,
+}
+
+impl CanBeExtendedBy<Self> for JobsDto {
+    fn extend(&self, another: &Self) -> Self {
+        Self {
+            network: another.network.or(self.network),
+            cpu: another.cpu.or(self.cpu),
+        }
+    }
+}
+
+mod de {
+    use super::*;
+    use crate::ParseWithContext;
+    use toml_span::{ErrorKind, Spanned, de_helpers::TableHelper};
+
+    impl<'de> ParseWithContext<'de> for JobsDto {
+        type Context = ();
+
+        fn parse_with_ctx(
+            value: &mut toml_span::Value<'de>,
+            _ctx: Self::Context,
+        ) -> std::result::Result<Self, toml_span::DeserError> {
+            // region: extract
+            let mut th = TableHelper::new(value)?;
+            let network = th.optional_s::<usize>("network");
+            let cpu = th.optional_s::<usize>("cpu");
+            th.finalize(None)?;
+            // endregion: extract
+
+            // region: validate
+            let network = validate_jobs("network", network)?;
+            let cpu = validate_jobs("cpu", cpu)?;
+            // endregion: validate
+
+            Ok(Self { network, cpu })
+        }
+    }
+
+    fn validate_jobs(
+        key: &str,
+        jobs: Option<Spanned<usize>>,
+    ) -> std::result::Result<Option<usize>, toml_span::DeserError> {
+        match jobs {
+            Some(jobs) if jobs.value == 0 => Err(toml_span::Error::from((
+                ErrorKind::Custom(format!("{key} must be greater than 0").into()),
+                jobs.span,
+            ))
+            .into()),
+            jobs => Ok(jobs.map(|it| it.value)),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use crate::ParseWithContext;
+
+    #[test]
+    fn JobsDto__parse_both_lanes__EXPECT__valid_dto() {
+        // Given
+        let toml = r#"
+            network = 32
+            cpu = 4
+        "#;
+        let expected_dto = JobsDto {
+            network: Some(32),
+            cpu: Some(4),
+        };
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let actual_dto = JobsDto::parse_with_ctx(&mut value, ()).unwrap();
+
+        // Then
+        assert_eq!(expected_dto, actual_dto);
+    }
+
+    #[test]
+    fn JobsDto__zero_jobs__EXPECT__error() {
+        // Given
+        let toml = r#"network = 0"#;
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let result = JobsDto::parse_with_ctx(&mut value, ());
+
+        // Then
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn JobsDto__extend_with_local__EXPECT__local_values_take_precedence() {
+        // Given
+        let workspace = JobsDto {
+            network: Some(32),
+            cpu: Some(4),
+        };
+        let local = JobsDto {
+            network: None,
+            cpu: Some(2),
+        };
+
+        // When
+        let actual_dto = workspace.extend(&local);
+
+        // Then
+        assert_eq!(
+            JobsDto {
+                network: Some(32),
+                cpu: Some(2),
+            },
+            actual_dto
+        );
+    }
+}
@@ -0,0 +1,263 @@
+use super::{
+    BUILTIN_PROFILE_IDS, JobsDto, MetricsDto, NetworkDto, OutputDto, ProfileDto, ProfilesDto,
+    RemoteOverrideDto,
+};
+use ordermap::OrderMap;
+use std::collections::{HashMap, HashSet};
+
+/// Content of `.figtree.local.toml`, which overrides the settings of the workspace file
+/// for a single developer
+#[derive(Default)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub(crate) struct LocalWorkspaceDto {
+    pub remotes: OrderMap<String, RemoteOverrideDto>,
+    /// Overrides of the declared profiles, each one parsed as the kind of the profile it overrides
+    pub profiles: OrderMap<String, ProfileDto>,
+    pub metrics: MetricsDto,
+    pub output: OutputDto,
+    pub network: NetworkDto,
+    pub jobs: JobsDto,
+}
+
+pub(crate) struct LocalWorkspaceDtoContext<'a> {
+    pub declared_remote_ids: &'a HashSet<String>,
+    /// Ids of the declared profiles mapped to the ids of the built-in profiles they extend
+    pub declared_profile_kinds: &'a HashMap<String, &'static str>,
+}
+
+/// Finds the kinds of the profiles declared in the workspace file without parsing them,
+/// because the overrides are parsed before the workspace file itself
+pub(crate) fn peek_declared_profile_kinds(
+    ws_value: &toml_span::Value<'_>,
+    included: Option<&ProfilesDto>,
+) -> HashMap<String, &'static str> {
+    let mut kinds = HashMap::new();
+    if let Some(included) = included {
+        for (id, profile) in included.0.iter() {
+            kinds.insert(id.clone(), profile.builtin_id());
+        }
+    }
+    let profiles = ws_value
+        .as_table()
+        .and_then(|it| it.get("profiles"))
+        .and_then(|it| it.as_table());
+    let Some(profiles) = profiles else {
+        return kinds;
+    };
+    // built-in profiles exist as soon as there is a `profiles` table
+    for id in BUILTIN_PROFILE_IDS {
+        kinds.insert(id.to_string(), *id);
+    }
+    let parents = profiles
+        .iter()
+        .filter_map(|(key, value)| {
+            let extends = value.as_table()?.get("extends")?.as_str()?;
+            Some((key.name.as_ref(), extends))
+        })
+        .collect::<HashMap<_, _>>();
+    for &profile_id in parents.keys() {
+        // unknown parents and cycles are reported when the workspace file is parsed,
+        // so the chain is followed no longer than the number of profiles
+        let mut parent = parents[profile_id];
+        for _ in 0..parents.len() {
+            if let Some(&kind) = kinds.get(parent) {
+                kinds.insert(profile_id.to_string(), kind);
+                break;
+            }
+            match parents.get(parent) {
+                Some(&grandparent) => parent = grandparent,
+                None => break,
+            }
+        }
+    }
+    kinds
+}
+
+mod de {
+    use super::*;
+    use crate::{ParseWithContext, parser::ProfilesDtoContext};
+    use toml_span::{ErrorKind, de_helpers::TableHelper};
+
+    impl<'de> ParseWithContext<'de> for LocalWorkspaceDto {
+        type Context = LocalWorkspaceDtoContext<'de>;
+
+        fn parse_with_ctx(
+            value: &mut toml_span::Value<'de>,
+            ctx: Self::Context,
+        ) -> std::result::Result<Self, toml_span::DeserError> {
+            // region: extract
+            let mut th = TableHelper::new(value)?;
+            let remotes = th.take("remotes");
+            let profiles = th.take("profiles");
+            let metrics = th.take("metrics");
+            let output = th.take("output");
+            let network = th.take("network");
+            let jobs = th.take("jobs");
+            th.finalize(None)?;
+            // endregion: extract
+
+            // region: validate
+            let mut remote_overrides = OrderMap::new();
+            if let Some((_, mut value)) = remotes {
+                let mut th = TableHelper::new(&mut value)?;
+                for (key, value) in th.table.iter_mut() {
+                    if !ctx.declared_remote_ids.contains(key.name.as_ref()) {
+                        return Err(toml_span::Error::from((
+                            ErrorKind::Custom(
+                                format!("remote `{}` is not declared in the workspace", key.name)
+                                    .into(),
+                            ),
+                            key.span,
+                        ))
+                        .into());
+                    }
+                    let remote = RemoteOverrideDto::parse_with_ctx(value, ())?;
+                    remote_overrides.insert(key.to_string(), remote);
+                }
+                th.finalize(Some(&mut value))?;
+            }
+
+            let mut profile_overrides = OrderMap::new();
+            if let Some((_, mut value)) = profiles {
+                let mut th = TableHelper::new(&mut value)?;
+                for (key, value) in th.table.iter_mut() {
+                    let Some(kind) = ctx.declared_profile_kinds.get(key.name.as_ref()) else {
+                        return Err(toml_span::Error::from((
+                            ErrorKind::Custom(
+                                format!("profile `{}` is not declared in the workspace", key.name)
+                                    .into(),
+                            ),
+                            key.span,
+                        ))
+                        .into());
+                    };
+                    let profile_ctx = ProfilesDtoContext {
+                        declared_remote_ids: ctx.declared_remote_ids,
+                        included: None,
+                        overrides: None,
+                    };
+                    let profile = ProfileDto::parse_as(kind, value, profile_ctx)?
+                        .expect("kinds are the ids of the built-in profiles");
+                    profile_overrides.insert(key.to_string(), profile);
+                }
+                th.finalize(Some(&mut value))?;
+            }
+
+            let metrics = match metrics {
+                Some((_, mut value)) => MetricsDto::parse_with_ctx(&mut value, ())?,
+                None => MetricsDto::default(),
+            };
+            let output = match output {
+                Some((_, mut value)) => OutputDto::parse_with_ctx(&mut value, ())?,
+                None => OutputDto::default(),
+            };
+            let network = match network {
+                Some((_, mut value)) => NetworkDto::parse_with_ctx(&mut value, ())?,
+                None => NetworkDto::default(),
+            };
+            let jobs = match jobs {
+                Some((_, mut value)) => JobsDto::parse_with_ctx(&mut value, ())?,
+                None => JobsDto::default(),
+            };
+            // endregion: validate
+
+            Ok(Self {
+                remotes: remote_overrides,
+                profiles: profile_overrides,
+                metrics,
+                output,
+                network,
+                jobs,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use crate::{
+        ParseWithContext,
+        parser::{AccessTokenDefinitionDto, PngProfileDto},
+    };
+    use std::path::PathBuf;
+    use toml_span::Span;
+    use unindent::unindent;
+
+    fn declared() -> (HashSet<String>, HashMap<String, &'static str>) {
+        let remote_ids = ["design".to_string()].into_iter().collect();
+        let profile_kinds = [
+            ("png".to_string(), "png"),
+            ("illustrations".to_string(), "png"),
+        ]
+        .into_iter()
+        .collect();
+        (remote_ids, profile_kinds)
+    }
+
+    #[test]
+    fn LocalWorkspaceDto__token_and_output_dir__EXPECT__overrides_of_declared_kinds() {
+        // Given
+        let toml = unindent(
+            r#"
+                [remotes.design]
+                access_token = { env = "MY_FIGMA_TOKEN" }
+
+                [profiles.illustrations]
+                output_dir = "tmp/illustrations"
+            "#,
+        );
+        let (declared_remote_ids, declared_profile_kinds) = declared();
+
+        // When
+        let mut value = toml_span::parse(&toml).unwrap();
+        let dto = LocalWorkspaceDto::parse_with_ctx(
+            &mut value,
+            LocalWorkspaceDtoContext {
+                declared_remote_ids: &declared_remote_ids,
+                declared_profile_kinds: &declared_profile_kinds,
+            },
+        )
+        .unwrap();
+
+        // Then
+        assert_eq!(
+            Some(&AccessTokenDefinitionDto::Env("MY_FIGMA_TOKEN".to_string())),
+            dto.remotes["design"].access_token.as_ref(),
+        );
+        assert_eq!(
+            Some(&ProfileDto::Png(PngProfileDto {
+                output_dir: Some(PathBuf::from("tmp/illustrations")),
+                ..Default::default()
+            })),
+            dto.profiles.get("illustrations"),
+        );
+    }
+
+    #[test]
+    fn LocalWorkspaceDto__undeclared_profile__EXPECT__error_with_correct_span() {
+        // Given
+        let toml = unindent(
+            r#"
+                [profiles.icons]
+                output_dir = "tmp/icons"
+            "#,
+        );
+        let (declared_remote_ids, declared_profile_kinds) = declared();
+
+        // When
+        let mut value = toml_span::parse(&toml).unwrap();
+        let err = LocalWorkspaceDto::parse_with_ctx(
+            &mut value,
+            LocalWorkspaceDtoContext {
+                declared_remote_ids: &declared_remote_ids,
+                declared_profile_kinds: &declared_profile_kinds,
+            },
+        )
+        .unwrap_err();
+
+        // Then
+        assert_eq!(Span::new(10, 15), err.errors[0].span);
+    }
+}
@@ -0,0 +1,99 @@
+use crate::CanBeExtendedBy;
+
+#[derive(Default)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub(crate) struct MetricsDto {
+    /// Base url of the OpenTelemetry collector, e.g. `http://localhost:4318`
+    pub otlp_endpoint: Option<String>,
+}
+
+impl CanBeExtendedBy<Self> for MetricsDto {
+    fn extend(&self, another: &Self) -> Self {
+        Self {
+            otlp_endpoint: another
+                .otlp_endpoint
+                .as_ref()
+                .or(self.otlp_endpoint.as_ref())
+                .cloned(),
+        }
+    }
+}
+
+mod de {
+    use super::*;
+    use crate::ParseWithContext;
+    use toml_span::{ErrorKind, de_helpers::TableHelper};
+
+    impl<'de> ParseWithContext<'de> for MetricsDto {
+        type Context = ();
+
+        fn parse_with_ctx(
+            value: &mut toml_span::Value<'de>,
+            _ctx: Self::Context,
+        ) -> std::result::Result<Self, toml_span::DeserError> {
+            // region: extract
+            let mut th = TableHelper::new(value)?;
+            let otlp_endpoint = th.optional_s::<String>("otlp_endpoint");
+            th.finalize(None)?;
+            // endregion: extract
+
+            // region: validate
+            if let Some(endpoint) = &otlp_endpoint {
+                let is_http =
+                    endpoint.value.starts_with("http://") || endpoint.value.starts_with("https://");
+                if !is_http {
+                    return Err(toml_span::Error::from((
+                        ErrorKind::Custom("otlp endpoint should be an http(s) url".into()),
+                        endpoint.span,
+                    ))
+                    .into());
+                }
+            }
+            // endregion: validate
+
+            Ok(Self {
+                otlp_endpoint: otlp_endpoint.map(|it| it.value),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use crate::ParseWithContext;
+    use toml_span::Span;
+
+    #[test]
+    fn MetricsDto__parse_valid_endpoint__EXPECT__valid_dto() {
+        // Given
+        let toml = r#"otlp_endpoint = "http://localhost:4318""#;
+        let expected_dto = MetricsDto {
+            otlp_endpoint: Some("http://localhost:4318".to_owned()),
+        };
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let actual_dto = MetricsDto::parse_with_ctx(&mut value, ()).unwrap();
+
+        // Then
+        assert_eq!(expected_dto, actual_dto);
+    }
+
+    #[test]
+    fn MetricsDto__parse_endpoint_wo_scheme__EXPECT__error_with_correct_span() {
+        // Given
+        let toml = r#"otlp_endpoint = "localhost:4318""#;
+        let expected_spans = [Span::new(17, 31)];
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let actual_err = MetricsDto::parse_with_ctx(&mut value, ()).unwrap_err();
+
+        // Then
+        for (err, expected_span) in actual_err.errors.iter().zip(expected_spans) {
+            assert_eq!(expected_span, err.span);
+        }
+    }
+}
@@ -1,18 +1,39 @@
 mod access_token_definition;
 mod android_drawable_profile_dto;
 mod android_webp_profile_dto;
+mod bulk_dto;
 mod compose_profile_dto;
+mod env_interpolation;
+mod expected_dimensions_dto;
 mod export_scale;
+mod externals_dto;
+mod favicon_profile_dto;
+mod favicon_size;
+mod flutter_profile_dto;
+mod include_dto;
+mod jobs_dto;
+mod local_workspace_dto;
+mod metrics_dto;
+mod naming_rules_dto;
+mod network_dto;
 mod node_id_list_dto;
+mod output_dto;
+mod output_link_mode;
 mod pdf_profile_dto;
+mod png_optimization_level;
 mod png_profile_dto;
+mod post_process_command;
 mod profiles_dto;
+mod react_profile_dto;
 mod remotes_dto;
 mod resources_dto;
+mod retry_policy_dto;
 mod single_name_pattern;
 mod svg_profile_dto;
 mod util;
 mod variants_dto;
+mod vector_limits_dto;
+mod webp_method;
 mod webp_profile_dto;
 mod webp_quality;
 mod workspace_dto;
@@ -20,14 +41,30 @@ mod workspace_dto;
 pub(crate) use access_token_definition::*;
 pub(crate) use android_drawable_profile_dto::*;
 pub(crate) use android_webp_profile_dto::*;
+pub(crate) use bulk_dto::*;
 pub(crate) use compose_profile_dto::*;
+pub(crate) use env_interpolation::*;
+pub(crate) use expected_dimensions_dto::*;
+pub(crate) use externals_dto::*;
+pub(crate) use favicon_profile_dto::*;
+pub(crate) use flutter_profile_dto::*;
+pub(crate) use include_dto::*;
+pub(crate) use jobs_dto::*;
+pub(crate) use local_workspace_dto::*;
+pub(crate) use metrics_dto::*;
+pub(crate) use naming_rules_dto::*;
+pub(crate) use network_dto::*;
 pub(crate) use node_id_list_dto::*;
+pub(crate) use output_dto::*;
 pub(crate) use pdf_profile_dto::*;
 pub(crate) use png_profile_dto::*;
 pub(crate) use profiles_dto::*;
+pub(crate) use react_profile_dto::*;
 pub(crate) use remotes_dto::*;
 pub(crate) use resources_dto::*;
+pub(crate) use retry_policy_dto::*;
 pub(crate) use svg_profile_dto::*;
 pub(crate) use variants_dto::*;
+pub(crate) use vector_limits_dto::*;
 pub(crate) use webp_profile_dto::*;
 pub(crate) use workspace_dto::*;
@@ -0,0 +1,157 @@
+use crate::{CanBeExtendedBy, NameCase};
+
+#[derive(Clone, Default, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub(crate) struct NamingRulesDto {
+    pub strip: Option<Vec<String>>,
+    pub case: Option<NameCase>,
+    pub prefix: Option<String>,
+    pub suffix: Option<String>,
+}
+
+impl CanBeExtendedBy<NamingRulesDto> for NamingRulesDto {
+    fn extend(&self, another: &NamingRulesDto) -> Self {
+        Self {
+            strip: another.strip.as_ref().or(self.strip.as_ref()).cloned(),
+            case: another.case.or(self.case),
+            prefix: another.prefix.as_ref().or(self.prefix.as_ref()).cloned(),
+            suffix: another.suffix.as_ref().or(self.suffix.as_ref()).cloned(),
+        }
+    }
+}
+
+mod de {
+    use super::*;
+    use toml_span::{Deserialize, ErrorKind, Spanned, de_helpers::TableHelper, value::ValueInner};
+
+    impl<'de> Deserialize<'de> for NamingRulesDto {
+        fn deserialize(value: &mut toml_span::Value<'de>) -> Result<Self, toml_span::DeserError> {
+            // region: extract
+            let mut th = TableHelper::new(value)?;
+            let strip = th.optional::<Vec<Spanned<String>>>("strip");
+            let case = th.optional::<NameCase>("case");
+            let prefix = th.optional_s::<String>("prefix");
+            let suffix = th.optional_s::<String>("suffix");
+            th.finalize(None)?;
+            // endregion: extract
+
+            // region: validate
+            for pattern in strip.iter().flatten() {
+                if pattern.value.is_empty() {
+                    return Err(toml_span::Error::from((
+                        ErrorKind::Custom("strip pattern cannot be empty".into()),
+                        pattern.span,
+                    ))
+                    .into());
+                }
+            }
+            // names become file names, and the output directories are set by the profiles
+            for affix in prefix.iter().chain(suffix.iter()) {
+                if affix.value.contains(['/', '\\']) {
+                    return Err(toml_span::Error::from((
+                        ErrorKind::Custom("path separators are not allowed in names".into()),
+                        affix.span,
+                    ))
+                    .into());
+                }
+            }
+            // endregion: validate
+
+            Ok(Self {
+                strip: strip.map(|it| it.into_iter().map(|it| it.value).collect()),
+                case,
+                prefix: prefix.map(|it| it.value),
+                suffix: suffix.map(|it| it.value),
+            })
+        }
+    }
+
+    impl<'de> Deserialize<'de> for NameCase {
+        fn deserialize(value: &mut toml_span::Value<'de>) -> Result<Self, toml_span::DeserError> {
+            match value.take() {
+                ValueInner::String(case) if case == "snake" || case == "snake_case" => {
+                    Ok(NameCase::Snake)
+                }
+                ValueInner::String(case) if case == "kebab" || case == "kebab-case" => {
+                    Ok(NameCase::Kebab)
+                }
+                ValueInner::String(case) if case == "camel" || case == "camelCase" => {
+                    Ok(NameCase::Camel)
+                }
+                ValueInner::String(case) if case == "pascal" || case == "PascalCase" => {
+                    Ok(NameCase::Pascal)
+                }
+                _ => Err(toml_span::Error {
+                    kind: ErrorKind::Custom(
+                        "case must be `snake`, `kebab`, `camel` or `pascal`".into(),
+                    ),
+                    span: value.span,
+                    line_info: None,
+                }
+                .into()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use toml_span::{Span, de_helpers::TableHelper};
+
+    #[test]
+    fn NamingRulesDto__valid_toml__EXPECT__valid_dto() {
+        // Given
+        let toml = r#"naming = { case = "snake", prefix = "ic_", strip = ["Icon"] }"#;
+        let expected_dto = NamingRulesDto {
+            strip: Some(vec!["Icon".to_string()]),
+            case: Some(NameCase::Snake),
+            prefix: Some("ic_".to_string()),
+            suffix: None,
+        };
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let mut th = TableHelper::new(&mut value).unwrap();
+
+        // Then
+        assert_eq!(
+            expected_dto,
+            th.required::<NamingRulesDto>("naming").unwrap()
+        );
+    }
+
+    #[test]
+    fn NamingRulesDto__path_separator_in_prefix__EXPECT__error_with_correct_span() {
+        // Given
+        let toml = r#"naming = { prefix = "icons/" }"#;
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let mut th = TableHelper::new(&mut value).unwrap();
+        let err = th.required::<NamingRulesDto>("naming").unwrap_err();
+
+        // Then
+        assert_eq!(Span::new(21, 27), err.span);
+    }
+
+    #[test]
+    fn NameCase__both_spellings__EXPECT__same_case() {
+        // Given
+        let toml = r#"
+        case1 = "kebab"
+        case2 = "kebab-case"
+        case3 = "KEBAB"
+        "#;
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let mut th = TableHelper::new(&mut value).unwrap();
+
+        // Then
+        assert_eq!(NameCase::Kebab, th.required::<NameCase>("case1").unwrap());
+        assert_eq!(NameCase::Kebab, th.required::<NameCase>("case2").unwrap());
+        assert!(th.required::<NameCase>("case3").is_err());
+    }
+}
@@ -0,0 +1,80 @@
+use crate::CanBeExtendedBy;
+
+#[derive(Default)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub(crate) struct NetworkDto {
+    /// Path to the PEM bundle of trusted root certificates, relative to the workspace
+    pub ca_bundle: Option<String>,
+}
+
+impl CanBeExtendedBy<Self> for NetworkDto {
+    fn extend(&self, another: &Self) -> Self {
+        Self {
+            ca_bundle: another
+                .ca_bundle
+                .as_ref()
+                .or(self.ca_bundle.as_ref())
+                .cloned(),
+        }
+    }
+}
+
+mod de {
+    use super::*;
+    use crate::ParseWithContext;
+    use toml_span::{ErrorKind, de_helpers::TableHelper};
+
+    impl<'de> ParseWithContext<'de> for NetworkDto {
+        type Context = ();
+
+        fn parse_with_ctx(
+            value: &mut toml_span::Value<'de>,
+            _ctx: Self::Context,
+        ) -> std::result::Result<Self, toml_span::DeserError> {
+            // region: extract
+            let mut th = TableHelper::new(value)?;
+            let ca_bundle = th.optional_s::<String>("ca_bundle");
+            th.finalize(None)?;
+            // endregion: extract
+
+            // region: validate
+            if let Some(path) = &ca_bundle {
+                if path.value.trim().is_empty() {
+                    return Err(toml_span::Error::from((
+                        ErrorKind::Custom("ca_bundle cannot be empty".into()),
+                        path.span,
+                    ))
+                    .into());
+                }
+            }
+            // endregion: validate
+
+            Ok(Self {
+                ca_bundle: ca_bundle.map(|it| it.value),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use crate::ParseWithContext;
+
+    #[test]
+    fn NetworkDto__parse_ca_bundle__EXPECT__valid_dto() {
+        // Given
+        let toml = r#"ca_bundle = "certs/corporate.pem""#;
+        let expected_dto = NetworkDto {
+            ca_bundle: Some("certs/corporate.pem".to_owned()),
+        };
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let actual_dto = NetworkDto::parse_with_ctx(&mut value, ()).unwrap();
+
+        // Then
+        assert_eq!(expected_dto, actual_dto);
+    }
+}
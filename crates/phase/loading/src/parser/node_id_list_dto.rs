@@ -1,5 +1,6 @@
 use std::collections::BTreeMap;
 
+#[derive(Clone)]
 #[cfg_attr(test, derive(PartialEq, Debug))]
 pub enum NodeIdListDto {
     Plain(Vec<String>),
@@ -0,0 +1,144 @@
+use crate::{CanBeExtendedBy, OutputLinkMode};
+
+#[derive(Default)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub(crate) struct OutputDto {
+    /// Write outputs to the local cache directory first and copy them to the workspace at the end
+    pub staging: Option<bool>,
+    pub content_addressed: Option<OutputLinkMode>,
+    /// Directory to write the outputs to instead of the packages, relative to the workspace
+    pub base: Option<String>,
+    /// Stamp the generated files with the resource and the node they come from
+    pub provenance: Option<bool>,
+}
+
+impl CanBeExtendedBy<Self> for OutputDto {
+    fn extend(&self, another: &Self) -> Self {
+        Self {
+            staging: another.staging.or(self.staging),
+            content_addressed: another.content_addressed.or(self.content_addressed),
+            base: another.base.as_ref().or(self.base.as_ref()).cloned(),
+            provenance: another.provenance.or(self.provenance),
+        }
+    }
+}
+
+mod de {
+    use super::*;
+    use crate::ParseWithContext;
+    use toml_span::{ErrorKind, de_helpers::TableHelper};
+
+    impl<'de> ParseWithContext<'de> for OutputDto {
+        type Context = ();
+
+        fn parse_with_ctx(
+            value: &mut toml_span::Value<'de>,
+            _ctx: Self::Context,
+        ) -> std::result::Result<Self, toml_span::DeserError> {
+            // region: extract
+            let mut th = TableHelper::new(value)?;
+            let staging = th.optional::<bool>("staging");
+            let content_addressed = th.optional::<OutputLinkMode>("content_addressed");
+            let base = th.optional_s::<String>("base");
+            let provenance = th.optional::<bool>("provenance");
+            th.finalize(None)?;
+            // endregion: extract
+
+            // region: validate
+            if let Some(base) = &base {
+                if base.value.trim().is_empty() {
+                    return Err(toml_span::Error::from((
+                        ErrorKind::Custom("base cannot be empty".into()),
+                        base.span,
+                    ))
+                    .into());
+                }
+            }
+            // endregion: validate
+
+            Ok(Self {
+                staging,
+                content_addressed,
+                base: base.map(|it| it.value),
+                provenance,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use crate::ParseWithContext;
+
+    #[test]
+    fn OutputDto__parse_staging__EXPECT__valid_dto() {
+        // Given
+        let toml = r#"staging = true"#;
+        let expected_dto = OutputDto {
+            staging: Some(true),
+            content_addressed: None,
+            base: None,
+            provenance: None,
+        };
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let actual_dto = OutputDto::parse_with_ctx(&mut value, ()).unwrap();
+
+        // Then
+        assert_eq!(expected_dto, actual_dto);
+    }
+
+    #[test]
+    fn OutputDto__parse_content_addressed__EXPECT__valid_dto() {
+        // Given
+        let toml = r#"content_addressed = "symlink""#;
+        let expected_dto = OutputDto {
+            staging: None,
+            content_addressed: Some(OutputLinkMode::Symlink),
+            base: None,
+            provenance: None,
+        };
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let actual_dto = OutputDto::parse_with_ctx(&mut value, ()).unwrap();
+
+        // Then
+        assert_eq!(expected_dto, actual_dto);
+    }
+
+    #[test]
+    fn OutputDto__parse_base__EXPECT__valid_dto() {
+        // Given
+        let toml = r#"base = "build/generated/figx""#;
+        let expected_dto = OutputDto {
+            staging: None,
+            content_addressed: None,
+            base: Some("build/generated/figx".to_owned()),
+            provenance: None,
+        };
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let actual_dto = OutputDto::parse_with_ctx(&mut value, ()).unwrap();
+
+        // Then
+        assert_eq!(expected_dto, actual_dto);
+    }
+
+    #[test]
+    fn OutputDto__parse_unknown_key__EXPECT__error() {
+        // Given
+        let toml = r#"stagging = true"#;
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let actual_err = OutputDto::parse_with_ctx(&mut value, ());
+
+        // Then
+        assert!(actual_err.is_err());
+    }
+}
@@ -0,0 +1,62 @@
+mod de {
+    use toml_span::{Deserialize, ErrorKind};
+
+    use crate::OutputLinkMode;
+
+    impl<'de> Deserialize<'de> for OutputLinkMode {
+        fn deserialize(value: &mut toml_span::Value<'de>) -> Result<Self, toml_span::DeserError> {
+            match value.take() {
+                toml_span::value::ValueInner::String(mode) if mode == "hardlink" => {
+                    Ok(OutputLinkMode::Hardlink)
+                }
+                toml_span::value::ValueInner::String(mode) if mode == "symlink" => {
+                    Ok(OutputLinkMode::Symlink)
+                }
+                _ => Err(toml_span::Error {
+                    kind: ErrorKind::Custom(
+                        "content-addressed output link must be `hardlink` or `symlink`".into(),
+                    ),
+                    span: value.span,
+                    line_info: None,
+                }
+                .into()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+
+    use toml_span::de_helpers::TableHelper;
+
+    use crate::OutputLinkMode;
+
+    #[test]
+    fn OutputLinkMode__valid_toml__EXPECT__valid_value() {
+        // Given
+        let toml = r#"
+        mode1 = "hardlink"
+        mode2 = "symlink"
+        mode3 = "copy"
+        mode4 = true
+        "#;
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let mut th = TableHelper::new(&mut value).unwrap();
+
+        // Then
+        assert_eq!(
+            OutputLinkMode::Hardlink,
+            th.required::<OutputLinkMode>("mode1").unwrap()
+        );
+        assert_eq!(
+            OutputLinkMode::Symlink,
+            th.required::<OutputLinkMode>("mode2").unwrap()
+        );
+        assert!(th.required::<OutputLinkMode>("mode3").is_err());
+        assert!(th.required::<OutputLinkMode>("mode4").is_err());
+    }
+}
@@ -1,5 +1,5 @@
-use super::VariantsDto;
-use crate::CanBeExtendedBy;
+use super::{NamingRulesDto, VariantsDto};
+use crate::{CanBeExtendedBy, ExportScale, PostProcessCommand};
 use std::{collections::HashSet, path::PathBuf};
 
 #[derive(Default)]
@@ -7,7 +7,12 @@ use std::{collections::HashSet, path::PathBuf};
 pub(crate) struct PdfProfileDto {
     pub remote_id: Option<String>,
     pub output_dir: Option<PathBuf>,
+    pub allow_raster: Option<bool>,
+    pub preview_scales: Option<Vec<ExportScale>>,
+    pub local_renderer: Option<bool>,
     pub variants: Option<VariantsDto>,
+    pub post_process: Option<PostProcessCommand>,
+    pub naming: Option<NamingRulesDto>,
 }
 
 impl CanBeExtendedBy<Self> for PdfProfileDto {
@@ -23,12 +28,28 @@ impl CanBeExtendedBy<Self> for PdfProfileDto {
                 .as_ref()
                 .or(self.output_dir.as_ref())
                 .cloned(),
+            allow_raster: another.allow_raster.or(self.allow_raster),
+            preview_scales: another
+                .preview_scales
+                .as_ref()
+                .or(self.preview_scales.as_ref())
+                .cloned(),
+            local_renderer: another.local_renderer.or(self.local_renderer),
             variants: match (another.variants.as_ref(), self.variants.as_ref()) {
                 (Some(another), Some(this)) => Some(another.extend(this)),
                 (Some(another), None) => Some(another.clone()),
                 (None, Some(this)) => Some(this.clone()),
                 _ => None,
             },
+            post_process: another
+                .post_process
+                .as_ref()
+                .or(self.post_process.as_ref())
+                .cloned(),
+            naming: match (self.naming.as_ref(), another.naming.as_ref()) {
+                (Some(this), Some(another)) => Some(this.extend(another)),
+                (this, another) => another.or(this).cloned(),
+            },
         }
     }
 }
@@ -54,7 +75,12 @@ mod de {
             let mut th = TableHelper::new(value)?;
             let remote_id = th.optional_s::<String>("remote");
             let output_dir = th.optional::<String>("output_dir").map(PathBuf::from);
+            let allow_raster = th.optional::<bool>("allow_raster");
+            let preview_scales = th.optional::<Vec<ExportScale>>("preview_scales");
+            let local_renderer = th.optional::<bool>("local_renderer");
             let variants = th.optional::<VariantsDto>("variants");
+            let post_process = th.optional::<PostProcessCommand>("post_process");
+            let naming = th.optional::<NamingRulesDto>("naming");
             th.finalize(None)?;
             // endregion: extract
 
@@ -65,7 +91,12 @@ mod de {
             Ok(Self {
                 remote_id,
                 output_dir,
+                allow_raster,
+                preview_scales,
+                local_renderer,
                 variants,
+                post_process,
+                naming,
             })
         }
     }
@@ -77,7 +108,7 @@ mod test {
 
     use super::*;
     use crate::{ParseWithContext, variant_dto};
-    use ordermap::{ordermap, OrderMap};
+    use ordermap::{OrderMap, ordermap};
     use toml_span::Span;
     use unindent::unindent;
 
@@ -87,6 +118,9 @@ mod test {
         let toml = r#"
         remote = "figma"
         output_dir = "images"
+        allow_raster = true
+        preview_scales = [1, 2, 3]
+        local_renderer = true
         variants.small = { output_name = "{base}Small", figma_name = "{base} / small", scale = 1.0 }
         variants.big = { output_name = "{base}Big", figma_name = "{base} / big", scale = 2.0 }
         variants.use = ["small", "big"]
@@ -95,6 +129,9 @@ mod test {
         let expected_dto = PdfProfileDto {
             remote_id: Some("figma".to_string()),
             output_dir: Some(PathBuf::from("images")),
+            allow_raster: Some(true),
+            preview_scales: Some(vec![ExportScale(1.0), ExportScale(2.0), ExportScale(3.0)]),
+            local_renderer: Some(true),
             variants: Some(VariantsDto {
                 all_variants: Some(ordermap! {
                     // alphabetic keys sorting because of BTreeMap under the hood of the toml parser
@@ -103,6 +140,8 @@ mod test {
                 }),
                 use_variants: Some(vec!["small".to_string(), "big".to_string()]),
             }),
+            post_process: None,
+            naming: None,
         };
 
         // When
@@ -125,7 +164,12 @@ mod test {
         let expected_dto = PdfProfileDto {
             remote_id: None,
             output_dir: None,
+            allow_raster: None,
+            preview_scales: None,
+            local_renderer: None,
             variants: None,
+            post_process: None,
+            naming: None,
         };
 
         // When
@@ -206,18 +250,28 @@ mod test {
         let first = PdfProfileDto {
             remote_id: Some("remote".to_string()),
             output_dir: None,
+            allow_raster: None,
+            preview_scales: None,
+            local_renderer: None,
             variants: Some(VariantsDto {
                 all_variants: Some(OrderMap::new()),
                 use_variants: None,
             }),
+            post_process: None,
+            naming: None,
         };
         let second = PdfProfileDto {
             remote_id: None,
             output_dir: Some(PathBuf::from("path/to")),
+            allow_raster: None,
+            preview_scales: None,
+            local_renderer: None,
             variants: Some(VariantsDto {
                 all_variants: None,
                 use_variants: Some(Vec::new()),
             }),
+            post_process: None,
+            naming: None,
         };
 
         // When
@@ -228,10 +282,15 @@ mod test {
             PdfProfileDto {
                 remote_id: Some("remote".to_string()),
                 output_dir: Some(PathBuf::from("path/to")),
+                allow_raster: None,
+                preview_scales: None,
+                local_renderer: None,
                 variants: Some(VariantsDto {
                     all_variants: Some(OrderMap::new()),
                     use_variants: Some(Vec::new()),
                 }),
+                post_process: None,
+                naming: None,
             },
             third,
         );
@@ -0,0 +1,61 @@
+mod de {
+    use toml_span::{Deserialize, ErrorKind};
+
+    use crate::PngOptimizationLevel;
+
+    impl<'de> Deserialize<'de> for PngOptimizationLevel {
+        fn deserialize(value: &mut toml_span::Value<'de>) -> Result<Self, toml_span::DeserError> {
+            match value.take() {
+                toml_span::value::ValueInner::Integer(level @ 0..=6) => {
+                    Ok(PngOptimizationLevel(level as u8))
+                }
+                _ => Err(toml_span::Error {
+                    kind: ErrorKind::Custom(
+                        "png optimization level must be an integer from 0 to 6".into(),
+                    ),
+                    span: value.span,
+                    line_info: None,
+                }
+                .into()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+
+    use toml_span::de_helpers::TableHelper;
+
+    use crate::PngOptimizationLevel;
+
+    #[test]
+    fn PngOptimizationLevel__valid_toml__EXPECT__valid_value() {
+        // Given
+        let toml = r#"
+        level1 = 0
+        level2 = 6
+        level3 = -1
+        level4 = 7
+        level5 = 2.5
+        "#;
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let mut th = TableHelper::new(&mut value).unwrap();
+
+        // Then
+        assert_eq!(
+            PngOptimizationLevel(0),
+            th.required::<PngOptimizationLevel>("level1").unwrap()
+        );
+        assert_eq!(
+            PngOptimizationLevel(6),
+            th.required::<PngOptimizationLevel>("level2").unwrap()
+        );
+        assert!(th.required::<PngOptimizationLevel>("level3").is_err());
+        assert!(th.required::<PngOptimizationLevel>("level4").is_err());
+        assert!(th.required::<PngOptimizationLevel>("level5").is_err());
+    }
+}
@@ -1,5 +1,5 @@
-use super::VariantsDto;
-use crate::{CanBeExtendedBy, ExportScale};
+use super::{NamingRulesDto, VariantsDto};
+use crate::{CanBeExtendedBy, ExportScale, PngOptimizationLevel, PostProcessCommand};
 use std::{collections::HashSet, path::PathBuf};
 
 #[derive(Default)]
@@ -10,6 +10,10 @@ pub(crate) struct PngProfileDto {
     pub output_dir: Option<PathBuf>,
     pub variants: Option<VariantsDto>,
     pub legacy_loader: Option<bool>,
+    pub optimize: Option<bool>,
+    pub optimization_level: Option<PngOptimizationLevel>,
+    pub post_process: Option<PostProcessCommand>,
+    pub naming: Option<NamingRulesDto>,
 }
 
 impl CanBeExtendedBy<Self> for PngProfileDto {
@@ -33,6 +37,17 @@ impl CanBeExtendedBy<Self> for PngProfileDto {
                 _ => None,
             },
             legacy_loader: another.legacy_loader.or(self.legacy_loader),
+            optimize: another.optimize.or(self.optimize),
+            optimization_level: another.optimization_level.or(self.optimization_level),
+            post_process: another
+                .post_process
+                .as_ref()
+                .or(self.post_process.as_ref())
+                .cloned(),
+            naming: match (self.naming.as_ref(), another.naming.as_ref()) {
+                (Some(this), Some(another)) => Some(this.extend(another)),
+                (this, another) => another.or(this).cloned(),
+            },
         }
     }
 }
@@ -44,7 +59,7 @@ pub(crate) struct PngProfileDtoContext<'a> {
 mod de {
     use super::*;
     use crate::ParseWithContext;
-    use crate::parser::util::{validate_remote_id};
+    use crate::parser::util::validate_remote_id;
     use toml_span::de_helpers::TableHelper;
 
     impl<'de> ParseWithContext<'de> for PngProfileDto {
@@ -61,6 +76,10 @@ mod de {
             let output_dir = th.optional::<String>("output_dir").map(PathBuf::from);
             let variants = th.optional::<VariantsDto>("variants");
             let legacy_loader = th.optional::<bool>("legacy_loader");
+            let optimize = th.optional::<bool>("optimize");
+            let optimization_level = th.optional::<PngOptimizationLevel>("optimization_level");
+            let post_process = th.optional::<PostProcessCommand>("post_process");
+            let naming = th.optional::<NamingRulesDto>("naming");
             th.finalize(None)?;
             // endregion: extract
 
@@ -74,6 +93,10 @@ mod de {
                 output_dir,
                 variants,
                 legacy_loader,
+                optimize,
+                optimization_level,
+                post_process,
+                naming,
             })
         }
     }
@@ -85,7 +108,7 @@ mod test {
 
     use super::*;
     use crate::{ParseWithContext, variant_dto};
-    use ordermap::{ordermap, OrderMap};
+    use ordermap::{OrderMap, ordermap};
     use toml_span::Span;
     use unindent::unindent;
 
@@ -100,6 +123,8 @@ mod test {
         variants.big = { output_name = "{base}Big", figma_name = "{base} / big", scale = 2.0 }
         variants.use = ["small", "big"]
         legacy_loader = false
+        optimize = true
+        optimization_level = 4
         "#;
         let declared_remote_ids: HashSet<_> = ["figma".to_string()].into_iter().collect();
         let expected_dto = PngProfileDto {
@@ -115,6 +140,10 @@ mod test {
                 use_variants: Some(vec!["small".to_string(), "big".to_string()]),
             }),
             legacy_loader: Some(false),
+            optimize: Some(true),
+            optimization_level: Some(PngOptimizationLevel(4)),
+            post_process: None,
+            naming: None,
         };
 
         // When
@@ -140,6 +169,10 @@ mod test {
             output_dir: None,
             variants: None,
             legacy_loader: None,
+            optimize: None,
+            optimization_level: None,
+            post_process: None,
+            naming: None,
         };
 
         // When
@@ -228,6 +261,10 @@ mod test {
                 use_variants: None,
             }),
             legacy_loader: Some(false),
+            optimize: Some(true),
+            optimization_level: None,
+            post_process: None,
+            naming: None,
         };
         let second = PngProfileDto {
             remote_id: None,
@@ -238,6 +275,10 @@ mod test {
                 use_variants: Some(Vec::new()),
             }),
             legacy_loader: None,
+            optimize: None,
+            optimization_level: Some(PngOptimizationLevel(6)),
+            post_process: None,
+            naming: None,
         };
 
         // When
@@ -254,6 +295,10 @@ mod test {
                     use_variants: Some(Vec::new()),
                 }),
                 legacy_loader: Some(false),
+                optimize: Some(true),
+                optimization_level: Some(PngOptimizationLevel(6)),
+                post_process: None,
+                naming: None,
             },
             third,
         );
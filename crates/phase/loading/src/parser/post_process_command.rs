@@ -0,0 +1,73 @@
+mod de {
+    use toml_span::{Deserialize, ErrorKind, value::ValueInner};
+
+    use crate::PostProcessCommand;
+
+    impl<'de> Deserialize<'de> for PostProcessCommand {
+        fn deserialize(value: &mut toml_span::Value<'de>) -> Result<Self, toml_span::DeserError> {
+            let span = value.span;
+            let command = match value.take() {
+                ValueInner::Array(array) => array
+                    .into_iter()
+                    .map(|mut it| match it.take() {
+                        ValueInner::String(s) => Some(s.into_owned()),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>(),
+                _ => None,
+            };
+            match command {
+                Some(command) if command.first().is_some_and(|it| !it.is_empty()) => {
+                    Ok(PostProcessCommand(command))
+                }
+                _ => Err(toml_span::Error {
+                    kind: ErrorKind::Custom(
+                        "post_process must be a non-empty array of strings, e.g. [\"svgo\", \"{output}\"]"
+                            .into(),
+                    ),
+                    span,
+                    line_info: None,
+                }
+                .into()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+
+    use std::path::Path;
+    use toml_span::de_helpers::TableHelper;
+
+    use crate::PostProcessCommand;
+
+    #[test]
+    fn PostProcessCommand__valid_toml__EXPECT__valid_value() {
+        // Given
+        let toml = r#"
+        cmd1 = ["svgo", "--multipass", "{output}"]
+        cmd2 = []
+        cmd3 = "svgo {output}"
+        cmd4 = ["svgo", 1]
+        cmd5 = ["", "{output}"]
+        "#;
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let mut th = TableHelper::new(&mut value).unwrap();
+
+        // Then
+        let command = th.required::<PostProcessCommand>("cmd1").unwrap();
+        assert_eq!("svgo", command.program());
+        assert_eq!(
+            vec!["--multipass".to_string(), "res/ic_star.svg".to_string()],
+            command.args(Path::new("res/ic_star.svg"))
+        );
+        assert!(th.required::<PostProcessCommand>("cmd2").is_err());
+        assert!(th.required::<PostProcessCommand>("cmd3").is_err());
+        assert!(th.required::<PostProcessCommand>("cmd4").is_err());
+        assert!(th.required::<PostProcessCommand>("cmd5").is_err());
+    }
+}
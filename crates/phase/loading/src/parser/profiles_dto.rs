@@ -1,4 +1,8 @@
-use crate::parser::{AndroidDrawableProfileDto, AndroidDrawableProfileDtoContext};
+use crate::parser::{
+    AndroidDrawableProfileDto, AndroidDrawableProfileDtoContext, FaviconProfileDto,
+    FaviconProfileDtoContext, FlutterProfileDto, FlutterProfileDtoContext, NamingRulesDto,
+    ReactProfileDto, ReactProfileDtoContext, VectorLimitsDto,
+};
 
 use super::{
     AndroidWebpProfileDtoContext, ComposeProfileDto, PdfProfileDto, PdfProfileDtoContext,
@@ -6,6 +10,7 @@ use super::{
     WebpProfileDtoContext, android_webp_profile_dto::AndroidWebpProfileDto,
     compose_profile_dto::ComposeProfileDtoContext,
 };
+use crate::CanBeExtendedBy;
 use ordermap::OrderMap;
 use std::collections::HashSet;
 
@@ -16,8 +21,26 @@ pub(crate) struct ProfilesDto(pub OrderMap<String, ProfileDto>);
 #[derive(Clone, Copy)]
 pub(crate) struct ProfilesDtoContext<'a> {
     pub declared_remote_ids: &'a HashSet<String>,
+    /// Profiles of the included files, which the parsed ones are defined on top of
+    pub included: Option<&'a ProfilesDto>,
+    /// Profiles overridden in `.figtree.local.toml`, applied over the parsed ones
+    pub overrides: Option<&'a OrderMap<String, ProfileDto>>,
 }
 
+/// Ids of the profiles available without declaration, custom profiles extend them
+pub(crate) const BUILTIN_PROFILE_IDS: &[&str] = &[
+    "png",
+    "svg",
+    "pdf",
+    "webp",
+    "compose",
+    "android-webp",
+    "android-drawable",
+    "flutter",
+    "react",
+    "favicon",
+];
+
 macro_rules! from_ctx_impl {
     ($from:tt, $to:tt) => {
         impl<'de> From<$from<'de>> for $to<'de> {
@@ -37,6 +60,9 @@ from_ctx_impl!(ProfilesDtoContext, WebpProfileDtoContext);
 from_ctx_impl!(ProfilesDtoContext, ComposeProfileDtoContext);
 from_ctx_impl!(ProfilesDtoContext, AndroidWebpProfileDtoContext);
 from_ctx_impl!(ProfilesDtoContext, AndroidDrawableProfileDtoContext);
+from_ctx_impl!(ProfilesDtoContext, FlutterProfileDtoContext);
+from_ctx_impl!(ProfilesDtoContext, ReactProfileDtoContext);
+from_ctx_impl!(ProfilesDtoContext, FaviconProfileDtoContext);
 
 #[cfg_attr(test, derive(PartialEq, Debug))]
 pub(crate) enum ProfileDto {
@@ -47,6 +73,108 @@ pub(crate) enum ProfileDto {
     Compose(ComposeProfileDto),
     AndroidWebp(AndroidWebpProfileDto),
     AndroidDrawable(AndroidDrawableProfileDto),
+    Flutter(FlutterProfileDto),
+    React(ReactProfileDto),
+    Favicon(FaviconProfileDto),
+}
+
+impl ProfilesDto {
+    /// Adds the profiles of the included files, which are not redefined by this ones.
+    ///
+    /// Built-in profiles are always redefined, because they are parsed on top of the included ones.
+    pub fn with_included(self, included: ProfilesDto) -> Self {
+        let ProfilesDto(mut profiles) = included;
+        profiles.retain(|id, _| !self.0.contains_key(id));
+        profiles.extend(self.0);
+        Self(profiles)
+    }
+
+    /// Applies the overrides of `.figtree.local.toml` to the already parsed profiles
+    pub fn overridden_by(self, overrides: &OrderMap<String, ProfileDto>) -> Self {
+        let ProfilesDto(profiles) = self;
+        Self(
+            profiles
+                .into_iter()
+                .map(|(id, profile)| match overrides.get(&id) {
+                    Some(profile_override) => {
+                        let profile = profile.extend(profile_override);
+                        (id, profile)
+                    }
+                    None => (id, profile),
+                })
+                .collect(),
+        )
+    }
+}
+
+impl CanBeExtendedBy<Self> for ProfileDto {
+    fn extend(&self, another: &Self) -> Self {
+        use ProfileDto::*;
+        match (self, another) {
+            (Png(this), Png(another)) => Png(this.extend(another)),
+            (Svg(this), Svg(another)) => Svg(this.extend(another)),
+            (Pdf(this), Pdf(another)) => Pdf(this.extend(another)),
+            (Webp(this), Webp(another)) => Webp(this.extend(another)),
+            (Compose(this), Compose(another)) => Compose(this.extend(another)),
+            (AndroidWebp(this), AndroidWebp(another)) => AndroidWebp(this.extend(another)),
+            (AndroidDrawable(this), AndroidDrawable(another)) => {
+                AndroidDrawable(this.extend(another))
+            }
+            (Flutter(this), Flutter(another)) => Flutter(this.extend(another)),
+            (React(this), React(another)) => React(this.extend(another)),
+            (Favicon(this), Favicon(another)) => Favicon(this.extend(another)),
+            _ => unreachable!("profiles are always parsed as the kind of the profile they extend"),
+        }
+    }
+}
+
+impl ProfileDto {
+    /// Id of the built-in profile of the same kind
+    pub fn builtin_id(&self) -> &'static str {
+        use ProfileDto::*;
+        match self {
+            Png(_) => "png",
+            Svg(_) => "svg",
+            Pdf(_) => "pdf",
+            Webp(_) => "webp",
+            Compose(_) => "compose",
+            AndroidWebp(_) => "android-webp",
+            AndroidDrawable(_) => "android-drawable",
+            Flutter(_) => "flutter",
+            React(_) => "react",
+            Favicon(_) => "favicon",
+        }
+    }
+
+    pub fn is_raster(&self) -> bool {
+        use ProfileDto::*;
+        matches!(self, Png(_) | Webp(_) | AndroidWebp(_) | Flutter(_))
+    }
+
+    pub fn naming(&self) -> Option<&NamingRulesDto> {
+        use ProfileDto::*;
+        match self {
+            Png(p) => p.naming.as_ref(),
+            Svg(p) => p.naming.as_ref(),
+            Pdf(p) => p.naming.as_ref(),
+            Webp(p) => p.naming.as_ref(),
+            Compose(p) => p.naming.as_ref(),
+            AndroidWebp(p) => p.naming.as_ref(),
+            AndroidDrawable(p) => p.naming.as_ref(),
+            Flutter(p) => p.naming.as_ref(),
+            React(p) => p.naming.as_ref(),
+            Favicon(p) => p.naming.as_ref(),
+        }
+    }
+
+    pub fn vector_limits_mut(&mut self) -> Option<&mut VectorLimitsDto> {
+        use ProfileDto::*;
+        match self {
+            Compose(p) => p.limits.as_mut(),
+            AndroidDrawable(p) => p.limits.as_mut(),
+            _ => None,
+        }
+    }
 }
 
 mod de {
@@ -55,7 +183,109 @@ mod de {
         CanBeExtendedBy, ParseWithContext, parser::android_webp_profile_dto::AndroidWebpProfileDto,
     };
     use ordermap::ordermap;
-    use toml_span::{ErrorKind, de_helpers::TableHelper};
+    use toml_span::{ErrorKind, Spanned, de_helpers::TableHelper};
+
+    /// Parses the settings of a built-in profile on top of its included version, if any
+    macro_rules! builtin_profile {
+        ($th:ident, $ctx:ident, $id:literal, $variant:ident, $dto:ty) => {{
+            let included = match $ctx.included.and_then(|it| it.0.get($id)) {
+                Some(ProfileDto::$variant(p)) => Some(p),
+                _ => None,
+            };
+            let profile = match ($th.take($id), included) {
+                (Some((_, mut value)), Some(included)) => {
+                    included.extend(&<$dto>::parse_with_ctx(&mut value, $ctx.into())?)
+                }
+                (Some((_, mut value)), None) => <$dto>::parse_with_ctx(&mut value, $ctx.into())?,
+                (None, Some(included)) => <$dto>::default().extend(included),
+                (None, None) => <$dto>::default(),
+            };
+            // overridden before the custom profiles extend it, as if it was in the same file
+            match $ctx.overrides.and_then(|it| it.get($id)) {
+                Some(ProfileDto::$variant(p)) => profile.extend(p),
+                _ => profile,
+            }
+        }};
+    }
+
+    impl ProfileDto {
+        /// Parses the settings of the profile of the given built-in kind,
+        /// `None` if there is no such kind
+        pub fn parse_as<'de>(
+            builtin_id: &str,
+            value: &mut toml_span::Value<'de>,
+            ctx: ProfilesDtoContext<'de>,
+        ) -> std::result::Result<Option<Self>, toml_span::DeserError> {
+            use ProfileDto::*;
+            let profile = match builtin_id {
+                "png" => Png(PngProfileDto::parse_with_ctx(value, ctx.into())?),
+                "svg" => Svg(SvgProfileDto::parse_with_ctx(value, ctx.into())?),
+                "pdf" => Pdf(PdfProfileDto::parse_with_ctx(value, ctx.into())?),
+                "webp" => Webp(WebpProfileDto::parse_with_ctx(value, ctx.into())?),
+                "compose" => Compose(ComposeProfileDto::parse_with_ctx(value, ctx.into())?),
+                "android-webp" => {
+                    AndroidWebp(AndroidWebpProfileDto::parse_with_ctx(value, ctx.into())?)
+                }
+                "android-drawable" => AndroidDrawable(AndroidDrawableProfileDto::parse_with_ctx(
+                    value,
+                    ctx.into(),
+                )?),
+                "flutter" => Flutter(FlutterProfileDto::parse_with_ctx(value, ctx.into())?),
+                "react" => React(ReactProfileDto::parse_with_ctx(value, ctx.into())?),
+                "favicon" => Favicon(FaviconProfileDto::parse_with_ctx(value, ctx.into())?),
+                _ => return Ok(None),
+            };
+            Ok(Some(profile))
+        }
+    }
+
+    /// Follows the chain of `extends` up to a built-in or an included profile
+    /// and returns the id of the built-in profile, which defines the kind of the whole chain
+    fn resolve_kind(
+        profile_id: &str,
+        parents: &OrderMap<String, Spanned<String>>,
+        included: Option<&ProfilesDto>,
+    ) -> std::result::Result<&'static str, toml_span::Error> {
+        let mut chain = vec![profile_id];
+        loop {
+            let parent = &parents[*chain.last().unwrap()];
+            if let Some(kind) = BUILTIN_PROFILE_IDS.iter().find(|it| **it == parent.value) {
+                return Ok(*kind);
+            }
+            if let Some(profile) = included.and_then(|it| it.0.get(&parent.value)) {
+                return Ok(profile.builtin_id());
+            }
+            if !parents.contains_key(&parent.value) {
+                return Err(toml_span::Error::from((
+                    ErrorKind::Custom(
+                        format!(
+                            "profile `{}` is not declared, expected one of the built-in profiles \
+                            ({}) or a custom one",
+                            parent.value,
+                            BUILTIN_PROFILE_IDS.join(", "),
+                        )
+                        .into(),
+                    ),
+                    parent.span,
+                )));
+            }
+            if let Some(start) = chain.iter().position(|it| *it == parent.value) {
+                let cycle = chain[start..]
+                    .iter()
+                    .chain(std::iter::once(&parent.value.as_str()))
+                    .map(|it| format!("`{it}`"))
+                    .collect::<Vec<_>>()
+                    .join(" -> ");
+                return Err(toml_span::Error::from((
+                    ErrorKind::Custom(
+                        format!("profiles extend each other in a cycle: {cycle}").into(),
+                    ),
+                    parent.span,
+                )));
+            }
+            chain.push(&parent.value);
+        }
+    }
 
     impl<'de> ParseWithContext<'de> for ProfilesDto {
         type Context = ProfilesDtoContext<'de>;
@@ -69,100 +299,260 @@ mod de {
             let mut profiles = OrderMap::with_capacity(th.table.len());
 
             // region: built-ins
-            let png_profile_dto = match th.take("png") {
-                Some((_, mut value)) => PngProfileDto::parse_with_ctx(&mut value, ctx.into())?,
-                None => PngProfileDto::default(),
-            };
-            let svg_profile_dto = match th.take("svg") {
-                Some((_, mut value)) => SvgProfileDto::parse_with_ctx(&mut value, ctx.into())?,
-                None => SvgProfileDto::default(),
-            };
-            let pdf_profile_dto = match th.take("pdf") {
-                Some((_, mut value)) => PdfProfileDto::parse_with_ctx(&mut value, ctx.into())?,
-                None => PdfProfileDto::default(),
-            };
-            let webp_profile_dto = match th.take("webp") {
-                Some((_, mut value)) => WebpProfileDto::parse_with_ctx(&mut value, ctx.into())?,
-                None => WebpProfileDto::default(),
-            };
-            let compose_profile_dto = match th.take("compose") {
-                Some((_, mut value)) => ComposeProfileDto::parse_with_ctx(&mut value, ctx.into())?,
-                None => ComposeProfileDto::default(),
-            };
-            let android_webp_profile_dto = match th.take("android-webp") {
-                Some((_, mut value)) => {
-                    AndroidWebpProfileDto::parse_with_ctx(&mut value, ctx.into())?
-                }
-                None => AndroidWebpProfileDto::default(),
-            };
-            let android_drawable_profile_dto = match th.take("android-drawable") {
-                Some((_, mut value)) => {
-                    AndroidDrawableProfileDto::parse_with_ctx(&mut value, ctx.into())?
-                }
-                None => AndroidDrawableProfileDto::default(),
-            };
+            let png_profile_dto = builtin_profile!(th, ctx, "png", Png, PngProfileDto);
+            let svg_profile_dto = builtin_profile!(th, ctx, "svg", Svg, SvgProfileDto);
+            let pdf_profile_dto = builtin_profile!(th, ctx, "pdf", Pdf, PdfProfileDto);
+            let webp_profile_dto = builtin_profile!(th, ctx, "webp", Webp, WebpProfileDto);
+            let compose_profile_dto =
+                builtin_profile!(th, ctx, "compose", Compose, ComposeProfileDto);
+            let android_webp_profile_dto =
+                builtin_profile!(th, ctx, "android-webp", AndroidWebp, AndroidWebpProfileDto);
+            let android_drawable_profile_dto = builtin_profile!(
+                th,
+                ctx,
+                "android-drawable",
+                AndroidDrawable,
+                AndroidDrawableProfileDto
+            );
+            let flutter_profile_dto =
+                builtin_profile!(th, ctx, "flutter", Flutter, FlutterProfileDto);
+            let react_profile_dto = builtin_profile!(th, ctx, "react", React, ReactProfileDto);
+            let favicon_profile_dto =
+                builtin_profile!(th, ctx, "favicon", Favicon, FaviconProfileDto);
             // region: built-ins
 
+            let mut builtins = ordermap! {
+                "png".to_string() => ProfileDto::Png(png_profile_dto),
+                "svg".to_string() => ProfileDto::Svg(svg_profile_dto),
+                "pdf".to_string() => ProfileDto::Pdf(pdf_profile_dto),
+                "webp".to_string() => ProfileDto::Webp(webp_profile_dto),
+                "compose".to_string() => ProfileDto::Compose(compose_profile_dto),
+                "android-webp".to_string() => ProfileDto::AndroidWebp(android_webp_profile_dto),
+                "android-drawable".to_string() => ProfileDto::AndroidDrawable(android_drawable_profile_dto),
+                "flutter".to_string() => ProfileDto::Flutter(flutter_profile_dto),
+                "react".to_string() => ProfileDto::React(react_profile_dto),
+                "favicon".to_string() => ProfileDto::Favicon(favicon_profile_dto),
+            };
+
+            // region: inheritance graph
+            let mut parents = OrderMap::with_capacity(th.table.len());
             for (key, value) in th.table.iter_mut() {
                 let profile_id = key.to_string();
+                if ctx
+                    .included
+                    .is_some_and(|it| it.0.contains_key(&profile_id))
+                {
+                    return Err(toml_span::Error::from((
+                        ErrorKind::Custom(
+                            format!(
+                                "profile `{profile_id}` is already defined in an included file"
+                            )
+                            .into(),
+                        ),
+                        key.span,
+                    ))
+                    .into());
+                }
                 let mut th = TableHelper::new(value)?;
                 let extends = th.required_s::<String>("extends")?;
                 th.finalize(Some(value))?;
+                parents.insert(profile_id, extends);
+            }
+            // endregion: inheritance graph
 
-                let profile = match extends.value.as_str() {
-                    "png" => ProfileDto::Png(
-                        png_profile_dto.extend(&PngProfileDto::parse_with_ctx(value, ctx.into())?),
-                    ),
-                    "svg" => ProfileDto::Svg(
-                        svg_profile_dto.extend(&SvgProfileDto::parse_with_ctx(value, ctx.into())?),
-                    ),
-                    "pdf" => ProfileDto::Pdf(
-                        pdf_profile_dto.extend(&PdfProfileDto::parse_with_ctx(value, ctx.into())?),
-                    ),
-                    "webp" => ProfileDto::Webp(
-                        webp_profile_dto
-                            .extend(&WebpProfileDto::parse_with_ctx(value, ctx.into())?),
-                    ),
-                    "compose" => ProfileDto::Compose(
-                        compose_profile_dto
-                            .extend(&ComposeProfileDto::parse_with_ctx(value, ctx.into())?),
-                    ),
-                    "android-webp" => ProfileDto::AndroidWebp(
-                        android_webp_profile_dto
-                            .extend(&AndroidWebpProfileDto::parse_with_ctx(value, ctx.into())?),
-                    ),
-                    "android-drawable" => {
-                        ProfileDto::AndroidDrawable(android_drawable_profile_dto.extend(
-                            &AndroidDrawableProfileDto::parse_with_ctx(value, ctx.into())?,
-                        ))
-                    }
-                    unknown => {
-                        return Err(toml_span::Error::from((
-                            ErrorKind::UnexpectedValue {
-                                expected: &["png", "svg", "pdf", "webp", "compose", "android-webp"],
-                                value: Some(unknown.to_string()),
-                            },
-                            extends.span,
-                        ))
-                        .into());
-                    }
-                };
-                profiles.insert(profile_id, profile);
+            let mut own_profiles = OrderMap::with_capacity(parents.len());
+            for (key, value) in th.table.iter_mut() {
+                let profile_id = key.to_string();
+                let kind = resolve_kind(&profile_id, &parents, ctx.included)?;
+                let profile = ProfileDto::parse_as(kind, value, ctx)?
+                    .expect("kind is always an id of a built-in profile");
+                own_profiles.insert(profile_id, profile);
             }
             th.finalize(Some(value))?;
 
-            profiles.append(&mut ordermap! {
-                "png".to_string() => ProfileDto::Png(png_profile_dto),
-                "svg".to_string() => ProfileDto::Svg(svg_profile_dto),
-                "pdf".to_string() => ProfileDto::Pdf(pdf_profile_dto),
-                "webp".to_string() => ProfileDto::Webp(webp_profile_dto),
-                "compose".to_string() => ProfileDto::Compose(compose_profile_dto),
-                "android-webp".to_string() => ProfileDto::AndroidWebp(android_webp_profile_dto),
-                "android-drawable".to_string() => ProfileDto::AndroidDrawable(android_drawable_profile_dto),
-            });
+            // parents are resolved before their children, whatever the order of declaration
+            let mut resolved = OrderMap::with_capacity(own_profiles.len());
+            for profile_id in own_profiles.keys() {
+                let mut pending = vec![profile_id];
+                while let Some(parent_id) = pending.last().map(|it| &parents[*it].value) {
+                    if !own_profiles.contains_key(parent_id) || resolved.contains_key(parent_id) {
+                        break;
+                    }
+                    pending.push(parent_id);
+                }
+                for profile_id in pending.into_iter().rev() {
+                    if resolved.contains_key(profile_id) {
+                        continue;
+                    }
+                    let parent_id = &parents[profile_id].value;
+                    let parent = resolved
+                        .get(parent_id)
+                        .or_else(|| builtins.get(parent_id))
+                        .or_else(|| ctx.included.and_then(|it| it.0.get(parent_id)))
+                        .expect("parent is resolved before its children");
+                    let mut profile = parent.extend(&own_profiles[profile_id]);
+                    if let Some(profile_override) = ctx.overrides.and_then(|it| it.get(profile_id))
+                    {
+                        profile = profile.extend(profile_override);
+                    }
+                    resolved.insert(profile_id.clone(), profile);
+                }
+            }
+            for profile_id in own_profiles.keys() {
+                let profile = resolved
+                    .remove(profile_id)
+                    .expect("all profiles are resolved");
+                profiles.insert(profile_id.clone(), profile);
+            }
+            profiles.append(&mut builtins);
             // endregion: extract
 
+            // region: validate
+            let raster_profiles = profiles
+                .iter()
+                .chain(ctx.included.iter().flat_map(|it| it.0.iter()))
+                .filter(|(_, profile)| profile.is_raster())
+                .map(|(id, _)| id.clone())
+                .collect::<Vec<_>>();
+            let raster_profiles = raster_profiles
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>();
+            for profile in profiles.values_mut() {
+                let fallback = profile
+                    .vector_limits_mut()
+                    .and_then(VectorLimitsDto::fallback_mut);
+                if let Some(fallback) = fallback {
+                    fallback.validate(&raster_profiles)?;
+                }
+            }
+            // endregion: validate
+
             Ok(Self(profiles))
         }
     }
 }
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use crate::ParseWithContext;
+    use std::path::PathBuf;
+    use toml_span::Span;
+    use unindent::unindent;
+
+    fn parse(toml: &str) -> std::result::Result<ProfilesDto, toml_span::DeserError> {
+        let declared_remote_ids = HashSet::new();
+        let mut value = toml_span::parse(toml).unwrap();
+        ProfilesDto::parse_with_ctx(
+            &mut value,
+            ProfilesDtoContext {
+                declared_remote_ids: &declared_remote_ids,
+                included: None,
+                overrides: None,
+            },
+        )
+    }
+
+    #[test]
+    fn ProfilesDto__custom_profile_extends_custom_one__EXPECT__settings_inherited() {
+        // Given
+        let toml = unindent(
+            r#"
+                [feature-webp]
+                extends = "base-webp"
+                output_dir = "feature"
+
+                [base-webp]
+                extends = "webp"
+                lossless = true
+                output_dir = "base"
+            "#,
+        );
+        let expected_dto = ProfileDto::Webp(WebpProfileDto {
+            lossless: Some(true),
+            output_dir: Some(PathBuf::from("feature")),
+            ..Default::default()
+        });
+
+        // When
+        let dto = parse(&toml).unwrap();
+
+        // Then
+        assert_eq!(Some(&expected_dto), dto.0.get("feature-webp"));
+    }
+
+    #[test]
+    fn ProfilesDto__profiles_extend_each_other__EXPECT__error_with_correct_span() {
+        // Given
+        let toml = unindent(
+            r#"
+                [a]
+                extends = "b"
+
+                [b]
+                extends = "a"
+            "#,
+        );
+
+        // When
+        let err = parse(&toml).unwrap_err();
+
+        // Then
+        assert_eq!(Span::new(34, 35), err.errors[0].span);
+        assert!(matches!(
+            &err.errors[0].kind,
+            toml_span::ErrorKind::Custom(message) if message.contains("`a` -> `b` -> `a`"),
+        ));
+    }
+
+    #[test]
+    fn ProfilesDto__unknown_parent__EXPECT__error_with_correct_span() {
+        // Given
+        let toml = unindent(
+            r#"
+                [a]
+                extends = "webpp"
+            "#,
+        );
+
+        // When
+        let err = parse(&toml).unwrap_err();
+
+        // Then
+        assert_eq!(Span::new(15, 20), err.errors[0].span);
+    }
+
+    #[test]
+    fn ProfilesDto__with_included__EXPECT__profiles_of_both_files() {
+        // Given
+        let declared_remote_ids = HashSet::new();
+        let parse_on = |toml: &str, included: Option<&ProfilesDto>| {
+            let mut value = toml_span::parse(toml).unwrap();
+            let ctx = ProfilesDtoContext {
+                declared_remote_ids: &declared_remote_ids,
+                included,
+                overrides: None,
+            };
+            ProfilesDto::parse_with_ctx(&mut value, ctx).unwrap()
+        };
+        let included = parse_on("[png]\nscale = 2.0\n[icons]\nextends = \"png\"\n", None);
+        let profiles = parse_on("[banners]\nextends = \"webp\"\n", Some(&included));
+
+        // When
+        let profiles = profiles.with_included(included);
+
+        // Then
+        let scale = |id: &str| match profiles.0.get(id) {
+            Some(ProfileDto::Png(png)) => png.scale,
+            other => panic!("unexpected profile `{id}`: {other:?}"),
+        };
+        assert_eq!(Some(crate::ExportScale(2.0)), scale("png"));
+        assert_eq!(Some(crate::ExportScale(2.0)), scale("icons"));
+        assert!(matches!(
+            profiles.0.get("banners"),
+            Some(ProfileDto::Webp(_))
+        ));
+    }
+}
@@ -1,7 +1,11 @@
-use crate::parser::{AccessTokenDefinitionDto, NodeIdListDto};
+use crate::CanBeExtendedBy;
+use crate::parser::{AccessTokenDefinitionDto, NodeIdListDto, RetryPolicyDto};
 use ordermap::OrderMap;
 use toml_span::Span;
 
+/// Longer lists of node ids make the export request URL too long
+const MAX_EXPORT_BATCH_SIZE: usize = 500;
+
 #[derive(Default)]
 #[cfg_attr(test, derive(PartialEq, Debug))]
 pub(crate) struct RemotesDto(pub OrderMap<String, RemoteDto>);
@@ -14,17 +18,58 @@ pub struct RemotesDtoContext {
 #[cfg_attr(test, derive(PartialEq, Debug))]
 pub(crate) struct RemoteDto {
     pub file_key: String,
+    pub branch: Option<String>,
     pub container_node_ids: NodeIdListDto,
     pub access_token: AccessTokenDefinitionDto,
     pub default: Option<bool>,
+    pub shallow_depth: Option<u32>,
+    pub export_batch_size: Option<usize>,
+    pub retry: Option<RetryPolicyDto>,
     pub key_span: Span,
 }
 
+/// Settings of a remote overridden in `.figtree.local.toml`
+#[derive(Default)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub(crate) struct RemoteOverrideDto {
+    pub file_key: Option<String>,
+    pub branch: Option<String>,
+    pub container_node_ids: Option<NodeIdListDto>,
+    pub access_token: Option<AccessTokenDefinitionDto>,
+    pub shallow_depth: Option<u32>,
+    pub export_batch_size: Option<usize>,
+    pub retry: Option<RetryPolicyDto>,
+}
+
+impl CanBeExtendedBy<RemoteOverrideDto> for RemoteDto {
+    fn extend(&self, another: &RemoteOverrideDto) -> Self {
+        Self {
+            file_key: another.file_key.as_ref().unwrap_or(&self.file_key).clone(),
+            branch: another.branch.as_ref().or(self.branch.as_ref()).cloned(),
+            container_node_ids: another
+                .container_node_ids
+                .as_ref()
+                .unwrap_or(&self.container_node_ids)
+                .clone(),
+            access_token: another
+                .access_token
+                .as_ref()
+                .unwrap_or(&self.access_token)
+                .clone(),
+            default: self.default,
+            shallow_depth: another.shallow_depth.or(self.shallow_depth),
+            export_batch_size: another.export_batch_size.or(self.export_batch_size),
+            retry: another.retry.as_ref().or(self.retry.as_ref()).cloned(),
+            key_span: self.key_span,
+        }
+    }
+}
+
 mod de {
     use super::*;
     use crate::ParseWithContext;
     use ordermap::OrderMap;
-    use toml_span::{Deserialize, ErrorKind, de_helpers::TableHelper};
+    use toml_span::{Deserialize, ErrorKind, Spanned, de_helpers::TableHelper};
 
     impl<'de> ParseWithContext<'de> for RemotesDto {
         type Context = RemotesDtoContext;
@@ -96,6 +141,7 @@ mod de {
             // region: extract
             let mut th = TableHelper::new(value)?;
             let file_key = th.required_s::<String>("file_key")?;
+            let branch = th.optional_s::<String>("branch");
             let container_node_ids = th.required_s::<NodeIdListDto>("container_node_ids")?.value;
             let access_token = if let Some((_, mut value)) = th.take("access_token") {
                 AccessTokenDefinitionDto::deserialize(&mut value)?
@@ -103,31 +149,133 @@ mod de {
                 AccessTokenDefinitionDto::default()
             };
             let default = th.optional("default");
+            let shallow_depth = th.optional_s::<u32>("shallow_depth");
+            let export_batch_size = th.optional_s::<usize>("export_batch_size");
+            let retry = th.optional::<RetryPolicyDto>("retry");
             th.finalize(None)?;
             // endregion: extract
 
             // region: validate
-            let file_key = match file_key.value.as_str() {
-                "" => {
-                    return Err(toml_span::Error::from((
-                        ErrorKind::Custom("file_key cannot be empty".into()),
-                        file_key.span,
-                    ))
-                    .into());
-                }
-                s => s.to_owned(),
-            };
+            let file_key = validate_file_key(file_key)?;
+            let branch = validate_branch(branch)?;
+            let shallow_depth = validate_shallow_depth(shallow_depth)?;
+            let export_batch_size = validate_export_batch_size(export_batch_size)?;
             // endregion: validate
 
             Ok(Self {
                 file_key,
+                branch,
                 container_node_ids,
                 access_token,
                 default,
+                shallow_depth,
+                export_batch_size,
+                retry,
                 key_span: Default::default(),
             })
         }
     }
+
+    impl<'de> ParseWithContext<'de> for RemoteOverrideDto {
+        type Context = ();
+
+        fn parse_with_ctx(
+            value: &mut toml_span::Value<'de>,
+            _ctx: Self::Context,
+        ) -> std::result::Result<Self, toml_span::DeserError> {
+            // region: extract
+            let mut th = TableHelper::new(value)?;
+            let file_key = th.optional_s::<String>("file_key");
+            let branch = th.optional_s::<String>("branch");
+            let container_node_ids = th.optional::<NodeIdListDto>("container_node_ids");
+            let access_token = match th.take("access_token") {
+                Some((_, mut value)) => Some(AccessTokenDefinitionDto::deserialize(&mut value)?),
+                None => None,
+            };
+            let shallow_depth = th.optional_s::<u32>("shallow_depth");
+            let export_batch_size = th.optional_s::<usize>("export_batch_size");
+            let retry = th.optional::<RetryPolicyDto>("retry");
+            th.finalize(None)?;
+            // endregion: extract
+
+            // region: validate
+            let file_key = match file_key {
+                Some(file_key) => Some(validate_file_key(file_key)?),
+                None => None,
+            };
+            let branch = validate_branch(branch)?;
+            let shallow_depth = validate_shallow_depth(shallow_depth)?;
+            let export_batch_size = validate_export_batch_size(export_batch_size)?;
+            // endregion: validate
+
+            Ok(Self {
+                file_key,
+                branch,
+                container_node_ids,
+                access_token,
+                shallow_depth,
+                export_batch_size,
+                retry,
+            })
+        }
+    }
+
+    fn validate_file_key(
+        file_key: Spanned<String>,
+    ) -> std::result::Result<String, toml_span::DeserError> {
+        if file_key.value.is_empty() {
+            return Err(toml_span::Error::from((
+                ErrorKind::Custom("file_key cannot be empty".into()),
+                file_key.span,
+            ))
+            .into());
+        }
+        Ok(file_key.value)
+    }
+
+    fn validate_branch(
+        branch: Option<Spanned<String>>,
+    ) -> std::result::Result<Option<String>, toml_span::DeserError> {
+        match branch {
+            Some(branch) if branch.value.trim().is_empty() => Err(toml_span::Error::from((
+                ErrorKind::Custom("branch cannot be empty".into()),
+                branch.span,
+            ))
+            .into()),
+            branch => Ok(branch.map(|it| it.value)),
+        }
+    }
+
+    fn validate_shallow_depth(
+        shallow_depth: Option<Spanned<u32>>,
+    ) -> std::result::Result<Option<u32>, toml_span::DeserError> {
+        match shallow_depth {
+            Some(depth) if depth.value == 0 => Err(toml_span::Error::from((
+                ErrorKind::Custom("shallow_depth must be greater than 0".into()),
+                depth.span,
+            ))
+            .into()),
+            depth => Ok(depth.map(|it| it.value)),
+        }
+    }
+
+    fn validate_export_batch_size(
+        export_batch_size: Option<Spanned<usize>>,
+    ) -> std::result::Result<Option<usize>, toml_span::DeserError> {
+        match export_batch_size {
+            Some(size) if size.value == 0 || size.value > MAX_EXPORT_BATCH_SIZE => {
+                Err(toml_span::Error::from((
+                    ErrorKind::Custom(
+                        format!("export_batch_size must be from 1 to {MAX_EXPORT_BATCH_SIZE}")
+                            .into(),
+                    ),
+                    size.span,
+                ))
+                .into())
+            }
+            size => Ok(size.map(|it| it.value)),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -162,9 +310,13 @@ mod test {
                 "icons".to_owned(),
                 RemoteDto {
                     file_key: "abcdefg".to_string(),
+                    branch: None,
                     container_node_ids: NodeIdListDto::Plain(vec!["42-42".to_string()]),
                     access_token: AccessTokenDefinitionDto::Explicit("fig_123456789".to_string()),
                     default: Some(true),
+                    shallow_depth: None,
+                    export_batch_size: None,
+                    retry: None,
                     key_span: Span::new(1, 6),
                 },
             );
@@ -172,9 +324,13 @@ mod test {
                 "illustrations".to_owned(),
                 RemoteDto {
                     file_key: "hijklmno".to_string(),
+                    branch: None,
                     container_node_ids: NodeIdListDto::Plain(vec!["0-1".to_string()]),
                     access_token: AccessTokenDefinitionDto::Explicit("fig_987654321".to_string()),
                     default: None,
+                    shallow_depth: None,
+                    export_batch_size: None,
+                    retry: None,
                     key_span: Span::new(108, 121),
                 },
             );
@@ -253,15 +409,22 @@ mod test {
         // Given
         let toml = r#"
         file_key = "abcdefg"
+        branch = "Q3-redesign"
         container_node_ids = ["42-42"]
         access_token = "fig_123456789"
         default = true
+        shallow_depth = 3
+        export_batch_size = 50
         "#;
         let expected_dto = RemoteDto {
             file_key: "abcdefg".to_string(),
+            branch: Some("Q3-redesign".to_string()),
             container_node_ids: NodeIdListDto::Plain(vec!["42-42".to_string()]),
             access_token: AccessTokenDefinitionDto::Explicit("fig_123456789".to_string()),
             default: Some(true),
+            shallow_depth: Some(3),
+            export_batch_size: Some(50),
+            retry: None,
             key_span: Default::default(),
         };
 
@@ -383,6 +546,52 @@ mod test {
         }
     }
 
+    #[test]
+    fn RemoteDto__parse_remote_w_zero_shallow_depth__EXPECT__error_with_correct_span() {
+        // Given
+        let toml = unindent(
+            r#"
+                file_key = "abcdefg"
+                container_node_ids = ["42-42"]
+                shallow_depth = 0
+            "#,
+        );
+        let expected_spans = [Span::new(68, 69)];
+
+        // When
+        let mut value = toml_span::parse(&toml).unwrap();
+        let actual_err = RemoteDto::parse_with_ctx(&mut value, ()).unwrap_err();
+
+        // Then
+        assert_eq!(expected_spans.len(), actual_err.errors.len());
+        for (err, expected_span) in actual_err.errors.iter().zip(expected_spans) {
+            assert_eq!(expected_span, err.span);
+        }
+    }
+
+    #[test]
+    fn RemoteDto__parse_remote_w_too_large_export_batch_size__EXPECT__error_with_correct_span() {
+        // Given
+        let toml = unindent(
+            r#"
+                file_key = "abcdefg"
+                container_node_ids = ["42-42"]
+                export_batch_size = 1000
+            "#,
+        );
+        let expected_spans = [Span::new(72, 76)];
+
+        // When
+        let mut value = toml_span::parse(&toml).unwrap();
+        let actual_err = RemoteDto::parse_with_ctx(&mut value, ()).unwrap_err();
+
+        // Then
+        assert_eq!(expected_spans.len(), actual_err.errors.len());
+        for (err, expected_span) in actual_err.errors.iter().zip(expected_spans) {
+            assert_eq!(expected_span, err.span);
+        }
+    }
+
     #[test]
     fn RemoteDto__undeclared_keys__EXPECT__error_with_correct_span() {
         // Given
@@ -1,8 +1,15 @@
 use super::{
-    AndroidWebpProfileDtoContext, ComposeProfileDtoContext, PdfProfileDtoContext,
-    PngProfileDtoContext, ProfileDto, SvgProfileDtoContext, WebpProfileDtoContext,
+    AndroidWebpProfileDtoContext, ComposeProfileDtoContext, ExpectedDimensionsDto,
+    PdfProfileDtoContext, PngProfileDtoContext, ProfileDto, SvgProfileDtoContext,
+    WebpProfileDtoContext,
+};
+use crate::{
+    Profile,
+    parser::{
+        AndroidDrawableProfileDtoContext, FaviconProfileDtoContext, FlutterProfileDtoContext,
+        ReactProfileDtoContext,
+    },
 };
-use crate::{Profile, parser::AndroidDrawableProfileDtoContext};
 use ordermap::OrderMap;
 use std::{collections::HashSet, sync::Arc};
 use toml_span::Span;
@@ -11,6 +18,7 @@ use toml_span::Span;
 #[cfg_attr(test, derive(PartialEq, Debug))]
 pub(crate) struct ResourcesDto(pub OrderMap<String, OrderMap<String, ResourceDto>>);
 
+#[derive(Clone, Copy)]
 pub(crate) struct ResourcesDtoContext<'de> {
     pub declared_remote_ids: &'de HashSet<String>,
     pub profiles: &'de OrderMap<String, Arc<Profile>>,
@@ -19,6 +27,11 @@ pub(crate) struct ResourcesDtoContext<'de> {
 #[cfg_attr(test, derive(PartialEq, Debug))]
 pub(crate) struct ResourceDto {
     pub node_name: String,
+    /// Export the node with its full dimensions, ignoring cropping and render bounds
+    pub use_absolute_bounds: bool,
+    pub expect: Option<ExpectedDimensionsDto>,
+    /// Arbitrary tags to select resources by, e.g. `tag:critical`
+    pub tags: Vec<String>,
     pub profile: Arc<Profile>,
     pub override_profile: Option<ProfileDto>,
     pub def_span: Span,
@@ -28,6 +41,7 @@ pub(crate) struct ResourceDto {
 pub(crate) struct ResourceDtoContext<'de> {
     pub declared_remote_ids: &'de HashSet<String>,
     pub profile: &'de Arc<Profile>,
+    pub profiles: &'de OrderMap<String, Arc<Profile>>,
 }
 
 macro_rules! from_ctx_impl {
@@ -49,16 +63,24 @@ from_ctx_impl!(ResourceDtoContext, WebpProfileDtoContext);
 from_ctx_impl!(ResourceDtoContext, ComposeProfileDtoContext);
 from_ctx_impl!(ResourceDtoContext, AndroidWebpProfileDtoContext);
 from_ctx_impl!(ResourceDtoContext, AndroidDrawableProfileDtoContext);
+from_ctx_impl!(ResourceDtoContext, FlutterProfileDtoContext);
+from_ctx_impl!(ResourceDtoContext, ReactProfileDtoContext);
+from_ctx_impl!(ResourceDtoContext, FaviconProfileDtoContext);
 
 mod de {
-    use toml_span::{ErrorKind, de_helpers::TableHelper};
+    use lib_label::is_valid_tag;
+    use toml_span::{ErrorKind, Spanned, de_helpers::TableHelper};
 
     use super::*;
     use crate::{
-        ParseWithContext,
+        CanBeExtendedBy, ParseWithContext,
+        parser::util::{
+            validate_android_resource_name, validate_file_name, validate_kotlin_identifier,
+        },
         parser::{
-            AndroidDrawableProfileDto, AndroidWebpProfileDto, ComposeProfileDto, PdfProfileDto,
-            PngProfileDto, SvgProfileDto, WebpProfileDto,
+            AndroidDrawableProfileDto, AndroidWebpProfileDto, ComposeProfileDto, FaviconProfileDto,
+            FlutterProfileDto, PdfProfileDto, PngProfileDto, ReactProfileDto, SvgProfileDto,
+            VectorLimitsDto, WebpProfileDto,
         },
     };
 
@@ -94,18 +116,37 @@ mod de {
                     sections.entry(profile_name.clone()).or_default();
 
                 let mut th = TableHelper::new(resources)?;
-                for (res_name, res_value) in th.table.iter_mut() {
-                    let res_name = res_name.to_string();
-                    output.insert(
-                        res_name,
-                        ResourceDto::parse_with_ctx(
-                            res_value,
-                            ResourceDtoContext {
-                                declared_remote_ids: ctx.declared_remote_ids,
-                                profile: profile,
-                            },
-                        )?,
-                    );
+                for (res_key, res_value) in th.table.iter_mut() {
+                    let res_name = res_key.to_string();
+                    let resource = ResourceDto::parse_with_ctx(
+                        res_value,
+                        ResourceDtoContext {
+                            declared_remote_ids: ctx.declared_remote_ids,
+                            profile: profile,
+                            profiles: ctx.profiles,
+                        },
+                    )?;
+                    // validate the name of the output file, not the name of the resource
+                    let naming = match resource
+                        .override_profile
+                        .as_ref()
+                        .and_then(|it| it.naming())
+                    {
+                        Some(naming) => profile.naming().extend(naming),
+                        None => profile.naming().clone(),
+                    };
+                    let output_name = naming.apply(&res_name);
+                    if output_name.is_empty() {
+                        return Err(toml_span::Error::from((
+                            ErrorKind::Custom(
+                                "naming rules of the profile leave nothing of the name".into(),
+                            ),
+                            res_key.span,
+                        ))
+                        .into());
+                    }
+                    validate_output_name(profile, &res_name, &output_name, res_key.span)?;
+                    output.insert(res_name, resource);
                 }
             }
 
@@ -113,6 +154,46 @@ mod de {
         }
     }
 
+    /// Checks the output name against the rules of the platform of the profile
+    fn validate_output_name(
+        profile: &Profile,
+        res_name: &str,
+        output_name: &str,
+        span: toml_span::Span,
+    ) -> std::result::Result<(), toml_span::DeserError> {
+        match profile {
+            // output files of android profiles are named after the resource
+            Profile::AndroidWebp(_) | Profile::AndroidDrawable(_) => {
+                validate_android_resource_name(output_name, span)
+            }
+            // names of the resources themselves are escaped with backticks by the import,
+            // but the ones produced by the naming rules are expected to be valid as is
+            Profile::Compose(_) if output_name == res_name => Ok(()),
+            Profile::Compose(p) => {
+                let names = match &p.variants {
+                    None => vec![output_name.to_owned()],
+                    Some(variants) => variants
+                        .active()
+                        .map(|(_, v)| v.output_name.as_ref().replace("{base}", output_name))
+                        .collect(),
+                };
+                for name in names {
+                    let property_name = match &p.property_name_template {
+                        Some(template) => template.replace("{name}", &name),
+                        None => name,
+                    };
+                    validate_kotlin_identifier(
+                        &property_name,
+                        p.identifier_prefix.as_deref(),
+                        span,
+                    )?;
+                }
+                Ok(())
+            }
+            _ => validate_file_name(output_name, span),
+        }
+    }
+
     impl<'de> ParseWithContext<'de> for ResourceDto {
         type Context = ResourceDtoContext<'de>;
 
@@ -121,15 +202,35 @@ mod de {
             ctx: Self::Context,
         ) -> std::result::Result<Self, toml_span::DeserError> {
             // region: extract
-            let (node_name, override_profile) = match value.as_str() {
-                Some(value) => (value.to_owned(), None),
+            let (node_name, use_absolute_bounds, expect, tags, override_profile) = match value
+                .as_str()
+            {
+                Some(value) => (value.to_owned(), false, None, Vec::new(), None),
                 None => {
                     let mut th = TableHelper::new(value)?;
                     let name = th.required::<String>("name")?;
+                    let use_absolute_bounds = th.optional("use_absolute_bounds").unwrap_or(false);
+                    let expect = th.optional::<ExpectedDimensionsDto>("expect");
+                    let tags = th
+                        .optional::<Vec<Spanned<String>>>("tags")
+                        .unwrap_or_default();
                     th.finalize(Some(value))?;
+                    for tag in &tags {
+                        if !is_valid_tag(&tag.value) {
+                            return Err(toml_span::Error::from((
+                                ErrorKind::Custom(
+                                    "tag must be non-empty and consist of letters, digits, `-` and `_`"
+                                        .into(),
+                                ),
+                                tag.span,
+                            ))
+                            .into());
+                        }
+                    }
+                    let tags = tags.into_iter().map(|it| it.value).collect::<Vec<_>>();
 
                     use Profile::*;
-                    let override_profile = match ctx.profile.as_ref() {
+                    let mut override_profile = match ctx.profile.as_ref() {
                         Png(_) => {
                             ProfileDto::Png(PngProfileDto::parse_with_ctx(value, ctx.into())?)
                         }
@@ -152,17 +253,245 @@ mod de {
                         AndroidDrawable(_) => ProfileDto::AndroidDrawable(
                             AndroidDrawableProfileDto::parse_with_ctx(value, ctx.into())?,
                         ),
+                        Flutter(_) => ProfileDto::Flutter(FlutterProfileDto::parse_with_ctx(
+                            value,
+                            ctx.into(),
+                        )?),
+                        React(_) => {
+                            ProfileDto::React(ReactProfileDto::parse_with_ctx(value, ctx.into())?)
+                        }
+                        Favicon(_) => ProfileDto::Favicon(FaviconProfileDto::parse_with_ctx(
+                            value,
+                            ctx.into(),
+                        )?),
                     };
-                    (name, Some(override_profile))
+                    resolve_fallback_profile(&mut override_profile, ctx.profiles)?;
+                    (
+                        name,
+                        use_absolute_bounds,
+                        expect,
+                        tags,
+                        Some(override_profile),
+                    )
                 }
             };
             // endregion: extract
             Ok(Self {
                 node_name,
+                use_absolute_bounds,
+                expect,
+                tags,
                 profile: ctx.profile.clone(),
                 override_profile,
                 def_span: value.span,
             })
         }
     }
+
+    fn resolve_fallback_profile(
+        profile: &mut ProfileDto,
+        profiles: &OrderMap<String, Arc<Profile>>,
+    ) -> std::result::Result<(), toml_span::DeserError> {
+        let Some(fallback) = profile
+            .vector_limits_mut()
+            .and_then(VectorLimitsDto::fallback_mut)
+        else {
+            return Ok(());
+        };
+        let raster_profiles = profiles
+            .iter()
+            .filter(|(_, profile)| !profile.vector())
+            .map(|(id, _)| id.as_str())
+            .collect::<Vec<_>>();
+        fallback.validate(&raster_profiles)?;
+        fallback.profile = profiles.get(&fallback.name).cloned();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use crate::{ComposeProfile, NameCase, NamingRules, ParseWithContext, PngProfile, SvgProfile};
+
+    fn parse(toml: &str) -> ResourceDto {
+        let declared_remote_ids: HashSet<_> = ["figma".to_string()].into_iter().collect();
+        let profile = Arc::new(Profile::Svg(SvgProfile::default()));
+        let profiles = OrderMap::new();
+        let mut value = toml_span::parse(toml).unwrap();
+        let mut th = toml_span::de_helpers::TableHelper::new(&mut value).unwrap();
+        let (_, res_value) = th.table.iter_mut().next().unwrap();
+        ResourceDto::parse_with_ctx(
+            res_value,
+            ResourceDtoContext {
+                declared_remote_ids: &declared_remote_ids,
+                profile: &profile,
+                profiles: &profiles,
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn ResourceDto__node_name_only__EXPECT__render_bounds() {
+        // Given
+        let toml = r#"ic_star = "Star""#;
+
+        // When
+        let dto = parse(toml);
+
+        // Then
+        assert_eq!("Star", dto.node_name);
+        assert!(!dto.use_absolute_bounds);
+        assert!(dto.override_profile.is_none());
+    }
+
+    #[test]
+    fn ResourceDto__expected_dimensions__EXPECT__parsed_dimensions() {
+        // Given
+        let toml = r#"ic_star = { name = "Star", expect = { width = 24, height = 24 } }"#;
+
+        // When
+        let dto = parse(toml);
+
+        // Then
+        assert_eq!("Star", dto.node_name);
+        assert_eq!(
+            Some(ExpectedDimensionsDto {
+                width: Some(24.0),
+                height: Some(24.0),
+                aspect_ratio: None,
+            }),
+            dto.expect,
+        );
+    }
+
+    #[test]
+    fn ResourceDto__tags__EXPECT__tags_without_profile_override() {
+        // Given
+        let toml = r#"ic_star = { name = "Star", tags = ["critical", "marketing"] }"#;
+
+        // When
+        let dto = parse(toml);
+
+        // Then
+        assert_eq!(vec!["critical", "marketing"], dto.tags);
+        assert_eq!(
+            Some(ProfileDto::Svg(crate::parser::SvgProfileDto {
+                remote_id: None,
+                output_dir: None,
+                variants: None,
+                post_process: None,
+                naming: None,
+            })),
+            dto.override_profile,
+        );
+    }
+
+    #[test]
+    fn ResourceDto__use_absolute_bounds__EXPECT__absolute_bounds_without_profile_override() {
+        // Given
+        let toml = r#"ic_star = { name = "Star", use_absolute_bounds = true }"#;
+
+        // When
+        let dto = parse(toml);
+
+        // Then
+        assert_eq!("Star", dto.node_name);
+        assert!(dto.use_absolute_bounds);
+        assert_eq!(
+            Some(ProfileDto::Svg(crate::parser::SvgProfileDto {
+                remote_id: None,
+                output_dir: None,
+                variants: None,
+                post_process: None,
+                naming: None,
+            })),
+            dto.override_profile,
+        );
+    }
+
+    fn parse_resources(
+        toml: &str,
+        profile: Profile,
+    ) -> std::result::Result<ResourcesDto, toml_span::DeserError> {
+        let declared_remote_ids = HashSet::new();
+        let profiles: OrderMap<_, _> = [("icons".to_owned(), Arc::new(profile))]
+            .into_iter()
+            .collect();
+        let mut value = toml_span::parse(toml).unwrap();
+        ResourcesDto::parse_with_ctx(
+            &mut value,
+            ResourcesDtoContext {
+                declared_remote_ids: &declared_remote_ids,
+                profiles: &profiles,
+            },
+        )
+    }
+
+    fn naming(case: NameCase, prefix: &str) -> NamingRules {
+        NamingRules {
+            case: Some(case),
+            prefix: prefix.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn ResourcesDto__compose_naming_produces_valid_identifier__EXPECT__ok() {
+        // Given
+        let profile = Profile::Compose(ComposeProfile {
+            naming: naming(NameCase::Pascal, ""),
+            ..Default::default()
+        });
+
+        // When
+        let result = parse_resources("[icons]\nic_star = \"Star\"\n", profile);
+
+        // Then
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn ResourcesDto__compose_naming_in_kebab_case__EXPECT__error() {
+        // Given
+        let profile = Profile::Compose(ComposeProfile {
+            naming: naming(NameCase::Kebab, ""),
+            ..Default::default()
+        });
+
+        // When
+        let result = parse_resources("[icons]\nIcStar = \"Star\"\n", profile);
+
+        // Then
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn ResourcesDto__compose_resource_name_without_naming__EXPECT__left_to_escaping() {
+        // Given
+        let profile = Profile::Compose(ComposeProfile::default());
+
+        // When
+        let result = parse_resources("[icons]\nic-star = \"Star\"\n", profile);
+
+        // Then
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn ResourcesDto__file_naming_with_path_separator__EXPECT__error() {
+        // Given
+        let profile = Profile::Png(PngProfile {
+            naming: naming(NameCase::Snake, "icons/"),
+            ..Default::default()
+        });
+
+        // When
+        let result = parse_resources("[icons]\nIcStar = \"Star\"\n", profile);
+
+        // Then
+        assert!(result.is_err());
+    }
 }
@@ -0,0 +1,182 @@
+use crate::{RetryCondition, RetryPolicy};
+use std::time::Duration;
+
+#[derive(Default, Clone)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub(crate) struct RetryPolicyDto {
+    pub max_attempts: Option<usize>,
+    pub initial_backoff: Option<Duration>,
+    pub max_backoff: Option<Duration>,
+    pub retry_on: Option<Vec<RetryCondition>>,
+}
+
+impl From<RetryPolicyDto> for RetryPolicy {
+    fn from(value: RetryPolicyDto) -> Self {
+        let default = RetryPolicy::default();
+        Self {
+            max_attempts: value.max_attempts.unwrap_or(default.max_attempts),
+            initial_backoff: value.initial_backoff.unwrap_or(default.initial_backoff),
+            max_backoff: value.max_backoff.unwrap_or(default.max_backoff),
+            retry_on: value.retry_on.unwrap_or(default.retry_on),
+        }
+    }
+}
+
+mod de {
+    use super::*;
+    use toml_span::de_helpers::TableHelper;
+    use toml_span::{Deserialize, ErrorKind, Span};
+
+    impl<'de> Deserialize<'de> for RetryPolicyDto {
+        fn deserialize(value: &mut toml_span::Value<'de>) -> Result<Self, toml_span::DeserError> {
+            // region: extract
+            let mut th = TableHelper::new(value)?;
+            let max_attempts = th.optional_s::<usize>("max_attempts");
+            let initial_backoff = th.optional_s::<String>("initial_backoff");
+            let max_backoff = th.optional_s::<String>("max_backoff");
+            let retry_on = th.optional_s::<Vec<toml_span::Spanned<String>>>("retry_on");
+            th.finalize(None)?;
+            // endregion: extract
+
+            // region: validate
+            let max_attempts = match max_attempts {
+                Some(attempts) if attempts.value == 0 => {
+                    return Err(toml_span::Error::from((
+                        ErrorKind::Custom("max_attempts must be greater than 0".into()),
+                        attempts.span,
+                    ))
+                    .into());
+                }
+                attempts => attempts.map(|it| it.value),
+            };
+            let initial_backoff = initial_backoff
+                .map(|it| parse_duration(&it.value, it.span))
+                .transpose()?;
+            let max_backoff_span = max_backoff.as_ref().map(|it| it.span);
+            let max_backoff = max_backoff
+                .map(|it| parse_duration(&it.value, it.span))
+                .transpose()?;
+            if let (Some(initial), Some(max), Some(span)) =
+                (initial_backoff, max_backoff, max_backoff_span)
+            {
+                if initial > max {
+                    return Err(toml_span::Error::from((
+                        ErrorKind::Custom("max_backoff cannot be less than initial_backoff".into()),
+                        span,
+                    ))
+                    .into());
+                }
+            }
+            let retry_on = match retry_on {
+                Some(conditions) => Some(
+                    conditions
+                        .value
+                        .into_iter()
+                        .map(|it| parse_condition(&it.value, it.span))
+                        .collect::<Result<Vec<_>, _>>()?,
+                ),
+                None => None,
+            };
+            // endregion: validate
+
+            Ok(Self {
+                max_attempts,
+                initial_backoff,
+                max_backoff,
+                retry_on,
+            })
+        }
+    }
+
+    /// Durations are written with a unit: `500ms`, `2s` or `1m`
+    fn parse_duration(value: &str, span: Span) -> Result<Duration, toml_span::DeserError> {
+        let (number, unit) = value
+            .find(|c: char| !c.is_ascii_digit())
+            .map(|idx| value.split_at(idx))
+            .unwrap_or((value, ""));
+        let duration = match (number.parse::<u64>(), unit) {
+            (Ok(n), "ms") => Duration::from_millis(n),
+            (Ok(n), "s") => Duration::from_secs(n),
+            (Ok(n), "m") => Duration::from_secs(n * 60),
+            _ => {
+                return Err(toml_span::Error::from((
+                    ErrorKind::Custom(
+                        format!("invalid duration `{value}`, expected e.g. `500ms`, `2s` or `1m`")
+                            .into(),
+                    ),
+                    span,
+                ))
+                .into());
+            }
+        };
+        Ok(duration)
+    }
+
+    fn parse_condition(value: &str, span: Span) -> Result<RetryCondition, toml_span::DeserError> {
+        match value {
+            "429" => Ok(RetryCondition::RateLimit),
+            "5xx" => Ok(RetryCondition::ServerError),
+            "timeout" => Ok(RetryCondition::Timeout),
+            _ => Err(toml_span::Error::from((
+                ErrorKind::UnexpectedValue {
+                    expected: &["429", "5xx", "timeout"],
+                    value: Some(value.to_owned()),
+                },
+                span,
+            ))
+            .into()),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use toml_span::Span;
+    use toml_span::de_helpers::TableHelper;
+
+    #[test]
+    fn RetryPolicyDto__valid_toml__EXPECT__valid_dto() {
+        // Given
+        let toml = r#"
+        retry = { max_attempts = 5, initial_backoff = "500ms", max_backoff = "1m", retry_on = ["429", "timeout"] }
+        "#;
+        let expected_dto = RetryPolicyDto {
+            max_attempts: Some(5),
+            initial_backoff: Some(Duration::from_millis(500)),
+            max_backoff: Some(Duration::from_secs(60)),
+            retry_on: Some(vec![RetryCondition::RateLimit, RetryCondition::Timeout]),
+        };
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let mut th = TableHelper::new(&mut value).unwrap();
+        let actual_dto = th.required::<RetryPolicyDto>("retry").unwrap();
+
+        // Then
+        assert_eq!(expected_dto, actual_dto);
+    }
+
+    #[test]
+    fn RetryPolicyDto__invalid_toml__EXPECT__error_with_correct_span() {
+        // Given
+        let toml = r#"
+        r1 = { max_attempts = 0 }
+        r2 = { initial_backoff = "5 sec" }
+        r3 = { retry_on = ["5xx", "4xx"] }
+        "#;
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let mut th = TableHelper::new(&mut value).unwrap();
+        let r1 = th.required::<RetryPolicyDto>("r1").unwrap_err();
+        let r2 = th.required::<RetryPolicyDto>("r2").unwrap_err();
+        let r3 = th.required::<RetryPolicyDto>("r3").unwrap_err();
+
+        // Then
+        assert_eq!(Span::new(31, 32), r1.span);
+        assert_eq!(Span::new(69, 74), r2.span);
+        assert_eq!(Span::new(113, 116), r3.span);
+    }
+}
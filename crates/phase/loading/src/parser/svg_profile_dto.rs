@@ -1,5 +1,5 @@
-use super::VariantsDto;
-use crate::CanBeExtendedBy;
+use super::{NamingRulesDto, VariantsDto};
+use crate::{CanBeExtendedBy, PostProcessCommand};
 use std::{collections::HashSet, path::PathBuf};
 
 #[derive(Default)]
@@ -8,6 +8,8 @@ pub(crate) struct SvgProfileDto {
     pub remote_id: Option<String>,
     pub output_dir: Option<PathBuf>,
     pub variants: Option<VariantsDto>,
+    pub post_process: Option<PostProcessCommand>,
+    pub naming: Option<NamingRulesDto>,
 }
 
 impl CanBeExtendedBy<Self> for SvgProfileDto {
@@ -29,6 +31,15 @@ impl CanBeExtendedBy<Self> for SvgProfileDto {
                 (None, Some(this)) => Some(this.clone()),
                 _ => None,
             },
+            post_process: another
+                .post_process
+                .as_ref()
+                .or(self.post_process.as_ref())
+                .cloned(),
+            naming: match (self.naming.as_ref(), another.naming.as_ref()) {
+                (Some(this), Some(another)) => Some(this.extend(another)),
+                (this, another) => another.or(this).cloned(),
+            },
         }
     }
 }
@@ -55,6 +66,8 @@ mod de {
             let remote_id = th.optional_s::<String>("remote");
             let output_dir = th.optional::<String>("output_dir").map(PathBuf::from);
             let variants = th.optional::<VariantsDto>("variants");
+            let post_process = th.optional::<PostProcessCommand>("post_process");
+            let naming = th.optional::<NamingRulesDto>("naming");
             th.finalize(None)?;
             // endregion: extract
 
@@ -66,6 +79,8 @@ mod de {
                 remote_id,
                 output_dir,
                 variants,
+                post_process,
+                naming,
             })
         }
     }
@@ -90,6 +105,7 @@ mod test {
         variants.small = { output_name = "{base}Small", figma_name = "{base} / small", scale = 1.0 }
         variants.big = { output_name = "{base}Big", figma_name = "{base} / big", scale = 2.0 }
         variants.use = ["small", "big"]
+        post_process = ["svgo", "--multipass", "{output}"]
         "#;
         let declared_remote_ids: HashSet<_> = ["figma".to_string()].into_iter().collect();
         let expected_dto = SvgProfileDto {
@@ -103,6 +119,11 @@ mod test {
                 }),
                 use_variants: Some(vec!["small".to_string(), "big".to_string()]),
             }),
+            post_process: Some(PostProcessCommand(vec![
+                "svgo".to_string(),
+                "--multipass".to_string(),
+                "{output}".to_string(),
+            ])),
         };
 
         // When
@@ -126,6 +147,8 @@ mod test {
             remote_id: None,
             output_dir: None,
             variants: None,
+            post_process: None,
+            naming: None,
         };
 
         // When
@@ -210,6 +233,8 @@ mod test {
                 all_variants: Some(OrderMap::new()),
                 use_variants: None,
             }),
+            post_process: None,
+            naming: None,
         };
         let second = SvgProfileDto {
             remote_id: None,
@@ -218,6 +243,8 @@ mod test {
                 all_variants: None,
                 use_variants: Some(Vec::new()),
             }),
+            post_process: None,
+            naming: None,
         };
 
         // When
@@ -232,6 +259,8 @@ mod test {
                     all_variants: Some(OrderMap::new()),
                     use_variants: Some(Vec::new()),
                 }),
+                post_process: None,
+                naming: None,
             },
             third,
         );
@@ -1,5 +1,20 @@
 use std::collections::HashSet;
-use toml_span::{ErrorKind, Spanned};
+use toml_span::{ErrorKind, Span, Spanned};
+
+/// Java keywords and literals, which cannot be used as fields of the `R` class
+const JAVA_RESERVED_WORDS: &str = "abstract assert boolean break byte case catch char class \
+    const continue default do double else enum extends false final finally float for goto if \
+    implements import instanceof int interface long native new null package private protected \
+    public return short static strictfp super switch synchronized this throw throws transient \
+    true try void volatile while";
+
+/// Hard keywords of Kotlin, which cannot be used as names of properties without backticks
+const KOTLIN_HARD_KEYWORDS: &str = "as break class continue do else false for fun if in \
+    interface is null object package return super this throw true try typealias typeof \
+    val var when while";
+
+/// Characters which are not allowed in file names on at least one of the platforms
+const FORBIDDEN_FILE_NAME_CHARS: &[char] = &['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
 
 pub(crate) fn validate_remote_id(
     remote_id: Option<Spanned<String>>,
@@ -43,6 +58,88 @@ pub(crate) fn validate_non_empty<T>(
     Ok(list.map(|it| it.value))
 }
 
+/// Checks that the name can be used as a file-based Android resource name,
+/// otherwise AAPT rejects the resource during the build of the app
+pub(crate) fn validate_android_resource_name(
+    name: &str,
+    span: Span,
+) -> std::result::Result<(), toml_span::DeserError> {
+    let reason = if name.is_empty() {
+        Some("the name cannot be empty")
+    } else if name
+        .chars()
+        .any(|c| !(c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'))
+    {
+        Some("only lowercase latin letters, digits and underscores are allowed")
+    } else if name.starts_with(|c: char| c.is_ascii_digit()) {
+        Some("the name cannot start with a digit")
+    } else if name == "_" || JAVA_RESERVED_WORDS.split_whitespace().any(|it| it == name) {
+        Some("the name is a reserved Java keyword")
+    } else {
+        None
+    };
+    match reason {
+        Some(reason) => Err(toml_span::Error::from((
+            ErrorKind::Custom(format!("invalid Android resource name '{name}': {reason}").into()),
+            span,
+        ))
+        .into()),
+        None => Ok(()),
+    }
+}
+
+/// Checks that the name can be used as a Kotlin property as is,
+/// otherwise the generated code doesn't compile
+pub(crate) fn validate_kotlin_identifier(
+    name: &str,
+    identifier_prefix: Option<&str>,
+    span: Span,
+) -> std::result::Result<(), toml_span::DeserError> {
+    let reason = if name.is_empty() {
+        Some("the name cannot be empty")
+    } else if name.chars().any(|c| !(c.is_alphanumeric() || c == '_')) {
+        Some("only letters, digits and underscores are allowed")
+    } else if name.starts_with(|c: char| c.is_ascii_digit()) && identifier_prefix.is_none() {
+        Some("the name cannot start with a digit unless `identifier_prefix` is set")
+    } else if KOTLIN_HARD_KEYWORDS.split_whitespace().any(|it| it == name) {
+        Some("the name is a reserved Kotlin keyword")
+    } else {
+        None
+    };
+    match reason {
+        Some(reason) => Err(toml_span::Error::from((
+            ErrorKind::Custom(format!("invalid Kotlin property name '{name}': {reason}").into()),
+            span,
+        ))
+        .into()),
+        None => Ok(()),
+    }
+}
+
+/// Checks that the name can be used as a file name on every platform
+pub(crate) fn validate_file_name(
+    name: &str,
+    span: Span,
+) -> std::result::Result<(), toml_span::DeserError> {
+    let reason = if name.is_empty() {
+        Some("the name cannot be empty")
+    } else if name.contains(FORBIDDEN_FILE_NAME_CHARS) || name.chars().any(char::is_control) {
+        Some("path separators and special characters are not allowed")
+    } else if name == "." || name == ".." || name.ends_with(['.', ' ']) {
+        Some("the name cannot end with a dot or a space")
+    } else {
+        None
+    };
+    match reason {
+        Some(reason) => Err(toml_span::Error::from((
+            ErrorKind::Custom(format!("invalid file name '{name}': {reason}").into()),
+            span,
+        ))
+        .into()),
+        None => Ok(()),
+    }
+}
+
 #[cfg(test)]
 #[allow(non_snake_case)]
 mod test {
@@ -57,10 +154,10 @@ mod test {
             value: vec![1, 2, 3],
             span: Span::new(0, 1),
         });
-        
+
         // When
         let result = validate_non_empty(valid_value, || "".to_string());
-        
+
         // Then
         assert!(result.is_ok());
     }
@@ -72,11 +169,66 @@ mod test {
             value: vec![],
             span: Span::new(0, 1),
         });
-        
+
         // When
         let result = validate_non_empty(valid_value, || "ERROR".to_string());
-        
+
         // Then
         assert!(result.is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn validate_android_resource_name__valid_names__ok() {
+        for name in ["ic_close", "_private", "logo2x", "classic"] {
+            assert!(validate_android_resource_name(name, Span::new(0, 1)).is_ok());
+        }
+    }
+
+    #[test]
+    fn validate_android_resource_name__invalid_names__err() {
+        for name in [
+            "IcClose", "ic-close", "ic close", "24_close", "class", "_", "",
+        ] {
+            let result = validate_android_resource_name(name, Span::new(0, 1));
+            assert!(result.is_err(), "{name} must be invalid");
+        }
+    }
+
+    #[test]
+    fn validate_kotlin_identifier__valid_names__ok() {
+        for name in [
+            "IcClose",
+            "icClose",
+            "ic_close",
+            "_private",
+            "Ярлык",
+            "classic",
+        ] {
+            assert!(validate_kotlin_identifier(name, None, Span::new(0, 1)).is_ok());
+        }
+        assert!(validate_kotlin_identifier("24Close", Some("Ic"), Span::new(0, 1)).is_ok());
+    }
+
+    #[test]
+    fn validate_kotlin_identifier__invalid_names__err() {
+        for name in ["ic-close", "ic close", "Ic.Close", "24Close", "class", ""] {
+            let result = validate_kotlin_identifier(name, None, Span::new(0, 1));
+            assert!(result.is_err(), "{name} must be invalid");
+        }
+    }
+
+    #[test]
+    fn validate_file_name__valid_names__ok() {
+        for name in ["ic-close", "Ic Close", "ic_close@2x", ".hidden"] {
+            assert!(validate_file_name(name, Span::new(0, 1)).is_ok());
+        }
+    }
+
+    #[test]
+    fn validate_file_name__invalid_names__err() {
+        for name in ["icons/close", "ic:close", "ic?", "close.", "..", ""] {
+            let result = validate_file_name(name, Span::new(0, 1));
+            assert!(result.is_err(), "{name} must be invalid");
+        }
+    }
+}
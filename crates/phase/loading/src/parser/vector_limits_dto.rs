@@ -0,0 +1,211 @@
+use std::sync::Arc;
+
+use crate::{CanBeExtendedBy, Profile};
+use toml_span::{ErrorKind, Span};
+
+#[derive(Default, Clone)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub(crate) struct VectorLimitsDto {
+    pub max_paths: Option<usize>,
+    pub max_commands: Option<usize>,
+    pub on_exceeded: Option<LimitActionDto>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub(crate) enum LimitActionDto {
+    Warn,
+    Fail,
+    Fallback(FallbackProfileDto),
+}
+
+/// Raster profile used instead of the vector one, when the limits are exceeded
+#[derive(Clone)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub(crate) struct FallbackProfileDto {
+    pub name: String,
+    pub span: Span,
+    /// Filled in as soon as the referenced profile is known
+    pub profile: Option<Arc<Profile>>,
+}
+
+impl VectorLimitsDto {
+    pub fn fallback_mut(&mut self) -> Option<&mut FallbackProfileDto> {
+        match &mut self.on_exceeded {
+            Some(LimitActionDto::Fallback(fallback)) => Some(fallback),
+            _ => None,
+        }
+    }
+}
+
+impl FallbackProfileDto {
+    /// Checks that the fallback refers to one of the declared raster profiles
+    pub fn validate(&self, raster_profiles: &[&str]) -> Result<(), toml_span::DeserError> {
+        if raster_profiles.contains(&self.name.as_str()) {
+            return Ok(());
+        }
+        let expected = raster_profiles
+            .iter()
+            .map(|it| format!("`{it}`"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Err(toml_span::Error::from((
+            ErrorKind::Custom(
+                format!("fallback profile must be one of the raster profiles: [{expected}]").into(),
+            ),
+            self.span,
+        ))
+        .into())
+    }
+}
+
+impl CanBeExtendedBy<Self> for VectorLimitsDto {
+    fn extend(&self, another: &Self) -> Self {
+        Self {
+            max_paths: another.max_paths.or(self.max_paths),
+            max_commands: another.max_commands.or(self.max_commands),
+            on_exceeded: another
+                .on_exceeded
+                .as_ref()
+                .or(self.on_exceeded.as_ref())
+                .cloned(),
+        }
+    }
+}
+
+mod de {
+    use super::*;
+    use toml_span::Deserialize;
+    use toml_span::de_helpers::TableHelper;
+
+    impl<'de> Deserialize<'de> for VectorLimitsDto {
+        fn deserialize(value: &mut toml_span::Value<'de>) -> Result<Self, toml_span::DeserError> {
+            // region: extract
+            let mut th = TableHelper::new(value)?;
+            let max_paths = th.optional_s::<usize>("max_paths");
+            let max_commands = th.optional_s::<usize>("max_commands");
+            let on_exceeded = th.optional_s::<String>("on_exceeded");
+            let fallback = th.optional_s::<String>("fallback");
+            th.finalize(None)?;
+            // endregion: extract
+
+            // region: validate
+            for limit in [&max_paths, &max_commands].into_iter().flatten() {
+                if limit.value == 0 {
+                    return Err(toml_span::Error::from((
+                        ErrorKind::Custom("limit must be greater than zero".into()),
+                        limit.span,
+                    ))
+                    .into());
+                }
+            }
+            let on_exceeded = match (on_exceeded, fallback) {
+                (Some(action), Some(_)) => {
+                    return Err(toml_span::Error::from((
+                        ErrorKind::Custom(
+                            "`on_exceeded` cannot be used together with `fallback`".into(),
+                        ),
+                        action.span,
+                    ))
+                    .into());
+                }
+                (Some(action), None) => match action.value.as_str() {
+                    "warn" => Some(LimitActionDto::Warn),
+                    "fail" => Some(LimitActionDto::Fail),
+                    _ => {
+                        return Err(toml_span::Error::from((
+                            ErrorKind::UnexpectedValue {
+                                expected: &["warn", "fail"],
+                                value: Some(action.value),
+                            },
+                            action.span,
+                        ))
+                        .into());
+                    }
+                },
+                (None, Some(fallback)) => Some(LimitActionDto::Fallback(FallbackProfileDto {
+                    name: fallback.value,
+                    span: fallback.span,
+                    profile: None,
+                })),
+                (None, None) => None,
+            };
+            // endregion: validate
+
+            Ok(Self {
+                max_paths: max_paths.map(|it| it.value),
+                max_commands: max_commands.map(|it| it.value),
+                on_exceeded,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+    use toml_span::de_helpers::TableHelper;
+
+    #[test]
+    fn VectorLimitsDto__valid_toml__EXPECT__valid_dto() {
+        // Given
+        let toml = r#"
+        limits = { max_paths = 300, max_commands = 5000, fallback = "webp" }
+        "#;
+        let expected_dto = VectorLimitsDto {
+            max_paths: Some(300),
+            max_commands: Some(5000),
+            on_exceeded: Some(LimitActionDto::Fallback(FallbackProfileDto {
+                name: "webp".to_string(),
+                span: Span::new(70, 74),
+                profile: None,
+            })),
+        };
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let mut th = TableHelper::new(&mut value).unwrap();
+        let actual_dto = th.required::<VectorLimitsDto>("limits").unwrap();
+
+        // Then
+        assert_eq!(expected_dto, actual_dto);
+    }
+
+    #[test]
+    fn VectorLimitsDto__invalid_toml__EXPECT__error() {
+        // Given
+        let toml = r#"
+        l1 = { on_exceeded = "warn", fallback = "webp" }
+        l2 = { on_exceeded = "ignore" }
+        l3 = { max_paths = 0 }
+        "#;
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let mut th = TableHelper::new(&mut value).unwrap();
+
+        // Then
+        assert!(th.required::<VectorLimitsDto>("l1").is_err());
+        assert!(th.required::<VectorLimitsDto>("l2").is_err());
+        assert!(th.required::<VectorLimitsDto>("l3").is_err());
+    }
+
+    #[test]
+    fn FallbackProfileDto__vector_profile__EXPECT__error_with_correct_span() {
+        // Given
+        let fallback = FallbackProfileDto {
+            name: "compose".to_string(),
+            span: Span::new(10, 17),
+            profile: None,
+        };
+
+        // When
+        let actual_err = fallback.validate(&["png", "webp"]).unwrap_err();
+
+        // Then
+        assert_eq!(1, actual_err.errors.len());
+        assert_eq!(Span::new(10, 17), actual_err.errors[0].span);
+        assert!(fallback.validate(&["compose"]).is_ok());
+    }
+}
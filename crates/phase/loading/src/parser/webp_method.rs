@@ -0,0 +1,53 @@
+mod de {
+    use toml_span::{Deserialize, ErrorKind};
+
+    use crate::WebpMethod;
+
+    impl<'de> Deserialize<'de> for WebpMethod {
+        fn deserialize(value: &mut toml_span::Value<'de>) -> Result<Self, toml_span::DeserError> {
+            match value.take() {
+                toml_span::value::ValueInner::Integer(method @ 0..=6) => {
+                    Ok(WebpMethod(method as u8))
+                }
+                _ => Err(toml_span::Error {
+                    kind: ErrorKind::Custom("webp method must be an integer from 0 to 6".into()),
+                    span: value.span,
+                    line_info: None,
+                }
+                .into()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+
+    use toml_span::de_helpers::TableHelper;
+
+    use crate::WebpMethod;
+
+    #[test]
+    fn WebpMethod__valid_toml__EXPECT__valid_value() {
+        // Given
+        let toml = r#"
+        method1 = 0
+        method2 = 6
+        method3 = -1
+        method4 = 7
+        method5 = 2.5
+        "#;
+
+        // When
+        let mut value = toml_span::parse(toml).unwrap();
+        let mut th = TableHelper::new(&mut value).unwrap();
+
+        // Then
+        assert_eq!(WebpMethod(0), th.required::<WebpMethod>("method1").unwrap());
+        assert_eq!(WebpMethod(6), th.required::<WebpMethod>("method2").unwrap());
+        assert!(th.required::<WebpMethod>("method3").is_err());
+        assert!(th.required::<WebpMethod>("method4").is_err());
+        assert!(th.required::<WebpMethod>("method5").is_err());
+    }
+}
@@ -1,8 +1,8 @@
 use std::{collections::HashSet, path::PathBuf};
 
-use crate::{CanBeExtendedBy, ExportScale, WebpQuality};
+use crate::{CanBeExtendedBy, ExportScale, PostProcessCommand, WebpMethod, WebpQuality};
 
-use super::VariantsDto;
+use super::{NamingRulesDto, VariantsDto};
 
 #[derive(Default)]
 #[cfg_attr(test, derive(PartialEq, Debug))]
@@ -10,9 +10,14 @@ pub(crate) struct WebpProfileDto {
     pub remote_id: Option<String>,
     pub scale: Option<ExportScale>,
     pub quality: Option<WebpQuality>,
+    pub lossless: Option<bool>,
+    pub alpha_quality: Option<WebpQuality>,
+    pub method: Option<WebpMethod>,
     pub output_dir: Option<PathBuf>,
     pub variants: Option<VariantsDto>,
     pub legacy_loader: Option<bool>,
+    pub post_process: Option<PostProcessCommand>,
+    pub naming: Option<NamingRulesDto>,
 }
 
 impl CanBeExtendedBy<Self> for WebpProfileDto {
@@ -25,6 +30,9 @@ impl CanBeExtendedBy<Self> for WebpProfileDto {
                 .cloned(),
             scale: another.scale.or(self.scale),
             quality: another.quality.or(self.quality),
+            lossless: another.lossless.or(self.lossless),
+            alpha_quality: another.alpha_quality.or(self.alpha_quality),
+            method: another.method.or(self.method),
             output_dir: another
                 .output_dir
                 .as_ref()
@@ -37,6 +45,15 @@ impl CanBeExtendedBy<Self> for WebpProfileDto {
                 _ => None,
             },
             legacy_loader: another.legacy_loader.or(self.legacy_loader),
+            post_process: another
+                .post_process
+                .as_ref()
+                .or(self.post_process.as_ref())
+                .cloned(),
+            naming: match (self.naming.as_ref(), another.naming.as_ref()) {
+                (Some(this), Some(another)) => Some(this.extend(another)),
+                (this, another) => another.or(this).cloned(),
+            },
         }
     }
 }
@@ -48,7 +65,7 @@ pub(crate) struct WebpProfileDtoContext<'a> {
 mod de {
     use super::*;
     use crate::parser::util::validate_remote_id;
-    use crate::{ExportScale, ParseWithContext, WebpQuality};
+    use crate::{ExportScale, ParseWithContext, WebpMethod, WebpQuality};
     use toml_span::de_helpers::TableHelper;
 
     impl<'de> ParseWithContext<'de> for WebpProfileDto {
@@ -63,9 +80,14 @@ mod de {
             let remote_id = th.optional_s::<String>("remote");
             let scale = th.optional::<ExportScale>("scale");
             let quality = th.optional::<WebpQuality>("quality");
+            let lossless = th.optional::<bool>("lossless");
+            let alpha_quality = th.optional::<WebpQuality>("alpha_quality");
+            let method = th.optional::<WebpMethod>("method");
             let output_dir = th.optional::<String>("output_dir").map(PathBuf::from);
             let variants = th.optional::<VariantsDto>("variants");
             let legacy_loader = th.optional::<bool>("legacy_loader");
+            let post_process = th.optional::<PostProcessCommand>("post_process");
+            let naming = th.optional::<NamingRulesDto>("naming");
             th.finalize(None)?;
             // endregion: extract
 
@@ -77,9 +99,14 @@ mod de {
                 remote_id,
                 scale,
                 quality,
+                lossless,
+                alpha_quality,
+                method,
                 output_dir,
                 variants,
                 legacy_loader,
+                post_process,
+                naming,
             })
         }
     }
@@ -102,6 +129,9 @@ mod test {
         remote = "figma"
         scale = 0.42
         quality = 100
+        lossless = true
+        alpha_quality = 80
+        method = 6
         output_dir = "images"
         legacy_loader = false
         "#;
@@ -110,9 +140,14 @@ mod test {
             remote_id: Some("figma".to_string()),
             scale: Some(ExportScale(0.42)),
             quality: Some(WebpQuality(100.0)),
+            lossless: Some(true),
+            alpha_quality: Some(WebpQuality(80.0)),
+            method: Some(WebpMethod(6)),
             output_dir: Some(PathBuf::from("images")),
             variants: None,
             legacy_loader: Some(false),
+            post_process: None,
+            naming: None,
         };
 
         // When
@@ -136,9 +171,14 @@ mod test {
             remote_id: None,
             scale: None,
             quality: None,
+            lossless: None,
+            alpha_quality: None,
+            method: None,
             output_dir: None,
             variants: None,
             legacy_loader: None,
+            post_process: None,
+            naming: None,
         };
 
         // When
@@ -221,23 +261,33 @@ mod test {
             remote_id: Some("remote".to_string()),
             scale: None,
             quality: Some(WebpQuality(100.0)),
+            lossless: None,
+            alpha_quality: None,
+            method: None,
             output_dir: None,
             variants: Some(VariantsDto {
                 all_variants: Some(OrderMap::new()),
                 use_variants: None,
             }),
             legacy_loader: Some(false),
+            post_process: None,
+            naming: None,
         };
         let second = WebpProfileDto {
             remote_id: None,
             scale: Some(ExportScale(1.0)),
             quality: None,
+            lossless: None,
+            alpha_quality: None,
+            method: None,
             output_dir: Some(PathBuf::from("path/to")),
             variants: Some(VariantsDto {
                 all_variants: None,
                 use_variants: Some(Vec::new()),
             }),
             legacy_loader: None,
+            post_process: None,
+            naming: None,
         };
 
         // When
@@ -249,12 +299,17 @@ mod test {
                 remote_id: Some("remote".to_string()),
                 scale: Some(ExportScale(1.0)),
                 quality: Some(WebpQuality(100.0)),
+                lossless: None,
+                alpha_quality: None,
+                method: None,
                 output_dir: Some(PathBuf::from("path/to")),
                 variants: Some(VariantsDto {
                     all_variants: Some(OrderMap::new()),
                     use_variants: Some(Vec::new()),
                 }),
                 legacy_loader: Some(false),
+                post_process: None,
+                naming: None,
             },
             third,
         );
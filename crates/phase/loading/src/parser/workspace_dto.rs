@@ -1,19 +1,31 @@
 use crate::parser::RemotesDtoContext;
 
-use super::{ProfilesDto, RemotesDto};
+use super::{
+    ExternalsDto, JobsDto, LocalWorkspaceDto, MetricsDto, NetworkDto, OutputDto, ProfilesDto,
+    RemotesDto,
+};
 
 #[cfg_attr(test, derive(PartialEq, Debug))]
 pub(crate) struct WorkspaceDto {
     pub remotes: RemotesDto,
     pub profiles: ProfilesDto,
+    pub externals: ExternalsDto,
+    pub metrics: MetricsDto,
+    pub output: OutputDto,
+    pub network: NetworkDto,
+    pub jobs: JobsDto,
 }
 
 pub struct WorkspaceDtoContext {
     pub ignore_missing_access_token: bool,
+    /// Profiles of the files listed in `include`, parsed beforehand
+    pub included_profiles: Option<ProfilesDto>,
+    /// Content of `.figtree.local.toml`, if it exists next to the workspace file
+    pub local: Option<LocalWorkspaceDto>,
 }
 
-impl From<WorkspaceDtoContext> for RemotesDtoContext {
-    fn from(value: WorkspaceDtoContext) -> Self {
+impl From<&WorkspaceDtoContext> for RemotesDtoContext {
+    fn from(value: &WorkspaceDtoContext) -> Self {
         Self {
             ignore_missing_access_token: value.ignore_missing_access_token,
         }
@@ -22,7 +34,10 @@ impl From<WorkspaceDtoContext> for RemotesDtoContext {
 
 mod de {
     use super::*;
-    use crate::{ParseWithContext, parser::ProfilesDtoContext};
+    use crate::{
+        CanBeExtendedBy, ParseWithContext,
+        parser::{AccessTokenDefinitionDto, ProfilesDtoContext},
+    };
     use toml_span::{ErrorKind, de_helpers::TableHelper};
 
     impl<'de> ParseWithContext<'de> for WorkspaceDto {
@@ -36,12 +51,17 @@ mod de {
             let mut th = TableHelper::new(value)?;
             let remotes = th.take("remotes");
             let profiles = th.take("profiles");
+            let externals = th.take("externals");
+            let metrics = th.take("metrics");
+            let output = th.take("output");
+            let network = th.take("network");
+            let jobs = th.take("jobs");
             th.finalize(None)?;
             // endregion: extract
 
             // region: validate
-            let remotes = match remotes {
-                Some((_, mut value)) => RemotesDto::parse_with_ctx(&mut value, ctx.into())?,
+            let mut remotes = match remotes {
+                Some((_, mut value)) => RemotesDto::parse_with_ctx(&mut value, (&ctx).into())?,
                 None => {
                     return Err(toml_span::Error::from((
                         ErrorKind::Custom(
@@ -52,18 +72,77 @@ mod de {
                     .into());
                 }
             };
-            let profiles = match profiles {
-                Some((_, mut value)) => {
-                    let ctx = ProfilesDtoContext {
-                        declared_remote_ids: &remotes.0.iter().map(|(k, _)| k.clone()).collect(),
-                    };
-                    ProfilesDto::parse_with_ctx(&mut value, ctx)?
+            let local = ctx.local.unwrap_or_default();
+            for (id, remote) in remotes.0.iter_mut() {
+                let Some(remote_override) = local.remotes.get(id) else {
+                    continue;
+                };
+                *remote = remote.extend(remote_override);
+                if ctx.ignore_missing_access_token {
+                    remote.access_token = AccessTokenDefinitionDto::Explicit(":)".to_owned());
                 }
-                None => ProfilesDto::default(),
+            }
+            // included files don't know about the overrides, so they are applied afterwards
+            let included_profiles = ctx
+                .included_profiles
+                .map(|it| it.overridden_by(&local.profiles));
+            let profiles = match (profiles, included_profiles) {
+                (Some((_, mut value)), included) => {
+                    let profiles = ProfilesDto::parse_with_ctx(
+                        &mut value,
+                        ProfilesDtoContext {
+                            declared_remote_ids: &remotes
+                                .0
+                                .iter()
+                                .map(|(k, _)| k.clone())
+                                .collect(),
+                            included: included.as_ref(),
+                            overrides: Some(&local.profiles),
+                        },
+                    )?;
+                    match included {
+                        Some(included) => profiles.with_included(included),
+                        None => profiles,
+                    }
+                }
+                (None, Some(included)) => included,
+                (None, None) => ProfilesDto::default(),
+            };
+            let externals = match externals {
+                Some((_, mut value)) => ExternalsDto::parse_with_ctx(&mut value, ())?,
+                None => ExternalsDto::default(),
             };
+            let metrics = match metrics {
+                Some((_, mut value)) => MetricsDto::parse_with_ctx(&mut value, ())?,
+                None => MetricsDto::default(),
+            }
+            .extend(&local.metrics);
+            let output = match output {
+                Some((_, mut value)) => OutputDto::parse_with_ctx(&mut value, ())?,
+                None => OutputDto::default(),
+            }
+            .extend(&local.output);
+            let network = match network {
+                Some((_, mut value)) => NetworkDto::parse_with_ctx(&mut value, ())?,
+                None => NetworkDto::default(),
+            }
+            .extend(&local.network);
+            let jobs = match jobs {
+                Some((_, mut value)) => JobsDto::parse_with_ctx(&mut value, ())?,
+                None => JobsDto::default(),
+            }
+            .extend(&local.jobs);
             // endregion: validate
 
-            Ok(Self { remotes, profiles })
+            Ok(Self {
+                remotes,
+                profiles,
+                externals,
+                metrics,
+                output,
+                network,
+                jobs,
+            })
         }
     }
 }
@@ -0,0 +1,73 @@
+use crate::Workspace;
+use lib_label::Package as PackageLabel;
+use std::path::{Path, PathBuf};
+
+impl Workspace {
+    /// Writes the outputs of every package under `base` instead of the package itself,
+    /// e.g. into a generated sources directory of Gradle, keeping the structure of the
+    /// packages: `//icons` goes to `{base}/icons`, `@ds//icons` to `{base}/external/ds/icons`.
+    ///
+    /// The `base` is expected to be absolute.
+    pub fn relocate_outputs(&mut self, base: &Path) {
+        for pkg in &mut self.packages {
+            let package_dir = relocated_package_dir(base, &pkg.label);
+            for res in &mut pkg.resources {
+                res.attrs.package_dir = package_dir.clone();
+            }
+        }
+        self.output.base = Some(base.to_path_buf());
+    }
+}
+
+fn relocated_package_dir(base: &Path, label: &PackageLabel) -> PathBuf {
+    let base = match label.external() {
+        Some(name) => base.join("external").join(name),
+        None => base.to_path_buf(),
+    };
+    // package paths are relative to the workspace, the root package is empty
+    base.join(&**label)
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn relocated_package_dir__nested_package__EXPECT__same_structure_under_base() {
+        // Given
+        let label = PackageLabel::with_path("app/src/icons").unwrap();
+
+        // When
+        let dir = relocated_package_dir(Path::new("/out"), &label);
+
+        // Then
+        assert_eq!(PathBuf::from("/out/app/src/icons"), dir);
+    }
+
+    #[test]
+    fn relocated_package_dir__root_package__EXPECT__base_itself() {
+        // Given
+        let label = PackageLabel::empty();
+
+        // When
+        let dir = relocated_package_dir(Path::new("/out"), &label);
+
+        // Then
+        assert_eq!(PathBuf::from("/out"), dir);
+    }
+
+    #[test]
+    fn relocated_package_dir__external_package__EXPECT__under_external_dir() {
+        // Given
+        let label = PackageLabel::with_path("icons")
+            .unwrap()
+            .with_external("design_system");
+
+        // When
+        let dir = relocated_package_dir(Path::new("/out"), &label);
+
+        // Then
+        assert_eq!(PathBuf::from("/out/external/design_system/icons"), dir);
+    }
+}
@@ -1,16 +1,20 @@
-use crate::parser::{ResourcesDto, ResourcesDtoContext};
+use crate::parser::{BulkDto, ResourcesDto, ResourcesDtoContext, interpolate_env};
 use crate::workspace::fig::parse_resources;
 use crate::{Error, ParseWithContext, Result};
 use crate::{LoadedFigFile, Package};
 use crate::{Profile, RemoteSource};
-use lib_label::LabelPattern;
 use log::debug;
 use ordermap::OrderMap;
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use toml_span::de_helpers::TableHelper;
 
-pub(crate) struct FigFileDto(pub ResourcesDto);
+pub(crate) struct FigFileDto {
+    pub resources: ResourcesDto,
+    /// Declarations of `[bulk]` section, expanded when the scans are at hand
+    pub bulk: BulkDto,
+}
 
 impl FigFileDto {
     pub fn from_file(file: &Path, ctx: ResourcesDtoContext<'_>) -> Result<Self> {
@@ -22,8 +26,17 @@ impl FigFileDto {
         string: &str,
         ctx: ResourcesDtoContext<'_>,
     ) -> std::result::Result<Self, toml_span::DeserError> {
-        let resources = ResourcesDto::parse_with_ctx(&mut toml_span::parse(&string)?, ctx)?;
-        Ok(FigFileDto(resources))
+        let mut value = toml_span::parse(&string)?;
+        interpolate_env(&mut value)?;
+        let mut th = TableHelper::new(&mut value)?;
+        let bulk = th.take("bulk");
+        th.finalize(Some(&mut value))?;
+        let bulk = match bulk {
+            Some((_, mut value)) => BulkDto::parse_with_ctx(&mut value, ctx)?,
+            None => BulkDto::default(),
+        };
+        let resources = ResourcesDto::parse_with_ctx(&mut value, ctx)?;
+        Ok(FigFileDto { resources, bulk })
     }
 }
 
@@ -31,11 +44,13 @@ pub(crate) fn parse_fig(
     fig_file: &LoadedFigFile,
     remotes: &OrderMap<String, Arc<RemoteSource>>,
     profiles: &OrderMap<String, Arc<Profile>>,
-    pattern: &LabelPattern,
-    current_dir: &Path,
+    scans_dir: &Path,
 ) -> Result<Package> {
     debug!("Parsing fig-file {}", fig_file.fig_file.display());
-    let fig_dto = FigFileDto::from_file(
+    let FigFileDto {
+        mut resources,
+        bulk,
+    } = FigFileDto::from_file(
         &fig_file.fig_file,
         ResourcesDtoContext {
             declared_remote_ids: &remotes
@@ -45,10 +60,9 @@ pub(crate) fn parse_fig(
             profiles,
         },
     )?;
-    let mut resources = parse_resources(&fig_file, fig_dto.0, remotes)?;
-
-    // filter out irrelevant resources
-    resources.retain(|res| lib_label::matches(pattern, &res.attrs.label, current_dir));
+    bulk.expand_into(&mut resources, scans_dir, remotes)
+        .map_err(|e| Error::FigParse(e, PathBuf::new()))?;
+    let resources = parse_resources(&fig_file, resources, remotes)?;
 
     Ok(Package {
         label: fig_file.package.clone(),
@@ -1,8 +1,9 @@
 use crate::parser::ProfileDto;
 use crate::{CanBeExtendedBy, ResourceAttrs, ResourceDiagnostics, Result};
 use crate::{LoadedFigFile, Profile, RemoteSource, Resource, parser::ResourcesDto};
-use lib_label::Label;
+use lib_label::{Label, Name};
 use ordermap::OrderMap;
+use std::str::FromStr;
 use std::sync::Arc;
 
 pub(crate) fn parse_resources(
@@ -14,11 +15,12 @@ pub(crate) fn parse_resources(
     let mut output = Vec::new();
     let resource_location_file = Arc::new(fig_file.fig_file.to_owned());
 
-    for (_, res_dto_list) in resources {
+    for (profile_id, res_dto_list) in resources {
         for (res_id, res_dto) in res_dto_list {
             // Create label for the resource
-            let label: Label = Label::from_package_and_name(&fig_file.package, &res_id)
-                .expect("validated on parsing stage");
+            // keep the package as is, it may belong to an external workspace
+            let name = Name::from_str(&res_id).expect("validated on parsing stage");
+            let label = Label::from((fig_file.package.clone(), name));
             let profile = match res_dto.override_profile {
                 None => res_dto.profile,
                 Some(p) => Arc::new(res_dto.profile.extend(&p)),
@@ -26,8 +28,12 @@ pub(crate) fn parse_resources(
             let res = Resource {
                 attrs: ResourceAttrs {
                     label,
+                    profile_id: profile_id.clone(),
                     remote: parse_remote_by_id(remotes, profile.remote_id())?,
                     node_name: res_dto.node_name,
+                    use_absolute_bounds: res_dto.use_absolute_bounds,
+                    expect: res_dto.expect.map(Into::into).unwrap_or_default(),
+                    tags: res_dto.tags,
                     package_dir: fig_file.fig_dir.clone(),
                     diag: ResourceDiagnostics {
                         file: resource_location_file.clone(),
@@ -55,6 +61,9 @@ impl CanBeExtendedBy<ProfileDto> for Profile {
             (AndroidDrawable(domain), ProfileDto::AndroidDrawable(dto)) => {
                 AndroidDrawable(domain.extend(dto))
             }
+            (Flutter(domain), ProfileDto::Flutter(dto)) => Flutter(domain.extend(dto)),
+            (React(domain), ProfileDto::React(dto)) => React(domain.extend(dto)),
+            (Favicon(domain), ProfileDto::Favicon(dto)) => Favicon(domain.extend(dto)),
             _ => panic!(
                 "Inconsistent internal parser state. Cannot merge dto and domain profiles of different types"
             ),
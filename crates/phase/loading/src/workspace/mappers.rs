@@ -1,10 +1,12 @@
 use crate::{
-    AndroidDrawableProfile, AndroidWebpProfile, CanBeExtendedBy, ComposeProfile, PdfProfile,
-    PngProfile, ResourceVariants, SvgProfile, WebpProfile,
+    AndroidDrawableProfile, AndroidWebpProfile, CanBeExtendedBy, ComposeProfile, FaviconProfile,
+    FlutterProfile, LimitAction, NamingRules, PdfProfile, PngProfile, ReactProfile,
+    ResourceVariants, SvgProfile, VectorLimits, WebpProfile,
     parser::{
         AndroidDensityDto, AndroidDrawableProfileDto, AndroidWebpProfileDto, ColorMappingDto,
-        ComposePreviewDto, ComposeProfileDto, PdfProfileDto, PngProfileDto, SvgProfileDto,
-        VariantDto, VariantsDto, WebpProfileDto,
+        ComposePreviewDto, ComposeProfileDto, FaviconProfileDto, FlutterProfileDto, LimitActionDto,
+        NamingRulesDto, PdfProfileDto, PngProfileDto, ReactProfileDto, SvgProfileDto, VariantDto,
+        VariantsDto, VectorLimitsDto, WebpProfileDto,
     },
 };
 
@@ -29,6 +31,19 @@ impl CanBeExtendedBy<PngProfileDto> for PngProfile {
                 _ => None,
             },
             legacy_loader: another.legacy_loader.unwrap_or(self.legacy_loader),
+            optimize: another.optimize.unwrap_or(self.optimize),
+            optimization_level: another
+                .optimization_level
+                .unwrap_or(self.optimization_level),
+            post_process: another
+                .post_process
+                .as_ref()
+                .or(self.post_process.as_ref())
+                .cloned(),
+            naming: match another.naming.as_ref() {
+                Some(naming) => self.naming.extend(naming),
+                None => self.naming.clone(),
+            },
         }
     }
 }
@@ -52,6 +67,15 @@ impl CanBeExtendedBy<SvgProfileDto> for SvgProfile {
                 (None, Some(domain)) => Some(domain.clone()),
                 _ => None,
             },
+            post_process: another
+                .post_process
+                .as_ref()
+                .or(self.post_process.as_ref())
+                .cloned(),
+            naming: match another.naming.as_ref() {
+                Some(naming) => self.naming.extend(naming),
+                None => self.naming.clone(),
+            },
         }
     }
 }
@@ -69,12 +93,28 @@ impl CanBeExtendedBy<PdfProfileDto> for PdfProfile {
                 .as_ref()
                 .unwrap_or(&self.output_dir)
                 .clone(),
+            allow_raster: another.allow_raster.or(self.allow_raster),
+            preview_scales: another
+                .preview_scales
+                .as_ref()
+                .unwrap_or(&self.preview_scales)
+                .clone(),
+            local_renderer: another.local_renderer.unwrap_or(self.local_renderer),
             variants: match (another.variants.as_ref(), self.variants.as_ref()) {
                 (Some(dto), Some(domain)) => Some(domain.extend(dto)),
                 (Some(dto), None) => Some(dto.clone().into()),
                 (None, Some(domain)) => Some(domain.clone()),
                 _ => None,
             },
+            post_process: another
+                .post_process
+                .as_ref()
+                .or(self.post_process.as_ref())
+                .cloned(),
+            naming: match another.naming.as_ref() {
+                Some(naming) => self.naming.extend(naming),
+                None => self.naming.clone(),
+            },
         }
     }
 }
@@ -89,6 +129,9 @@ impl CanBeExtendedBy<WebpProfileDto> for WebpProfile {
                 .clone(),
             scale: another.scale.unwrap_or(self.scale),
             quality: another.quality.unwrap_or(self.quality),
+            lossless: another.lossless.unwrap_or(self.lossless),
+            alpha_quality: another.alpha_quality.unwrap_or(self.alpha_quality),
+            method: another.method.unwrap_or(self.method),
             output_dir: another
                 .output_dir
                 .as_ref()
@@ -101,6 +144,15 @@ impl CanBeExtendedBy<WebpProfileDto> for WebpProfile {
                 _ => None,
             },
             legacy_loader: another.legacy_loader.unwrap_or(self.legacy_loader),
+            post_process: another
+                .post_process
+                .as_ref()
+                .or(self.post_process.as_ref())
+                .cloned(),
+            naming: match another.naming.as_ref() {
+                Some(naming) => self.naming.extend(naming),
+                None => self.naming.clone(),
+            },
         }
     }
 }
@@ -144,6 +196,34 @@ impl CanBeExtendedBy<ComposeProfileDto> for ComposeProfile {
                 _ => None,
             },
             composable_get: another.composable_get.unwrap_or(self.composable_get),
+            property_name_template: another
+                .property_name_template
+                .clone()
+                .or_else(|| self.property_name_template.clone()),
+            receiver_object: another
+                .receiver_object
+                .clone()
+                .or_else(|| self.receiver_object.clone()),
+            combine_variants: another.combine_variants.unwrap_or(self.combine_variants),
+            limits: match another.limits.as_ref() {
+                Some(limits) => self.limits.extend(limits),
+                None => self.limits.clone(),
+            },
+            multiplatform: another.multiplatform.unwrap_or(self.multiplatform),
+            identifier_prefix: another
+                .identifier_prefix
+                .clone()
+                .or_else(|| self.identifier_prefix.clone()),
+            document_sources: another.document_sources.unwrap_or(self.document_sources),
+            post_process: another
+                .post_process
+                .as_ref()
+                .or(self.post_process.as_ref())
+                .cloned(),
+            naming: match another.naming.as_ref() {
+                Some(naming) => self.naming.extend(naming),
+                None => self.naming.clone(),
+            },
         }
     }
 }
@@ -162,13 +242,29 @@ impl CanBeExtendedBy<AndroidWebpProfileDto> for AndroidWebpProfile {
                 .unwrap_or(&self.android_res_dir)
                 .clone(),
             quality: another.quality.unwrap_or(self.quality),
+            lossless: another.lossless.unwrap_or(self.lossless),
+            alpha_quality: another.alpha_quality.unwrap_or(self.alpha_quality),
+            method: another.method.unwrap_or(self.method),
             scales: another
                 .densities
                 .as_ref()
                 .map(|set| set.iter().cloned().map(Into::into).collect())
                 .unwrap_or_else(|| self.scales.clone()),
             night: another.night.clone().or_else(|| self.night.clone()),
+            dark_source: another
+                .dark_source
+                .clone()
+                .or_else(|| self.dark_source.clone()),
             legacy_loader: another.legacy_loader.unwrap_or(self.legacy_loader),
+            post_process: another
+                .post_process
+                .as_ref()
+                .or(self.post_process.as_ref())
+                .cloned(),
+            naming: match another.naming.as_ref() {
+                Some(naming) => self.naming.extend(naming),
+                None => self.naming.clone(),
+            },
         }
     }
 }
@@ -187,7 +283,113 @@ impl CanBeExtendedBy<AndroidDrawableProfileDto> for AndroidDrawableProfile {
                 .unwrap_or(&self.android_res_dir)
                 .clone(),
             night: another.night.clone().or_else(|| self.night.clone()),
+            dark_source: another
+                .dark_source
+                .clone()
+                .or_else(|| self.dark_source.clone()),
             auto_mirrored: another.auto_mirrored.unwrap_or(self.auto_mirrored),
+            monochrome: another.monochrome.unwrap_or(self.monochrome),
+            limits: match another.limits.as_ref() {
+                Some(limits) => self.limits.extend(limits),
+                None => self.limits.clone(),
+            },
+            post_process: another
+                .post_process
+                .as_ref()
+                .or(self.post_process.as_ref())
+                .cloned(),
+            naming: match another.naming.as_ref() {
+                Some(naming) => self.naming.extend(naming),
+                None => self.naming.clone(),
+            },
+        }
+    }
+}
+
+impl CanBeExtendedBy<FlutterProfileDto> for FlutterProfile {
+    fn extend(&self, another: &FlutterProfileDto) -> Self {
+        Self {
+            remote_id: another
+                .remote_id
+                .as_ref()
+                .unwrap_or(&self.remote_id)
+                .clone(),
+            assets_dir: another
+                .assets_dir
+                .as_ref()
+                .unwrap_or(&self.assets_dir)
+                .clone(),
+            scales: another
+                .scales
+                .clone()
+                .unwrap_or_else(|| self.scales.clone()),
+            dart_class: another
+                .dart_class
+                .clone()
+                .or_else(|| self.dart_class.clone()),
+            dart_file: another
+                .dart_file
+                .as_ref()
+                .unwrap_or(&self.dart_file)
+                .clone(),
+            legacy_loader: another.legacy_loader.unwrap_or(self.legacy_loader),
+            optimize: another.optimize.unwrap_or(self.optimize),
+            optimization_level: another
+                .optimization_level
+                .unwrap_or(self.optimization_level),
+            post_process: another
+                .post_process
+                .as_ref()
+                .or(self.post_process.as_ref())
+                .cloned(),
+            naming: match another.naming.as_ref() {
+                Some(naming) => self.naming.extend(naming),
+                None => self.naming.clone(),
+            },
+        }
+    }
+}
+
+impl CanBeExtendedBy<ReactProfileDto> for ReactProfile {
+    fn extend(&self, another: &ReactProfileDto) -> Self {
+        Self {
+            remote_id: another
+                .remote_id
+                .as_ref()
+                .unwrap_or(&self.remote_id)
+                .clone(),
+            output_dir: another
+                .output_dir
+                .as_ref()
+                .unwrap_or(&self.output_dir)
+                .clone(),
+            current_color: another.current_color.unwrap_or(self.current_color),
+            variants: match (another.variants.as_ref(), self.variants.as_ref()) {
+                (Some(dto), Some(domain)) => Some(domain.extend(dto)),
+                (Some(dto), None) => Some(dto.clone().into()),
+                (None, Some(domain)) => Some(domain.clone()),
+                _ => None,
+            },
+            post_process: another
+                .post_process
+                .as_ref()
+                .or(self.post_process.as_ref())
+                .cloned(),
+            naming: match another.naming.as_ref() {
+                Some(naming) => self.naming.extend(naming),
+                None => self.naming.clone(),
+            },
+        }
+    }
+}
+
+impl CanBeExtendedBy<NamingRulesDto> for NamingRules {
+    fn extend(&self, another: &NamingRulesDto) -> Self {
+        Self {
+            strip: another.strip.as_ref().unwrap_or(&self.strip).clone(),
+            case: another.case.or(self.case),
+            prefix: another.prefix.as_ref().unwrap_or(&self.prefix).clone(),
+            suffix: another.suffix.as_ref().unwrap_or(&self.suffix).clone(),
         }
     }
 }
@@ -267,3 +469,57 @@ impl CanBeExtendedBy<VariantsDto> for ResourceVariants {
         }
     }
 }
+
+impl CanBeExtendedBy<VectorLimitsDto> for VectorLimits {
+    fn extend(&self, another: &VectorLimitsDto) -> Self {
+        Self {
+            max_paths: another.max_paths.or(self.max_paths),
+            max_commands: another.max_commands.or(self.max_commands),
+            on_exceeded: match another.on_exceeded.as_ref() {
+                Some(LimitActionDto::Warn) => LimitAction::Warn,
+                Some(LimitActionDto::Fail) => LimitAction::Fail,
+                Some(LimitActionDto::Fallback(fallback)) => LimitAction::Fallback(
+                    fallback
+                        .profile
+                        .clone()
+                        .expect("fallback profile is resolved on parsing stage"),
+                ),
+                None => self.on_exceeded.clone(),
+            },
+        }
+    }
+}
+
+impl CanBeExtendedBy<FaviconProfileDto> for FaviconProfile {
+    fn extend(&self, another: &FaviconProfileDto) -> Self {
+        Self {
+            remote_id: another
+                .remote_id
+                .as_ref()
+                .unwrap_or(&self.remote_id)
+                .clone(),
+            output_dir: another
+                .output_dir
+                .as_ref()
+                .unwrap_or(&self.output_dir)
+                .clone(),
+            sizes: another.sizes.as_ref().unwrap_or(&self.sizes).clone(),
+            webmanifest: another.webmanifest.unwrap_or(self.webmanifest),
+            variants: match (another.variants.as_ref(), self.variants.as_ref()) {
+                (Some(dto), Some(domain)) => Some(domain.extend(dto)),
+                (Some(dto), None) => Some(dto.clone().into()),
+                (None, Some(domain)) => Some(domain.clone()),
+                _ => None,
+            },
+            post_process: another
+                .post_process
+                .as_ref()
+                .or(self.post_process.as_ref())
+                .cloned(),
+            naming: match another.naming.as_ref() {
+                Some(naming) => self.naming.extend(naming),
+                None => self.naming.clone(),
+            },
+        }
+    }
+}
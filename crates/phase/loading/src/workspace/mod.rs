@@ -1,7 +1,9 @@
 mod fig;
 mod mappers;
+mod outputs;
 mod parser;
 mod profiles;
 mod remotes;
 
+pub(crate) use outputs::*;
 pub(crate) use parser::*;
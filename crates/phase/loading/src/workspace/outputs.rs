@@ -0,0 +1,180 @@
+use crate::{DuplicateOutputWriter, Error, Package, Resource, Result};
+use std::{
+    collections::HashMap,
+    path::{Component, Path, PathBuf},
+};
+
+/// Checks that no two resources of the loaded packages write the same file, including
+/// the auxiliary ones, otherwise their imports race and only one of them ends up in the file.
+///
+/// Expects all packages of the workspace, not only the ones matching the pattern.
+pub(crate) fn validate_output_paths(packages: &[Package]) -> Result<()> {
+    let mut writers = HashMap::<PathBuf, &Resource>::new();
+    for res in packages.iter().flat_map(|it| &it.resources) {
+        for path in res.expected_outputs() {
+            let path = normalize(&path);
+            match writers.get(&path) {
+                Some(other) if !std::ptr::eq(*other, res) => {
+                    return Err(Error::FigDuplicateOutput(
+                        path,
+                        Box::new([writer(other), writer(res)]),
+                    ));
+                }
+                Some(_) => (),
+                None => {
+                    writers.insert(path, res);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn writer(res: &Resource) -> DuplicateOutputWriter {
+    DuplicateOutputWriter {
+        label: res.attrs.label.to_string(),
+        file: res.attrs.diag.file.as_ref().clone(),
+        span: res.attrs.diag.definition_span.clone(),
+    }
+}
+
+/// Resolves `.` and `..` lexically, so that the paths through sibling packages compare equal
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use crate::{Error, load_workspace_in};
+    use lib_label::LabelPattern;
+    use std::path::{Path, PathBuf};
+
+    static REMOTE: &str = "[remotes.design]\nfile_key = \"FILE\"\ncontainer_node_ids = [\"0:1\"]\n";
+    static SHARED_PROFILE: &str =
+        "[profiles.shared]\nextends = \"svg\"\noutput_dir = \"../shared\"\n";
+
+    fn write(path: PathBuf, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn load(dir: &Path, pattern: &str) -> crate::Result<crate::Workspace> {
+        let pattern = LabelPattern::try_from(vec![pattern.to_string()]).unwrap();
+        load_workspace_in(dir, pattern, true)
+    }
+
+    #[test]
+    fn validate_output_paths__packages_outside_of_pattern_collide__EXPECT__error() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path().join(".figtree.toml"),
+            &format!("{REMOTE}{SHARED_PROFILE}"),
+        );
+        write(
+            dir.path().join("a").join(".fig.toml"),
+            "[shared]\nic_star = \"Star\"\n",
+        );
+        write(
+            dir.path().join("b").join(".fig.toml"),
+            "[shared]\nic_star = \"Star\"\n",
+        );
+
+        // When
+        let result = load(dir.path(), "//a/...");
+
+        // Then
+        let Err(Error::FigDuplicateOutput(path, writers)) = result else {
+            panic!("collision must be reported");
+        };
+        assert!(path.ends_with(Path::new("shared").join("ic_star.svg")));
+        let mut labels = (*writers).map(|it| it.label);
+        labels.sort();
+        assert_eq!(["//a:ic_star", "//b:ic_star"], labels);
+    }
+
+    #[test]
+    fn validate_output_paths__naming_rules_produce_same_name__EXPECT__error() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let profiles = "[profiles.prefixed]\nextends = \"svg\"\nnaming = { prefix = \"ic_\" }\n";
+        write(
+            dir.path().join(".figtree.toml"),
+            &format!("{REMOTE}{profiles}"),
+        );
+        write(
+            dir.path().join("a").join(".fig.toml"),
+            "[svg]\nic_star = \"Star\"\n[prefixed]\nstar = \"Star\"\n",
+        );
+
+        // When
+        let result = load(dir.path(), "//...");
+
+        // Then
+        assert!(matches!(result, Err(Error::FigDuplicateOutput(..))));
+    }
+
+    #[test]
+    fn validate_output_paths__different_files__EXPECT__only_matching_packages_loaded() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path().join(".figtree.toml"),
+            &format!("{REMOTE}{SHARED_PROFILE}"),
+        );
+        write(
+            dir.path().join("a").join(".fig.toml"),
+            "[shared]\nic_star = \"Star\"\n",
+        );
+        write(
+            dir.path().join("b").join(".fig.toml"),
+            "[shared]\nic_moon = \"Moon\"\n",
+        );
+
+        // When
+        let ws = load(dir.path(), "//a/...").unwrap();
+
+        // Then
+        let labels = ws
+            .packages
+            .iter()
+            .flat_map(|it| &it.resources)
+            .map(|it| it.attrs.label.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["//a:ic_star"], labels);
+    }
+
+    #[test]
+    fn validate_output_paths__auxiliary_file_collides_with_resource__EXPECT__error() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path().join(".figtree.toml"), REMOTE);
+        write(
+            dir.path().join("web").join(".fig.toml"),
+            "[favicon]\napp = \"App\"\n[png]\napp-32x32 = \"App\"\n",
+        );
+
+        // When
+        let result = load(dir.path(), "//...");
+
+        // Then
+        let Err(Error::FigDuplicateOutput(path, writers)) = result else {
+            panic!("collision must be reported");
+        };
+        assert!(path.ends_with(Path::new("web").join("app-32x32.png")));
+        let mut labels = (*writers).map(|it| it.label);
+        labels.sort();
+        assert_eq!(["//web:app", "//web:app-32x32"], labels);
+    }
+}
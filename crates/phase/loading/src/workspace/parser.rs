@@ -1,9 +1,21 @@
 use super::fig::parse_fig;
-use crate::parser::{WorkspaceDto, WorkspaceDtoContext};
+use super::validate_output_paths;
+use crate::parser::{
+    IncludeDto, IncludedFileDto, LocalWorkspaceDto, LocalWorkspaceDtoContext, ProfilesDto,
+    ProfilesDtoContext, WorkspaceDto, WorkspaceDtoContext, interpolate_env,
+    peek_declared_profile_kinds,
+};
 use crate::workspace::profiles::parse_profiles;
 use crate::workspace::remotes::parse_remotes;
 use crate::{Error, RemoteSource};
-use crate::{InvocationContext, Workspace};
+use crate::{
+    InvocationContext, JobsConfig, LoadedFigFile, MetricsConfig, NetworkConfig, OutputConfig,
+    Workspace,
+};
+use crate::{
+    LOCAL_WORKSPACE_FILE_NAME, OUT_DIR, SCANS_DIR, WORKSPACE_FILE_NAME, load_fig_files,
+    with_workspace_file,
+};
 use crate::{Package, Profile};
 use crate::{ParseWithContext, Result};
 use lib_label::LabelPattern;
@@ -11,25 +23,95 @@ use log::debug;
 use ordermap::OrderMap;
 use std::path::Path;
 use std::sync::Arc;
+use toml_span::ErrorKind;
 
 impl WorkspaceDto {
     pub fn from_file(file: &Path, ignore_missing_access_token: bool) -> Result<Self> {
         let string = std::fs::read_to_string(file).map_err(Error::WorkspaceRead)?;
-        Self::from_str(&string, ignore_missing_access_token)
-            .map_err(|e| Error::WorkspaceParse(e, file.to_owned()))
-    }
-
-    pub fn from_str(
-        string: &str,
-        ignore_missing_access_token: bool,
-    ) -> std::result::Result<Self, toml_span::DeserError> {
+        let parse_error = |e| Error::WorkspaceParse(e, file.to_owned());
+        let mut value = toml_span::parse(&string).map_err(|e| parse_error(e.into()))?;
+        interpolate_env(&mut value).map_err(parse_error)?;
+        let include = IncludeDto::parse_with_ctx(&mut value, ()).map_err(parse_error)?;
+        let included_profiles = parse_included_files(&include, file)?;
+        let local = parse_local_file(&value, &include, included_profiles.as_ref(), file)?;
         WorkspaceDto::parse_with_ctx(
-            &mut toml_span::parse(&string)?,
+            &mut value,
             WorkspaceDtoContext {
                 ignore_missing_access_token,
+                included_profiles,
+                local,
             },
         )
+        .map_err(parse_error)
+    }
+}
+
+/// Parses the files listed in `include`, each one on top of the previous ones.
+///
+/// Every file is parsed separately, so that errors point to the file they are found in.
+fn parse_included_files(include: &IncludeDto, ws_file: &Path) -> Result<Option<ProfilesDto>> {
+    let ws_dir = ws_file.parent().unwrap_or(Path::new(""));
+    let mut included: Option<ProfilesDto> = None;
+    for file in &include.files {
+        debug!("Parsing included file `{}`...", file.value.display());
+        let path = ws_dir.join(&file.value);
+        let string = std::fs::read_to_string(&path).map_err(|e| {
+            let message = format!("unable to read included file: {e}");
+            Error::WorkspaceParse(
+                toml_span::Error::from((ErrorKind::Custom(message.into()), file.span)).into(),
+                ws_file.to_owned(),
+            )
+        })?;
+        let parse = || -> std::result::Result<_, toml_span::DeserError> {
+            let ctx = ProfilesDtoContext {
+                declared_remote_ids: &include.declared_remote_ids,
+                included: included.as_ref(),
+                overrides: None,
+            };
+            let mut value = toml_span::parse(&string)?;
+            interpolate_env(&mut value)?;
+            IncludedFileDto::parse_with_ctx(&mut value, ctx)
+        };
+        let IncludedFileDto { profiles } =
+            parse().map_err(|e| Error::WorkspaceParse(e, path.clone()))?;
+        included = Some(match included {
+            Some(included) => profiles.with_included(included),
+            None => profiles,
+        });
     }
+    Ok(included)
+}
+
+/// Parses `.figtree.local.toml` next to the workspace file, if it exists
+fn parse_local_file(
+    ws_value: &toml_span::Value<'_>,
+    include: &IncludeDto,
+    included_profiles: Option<&ProfilesDto>,
+    ws_file: &Path,
+) -> Result<Option<LocalWorkspaceDto>> {
+    let path = ws_file
+        .parent()
+        .unwrap_or(Path::new(""))
+        .join(LOCAL_WORKSPACE_FILE_NAME);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    debug!("Parsing local overrides `{}`...", path.display());
+    let string = std::fs::read_to_string(&path).map_err(Error::WorkspaceRead)?;
+    let declared_profile_kinds = peek_declared_profile_kinds(ws_value, included_profiles);
+    let parse = || -> std::result::Result<_, toml_span::DeserError> {
+        let mut value = toml_span::parse(&string)?;
+        interpolate_env(&mut value)?;
+        LocalWorkspaceDto::parse_with_ctx(
+            &mut value,
+            LocalWorkspaceDtoContext {
+                declared_remote_ids: &include.declared_remote_ids,
+                declared_profile_kinds: &declared_profile_kinds,
+            },
+        )
+    };
+    let local = parse().map_err(|e| Error::WorkspaceParse(e, path.clone()))?;
+    Ok(Some(local))
 }
 
 pub(crate) fn parse_workspace(
@@ -41,32 +123,222 @@ pub(crate) fn parse_workspace(
     let ws_dto = WorkspaceDto::from_file(&context.workspace_file, ignore_missing_access_token)?;
     let remotes = parse_remotes(ws_dto.remotes)?;
     let profiles = parse_profiles(ws_dto.profiles)?;
-    let packages = parse_packages(&context, pattern, &remotes, &profiles)?;
+    let packages = parse_packages(
+        &context.fig_files,
+        &remotes,
+        &profiles,
+        &context.out_dir.join(SCANS_DIR),
+    )?;
+    let ca_bundle = ws_dto
+        .network
+        .ca_bundle
+        .map(|path| context.workspace_dir.join(path));
 
-    Ok(Workspace {
+    let mut workspace = Workspace {
         context,
         remotes: remotes.into_values().collect(),
         profiles: profiles.into_values().collect(),
         packages,
-    })
+        externals: ws_dto.externals.0.keys().cloned().collect(),
+        metrics: MetricsConfig {
+            otlp_endpoint: ws_dto.metrics.otlp_endpoint,
+        },
+        output: OutputConfig {
+            staging: ws_dto.output.staging.unwrap_or(false),
+            content_addressed: ws_dto.output.content_addressed,
+            base: None,
+            provenance: ws_dto.output.provenance.unwrap_or(false),
+        },
+        network: NetworkConfig { ca_bundle },
+        jobs: JobsConfig {
+            network: ws_dto.jobs.network,
+            cpu: ws_dto.jobs.cpu,
+        },
+    };
+    // load only those external workspaces which are mentioned in the pattern
+    for name in referenced_externals(&pattern) {
+        let Some(external) = ws_dto.externals.0.get(&name) else {
+            return Err(Error::WorkspaceExternalUnknown(name));
+        };
+        let ws_dir = workspace.context.workspace_dir.join(&external.path);
+        parse_external_workspace(&mut workspace, &name, &ws_dir, ignore_missing_access_token)?;
+    }
+    if let Some(base) = &ws_dto.output.base {
+        let base = workspace.context.workspace_dir.join(base);
+        workspace.relocate_outputs(&base);
+    }
+    // resources outside of the pattern still write their files on their own imports
+    validate_output_paths(&workspace.packages)?;
+    let current_dir = workspace.context.current_dir.clone();
+    retain_matching(&mut workspace.packages, &pattern, &current_dir);
+    Ok(workspace)
+}
+
+fn parse_external_workspace(
+    workspace: &mut Workspace,
+    name: &str,
+    ws_dir: &Path,
+    ignore_missing_access_token: bool,
+) -> Result<()> {
+    debug!("Parsing external workspace `@{name}`...");
+    let ws_file = ws_dir.join(WORKSPACE_FILE_NAME);
+    if !ws_file.is_file() {
+        return Err(Error::WorkspaceExternalNotFound(name.to_owned(), ws_file));
+    }
+    let parse = || -> Result<_> {
+        let ws_dto = WorkspaceDto::from_file(&ws_file, ignore_missing_access_token)?;
+        let remotes = parse_remotes(ws_dto.remotes)?;
+        let profiles = parse_profiles(ws_dto.profiles)?;
+        Ok((remotes, profiles))
+    };
+    let (remotes, profiles) = parse().map_err(|e| with_workspace_file(e, &ws_file))?;
+    // ids of the external remotes are qualified, so that caches and reports keyed by the id
+    // don't mix them up with the remotes of the current workspace, e.g. `@design_system/icons`
+    let remotes = remotes
+        .into_iter()
+        .map(|(key, remote)| {
+            let remote = RemoteSource {
+                id: format!("@{name}/{}", remote.id),
+                ..remote.as_ref().clone()
+            };
+            (key, Arc::new(remote))
+        })
+        .collect::<OrderMap<_, _>>();
+    let fig_files = load_fig_files(ws_dir, Some(name))?;
+    let scans_dir = ws_dir.join(OUT_DIR).join(SCANS_DIR);
+    let packages = parse_packages(&fig_files, &remotes, &profiles, &scans_dir)?;
+
+    workspace.remotes.extend(remotes.into_values());
+    workspace.profiles.extend(profiles.into_values());
+    workspace.packages.extend(packages);
+    workspace.context.fig_files.extend(fig_files);
+    Ok(())
+}
+
+fn referenced_externals(pattern: &LabelPattern) -> Vec<String> {
+    let patterns = match pattern {
+        LabelPattern::Single(pattern) => std::slice::from_ref(pattern),
+        LabelPattern::Composed(patterns) => patterns.as_slice(),
+    };
+    let mut externals = Vec::new();
+    for pattern in patterns.iter().filter(|it| !it.negative) {
+        if let Some(name) = &pattern.external {
+            if !externals.contains(name) {
+                externals.push(name.clone());
+            }
+        }
+    }
+    externals
 }
 
 fn parse_packages(
-    context: &InvocationContext,
-    pattern: LabelPattern,
+    fig_files: &[LoadedFigFile],
     remotes: &OrderMap<String, Arc<RemoteSource>>,
     profiles: &OrderMap<String, Arc<Profile>>,
+    scans_dir: &Path,
 ) -> Result<Vec<Package>> {
-    context
-        .fig_files
+    fig_files
         .iter()
-        // do not load irrelevant packages
-        .filter(|f| lib_label::package_matches(&pattern, &f.package, &context.current_dir))
         .map(|f| {
-            parse_fig(f, remotes, profiles, &pattern, &context.current_dir).map_err(|e| match e {
+            parse_fig(f, remotes, profiles, scans_dir).map_err(|e| match e {
                 Error::FigParse(e, _) => Error::FigParse(e, f.fig_file.to_owned()),
                 e => e,
             })
         })
         .collect()
 }
+
+/// Filters out irrelevant packages and resources.
+///
+/// Relative patterns never match external packages, so the current dir doesn't matter for them.
+fn retain_matching(packages: &mut Vec<Package>, pattern: &LabelPattern, current_dir: &Path) {
+    packages.retain_mut(|pkg| {
+        if !lib_label::package_matches(pattern, &pkg.label, current_dir) {
+            return false;
+        }
+        pkg.resources
+            .retain(|res| lib_label::matches(pattern, &res.attrs.label, current_dir));
+        true
+    });
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod test {
+    use crate::{Error, load_workspace_in};
+    use lib_label::LabelPattern;
+    use std::path::PathBuf;
+
+    fn write(path: PathBuf, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn parse_workspace__external_remote_with_local_id__EXPECT__qualified_remote_id() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let remote = "[remotes.design]\nfile_key = \"FILE\"\ncontainer_node_ids = [\"0:1\"]\n";
+        write(
+            dir.path().join("app").join(".figtree.toml"),
+            &format!("{remote}[externals]\nds = {{ path = \"../ds\" }}\n"),
+        );
+        write(
+            dir.path().join("app").join("icons").join(".fig.toml"),
+            "[svg]\nic_star = \"Star\"\n",
+        );
+        write(dir.path().join("ds").join(".figtree.toml"), remote);
+        write(
+            dir.path().join("ds").join("icons").join(".fig.toml"),
+            "[svg]\nic_star = \"Star\"\n",
+        );
+        let pattern =
+            LabelPattern::try_from(vec!["//...".to_string(), "@ds//...".to_string()]).unwrap();
+
+        // When
+        let ws = load_workspace_in(&dir.path().join("app"), pattern, true).unwrap();
+
+        // Then
+        let mut remote_ids = ws
+            .packages
+            .iter()
+            .flat_map(|it| &it.resources)
+            .map(|it| (it.attrs.label.to_string(), it.attrs.remote.id.clone()))
+            .collect::<Vec<_>>();
+        remote_ids.sort();
+        assert_eq!(
+            vec![
+                ("//icons:ic_star".to_owned(), "design".to_owned()),
+                ("@ds//icons:ic_star".to_owned(), "@ds/design".to_owned()),
+            ],
+            remote_ids,
+        );
+    }
+
+    #[test]
+    fn parse_workspace__invalid_included_file__EXPECT__error_in_included_file() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path().join(".figtree.toml"),
+            "include = [\"tools/profiles.toml\"]\n[remotes.design]\nfile_key = \"FILE\"\ncontainer_node_ids = [\"0:1\"]\n",
+        );
+        write(
+            dir.path().join("tools").join("profiles.toml"),
+            "[profiles.icons]\nextends = \"png\"\nscale = \"big\"\n",
+        );
+        let pattern = LabelPattern::try_from(vec!["//...".to_string()]).unwrap();
+
+        // When
+        let result = load_workspace_in(dir.path(), pattern, true);
+
+        // Then
+        match result {
+            Err(Error::WorkspaceParse(_, file)) => {
+                assert_eq!(dir.path().join("tools").join("profiles.toml"), file)
+            }
+            Err(e) => panic!("unexpected error: {e}"),
+            Ok(_) => panic!("invalid included file is loaded"),
+        }
+    }
+}
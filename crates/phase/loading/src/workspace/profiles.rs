@@ -3,32 +3,51 @@ use std::sync::Arc;
 use ordermap::OrderMap;
 
 use crate::{
-    AndroidDrawableProfile, AndroidWebpProfile, CanBeExtendedBy, ComposeProfile, PdfProfile,
-    PngProfile, Profile, Result, SvgProfile, WebpProfile,
-    parser::{ProfileDto, ProfilesDto},
+    AndroidDrawableProfile, AndroidWebpProfile, CanBeExtendedBy, ComposeProfile, FaviconProfile,
+    FlutterProfile, PdfProfile, PngProfile, Profile, ReactProfile, Result, SvgProfile, WebpProfile,
+    parser::{ProfileDto, ProfilesDto, VectorLimitsDto},
 };
 
 pub fn parse_profiles(
     ProfilesDto(profiles): ProfilesDto,
 ) -> Result<OrderMap<String, Arc<Profile>>> {
-    let mut output = OrderMap::with_capacity(profiles.len());
+    // raster profiles go first, because vector ones can refer to them as a fallback
+    let mut raster_profiles = OrderMap::new();
+    for (id, profile) in profiles.iter().filter(|(_, p)| p.is_raster()) {
+        raster_profiles.insert(id.clone(), Arc::new(parse_profile(profile)));
+    }
 
-    for (id, profile) in profiles {
-        let profile = match profile {
-            ProfileDto::Png(p) => Profile::Png(PngProfile::default().extend(&p)),
-            ProfileDto::Svg(p) => Profile::Svg(SvgProfile::default().extend(&p)),
-            ProfileDto::Pdf(p) => Profile::Pdf(PdfProfile::default().extend(&p)),
-            ProfileDto::Webp(p) => Profile::Webp(WebpProfile::default().extend(&p)),
-            ProfileDto::Compose(p) => Profile::Compose(ComposeProfile::default().extend(&p)),
-            ProfileDto::AndroidWebp(p) => {
-                Profile::AndroidWebp(AndroidWebpProfile::default().extend(&p))
-            }
-            ProfileDto::AndroidDrawable(p) => {
-                Profile::AndroidDrawable(AndroidDrawableProfile::default().extend(&p))
-            }
-        };
-        output.insert(id, Arc::new(profile));
+    let mut output = OrderMap::with_capacity(profiles.len());
+    for (id, mut profile) in profiles {
+        if let Some(profile) = raster_profiles.get(&id) {
+            output.insert(id, profile.clone());
+            continue;
+        }
+        let fallback = profile
+            .vector_limits_mut()
+            .and_then(VectorLimitsDto::fallback_mut);
+        if let Some(fallback) = fallback {
+            fallback.profile = raster_profiles.get(&fallback.name).cloned();
+        }
+        output.insert(id, Arc::new(parse_profile(&profile)));
     }
 
     Ok(output)
 }
+
+fn parse_profile(profile: &ProfileDto) -> Profile {
+    match profile {
+        ProfileDto::Png(p) => Profile::Png(PngProfile::default().extend(p)),
+        ProfileDto::Svg(p) => Profile::Svg(SvgProfile::default().extend(p)),
+        ProfileDto::Pdf(p) => Profile::Pdf(PdfProfile::default().extend(p)),
+        ProfileDto::Webp(p) => Profile::Webp(WebpProfile::default().extend(p)),
+        ProfileDto::Compose(p) => Profile::Compose(ComposeProfile::default().extend(p)),
+        ProfileDto::AndroidWebp(p) => Profile::AndroidWebp(AndroidWebpProfile::default().extend(p)),
+        ProfileDto::AndroidDrawable(p) => {
+            Profile::AndroidDrawable(AndroidDrawableProfile::default().extend(p))
+        }
+        ProfileDto::Flutter(p) => Profile::Flutter(FlutterProfile::default().extend(p)),
+        ProfileDto::React(p) => Profile::React(ReactProfile::default().extend(p)),
+        ProfileDto::Favicon(p) => Profile::Favicon(FaviconProfile::default().extend(p)),
+    }
+}
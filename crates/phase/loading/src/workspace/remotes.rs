@@ -18,8 +18,14 @@ pub(crate) fn parse_remotes(
         let remote = RemoteSource {
             id: id.clone(),
             file_key: dto.file_key.to_owned(),
+            branch: dto.branch.clone(),
             container_node_ids: parse_container_node_ids(&dto.container_node_ids),
             access_token: parse_access_token_definition(id, &dto.access_token, &dto.key_span)?,
+            shallow_depth: dto.shallow_depth,
+            export_batch_size: dto
+                .export_batch_size
+                .unwrap_or(RemoteSource::DEFAULT_EXPORT_BATCH_SIZE),
+            retry: dto.retry.clone().map(Into::into).unwrap_or_default(),
         };
         all_remotes.insert(id.to_owned(), Arc::new(remote));
     }